@@ -2,6 +2,7 @@ use core::ops::{Add, BitOrAssign, Bound::*, Range, RangeBounds};
 use num::PrimInt;
 
 use crate::bit::Error;
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{Deserialize, Serialize, Serializer};
 
 use super::bit_pack::{PackInto, UnpackFrom};
@@ -171,7 +172,7 @@ where
         let from_bits = reduce_range(&source_bits, &Self::space());
         Self::validate_range(&from_bits)?;
         let num_bits = (from_bits.end - from_bits.start) as usize;
-        Value::unpack_from(self.bits >> from_bits.start as usize, num_bits).map_err(|_| Error::TooManyBits)
+        Value::unpack_from(self.bits >> from_bits.start as usize, num_bits).map_err(|_| Value::unpack_error())
     }
 
     /// Convert the bit field to its underlying type.
@@ -186,20 +187,23 @@ where
     }
 
     fn validate_range(range: &Range<i64>) -> Result<(), Error> {
-        let space = Self::space();
-        let is_start_within_space = space.contains(&range.start);
-        let is_end_within_space = space.contains(&(range.end - 1));
-        let is_not_reversed = range.start <= range.end;
+        validate_range(range, &Self::space())
+    }
+}
 
-        let is_start_within_space_err = is_start_within_space.then_some(()).ok_or(Error::OutOfRange);
-        let is_end_within_space_err = is_end_within_space.then_some(()).ok_or(Error::OutOfRange);
-        let is_not_reversed_err = is_not_reversed.then_some(()).ok_or(Error::ReversedRange);
+pub(super) fn validate_range(range: &Range<i64>, space: &Range<i64>) -> Result<(), Error> {
+    let is_start_within_space = space.contains(&range.start);
+    let is_end_within_space = space.contains(&(range.end - 1));
+    let is_not_reversed = range.start <= range.end;
 
-        is_start_within_space_err.and(is_end_within_space_err).and(is_not_reversed_err)
-    }
+    let is_start_within_space_err = is_start_within_space.then_some(()).ok_or(Error::OutOfRange);
+    let is_end_within_space_err = is_end_within_space.then_some(()).ok_or(Error::OutOfRange);
+    let is_not_reversed_err = is_not_reversed.then_some(()).ok_or(Error::ReversedRange);
+
+    is_start_within_space_err.and(is_end_within_space_err).and(is_not_reversed_err)
 }
 
-fn reduce_range<BitScalar, BitRange>(range: &BitRange, space: &Range<i64>) -> Range<i64>
+pub(super) fn reduce_range<BitScalar, BitRange>(range: &BitRange, space: &Range<i64>) -> Range<i64>
 where
     BitScalar: Add + Into<i64> + Clone,
     BitRange: RangeBounds<BitScalar>,
@@ -217,6 +221,15 @@ where
     }
 }
 
+impl<Packed> Default for BitField<Packed>
+where
+    Packed: PrimInt + BitOrAssign,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<Packed> Serialize for BitField<Packed>
 where
     Packed: Serialize + PrimInt + BitOrAssign,
@@ -236,6 +249,13 @@ where
     }
 }
 
+impl<Packed> StaticLayout for BitField<Packed>
+where
+    Packed: StaticLayout + PrimInt + BitOrAssign,
+{
+    const LAYOUT: Layout = Packed::LAYOUT;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,6 +299,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::reversed_empty_ranges)]
     fn pack_reversed() {
         let mut bit_field = BitField::<u32>::new();
         assert!(bit_field.pack(0b1011_u8, 11..7).is_err());
@@ -304,6 +325,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::reversed_empty_ranges)]
     fn unpack_reversed() {
         let bit_field = BitField::from_bits(0b0000_0101_1000_0001_u16);
         assert!(bit_field.unpack::<u8, _, _>(11..7).is_err());
@@ -1,10 +1,11 @@
+use super::Error;
 use super::bit_util::{bit_size_of, bit_size_of_val, keep_lowest_n_bits, zero_lowest_n_bits};
 
 /// Convert a type to an arbitrary bit width representation.
 ///
 /// **Generic parameters**:
 /// - `Packed`: the type of the object that holds the arbitrary bit width
-///    representation. Typically an unsigned integer, but can be anything.
+///   representation. Typically an unsigned integer, but can be anything.
 ///
 /// This trait is implemented to pack `bool`, signed, and unsigned integers
 /// into unsigned integers.
@@ -37,7 +38,7 @@ where
 ///
 /// **Generic parameters**:
 /// - `Packed`: the type of the object that holds the arbitrary bit width
-///    representation. Typically an unsigned integer, but can be anything.
+///   representation. Typically an unsigned integer, but can be anything.
 ///
 /// This trait is implemented to unpack `bool`, signed, and unsigned integers
 /// from unsigned integers.
@@ -67,6 +68,16 @@ where
     /// unpack signed integers when `num_bits` is wider than the `Packed` type
     /// will result in an error too.
     fn unpack_from(value: Packed, num_bits: usize) -> Result<Self, Packed>;
+
+    /// Return the error that describes why [`unpack_from`](Self::unpack_from)
+    /// failed.
+    ///
+    /// Types that do not accept some bit patterns, like `bool` and enums,
+    /// return [`Error::InvalidEnumVariant`]. Other types return
+    /// [`Error::TooManyBits`], meaning that the value did not fit.
+    fn unpack_error() -> Error {
+        Error::TooManyBits
+    }
 }
 
 macro_rules! impl_bit_pack_unsigned {
@@ -143,6 +154,9 @@ macro_rules! impl_bit_pack_bool {
                     _ => Err(value),
                 }
             }
+            fn unpack_error() -> Error {
+                Error::InvalidEnumVariant
+            }
         }
     };
 }
@@ -325,7 +339,7 @@ mod tests {
         }
         {
             let value: i16 = -128;
-            let expected = (-128i8).cast_unsigned() & 0b1111_1111;
+            let expected = (-128i8).cast_unsigned();
             assert_eq!(value.pack_into(8), Some(expected));
         }
     }
@@ -0,0 +1,222 @@
+use core::ops::{Add, Range, RangeBounds};
+
+use crate::bit::Error;
+use crate::layout::{Layout, StaticLayout};
+use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::bit_field::{reduce_range, validate_range};
+use super::bit_pack::{PackInto, UnpackFrom};
+use super::bit_util::keep_lowest_n_bits;
+
+/// A bit field whose storage is a byte array, for bit fields wider than the
+/// primitive integers.
+///
+/// The byte array is interpreted as a big-endian integer of `8 * N` bits: bit
+/// zero (LSB0) is the least significant bit of the last byte. Members may span
+/// across bytes, but a single member can be at most 64 bits wide, as members
+/// are packed into and unpacked from a [`u64`].
+///
+/// Otherwise, this works the same as [`BitField`](super::BitField).
+///
+/// # Example
+///
+/// ```
+/// use sorbit::bit::ByteArrayBitField;
+///
+/// let mut bit_field = ByteArrayBitField::<12>::new();
+/// bit_field.pack(0xABCu16, 4..16)?;
+/// bit_field.pack(0x1234_5678u32, 64..96)?;
+/// assert_eq!(bit_field.into_bits(), [0x12, 0x34, 0x56, 0x78, 0, 0, 0, 0, 0, 0, 0xAB, 0xC0]);
+/// # Ok::<(), sorbit::bit::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ByteArrayBitField<const N: usize> {
+    bits: [u8; N],
+    mask: [u8; N],
+}
+
+impl<const N: usize> ByteArrayBitField<N> {
+    /// Create a new bit field all bits set to zero and the mask set to zero as well.
+    pub fn new() -> Self {
+        Self { bits: [0; N], mask: [0; N] }
+    }
+
+    /// Create a new bit field from the given bits, with the mask set to all zeros.
+    pub fn from_bits(bits: [u8; N]) -> Self {
+        Self { bits, mask: [0; N] }
+    }
+
+    /// The size of the bit field's underlying byte array in bits.
+    pub fn bit_size_of(&self) -> usize {
+        8 * N
+    }
+
+    /// Add a new member to the bit field.
+    ///
+    /// The same as [`BitField::pack`](super::BitField::pack), but the member
+    /// is packed into a [`u64`] first, so it can be at most 64 bits wide.
+    ///
+    /// # Parameters
+    ///
+    /// - `value`: the bits of the new member.
+    /// - `target_bits`: the bit range where the new member is inserted. The least
+    ///   significant bit is numbered zero (LSB0).
+    pub fn pack<Value, BitRange, BitScalar>(&mut self, value: Value, target_bits: BitRange) -> Result<(), Error>
+    where
+        Value: PackInto<u64>,
+        BitRange: RangeBounds<BitScalar>,
+        BitScalar: Add + Into<i64> + Clone,
+    {
+        let to_bits = reduce_range(&target_bits, &Self::space());
+        validate_range(&to_bits, &Self::space())?;
+        let num_bits = (to_bits.end - to_bits.start) as usize;
+        if num_bits > u64::BITS as usize {
+            return Err(Error::TooManyBits);
+        }
+        if to_bits.clone().any(|bit| Self::get_bit(&self.mask, bit)) {
+            return Err(Error::Overlap);
+        }
+
+        let packed_bits: u64 = value.pack_into(num_bits).ok_or(Error::TooManyBits)?;
+        for (index, bit) in to_bits.enumerate() {
+            Self::set_bit(&mut self.mask, bit, true);
+            Self::set_bit(&mut self.bits, bit, (packed_bits >> index) & 1 != 0);
+        }
+        Ok(())
+    }
+
+    /// Read a member of the bit field.
+    ///
+    /// The same as [`BitField::unpack`](super::BitField::unpack), but the
+    /// member is unpacked from a [`u64`], so it can be at most 64 bits wide.
+    ///
+    /// # Parameters
+    ///
+    /// - `source_bits`: the bit range where the member to read resides. The least
+    ///   significant bit is numbered zero (LSB0).
+    pub fn unpack<Value, BitRange, BitScalar>(&self, source_bits: BitRange) -> Result<Value, Error>
+    where
+        Value: UnpackFrom<u64>,
+        BitRange: RangeBounds<BitScalar>,
+        BitScalar: Add + Into<i64> + Clone,
+    {
+        let from_bits = reduce_range(&source_bits, &Self::space());
+        validate_range(&from_bits, &Self::space())?;
+        let num_bits = (from_bits.end - from_bits.start) as usize;
+        if num_bits > u64::BITS as usize {
+            return Err(Error::TooManyBits);
+        }
+        let packed_bits = from_bits
+            .enumerate()
+            .fold(0u64, |packed, (index, bit)| packed | (u64::from(Self::get_bit(&self.bits, bit)) << index));
+        Value::unpack_from(keep_lowest_n_bits!(packed_bits, num_bits), num_bits).map_err(|_| Value::unpack_error())
+    }
+
+    /// Convert the bit field to its underlying byte array.
+    ///
+    /// The mask is dropped.
+    pub fn into_bits(self) -> [u8; N] {
+        self.bits
+    }
+
+    const fn space() -> Range<i64> {
+        0..(8 * N as i64)
+    }
+
+    fn get_bit(bytes: &[u8; N], bit: i64) -> bool {
+        let (byte, shift) = Self::locate(bit);
+        (bytes[byte] >> shift) & 1 != 0
+    }
+
+    fn set_bit(bytes: &mut [u8; N], bit: i64, value: bool) {
+        let (byte, shift) = Self::locate(bit);
+        bytes[byte] = (bytes[byte] & !(1 << shift)) | (u8::from(value) << shift);
+    }
+
+    fn locate(bit: i64) -> (usize, u32) {
+        (N - 1 - (bit / 8) as usize, (bit % 8) as u32)
+    }
+}
+
+impl<const N: usize> Default for ByteArrayBitField<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Serialize for ByteArrayBitField<N> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer.serialize_array(&self.bits)
+    }
+}
+
+impl<const N: usize> Deserialize for ByteArrayBitField<N> {
+    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+        let bits = deserializer.deserialize_array()?;
+        Ok(Self::from_bits(bits))
+    }
+}
+
+impl<const N: usize> StaticLayout for ByteArrayBitField<N> {
+    const LAYOUT: Layout = u8::LAYOUT.repeat(N as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_across_bytes() {
+        let mut bit_field = ByteArrayBitField::<3>::new();
+        bit_field.pack(0b1_0110_1001_u16, 6..15).unwrap();
+        assert_eq!(bit_field.into_bits(), [0b0000_0000, 0b0101_1010, 0b0100_0000]);
+    }
+
+    #[test]
+    fn pack_wide() {
+        let mut bit_field = ByteArrayBitField::<12>::new();
+        bit_field.pack(u64::MAX, 16..80).unwrap();
+        bit_field.pack(0xAB_u8, 88..96).unwrap();
+        let expected = [
+            0xAB, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0,
+        ];
+        assert_eq!(bit_field.into_bits(), expected);
+    }
+
+    #[test]
+    fn pack_overlap() {
+        let mut bit_field = ByteArrayBitField::<12>::new();
+        bit_field.pack(0b1011_u8, 62..66).unwrap();
+        assert_eq!(bit_field.pack(0b11_u8, 65..67), Err(Error::Overlap));
+    }
+
+    #[test]
+    fn pack_too_wide() {
+        let mut bit_field = ByteArrayBitField::<12>::new();
+        assert_eq!(bit_field.pack(0_u8, 0..65), Err(Error::TooManyBits));
+        assert_eq!(bit_field.pack(0b100_u8, 0..2), Err(Error::TooManyBits));
+    }
+
+    #[test]
+    fn pack_space_overflow() {
+        let mut bit_field = ByteArrayBitField::<12>::new();
+        assert_eq!(bit_field.pack(0b1011_u8, 94..98), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn unpack_across_bytes() {
+        let bit_field = ByteArrayBitField::from_bits([0b0000_0000, 0b0101_1010, 0b0100_0000]);
+        assert_eq!(bit_field.unpack::<u16, _, _>(6..15), Ok(0b1_0110_1001));
+        assert_eq!(bit_field.unpack::<i8, _, _>(6..10), Ok(-7));
+    }
+
+    #[test]
+    fn unpack_wide() {
+        let bit_field = ByteArrayBitField::from_bits([
+            0xAB, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0,
+        ]);
+        assert_eq!(bit_field.unpack::<u64, _, _>(16..80), Ok(u64::MAX));
+        assert_eq!(bit_field.unpack::<u8, _, _>(88..96), Ok(0xAB));
+        assert_eq!(bit_field.unpack::<u8, _, _>(80..96), Err(Error::TooManyBits));
+    }
+}
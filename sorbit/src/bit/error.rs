@@ -6,6 +6,7 @@ pub enum Error {
     Overlap,
     OutOfRange,
     ReversedRange,
+    InvalidEnumVariant,
 }
 
 impl core::error::Error for Error {}
@@ -17,6 +18,7 @@ impl core::fmt::Display for Error {
             Error::Overlap => write!(f, "the field's bit range overlaps with fields previously packed"),
             Error::OutOfRange => write!(f, "the packed value's target bit range falls outside the packed type"),
             Error::ReversedRange => write!(f, "bit ranges must not be reversed"),
+            Error::InvalidEnumVariant => write!(f, "the packed value does not correspond to an enum or bool variant"),
         }
     }
 }
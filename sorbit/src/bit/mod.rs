@@ -21,9 +21,11 @@
 mod bit_field;
 mod bit_pack;
 mod bit_util;
+mod byte_array_bit_field;
 mod error;
 
 pub use bit_field::BitField;
 pub use bit_pack::{PackInto, UnpackFrom};
 pub use bit_util::{bit_size_of, bit_size_of_val};
+pub use byte_array_bit_field::ByteArrayBitField;
 pub use error::Error;
@@ -0,0 +1,145 @@
+//! Utilities for deserializing byte slices and strings without copying them.
+//!
+//! A [`BorrowDeserializer`], like the [`SliceDeserializer`](crate::stream_ser_de::SliceDeserializer),
+//! can lend the bytes it reads. With the derive macros, `&'de [u8]` and
+//! `&'de str` fields marked with `borrow` point into the deserialized buffer
+//! instead of being copied. The structure then implements [`DeserializeBorrowed`](crate::ser_de::DeserializeBorrowed)
+//! instead of [`Deserialize`]:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::ser_de::{DeserializeBorrowed, ToBytes};
+//! use sorbit::stream_ser_de::SliceDeserializer;
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! struct Packet<'a> {
+//!     #[sorbit(value=len(payload))]
+//!     len: u8,
+//!     #[sorbit(borrow)]
+//!     payload: &'a [u8],
+//!     #[sorbit(borrow, string=len_prefix(u8))]
+//!     name: &'a str,
+//! }
+//!
+//! let bytes = [3, 0xAA, 0xBB, 0xCC, 2, b'o', b'k'];
+//! let mut deserializer = SliceDeserializer::from_slice(&bytes);
+//! let packet = Packet::deserialize_borrowed(&mut deserializer).unwrap();
+//! assert_eq!(packet, Packet { len: 3, payload: &[0xAA, 0xBB, 0xCC], name: "ok" });
+//! assert_eq!(packet.to_bytes().unwrap(), bytes);
+//! ```
+
+use crate::ser_de::{BorrowDeserializer, Deserialize};
+
+/// A reference that can be borrowed from the bytes of a [`BorrowDeserializer`].
+pub trait Borrowed<'de>: Sized {
+    /// Borrow the next `len` bytes of the `deserializer` as `Self`.
+    fn borrow_from<D: BorrowDeserializer<'de>>(deserializer: &mut D, len: usize) -> Result<Self, D::Error>;
+}
+
+impl<'de> Borrowed<'de> for &'de [u8] {
+    fn borrow_from<D: BorrowDeserializer<'de>>(deserializer: &mut D, len: usize) -> Result<Self, D::Error> {
+        deserializer.deserialize_borrowed(len)
+    }
+}
+
+impl<'de> Borrowed<'de> for &'de str {
+    fn borrow_from<D: BorrowDeserializer<'de>>(deserializer: &mut D, len: usize) -> Result<Self, D::Error> {
+        let bytes = deserializer.deserialize_borrowed(len)?;
+        core::str::from_utf8(bytes).or_else(|_| deserializer.error("the string is not valid UTF-8"))
+    }
+}
+
+/// Borrow a byte slice or string given its length.
+///
+/// The length is checked against the [`Limits`](crate::ser_de::Limits) of the
+/// deserializer.
+pub fn deserialize_borrowed_by_len<'de, B, D, Len>(deserializer: &mut D, len: &Len) -> Result<B, D::Error>
+where
+    B: Borrowed<'de>,
+    D: BorrowDeserializer<'de>,
+    Len: Clone,
+    usize: TryFrom<Len>,
+{
+    borrow_by_len(deserializer, len.clone())
+}
+
+/// Borrow a byte slice or string preceded by its length as `Len`.
+pub fn deserialize_borrowed_len_prefixed<'de, B, D, Len>(deserializer: &mut D) -> Result<B, D::Error>
+where
+    B: Borrowed<'de>,
+    D: BorrowDeserializer<'de>,
+    Len: Deserialize,
+    usize: TryFrom<Len>,
+{
+    deserializer.deserialize_composite(|deserializer| {
+        let len = Len::deserialize(deserializer)?;
+        borrow_by_len(deserializer, len)
+    })
+}
+
+fn borrow_by_len<'de, B, D, Len>(deserializer: &mut D, len: Len) -> Result<B, D::Error>
+where
+    B: Borrowed<'de>,
+    D: BorrowDeserializer<'de>,
+    usize: TryFrom<Len>,
+{
+    let Ok(len) = usize::try_from(len) else {
+        return deserializer.error("the length of the collection can not be converted into a `usize`");
+    };
+    if len > deserializer.limits().max_len {
+        return deserializer.length_limit();
+    }
+    B::borrow_from(deserializer, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind, TraceError as _};
+    use crate::ser_de::Limits;
+    use crate::stream_ser_de::SliceDeserializer;
+
+    #[test]
+    fn borrow_bytes() {
+        let bytes = [1, 2, 3, 4];
+        let mut deserializer = SliceDeserializer::from_slice(&bytes);
+        let borrowed: &[u8] = deserialize_borrowed_by_len(&mut deserializer, &3u8).unwrap();
+        assert!(core::ptr::eq(borrowed, &bytes[0..3]));
+        assert_eq!(u8::deserialize(&mut deserializer), Ok(4));
+    }
+
+    #[test]
+    fn borrow_str() {
+        let mut deserializer = SliceDeserializer::from_slice(b"\x02ok");
+        assert_eq!(deserialize_borrowed_len_prefixed::<&str, _, u8>(&mut deserializer), Ok("ok"));
+    }
+
+    #[test]
+    fn borrow_str_invalid_utf8() {
+        let mut deserializer = SliceDeserializer::from_slice(&[2, 0xC3, 0x28]);
+        assert_eq!(
+            deserialize_borrowed_len_prefixed::<&str, _, u8>(&mut deserializer),
+            Err(Error::from(ErrorKind::Custom("the string is not valid UTF-8")).locate(0))
+        );
+    }
+
+    #[test]
+    fn borrow_past_end() {
+        let mut deserializer = SliceDeserializer::from_slice(&[1, 2]);
+        assert_eq!(
+            deserialize_borrowed_by_len::<&[u8], _, _>(&mut deserializer, &3u8),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 3, bytes_available: 2 }.into())
+        );
+    }
+
+    #[test]
+    fn borrow_length_limit() {
+        let limits = Limits::DEFAULT.with_max_len(2);
+        let mut deserializer = SliceDeserializer::from_slice(&[1, 2, 3]).with_limits(limits);
+        assert_eq!(
+            deserialize_borrowed_by_len::<&[u8], _, _>(&mut deserializer, &3u8),
+            Err(ErrorKind::LengthLimit.into())
+        );
+    }
+}
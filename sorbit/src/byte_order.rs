@@ -7,6 +7,13 @@ pub enum ByteOrder {
     LittleEndian,
     /// The type's most significant byte is at the lowest memory address.
     BigEndian,
+    /// The type's 16-bit words are in big endian order, but the bytes within
+    /// each word are in little endian order, as on the PDP-11.
+    ///
+    /// For example, a 32-bit value with the bytes `ABCD` from most to least
+    /// significant is stored as `BADC`. 16-bit values are stored as little
+    /// endian, single bytes as they are.
+    MiddleEndian,
 }
 
 impl ByteOrder {
@@ -21,9 +28,26 @@ impl ByteOrder {
     }
 }
 
+/// Convert between the big endian and middle endian byte orders by swapping
+/// the bytes of each 16-bit word.
+pub(crate) fn swap_word_bytes<const N: usize>(mut bytes: [u8; N]) -> [u8; N] {
+    bytes.chunks_exact_mut(2).for_each(|word| word.swap(0, 1));
+    bytes
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::byte_order::ByteOrder;
+    use crate::byte_order::{ByteOrder, swap_word_bytes};
+
+    #[test]
+    fn swap_word_bytes_u32() {
+        assert_eq!(swap_word_bytes(0x0A0B0C0Du32.to_be_bytes()), [0x0B, 0x0A, 0x0D, 0x0C]);
+    }
+
+    #[test]
+    fn swap_word_bytes_u8() {
+        assert_eq!(swap_word_bytes([0x0A]), [0x0A]);
+    }
 
     #[test]
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -0,0 +1,276 @@
+//! Utilities for protecting serialized data with checksums.
+//!
+//! Many formats store a checksum, like a CRC, over a range of fields, so that
+//! readers can detect corrupted data. With the derive macros, a field can be
+//! annotated to hold the checksum of other fields of the same struct:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::ser_de::{FromBytes, ToBytes};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! #[sorbit(byte_order=big_endian)]
+//! struct Packet {
+//!     kind: u8,
+//!     payload: [u8; 4],
+//!     #[sorbit(checksum=crc32(kind, payload))]
+//!     crc: u32,
+//! }
+//!
+//! let packet = Packet { kind: 1, payload: *b"data", crc: 0 };
+//! let bytes = packet.to_bytes().unwrap();
+//! assert_eq!(bytes[5..], [0x77, 0xF5, 0xF2, 0xD2]);
+//! assert_eq!(Packet::from_bytes(&bytes).unwrap(), Packet { crc: 0x77F5F2D2, ..packet });
+//! ```
+//!
+//! When serializing, the value of the checksum field is a placeholder that is
+//! revised once all fields of the struct have been serialized. This requires
+//! a [`RevisableSerializer`]. When deserializing, the checksum is computed
+//! over the bytes the covered fields were deserialized from, and an error is
+//! returned if it doesn't match. This requires the `alloc` feature and a
+//! deserializer that supports [`Deserializer::deserialize_recorded`].
+//!
+//! The covered fields are checksummed in the order they are listed in, and
+//! they don't have to be adjacent. The checksum field itself may come before
+//! or after the fields it covers.
+
+use crate::error::MessageError as _;
+use crate::io::Read;
+use crate::ser_de::{Deserializer, RevisableSerializer, Serialize, Span};
+
+/// An algorithm that computes a checksum over a sequence of bytes.
+pub trait Checksum: Default {
+    /// The type of the computed checksum.
+    type Output;
+
+    /// Feed more bytes into the checksum.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Return the checksum of all bytes fed so far.
+    fn finish(&self) -> Self::Output;
+}
+
+/// The CRC-32 used by Ethernet, zlib, and PNG.
+///
+/// Also known as CRC-32/ISO-HDLC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Crc32 {
+    state: u32,
+}
+
+/// The CRC-16 with the polynomial `0x1021` and the initial value `0xFFFF`.
+///
+/// Also known as CRC-16/IBM-3740 or CRC-16/CCITT-FALSE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Crc16 {
+    state: u16,
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 1 {
+                0 => crc >> 1,
+                _ => (crc >> 1) ^ 0xEDB8_8320,
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+const CRC16_TABLE: [u16; 256] = {
+    let mut table = [0u16; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = (index as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = match crc & 0x8000 {
+                0 => crc << 1,
+                _ => (crc << 1) ^ 0x1021,
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self { state: 0xFFFF_FFFF }
+    }
+}
+
+impl Default for Crc16 {
+    fn default() -> Self {
+        Self { state: 0xFFFF }
+    }
+}
+
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state = CRC32_TABLE[((self.state ^ *byte as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    fn finish(&self) -> Self::Output {
+        self.state ^ 0xFFFF_FFFF
+    }
+}
+
+impl Checksum for Crc16 {
+    type Output = u16;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state = CRC16_TABLE[((self.state >> 8) ^ *byte as u16) as usize] ^ (self.state << 8);
+        }
+    }
+
+    fn finish(&self) -> Self::Output {
+        self.state
+    }
+}
+
+/// Compute the checksum over the bytes of previously serialized spans.
+///
+/// The spans are checksummed in the order they are given in.
+pub fn checksum_spans<C, S>(serializer: &mut S, spans: &[&S::Success]) -> Result<C::Output, S::Error>
+where
+    C: Checksum,
+    S: RevisableSerializer,
+{
+    let mut checksum = C::default();
+    for span in spans {
+        serializer.analyze_span(span, |reader: &mut dyn Read| {
+            let mut chunk = [0u8; 64];
+            let mut remaining = span.len();
+            while remaining > 0 {
+                let chunk_len = core::cmp::min(chunk.len() as u64, remaining) as usize;
+                reader.read(&mut chunk[..chunk_len]).map_err(|_| S::Error::message("failed to read the span"))?;
+                checksum.update(&chunk[..chunk_len]);
+                remaining -= chunk_len as u64;
+            }
+            Ok::<_, S::Error>(())
+        })?;
+    }
+    Ok(checksum.finish())
+}
+
+/// Serialize the checksum over the bytes of previously serialized spans.
+///
+/// This is meant to be called within [`RevisableSerializer::revise_span`] to
+/// overwrite the placeholder of the checksum.
+pub fn serialize_checksum<C, S>(serializer: &mut S, spans: &[&S::Success]) -> Result<S::Success, S::Error>
+where
+    C: Checksum<Output: Serialize>,
+    S: RevisableSerializer,
+{
+    checksum_spans::<C, S>(serializer, spans)?.serialize(serializer)
+}
+
+/// Verify that the checksum over the `sections` is equal to `checksum`.
+///
+/// The sections are checksummed in the order they are given in. The checksum
+/// is not verified if the deserializer [trusts](Deserializer::is_trusted) its
+/// input.
+pub fn verify_checksum<C, D>(deserializer: &D, checksum: &C::Output, sections: &[&[u8]]) -> Result<(), D::Error>
+where
+    C: Checksum<Output: PartialEq>,
+    D: Deserializer,
+{
+    if deserializer.is_trusted() {
+        return Ok(());
+    }
+    let mut computed = C::default();
+    sections.iter().for_each(|section| computed.update(section));
+    match &computed.finish() == checksum {
+        true => Ok(()),
+        false => deserializer.error("the checksum does not match the data"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind};
+    use crate::io::GrowingMemoryStream;
+    use crate::ser_de::Serializer;
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    fn checksum<C: Checksum>(bytes: &[u8]) -> C::Output {
+        let mut checksum = C::default();
+        checksum.update(bytes);
+        checksum.finish()
+    }
+
+    #[test]
+    fn crc32() {
+        assert_eq!(checksum::<Crc32>(b""), 0);
+        assert_eq!(checksum::<Crc32>(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn crc16() {
+        assert_eq!(checksum::<Crc16>(b""), 0xFFFF);
+        assert_eq!(checksum::<Crc16>(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn update_in_parts() {
+        let mut checksum = Crc32::default();
+        checksum.update(b"1234");
+        checksum.update(b"56789");
+        assert_eq!(checksum.finish(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn checksum_spans_in_order() -> Result<(), Error> {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let first = serializer.serialize_slice(b"1234")?;
+        let second = serializer.serialize_slice(b"56789")?;
+        assert_eq!(checksum_spans::<Crc32, _>(&mut serializer, &[&first, &second]), Ok(0xCBF4_3926));
+        assert_ne!(checksum_spans::<Crc32, _>(&mut serializer, &[&second, &first]), Ok(0xCBF4_3926));
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_checksum_revised() -> Result<(), Error> {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let data = serializer.serialize_slice(b"123456789")?;
+        let placeholder = serializer.serialize_u16(0)?;
+        serializer.revise_span(&placeholder, |serializer| serialize_checksum::<Crc16, _>(serializer, &[&data]))?;
+        let bytes = serializer.take().take();
+        assert_eq!(bytes[9..], 0x29B1u16.to_ne_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn verify() {
+        let deserializer = StreamDeserializer::new(GrowingMemoryStream::new());
+        assert_eq!(verify_checksum::<Crc16, _>(&deserializer, &0x29B1, &[b"1234", b"56789"]), Ok(()));
+        assert_eq!(
+            verify_checksum::<Crc16, _>(&deserializer, &0x29B2, &[b"1234", b"56789"]),
+            Err(ErrorKind::Custom("the checksum does not match the data").into())
+        );
+    }
+
+    #[test]
+    fn verify_trusted() {
+        let deserializer = StreamDeserializer::new(GrowingMemoryStream::new()).trusted();
+        assert_eq!(verify_checksum::<Crc16, _>(&deserializer, &0x29B2, &[b"1234", b"56789"]), Ok(()));
+    }
+}
@@ -1,6 +1,14 @@
 //! Utilities for serializing collections, like `Vec`.
 
+use core::marker::PhantomData;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::error::{Error, ErrorKind};
 use crate::ser_de::{Deserialize, Deserializer, MultiPassSerialize, RevisableSerializer, Serialize, Serializer, Span};
+use crate::stream_ser_de::{RangeSpan, SizeSerializer};
+use crate::types::try_array_from_fn;
 
 /// Return the length of a collection as a specific (integer) type.
 pub trait LenAs<T> {
@@ -11,7 +19,7 @@ pub trait LenAs<T> {
 }
 
 // Blanket implementation of [`LenAs`] for collections.
-impl<T, C> LenAs<T> for C
+impl<T, C: ?Sized> LenAs<T> for C
 where
     for<'c> &'c C: IntoIterator<IntoIter: ExactSizeIterator>,
     T: TryFrom<usize>,
@@ -34,7 +42,7 @@ pub trait MultiPassSerializeItems {
 }
 
 // Blanket implementation of [`SerializeItems`] for collections.
-impl<C> SerializeItems for C
+impl<C: ?Sized> SerializeItems for C
 where
     for<'c> &'c C: IntoIterator<Item: Serialize>,
 {
@@ -51,7 +59,7 @@ where
 }
 
 // Blanket implementation of [`MultiPassSerializeItems`] for collections.
-impl<C> MultiPassSerializeItems for C
+impl<C: ?Sized> MultiPassSerializeItems for C
 where
     for<'c> &'c C: IntoIterator<Item: MultiPassSerialize>,
 {
@@ -89,7 +97,10 @@ where
         let Ok(len) = usize::try_from(len) else {
             return deserializer.error("the length of the collection can not be converted into a `usize`");
         };
-        (0..len).into_iter().map(|_| Item::deserialize(deserializer)).collect()
+        if len > deserializer.limits().max_len {
+            return deserializer.length_limit();
+        }
+        (0..len).map(|_| Item::deserialize(deserializer)).collect()
     }
 }
 
@@ -116,7 +127,6 @@ where
         };
         deserializer.deserialize_bounded(byte_count as u64, |deserializer| {
             (0..)
-                .into_iter()
                 .map_while(|_| {
                     (0 != deserializer.bytes_in_bounds().expect("expected to be Some within deserialize_bounded"))
                         .then(|| Item::deserialize(deserializer))
@@ -126,18 +136,60 @@ where
     }
 }
 
+/// Deserialize the items of a collection into an existing collection.
+///
+/// The previous items of the collection are replaced, but its storage is
+/// reused. This avoids repeated allocations when many collections are
+/// deserialized one after the other. The capacity reserved for the items up
+/// front is capped by the [`Limits`](crate::ser_de::Limits) of the deserializer.
+pub trait DeserializeInto<T> {
+    /// Replace the items of the collection with `len` deserialized items.
+    ///
+    /// On error, the collection is left empty, without any of the items
+    /// deserialized before the error, but its storage is kept.
+    fn deserialize_into<D: Deserializer>(&mut self, deserializer: &mut D, len: T) -> Result<(), D::Error>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T, Item> DeserializeInto<T> for Vec<Item>
+where
+    Item: Deserialize,
+    usize: TryFrom<T>,
+{
+    fn deserialize_into<D: Deserializer>(&mut self, deserializer: &mut D, len: T) -> Result<(), D::Error> {
+        self.clear();
+        let Ok(len) = usize::try_from(len) else {
+            return deserializer.error("the length of the collection can not be converted into a `usize`");
+        };
+        if len > deserializer.limits().max_len {
+            return deserializer.length_limit();
+        }
+        self.reserve(deserializer.limits().preallocated_items::<Item>(len));
+        for _ in 0..len {
+            match Item::deserialize(deserializer) {
+                Ok(item) => self.push(item),
+                Err(error) => {
+                    self.clear();
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// The items of a collection.
 ///
 /// This is wrapper around a collection like a `Vec`. It implements [`Serialize`]
 /// to serialize the items of the collection one after the other, but the length
 /// is **not** serialized.
-pub struct Items<'collection, Collection> {
+pub struct Items<'collection, Collection: ?Sized> {
     collection: &'collection Collection,
 }
 
 impl<'collection, C> Serialize for Items<'collection, C>
 where
-    C: SerializeItems,
+    C: SerializeItems + ?Sized,
 {
     /// Serialize the items of the collection, but **not** its length.
     fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
@@ -147,7 +199,7 @@ where
 
 impl<'collection, C> MultiPassSerialize for Items<'collection, C>
 where
-    C: MultiPassSerializeItems,
+    C: MultiPassSerializeItems + ?Sized,
 {
     /// Serialize the items of the collection, but **not** its length.
     fn serialize<S: RevisableSerializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
@@ -155,6 +207,94 @@ where
     }
 }
 
+/// The items of a collection preceded by their number.
+///
+/// This is wrapper around a collection like a `Vec`. It implements [`Serialize`]
+/// to serialize the length of the collection as `Len`, followed by the items of
+/// the collection one after the other.
+pub struct LenPrefixed<'collection, Len, Collection: ?Sized> {
+    collection: &'collection Collection,
+    len: PhantomData<Len>,
+}
+
+impl<'collection, Len, C> Serialize for LenPrefixed<'collection, Len, C>
+where
+    Len: Serialize,
+    C: LenAs<Len> + SerializeItems + ?Sized,
+{
+    /// Serialize the length of the collection, followed by its items.
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer
+            .serialize_composite(|serializer| {
+                len::<Len, _, _>(serializer, self.collection)?.serialize(serializer)?;
+                self.collection.serialize_items(serializer)
+            })
+            .map(|(composite_span, _)| composite_span)
+    }
+}
+
+impl<'collection, Len, C> MultiPassSerialize for LenPrefixed<'collection, Len, C>
+where
+    Len: Serialize,
+    C: LenAs<Len> + MultiPassSerializeItems + ?Sized,
+{
+    /// Serialize the length of the collection, followed by its items.
+    fn serialize<S: RevisableSerializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer
+            .serialize_composite(|serializer| {
+                Serialize::serialize(&len::<Len, _, _>(serializer, self.collection)?, serializer)?;
+                self.collection.serialize_items(serializer)
+            })
+            .map(|(composite_span, _)| composite_span)
+    }
+}
+
+/// The items of a collection, each in a slot of a fixed size.
+///
+/// This is wrapper around a collection like a `Vec` or an array. It implements
+/// [`Serialize`] to serialize the items of the collection one after the other,
+/// each padded to the size of the slot, but the length is **not** serialized.
+pub struct Strided<'collection, Collection: ?Sized> {
+    collection: &'collection Collection,
+    stride: u64,
+}
+
+impl<'collection, C> Serialize for Strided<'collection, C>
+where
+    C: ?Sized,
+    for<'c> &'c C: IntoIterator<Item: Serialize>,
+{
+    /// Serialize the items of the collection in slots, but **not** its length.
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer
+            .serialize_composite(|serializer| {
+                for item in self.collection {
+                    serialize_slot(serializer, self.stride, |serializer| item.serialize(serializer))?;
+                }
+                serializer.success()
+            })
+            .map(|(composite_span, _)| composite_span)
+    }
+}
+
+impl<'collection, C> MultiPassSerialize for Strided<'collection, C>
+where
+    C: ?Sized,
+    for<'c> &'c C: IntoIterator<Item: MultiPassSerialize>,
+{
+    /// Serialize the items of the collection in slots, but **not** its length.
+    fn serialize<S: RevisableSerializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer
+            .serialize_composite(|serializer| {
+                for item in self.collection {
+                    serialize_slot(serializer, self.stride, |serializer| item.serialize(serializer))?;
+                }
+                serializer.success()
+            })
+            .map(|(composite_span, _)| composite_span)
+    }
+}
+
 /// Return the length of a collection as a specific (integer) type.
 ///
 /// If the length of the collection can not be converted into the requested type
@@ -162,7 +302,7 @@ where
 pub fn len<T, S, C>(serializer: &mut S, collection: &C) -> Result<T, S::Error>
 where
     S: Serializer,
-    C: LenAs<T>,
+    C: LenAs<T> + ?Sized,
 {
     collection.len_as().ok_or_else(|| {
         serializer
@@ -171,6 +311,24 @@ where
     })
 }
 
+/// Check that the length of a collection equals the length it's deserialized
+/// with.
+///
+/// The length of collections whose length is not stored next to them is not
+/// serialized, so it must match the collection to be deserialized correctly.
+pub fn check_len<S, C, Len>(serializer: &mut S, collection: &C, len: &Len) -> Result<(), S::Error>
+where
+    S: Serializer,
+    C: LenAs<usize> + ?Sized,
+    Len: Clone,
+    usize: TryFrom<Len>,
+{
+    match usize::try_from(len.clone()).ok() == collection.len_as() {
+        true => Ok(()),
+        false => Err(serializer.error("the length of the collection differs from its count").unwrap_err()),
+    }
+}
+
 /// Return the number of bytes an object occupies as serialized.
 ///
 /// If the number of bytes cannot be converted into the requested type without
@@ -188,8 +346,38 @@ where
     })
 }
 
+/// Return the number of bytes an object occupies as serialized, measured
+/// before the object is serialized into `serializer`.
+///
+/// The object is serialized by `serialize_object` into a [`SizeSerializer`]
+/// that starts at the position of `serializer`, but only counts the bytes.
+/// This lets byte counts precede the objects they count without revising the
+/// output later. If the measurement fails, the error is reported through
+/// `serializer`.
+pub fn measure_byte_count<T, S>(
+    serializer: &mut S,
+    serialize_object: impl FnOnce(&mut SizeSerializer) -> Result<RangeSpan, Error>,
+) -> Result<T, S::Error>
+where
+    T: TryFrom<u64>,
+    S: Serializer,
+{
+    let mut sizer = SizeSerializer::default().resume_at(serializer.position());
+    if serializer.is_canonical() {
+        sizer = sizer.canonical();
+    }
+    match serialize_object(&mut sizer) {
+        Ok(span) => byte_count(serializer, &span),
+        Err(err) => Err(match err.kind() {
+            ErrorKind::Custom(message) => serializer.error(message),
+            _ => serializer.error("the byte count of the object could not be measured"),
+        }
+        .unwrap_err()),
+    }
+}
+
 /// Serialize the items in a collection, but not the length.
-pub fn items<'collection, Collection>(collection: &'collection Collection) -> Items<'collection, Collection> {
+pub fn items<'collection, Collection: ?Sized>(collection: &'collection Collection) -> Items<'collection, Collection> {
     Items { collection }
 }
 
@@ -206,6 +394,127 @@ where
     Collection::deserialize_by_len(deserializer, len.clone())
 }
 
+/// Serialize the items in a collection preceded by their number as `Len`.
+pub fn len_prefixed<'collection, Len, Collection: ?Sized>(
+    collection: &'collection Collection,
+) -> LenPrefixed<'collection, Len, Collection> {
+    LenPrefixed { collection, len: PhantomData }
+}
+
+/// Deserialize a collection preceded by the number of its elements as `Len`.
+pub fn deserialize_len_prefixed<Collection, Item, D, Len>(deserializer: &mut D) -> Result<Collection, D::Error>
+where
+    Collection: DeserializeByLen<Len, Item>,
+    D: Deserializer,
+    Len: Deserialize,
+{
+    deserializer.deserialize_composite(|deserializer| {
+        let len = Len::deserialize(deserializer)?;
+        Collection::deserialize_by_len(deserializer, len)
+    })
+}
+
+/// Serialize an object into a slot of `stride` bytes.
+///
+/// The object is padded with the fill byte until the end of the slot. If the
+/// object is larger than the slot, an error is returned.
+pub fn serialize_slot<S: Serializer>(
+    serializer: &mut S,
+    stride: u64,
+    serialize_object: impl FnOnce(&mut S) -> Result<S::Success, S::Error>,
+) -> Result<S::Success, S::Error> {
+    serializer
+        .serialize_composite(|serializer| {
+            serialize_object(serializer)?;
+            serializer.pad(stride)
+        })
+        .map(|(composite_span, _)| composite_span)
+}
+
+/// Deserialize an object from a slot of `stride` bytes.
+///
+/// The rest of the slot is skipped after the object, so the deserializer ends
+/// up at the beginning of the next slot.
+pub fn deserialize_slot<T, D: Deserializer>(
+    deserializer: &mut D,
+    stride: u64,
+    deserialize_object: impl FnOnce(&mut D) -> Result<T, D::Error>,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_composite(|deserializer| {
+        let value = deserialize_object(deserializer)?;
+        deserializer.pad(stride)?;
+        Ok(value)
+    })
+}
+
+/// Serialize the items in a collection, each in a slot of `stride` bytes, but
+/// not the length.
+pub fn strided<'collection, Collection: ?Sized>(
+    collection: &'collection Collection,
+    stride: u64,
+) -> Strided<'collection, Collection> {
+    Strided { collection, stride }
+}
+
+/// Deserialize an array whose items are each in a slot of `stride` bytes.
+pub fn deserialize_strided_array<T, D, const N: usize>(deserializer: &mut D, stride: u64) -> Result<[T; N], D::Error>
+where
+    T: Deserialize,
+    D: Deserializer,
+{
+    try_array_from_fn(|| deserialize_slot(deserializer, stride, T::deserialize))
+}
+
+/// Deserialize a collection whose items are each in a slot of `stride` bytes,
+/// given the number of its elements is given.
+pub fn deserialize_strided_items_by_len<Collection, Item, D, Len>(
+    deserializer: &mut D,
+    len: &Len,
+    stride: u64,
+) -> Result<Collection, D::Error>
+where
+    Collection: FromIterator<Item>,
+    Item: Deserialize,
+    D: Deserializer,
+    Len: Clone,
+    usize: TryFrom<Len>,
+{
+    let Ok(len) = usize::try_from(len.clone()) else {
+        return deserializer.error("the length of the collection can not be converted into a `usize`");
+    };
+    if len > deserializer.limits().max_len {
+        return deserializer.length_limit();
+    }
+    (0..len).map(|_| deserialize_slot(deserializer, stride, Item::deserialize)).collect()
+}
+
+/// Deserialize a collection whose items are each in a slot of `stride` bytes,
+/// given the number of bytes is given.
+pub fn deserialize_strided_items_by_byte_count<Collection, Item, D, Len>(
+    deserializer: &mut D,
+    byte_count: &Len,
+    stride: u64,
+) -> Result<Collection, D::Error>
+where
+    Collection: FromIterator<Item>,
+    Item: Deserialize,
+    D: Deserializer,
+    Len: Clone,
+    usize: TryFrom<Len>,
+{
+    let Ok(byte_count) = usize::try_from(byte_count.clone()) else {
+        return deserializer.error("the length of the collection can not be converted into a `usize`");
+    };
+    deserializer.deserialize_bounded(byte_count as u64, |deserializer| {
+        (0..)
+            .map_while(|_| {
+                (0 != deserializer.bytes_in_bounds().expect("expected to be Some within deserialize_bounded"))
+                    .then(|| deserialize_slot(deserializer, stride, Item::deserialize))
+            })
+            .collect()
+    })
+}
+
 /// Deserialize a collection given the number of bytes is given.
 pub fn deserialize_items_by_byte_count<Collection, Item, D, Len>(
     deserializer: &mut D,
@@ -221,7 +530,17 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::{collection::len, io::GrowingMemoryStream, stream_ser_de::StreamSerializer};
+    use crate::{
+        collection::{
+            DeserializeInto, check_len, deserialize_len_prefixed, deserialize_strided_array,
+            deserialize_strided_items_by_byte_count, deserialize_strided_items_by_len, items, len, len_prefixed,
+            measure_byte_count, strided,
+        },
+        error::{Error, ErrorKind, TraceError as _},
+        io::GrowingMemoryStream,
+        ser_de::{Limits, Serialize as _},
+        stream_ser_de::{StreamDeserializer, StreamSerializer},
+    };
 
     #[test]
     fn len_() {
@@ -229,4 +548,155 @@ mod tests {
         let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
         assert_eq!(len(&mut serializer, &collection), Ok(3));
     }
+
+    #[test]
+    fn check_len_() {
+        let collection = vec![1, 2, 3];
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        assert_eq!(check_len(&mut serializer, &collection, &3u8), Ok(()));
+        assert!(check_len(&mut serializer, &collection, &2u8).is_err());
+        assert!(check_len(&mut serializer, &collection, &-1i8).is_err());
+    }
+
+    #[test]
+    fn measure_byte_count_() {
+        let collection = vec![1u16, 2, 3];
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let measured = measure_byte_count::<u8, _>(&mut serializer, |sizer| items(&collection).serialize(sizer));
+        assert_eq!(measured, Ok(6));
+        assert_eq!(serializer.take().take(), []);
+    }
+
+    #[test]
+    fn measure_byte_count_error() {
+        let collection = vec![0u8; 256];
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let measured =
+            measure_byte_count::<u8, _>(&mut serializer, |sizer| len_prefixed::<u8, _>(&collection).serialize(sizer));
+        assert_eq!(
+            measured,
+            Err(Error::from(ErrorKind::Custom(
+                "the length of the collection is too large for its binary representation"
+            )))
+        );
+    }
+
+    #[test]
+    fn serialize_len_prefixed() {
+        let collection = vec![1u8, 2, 3];
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        assert!(len_prefixed::<u8, _>(&collection).serialize(&mut serializer).is_ok());
+        assert_eq!(serializer.take().take(), [3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn serialize_len_prefixed_too_long() {
+        let collection = vec![0u8; 256];
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        assert_eq!(
+            len_prefixed::<u8, _>(&collection).serialize(&mut serializer),
+            Err(Error::from(ErrorKind::Custom(
+                "the length of the collection is too large for its binary representation"
+            ))
+            .locate(0))
+        );
+    }
+
+    #[test]
+    fn deserialize_len_prefixed_() {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![2, 1, 2, 3]));
+        let collection: Result<Vec<u8>, _> = deserialize_len_prefixed::<_, _, _, u8>(&mut deserializer);
+        assert_eq!(collection, Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn deserialize_len_prefixed_length_limit() {
+        let limits = Limits::DEFAULT.with_max_len(2);
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![3, 1, 2, 3])).with_limits(limits);
+        let collection: Result<Vec<u8>, _> = deserialize_len_prefixed::<_, _, _, u8>(&mut deserializer);
+        assert_eq!(collection, Err(Error::from(ErrorKind::LengthLimit).locate(0)));
+    }
+
+    #[test]
+    fn deserialize_into_length_limit() {
+        let mut collection: Vec<u8> = Vec::new();
+        let limits = Limits::DEFAULT.with_max_len(2);
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![1, 2, 3])).with_limits(limits);
+        assert_eq!(collection.deserialize_into(&mut deserializer, 3u8), Err(ErrorKind::LengthLimit.into()));
+        assert_eq!(collection.deserialize_into(&mut deserializer, 2u8), Ok(()));
+    }
+
+    #[test]
+    fn deserialize_into_clears_on_error() {
+        let mut collection: Vec<u8> = vec![0xFF];
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![1, 2]));
+        assert!(collection.deserialize_into(&mut deserializer, 3u8).is_err());
+        assert_eq!(collection, []);
+    }
+
+    #[test]
+    fn deserialize_into_reuses_capacity() {
+        let mut collection: Vec<u8> = Vec::with_capacity(16);
+        collection.push(0xFF);
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![1, 2, 3]));
+        assert_eq!(collection.deserialize_into(&mut deserializer, 3u8), Ok(()));
+        assert_eq!(collection, [1, 2, 3]);
+        assert_eq!(collection.capacity(), 16);
+    }
+
+    #[test]
+    fn deserialize_into_limits_preallocation() {
+        let mut collection: Vec<u8> = Vec::new();
+        let limits = Limits::DEFAULT.with_max_preallocation(4);
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![1, 2])).with_limits(limits);
+        assert!(collection.deserialize_into(&mut deserializer, 1000u16).is_err());
+        assert!(collection.capacity() < 1000);
+    }
+
+    #[test]
+    fn deserialize_into_preallocates_trusted() {
+        let mut collection: Vec<u8> = Vec::new();
+        let limits = Limits::DEFAULT.with_max_preallocation(usize::MAX);
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![1, 2])).with_limits(limits);
+        assert!(collection.deserialize_into(&mut deserializer, 1000u16).is_err());
+        assert!(collection.capacity() >= 1000);
+    }
+
+    #[test]
+    fn serialize_strided() {
+        let collection = vec![1u8, 2];
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        assert!(strided(&collection, 3).serialize(&mut serializer).is_ok());
+        assert_eq!(serializer.take().take(), [1, 0, 0, 2, 0, 0]);
+    }
+
+    #[test]
+    fn serialize_strided_overflow() {
+        let collection = vec![1u16, 2];
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        assert_eq!(
+            strided(&collection, 1).serialize(&mut serializer).map_err(|err| err.kind()),
+            Err(ErrorKind::LengthExceedsPadding { offset: 1, position: 2 })
+        );
+    }
+
+    #[test]
+    fn deserialize_strided_array_() {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![1, 0xFF, 2, 0xFF]));
+        assert_eq!(deserialize_strided_array::<u8, _, 2>(&mut deserializer, 2), Ok([1, 2]));
+    }
+
+    #[test]
+    fn deserialize_strided_items_by_len_() {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![1, 0xFF, 2, 0xFF, 3]));
+        let collection: Result<Vec<u8>, _> = deserialize_strided_items_by_len(&mut deserializer, &2u8, 2);
+        assert_eq!(collection, Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn deserialize_strided_items_by_byte_count_() {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![1, 0xFF, 2, 0xFF, 3]));
+        let collection: Result<Vec<u8>, _> = deserialize_strided_items_by_byte_count(&mut deserializer, &4u8, 2);
+        assert_eq!(collection, Ok(vec![1, 2]));
+    }
 }
@@ -0,0 +1,403 @@
+//! Interpret binary formats that are only known at runtime.
+//!
+//! Derived types describe their format at compile time, which is not possible
+//! when the format comes from a plugin or a configuration file. A
+//! [`DynamicStruct`] describes a format at runtime instead: it lists fields with
+//! their names, [types](DynamicType), offsets, and byte orders, and it
+//! deserializes a [`DynamicValue`] tree from any
+//! [`Deserializer`].
+//!
+//! ```
+//! use sorbit::byte_order::ByteOrder;
+//! use sorbit::dynamic::{DynamicField, DynamicStruct, DynamicType, DynamicValue};
+//! use sorbit::io::FixedMemoryStream;
+//! use sorbit::stream_ser_de::StreamDeserializer;
+//!
+//! let header = DynamicStruct::new()
+//!     .field("version", DynamicType::U8)
+//!     .with_field(DynamicField::new("len", DynamicType::U16).at(2).with_byte_order(ByteOrder::BigEndian));
+//! let format = DynamicStruct::new()
+//!     .field("header", DynamicType::Struct(header))
+//!     .field("payload", DynamicType::Bytes(2));
+//!
+//! let bytes = [1, 0xFF, 0x00, 0x02, 0xAB, 0xCD];
+//! let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new(&bytes));
+//! let value = format.deserialize(&mut deserializer)?;
+//! assert_eq!(value.get("header.len"), Some(&DynamicValue::U16(2)));
+//! assert_eq!(value.get("payload"), Some(&DynamicValue::Bytes(vec![0xAB, 0xCD])));
+//! # Ok::<(), sorbit::error::Error>(())
+//! ```
+//!
+//! The description can also be created from the [`Schema`] of a derived type,
+//! in which case the leaf fields are interpreted as raw bytes.
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString as _};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::byte_order::ByteOrder;
+use crate::error::{Error, ErrorKind, TraceError as _};
+use crate::schema::Schema;
+use crate::ser_de::{Deserialize as _, Deserializer};
+
+/// The type of a field of a [`DynamicStruct`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DynamicType {
+    /// A [`bool`].
+    Bool,
+    /// A [`u8`].
+    U8,
+    /// A [`u16`].
+    U16,
+    /// A [`u32`].
+    U32,
+    /// A [`u64`].
+    U64,
+    /// A [`u128`].
+    U128,
+    /// A [`i8`].
+    I8,
+    /// A [`i16`].
+    I16,
+    /// A [`i32`].
+    I32,
+    /// A [`i64`].
+    I64,
+    /// A [`i128`].
+    I128,
+    /// A [`f32`].
+    F32,
+    /// A [`f64`].
+    F64,
+    /// The given number of raw bytes.
+    Bytes(usize),
+    /// The given number of items of the same type.
+    Array(Box<DynamicType>, usize),
+    /// A nested structure.
+    Struct(DynamicStruct),
+}
+
+/// A field of a [`DynamicStruct`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DynamicField {
+    name: String,
+    offset: Option<u64>,
+    byte_order: Option<ByteOrder>,
+    ty: DynamicType,
+}
+
+/// The runtime description of a structure.
+///
+/// The fields are deserialized in the order they were added. Like the fields
+/// of derived structures, fields with an offset are padded to their offset,
+/// which is relative to the beginning of the structure, and the structure is
+/// padded to its length if it has one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DynamicStruct {
+    fields: Vec<DynamicField>,
+    byte_order: Option<ByteOrder>,
+    len: Option<u64>,
+}
+
+/// A value deserialized according to a [`DynamicType`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynamicValue {
+    /// A [`bool`] value.
+    Bool(bool),
+    /// A [`u8`] value.
+    U8(u8),
+    /// A [`u16`] value.
+    U16(u16),
+    /// A [`u32`] value.
+    U32(u32),
+    /// A [`u64`] value.
+    U64(u64),
+    /// A [`u128`] value.
+    U128(u128),
+    /// A [`i8`] value.
+    I8(i8),
+    /// A [`i16`] value.
+    I16(i16),
+    /// A [`i32`] value.
+    I32(i32),
+    /// A [`i64`] value.
+    I64(i64),
+    /// A [`i128`] value.
+    I128(i128),
+    /// A [`f32`] value.
+    F32(f32),
+    /// A [`f64`] value.
+    F64(f64),
+    /// Raw bytes.
+    Bytes(Vec<u8>),
+    /// The items of an array.
+    Array(Vec<DynamicValue>),
+    /// The names and values of the fields of a structure.
+    Struct(Vec<(String, DynamicValue)>),
+}
+
+impl DynamicType {
+    /// Deserialize a value of this type.
+    pub fn deserialize<D: Deserializer>(&self, deserializer: &mut D) -> Result<DynamicValue, D::Error> {
+        Ok(match self {
+            Self::Bool => DynamicValue::Bool(bool::deserialize(deserializer)?),
+            Self::U8 => DynamicValue::U8(u8::deserialize(deserializer)?),
+            Self::U16 => DynamicValue::U16(u16::deserialize(deserializer)?),
+            Self::U32 => DynamicValue::U32(u32::deserialize(deserializer)?),
+            Self::U64 => DynamicValue::U64(u64::deserialize(deserializer)?),
+            Self::U128 => DynamicValue::U128(u128::deserialize(deserializer)?),
+            Self::I8 => DynamicValue::I8(i8::deserialize(deserializer)?),
+            Self::I16 => DynamicValue::I16(i16::deserialize(deserializer)?),
+            Self::I32 => DynamicValue::I32(i32::deserialize(deserializer)?),
+            Self::I64 => DynamicValue::I64(i64::deserialize(deserializer)?),
+            Self::I128 => DynamicValue::I128(i128::deserialize(deserializer)?),
+            Self::F32 => DynamicValue::F32(f32::deserialize(deserializer)?),
+            Self::F64 => DynamicValue::F64(f64::deserialize(deserializer)?),
+            Self::Bytes(len) => {
+                let mut bytes = vec![0; *len];
+                deserializer.deserialize_slice(&mut bytes)?;
+                DynamicValue::Bytes(bytes)
+            }
+            Self::Array(item, len) => {
+                let items = (0..*len)
+                    .map(|index| item.deserialize(deserializer).map_err(|err| err.annotate(&index.to_string())));
+                DynamicValue::Array(items.collect::<Result<_, _>>()?)
+            }
+            Self::Struct(structure) => structure.deserialize(deserializer)?,
+        })
+    }
+}
+
+impl DynamicField {
+    /// Create a field with the given `name` and type.
+    pub fn new(name: impl Into<String>, ty: DynamicType) -> Self {
+        Self { name: name.into(), offset: None, byte_order: None, ty }
+    }
+
+    /// Place the field at `offset` bytes from the beginning of the structure.
+    pub fn at(self, offset: u64) -> Self {
+        Self { offset: Some(offset), ..self }
+    }
+
+    /// Deserialize the field in the given byte order.
+    pub fn with_byte_order(self, byte_order: ByteOrder) -> Self {
+        Self { byte_order: Some(byte_order), ..self }
+    }
+
+    /// The name of the field.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The offset of the field from the beginning of the structure, if
+    /// specified.
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// The byte order of the field, if specified.
+    pub fn byte_order(&self) -> Option<ByteOrder> {
+        self.byte_order
+    }
+
+    /// The type of the field.
+    pub fn ty(&self) -> &DynamicType {
+        &self.ty
+    }
+
+    fn deserialize<D: Deserializer>(&self, deserializer: &mut D) -> Result<DynamicValue, D::Error> {
+        if let Some(offset) = self.offset {
+            deserializer.pad(offset)?;
+        }
+        match self.byte_order {
+            Some(byte_order) => deserializer.with_byte_order(byte_order, |de| self.ty.deserialize(de)),
+            None => self.ty.deserialize(deserializer),
+        }
+    }
+}
+
+impl DynamicStruct {
+    /// Create a structure without fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the description of the type that `schema` describes.
+    ///
+    /// Nested structures become [`DynamicType::Struct`], and all other fields
+    /// become [`DynamicType::Bytes`]. Fails if a field has a variable size.
+    pub fn from_schema(schema: &Schema) -> Result<Self, Error> {
+        let mut structure = Self::new();
+        for field in schema.fields() {
+            let ty = match field.schema().layout().size() {
+                _ if !field.schema().fields().is_empty() => DynamicType::Struct(Self::from_schema(field.schema())?),
+                Some(size) => DynamicType::Bytes(size.try_into().map_err(|_| ErrorKind::LengthLimit)?),
+                None => {
+                    return Err(
+                        Error::from(ErrorKind::Custom("the size of the field is not fixed")).annotate(field.id())
+                    );
+                }
+            };
+            let field = DynamicField { name: field.id().into(), offset: field.offset(), byte_order: None, ty };
+            structure.fields.push(field);
+        }
+        structure.len = schema.layout().size();
+        Ok(structure)
+    }
+
+    /// Add a field with the given `name` and type after the existing fields.
+    pub fn field(self, name: impl Into<String>, ty: DynamicType) -> Self {
+        self.with_field(DynamicField::new(name, ty))
+    }
+
+    /// Add a `field` after the existing fields.
+    pub fn with_field(mut self, field: DynamicField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Deserialize the fields in the given byte order, unless they specify
+    /// their own.
+    pub fn with_byte_order(self, byte_order: ByteOrder) -> Self {
+        Self { byte_order: Some(byte_order), ..self }
+    }
+
+    /// Pad the structure to `len` bytes after its fields.
+    pub fn with_len(self, len: u64) -> Self {
+        Self { len: Some(len), ..self }
+    }
+
+    /// The fields of the structure.
+    pub fn fields(&self) -> &[DynamicField] {
+        &self.fields
+    }
+
+    /// The byte order of the structure, if specified.
+    pub fn byte_order(&self) -> Option<ByteOrder> {
+        self.byte_order
+    }
+
+    /// The length the structure is padded to, if specified.
+    pub fn fixed_len(&self) -> Option<u64> {
+        self.len
+    }
+
+    /// Deserialize a [`DynamicValue::Struct`] with the fields of this
+    /// structure.
+    pub fn deserialize<D: Deserializer>(&self, deserializer: &mut D) -> Result<DynamicValue, D::Error> {
+        let deserialize_members = |de: &mut D| {
+            de.deserialize_composite(|de| {
+                let mut values = Vec::with_capacity(self.fields.len());
+                for field in &self.fields {
+                    let position = de.position();
+                    let value = field.deserialize(de).map_err(|err| err.annotate(&field.name).locate(position))?;
+                    values.push((field.name.clone(), value));
+                }
+                if let Some(len) = self.len {
+                    de.pad(len)?;
+                }
+                Ok(DynamicValue::Struct(values))
+            })
+        };
+        match self.byte_order {
+            Some(byte_order) => deserializer.with_byte_order(byte_order, deserialize_members),
+            None => deserialize_members(deserializer),
+        }
+    }
+}
+
+impl DynamicValue {
+    /// Return the nested value at `path`.
+    ///
+    /// The path consists of the names of structure fields and the indices of
+    /// array items, separated by dots, like `header.entries.2`.
+    pub fn get(&self, path: &str) -> Option<&DynamicValue> {
+        path.split('.').try_fold(self, |value, member| match value {
+            Self::Struct(fields) => fields.iter().find(|(name, _)| name == member).map(|(_, value)| value),
+            Self::Array(items) => items.get(member.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::io::FixedMemoryStream;
+    use crate::layout::{FieldLayout, Layout};
+    use crate::stream_ser_de::StreamDeserializer;
+
+    fn deserialize(ty: &DynamicType, bytes: &[u8]) -> Result<DynamicValue, Error> {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new(bytes));
+        ty.deserialize(&mut deserializer)
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!(deserialize(&DynamicType::Bool, &[1]), Ok(DynamicValue::Bool(true)));
+        assert_eq!(deserialize(&DynamicType::I16, &[0xFE, 0xFF]), Ok(DynamicValue::I16(-2)));
+        assert_eq!(deserialize(&DynamicType::F32, &1.5f32.to_le_bytes()), Ok(DynamicValue::F32(1.5)));
+    }
+
+    #[test]
+    fn array() {
+        let ty = DynamicType::Array(Box::new(DynamicType::U16), 2);
+        let value = deserialize(&ty, &[1, 0, 2, 0]).unwrap();
+        assert_eq!(value, DynamicValue::Array(vec![DynamicValue::U16(1), DynamicValue::U16(2)]));
+        assert_eq!(value.get("1"), Some(&DynamicValue::U16(2)));
+        assert_eq!(value.get("2"), None);
+    }
+
+    #[test]
+    fn byte_order() {
+        let structure = DynamicStruct::new()
+            .with_byte_order(ByteOrder::BigEndian)
+            .field("a", DynamicType::U16)
+            .with_field(DynamicField::new("b", DynamicType::U16).with_byte_order(ByteOrder::LittleEndian));
+        let value = deserialize(&DynamicType::Struct(structure), &[0, 1, 2, 0]).unwrap();
+        assert_eq!(value.get("a"), Some(&DynamicValue::U16(1)));
+        assert_eq!(value.get("b"), Some(&DynamicValue::U16(2)));
+    }
+
+    #[test]
+    fn offset_and_len() {
+        let inner = DynamicStruct::new().with_field(DynamicField::new("a", DynamicType::U8).at(1)).with_len(3);
+        let structure = DynamicStruct::new().field("inner", DynamicType::Struct(inner)).field("b", DynamicType::U8);
+        let value = deserialize(&DynamicType::Struct(structure), &[0, 1, 0, 2]).unwrap();
+        assert_eq!(value.get("inner.a"), Some(&DynamicValue::U8(1)));
+        assert_eq!(value.get("b"), Some(&DynamicValue::U8(2)));
+    }
+
+    #[test]
+    fn error_annotated() {
+        let structure = DynamicStruct::new().field("a", DynamicType::U8).field("b", DynamicType::U16);
+        let error = Error::from(ErrorKind::UnexpectedEof { bytes_needed: 2, bytes_available: 1 })
+            .annotate("b")
+            .locate(1);
+        assert_eq!(deserialize(&DynamicType::Struct(structure), &[1, 2]), Err(error));
+    }
+
+    #[test]
+    fn from_schema() {
+        const INNER: &[FieldLayout] = &[FieldLayout::new("a", Some(1), Layout::fixed(1))];
+        const FIELDS: &[FieldLayout] = &[
+            FieldLayout::new("inner", Some(0), Layout::fixed(3)).with_fields(INNER),
+            FieldLayout::new("b", Some(4), Layout::fixed(2)),
+        ];
+        let schema = Schema::new("test", Layout::fixed(6), FIELDS);
+        let structure = DynamicStruct::from_schema(&schema).unwrap();
+        let value = deserialize(&DynamicType::Struct(structure), &[0, 1, 0, 0, 2, 3]).unwrap();
+        assert_eq!(value.get("inner.a"), Some(&DynamicValue::Bytes(vec![1])));
+        assert_eq!(value.get("b"), Some(&DynamicValue::Bytes(vec![2, 3])));
+    }
+
+    #[test]
+    fn from_schema_variable_size() {
+        const FIELDS: &[FieldLayout] = &[FieldLayout::new("a", Some(0), Layout::new(0, None, 0))];
+        let schema = Schema::new("test", Layout::new(0, None, 0), FIELDS);
+        let error = DynamicStruct::from_schema(&schema).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Custom("the size of the field is not fixed"));
+    }
+}
@@ -1,6 +1,7 @@
 //! The error types for sorbit's builtin serializer implementations.
 
 use crate::bit::Error as BitError;
+use crate::ser_de::{Deserializer, Serializer};
 #[cfg(feature = "alloc")]
 use alloc::string::String;
 #[cfg(feature = "alloc")]
@@ -11,9 +12,44 @@ use alloc::vec::Vec;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ErrorKind {
     OutOfBounds,
-    LengthExceedsPadding,
-    UnexpectedEof,
+    /// Padding or an offset asked to move to a position that precedes the
+    /// current position of the stream.
+    LengthExceedsPadding {
+        /// The requested position from the beginning of the composite.
+        offset: u64,
+        /// The current position from the beginning of the composite.
+        position: u64,
+    },
+    /// The stream ended before the requested number of bytes could be read or
+    /// written.
+    UnexpectedEof {
+        /// The number of bytes that were requested.
+        bytes_needed: u64,
+        /// The number of bytes that the stream could provide or accept.
+        bytes_available: u64,
+    },
     InvalidEnumVariant,
+    /// None of the variants of an untagged enum could be deserialized. The
+    /// errors of the individual variants are available through
+    /// [`Error::causes`].
+    NoMatchingVariant,
+    /// The deserialized value is not valid for its type, or is outside the
+    /// range of accepted values.
+    InvalidValue,
+    /// The composites are nested deeper than the deserializer's limit.
+    RecursionLimit,
+    /// More bytes were read than the deserializer's limit.
+    ByteLimit,
+    /// The length of a collection or a string exceeds the deserializer's limit.
+    LengthLimit,
+    UnexpectedFill,
+    /// The magic bytes that identify the data are not the expected ones.
+    BadMagic {
+        /// The magic bytes that were expected.
+        expected: &'static [u8],
+        /// The bytes that were found instead.
+        found: MagicBytes,
+    },
     Bit(BitError),
     Custom(&'static str),
     #[cfg(feature = "std")]
@@ -21,21 +57,56 @@ pub enum ErrorKind {
 }
 
 /// The cause and location of the error that occured during serialization.
+///
+/// Without the `alloc` feature, the error is [`Copy`], as its trace is made of
+/// static strings only.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(not(feature = "alloc"), derive(Copy))]
 pub struct Error {
     kind: ErrorKind,
     trace: Trace,
+    offset: Option<u64>,
+    #[cfg(feature = "alloc")]
+    causes: Vec<Error>,
 }
 
 /// The location of the error that occured during serialization.
+///
+/// The trace is the path of members/items from the outermost to the innermost
+/// one. Without the `alloc` feature, the trace holds the innermost 4 members/items
+/// as static strings, and the rest of the path is left out.
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(not(feature = "alloc"), derive(Copy))]
 pub struct Trace {
+    /// The members/items from the innermost to the outermost one.
+    #[cfg(not(feature = "alloc"))]
+    frames: [&'static str; 4],
     #[cfg(not(feature = "alloc"))]
-    name: Option<&'static str>,
+    len: u8,
+    /// There were more members/items than the frames can hold.
+    #[cfg(not(feature = "alloc"))]
+    truncated: bool,
     #[cfg(feature = "alloc")]
     path: Vec<String>,
 }
 
+/// The bytes found in place of the expected magic bytes.
+///
+/// Only the first [`MagicBytes::CAPACITY`] bytes are kept, so that
+/// [`ErrorKind`] remains [`Copy`] without allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MagicBytes {
+    bytes: [u8; MagicBytes::CAPACITY],
+    len: u8,
+}
+
+/// The error returned when converting an integer into an enum that has no
+/// variant with that discriminant.
+///
+/// The error carries the invalid discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InvalidDiscriminant<T>(pub T);
+
 /// Enable errors to trace the serialized data structure's hierarchy.
 pub trait TraceError {
     /// Annotate the error with the member/item that's being serialized.
@@ -45,6 +116,18 @@ pub trait TraceError {
     /// Annotate the error with the member/item that's being serialized.
     #[cfg(feature = "alloc")]
     fn annotate(self, ident: &str) -> Self;
+
+    /// Record the position of the stream where the failing member/item starts.
+    ///
+    /// Only the first recorded position is kept, as errors are located from
+    /// the innermost member outwards. By default, the position is ignored.
+    fn locate(self, offset: u64) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = offset;
+        self
+    }
 }
 
 /// Enable errors to contain a custom message.
@@ -53,6 +136,32 @@ pub trait MessageError {
     fn message(message: &'static str) -> Self;
 }
 
+/// Serialize a member of a composite, and annotate the error with the
+/// member's name `ident` and the `position` where the member starts.
+///
+/// The derived implementations trace each field with this.
+pub fn trace_serialized<S: Serializer, O>(
+    serializer: &mut S,
+    ident: &'static str,
+    position: u64,
+    serialize_member: impl FnOnce(&mut S) -> Result<O, S::Error>,
+) -> Result<O, S::Error> {
+    serialize_member(serializer).map_err(|error| error.annotate(ident).locate(position))
+}
+
+/// Deserialize a member of a composite, and annotate the error with the
+/// member's name `ident` and the `position` where the member starts.
+///
+/// The derived implementations trace each field with this.
+pub fn trace_deserialized<D: Deserializer, O>(
+    deserializer: &mut D,
+    ident: &'static str,
+    position: u64,
+    deserialize_member: impl FnOnce(&mut D) -> Result<O, D::Error>,
+) -> Result<O, D::Error> {
+    deserialize_member(deserializer).map_err(|error| error.annotate(ident).locate(position))
+}
+
 //------------------------------------------------------------------------------
 // Error implementations
 //------------------------------------------------------------------------------
@@ -62,29 +171,75 @@ impl Error {
     pub fn kind(&self) -> ErrorKind {
         self.kind
     }
+
+    /// Return the position of the stream where the failing member starts, if
+    /// known.
+    ///
+    /// The derived implementations record the position where each field
+    /// starts, and the [`StreamSerializer`](crate::stream_ser_de::StreamSerializer)
+    /// and the [`StreamDeserializer`](crate::stream_ser_de::StreamDeserializer)
+    /// record the position where each composite starts. The innermost one is
+    /// kept.
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// Return the errors that led to this error.
+    ///
+    /// For [`ErrorKind::NoMatchingVariant`], these are the errors of the
+    /// variants in the order they were attempted. Other errors have no causes.
+    #[cfg(feature = "alloc")]
+    pub fn causes(&self) -> &[Error] {
+        &self.causes
+    }
+
+    /// Create an [`ErrorKind::NoMatchingVariant`] error from the errors of
+    /// the variants.
+    pub(crate) fn no_matching_variant(causes: impl IntoIterator<Item = Error>) -> Self {
+        #[cfg(feature = "alloc")]
+        return Self { causes: causes.into_iter().collect(), ..ErrorKind::NoMatchingVariant.into() };
+        #[cfg(not(feature = "alloc"))]
+        {
+            let _ = causes;
+            ErrorKind::NoMatchingVariant.into()
+        }
+    }
+
+    /// Create an [`ErrorKind::UnexpectedEof`] error.
+    pub(crate) fn unexpected_eof(bytes_needed: usize, bytes_available: u64) -> Self {
+        ErrorKind::UnexpectedEof { bytes_needed: bytes_needed as u64, bytes_available }.into()
+    }
 }
 
 impl From<BitError> for Error {
     fn from(value: BitError) -> Self {
-        Self { kind: ErrorKind::Bit(value), trace: Trace::default() }
+        let kind = match value {
+            BitError::InvalidEnumVariant => ErrorKind::InvalidEnumVariant,
+            _ => ErrorKind::Bit(value),
+        };
+        Self::from(kind)
     }
 }
 
 impl TraceError for Error {
     #[cfg(not(feature = "alloc"))]
     fn annotate(self, ident: &'static str) -> Self {
-        Self { kind: self.kind, trace: self.trace.annotate(ident) }
+        Self { trace: self.trace.annotate(ident), ..self }
     }
 
     #[cfg(feature = "alloc")]
     fn annotate(self, ident: &str) -> Self {
-        Self { kind: self.kind, trace: self.trace.annotate(ident) }
+        Self { trace: self.trace.annotate(ident), ..self }
+    }
+
+    fn locate(self, offset: u64) -> Self {
+        Self { offset: self.offset.or(Some(offset)), ..self }
     }
 }
 
 impl MessageError for Error {
     fn message(message: &'static str) -> Self {
-        Self { kind: ErrorKind::Custom(message), trace: Trace::default() }
+        Self::from(ErrorKind::Custom(message))
     }
 }
 
@@ -92,17 +247,24 @@ impl core::error::Error for Error {}
 
 impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        if !self.trace.is_empty() {
-            write!(f, "{}: {}", self.trace, self.kind)
-        } else {
-            write!(f, "{}", self.kind)
+        match (self.trace.is_empty(), self.offset) {
+            (false, Some(offset)) => write!(f, "{} @ offset {offset:#x}: {}", self.trace, self.kind),
+            (false, None) => write!(f, "{}: {}", self.trace, self.kind),
+            (true, Some(offset)) => write!(f, "@ offset {offset:#x}: {}", self.kind),
+            (true, None) => write!(f, "{}", self.kind),
         }
     }
 }
 
 impl From<ErrorKind> for Error {
     fn from(value: ErrorKind) -> Self {
-        Self { kind: value, trace: Trace::default() }
+        Self {
+            kind: value,
+            trace: Trace::default(),
+            offset: None,
+            #[cfg(feature = "alloc")]
+            causes: Vec::new(),
+        }
     }
 }
 
@@ -115,9 +277,22 @@ impl core::fmt::Display for ErrorKind {
         use ErrorKind::*;
         match self {
             OutOfBounds => write!(f, "reading/writing outside readable/writable area of the stream"),
-            LengthExceedsPadding => write!(f, "the current length of the buffer already exceeds the requested padding"),
-            UnexpectedEof => write!(f, "end of file reached, cannot read/write more data"),
+            LengthExceedsPadding { offset, position } => {
+                write!(f, "cannot pad to offset {offset} because the current position {position} is already past it")
+            }
+            UnexpectedEof { bytes_needed, bytes_available } => {
+                write!(f, "end of file reached, needed {bytes_needed} bytes but only {bytes_available} were available")
+            }
             InvalidEnumVariant => write!(f, "the numeric value does not correspond to an enum or bool variant"),
+            NoMatchingVariant => write!(f, "the data does not match any variant of the enum"),
+            InvalidValue => write!(f, "the value is invalid or outside the accepted range"),
+            RecursionLimit => write!(f, "the data is nested deeper than the recursion limit"),
+            ByteLimit => write!(f, "the data is longer than the byte limit"),
+            LengthLimit => write!(f, "the length of the collection exceeds the length limit"),
+            UnexpectedFill => write!(f, "the reserved bytes do not match the expected fill pattern"),
+            BadMagic { expected, found } => {
+                write!(f, "expected the magic bytes {expected:02X?}, found {:02X?}", found.as_bytes())
+            }
             Bit(err) => write!(f, "the bit field cannot be packed: {err}"),
             Custom(message) => write!(f, "{message}"),
             #[cfg(feature = "std")]
@@ -129,10 +304,47 @@ impl core::fmt::Display for ErrorKind {
 #[cfg(feature = "std")]
 impl From<std::io::Error> for ErrorKind {
     fn from(value: std::io::Error) -> Self {
-        match value.kind() {
-            std::io::ErrorKind::UnexpectedEof => ErrorKind::UnexpectedEof,
-            kind => ErrorKind::IO(kind),
-        }
+        ErrorKind::IO(value.kind())
+    }
+}
+
+//------------------------------------------------------------------------------
+// MagicBytes implementations
+//------------------------------------------------------------------------------
+
+impl MagicBytes {
+    /// The maximum number of bytes kept.
+    pub const CAPACITY: usize = 8;
+
+    /// Keep the first [`Self::CAPACITY`] bytes of `bytes`.
+    pub fn new(bytes: &[u8]) -> Self {
+        let len = bytes.len().min(Self::CAPACITY);
+        let mut kept = [0; Self::CAPACITY];
+        kept[..len].copy_from_slice(&bytes[..len]);
+        Self { bytes: kept, len: len as u8 }
+    }
+
+    /// Return the kept bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+}
+
+//------------------------------------------------------------------------------
+// InvalidDiscriminant implementations
+//------------------------------------------------------------------------------
+
+impl<T: core::fmt::Debug + core::fmt::Display> core::error::Error for InvalidDiscriminant<T> {}
+
+impl<T: core::fmt::Display> core::fmt::Display for InvalidDiscriminant<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "the numeric value {} does not correspond to an enum variant", self.0)
+    }
+}
+
+impl<T> From<InvalidDiscriminant<T>> for Error {
+    fn from(_: InvalidDiscriminant<T>) -> Self {
+        ErrorKind::InvalidEnumVariant.into()
     }
 }
 
@@ -144,7 +356,7 @@ impl Trace {
     /// Check if there are any member/item annotations recorded.
     #[cfg(not(feature = "alloc"))]
     pub fn is_empty(&self) -> bool {
-        self.name.is_some()
+        self.len == 0
     }
 
     /// Check if there are any member/item annotations recorded.
@@ -155,8 +367,15 @@ impl Trace {
 
     /// Annotate the item with the member/item that's being serialized.
     #[cfg(not(feature = "alloc"))]
-    pub fn annotate(self, ident: &'static str) -> Self {
-        Self { name: Some(self.name.unwrap_or(ident)) }
+    pub fn annotate(mut self, ident: &'static str) -> Self {
+        match self.frames.get_mut(self.len as usize) {
+            Some(frame) => {
+                *frame = ident;
+                self.len += 1;
+            }
+            None => self.truncated = true,
+        }
+        self
     }
 
     /// Annotate the item with the member/item that's being serialized.
@@ -170,15 +389,18 @@ impl Trace {
 impl core::fmt::Display for Trace {
     #[cfg(not(feature = "alloc"))]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        match self.name {
-            Some(name) => write!(f, "{name}"),
-            None => Ok(()),
+        if self.truncated {
+            write!(f, "..")?
         }
+        for ident in self.frames[..self.len as usize].iter().rev() {
+            write!(f, ".{ident}")?
+        }
+        Ok(())
     }
 
     #[cfg(feature = "alloc")]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        self.path.iter().rev().next().map(|root| write!(f, ".{root}")).unwrap_or(Ok(()))?;
+        self.path.iter().next_back().map(|root| write!(f, ".{root}")).unwrap_or(Ok(()))?;
         for ident in self.path.iter().rev().skip(1) {
             write!(f, ".{ident}")?
         }
@@ -0,0 +1,235 @@
+//! Asynchronous I/O for whole serialized objects.
+//!
+//! Serialization itself is synchronous. To (de)serialize objects over an
+//! asynchronous stream, like a network socket, the bytes of each object are
+//! read or written asynchronously as a whole, and the object is then
+//! (de)serialized in memory. This requires the size of the object to be known
+//! up front: either the object has a fixed size as per its [`StaticLayout`],
+//! or it's preceded by its size in bytes.
+//!
+//! The [`AsyncRead`] and [`AsyncWrite`] traits are intentionally minimal, so
+//! that they can be implemented for the streams of any async runtime with a
+//! few lines of code.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::future::Future;
+
+use crate::byte_order::ByteOrder;
+use crate::error::{Error, ErrorKind};
+use crate::layout::StaticLayout;
+use crate::ser_de::{Deserialize, FromBytes, Limits, Serialize, ToBytes};
+
+/// This trait allows for reading bytes from an asynchronous source.
+///
+/// This is the asynchronous counterpart of [`Read`](super::Read), and it
+/// follows the same contract: the entire buffer must be filled, or an
+/// [`ErrorKind::UnexpectedEof`] must be returned.
+pub trait AsyncRead {
+    /// Read exactly as many bytes as fits in `bytes`.
+    fn read(&mut self, bytes: &mut [u8]) -> impl Future<Output = Result<(), Error>>;
+}
+
+/// This trait allows for writing bytes into an asynchronous sink.
+///
+/// This is the asynchronous counterpart of [`Write`](super::Write).
+pub trait AsyncWrite {
+    /// Write exactly as many bytes as there are in `bytes`.
+    fn write(&mut self, bytes: &[u8]) -> impl Future<Output = Result<(), Error>>;
+}
+
+/// Read and deserialize an object of fixed size.
+///
+/// The size of the object is given by its [`StaticLayout`]. If the size is
+/// not fixed, an error is returned.
+pub async fn read_object<T, R>(reader: &mut R, byte_order: ByteOrder) -> Result<T, Error>
+where
+    T: Deserialize + StaticLayout,
+    R: AsyncRead,
+{
+    let Some(size) = T::LAYOUT.size() else {
+        return Err(ErrorKind::Custom("the size of the object is not fixed").into());
+    };
+    let mut bytes = vec![0; size as usize];
+    reader.read(&mut bytes).await?;
+    T::from_xe_bytes(&bytes, byte_order)
+}
+
+/// Read and deserialize an object preceded by its size in bytes.
+///
+/// The size is stored as `Size`, and it's not part of the object. When
+/// `includes_prefix` is set, the size is expected to account for the size
+/// prefix itself as well. The storage reserved for the object up front is
+/// capped by the default [`Limits`].
+pub async fn read_size_prefixed<Size, T, R>(
+    reader: &mut R,
+    byte_order: ByteOrder,
+    includes_prefix: bool,
+) -> Result<T, Error>
+where
+    Size: Deserialize + StaticLayout,
+    u64: TryFrom<Size>,
+    T: Deserialize,
+    R: AsyncRead,
+{
+    let Some(prefix_size) = Size::LAYOUT.size() else {
+        return Err(ErrorKind::Custom("the size of the size prefix is not fixed").into());
+    };
+    let mut prefix = vec![0; prefix_size as usize];
+    reader.read(&mut prefix).await?;
+    let size = u64::try_from(Size::from_xe_bytes(&prefix, byte_order)?)
+        .map_err(|_| Error::from(ErrorKind::Custom("the size of the object is out of range")))?;
+    let byte_count = match includes_prefix {
+        true => size.checked_sub(prefix_size),
+        false => Some(size),
+    };
+    let Some(byte_count) = byte_count.and_then(|byte_count| usize::try_from(byte_count).ok()) else {
+        return Err(ErrorKind::Custom("the size of the object is smaller than its size prefix").into());
+    };
+    let bytes = read_bytes(reader, byte_count).await?;
+    T::from_xe_bytes(&bytes, byte_order)
+}
+
+/// Serialize and write an object.
+pub async fn write_object<const MULTI_PASS: bool, T, W>(
+    writer: &mut W,
+    object: &T,
+    byte_order: ByteOrder,
+) -> Result<(), Error>
+where
+    T: ToBytes<MULTI_PASS>,
+    W: AsyncWrite,
+{
+    let bytes = object.to_xe_bytes(byte_order)?;
+    writer.write(&bytes).await
+}
+
+/// Serialize and write an object preceded by its size in bytes.
+///
+/// The counterpart of [`read_size_prefixed`].
+pub async fn write_size_prefixed<const MULTI_PASS: bool, Size, T, W>(
+    writer: &mut W,
+    object: &T,
+    byte_order: ByteOrder,
+    includes_prefix: bool,
+) -> Result<(), Error>
+where
+    Size: Serialize + StaticLayout + TryFrom<u64>,
+    T: ToBytes<MULTI_PASS>,
+    W: AsyncWrite,
+{
+    let bytes = object.to_xe_bytes(byte_order)?;
+    let size = match includes_prefix {
+        true => Size::LAYOUT.size().and_then(|prefix_size| prefix_size.checked_add(bytes.len() as u64)),
+        false => Some(bytes.len() as u64),
+    };
+    let Some(size) = size.and_then(|size| Size::try_from(size).ok()) else {
+        return Err(ErrorKind::Custom("the size of the object is too large for its binary representation").into());
+    };
+    writer.write(&size.to_xe_bytes(byte_order)?).await?;
+    writer.write(&bytes).await
+}
+
+/// Read `len` bytes in chunks, so that the storage reserved up front is
+/// capped by the default [`Limits`].
+async fn read_bytes<R: AsyncRead>(reader: &mut R, len: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::with_capacity(Limits::DEFAULT.preallocated_items::<u8>(len));
+    let mut chunk = [0u8; 256];
+    while bytes.len() < len {
+        let chunk_len = chunk.len().min(len - bytes.len());
+        reader.read(&mut chunk[..chunk_len]).await?;
+        bytes.extend_from_slice(&chunk[..chunk_len]);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    use crate::io::{GrowingMemoryStream, Read, Write};
+
+    /// Wraps a synchronous stream, and yields once before every operation.
+    struct Yielding(GrowingMemoryStream);
+
+    async fn yield_now() {
+        let mut yielded = false;
+        core::future::poll_fn(|context| match core::mem::replace(&mut yielded, true) {
+            true => Poll::Ready(()),
+            false => {
+                context.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    impl AsyncRead for Yielding {
+        async fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+            yield_now().await;
+            Read::read(&mut self.0, bytes)
+        }
+    }
+
+    impl AsyncWrite for Yielding {
+        async fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+            yield_now().await;
+            Write::write(&mut self.0, bytes)
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn read_object_fixed() {
+        let mut reader = Yielding(GrowingMemoryStream::from(vec![0x12, 0x34, 0x56]));
+        assert_eq!(block_on(read_object::<u16, _>(&mut reader, ByteOrder::BigEndian)), Ok(0x1234));
+    }
+
+    #[test]
+    fn read_object_eof() {
+        let mut reader = Yielding(GrowingMemoryStream::from(vec![0x12]));
+        assert!(block_on(read_object::<u16, _>(&mut reader, ByteOrder::BigEndian)).is_err());
+    }
+
+    #[test]
+    fn write_object_() {
+        let mut writer = Yielding(GrowingMemoryStream::new());
+        assert_eq!(block_on(write_object(&mut writer, &0x1234u16, ByteOrder::BigEndian)), Ok(()));
+        assert_eq!(writer.0.take(), [0x12, 0x34]);
+    }
+
+    #[test]
+    fn size_prefixed_round_trip() {
+        let mut writer = Yielding(GrowingMemoryStream::new());
+        let result =
+            block_on(write_size_prefixed::<_, u8, _, _>(&mut writer, &[1u8, 2, 3], ByteOrder::BigEndian, true));
+        assert_eq!(result, Ok(()));
+        let bytes = writer.0.take();
+        assert_eq!(bytes, [4, 1, 2, 3]);
+
+        let mut reader = Yielding(GrowingMemoryStream::from(bytes));
+        let object = block_on(read_size_prefixed::<u8, [u8; 3], _>(&mut reader, ByteOrder::BigEndian, true));
+        assert_eq!(object, Ok([1, 2, 3]));
+    }
+
+    #[test]
+    fn read_size_prefixed_smaller_than_prefix() {
+        let mut reader = Yielding(GrowingMemoryStream::from(vec![0]));
+        assert_eq!(
+            block_on(read_size_prefixed::<u8, u8, _>(&mut reader, ByteOrder::BigEndian, true)),
+            Err(ErrorKind::Custom("the size of the object is smaller than its size prefix").into())
+        );
+    }
+}
@@ -45,8 +45,18 @@ impl<Stream: Read> Read for BoundedSection<Stream> {
             self.remaining_bytes -= bytes_to_read;
             self.stream.read(bytes)
         } else {
-            self.remaining_bytes = 0;
-            Err(ErrorKind::UnexpectedEof.into())
+            let bytes_available = core::mem::take(&mut self.remaining_bytes);
+            Err(Error::unexpected_eof(bytes.len(), bytes_available))
+        }
+    }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        if byte_count <= self.remaining_bytes {
+            self.remaining_bytes -= byte_count;
+            self.stream.skip(byte_count)
+        } else {
+            let bytes_available = core::mem::take(&mut self.remaining_bytes);
+            Err(ErrorKind::UnexpectedEof { bytes_needed: byte_count, bytes_available }.into())
         }
     }
 }
@@ -58,8 +68,8 @@ impl<Stream: Write> Write for BoundedSection<Stream> {
             self.remaining_bytes -= bytes_to_write;
             self.stream.write(bytes)
         } else {
-            self.remaining_bytes = 0;
-            Err(ErrorKind::UnexpectedEof.into())
+            let bytes_available = core::mem::take(&mut self.remaining_bytes);
+            Err(Error::unexpected_eof(bytes.len(), bytes_available))
         }
     }
 }
@@ -72,6 +82,7 @@ impl<Stream> Bounded for BoundedSection<Stream> {
 
 #[cfg(test)]
 mod tests {
+    use crate::error::ErrorKind;
     use crate::io::FixedMemoryStream;
 
     use super::*;
@@ -91,7 +102,7 @@ mod tests {
         let mut values = [0u8; 3];
         stream.read(&mut values)?;
         assert_eq!(stream.remaining_bytes(), 1);
-        assert_eq!(stream.is_finished(), false);
+        assert!(!stream.is_finished());
         assert_eq!(values, [1, 2, 3]);
         Ok(())
     }
@@ -103,7 +114,7 @@ mod tests {
         let mut values = [0u8; 4];
         stream.read(&mut values)?;
         assert_eq!(stream.remaining_bytes(), 0);
-        assert_eq!(stream.is_finished(), true);
+        assert!(stream.is_finished());
         assert_eq!(values, [1, 2, 3, 4]);
         Ok(())
     }
@@ -113,9 +124,23 @@ mod tests {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = BoundedSection::new(FixedMemoryStream::new(&mut buffer), 4);
         let mut values = [0u8; 5];
-        assert_eq!(stream.read(&mut values), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(
+            stream.read(&mut values),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 5, bytes_available: 4 }.into())
+        );
         assert_eq!(stream.remaining_bytes(), 0);
-        assert_eq!(stream.is_finished(), true);
+        assert!(stream.is_finished());
+        Ok(())
+    }
+
+    #[test]
+    fn skip() -> Result<(), Error> {
+        let mut buffer = [1, 2, 3, 4, 5, 6, 7];
+        let mut stream = BoundedSection::new(FixedMemoryStream::new(&mut buffer), 4);
+        stream.skip(3)?;
+        assert_eq!(stream.remaining_bytes(), 1);
+        assert_eq!(stream.skip(2), Err(ErrorKind::UnexpectedEof { bytes_needed: 2, bytes_available: 1 }.into()));
+        assert!(stream.is_finished());
         Ok(())
     }
 
@@ -126,7 +151,7 @@ mod tests {
         let values = [0u8; 3];
         stream.write(&values)?;
         assert_eq!(stream.remaining_bytes(), 1);
-        assert_eq!(stream.is_finished(), false);
+        assert!(!stream.is_finished());
         assert_eq!(buffer, [0, 0, 0, 4, 5, 6, 7]);
         Ok(())
     }
@@ -138,7 +163,7 @@ mod tests {
         let values = [0u8; 4];
         stream.write(&values)?;
         assert_eq!(stream.remaining_bytes(), 0);
-        assert_eq!(stream.is_finished(), true);
+        assert!(stream.is_finished());
         assert_eq!(buffer, [0, 0, 0, 0, 5, 6, 7]);
         Ok(())
     }
@@ -148,9 +173,9 @@ mod tests {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = BoundedSection::new(FixedMemoryStream::new(&mut buffer), 4);
         let values = [0u8; 5];
-        assert_eq!(stream.write(&values), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.write(&values), Err(ErrorKind::UnexpectedEof { bytes_needed: 5, bytes_available: 4 }.into()));
         assert_eq!(stream.remaining_bytes(), 0);
-        assert_eq!(stream.is_finished(), true);
+        assert!(stream.is_finished());
         assert_eq!(buffer, [1, 2, 3, 4, 5, 6, 7]);
         Ok(())
     }
@@ -0,0 +1,252 @@
+use super::stream::{Read, Seek, SeekFrom, Write};
+use crate::error::Error;
+
+/// Coalesces small writes into larger chunks before passing them on.
+///
+/// Serializers write every primitive separately, which is slow for streams
+/// that go to a device, like files or sockets. This stream collects the writes
+/// in a buffer and writes them to the underlying stream in chunks as large as
+/// the buffer. Writes that don't fit in the buffer go to the stream directly.
+///
+/// The buffer is provided by the caller, so the stream is also available
+/// without `alloc`. Reading and seeking flush the buffer first, thus the
+/// stream works with revisable serializers too, but seeking often negates the
+/// benefits of buffering.
+///
+/// The buffered bytes must be written with [`flush`](Self::flush) or
+/// [`into_inner`](Self::into_inner) when done, they are lost when the stream
+/// is dropped.
+///
+/// ```
+/// use sorbit::io::{BufferedStream, GrowingMemoryStream};
+/// use sorbit::ser_de::Serialize;
+/// use sorbit::stream_ser_de::StreamSerializer;
+///
+/// let stream = BufferedStream::new(GrowingMemoryStream::new(), [0u8; 64]);
+/// let mut serializer = StreamSerializer::new(stream);
+/// 0xDEADBEEFu32.serialize(&mut serializer).unwrap();
+/// let stream = serializer.take().into_inner().unwrap();
+/// assert_eq!(stream.take(), [0xEF, 0xBE, 0xAD, 0xDE]);
+/// ```
+#[derive(Debug)]
+pub struct BufferedStream<Stream: Write, Buffer: AsMut<[u8]>> {
+    stream: Stream,
+    buffer: Buffer,
+    len: usize,
+}
+
+impl<Stream: Write, Buffer: AsMut<[u8]>> BufferedStream<Stream, Buffer> {
+    /// Create a stream that collects writes in `buffer`.
+    ///
+    /// The capacity of the stream is the length of `buffer`.
+    pub fn new(stream: Stream, buffer: Buffer) -> Self {
+        Self { stream, buffer, len: 0 }
+    }
+
+    /// Return the number of bytes in the buffer not yet written to the
+    /// underlying stream.
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+
+    /// Write the buffered bytes to the underlying stream.
+    ///
+    /// If the underlying stream fails, the bytes remain in the buffer, and
+    /// the next flush tries to write them again.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        if self.len != 0 {
+            self.stream.write(&self.buffer.as_mut()[..self.len])?;
+            self.len = 0;
+        }
+        Ok(())
+    }
+
+    /// Return a reference to the underlying stream.
+    ///
+    /// The buffered bytes are not yet written to the stream.
+    pub fn get_ref(&self) -> &Stream {
+        &self.stream
+    }
+
+    /// Flush the buffer, and return the underlying stream.
+    pub fn into_inner(mut self) -> Result<Stream, Error> {
+        self.flush()?;
+        Ok(self.stream)
+    }
+}
+
+impl<Stream: Write, Buffer: AsRef<[u8]> + AsMut<[u8]>> BufferedStream<Stream, Buffer> {
+    /// Return the number of bytes the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.buffer.as_ref().len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Stream: Write> BufferedStream<Stream, alloc::boxed::Box<[u8]>> {
+    /// Create a stream that collects writes in a buffer of `capacity` bytes
+    /// allocated on the heap.
+    pub fn with_capacity(stream: Stream, capacity: usize) -> Self {
+        Self::new(stream, alloc::vec![0; capacity].into_boxed_slice())
+    }
+}
+
+impl<Stream: Write, Buffer: AsMut<[u8]>> Write for BufferedStream<Stream, Buffer> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let capacity = self.buffer.as_mut().len();
+        if bytes.len() > capacity - self.len {
+            self.flush()?;
+        }
+        if bytes.len() >= capacity {
+            self.stream.write(bytes)
+        } else {
+            self.buffer.as_mut()[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+            self.len += bytes.len();
+            Ok(())
+        }
+    }
+}
+
+impl<Stream: Read + Write, Buffer: AsMut<[u8]>> Read for BufferedStream<Stream, Buffer> {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        self.flush()?;
+        self.stream.read(bytes)
+    }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        self.flush()?;
+        self.stream.skip(byte_count)
+    }
+}
+
+impl<Stream: Write + Seek, Buffer: AsMut<[u8]>> Seek for BufferedStream<Stream, Buffer> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        self.flush()?;
+        self.stream.seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte_order::ByteOrder;
+    use crate::error::ErrorKind;
+    use crate::io::{FixedMemoryStream, GrowingMemoryStream};
+    use crate::ser_de::{RevisableSerializer, Serializer};
+    use crate::stream_ser_de::StreamSerializer;
+
+    /// A stream that records the size of each write.
+    #[derive(Default)]
+    struct Chunks(Vec<Vec<u8>>);
+
+    impl Write for Chunks {
+        fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+            self.0.push(bytes.to_vec());
+            Ok(())
+        }
+    }
+
+    /// A stream that fails the first write.
+    #[derive(Default)]
+    struct Unreliable {
+        failed: bool,
+        chunks: Chunks,
+    }
+
+    impl Write for Unreliable {
+        fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+            if !core::mem::replace(&mut self.failed, true) {
+                return Err(ErrorKind::UnexpectedEof { bytes_needed: bytes.len() as u64, bytes_available: 0 }.into());
+            }
+            self.chunks.write(bytes)
+        }
+    }
+
+    #[test]
+    fn write_coalesced() -> Result<(), Error> {
+        let mut stream = BufferedStream::new(Chunks::default(), [0u8; 4]);
+        stream.write(&[1, 2])?;
+        stream.write(&[3])?;
+        assert_eq!(stream.pending(), 3);
+        assert!(stream.get_ref().0.is_empty());
+        stream.write(&[4, 5])?;
+        stream.write(&[6])?;
+        assert_eq!(stream.into_inner()?.0, [vec![1, 2, 3], vec![4, 5, 6]]);
+        Ok(())
+    }
+
+    #[test]
+    fn write_larger_than_capacity() -> Result<(), Error> {
+        let mut stream = BufferedStream::new(Chunks::default(), [0u8; 4]);
+        stream.write(&[1])?;
+        stream.write(&[2, 3, 4, 5])?;
+        stream.write(&[6])?;
+        assert_eq!(stream.into_inner()?.0, [vec![1], vec![2, 3, 4, 5], vec![6]]);
+        Ok(())
+    }
+
+    #[test]
+    fn flush() -> Result<(), Error> {
+        let mut stream = BufferedStream::with_capacity(Chunks::default(), 8);
+        stream.write(&[1, 2])?;
+        stream.flush()?;
+        stream.flush()?;
+        assert_eq!(stream.pending(), 0);
+        assert_eq!(stream.get_ref().0, [vec![1, 2]]);
+        Ok(())
+    }
+
+    #[test]
+    fn seek_flushes() -> Result<(), Error> {
+        let mut stream = BufferedStream::new(GrowingMemoryStream::new(), [0u8; 8]);
+        stream.write(&[1, 2, 3, 4])?;
+        assert_eq!(stream.stream_position(), Ok(4));
+        stream.seek(SeekFrom::Start(1))?;
+        stream.write(&[5, 6])?;
+        assert_eq!(stream.into_inner()?.take(), [1, 5, 6, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn flush_error() {
+        let mut stream = BufferedStream::new(FixedMemoryStream::new([0u8; 2]), [0u8; 8]);
+        assert_eq!(stream.write(&[1, 2, 3]), Ok(()));
+        assert!(matches!(stream.flush().map_err(|err| err.kind()), Err(ErrorKind::UnexpectedEof { .. })));
+        assert_eq!(stream.pending(), 3);
+    }
+
+    #[test]
+    fn flush_retry() {
+        let mut stream = BufferedStream::new(Unreliable::default(), [0u8; 8]);
+        assert_eq!(stream.write(&[1, 2, 3]), Ok(()));
+        assert!(stream.flush().is_err());
+        assert_eq!(stream.pending(), 3);
+        assert_eq!(stream.flush(), Ok(()));
+        assert_eq!(stream.pending(), 0);
+        assert_eq!(stream.get_ref().chunks.0, [vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn read_flushes() -> Result<(), Error> {
+        let mut stream = BufferedStream::new(GrowingMemoryStream::new(), [0u8; 8]);
+        stream.write(&[1, 2, 3, 4])?;
+        stream.seek(SeekFrom::Start(1))?;
+        stream.write(&[5])?;
+        let mut bytes = [0u8; 2];
+        stream.read(&mut bytes)?;
+        assert_eq!(bytes, [3, 4]);
+        assert_eq!(stream.pending(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn revisable_serializer() -> Result<(), Error> {
+        let stream = BufferedStream::new(GrowingMemoryStream::new(), [0u8; 8]);
+        let mut serializer = StreamSerializer::new(stream).change_byte_order(ByteOrder::BigEndian);
+        let span = serializer.serialize_u16(0)?;
+        serializer.serialize_u8(0xCC)?;
+        serializer.revise_span(&span, |serializer| serializer.serialize_u16(0xAABB))?;
+        assert_eq!(serializer.take().into_inner()?.take(), [0xAA, 0xBB, 0xCC]);
+        Ok(())
+    }
+}
@@ -0,0 +1,373 @@
+use super::lz77::{Token, tokenize};
+use super::{Codec, reserve};
+use crate::error::{Error, ErrorKind};
+use crate::io::{Read, Write};
+use alloc::vec::Vec;
+
+/// The DEFLATE compression algorithm, as specified by RFC 1951.
+///
+/// This is raw DEFLATE data, without the zlib or gzip headers around it.
+/// Decompression supports all block types, compression emits a single block
+/// with the fixed Huffman codes.
+#[derive(Debug)]
+pub struct Deflate;
+
+const WINDOW: usize = 32768;
+const MAX_LEN: usize = 258;
+const END_OF_BLOCK: u16 = 256;
+
+const LEN_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LEN_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+impl Codec for Deflate {
+    fn compress(bytes: &[u8], stream: &mut impl Write) -> Result<(), Error> {
+        let mut writer = BitWriter { stream, bits: 0, count: 0 };
+        writer.write(1, 1)?;
+        writer.write(0b01, 2)?;
+        let mut result = Ok(());
+        tokenize(bytes, WINDOW, MAX_LEN, |token| {
+            if result.is_ok() {
+                result = match token {
+                    Token::Literal(byte) => writer.write_fixed_symbol(u16::from(byte)),
+                    Token::Match { len, distance } => writer.write_match(len as u16, distance as u16),
+                };
+            }
+        });
+        result?;
+        writer.write_fixed_symbol(END_OF_BLOCK)?;
+        writer.flush()
+    }
+
+    fn decompress(stream: &mut impl Read, bytes: &mut Vec<u8>, max_len: usize) -> Result<(), Error> {
+        let mut reader = BitReader { stream, bits: 0, count: 0 };
+        loop {
+            let last = reader.read(1)? == 1;
+            match reader.read(2)? {
+                0b00 => inflate_stored(&mut reader, bytes, max_len)?,
+                0b01 => inflate_codes(
+                    &mut reader,
+                    bytes,
+                    max_len,
+                    &Huffman::fixed_literals()?,
+                    &Huffman::fixed_distances()?,
+                )?,
+                0b10 => {
+                    let (literals, distances) = read_dynamic_codes(&mut reader)?;
+                    inflate_codes(&mut reader, bytes, max_len, &literals, &distances)?
+                }
+                _ => return Err(ErrorKind::InvalidValue.into()),
+            }
+            if last {
+                return Ok(());
+            }
+        }
+    }
+}
+
+struct BitWriter<'a, Stream: Write> {
+    stream: &'a mut Stream,
+    bits: u32,
+    count: u32,
+}
+
+impl<Stream: Write> BitWriter<'_, Stream> {
+    /// Write the lowest `count` bits of `value`, least significant bit first.
+    fn write(&mut self, value: u32, count: u32) -> Result<(), Error> {
+        self.bits |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.stream.write(&[self.bits as u8])?;
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+        Ok(())
+    }
+
+    /// Write a Huffman code of `len` bits, most significant bit first.
+    fn write_code(&mut self, code: u32, len: u32) -> Result<(), Error> {
+        self.write(code.reverse_bits() >> (32 - len), len)
+    }
+
+    fn write_fixed_symbol(&mut self, symbol: u16) -> Result<(), Error> {
+        let symbol = u32::from(symbol);
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn write_match(&mut self, len: u16, distance: u16) -> Result<(), Error> {
+        let len_code = LEN_BASE.iter().rposition(|base| *base <= len).expect("matches are at least 3 long");
+        self.write_fixed_symbol(257 + len_code as u16)?;
+        self.write(u32::from(len - LEN_BASE[len_code]), u32::from(LEN_EXTRA[len_code]))?;
+        let dist_code = DIST_BASE.iter().rposition(|base| *base <= distance).expect("distances are at least 1");
+        self.write_code(dist_code as u32, 5)?;
+        self.write(u32::from(distance - DIST_BASE[dist_code]), u32::from(DIST_EXTRA[dist_code]))
+    }
+
+    /// Write the remaining bits, padded with zeros to a whole byte.
+    fn flush(&mut self) -> Result<(), Error> {
+        match self.count {
+            0 => Ok(()),
+            _ => self.write(0, 8 - self.count),
+        }
+    }
+}
+
+struct BitReader<'a, Stream: Read> {
+    stream: &'a mut Stream,
+    bits: u32,
+    count: u32,
+}
+
+impl<Stream: Read> BitReader<'_, Stream> {
+    /// Read `count` bits, least significant bit first.
+    fn read(&mut self, count: u32) -> Result<u32, Error> {
+        while self.count < count {
+            let mut byte = [0u8];
+            self.stream.read(&mut byte)?;
+            self.bits |= u32::from(byte[0]) << self.count;
+            self.count += 8;
+        }
+        let value = self.bits & ((1 << count) - 1);
+        self.bits >>= count;
+        self.count -= count;
+        Ok(value)
+    }
+
+    /// Drop the remaining bits of the current byte.
+    fn align(&mut self) {
+        self.bits = 0;
+        self.count = 0;
+    }
+}
+
+/// A canonical Huffman code, decoded bit by bit.
+struct Huffman {
+    /// The number of codes of each length.
+    counts: [u16; 16],
+    /// The symbols ordered by their codes.
+    symbols: [u16; 288],
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, Error> {
+        let mut counts = [0u16; 16];
+        lengths.iter().for_each(|len| counts[usize::from(*len)] += 1);
+        counts[0] = 0;
+        let mut left = 1i32;
+        for count in &counts[1..] {
+            left = 2 * left - i32::from(*count);
+            if left < 0 {
+                return Err(ErrorKind::InvalidValue.into());
+            }
+        }
+        let mut offsets = [0u16; 16];
+        (1..15).for_each(|len| offsets[len + 1] = offsets[len] + counts[len]);
+        let mut symbols = [0u16; 288];
+        for (symbol, len) in lengths.iter().enumerate().filter(|(_, len)| **len != 0) {
+            symbols[usize::from(offsets[usize::from(*len)])] = symbol as u16;
+            offsets[usize::from(*len)] += 1;
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn fixed_literals() -> Result<Self, Error> {
+        let mut lengths = [8u8; 288];
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        Self::new(&lengths)
+    }
+
+    fn fixed_distances() -> Result<Self, Error> {
+        Self::new(&[5u8; 30])
+    }
+
+    fn decode(&self, reader: &mut BitReader<impl Read>) -> Result<u16, Error> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for count in &self.counts[1..] {
+            code |= reader.read(1)? as i32;
+            let count = i32::from(*count);
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(ErrorKind::InvalidValue.into())
+    }
+}
+
+fn inflate_stored(reader: &mut BitReader<impl Read>, bytes: &mut Vec<u8>, max_len: usize) -> Result<(), Error> {
+    reader.align();
+    let mut header = [0u8; 4];
+    reader.stream.read(&mut header)?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    if len != !u16::from_le_bytes([header[2], header[3]]) {
+        return Err(ErrorKind::InvalidValue.into());
+    }
+    reserve(bytes, usize::from(len), max_len)?;
+    let start = bytes.len();
+    bytes.resize(start + usize::from(len), 0);
+    reader.stream.read(&mut bytes[start..])
+}
+
+fn inflate_codes(
+    reader: &mut BitReader<impl Read>,
+    bytes: &mut Vec<u8>,
+    max_len: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), Error> {
+    loop {
+        let symbol = literals.decode(reader)?;
+        if symbol < END_OF_BLOCK {
+            reserve(bytes, 1, max_len)?;
+            bytes.push(symbol as u8);
+            continue;
+        } else if symbol == END_OF_BLOCK {
+            return Ok(());
+        }
+        let len_code = usize::from(symbol - 257);
+        let (Some(len_base), Some(len_extra)) = (LEN_BASE.get(len_code), LEN_EXTRA.get(len_code)) else {
+            return Err(ErrorKind::InvalidValue.into());
+        };
+        let len = usize::from(*len_base) + reader.read(u32::from(*len_extra))? as usize;
+        let dist_code = usize::from(distances.decode(reader)?);
+        let (Some(dist_base), Some(dist_extra)) = (DIST_BASE.get(dist_code), DIST_EXTRA.get(dist_code)) else {
+            return Err(ErrorKind::InvalidValue.into());
+        };
+        let distance = usize::from(*dist_base) + reader.read(u32::from(*dist_extra))? as usize;
+        if distance > bytes.len() {
+            return Err(ErrorKind::InvalidValue.into());
+        }
+        reserve(bytes, len, max_len)?;
+        let start = bytes.len() - distance;
+        (start..start + len).for_each(|index| bytes.push(bytes[index]));
+    }
+}
+
+fn read_dynamic_codes(reader: &mut BitReader<impl Read>) -> Result<(Huffman, Huffman), Error> {
+    let num_literals = reader.read(5)? as usize + 257;
+    let num_distances = reader.read(5)? as usize + 1;
+    let num_code_lengths = reader.read(4)? as usize + 4;
+    if num_literals > 286 || num_distances > 30 {
+        return Err(ErrorKind::InvalidValue.into());
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for index in &CODE_LENGTH_ORDER[..num_code_lengths] {
+        code_lengths[*index] = reader.read(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let mut lengths = [0u8; 286 + 30];
+    let mut index = 0;
+    while index < num_literals + num_distances {
+        let (len, repeat) = match code_lengths.decode(reader)? {
+            symbol @ 0..16 => (symbol as u8, 1),
+            16 if index != 0 => (lengths[index - 1], 3 + reader.read(2)? as usize),
+            17 => (0, 3 + reader.read(3)? as usize),
+            18 => (0, 11 + reader.read(7)? as usize),
+            _ => return Err(ErrorKind::InvalidValue.into()),
+        };
+        let Some(run) = lengths[..num_literals + num_distances].get_mut(index..index + repeat) else {
+            return Err(ErrorKind::InvalidValue.into());
+        };
+        run.fill(len);
+        index += repeat;
+    }
+    if lengths[usize::from(END_OF_BLOCK)] == 0 {
+        return Err(ErrorKind::InvalidValue.into());
+    }
+    Ok((
+        Huffman::new(&lengths[..num_literals])?,
+        Huffman::new(&lengths[num_literals..num_literals + num_distances])?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::GrowingMemoryStream;
+
+    fn compress(bytes: &[u8]) -> Vec<u8> {
+        let mut stream = GrowingMemoryStream::new();
+        Deflate::compress(bytes, &mut stream).unwrap();
+        stream.take()
+    }
+
+    fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut decompressed = Vec::new();
+        Deflate::decompress(&mut GrowingMemoryStream::from(bytes), &mut decompressed, usize::MAX)?;
+        Ok(decompressed)
+    }
+
+    #[test]
+    fn compress_empty() {
+        // The same as `zlib.compress(b"", wbits=-15)` in Python.
+        assert_eq!(compress(b""), [0x03, 0x00]);
+    }
+
+    #[test]
+    fn decompress_fixed() {
+        // The result of `zlib.compress(b"hello hello", wbits=-15)` in Python.
+        let compressed = [0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57, 0xC8, 0x00, 0x91, 0x00];
+        assert_eq!(decompress(&compressed), Ok(b"hello hello".to_vec()));
+    }
+
+    #[test]
+    fn decompress_stored() {
+        let compressed = [0x01, 0x03, 0x00, 0xFC, 0xFF, b'a', b'b', b'c'];
+        assert_eq!(decompress(&compressed), Ok(b"abc".to_vec()));
+    }
+
+    #[test]
+    fn decompress_stored_invalid_len() {
+        let compressed = [0x01, 0x03, 0x00, 0xFC, 0xFE, b'a', b'b', b'c'];
+        assert_eq!(decompress(&compressed), Err(ErrorKind::InvalidValue.into()));
+    }
+
+    #[test]
+    fn decompress_dynamic() {
+        // The result of `zlib.compress(text, wbits=-15)` in Python.
+        let compressed = [
+            0xCD, 0xCB, 0xC1, 0x11, 0xC0, 0x30, 0x08, 0x03, 0xB0, 0x55, 0x58, 0xCD, 0xB4, 0x26, 0xBE, 0x40, 0xCE, 0xD9,
+            0xFF, 0xD5, 0x35, 0xAA, 0xBF, 0x50, 0x23, 0x19, 0x67, 0xDD, 0x5D, 0xB2, 0x03, 0xD5, 0x39, 0x87, 0xF5, 0x0C,
+            0xDD, 0x43, 0x7A, 0xF5, 0x55, 0x66, 0xB4, 0x32, 0x5E, 0xE4, 0x0E, 0x36, 0xFE, 0x58, 0x3E,
+        ];
+        let text: Vec<u8> = (0..200usize).map(|i| b"abcdefghij klmnop"[(i * i * i / 3 + i * 5) % 17]).collect();
+        assert_eq!(decompress(&compressed), Ok(text));
+    }
+
+    #[test]
+    fn decompress_truncated() {
+        let compressed = [0xCB, 0x48, 0xCD];
+        assert!(matches!(decompress(&compressed).map_err(|err| err.kind()), Err(ErrorKind::UnexpectedEof { .. })));
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..100000u64).map(|i| (i * i / 7 % 251) as u8).collect();
+        let compressed = compress(&bytes);
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(decompress(&compressed), Ok(bytes));
+    }
+}
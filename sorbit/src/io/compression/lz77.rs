@@ -0,0 +1,126 @@
+use alloc::vec;
+
+/// An item of LZ77-compressed data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    /// A byte copied as is.
+    Literal(u8),
+    /// A repetition of `len` bytes from `distance` bytes before.
+    Match { len: usize, distance: usize },
+}
+
+const MIN_LEN: usize = 3;
+const HASH_BITS: u32 = 15;
+const MAX_CHAIN: usize = 64;
+
+/// Split `bytes` into literals and back-references, greedily taking the
+/// longest match within the last `window` bytes.
+///
+/// Matches are between 3 and `max_len` bytes long.
+pub fn tokenize(bytes: &[u8], window: usize, max_len: usize, mut emit: impl FnMut(Token)) {
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; bytes.len()];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let (len, distance) = longest_match(bytes, pos, &head, &prev, window, max_len);
+        if len >= MIN_LEN {
+            emit(Token::Match { len, distance });
+            (pos..pos + len).for_each(|pos| insert(bytes, pos, &mut head, &mut prev));
+            pos += len;
+        } else {
+            emit(Token::Literal(bytes[pos]));
+            insert(bytes, pos, &mut head, &mut prev);
+            pos += 1;
+        }
+    }
+}
+
+fn insert(bytes: &[u8], pos: usize, head: &mut [usize], prev: &mut [usize]) {
+    if let Some(key) = bytes.get(pos..pos + MIN_LEN) {
+        let hash = hash(key);
+        prev[pos] = head[hash];
+        head[hash] = pos;
+    }
+}
+
+fn longest_match(
+    bytes: &[u8],
+    pos: usize,
+    head: &[usize],
+    prev: &[usize],
+    window: usize,
+    max_len: usize,
+) -> (usize, usize) {
+    let Some(key) = bytes.get(pos..pos + MIN_LEN) else {
+        return (0, 0);
+    };
+    let max_len = core::cmp::min(max_len, bytes.len() - pos);
+    let mut best = (0, 0);
+    let mut candidate = head[hash(key)];
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || pos - candidate > window {
+            break;
+        }
+        let len = bytes[candidate..].iter().zip(&bytes[pos..pos + max_len]).take_while(|(a, b)| a == b).count();
+        if len > best.0 {
+            best = (len, pos - candidate);
+            if len == max_len {
+                break;
+            }
+        }
+        candidate = prev[candidate];
+    }
+    best
+}
+
+fn hash(key: &[u8]) -> usize {
+    let value = u32::from(key[0]) << 16 | u32::from(key[1]) << 8 | u32::from(key[2]);
+    (value.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn collect(bytes: &[u8], window: usize, max_len: usize) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        tokenize(bytes, window, max_len, |token| tokens.push(token));
+        tokens
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(
+            collect(b"abc", 16, 16),
+            [
+                Token::Literal(b'a'),
+                Token::Literal(b'b'),
+                Token::Literal(b'c')
+            ]
+        );
+    }
+
+    #[test]
+    fn overlapping_match() {
+        let expected = [Token::Literal(b'a'), Token::Match { len: 5, distance: 1 }];
+        assert_eq!(collect(b"aaaaaa", 16, 16), expected);
+    }
+
+    #[test]
+    fn max_len() {
+        let expected = [
+            Token::Literal(b'a'),
+            Token::Match { len: 4, distance: 1 },
+            Token::Match { len: 3, distance: 1 },
+        ];
+        assert_eq!(collect(b"aaaaaaaa", 16, 4), expected);
+    }
+
+    #[test]
+    fn window() {
+        let tokens = collect(b"abcxxxxabc", 4, 16);
+        assert!(!tokens.contains(&Token::Match { len: 3, distance: 7 }));
+        assert_eq!(collect(b"abcxxxxabc", 7, 16).last(), Some(&Token::Match { len: 3, distance: 7 }));
+    }
+}
@@ -0,0 +1,137 @@
+use super::lz77::{Token, tokenize};
+use super::{Codec, read_byte, reserve};
+use crate::error::{Error, ErrorKind};
+use crate::io::{Read, Write};
+use alloc::vec::Vec;
+
+/// The LZSS compression algorithm, as commonly used in game archives and
+/// firmware images.
+///
+/// The compressed data is a sequence of groups, each made of a flag byte and
+/// up to eight items. The bits of the flag byte, starting from the least
+/// significant one, tell whether the next item is a literal byte (1), or a
+/// back-reference (0). A back-reference is a little-endian `u16`, where the
+/// lowest 12 bits are the distance minus one, and the highest 4 bits are the
+/// length minus three. This gives a window of 4096 bytes and matches of 3 to
+/// 18 bytes.
+///
+/// The data has no end marker, it's decompressed until the end of the stream.
+#[derive(Debug)]
+pub struct Lzss;
+
+const WINDOW: usize = 4096;
+const MIN_LEN: usize = 3;
+const MAX_LEN: usize = 18;
+
+impl Codec for Lzss {
+    fn compress(bytes: &[u8], stream: &mut impl Write) -> Result<(), Error> {
+        let mut group = Vec::with_capacity(17);
+        let mut flags = 0u8;
+        let mut count = 0;
+        let mut result = Ok(());
+        tokenize(bytes, WINDOW, MAX_LEN, |token| {
+            match token {
+                Token::Literal(byte) => {
+                    flags |= 1 << count;
+                    group.push(byte);
+                }
+                Token::Match { len, distance } => {
+                    let reference = ((len - MIN_LEN) << 12 | (distance - 1)) as u16;
+                    group.extend_from_slice(&reference.to_le_bytes());
+                }
+            }
+            count += 1;
+            if count == 8 {
+                if result.is_ok() {
+                    result = write_group(stream, flags, &group);
+                }
+                flags = 0;
+                group.clear();
+                count = 0;
+            }
+        });
+        result?;
+        match count {
+            0 => Ok(()),
+            _ => write_group(stream, flags, &group),
+        }
+    }
+
+    fn decompress(stream: &mut impl Read, bytes: &mut Vec<u8>, max_len: usize) -> Result<(), Error> {
+        while let Some(flags) = read_byte(stream)? {
+            for bit in 0..8 {
+                let Some(first) = read_byte(stream)? else {
+                    return Ok(());
+                };
+                if flags & (1 << bit) != 0 {
+                    reserve(bytes, 1, max_len)?;
+                    bytes.push(first);
+                } else {
+                    let mut second = [0u8];
+                    stream.read(&mut second)?;
+                    let reference = u16::from_le_bytes([first, second[0]]) as usize;
+                    let len = (reference >> 12) + MIN_LEN;
+                    let distance = (reference & 0xFFF) + 1;
+                    if distance > bytes.len() {
+                        return Err(ErrorKind::InvalidValue.into());
+                    }
+                    reserve(bytes, len, max_len)?;
+                    let start = bytes.len() - distance;
+                    (start..start + len).for_each(|index| bytes.push(bytes[index]));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_group(stream: &mut impl Write, flags: u8, items: &[u8]) -> Result<(), Error> {
+    stream.write(&[flags])?;
+    stream.write(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::GrowingMemoryStream;
+
+    fn compress(bytes: &[u8]) -> Vec<u8> {
+        let mut stream = GrowingMemoryStream::new();
+        Lzss::compress(bytes, &mut stream).unwrap();
+        stream.take()
+    }
+
+    fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut decompressed = Vec::new();
+        Lzss::decompress(&mut GrowingMemoryStream::from(bytes), &mut decompressed, usize::MAX)?;
+        Ok(decompressed)
+    }
+
+    #[test]
+    fn compress_literals() {
+        assert_eq!(compress(b"ab"), [0b11, b'a', b'b']);
+    }
+
+    #[test]
+    fn compress_match() {
+        assert_eq!(compress(b"aaaaa"), [0b01, b'a', 0x00, 0x10]);
+    }
+
+    #[test]
+    fn decompress_match() {
+        assert_eq!(decompress(&[0b01, b'a', 0x00, 0x10]), Ok(b"aaaaa".to_vec()));
+    }
+
+    #[test]
+    fn decompress_invalid_distance() {
+        assert_eq!(decompress(&[0b10, 0x00, 0x10]), Err(ErrorKind::InvalidValue.into()));
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..5000u32).map(|i| (i * i / 7 % 13) as u8).collect();
+        let compressed = compress(&bytes);
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(decompress(&compressed), Ok(bytes));
+    }
+}
@@ -0,0 +1,185 @@
+use super::GrowingMemoryStream;
+use super::stream::{Read, Seek, SeekFrom, Write};
+use crate::error::{Error, ErrorKind};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+mod deflate;
+mod lz77;
+mod lzss;
+
+pub use deflate::Deflate;
+pub use lzss::Lzss;
+
+/// A compression algorithm used by a [`CompressedStream`].
+pub trait Codec {
+    /// Compress all of `bytes` and write the result into `stream`.
+    fn compress(bytes: &[u8], stream: &mut impl Write) -> Result<(), Error>;
+
+    /// Decompress the data in `stream` and append it to `bytes`.
+    ///
+    /// Self-terminating formats stop at their end marker, others read the
+    /// stream until its end. Decompression fails with [`ErrorKind::ByteLimit`]
+    /// if the decompressed data would be longer than `max_len`.
+    fn decompress(stream: &mut impl Read, bytes: &mut Vec<u8>, max_len: usize) -> Result<(), Error>;
+}
+
+/// Compresses the bytes written to it, or decompresses the bytes read from it.
+///
+/// When reading, [`decompress`](Self::decompress) decompresses the entire
+/// underlying stream up front, and the reads are served from the decompressed
+/// data. Wrapping a compressed section of a file, for example in a
+/// [`BoundedSection`](super::BoundedSection), lets you deserialize its
+/// contents like any other data.
+///
+/// When writing, the uncompressed data is collected in memory, and compressed
+/// into the underlying stream by [`finish`](Self::finish).
+///
+/// In both cases, the stream can [`Seek`] within the uncompressed data, so
+/// revisable serializers work as well.
+///
+/// ```
+/// use sorbit::io::{DeflateStream, GrowingMemoryStream};
+/// use sorbit::ser_de::{Deserialize, Serialize};
+/// use sorbit::stream_ser_de::{StreamDeserializer, StreamSerializer};
+///
+/// let mut serializer = StreamSerializer::new(DeflateStream::new(GrowingMemoryStream::new()));
+/// [0xABu8; 100].serialize(&mut serializer).unwrap();
+/// let compressed = serializer.take().finish().unwrap().take();
+/// assert!(compressed.len() < 100);
+///
+/// let stream = DeflateStream::decompress(GrowingMemoryStream::from(compressed)).unwrap();
+/// let mut deserializer = StreamDeserializer::new(stream);
+/// assert_eq!(<[u8; 100]>::deserialize(&mut deserializer), Ok([0xABu8; 100]));
+/// ```
+#[derive(Debug)]
+pub struct CompressedStream<Stream, C: Codec> {
+    stream: Stream,
+    data: GrowingMemoryStream,
+    codec: PhantomData<C>,
+}
+
+/// A [`CompressedStream`] using [`Deflate`].
+pub type DeflateStream<Stream> = CompressedStream<Stream, Deflate>;
+
+/// A [`CompressedStream`] using [`Lzss`].
+pub type LzssStream<Stream> = CompressedStream<Stream, Lzss>;
+
+impl<Stream, C: Codec> CompressedStream<Stream, C> {
+    /// Create an empty stream that writes compressed data to `stream` when
+    /// finished.
+    pub fn new(stream: Stream) -> Self {
+        Self { stream, data: GrowingMemoryStream::new(), codec: PhantomData }
+    }
+
+    /// Return the original stream, discarding the uncompressed data.
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+}
+
+impl<Stream: Read, C: Codec> CompressedStream<Stream, C> {
+    /// Create a stream that reads the decompressed contents of `stream`.
+    pub fn decompress(stream: Stream) -> Result<Self, Error> {
+        Self::decompress_limited(stream, usize::MAX)
+    }
+
+    /// Create a stream that reads the decompressed contents of `stream`, but
+    /// fail with [`ErrorKind::ByteLimit`] if they are longer than `max_len`.
+    ///
+    /// Use it to guard against decompression bombs when reading untrusted
+    /// data.
+    pub fn decompress_limited(mut stream: Stream, max_len: usize) -> Result<Self, Error> {
+        let mut bytes = Vec::new();
+        C::decompress(&mut stream, &mut bytes, max_len)?;
+        Ok(Self { stream, data: GrowingMemoryStream::from(bytes), codec: PhantomData })
+    }
+}
+
+impl<Stream: Write, C: Codec> CompressedStream<Stream, C> {
+    /// Compress the uncompressed data, write it to the original stream, and
+    /// return the original stream.
+    ///
+    /// When the stream was decompressed from the original stream, this writes
+    /// the (possibly modified) data compressed again after the original
+    /// compressed data.
+    pub fn finish(mut self) -> Result<Stream, Error> {
+        C::compress(&self.data.take(), &mut self.stream)?;
+        Ok(self.stream)
+    }
+}
+
+impl<Stream, C: Codec> Read for CompressedStream<Stream, C> {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        self.data.read(bytes)
+    }
+}
+
+impl<Stream, C: Codec> Write for CompressedStream<Stream, C> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.data.write(bytes)
+    }
+}
+
+impl<Stream, C: Codec> Seek for CompressedStream<Stream, C> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        self.data.seek(pos)
+    }
+}
+
+/// Read a single byte, or return `None` at the end of the stream.
+fn read_byte(stream: &mut impl Read) -> Result<Option<u8>, Error> {
+    let mut byte = [0u8];
+    match stream.read(&mut byte) {
+        Ok(()) => Ok(Some(byte[0])),
+        Err(err) if matches!(err.kind(), ErrorKind::UnexpectedEof { bytes_available: 0, .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Check that `bytes` can grow by `additional` bytes without exceeding `max_len`.
+fn reserve(bytes: &[u8], additional: usize, max_len: usize) -> Result<(), Error> {
+    match bytes.len().checked_add(additional) {
+        Some(len) if len <= max_len => Ok(()),
+        _ => Err(ErrorKind::ByteLimit.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() -> Result<(), Error> {
+        let compressed = DeflateStream::new(GrowingMemoryStream::new()).finish()?.take();
+        let mut stream = DeflateStream::decompress(GrowingMemoryStream::from(compressed))?;
+        assert_eq!(stream.stream_len(), Ok(0));
+        let eof = ErrorKind::UnexpectedEof { bytes_needed: 1, bytes_available: 0 };
+        assert_eq!(stream.read(&mut [0u8]), Err(eof.into()));
+        Ok(())
+    }
+
+    #[test]
+    fn seek_while_writing() -> Result<(), Error> {
+        let mut stream = LzssStream::new(GrowingMemoryStream::new());
+        stream.write(&[1, 2, 3])?;
+        stream.seek(SeekFrom::Start(1))?;
+        stream.write(&[4])?;
+        let compressed = stream.finish()?.take();
+        let mut stream = LzssStream::decompress(GrowingMemoryStream::from(compressed))?;
+        let mut values = [0u8; 3];
+        stream.read(&mut values)?;
+        assert_eq!(values, [1, 4, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn decompress_limited() -> Result<(), Error> {
+        let mut stream = DeflateStream::new(GrowingMemoryStream::new());
+        stream.write(&[0u8; 1000])?;
+        let compressed = stream.finish()?.take();
+        let result = DeflateStream::decompress_limited(GrowingMemoryStream::from(compressed), 999);
+        assert_eq!(result.map(|_| ()), Err(ErrorKind::ByteLimit.into()));
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use core::ops::Range;
+
+use crate::error::{Error, ErrorKind};
+
+/// Verify that a reserved region of `bytes` contains only the `pattern` byte.
+///
+/// This is useful to check that padding and other reserved regions of a
+/// serialized frame don't leak any data. Returns an error if `region` is
+/// outside `bytes`, or if any byte in `region` differs from `pattern`.
+///
+/// ```
+/// use sorbit::io::verify_fill;
+///
+/// let frame = [0xAB, 0x00, 0x00, 0x00, 0xCD];
+/// assert!(verify_fill(&frame, 1..4, 0x00).is_ok());
+/// assert!(verify_fill(&frame, 0..4, 0x00).is_err());
+/// ```
+pub fn verify_fill(bytes: &[u8], region: Range<usize>, pattern: u8) -> Result<(), Error> {
+    match bytes.get(region) {
+        Some(reserved) if reserved.iter().all(|byte| *byte == pattern) => Ok(()),
+        Some(_) => Err(ErrorKind::UnexpectedFill.into()),
+        None => Err(ErrorKind::OutOfBounds.into()),
+    }
+}
+
+/// Verify multiple reserved regions of `bytes` with [`verify_fill`].
+pub fn verify_fill_regions(
+    bytes: &[u8],
+    regions: impl IntoIterator<Item = Range<usize>>,
+    pattern: u8,
+) -> Result<(), Error> {
+    regions.into_iter().try_for_each(|region| verify_fill(bytes, region, pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_fill() {
+        assert_eq!(verify_fill(&[1, 0xFF, 0xFF, 2], 1..3, 0xFF), Ok(()));
+        assert_eq!(verify_fill(&[1, 2], 1..1, 0xFF), Ok(()));
+    }
+
+    #[test]
+    fn mismatching_fill() {
+        assert_eq!(verify_fill(&[1, 0xFF, 0xFE, 2], 1..3, 0xFF), Err(ErrorKind::UnexpectedFill.into()));
+    }
+
+    #[test]
+    fn region_out_of_bounds() {
+        assert_eq!(verify_fill(&[0, 0], 1..3, 0), Err(ErrorKind::OutOfBounds.into()));
+    }
+
+    #[test]
+    fn multiple_regions() {
+        let bytes = [1, 0, 0, 2, 0, 3];
+        assert_eq!(verify_fill_regions(&bytes, [1..3, 4..5], 0), Ok(()));
+        assert_eq!(verify_fill_regions(&bytes, [1..3, 3..5], 0), Err(ErrorKind::UnexpectedFill.into()));
+    }
+}
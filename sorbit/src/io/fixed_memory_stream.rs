@@ -1,4 +1,4 @@
-use super::stream::{Read, Seek, SeekFrom, Write};
+use super::stream::{BorrowRead, Read, Seek, SeekFrom, Write};
 use crate::{
     error::{Error, ErrorKind},
     io::Bounded,
@@ -9,18 +9,49 @@ use crate::{
 /// You may pass a vector, an in-memory slice, or a memory mapped file, mutable or not.
 /// The size of the buffer will never be changed, even if the type you passed is
 /// resizable. Reads and writes outside the buffer will result in an error.
+///
+/// The stream doesn't have to own the buffer. Over a borrowed slice, like a DMA
+/// buffer, the stream reads and writes the slice directly, without copying it.
+/// See [`SliceStream`] and [`SliceStreamMut`]:
+///
+/// ```
+/// use sorbit::io::SliceStreamMut;
+/// use sorbit::ser_de::{Deserialize, Serialize};
+/// use sorbit::stream_ser_de::{StreamDeserializer, StreamSerializer};
+///
+/// let mut dma_buffer = [0u8; 4];
+/// let mut serializer = StreamSerializer::new(SliceStreamMut::new(&mut dma_buffer));
+/// 0xDEADBEEFu32.serialize(&mut serializer).unwrap();
+/// assert_eq!(dma_buffer, [0xEF, 0xBE, 0xAD, 0xDE]);
+///
+/// let mut deserializer = StreamDeserializer::new(SliceStreamMut::new(&mut dma_buffer));
+/// assert_eq!(u32::deserialize(&mut deserializer), Ok(0xDEADBEEF));
+/// ```
 #[derive(Debug)]
 pub struct FixedMemoryStream<Buffer> {
     buffer: Buffer,
     stream_pos: usize,
 }
 
+/// A read-only [`FixedMemoryStream`] that borrows its buffer.
+///
+/// The stream can also lend the bytes it reads via [`BorrowRead`].
+pub type SliceStream<'a> = FixedMemoryStream<&'a [u8]>;
+
+/// A read-write [`FixedMemoryStream`] that borrows its buffer.
+pub type SliceStreamMut<'a> = FixedMemoryStream<&'a mut [u8]>;
+
 impl<Buffer> FixedMemoryStream<Buffer> {
     /// Create a stream from the given buffer.
     pub fn new(buffer: Buffer) -> Self {
         Self { buffer, stream_pos: 0 }
     }
 
+    /// Return a reference to the underlying buffer.
+    pub fn get_ref(&self) -> &Buffer {
+        &self.buffer
+    }
+
     /// Return the underlying buffer and consume `self`.
     pub fn take(self) -> Buffer {
         self.buffer
@@ -35,7 +66,34 @@ impl<Buffer: AsRef<[u8]>> Read for FixedMemoryStream<Buffer> {
             self.stream_pos += bytes.len();
             Ok(())
         } else {
-            Err(ErrorKind::UnexpectedEof.into())
+            Err(Error::unexpected_eof(
+                bytes.len(),
+                self.buffer.as_ref().len().saturating_sub(self.stream_pos) as u64,
+            ))
+        }
+    }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        let bytes_available = self.buffer.as_ref().len().saturating_sub(self.stream_pos) as u64;
+        match byte_count <= bytes_available {
+            true => {
+                self.stream_pos += byte_count as usize;
+                Ok(())
+            }
+            false => Err(ErrorKind::UnexpectedEof { bytes_needed: byte_count, bytes_available }.into()),
+        }
+    }
+}
+
+impl<'de> BorrowRead<'de> for FixedMemoryStream<&'de [u8]> {
+    fn read_borrowed(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        let buffer: &'de [u8] = self.buffer;
+        match buffer.get(self.stream_pos..).and_then(|remaining| remaining.get(..len)) {
+            Some(bytes) => {
+                self.stream_pos += len;
+                Ok(bytes)
+            }
+            None => Err(Error::unexpected_eof(len, buffer.len().saturating_sub(self.stream_pos) as u64)),
         }
     }
 }
@@ -48,7 +106,10 @@ impl<Buffer: AsMut<[u8]>> Write for FixedMemoryStream<Buffer> {
             self.stream_pos += bytes.len();
             Ok(())
         } else {
-            Err(ErrorKind::UnexpectedEof.into())
+            Err(Error::unexpected_eof(
+                bytes.len(),
+                self.buffer.as_mut().len().saturating_sub(self.stream_pos) as u64,
+            ))
         }
     }
 }
@@ -61,7 +122,7 @@ impl<Buffer: AsRef<[u8]>> Seek for FixedMemoryStream<Buffer> {
             self.stream_pos = new_stream_pos as usize;
             Ok(self.stream_pos as u64)
         } else {
-            Err(ErrorKind::UnexpectedEof.into())
+            Err(ErrorKind::OutOfBounds.into())
         }
     }
 
@@ -119,10 +180,35 @@ mod tests {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = FixedMemoryStream::new(&mut buffer);
         let mut values = [0u8; 8];
-        assert_eq!(stream.read(&mut values), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(
+            stream.read(&mut values),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 8, bytes_available: 7 }.into())
+        );
         assert_eq!(stream.stream_position(), Ok(0));
     }
 
+    #[test]
+    fn skip() {
+        let mut stream = FixedMemoryStream::new([1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(stream.skip(5), Ok(()));
+        assert_eq!(stream.stream_position(), Ok(5));
+        assert_eq!(stream.skip(3), Err(ErrorKind::UnexpectedEof { bytes_needed: 3, bytes_available: 2 }.into()));
+        assert_eq!(stream.stream_position(), Ok(5));
+    }
+
+    #[test]
+    fn read_borrowed() {
+        let buffer = [1, 2, 3, 4, 5, 6, 7];
+        let mut stream = FixedMemoryStream::new(&buffer[..]);
+        assert_eq!(stream.read_borrowed(3), Ok(&buffer[0..3]));
+        assert_eq!(stream.read_borrowed(4), Ok(&buffer[3..7]));
+        assert_eq!(
+            stream.read_borrowed(1),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 1, bytes_available: 0 }.into())
+        );
+        assert_eq!(stream.stream_position(), Ok(7));
+    }
+
     #[test]
     fn write_well_within_bounds() -> Result<(), Error> {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
@@ -150,7 +236,7 @@ mod tests {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = FixedMemoryStream::new(&mut buffer);
         let values = [0u8; 8];
-        assert_eq!(stream.write(&values), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.write(&values), Err(ErrorKind::UnexpectedEof { bytes_needed: 8, bytes_available: 7 }.into()));
         assert_eq!(stream.stream_position(), Ok(0));
         assert_eq!(buffer, [1, 2, 3, 4, 5, 6, 7]);
     }
@@ -167,7 +253,7 @@ mod tests {
     fn seek_from_start_out_of_bounds() {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = FixedMemoryStream::new(&mut buffer);
-        assert_eq!(stream.seek(SeekFrom::Start(9)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::Start(9)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_pos, 0);
     }
 
@@ -184,7 +270,7 @@ mod tests {
     fn seek_from_current_out_of_bounds() {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = FixedMemoryStream::new(&mut buffer);
-        assert_eq!(stream.seek(SeekFrom::Current(9)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::Current(9)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_pos, 0);
     }
 
@@ -192,7 +278,7 @@ mod tests {
     fn seek_from_current_negative_out_of_bounds() {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = FixedMemoryStream::new(&mut buffer);
-        assert_eq!(stream.seek(SeekFrom::Current(-2)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::Current(-2)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_pos, 0);
     }
 
@@ -208,7 +294,7 @@ mod tests {
     fn seek_from_end_out_of_bounds() {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = FixedMemoryStream::new(&mut buffer);
-        assert_eq!(stream.seek(SeekFrom::End(2)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::End(2)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_pos, 0);
     }
 
@@ -216,7 +302,7 @@ mod tests {
     fn seek_from_end_negative_out_of_bounds() {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = FixedMemoryStream::new(&mut buffer);
-        assert_eq!(stream.seek(SeekFrom::End(-12)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::End(-12)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_pos, 0);
     }
 }
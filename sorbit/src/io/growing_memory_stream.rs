@@ -29,6 +29,12 @@ impl GrowingMemoryStream {
     }
 }
 
+impl Default for GrowingMemoryStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl From<Vec<u8>> for GrowingMemoryStream {
     fn from(value: Vec<u8>) -> Self {
         Self { buffer: value, stream_pos: 0 }
@@ -49,7 +55,18 @@ impl Read for GrowingMemoryStream {
             self.stream_pos += bytes.len();
             Ok(())
         } else {
-            Err(ErrorKind::UnexpectedEof.into())
+            Err(Error::unexpected_eof(bytes.len(), self.buffer.len().saturating_sub(self.stream_pos) as u64))
+        }
+    }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        let bytes_available = self.buffer.len().saturating_sub(self.stream_pos) as u64;
+        match byte_count <= bytes_available {
+            true => {
+                self.stream_pos += byte_count as usize;
+                Ok(())
+            }
+            false => Err(ErrorKind::UnexpectedEof { bytes_needed: byte_count, bytes_available }.into()),
         }
     }
 }
@@ -69,10 +86,10 @@ impl Seek for GrowingMemoryStream {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
         let new_stream_pos = pos.absolute(self.stream_pos as u64, self.buffer.len() as u64);
         if let Ok(new_stream_pos) = usize::try_from(new_stream_pos) {
-            self.stream_pos = new_stream_pos as usize;
+            self.stream_pos = new_stream_pos;
             Ok(self.stream_pos as u64)
         } else {
-            Err(ErrorKind::UnexpectedEof.into())
+            Err(ErrorKind::OutOfBounds.into())
         }
     }
 }
@@ -112,7 +129,10 @@ mod tests {
     fn read_outside_bounds() {
         let mut stream = GrowingMemoryStream::from(vec![1, 2, 3, 4, 5, 6, 7]);
         let mut values = [0u8; 8];
-        assert_eq!(stream.read(&mut values), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(
+            stream.read(&mut values),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 8, bytes_available: 7 }.into())
+        );
         assert_eq!(stream.stream_position(), Ok(0));
     }
 
@@ -187,7 +207,7 @@ mod tests {
     #[test]
     fn seek_from_current_negative_out_of_bounds() {
         let mut stream = GrowingMemoryStream::from(vec![1, 2, 3, 4, 5, 6, 7]);
-        assert_eq!(stream.seek(SeekFrom::Current(-2)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::Current(-2)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_pos, 0);
     }
 
@@ -208,7 +228,7 @@ mod tests {
     #[test]
     fn seek_from_end_negative_out_of_bounds() {
         let mut stream = GrowingMemoryStream::from(vec![1, 2, 3, 4, 5, 6, 7]);
-        assert_eq!(stream.seek(SeekFrom::End(-12)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::End(-12)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_pos, 0);
     }
 }
@@ -0,0 +1,204 @@
+use super::stream::{Read, Seek, SeekFrom};
+use crate::error::{Error, ErrorKind};
+use alloc::collections::VecDeque;
+
+/// Makes a forward-only stream seekable within a window of recently read bytes.
+///
+/// The stream keeps the last `capacity` bytes read from the underlying stream
+/// in a ring buffer. Seeking backward is possible as long as the target is
+/// still in the buffer, seeking forward reads and buffers the skipped bytes.
+/// Seeking relative to the end of the stream is not supported.
+///
+/// This allows peeking at the data of sockets, pipes, and other streams that
+/// can't [`Seek`], for example to identify a message by its magic number
+/// before deserializing it.
+#[derive(Debug)]
+pub struct LookbackStream<Stream: Read> {
+    stream: Stream,
+    history: VecDeque<u8>,
+    capacity: usize,
+    head_pos: u64,
+    behind: usize,
+}
+
+impl<Stream: Read> LookbackStream<Stream> {
+    /// Create a stream that can rewind by at most `capacity` bytes.
+    pub fn new(stream: Stream, capacity: usize) -> Self {
+        Self { stream, history: VecDeque::with_capacity(capacity), capacity, head_pos: 0, behind: 0 }
+    }
+
+    /// Return the number of bytes the stream can currently rewind by.
+    pub fn lookback(&self) -> usize {
+        self.history.len() - self.behind
+    }
+
+    /// Take the underlying stream.
+    ///
+    /// Buffered bytes that were rewound but not read again are lost.
+    pub fn take(self) -> Stream {
+        self.stream
+    }
+
+    fn read_ahead(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        self.stream.read(bytes)?;
+        self.head_pos += bytes.len() as u64;
+        let retained = &bytes[bytes.len().saturating_sub(self.capacity)..];
+        let overflow = (self.history.len() + retained.len()).saturating_sub(self.capacity);
+        self.history.drain(..overflow);
+        self.history.extend(retained);
+        Ok(())
+    }
+}
+
+impl<Stream: Read> Read for LookbackStream<Stream> {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        let buffered = core::cmp::min(self.behind, bytes.len());
+        let (from_history, from_stream) = bytes.split_at_mut(buffered);
+        let history_pos = self.history.len() - self.behind;
+        from_history
+            .iter_mut()
+            .zip(self.history.range(history_pos..))
+            .for_each(|(byte, value)| *byte = *value);
+        if !from_stream.is_empty() {
+            self.read_ahead(from_stream).map_err(|err| match err.kind() {
+                ErrorKind::UnexpectedEof { bytes_available, .. } => ErrorKind::UnexpectedEof {
+                    bytes_needed: bytes.len() as u64,
+                    bytes_available: bytes_available + buffered as u64,
+                }
+                .into(),
+                _ => err,
+            })?;
+        }
+        self.behind -= buffered;
+        Ok(())
+    }
+}
+
+impl<Stream: Read> Seek for LookbackStream<Stream> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        let stream_pos = self.head_pos - self.behind as u64;
+        let target = match pos {
+            SeekFrom::End(_) => return Err(ErrorKind::OutOfBounds.into()),
+            pos => pos.absolute(stream_pos, 0),
+        };
+        let oldest = self.head_pos - self.history.len() as u64;
+        if target < oldest as i64 {
+            return Err(ErrorKind::OutOfBounds.into());
+        }
+        let target = target as u64;
+        if target <= self.head_pos {
+            self.behind = (self.head_pos - target) as usize;
+        } else {
+            self.behind = 0;
+            let mut skipped = [0u8; 64];
+            let mut remaining = target - self.head_pos;
+            while remaining != 0 {
+                let chunk = core::cmp::min(remaining, skipped.len() as u64) as usize;
+                self.read_ahead(&mut skipped[..chunk])?;
+                remaining -= chunk as u64;
+            }
+        }
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::GrowingMemoryStream;
+
+    /// A stream that can only be read forward.
+    struct Forward(GrowingMemoryStream);
+
+    impl Read for Forward {
+        fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+            self.0.read(bytes)
+        }
+    }
+
+    fn make_stream(capacity: usize) -> LookbackStream<Forward> {
+        LookbackStream::new(Forward(GrowingMemoryStream::from(vec![1, 2, 3, 4, 5, 6, 7])), capacity)
+    }
+
+    #[test]
+    fn read_forward() -> Result<(), Error> {
+        let mut stream = make_stream(4);
+        let mut values = [0u8; 3];
+        stream.read(&mut values)?;
+        assert_eq!(values, [1, 2, 3]);
+        stream.read(&mut values)?;
+        assert_eq!(values, [4, 5, 6]);
+        assert_eq!(stream.stream_position(), Ok(6));
+        assert_eq!(stream.lookback(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn skip() -> Result<(), Error> {
+        let mut stream = make_stream(4);
+        stream.skip(5)?;
+        assert_eq!(stream.stream_position(), Ok(5));
+        stream.seek_relative(-2)?;
+        let mut values = [0u8; 2];
+        stream.read(&mut values)?;
+        assert_eq!(values, [4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn rewind_within_window() -> Result<(), Error> {
+        let mut stream = make_stream(4);
+        let mut values = [0u8; 5];
+        stream.read(&mut values)?;
+        stream.seek_relative(-3)?;
+        assert_eq!(stream.stream_position(), Ok(2));
+        let mut values = [0u8; 4];
+        stream.read(&mut values)?;
+        assert_eq!(values, [3, 4, 5, 6]);
+        assert_eq!(stream.stream_position(), Ok(6));
+        Ok(())
+    }
+
+    #[test]
+    fn rewind_beyond_window() -> Result<(), Error> {
+        let mut stream = make_stream(4);
+        let mut values = [0u8; 6];
+        stream.read(&mut values)?;
+        assert_eq!(stream.seek(SeekFrom::Start(1)), Err(ErrorKind::OutOfBounds.into()));
+        assert_eq!(stream.seek(SeekFrom::Start(2)), Ok(2));
+        Ok(())
+    }
+
+    #[test]
+    fn seek_forward() -> Result<(), Error> {
+        let mut stream = make_stream(4);
+        stream.seek(SeekFrom::Start(5))?;
+        let mut values = [0u8; 2];
+        stream.read(&mut values)?;
+        assert_eq!(values, [6, 7]);
+        stream.seek(SeekFrom::Start(3))?;
+        stream.read(&mut values)?;
+        assert_eq!(values, [4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn seek_from_end() {
+        let mut stream = make_stream(4);
+        assert_eq!(stream.seek(SeekFrom::End(0)), Err(ErrorKind::OutOfBounds.into()));
+    }
+
+    #[test]
+    fn read_past_end() -> Result<(), Error> {
+        let mut stream = make_stream(4);
+        let mut values = [0u8; 6];
+        stream.read(&mut values)?;
+        stream.seek_relative(-2)?;
+        let mut values = [0u8; 4];
+        assert_eq!(
+            stream.read(&mut values),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 4, bytes_available: 3 }.into())
+        );
+        Ok(())
+    }
+}
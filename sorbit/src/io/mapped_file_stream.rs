@@ -0,0 +1,265 @@
+use super::FixedMemoryStream;
+use super::stream::{Bounded, Read, Seek, SeekFrom, Write};
+use crate::error::{Error, ErrorKind};
+use core::ffi::{c_int, c_long, c_void};
+use std::fs::File;
+use std::os::fd::AsRawFd;
+
+/// A stream over a file that is mapped into memory.
+///
+/// The contents of the file are loaded by the operating system as the stream
+/// reads them, so huge files can be deserialized without reading them into a
+/// [`GrowingMemoryStream`](super::GrowingMemoryStream) first. The stream has
+/// the length of the file, and like a [`FixedMemoryStream`], it cannot grow.
+///
+/// The file can be mapped in two modes:
+/// - [`open`](Self::open) maps the file read-only, writing to the stream
+///   fails.
+/// - [`open_copy_on_write`](Self::open_copy_on_write) maps the file
+///   privately. Writes change the mapped contents, but not the file. This
+///   allows patching the data in place, for example by seeking to a field
+///   and serializing its new value, and saving the result elsewhere via
+///   [`as_slice`](Self::as_slice).
+///
+/// The stream is only available on Unix platforms.
+///
+/// ```
+/// use std::io::Write as _;
+/// use sorbit::io::MappedFileStream;
+/// use sorbit::ser_de::Deserialize;
+/// use sorbit::stream_ser_de::StreamDeserializer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let path = std::env::temp_dir().join("sorbit_mapped_file_stream_doc");
+/// # std::fs::File::create(&path)?.write_all(&[0xEF, 0xBE, 0xAD, 0xDE])?;
+/// let file = std::fs::File::open(&path)?;
+/// // Safety: the file is not modified while it's mapped.
+/// let stream = unsafe { MappedFileStream::open(&file)? };
+/// let mut deserializer = StreamDeserializer::new(stream);
+/// assert_eq!(u32::deserialize(&mut deserializer)?, 0xDEADBEEF);
+/// # std::fs::remove_file(&path)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MappedFileStream {
+    stream: FixedMemoryStream<Mapping>,
+    writable: bool,
+}
+
+impl MappedFileStream {
+    /// Map the whole `file` read-only.
+    ///
+    /// The file can be closed once it's mapped.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified, truncated in particular, while it's
+    /// mapped, neither by this process nor by others. Modifications change
+    /// memory that the stream assumes to be immutable, truncation makes
+    /// accessing the memory fail with a signal.
+    pub unsafe fn open(file: &File) -> Result<Self, Error> {
+        Ok(Self { stream: FixedMemoryStream::new(Mapping::new(file, PROT_READ)?), writable: false })
+    }
+
+    /// Map the whole `file` privately, so that writes don't reach the file.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`open`](Self::open). Modifications to parts of the
+    /// file that were not yet written through the stream may or may not be
+    /// visible in the stream.
+    pub unsafe fn open_copy_on_write(file: &File) -> Result<Self, Error> {
+        Ok(Self { stream: FixedMemoryStream::new(Mapping::new(file, PROT_READ | PROT_WRITE)?), writable: true })
+    }
+
+    /// Return whether the stream can be written.
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Borrow the mapped contents of the file, including the changes written
+    /// to the stream.
+    pub fn as_slice(&self) -> &[u8] {
+        self.stream.get_ref().as_ref()
+    }
+}
+
+impl Read for MappedFileStream {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        self.stream.read(bytes)
+    }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        self.stream.skip(byte_count)
+    }
+}
+
+impl Write for MappedFileStream {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        match self.writable {
+            true => self.stream.write(bytes),
+            false => Err(ErrorKind::IO(std::io::ErrorKind::PermissionDenied).into()),
+        }
+    }
+}
+
+impl Seek for MappedFileStream {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        self.stream.seek(pos)
+    }
+
+    fn stream_position(&mut self) -> Result<u64, Error> {
+        self.stream.stream_position()
+    }
+
+    fn stream_len(&mut self) -> Result<u64, Error> {
+        self.stream.stream_len()
+    }
+}
+
+impl Bounded for MappedFileStream {
+    fn remaining_bytes(&self) -> u64 {
+        self.stream.remaining_bytes()
+    }
+}
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_PRIVATE: c_int = 2;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+
+unsafe extern "C" {
+    fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, offset: c_long) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+/// The memory of a mapped file, unmapped when dropped.
+#[derive(Debug)]
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// The mapping is private to the stream, and it's only accessed through
+// references to the stream.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn new(file: &File, prot: c_int) -> Result<Self, Error> {
+        let len = file.metadata().map_err(ErrorKind::from)?.len();
+        let Ok(len) = usize::try_from(len) else {
+            return Err(ErrorKind::OutOfBounds.into());
+        };
+        if len == 0 {
+            // Empty mappings are not allowed, but there is nothing to map.
+            return Ok(Self { ptr: core::ptr::NonNull::dangling().as_ptr(), len });
+        }
+        let ptr = unsafe { mmap(core::ptr::null_mut(), len, prot, MAP_PRIVATE, file.as_raw_fd(), 0) };
+        match ptr {
+            MAP_FAILED => Err(ErrorKind::from(std::io::Error::last_os_error()).into()),
+            ptr => Ok(Self { ptr: ptr.cast(), len }),
+        }
+    }
+}
+
+impl AsRef<[u8]> for Mapping {
+    fn as_ref(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl AsMut<[u8]> for Mapping {
+    fn as_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            unsafe { munmap(self.ptr.cast(), self.len) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+    use std::path::PathBuf;
+
+    /// A file in the temporary directory, removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &[u8]) -> Self {
+            let path = std::env::temp_dir().join(format!("sorbit_{name}_{}", std::process::id()));
+            File::create(&path).unwrap().write_all(contents).unwrap();
+            Self(path)
+        }
+
+        fn open(&self) -> File {
+            File::open(&self.0).unwrap()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn read() -> Result<(), Error> {
+        let file = TempFile::new("mapped_read", &[1, 2, 3, 4, 5]);
+        let mut stream = unsafe { MappedFileStream::open(&file.open())? };
+        let mut values = [0u8; 3];
+        stream.seek(SeekFrom::Start(2))?;
+        stream.read(&mut values)?;
+        assert_eq!(values, [3, 4, 5]);
+        assert_eq!(stream.stream_len(), Ok(5));
+        assert!(stream.is_finished());
+        Ok(())
+    }
+
+    #[test]
+    fn read_past_end() -> Result<(), Error> {
+        let file = TempFile::new("mapped_read_past_end", &[1, 2]);
+        let mut stream = unsafe { MappedFileStream::open(&file.open())? };
+        let eof = ErrorKind::UnexpectedEof { bytes_needed: 3, bytes_available: 2 };
+        assert_eq!(stream.read(&mut [0u8; 3]), Err(eof.into()));
+        Ok(())
+    }
+
+    #[test]
+    fn write_read_only() -> Result<(), Error> {
+        let file = TempFile::new("mapped_write_read_only", &[1, 2]);
+        let mut stream = unsafe { MappedFileStream::open(&file.open())? };
+        assert!(!stream.is_writable());
+        assert_eq!(stream.write(&[3]), Err(ErrorKind::IO(std::io::ErrorKind::PermissionDenied).into()));
+        Ok(())
+    }
+
+    #[test]
+    fn write_copy_on_write() -> Result<(), Error> {
+        let file = TempFile::new("mapped_write_copy_on_write", &[1, 2, 3]);
+        let mut stream = unsafe { MappedFileStream::open_copy_on_write(&file.open())? };
+        stream.seek(SeekFrom::Start(1))?;
+        stream.write(&[7])?;
+        assert_eq!(stream.as_slice(), [1, 7, 3]);
+        drop(stream);
+        assert_eq!(std::fs::read(&file.0).unwrap(), [1, 2, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_file() -> Result<(), Error> {
+        let file = TempFile::new("mapped_empty_file", &[]);
+        let mut stream = unsafe { MappedFileStream::open(&file.open())? };
+        assert_eq!(stream.as_slice(), []);
+        assert_eq!(stream.stream_len(), Ok(0));
+        Ok(())
+    }
+}
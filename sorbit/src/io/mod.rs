@@ -1,15 +1,47 @@
 //! I/O traits and I/O streams.
 
+#[cfg(feature = "async")]
+mod async_stream;
 mod bounded_section;
+mod buffered_stream;
+#[cfg(feature = "compression")]
+mod compression;
+mod fill;
 mod fixed_memory_stream;
 #[cfg(feature = "alloc")]
 mod growing_memory_stream;
+#[cfg(feature = "alloc")]
+mod lookback_stream;
+#[cfg(all(feature = "std", unix))]
+mod mapped_file_stream;
+mod null_stream;
+#[cfg(feature = "zeroize")]
+mod secure_buffer;
+#[cfg(feature = "std")]
+mod std_stream;
 mod stream;
 mod stream_section;
+mod transform_stream;
 
+#[cfg(feature = "async")]
+pub use async_stream::{AsyncRead, AsyncWrite, read_object, read_size_prefixed, write_object, write_size_prefixed};
 pub use bounded_section::BoundedSection;
-pub use fixed_memory_stream::FixedMemoryStream;
+pub use buffered_stream::BufferedStream;
+#[cfg(feature = "compression")]
+pub use compression::{Codec, CompressedStream, Deflate, DeflateStream, Lzss, LzssStream};
+pub use fill::{verify_fill, verify_fill_regions};
+pub use fixed_memory_stream::{FixedMemoryStream, SliceStream, SliceStreamMut};
 #[cfg(feature = "alloc")]
 pub use growing_memory_stream::GrowingMemoryStream;
-pub use stream::{Bounded, Read, Seek, SeekFrom, Write};
+#[cfg(feature = "alloc")]
+pub use lookback_stream::LookbackStream;
+#[cfg(all(feature = "std", unix))]
+pub use mapped_file_stream::MappedFileStream;
+pub use null_stream::NullStream;
+#[cfg(feature = "zeroize")]
+pub use secure_buffer::SecureBuffer;
+#[cfg(feature = "std")]
+pub use std_stream::StdStream;
+pub use stream::{BorrowRead, Bounded, Read, Seek, SeekFrom, Write, read_exact};
 pub use stream_section::StreamSection;
+pub use transform_stream::{TransformStream, xor_cipher};
@@ -0,0 +1,118 @@
+use super::stream::{Read, Seek, SeekFrom, Write};
+use crate::error::{Error, ErrorKind};
+
+/// A stream that discards the bytes written to it, and only keeps track of
+/// the length of the data.
+///
+/// This is useful to measure the size of serialized objects without storing
+/// them, see [`SizeSerializer`](crate::stream_ser_de::SizeSerializer).
+///
+/// The stream can be read and seeked like any other stream, so that
+/// serializers can revise the data written to it. As no bytes are stored,
+/// reading the stream returns zeros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NullStream {
+    stream_pos: u64,
+    stream_len: u64,
+}
+
+impl NullStream {
+    /// Create an empty stream.
+    pub const fn new() -> Self {
+        Self { stream_pos: 0, stream_len: 0 }
+    }
+
+    /// Return the number of bytes written to the stream.
+    pub const fn len(&self) -> u64 {
+        self.stream_len
+    }
+
+    /// Check if no bytes were written to the stream.
+    pub const fn is_empty(&self) -> bool {
+        self.stream_len == 0
+    }
+}
+
+impl Read for NullStream {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        let available = self.stream_len - self.stream_pos;
+        if bytes.len() as u64 <= available {
+            bytes.fill(0);
+            self.stream_pos += bytes.len() as u64;
+            Ok(())
+        } else {
+            Err(Error::unexpected_eof(bytes.len(), available))
+        }
+    }
+}
+
+impl Write for NullStream {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.stream_pos += bytes.len() as u64;
+        self.stream_len = core::cmp::max(self.stream_len, self.stream_pos);
+        Ok(())
+    }
+}
+
+impl Seek for NullStream {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        let new_stream_pos = pos.absolute(self.stream_pos, self.stream_len);
+        if (0..=self.stream_len as i64).contains(&new_stream_pos) {
+            self.stream_pos = new_stream_pos as u64;
+            Ok(self.stream_pos)
+        } else {
+            Err(ErrorKind::OutOfBounds.into())
+        }
+    }
+
+    fn stream_position(&mut self) -> Result<u64, Error> {
+        Ok(self.stream_pos)
+    }
+
+    fn stream_len(&mut self) -> Result<u64, Error> {
+        Ok(self.stream_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write() -> Result<(), Error> {
+        let mut stream = NullStream::new();
+        stream.write(&[1, 2, 3])?;
+        assert_eq!(stream.len(), 3);
+        assert_eq!(stream.stream_position(), Ok(3));
+        Ok(())
+    }
+
+    #[test]
+    fn overwrite() -> Result<(), Error> {
+        let mut stream = NullStream::new();
+        stream.write(&[1, 2, 3])?;
+        stream.seek(SeekFrom::Start(1))?;
+        stream.write(&[4])?;
+        assert_eq!(stream.len(), 3);
+        assert_eq!(stream.stream_position(), Ok(2));
+        Ok(())
+    }
+
+    #[test]
+    fn read() -> Result<(), Error> {
+        let mut stream = NullStream::new();
+        stream.write(&[1, 2, 3])?;
+        stream.rewind()?;
+        let mut bytes = [0xFF; 2];
+        stream.read(&mut bytes)?;
+        assert_eq!(bytes, [0, 0]);
+        assert_eq!(stream.read(&mut bytes), Err(Error::unexpected_eof(2, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn seek_out_of_bounds() {
+        let mut stream = NullStream::new();
+        assert_eq!(stream.seek(SeekFrom::Start(1)), Err(ErrorKind::OutOfBounds.into()));
+    }
+}
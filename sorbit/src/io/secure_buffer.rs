@@ -0,0 +1,191 @@
+use super::stream::{Read, Seek, SeekFrom, Write};
+use crate::error::{Error, ErrorKind};
+use alloc::vec::Vec;
+
+/// A growing in-memory stream that wipes its memory when it's no longer used.
+///
+/// The stream behaves like a [`GrowingMemoryStream`](super::GrowingMemoryStream),
+/// but it overwrites its buffer with zeros when dropped. When the buffer has to
+/// grow, the old allocation is wiped before it's released, so no copies of the
+/// serialized data are left behind in freed memory. Use it for serializing key
+/// material or other security-sensitive frames.
+///
+/// To keep this guarantee, the buffer cannot be taken out of the stream, only
+/// borrowed via [`as_slice`](Self::as_slice).
+pub struct SecureBuffer {
+    buffer: Vec<u8>,
+    stream_pos: usize,
+}
+
+impl SecureBuffer {
+    /// Create a stream with a zero-sized buffer.
+    pub fn new() -> Self {
+        Self { buffer: Vec::new(), stream_pos: 0 }
+    }
+
+    /// Create a stream with a zero-sized buffer that can grow to `capacity`
+    /// bytes without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { buffer: Vec::with_capacity(capacity), stream_pos: 0 }
+    }
+
+    /// Borrow the contents of the stream.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Overwrite the contents of the stream with zeros and make it empty.
+    pub fn clear(&mut self) {
+        wipe(&mut self.buffer);
+        self.buffer.clear();
+        self.stream_pos = 0;
+    }
+
+    fn resize(&mut self, new_len: usize) {
+        if new_len > self.buffer.capacity() {
+            let mut new_buffer = Vec::with_capacity(core::cmp::max(new_len, 2 * self.buffer.capacity()));
+            new_buffer.extend_from_slice(&self.buffer);
+            wipe(&mut self.buffer);
+            self.buffer = new_buffer;
+        }
+        self.buffer.resize(new_len, 0);
+    }
+}
+
+impl Default for SecureBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for SecureBuffer {
+    fn drop(&mut self) {
+        wipe(&mut self.buffer);
+    }
+}
+
+impl core::fmt::Debug for SecureBuffer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SecureBuffer")
+            .field("len", &self.buffer.len())
+            .field("stream_pos", &self.stream_pos)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Read for SecureBuffer {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        if self.stream_pos + bytes.len() <= self.buffer.len() {
+            let range = self.stream_pos..(self.stream_pos + bytes.len());
+            bytes.copy_from_slice(&self.buffer[range]);
+            self.stream_pos += bytes.len();
+            Ok(())
+        } else {
+            Err(Error::unexpected_eof(bytes.len(), self.buffer.len().saturating_sub(self.stream_pos) as u64))
+        }
+    }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        let bytes_available = self.buffer.len().saturating_sub(self.stream_pos) as u64;
+        match byte_count <= bytes_available {
+            true => {
+                self.stream_pos += byte_count as usize;
+                Ok(())
+            }
+            false => Err(ErrorKind::UnexpectedEof { bytes_needed: byte_count, bytes_available }.into()),
+        }
+    }
+}
+
+impl Write for SecureBuffer {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let new_len = core::cmp::max(self.buffer.len(), self.stream_pos + bytes.len());
+        self.resize(new_len);
+        let range = self.stream_pos..(self.stream_pos + bytes.len());
+        self.buffer[range].copy_from_slice(bytes);
+        self.stream_pos += bytes.len();
+        Ok(())
+    }
+}
+
+impl Seek for SecureBuffer {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        let new_stream_pos = pos.absolute(self.stream_pos as u64, self.buffer.len() as u64);
+        if let Ok(new_stream_pos) = usize::try_from(new_stream_pos) {
+            self.stream_pos = new_stream_pos;
+            Ok(self.stream_pos as u64)
+        } else {
+            Err(ErrorKind::OutOfBounds.into())
+        }
+    }
+}
+
+/// Overwrite the entire allocation of `buffer` with zeros.
+///
+/// Volatile writes are used so that the compiler cannot elide the writes even
+/// though the memory is not read afterwards.
+fn wipe(buffer: &mut Vec<u8>) {
+    let ptr = buffer.as_mut_ptr();
+    for offset in 0..buffer.capacity() {
+        // SAFETY: `ptr` is valid for writes of `capacity` bytes.
+        unsafe { ptr.add(offset).write_volatile(0) };
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read() -> Result<(), Error> {
+        let mut stream = SecureBuffer::new();
+        stream.write(&[1, 2, 3, 4, 5])?;
+        stream.rewind()?;
+        let mut values = [0u8; 5];
+        stream.read(&mut values)?;
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+        assert_eq!(stream.as_slice(), [1, 2, 3, 4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn write_past_end() -> Result<(), Error> {
+        let mut stream = SecureBuffer::with_capacity(2);
+        stream.seek(SeekFrom::Start(3))?;
+        stream.write(&[1, 2])?;
+        assert_eq!(stream.as_slice(), [0, 0, 0, 1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn read_outside_bounds() {
+        let mut stream = SecureBuffer::new();
+        let mut values = [0u8; 1];
+        assert_eq!(
+            stream.read(&mut values),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 1, bytes_available: 0 }.into())
+        );
+    }
+
+    #[test]
+    fn clear() -> Result<(), Error> {
+        let mut stream = SecureBuffer::new();
+        stream.write(&[1, 2, 3])?;
+        stream.clear();
+        assert_eq!(stream.as_slice(), []);
+        assert_eq!(stream.stream_position(), Ok(0));
+        Ok(())
+    }
+
+    #[test]
+    fn wipe_whole_capacity() {
+        let mut buffer = Vec::with_capacity(8);
+        buffer.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        buffer.truncate(3);
+        wipe(&mut buffer);
+        // SAFETY: all bytes of the allocation were initialized above.
+        unsafe { buffer.set_len(8) };
+        assert_eq!(buffer, [0; 8]);
+    }
+}
@@ -0,0 +1,181 @@
+use super::stream::{Read, Seek, SeekFrom, Write, read_exact};
+use crate::error::{Error, ErrorKind};
+
+/// An adapter that makes [`std::io`] streams usable as sorbit streams.
+///
+/// For example, you can wrap a [`std::fs::File`] to serialize directly into
+/// a file, or to deserialize directly from a file.
+///
+/// Serializers write every primitive separately, so unbuffered streams like
+/// files or sockets should be wrapped in a [`std::io::BufWriter`] or
+/// [`std::io::BufReader`] first. Both of them implement [`std::io::Seek`] as
+/// well, so they work with revisable serializers too:
+///
+/// ```
+/// use std::io::{BufWriter, Cursor, Write as _};
+/// use sorbit::io::StdStream;
+/// use sorbit::ser_de::{Serialize, Serializer};
+/// use sorbit::stream_ser_de::StreamSerializer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let file = Cursor::new(Vec::new());
+/// let stream = StdStream::new(BufWriter::new(file));
+/// let mut serializer = StreamSerializer::new(stream);
+/// 0xDEADBEEFu32.serialize(&mut serializer)?;
+/// let mut writer = serializer.take().into_inner();
+/// writer.flush()?;
+/// # assert_eq!(writer.get_ref().get_ref().len(), 4);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct StdStream<Stream> {
+    stream: Stream,
+}
+
+impl<Stream> StdStream<Stream> {
+    /// Create a stream by wrapping a [`std::io`] stream.
+    pub fn new(stream: Stream) -> Self {
+        Self { stream }
+    }
+
+    /// Return a reference to the original stream.
+    pub fn get_ref(&self) -> &Stream {
+        &self.stream
+    }
+
+    /// Return a mutable reference to the original stream.
+    ///
+    /// This can be used to flush a buffered stream, for example.
+    pub fn get_mut(&mut self) -> &mut Stream {
+        &mut self.stream
+    }
+
+    /// Return the original stream.
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+}
+
+impl<Stream: std::io::Read> Read for StdStream<Stream> {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        read_exact(bytes, |buffer| {
+            loop {
+                match self.stream.read(buffer) {
+                    Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                    result => break result.map_err(|err| ErrorKind::from(err).into()),
+                }
+            }
+        })
+    }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        let mut section = std::io::Read::take(&mut self.stream, byte_count);
+        match std::io::copy(&mut section, &mut std::io::sink()) {
+            Ok(bytes_available) if bytes_available < byte_count => {
+                Err(ErrorKind::UnexpectedEof { bytes_needed: byte_count, bytes_available }.into())
+            }
+            result => result.map(|_| ()).map_err(|err| ErrorKind::from(err).into()),
+        }
+    }
+}
+
+impl<Stream: std::io::Write> Write for StdStream<Stream> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.stream.write_all(bytes).map_err(|err| ErrorKind::from(err).into())
+    }
+}
+
+impl<Stream: std::io::Seek> Seek for StdStream<Stream> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        self.stream.seek(pos.into()).map_err(|err| ErrorKind::from(err).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn read() -> Result<(), Error> {
+        let mut stream = StdStream::new(Cursor::new(vec![1, 2, 3]));
+        let mut values = [0u8; 2];
+        stream.read(&mut values)?;
+        assert_eq!(values, [1, 2]);
+        assert_eq!(
+            stream.read(&mut values),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 2, bytes_available: 1 }.into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn skip() -> Result<(), Error> {
+        let mut stream = StdStream::new(Cursor::new(vec![1, 2, 3, 4]));
+        stream.skip(2)?;
+        let mut values = [0u8; 1];
+        stream.read(&mut values)?;
+        assert_eq!(values, [3]);
+        assert_eq!(stream.skip(2), Err(ErrorKind::UnexpectedEof { bytes_needed: 2, bytes_available: 1 }.into()));
+        Ok(())
+    }
+
+    #[test]
+    fn read_short() -> Result<(), Error> {
+        struct Trickle(Vec<std::io::Result<u8>>);
+
+        impl std::io::Read for Trickle {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.pop() {
+                    Some(Ok(byte)) => {
+                        buf[0] = byte;
+                        Ok(1)
+                    }
+                    Some(Err(err)) => Err(err),
+                    None => Ok(0),
+                }
+            }
+        }
+
+        let interrupted = || Err(std::io::ErrorKind::Interrupted.into());
+        let mut stream = StdStream::new(Trickle(vec![Ok(3), interrupted(), Ok(2), Ok(1)]));
+        let mut values = [0u8; 2];
+        stream.read(&mut values)?;
+        assert_eq!(values, [1, 2]);
+        assert_eq!(
+            stream.read(&mut values),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 2, bytes_available: 1 }.into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn write() -> Result<(), Error> {
+        let mut stream = StdStream::new(Cursor::new(Vec::new()));
+        stream.write(&[1, 2, 3])?;
+        assert_eq!(stream.into_inner().into_inner(), [1, 2, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn write_buffered() -> Result<(), Error> {
+        let mut stream = StdStream::new(std::io::BufWriter::new(Cursor::new(Vec::new())));
+        stream.write(&[1, 2, 3])?;
+        assert!(stream.get_ref().get_ref().get_ref().is_empty());
+        stream.seek(SeekFrom::Start(1))?;
+        stream.write(&[4])?;
+        std::io::Write::flush(stream.get_mut()).unwrap();
+        assert_eq!(stream.get_ref().get_ref().get_ref(), &[1, 4, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn seek() {
+        let mut stream = StdStream::new(Cursor::new(vec![1, 2, 3]));
+        assert_eq!(stream.seek(SeekFrom::End(-1)), Ok(2));
+        assert_eq!(stream.stream_len(), Ok(3));
+        assert_eq!(stream.stream_position(), Ok(2));
+    }
+}
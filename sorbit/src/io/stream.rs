@@ -1,12 +1,101 @@
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 
 /// This trait allows for writing bytes into a sink.
 ///
 /// This trait is used by some [`Deserializer`](crate::ser_de::Deserializer)s
 /// that can deserialize from a plain byte stream.
+///
+/// ## Short reads and end of file
+///
+/// Unlike [`std::io::Read::read`], this method must fill the entire buffer,
+/// it behaves like [`std::io::Read::read_exact`]. Streams whose source
+/// delivers data in chunks, like network sockets, have to keep reading until
+/// the buffer is full. [`read_exact`] helps implement that.
+///
+/// When the stream ends before the buffer is filled, the error must be
+/// [`ErrorKind::UnexpectedEof`], with the number of bytes requested and the
+/// number of bytes the stream could provide. After an error, the contents of
+/// the buffer and the position of the stream are unspecified, unless the
+/// stream documents otherwise. The in-memory streams of this crate leave both
+/// unchanged.
 pub trait Read {
     /// Read exactly as many bytes as fits in `bytes`.
     fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error>;
+
+    /// Skip exactly `byte_count` bytes without looking at them.
+    ///
+    /// The default implementation reads the bytes into a small buffer and
+    /// discards them. Streams that can move forward without reading, like
+    /// in-memory or seekable streams, should override it.
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        let mut buffer = [0u8; 64];
+        let mut remaining = byte_count;
+        while remaining > 0 {
+            let count = core::cmp::min(buffer.len() as u64, remaining) as usize;
+            self.read(&mut buffer[..count])?;
+            remaining -= count as u64;
+        }
+        Ok(())
+    }
+}
+
+/// Fill `bytes` by repeatedly calling `read_some`.
+///
+/// `read_some` should read at least one byte into the beginning of the buffer
+/// it's given and return the number of bytes read, or return zero at the end
+/// of the stream. This adapts sources that may return short reads to the
+/// contract of [`Read`]:
+///
+/// ```
+/// use sorbit::error::{Error, ErrorKind};
+/// use sorbit::io::{Read, read_exact};
+///
+/// /// A source that only returns one byte at a time.
+/// struct Trickle<'a>(&'a [u8]);
+///
+/// impl Read for Trickle<'_> {
+///     fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+///         read_exact(bytes, |buffer| match (self.0.split_first(), buffer.first_mut()) {
+///             (Some((first, rest)), Some(byte)) => {
+///                 *byte = *first;
+///                 self.0 = rest;
+///                 Ok(1)
+///             }
+///             _ => Ok(0),
+///         })
+///     }
+/// }
+///
+/// let mut bytes = [0u8; 2];
+/// assert_eq!(Trickle(&[1, 2, 3]).read(&mut bytes), Ok(()));
+/// assert_eq!(bytes, [1, 2]);
+///
+/// let eof = ErrorKind::UnexpectedEof { bytes_needed: 4, bytes_available: 3 };
+/// assert_eq!(Trickle(&[1, 2, 3]).read(&mut [0u8; 4]), Err(eof.into()));
+/// ```
+pub fn read_exact(bytes: &mut [u8], mut read_some: impl FnMut(&mut [u8]) -> Result<usize, Error>) -> Result<(), Error> {
+    let mut filled = 0;
+    while filled < bytes.len() {
+        match read_some(&mut bytes[filled..])? {
+            0 => {
+                let eof = ErrorKind::UnexpectedEof { bytes_needed: bytes.len() as u64, bytes_available: filled as u64 };
+                return Err(eof.into());
+            }
+            count => filled += count,
+        }
+    }
+    Ok(())
+}
+
+/// A stream over an in-memory buffer that can lend the bytes it reads.
+///
+/// Instead of copying the bytes into a buffer, [`read_borrowed`](Self::read_borrowed)
+/// returns a slice of the underlying buffer, which outlives the stream. This
+/// trait is used by [`StreamDeserializer`](crate::stream_ser_de::StreamDeserializer)
+/// to implement [`BorrowDeserializer`](crate::ser_de::BorrowDeserializer).
+pub trait BorrowRead<'de>: Read {
+    /// Read exactly `len` bytes, and return them without copying.
+    fn read_borrowed(&mut self, len: usize) -> Result<&'de [u8], Error>;
 }
 
 /// This trait allows for reading bytes from a source.
@@ -118,6 +207,10 @@ impl<T: Read + ?Sized> Read for &mut T {
     fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
         (**self).read(bytes)
     }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        (**self).skip(byte_count)
+    }
 }
 
 impl<T: Write + ?Sized> Write for &mut T {
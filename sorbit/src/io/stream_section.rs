@@ -48,7 +48,18 @@ impl<Stream: Read + Seek> Read for StreamSection<Stream> {
         if range_contains(&self.range, &read_range) {
             self.stream.read(bytes)
         } else {
-            Err(ErrorKind::UnexpectedEof.into())
+            Err(Error::unexpected_eof(bytes.len(), self.range.end.saturating_sub(stream_pos)))
+        }
+    }
+
+    fn skip(&mut self, byte_count: u64) -> Result<(), Error> {
+        let stream_pos = self.stream.stream_position()?;
+        let skip_range = stream_pos..(stream_pos + byte_count);
+        if range_contains(&self.range, &skip_range) {
+            self.stream.seek(SeekFrom::Start(skip_range.end)).map(|_| ())
+        } else {
+            let bytes_available = self.range.end.saturating_sub(stream_pos);
+            Err(ErrorKind::UnexpectedEof { bytes_needed: byte_count, bytes_available }.into())
         }
     }
 }
@@ -60,7 +71,7 @@ impl<Stream: Write + Seek> Write for StreamSection<Stream> {
         if range_contains(&self.range, &write_range) {
             self.stream.write(bytes)
         } else {
-            Err(ErrorKind::UnexpectedEof.into())
+            Err(Error::unexpected_eof(bytes.len(), self.range.end.saturating_sub(stream_pos)))
         }
     }
 }
@@ -74,7 +85,7 @@ impl<Stream: Seek> Seek for StreamSection<Stream> {
             self.stream.seek(SeekFrom::Start(new_underlying_stream_pos))?;
             Ok(new_stream_pos as u64)
         } else {
-            Err(ErrorKind::UnexpectedEof.into())
+            Err(ErrorKind::OutOfBounds.into())
         }
     }
 
@@ -111,6 +122,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn skip() -> Result<(), Error> {
+        let mut buffer = [1, 2, 3, 4, 5, 6, 7];
+        let mut stream = StreamSection::new(FixedMemoryStream::new(&mut buffer), 2..6).expect("new failed");
+        stream.skip(3)?;
+        assert_eq!(stream.stream_position(), Ok(3));
+        assert_eq!(stream.skip(2), Err(ErrorKind::UnexpectedEof { bytes_needed: 2, bytes_available: 1 }.into()));
+        assert_eq!(stream.stream_position(), Ok(3));
+        Ok(())
+    }
+
     #[test]
     fn read_well_within_bounds() -> Result<(), Error> {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
@@ -138,7 +160,10 @@ mod tests {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = StreamSection::new(FixedMemoryStream::new(&mut buffer), 2..6).expect("new failed");
         let mut values = [0u8; 5];
-        assert_eq!(stream.read(&mut values), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(
+            stream.read(&mut values),
+            Err(ErrorKind::UnexpectedEof { bytes_needed: 5, bytes_available: 4 }.into())
+        );
         assert_eq!(stream.stream_position(), Ok(0));
         Ok(())
     }
@@ -170,7 +195,7 @@ mod tests {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = StreamSection::new(FixedMemoryStream::new(&mut buffer), 2..6).expect("new failed");
         let values = [0u8; 5];
-        assert_eq!(stream.write(&values), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.write(&values), Err(ErrorKind::UnexpectedEof { bytes_needed: 5, bytes_available: 4 }.into()));
         assert_eq!(stream.stream_position(), Ok(0));
         assert_eq!(buffer, [1, 2, 3, 4, 5, 6, 7]);
         Ok(())
@@ -189,7 +214,7 @@ mod tests {
     fn seek_from_start_out_of_bounds() -> Result<(), Error> {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = StreamSection::new(FixedMemoryStream::new(&mut buffer), 2..6).expect("new failed");
-        assert_eq!(stream.seek(SeekFrom::Start(5)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::Start(5)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_position(), Ok(0));
         Ok(())
     }
@@ -208,7 +233,7 @@ mod tests {
     fn seek_from_current_out_of_bounds() -> Result<(), Error> {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = StreamSection::new(FixedMemoryStream::new(&mut buffer), 2..6).expect("new failed");
-        assert_eq!(stream.seek(SeekFrom::Current(5)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::Current(5)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_position(), Ok(0));
         Ok(())
     }
@@ -217,7 +242,7 @@ mod tests {
     fn seek_from_current_negative_out_of_bounds() -> Result<(), Error> {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = StreamSection::new(FixedMemoryStream::new(&mut buffer), 2..6).expect("new failed");
-        assert_eq!(stream.seek(SeekFrom::Current(-2)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::Current(-2)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_position(), Ok(0));
         Ok(())
     }
@@ -235,7 +260,7 @@ mod tests {
     fn seek_from_end_out_of_bounds() -> Result<(), Error> {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = StreamSection::new(FixedMemoryStream::new(&mut buffer), 2..6).expect("new failed");
-        assert_eq!(stream.seek(SeekFrom::End(2)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::End(2)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_position(), Ok(0));
         Ok(())
     }
@@ -244,7 +269,7 @@ mod tests {
     fn seek_from_end_negative_out_of_bounds() -> Result<(), Error> {
         let mut buffer = [1, 2, 3, 4, 5, 6, 7];
         let mut stream = StreamSection::new(FixedMemoryStream::new(&mut buffer), 2..6).expect("new failed");
-        assert_eq!(stream.seek(SeekFrom::End(-12)), Err(ErrorKind::UnexpectedEof.into()));
+        assert_eq!(stream.seek(SeekFrom::End(-12)), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(stream.stream_position(), Ok(0));
         Ok(())
     }
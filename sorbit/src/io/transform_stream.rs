@@ -0,0 +1,177 @@
+use super::stream::{Bounded, Read, Seek, SeekFrom, Write};
+use crate::error::{Error, ErrorKind};
+
+/// Transforms the bytes read from or written to a stream on the fly.
+///
+/// The `transform` is called with the position of the bytes in the stream and
+/// the bytes themselves, which it modifies in place. It's applied to the bytes
+/// after reading them, and before writing them. Because the transform knows
+/// the position, it can decode any part of the stream, thus seeking works as
+/// usual. The position is counted from the point where the stream was wrapped.
+/// To transform only a region of a stream, wrap a [`StreamSection`](super::StreamSection)
+/// or a [`BoundedSection`](super::BoundedSection) of it.
+///
+/// Reading and writing apply the same transform, so a stream that is both read
+/// and written needs a transform that is its own inverse. XOR obfuscation and
+/// stream ciphers like RC4 are such. [`xor_cipher`] implements XOR with a
+/// repeating key:
+///
+/// ```
+/// use sorbit::io::{FixedMemoryStream, TransformStream, xor_cipher};
+/// use sorbit::ser_de::Deserialize;
+/// use sorbit::stream_ser_de::StreamDeserializer;
+///
+/// let obfuscated = [0xEF ^ 0x5A, 0xBE ^ 0xA5, 0xAD ^ 0x5A, 0xDE ^ 0xA5];
+/// let stream = TransformStream::new(FixedMemoryStream::new(obfuscated), xor_cipher([0x5A, 0xA5]));
+/// let mut deserializer = StreamDeserializer::new(stream);
+/// assert_eq!(u32::deserialize(&mut deserializer), Ok(0xDEADBEEF));
+/// ```
+#[derive(Debug)]
+pub struct TransformStream<Stream, F: FnMut(u64, &mut [u8])> {
+    stream: Stream,
+    transform: F,
+    stream_pos: u64,
+}
+
+impl<Stream, F: FnMut(u64, &mut [u8])> TransformStream<Stream, F> {
+    /// Create a stream that applies `transform` to the bytes of `stream`.
+    pub fn new(stream: Stream, transform: F) -> Self {
+        Self { stream, transform, stream_pos: 0 }
+    }
+
+    /// Return the original stream.
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+}
+
+impl<Stream: Read, F: FnMut(u64, &mut [u8])> Read for TransformStream<Stream, F> {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        self.stream.read(bytes)?;
+        (self.transform)(self.stream_pos, bytes);
+        self.stream_pos += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+impl<Stream: Write, F: FnMut(u64, &mut [u8])> Write for TransformStream<Stream, F> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let mut chunk = [0u8; 64];
+        for part in bytes.chunks(chunk.len()) {
+            let chunk = &mut chunk[..part.len()];
+            chunk.copy_from_slice(part);
+            (self.transform)(self.stream_pos, chunk);
+            self.stream.write(chunk)?;
+            self.stream_pos += chunk.len() as u64;
+        }
+        Ok(())
+    }
+}
+
+impl<Stream: Seek, F: FnMut(u64, &mut [u8])> Seek for TransformStream<Stream, F> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        let inner_pos = self.stream.stream_position()?;
+        let origin = inner_pos - self.stream_pos;
+        let new_inner_pos = match pos {
+            SeekFrom::Start(offset) => self.stream.seek(SeekFrom::Start(origin + offset))?,
+            pos => self.stream.seek(pos)?,
+        };
+        if new_inner_pos < origin {
+            self.stream.seek(SeekFrom::Start(inner_pos))?;
+            return Err(ErrorKind::OutOfBounds.into());
+        }
+        self.stream_pos = new_inner_pos - origin;
+        Ok(self.stream_pos)
+    }
+}
+
+impl<Stream: Bounded, F: FnMut(u64, &mut [u8])> Bounded for TransformStream<Stream, F> {
+    fn remaining_bytes(&self) -> u64 {
+        self.stream.remaining_bytes()
+    }
+}
+
+/// Create a transform for a [`TransformStream`] that XORs the bytes with a
+/// repeating `key`.
+///
+/// The first byte of the stream is XORed with the first byte of the key.
+pub fn xor_cipher<Key: AsRef<[u8]>>(key: Key) -> impl FnMut(u64, &mut [u8]) {
+    move |stream_pos, bytes| {
+        let key = key.as_ref();
+        if !key.is_empty() {
+            let offset = (stream_pos % key.len() as u64) as usize;
+            let key_bytes = key.iter().cycle().skip(offset);
+            bytes.iter_mut().zip(key_bytes).for_each(|(byte, key_byte)| *byte ^= key_byte);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{FixedMemoryStream, GrowingMemoryStream};
+
+    #[test]
+    fn read() -> Result<(), Error> {
+        let mut stream = TransformStream::new(FixedMemoryStream::new([1, 2, 3, 4, 5]), xor_cipher([0xF0, 0x0F]));
+        let mut values = [0u8; 3];
+        stream.read(&mut values)?;
+        assert_eq!(values, [0xF1, 0x0D, 0xF3]);
+        stream.read(&mut values[..2])?;
+        assert_eq!(values[..2], [0x0B, 0xF5]);
+        Ok(())
+    }
+
+    #[test]
+    fn write() -> Result<(), Error> {
+        let mut stream = TransformStream::new(GrowingMemoryStream::new(), xor_cipher([0xF0, 0x0F]));
+        stream.write(&[1, 2, 3])?;
+        stream.write(&[0u8; 100])?;
+        let expected: Vec<u8> = [1, 2, 3]
+            .into_iter()
+            .chain([0u8; 100])
+            .zip([0xF0, 0x0F].iter().cycle())
+            .map(|(a, b)| a ^ b)
+            .collect();
+        assert_eq!(stream.into_inner().take(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn seek() -> Result<(), Error> {
+        let mut stream = TransformStream::new(FixedMemoryStream::new([1, 2, 3, 4, 5]), xor_cipher([0xF0, 0x0F]));
+        assert_eq!(stream.seek(SeekFrom::Start(3)), Ok(3));
+        let mut value = [0u8];
+        stream.read(&mut value)?;
+        assert_eq!(value, [0x0B]);
+        assert_eq!(stream.seek(SeekFrom::Current(-3)), Ok(1));
+        stream.read(&mut value)?;
+        assert_eq!(value, [0x0D]);
+        assert_eq!(stream.seek(SeekFrom::Start(6)), Err(ErrorKind::OutOfBounds.into()));
+        assert_eq!(stream.stream_position(), Ok(2));
+        Ok(())
+    }
+
+    #[test]
+    fn seek_from_wrapped_position() -> Result<(), Error> {
+        let mut inner = FixedMemoryStream::new([0, 0, 1, 2, 3]);
+        inner.seek(SeekFrom::Start(2))?;
+        let mut stream = TransformStream::new(inner, xor_cipher([0xF0, 0x0F]));
+        assert_eq!(stream.seek(SeekFrom::Start(1)), Ok(1));
+        let mut value = [0u8];
+        stream.read(&mut value)?;
+        assert_eq!(value, [0x0D]);
+        assert_eq!(stream.seek(SeekFrom::End(-3)), Ok(0));
+        assert_eq!(stream.seek(SeekFrom::Current(-1)), Err(ErrorKind::OutOfBounds.into()));
+        assert_eq!(stream.stream_position(), Ok(0));
+        Ok(())
+    }
+
+    #[test]
+    fn read_error() {
+        let mut stream = TransformStream::new(FixedMemoryStream::new([1, 2]), |_, _: &mut [u8]| panic!());
+        let eof = ErrorKind::UnexpectedEof { bytes_needed: 3, bytes_available: 2 };
+        assert_eq!(stream.read(&mut [0u8; 3]), Err(eof.into()));
+        assert_eq!(stream.stream_position(), Ok(0));
+    }
+}
@@ -0,0 +1,596 @@
+//! Static information about the serialized representation of types.
+//!
+//! The [`Layout`] of a type describes the bounds of its encoded size and how
+//! deeply composites are nested within it. Layouts are computed at compile
+//! time, so they can be used to verify that the worst-case serialized size of
+//! a frame fits a fixed-size buffer:
+//!
+//! ```
+//! use sorbit::layout::StaticLayout;
+//! use sorbit::{Serialize, StaticLayout};
+//!
+//! #[derive(Serialize, StaticLayout)]
+//! struct Frame {
+//!     header: u16,
+//!     #[sorbit(align = 4)]
+//!     payload: [u8; 12],
+//! }
+//!
+//! const _: () = assert!(Frame::LAYOUT.fits_in(16));
+//! assert_eq!(Frame::LAYOUT.size(), Some(16));
+//! ```
+//!
+//! Derived layouts of structures also list the [`FieldLayout`] of each field,
+//! which lets you access the fields of a serialized frame in a raw buffer
+//! without deserializing it:
+//!
+//! ```
+//! # use sorbit::layout::{StaticLayout, find_field};
+//! # use sorbit::{Serialize, StaticLayout};
+//! # #[derive(Serialize, StaticLayout)]
+//! # struct Frame {
+//! #     header: u16,
+//! #     #[sorbit(align = 4)]
+//! #     payload: [u8; 12],
+//! # }
+//! const PAYLOAD: usize = find_field(Frame::FIELDS, "payload").unwrap().offset().unwrap() as usize;
+//! let buffer = [0u8; 16];
+//! let payload = &buffer[PAYLOAD..PAYLOAD + 12];
+//! # assert_eq!(PAYLOAD, 4);
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::ops::Range;
+
+use crate::error::{Error, ErrorKind};
+
+/// Bounds on the serialized representation of a type.
+///
+/// The sizes are given in bytes, the nesting depth in the number of composites
+/// (see [`Serializer::serialize_composite`](crate::ser_de::Serializer::serialize_composite))
+/// that are nested inside each other. A primitive has a nesting depth of zero,
+/// a structure of primitives has a nesting depth of one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Layout {
+    min_size: u64,
+    max_size: Option<u64>,
+    max_depth: u32,
+}
+
+impl Layout {
+    /// The layout of an object that serializes to nothing.
+    pub const EMPTY: Self = Self::fixed(0);
+
+    /// Create a layout with the given bounds.
+    ///
+    /// A `max_size` of [`None`] means that the size is unbounded.
+    pub const fn new(min_size: u64, max_size: Option<u64>, max_depth: u32) -> Self {
+        Self { min_size, max_size, max_depth }
+    }
+
+    /// Create the layout of a primitive that always serializes to `size` bytes.
+    pub const fn fixed(size: u64) -> Self {
+        Self { min_size: size, max_size: Some(size), max_depth: 0 }
+    }
+
+    /// The smallest number of bytes the object may serialize to.
+    pub const fn min_size(&self) -> u64 {
+        self.min_size
+    }
+
+    /// The largest number of bytes the object may serialize to.
+    ///
+    /// [`None`] if the size is unbounded, e.g. for collections.
+    pub const fn max_size(&self) -> Option<u64> {
+        self.max_size
+    }
+
+    /// The exact number of bytes the object serializes to, if it's the same
+    /// for all values.
+    pub const fn size(&self) -> Option<u64> {
+        match self.max_size {
+            Some(max_size) if max_size == self.min_size => Some(max_size),
+            _ => None,
+        }
+    }
+
+    /// The maximum number of composites nested inside each other.
+    pub const fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+
+    /// Check if any value of the object serializes to at most `size` bytes.
+    pub const fn fits_in(&self, size: u64) -> bool {
+        match self.max_size {
+            Some(max_size) => max_size <= size,
+            None => false,
+        }
+    }
+
+    /// The layout of this object immediately followed by `next`.
+    pub const fn then(self, next: Self) -> Self {
+        Self {
+            min_size: self.min_size.saturating_add(next.min_size),
+            max_size: match (self.max_size, next.max_size) {
+                (Some(lhs), Some(rhs)) => lhs.checked_add(rhs),
+                _ => None,
+            },
+            max_depth: deeper(self.max_depth, next.max_depth),
+        }
+    }
+
+    /// The layout of an object that is either this or `other`.
+    pub const fn or(self, other: Self) -> Self {
+        Self {
+            min_size: min(self.min_size, other.min_size),
+            max_size: match (self.max_size, other.max_size) {
+                (Some(lhs), Some(rhs)) => Some(max(lhs, rhs)),
+                _ => None,
+            },
+            max_depth: deeper(self.max_depth, other.max_depth),
+        }
+    }
+
+    /// The layout of this object and `other` serialized over each other, both
+    /// starting at the same position.
+    ///
+    /// The size is the size of the larger one.
+    pub const fn overlap(self, other: Self) -> Self {
+        Self {
+            min_size: max(self.min_size, other.min_size),
+            max_size: match (self.max_size, other.max_size) {
+                (Some(lhs), Some(rhs)) => Some(max(lhs, rhs)),
+                _ => None,
+            },
+            max_depth: deeper(self.max_depth, other.max_depth),
+        }
+    }
+
+    /// The layout of `count` of these objects serialized one after the other.
+    pub const fn repeat(self, count: u64) -> Self {
+        Self {
+            min_size: self.min_size.saturating_mul(count),
+            max_size: match self.max_size {
+                Some(max_size) => max_size.checked_mul(count),
+                None => None,
+            },
+            max_depth: self.max_depth,
+        }
+    }
+
+    /// The layout of any number of these objects serialized one after the other.
+    pub const fn repeat_unbounded(self) -> Self {
+        Self { min_size: 0, max_size: None, max_depth: self.max_depth }
+    }
+
+    /// The layout of this object wrapped in a composite.
+    pub const fn nest(self) -> Self {
+        Self { max_depth: self.max_depth.saturating_add(1), ..self }
+    }
+
+    /// The layout of this object padded until `offset` bytes.
+    ///
+    /// See [`Serializer::pad`](crate::ser_de::Serializer::pad).
+    pub const fn pad(self, offset: u64) -> Self {
+        Self {
+            min_size: max(self.min_size, offset),
+            max_size: match self.max_size {
+                Some(max_size) => Some(max(max_size, offset)),
+                None => None,
+            },
+            max_depth: self.max_depth,
+        }
+    }
+
+    /// The layout of this object padded until a multiple of `multiple_of` bytes.
+    ///
+    /// See [`Serializer::align`](crate::ser_de::Serializer::align).
+    pub const fn align(self, multiple_of: u64) -> Self {
+        Self {
+            min_size: round_up(self.min_size, multiple_of),
+            max_size: match self.max_size {
+                Some(max_size) => Some(round_up(max_size, multiple_of)),
+                None => None,
+            },
+            max_depth: self.max_depth,
+        }
+    }
+
+    /// The layout of this object padded until a multiple of `multiple_of` bytes
+    /// from the beginning of the stream.
+    ///
+    /// The position in the stream is not known statically, so the padding may
+    /// be anything less than `multiple_of`. See [`Serializer::align_absolute`](crate::ser_de::Serializer::align_absolute).
+    pub const fn align_absolute(self, multiple_of: u64) -> Self {
+        Self {
+            min_size: self.min_size,
+            max_size: match self.max_size {
+                Some(max_size) => max_size.checked_add(multiple_of.saturating_sub(1)),
+                None => None,
+            },
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+/// The position and the layout of a field within a structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldLayout {
+    name: &'static str,
+    offset: Option<u64>,
+    layout: Layout,
+    fields: &'static [FieldLayout],
+}
+
+impl FieldLayout {
+    /// Create the layout of a field.
+    ///
+    /// An `offset` of [`None`] means that the field's position depends on the
+    /// value of the preceding fields.
+    pub const fn new(name: &'static str, offset: Option<u64>, layout: Layout) -> Self {
+        Self { name, offset, layout, fields: &[] }
+    }
+
+    /// Set the layouts of the fields nested inside this field.
+    pub const fn with_fields(self, fields: &'static [FieldLayout]) -> Self {
+        Self { fields, ..self }
+    }
+
+    /// The name of the field.
+    ///
+    /// Tuple fields are named by their index, and bit fields by the identifier
+    /// of their storage.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The offset of the field in bytes from the beginning of the structure.
+    pub const fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// The layout of the field itself.
+    pub const fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// The layouts of the fields nested inside this field, with offsets from
+    /// the beginning of this field.
+    ///
+    /// Derived layouts list the [`FIELDS`](StaticLayout::FIELDS) of the
+    /// field's type if the type is serialized as is, and nothing otherwise.
+    pub const fn fields(&self) -> &'static [FieldLayout] {
+        self.fields
+    }
+
+    /// The bytes the field occupies from the beginning of the structure,
+    /// if both its offset and its size are fixed.
+    pub const fn range(&self) -> Option<Range<u64>> {
+        match (self.offset, self.layout.size()) {
+            (Some(offset), Some(size)) => Some(offset..offset + size),
+            _ => None,
+        }
+    }
+}
+
+/// Types whose [`Layout`] is known at compile time.
+///
+/// This trait can be derived for structures and enumerations using the
+/// `StaticLayout` derive macro, which takes into account the same `#[sorbit(...)]`
+/// attributes as the `Serialize` derive macro.
+pub trait StaticLayout {
+    /// The layout of the serialized representation of the type.
+    const LAYOUT: Layout;
+
+    /// The layouts of the fields of the type, in serialization order.
+    ///
+    /// Empty for primitives and enumerations.
+    const FIELDS: &'static [FieldLayout] = &[];
+
+    /// Return a zero-filled placeholder for the serialized representation.
+    ///
+    /// The placeholder has the exact size of the serialized type, including
+    /// padding. This is useful to reserve space for records that are written
+    /// later, without having to construct a value. Using this function for
+    /// types that don't have a fixed size is a compile time error.
+    ///
+    /// All bytes are zero, including the padding, regardless of the fill byte
+    /// of the serializer. A placeholder is not a valid serialized value, so
+    /// overwrite it with the serialized value before deserializing it,
+    /// especially if the deserializer [verifies](crate::stream_ser_de::StreamDeserializer::verify_fill)
+    /// a non-zero fill byte.
+    #[cfg(feature = "alloc")]
+    fn zeroed_wire() -> Vec<u8> {
+        vec![0; const { fixed_size(Self::LAYOUT) } as usize]
+    }
+
+    /// Fill the beginning of `bytes` with a zero-filled placeholder for the
+    /// serialized representation.
+    ///
+    /// Returns the part of `bytes` that was filled, or an error if `bytes` is
+    /// too short. See [`zeroed_wire`](Self::zeroed_wire).
+    fn zeroed_wire_slice(bytes: &mut [u8]) -> Result<&mut [u8], Error> {
+        let size = const { fixed_size(Self::LAYOUT) };
+        match usize::try_from(size).ok().and_then(|size| bytes.get_mut(..size)) {
+            Some(placeholder) => {
+                placeholder.fill(0);
+                Ok(placeholder)
+            }
+            None => Err(ErrorKind::OutOfBounds.into()),
+        }
+    }
+}
+
+/// Types whose serialized size may be known at compile time.
+///
+/// Implemented for all types with a [`StaticLayout`], including the derived
+/// ones, so it's a shorthand for the fixed size of the [`Layout`] in bytes.
+/// The size is [`None`] if it depends on the value, like for collections.
+///
+/// ```
+/// use sorbit::layout::{SerializedSize, serialized_size};
+/// use sorbit::io::FixedMemoryStream;
+/// use sorbit::ser_de::Serialize as _;
+/// use sorbit::stream_ser_de::StreamSerializer;
+/// use sorbit::{Serialize, StaticLayout};
+///
+/// #[derive(Serialize, StaticLayout)]
+/// struct Header {
+///     kind: u8,
+///     #[sorbit(align = 4)]
+///     len: u32,
+/// }
+///
+/// assert_eq!(Header::SIZE, Some(8));
+///
+/// let mut buffer = [0xFF; serialized_size::<Header>()];
+/// let mut serializer = StreamSerializer::new(FixedMemoryStream::new(&mut buffer[..]));
+/// Header { kind: 1, len: 2 }.serialize(&mut serializer)?;
+/// assert_eq!(buffer, [1, 0, 0, 0, 2, 0, 0, 0]);
+/// # Ok::<(), sorbit::error::Error>(())
+/// ```
+pub trait SerializedSize {
+    /// The number of bytes the type is serialized into, if it's fixed.
+    const SIZE: Option<usize>;
+}
+
+impl<T: ?Sized + StaticLayout> SerializedSize for T {
+    const SIZE: Option<usize> = match T::LAYOUT.size() {
+        Some(size) if size <= usize::MAX as u64 => Some(size as usize),
+        _ => None,
+    };
+}
+
+/// Return the fixed serialized size of the type `T` in bytes.
+///
+/// Unlike [`SerializedSize::SIZE`], the size can be used directly as the
+/// length of an array. Using this function for types that don't have a fixed
+/// size is a compile time error when evaluated in a constant context.
+pub const fn serialized_size<T: ?Sized + SerializedSize>() -> usize {
+    match T::SIZE {
+        Some(size) => size,
+        None => panic!("the serialized size of the type is not fixed"),
+    }
+}
+
+/// Return the layout of the items of a collection, without the length.
+///
+/// This is the layout of the collection fields that use the `len_by` and
+/// `byte_count_by` transforms.
+pub const fn items<C>() -> Layout
+where
+    C: IntoIterator<Item: StaticLayout>,
+{
+    C::Item::LAYOUT.repeat_unbounded().nest()
+}
+
+/// Return the layout of the items of a collection, each in a slot of `stride`
+/// bytes, without the length.
+///
+/// This is the layout of the collection fields that use the `len_by` and
+/// `byte_count_by` transforms together with `stride`.
+pub const fn strided_items<C>(stride: u64) -> Layout
+where
+    C: IntoIterator<Item: StaticLayout>,
+{
+    C::Item::LAYOUT.pad(stride).nest().repeat_unbounded().nest()
+}
+
+/// Return the layout of an array of `N` items, each in a slot of `stride` bytes.
+pub const fn strided_array<T, const N: usize>(stride: u64) -> Layout
+where
+    T: StaticLayout,
+{
+    T::LAYOUT.pad(stride).nest().repeat(N as u64).nest()
+}
+
+/// Find the layout of the field called `name`.
+///
+/// This function can be used in constant expressions, see the
+/// [module documentation](self) for an example.
+pub const fn find_field<'fields>(fields: &'fields [FieldLayout], name: &str) -> Option<&'fields FieldLayout> {
+    let mut index = 0;
+    while index < fields.len() {
+        if str_eq(fields[index].name, name) {
+            return Some(&fields[index]);
+        }
+        index += 1;
+    }
+    None
+}
+
+pub(crate) const fn fixed_size(layout: Layout) -> u64 {
+    match layout.size() {
+        Some(size) => size,
+        None => panic!("the serialized size of the type is not fixed"),
+    }
+}
+
+const fn str_eq(lhs: &str, rhs: &str) -> bool {
+    let (lhs, rhs) = (lhs.as_bytes(), rhs.as_bytes());
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < lhs.len() {
+        if lhs[index] != rhs[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+const fn min(lhs: u64, rhs: u64) -> u64 {
+    if lhs < rhs { lhs } else { rhs }
+}
+
+const fn max(lhs: u64, rhs: u64) -> u64 {
+    if lhs < rhs { rhs } else { lhs }
+}
+
+const fn deeper(lhs: u32, rhs: u32) -> u32 {
+    if lhs < rhs { rhs } else { lhs }
+}
+
+const fn round_up(value: u64, multiple_of: u64) -> u64 {
+    match multiple_of {
+        0 => value,
+        _ => value.div_ceil(multiple_of).saturating_mul(multiple_of),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size() {
+        assert_eq!(Layout::fixed(4).size(), Some(4));
+        assert_eq!(Layout::new(2, Some(4), 0).size(), None);
+        assert_eq!(Layout::new(2, None, 0).size(), None);
+    }
+
+    #[test]
+    fn serialized_size() {
+        struct Variable;
+        impl StaticLayout for Variable {
+            const LAYOUT: Layout = Layout::new(2, Some(4), 0);
+        }
+        assert_eq!(<[u16; 3]>::SIZE, Some(6));
+        assert_eq!(Variable::SIZE, None);
+        assert_eq!(const { super::serialized_size::<(u8, u32)>() }, 5);
+    }
+
+    #[test]
+    fn fits_in() {
+        assert!(Layout::fixed(4).fits_in(4));
+        assert!(!Layout::fixed(4).fits_in(3));
+        assert!(!Layout::new(0, None, 0).fits_in(u64::MAX));
+    }
+
+    #[test]
+    fn then() {
+        assert_eq!(Layout::fixed(2).then(Layout::new(1, Some(3), 2)), Layout::new(3, Some(5), 2));
+        assert_eq!(Layout::fixed(2).then(Layout::new(1, None, 0)), Layout::new(3, None, 0));
+        assert_eq!(Layout::fixed(u64::MAX).then(Layout::fixed(1)), Layout::new(u64::MAX, None, 0));
+    }
+
+    #[test]
+    fn or() {
+        assert_eq!(Layout::fixed(2).or(Layout::new(1, Some(3), 2)), Layout::new(1, Some(3), 2));
+        assert_eq!(Layout::fixed(2).or(Layout::new(3, None, 0)), Layout::new(2, None, 0));
+    }
+
+    #[test]
+    fn overlap() {
+        assert_eq!(Layout::fixed(2).overlap(Layout::new(1, Some(3), 2)), Layout::new(2, Some(3), 2));
+        assert_eq!(Layout::fixed(2).overlap(Layout::new(3, None, 0)), Layout::new(3, None, 0));
+    }
+
+    #[test]
+    fn repeat() {
+        assert_eq!(Layout::new(1, Some(3), 1).repeat(4), Layout::new(4, Some(12), 1));
+        assert_eq!(Layout::new(1, Some(3), 1).repeat(0), Layout::new(0, Some(0), 1));
+        assert_eq!(Layout::fixed(3).repeat_unbounded(), Layout::new(0, None, 0));
+    }
+
+    #[test]
+    fn nest() {
+        assert_eq!(Layout::fixed(3).nest().nest(), Layout::new(3, Some(3), 2));
+    }
+
+    #[test]
+    fn pad() {
+        assert_eq!(Layout::new(2, Some(6), 0).pad(4), Layout::new(4, Some(6), 0));
+        assert_eq!(Layout::new(2, Some(6), 0).pad(8), Layout::new(8, Some(8), 0));
+        assert_eq!(Layout::new(2, None, 0).pad(8), Layout::new(8, None, 0));
+    }
+
+    #[test]
+    fn align() {
+        assert_eq!(Layout::new(2, Some(6), 0).align(4), Layout::new(4, Some(8), 0));
+        assert_eq!(Layout::new(4, Some(8), 0).align(4), Layout::new(4, Some(8), 0));
+        assert_eq!(Layout::new(2, None, 0).align(4), Layout::new(4, None, 0));
+        assert_eq!(Layout::new(2, Some(6), 0).align(0), Layout::new(2, Some(6), 0));
+    }
+
+    #[test]
+    fn align_absolute() {
+        assert_eq!(Layout::new(2, Some(6), 0).align_absolute(4), Layout::new(2, Some(9), 0));
+        assert_eq!(Layout::new(2, None, 0).align_absolute(4), Layout::new(2, None, 0));
+        assert_eq!(Layout::new(2, Some(6), 0).align_absolute(0), Layout::new(2, Some(6), 0));
+    }
+
+    #[test]
+    fn strided() {
+        assert_eq!(strided_items::<[u16; 1]>(4), Layout::new(0, None, 2));
+        assert_eq!(strided_array::<u16, 3>(4), Layout::new(12, Some(12), 2));
+        assert_eq!(strided_array::<u32, 3>(2), Layout::new(12, Some(12), 2));
+    }
+
+    #[test]
+    fn zeroed_wire() {
+        assert_eq!(<(u8, [u16; 2])>::zeroed_wire(), vec![0; 5]);
+    }
+
+    #[test]
+    fn zeroed_wire_slice() {
+        let mut bytes = [0xFF; 6];
+        assert_eq!(<(u8, [u16; 2])>::zeroed_wire_slice(&mut bytes), Ok([0u8; 5].as_mut_slice()));
+        assert_eq!(bytes, [0, 0, 0, 0, 0, 0xFF]);
+    }
+
+    #[test]
+    fn zeroed_wire_slice_too_short() {
+        let mut bytes = [0xFF; 4];
+        assert_eq!(<(u8, [u16; 2])>::zeroed_wire_slice(&mut bytes), Err(ErrorKind::OutOfBounds.into()));
+        assert_eq!(bytes, [0xFF; 4]);
+    }
+
+    #[test]
+    fn items() {
+        assert_eq!(super::items::<[u16; 3]>(), Layout::new(0, None, 1));
+    }
+
+    #[test]
+    fn field_range() {
+        assert_eq!(FieldLayout::new("a", Some(2), Layout::fixed(4)).range(), Some(2..6));
+        assert_eq!(FieldLayout::new("a", None, Layout::fixed(4)).range(), None);
+        assert_eq!(FieldLayout::new("a", Some(2), Layout::new(1, Some(4), 0)).range(), None);
+    }
+
+    #[test]
+    fn find_field() {
+        const FIELDS: &[FieldLayout] = &[
+            FieldLayout::new("a", Some(0), Layout::fixed(1)),
+            FieldLayout::new("ab", Some(1), Layout::fixed(2)),
+        ];
+        assert_eq!(super::find_field(FIELDS, "ab"), Some(&FIELDS[1]));
+        assert_eq!(super::find_field(FIELDS, "b"), None);
+    }
+}
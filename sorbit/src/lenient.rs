@@ -0,0 +1,229 @@
+//! Types that keep malformed values in their raw form.
+//!
+//! By default, deserialization is strict, and a [`bool`] that is neither 0 nor
+//! 1, or an unknown tag of an enumeration, results in an error. Tools that
+//! inspect malformed data, like forensic or diagnostic tools, need to see such
+//! values instead. Fields of the types in this module keep the raw
+//! representation of invalid values when the deserializer is
+//! [lenient](crate::stream_ser_de::StreamDeserializer::lenient), and
+//! serialize them back unchanged:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize, SorbitTag};
+//! use sorbit::lenient::{Lenient, RawBool};
+//! use sorbit::io::FixedMemoryStream;
+//! use sorbit::ser_de::Deserialize as _;
+//! use sorbit::stream_ser_de::StreamDeserializer;
+//!
+//! #[derive(Debug, PartialEq, SorbitTag)]
+//! #[repr(u8)]
+//! enum Opcode {
+//!     Read = 1,
+//!     Write = 2,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Command {
+//!     opcode: Lenient<Opcode, u8>,
+//!     enabled: RawBool,
+//! }
+//!
+//! let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([7u8, 2])).lenient();
+//! let command = Command::deserialize(&mut deserializer).unwrap();
+//! assert_eq!(command.opcode, Lenient::Invalid(7));
+//! assert_eq!(command.enabled, RawBool(2));
+//!
+//! let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([7u8, 2]));
+//! assert!(Command::deserialize(&mut deserializer).is_err());
+//! ```
+
+use crate::layout::{Layout, StaticLayout};
+use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A value of type `T`, or its raw representation `Repr` if that doesn't
+/// correspond to any `T`.
+///
+/// The raw representation is converted into `T` with [`TryFrom`], which is
+/// implemented by the enumerations that derive `SorbitTag`. Both the valid and
+/// the invalid values are deserialized as a `Repr`, so `T` must serialize as
+/// its `Repr` in the byte order of the serializer, without overriding it.
+///
+/// When the deserializer is not lenient, invalid values result in the
+/// [`invalid_enum_variant`](Deserializer::invalid_enum_variant) error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lenient<T, Repr> {
+    /// The raw representation corresponds to the value.
+    Valid(T),
+    /// The raw representation doesn't correspond to any value.
+    Invalid(Repr),
+}
+
+impl<T, Repr> Lenient<T, Repr> {
+    /// Return the value if it's valid.
+    pub fn valid(self) -> Option<T> {
+        match self {
+            Self::Valid(value) => Some(value),
+            Self::Invalid(_) => None,
+        }
+    }
+}
+
+impl<T, Repr> From<T> for Lenient<T, Repr> {
+    fn from(value: T) -> Self {
+        Self::Valid(value)
+    }
+}
+
+impl<T: Serialize, Repr: Serialize> Serialize for Lenient<T, Repr> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        match self {
+            Self::Valid(value) => value.serialize(serializer),
+            Self::Invalid(repr) => repr.serialize(serializer),
+        }
+    }
+}
+
+impl<T, Repr> Deserialize for Lenient<T, Repr>
+where
+    T: TryFrom<Repr>,
+    Repr: Deserialize + Clone,
+{
+    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+        let repr = Repr::deserialize(deserializer)?;
+        match T::try_from(repr.clone()) {
+            Ok(value) => Ok(Self::Valid(value)),
+            Err(_) if deserializer.is_lenient() => Ok(Self::Invalid(repr)),
+            Err(_) => deserializer.invalid_enum_variant(),
+        }
+    }
+}
+
+impl<T, Repr: StaticLayout> StaticLayout for Lenient<T, Repr> {
+    const LAYOUT: Layout = Repr::LAYOUT;
+}
+
+/// A [`bool`] that keeps its raw byte.
+///
+/// Any non-zero byte is `true`, but only 0 and 1 are valid. When the
+/// deserializer is not lenient, other bytes result in the
+/// [`invalid_enum_variant`](Deserializer::invalid_enum_variant) error, as
+/// for [`bool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawBool(pub u8);
+
+impl RawBool {
+    /// Return the value of the boolean.
+    pub fn get(self) -> bool {
+        self.0 != 0
+    }
+
+    /// Check if the raw byte is either 0 or 1.
+    pub fn is_valid(self) -> bool {
+        self.0 <= 1
+    }
+}
+
+impl From<bool> for RawBool {
+    fn from(value: bool) -> Self {
+        Self(value.into())
+    }
+}
+
+impl Serialize for RawBool {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer.serialize_u8(self.0)
+    }
+}
+
+impl Deserialize for RawBool {
+    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+        let raw = Self(deserializer.deserialize_u8()?);
+        match raw.is_valid() || deserializer.is_lenient() {
+            true => Ok(raw),
+            false => deserializer.invalid_enum_variant(),
+        }
+    }
+}
+
+impl StaticLayout for RawBool {
+    const LAYOUT: Layout = Layout::fixed(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind};
+    use crate::io::{FixedMemoryStream, GrowingMemoryStream};
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    #[derive(Debug, PartialEq)]
+    enum Opcode {
+        Read,
+    }
+
+    impl Serialize for Opcode {
+        fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+            serializer.serialize_u8(1)
+        }
+    }
+
+    impl TryFrom<u8> for Opcode {
+        type Error = ();
+
+        fn try_from(value: u8) -> Result<Self, Self::Error> {
+            match value {
+                1 => Ok(Opcode::Read),
+                _ => Err(()),
+            }
+        }
+    }
+
+    #[test]
+    fn lenient_valid() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([1u8]));
+        assert_eq!(Lenient::<Opcode, u8>::deserialize(&mut deserializer), Ok(Lenient::Valid(Opcode::Read)));
+    }
+
+    #[test]
+    fn lenient_invalid() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([7u8])).lenient();
+        assert_eq!(Lenient::<Opcode, u8>::deserialize(&mut deserializer), Ok(Lenient::Invalid(7)));
+    }
+
+    #[test]
+    fn lenient_invalid_strict() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([7u8]));
+        assert_eq!(
+            Lenient::<Opcode, u8>::deserialize(&mut deserializer),
+            Err(Error::from(ErrorKind::InvalidEnumVariant))
+        );
+    }
+
+    #[test]
+    fn lenient_round_trip() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        Lenient::<Opcode, u8>::Valid(Opcode::Read).serialize(&mut serializer).unwrap();
+        Lenient::<Opcode, u8>::Invalid(7).serialize(&mut serializer).unwrap();
+        assert_eq!(serializer.take().take(), [1, 7]);
+    }
+
+    #[test]
+    fn raw_bool() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([0u8, 1, 45])).lenient();
+        assert_eq!(RawBool::deserialize(&mut deserializer), Ok(RawBool(0)));
+        assert_eq!(RawBool::deserialize(&mut deserializer), Ok(RawBool(1)));
+        let raw = RawBool::deserialize(&mut deserializer).unwrap();
+        assert_eq!((raw.get(), raw.is_valid()), (true, false));
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        raw.serialize(&mut serializer).unwrap();
+        assert_eq!(serializer.take().take(), [45]);
+    }
+
+    #[test]
+    fn raw_bool_strict() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([1u8, 45]));
+        assert_eq!(RawBool::deserialize(&mut deserializer), Ok(RawBool(1)));
+        assert_eq!(RawBool::deserialize(&mut deserializer), Err(Error::from(ErrorKind::InvalidEnumVariant)));
+    }
+}
@@ -107,6 +107,25 @@
 //! both `alloc` and no `alloc` environments. You can implement your own streams
 //! as necessary.
 //!
+//! For security-sensitive data, enable the `zeroize` feature to get the
+//! `SecureBuffer` stream, which wipes its memory when dropped. The
+//! [`verify_fill`](io::verify_fill) helper checks that reserved regions of the
+//! serialized bytes contain only the expected fill pattern.
+//!
+//! To exchange objects over asynchronous streams, like network sockets, enable
+//! the `async` feature. It provides the `AsyncRead` and `AsyncWrite` traits and
+//! helpers that read or write whole objects without blocking, and
+//! (de)serialize them in memory.
+//!
+//! For compressed sections of archives and similar formats, enable the
+//! `compression` feature. It provides the `DeflateStream` and `LzssStream`
+//! adapters, which decompress a stream so that its contents can be
+//! deserialized, and compress the serialized bytes written into them.
+//!
+//! To serialize large batches of records faster, enable the `parallel`
+//! feature. It provides [`StreamSerializer::serialize_batch`](stream_ser_de::StreamSerializer),
+//! which serializes the records on scoped threads, and writes them in order.
+//!
 //! ## Multi-pass serialization
 //!
 //! Regular `Serializer`s write the output bytes monotonously, without ever
@@ -125,6 +144,10 @@
 //!
 //! The directives can be merged (e.g. `#[sorbit(offset=4, round=4)]`) or
 //! written separately (e.g. `#[sorbit(offset=4)] #[sorbit(round=4)]`).
+//! Specifying the same directive twice with different values is an error,
+//! unless the later one is wrapped in `override(...)`, in which case it
+//! replaces the earlier value (e.g. `#[sorbit(offset=4)] #[sorbit(override(offset=8))]`).
+//! This is useful when the attributes are assembled by other macros.
 //!
 //! ### Structures
 //!
@@ -167,6 +190,12 @@
 //! }
 //!
 //! #[derive(Serialize, Deserialize)]
+//! struct CollectionLenPrefixed {
+//!     #[sorbit(len_prefix=u8)]
+//!     items: Vec<u8>,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
 //! struct CollectionByByteCount {
 //!     #[sorbit(value=byte_count(items))]
 //!     byte_count: u8,
@@ -185,20 +214,56 @@
 //!
 //! | Directive     | Values                        | Description |
 //! |---------------|-------------------------------|-------------|
-//! | `byte_order`  | `big_endian`, `little_endian` | The default byte ordering for all fields and bit fields. If not present, the byte order is inherited from the enclosing structure. |
+//! | `byte_order`  | `big_endian`, `little_endian`, `middle_endian` | The default byte ordering for all fields and bit fields. If not present, the byte order is inherited from the enclosing structure. |
 //! | `len`         | Any positive integer          | The structure's total length in bytes. If the serialized structure is smaller, it is padded to this length, if larger, this is ignored. |
 //! | `round`       | Any positive integer          | The structure's total length is padded to be a multiple of this value. Will pad beyond the requested `len` to satisfy rounding. |
+//! | `size_prefix` | An integer type               | The structure is preceded by its size in bytes, stored as this type. The size is written after the structure is serialized, and deserialization is bounded to the size, skipping any remaining bytes. Makes the structure only [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize). See [`size_prefix`](mod@crate::size_prefix). |
+//! | `includes_prefix` | None, true, false         | The `size_prefix` also counts the bytes of the size prefix itself. Defaults to false. |
+//! | `dyn_byte_order` | None, true, false          | The structure has no fixed byte order, and gets the `read_le`, `read_be`, `write_le`, and `write_be` methods to (de)serialize it from/to a stream in the given byte order. Cannot be combined with `byte_order`. |
+//! | `max_depth`   | Any positive integer          | Deserialization fails with [`ErrorKind::RecursionLimit`](crate::error::ErrorKind::RecursionLimit) if the structure is nested within this many composites or more. Structures and some field encodings, like `option`, each form a composite. Useful to tighten the [`max_depth`](crate::ser_de::Limits::max_depth) of the deserializer for recursive structures. |
+//! | `assert_size` | Any positive integer          | Compilation fails if the structure's layout does not have this fixed size in bytes. Catches changes to the layout that break the size given by a specification. The structure must derive [`StaticLayout`](crate::layout::StaticLayout), and it cannot be generic. |
+//! | `fill`        | Any integer in `0..=255`      | Padding of the structure, like that of `offset`, `align`, `len`, and `round`, is filled with this byte instead of zeros. Nested structures inherit the fill byte unless they specify their own. Deserialization skips padding unless the deserializer is set to [verify it](crate::stream_ser_de::StreamDeserializer::verify_fill). |
+//! | `bit_numbering` | `LSB0`, `MSB0`              | The default bit numbering for all bit fields of the structure. Bit fields may override it individually. If not present, bit fields default to `LSB0`. |
+//! | `transparent`  | None, true, false            | The structure must have a single field (besides skipped ones), and it's (de)serialized exactly as that field, without forming a composite. The layout is also that of the field. Cannot be combined with other directives, and the field cannot have directives either. |
+//! | `multi_pass`  | None, true, false             | The byte counts of `value=byte_count(...)` fields are measured in a first pass that only counts bytes, then all fields are written front to back in a second pass. This way, byte counts don't have to be revised after the fields they count, so the structure remains [`Serialize`](crate::ser_de::Serialize) unless other directives make it [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize), and it can be written to streams that cannot seek. The fields that are counted are serialized twice. |
+//! | `validate`    | A path to a function          | The function is called with a reference to the deserialized structure, and it must return `Result<(), &'static str>`. An error fails deserialization with [`ErrorKind::Custom`](crate::error::ErrorKind::Custom), carrying the returned message. Useful to check magic numbers and invariants that span multiple fields. The path may also be given as a string literal, like `"Self::check"`. |
+//! | `magic`       | A byte string, like `b"RIFF"` | The fields of the structure are preceded by these bytes. When deserializing, the bytes are verified, and [`ErrorKind::BadMagic`](crate::error::ErrorKind::BadMagic) is returned if they differ. See [`magic`](mod@crate::magic). |
+//! | `bound`       | Where predicates in a string, like `"T: Copy"` | The bounds of the generic parameters on the derived impls. Replaces the bounds that are inferred from the types of the fields, which is useful when the inference is too strict or insufficient, like for fields with a `with` directive. Applies to all derived traits, so it must also satisfy `StaticLayout` when that's derived. |
 //!
 //! #### Fields
 //!
 //! | Directive     | Values                        | Description |
 //! |---------------|-------------------------------|-------------|
-//! | `byte_order`  | `big_endian`, `little_endian` | The byte ordering of this specific field. When present, overrides the ordering inherited from the structure. |
-//! | `offset`      | Any positive integer          | The offset from the beginning of the structure where this field begins. An error is raised during serialization if the offset is already occupied. |
+//! | `byte_order`  | `big_endian`, `little_endian`, `middle_endian` | The byte ordering of this specific field. When present, overrides the ordering inherited from the structure. |
+//! | `offset`      | Any positive integer          | The offset from the beginning of the structure where this field begins. An error naming the field is raised during (de)serialization if the offset is already occupied. |
+//! | `allow_backward` | `seek`, `overlay`, `reorder` | With `seek`, rewind the stream during deserialization if the `offset` precedes the current position, for example, to deserialize overlapping fields. With `overlay`, the field views the bytes at `offset` like a member of a C union: it's deserialized from there, then the stream moves back to where it was, and it's not serialized at all, as its bytes come from the fields it overlays. With `reorder`, the field is (de)serialized at `offset` even if it precedes the current position, then the stream moves on from the end of the furthest field, so fields can be laid out in any order. Holes are filled with the fill byte. Requires `offset`, and makes the structure only [`MultiPassDeserialize`](crate::ser_de::MultiPassDeserialize). With `reorder`, the structure is also only [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize). |
 //! | `align`       | Any positive integer          | The offset from the beginning of the structure will be a multiple of `align`. Zero padding is applied before the field, as necessary. |
+//! | `align_absolute` | Any positive integer       | The position of the field from the beginning of the stream will be a multiple of `align_absolute`, regardless of where the structure begins. Useful for DMA-friendly formats. Padding is applied before the field, as necessary, after `align`. |
 //! | `round`       | Any positive integer          | The field's length is zero-padded to be a multiple of this value. |
+//! | `stride`      | Any positive integer          | Each item of an array, or of a collection whose length or byte count is stored in another field, is serialized into a slot of this many bytes. Items are padded with the fill byte until the end of their slot, and serializing an item larger than the slot fails. |
+//! | `size_prefix`, `includes_prefix` | See the structure | The field is preceded by its size in bytes, the same way as for the structure. |
+//! | `len_prefix`  | An integer type               | The collection is preceded by the number of its items, stored as this type, without a separate length field. Requires the same traits as `value=len(c)` and `value=len_by(l)`. Cannot be combined with `value`. |
+//! | `count`       | Expression; `ctx("<KEY>")`    | The collection has as many items as the expression gives, and its length is not serialized. The expression may refer to preceding fields and their members as `self.field`, like `count = "self.header.num_entries"`. With `ctx`, the length is the context value of that name, which is usually set by a field of an enclosing structure via `ctx`. Serialization fails if the collection's length differs. Cannot be combined with `value`. |
 //! | `value`       | Expression (see below)        | Ignore the field's value, and use the value provided by the expression. |
 //! | `multi_pass`  | None, true, false             | A marker attribute to tell sorbit that the field only implements [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize), but not [`Serialize`](crate::ser_de::Serialize). Apply it only when necessary. This marker *is* indeed superfluous, but proc macros cannot look into the type system, and generic programming is not quite there yet. |
+//! | `borrow`      | None, true, false             | The `&'a [u8]` or `&'a str` field points into the deserialized buffer instead of being copied, see the [`borrow`] module. The length must come from another field or `len_prefix`. Nested structures with borrowed fields are marked the same way. The structure then implements [`DeserializeBorrowed`](crate::ser_de::DeserializeBorrowed) instead of [`Deserialize`]. |
+//! | `boxed`       | None, true, false             | The field of type `Box<T>` is deserialized directly into heap storage via [`DeserializeBoxed`](crate::ser_de::DeserializeBoxed), without a temporary on the stack. Useful for large arrays on small stacks. Cannot be combined with `value`, and requires the `alloc` feature. |
+//! | `unit`, `repr` | `ns`, `us`, `ms`, `s`, `min`, `h`; an integer type | The [`Duration`](core::time::Duration) field is stored as a whole number of `unit`s in the integer type `repr`. The fraction of the last unit is truncated, and an error is raised if the duration does not fit into `repr`. See [`time`]. |
+//! | `string`, `pad` | `null_terminated`, `fixed(<LEN>)`, `len_prefix(<TYPE>)`; a byte | The [`String`] field is stored as UTF-8 followed by a null byte, in exactly `<LEN>` bytes padded with `pad` (zero by default), or preceded by its length in bytes. Trailing padding is removed when deserializing. Cannot be combined with `value`, and requires the `alloc` feature. See [`string`]. |
+//! | `option`      | `flag(<TYPE>)`, `sentinel(<EXPR>)`, `remaining` | The [`Option`] field is preceded by a presence flag of the given integer type, is absent when equal to the sentinel, or is present only if there are bytes remaining within bounds (e.g. within a `size_prefix`). Cannot be combined with `value` or `if`. See [`option`](mod@crate::option). |
+//! | `varint`      | None, true, false             | The integer field is stored as a variable-length LEB128 integer, and signed integers are zig-zag encoded first. Supports the integer types up to 64 bits, and the byte order does not apply. Cannot be combined with `value`. See [`varint`]. |
+//! | `min`, `max`  | Expression                    | Deserialization fails with [`ErrorKind::InvalidValue`](crate::error::ErrorKind::InvalidValue) if the field is less than `min` or greater than `max`. Either bound may be omitted. Serialization is not affected. Cannot be combined with `value`. |
+//! | `scale`, `bias`, `raw` | Expressions; an integer type | The floating-point field is stored as the integer `(value - bias) / scale`, rounded to the nearest, in the type `raw`. `bias` defaults to zero, and `raw` to `u64`. An error is raised if the value does not fit into `raw`. Cannot be combined with `value`. See [`scale`]. |
+//! | `checksum`    | `crc16(<FIELDS>)`, `crc32(<FIELDS>)` | The field is set to the CRC of the listed fields, in the order they are listed in, once all fields are serialized. Deserialization fails if the CRC does not match the bytes the listed fields were deserialized from. The field's type must be `u16` or `u32`, respectively. Cannot be combined with `value`, makes the structure only [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize), and deserialization requires the `alloc` feature. See [`checksum`]. |
+//! | `with`, `serialize_with`, `deserialize_with` | A module; a function path | The field is serialized by `serialize_with` and deserialized by `deserialize_with` instead of via its traits, or by the `serialize` and `deserialize` functions of the `with` module. The functions have the signatures `fn<S: Serializer>(&T, &mut S) -> Result<S::Success, S::Error>` and `fn<D: Deserializer>(&mut D) -> Result<T, D::Error>`. The layout of the field is unknown. Cannot be combined with `value`. |
+//! | `magic`       | A byte string, like `b"RIFF"` | The field is a byte array of the same length, and these bytes are serialized instead of its value. When deserializing, the bytes are verified, and [`ErrorKind::BadMagic`](crate::error::ErrorKind::BadMagic) is returned if they differ. Cannot be combined with `value`. See [`magic`](mod@crate::magic). |
+//! | `pointer`, `base` | An integer type; `absolute`, `composite`, `relative` | The field is stored elsewhere, and only its offset is stored in its place as the integer type `pointer`. The offset is interpreted from the beginning of the stream, of the structure, or of the offset itself, as given by `base`, which defaults to `absolute`. When serializing, the field is written right after the structure, and when deserializing, it's read from the offset, after which the stream moves past it if it follows the structure. Cannot be combined with `value`, and makes the structure only [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize) and [`MultiPassDeserialize`](crate::ser_de::MultiPassDeserialize). See [`pointer`](mod@crate::pointer). |
+//! | `offset_of`, `base` | A field; `absolute`, `composite`, `relative` | The field is set to the offset of the listed field once all fields are serialized, which makes offset tables that precede their payloads possible. The offset points to the first byte of the listed field, after the padding that aligns it, and it's interpreted as for `pointer`. When deserializing, the offset is read as is. Cannot be combined with `value` or `pointer`, and makes the structure only [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize). See [`serialize_offset`](crate::pointer::serialize_offset). |
+//! | `deferred`    | None, true, false             | The field is serialized as a placeholder first, then revised via [`DeferredSerialize`](crate::ser_de::DeferredSerialize) once the sections of all fields are known. Useful for checksums and offsets. Cannot be combined with `value`, and makes the structure only [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize). |
+//! | `if`          | Expression                    | The field is only serialized when the expression holds, and it's deserialized as [`Default::default()`] otherwise. The expression may refer to preceding fields as `self.field`, like `if = "self.flags & 0x80 != 0"`. A field of type `Option<T>` stores `T` when present, and deserializes as [`None`] when absent. Offset and alignment apply regardless of the condition. Cannot be combined with `value` or `deferred`. |
+//! | `ctx`         | A string literal, like `"version"` | Once deserialized or serialized, the field's value is stored as the context value of this name via [`Deserializer::set_context`](crate::ser_de::Deserializer::set_context) or [`Serializer::set_context`](crate::ser_de::Serializer::set_context). The value lasts until the end of the containing structure, so nested types can depend on it. The field's type must be convertible into `u64`. Cannot be combined with `if` or `if_version`. See [versioned fields](#versioned-fields). |
+//! | `if_version`  | A comparison, like `">= 3"`   | The field is conditional like with `if`, and is present when the context value `"version"` is set and the comparison holds for it. Cannot be combined with `if`. See [versioned fields](#versioned-fields). |
+//! | `skip`, `default` | None; expression         | The field is neither serialized nor deserialized, and it's initialized with `default` when deserializing, or with [`Default::default()`] if there is no `default`. The field's type doesn't have to implement the (de)serialization traits, and the field is not part of the layout. Cannot be combined with any other directive. In tuple structs, skipped fields must come after all other fields. |
 //!
 //! Value expressions:
 //!
@@ -206,10 +271,107 @@
 //! |--------------------------|-------------|
 //! | `value=len(c)`           | The serialized value will be the length of `self.c`, calculated as `(&self.c).into_iterator().len()`. This implies that `&self.c` supports [`IntoIterator`] and the iterator is an [`ExactSizeIterator`]. During deserialization, `self.c` will contain the number of items as per this field. Deserialization requires `self.c` to implement [`FromIterator`]. |
 //! | `value=len_by(l)`        | The length of this collection is serialized as `self.l`. This is the sibling attribute of `value=len(c)`, and it's enough if you specify only one of them. |
-//! | `value=byte_count(c)`    | The serialized value will be the number of bytes the serialized items of `self.c` occupy altogether. For deserialization, `self.c` has to implement [`FromIterator`]. Using this attribute will make the structure only [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize). |
+//! | `value=byte_count(c)`    | The serialized value will be the number of bytes the serialized items of `self.c` occupy altogether. For deserialization, `self.c` has to implement [`FromIterator`]. Using this attribute will make the structure only [`MultiPassSerialize`](crate::ser_de::MultiPassSerialize), unless the structure is `multi_pass`. |
 //! | `value=byte_count_by(b)` | The number of bytes the serialized items of this field occupy together is serialized as `self.b`. This is the sibling attribute of `value=byte_count(c)`, and it's enough if you specify only one of them. |
 //! | `value=constant(expr)`   | The item's value will always be `expr` when serializing, and deserialization will fail if the value is not `expr`. The `expr` must be convertible to the field's type via [`From`]. |
 //!
+//! #### Byte order inheritance
+//!
+//! The byte order is resolved from the innermost `byte_order` directive that
+//! applies to a value, and falls back to the byte order of the serializer if
+//! there is none:
+//!
+//! 1. The `byte_order` directive of the serialized type itself (structure or enum),
+//! 2. the `byte_order` directive of the field that contains the value,
+//! 3. the `byte_order` directive of the structure that contains the field,
+//! 4. and so on, towards the outermost structure.
+//!
+//! In other words, a field's directive only sets the default for a nested
+//! type, and a nested type that specifies its own byte order always uses it.
+//! The byte order is restored after each field, even if serialization fails.
+//!
+//! ```
+//! use sorbit::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Inherits {
+//!     value: u16, // Uses the byte order of the field in `Outer`.
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[sorbit(byte_order=little_endian)]
+//! struct Overrides {
+//!     value: u16, // Always little endian.
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[sorbit(byte_order=little_endian)]
+//! struct Outer {
+//!     #[sorbit(byte_order=big_endian)]
+//!     inherits: Inherits, // Big endian.
+//!     #[sorbit(byte_order=big_endian)]
+//!     overrides: Overrides, // Little endian.
+//! }
+//! ```
+//!
+//! Formats that exist in both byte orders, like TIFF, can use the
+//! `dyn_byte_order` directive to get concise methods for both:
+//!
+//! ```
+//! use sorbit::{Serialize, Deserialize};
+//! use sorbit::io::FixedMemoryStream;
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! #[sorbit(dyn_byte_order)]
+//! struct Header {
+//!     magic: u16,
+//!     offset: u32,
+//! }
+//!
+//! let header = Header::read_le(FixedMemoryStream::new([42, 0, 8, 0, 0, 0])).unwrap();
+//! assert_eq!(header, Header { magic: 42, offset: 8 });
+//!
+//! let mut bytes = [0u8; 6];
+//! header.write_be(FixedMemoryStream::new(&mut bytes)).unwrap();
+//! assert_eq!(bytes, [0, 42, 0, 0, 0, 8]);
+//! ```
+//!
+//! #### Versioned fields
+//!
+//! Formats often change their layout from one version to the next, and store
+//! the version in a header that precedes the data. A field marked with `ctx`
+//! makes its value available to the fields after it, including the fields of
+//! nested structures, which can then depend on it via `if_version`. Fields
+//! whose width changes between versions are split into one optional field for
+//! each width:
+//!
+//! ```
+//! use sorbit::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     id: u8,
+//!     #[sorbit(if_version = ">= 2")]
+//!     flags: Option<u8>,
+//!     #[sorbit(if_version = "< 3")]
+//!     short_length: Option<u16>,
+//!     #[sorbit(if_version = ">= 3")]
+//!     long_length: Option<u32>,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct File {
+//!     #[sorbit(ctx = "version")]
+//!     version: u8,
+//!     record: Record,
+//! }
+//! ```
+//!
+//! When a versioned type is (de)serialized on its own, the version can be set
+//! via [`Deserializer::with_context`](crate::ser_de::Deserializer::with_context)
+//! or [`Serializer::with_context`](crate::ser_de::Serializer::with_context).
+//! If no version is set, versioned fields are absent.
+//!
 //! #### Bit fields
 //!
 //! Bit fields in sorbit are defined using two concepts:
@@ -260,13 +422,16 @@
 //!
 //! | Directive       | Values                        | Description |
 //! |-----------------|-------------------------------|-------------|
-//! | `byte_order`    | `big_endian`, `little_endian` | The byte ordering of the bit field storage. Same as for regular fields. |
+//! | `byte_order`    | `big_endian`, `little_endian`, `middle_endian` | The byte ordering of the bit field storage. Same as for regular fields. |
 //! | `offset`        | Any positive integer          | The offset of the bit field storage. Same as for regular fields. |
 //! | `align`         | Any positive integer          | The alignment of the bit field storage. Same as for regular fields. |
+//! | `align_absolute` | Any positive integer         | The absolute alignment of the bit field storage. Same as for regular fields. |
 //! | `round`         | Any positive integer          | The rounding of the bit field storage. Same as for regular fields. |
-//! | `bit_numbering` | `LSB0` (default), `MSB0`      | The bit numbering of all members of the storage. With `LSB0`, bit `0` refers to the least significant bit, and `MSB0` is the opposite. Note that this does not affect the serialized format, it merely affects the number you write for the `bits` meta attribute of bit field members. |
-//! | `repr`          | Any type                      | The type of the bit field storage. |
-//! | `bits`          | Bounded range (`bits=a..b`, `bits=a..=b`), number (`bits=a`) | The bits occupied by the member within the storage. The values must be integer literals. |
+//! | `bit_numbering` | `LSB0` (default), `MSB0`      | The bit numbering of all members of the storage. Overrides the structure's `bit_numbering`. With `LSB0`, bit `0` refers to the least significant bit, and `MSB0` is the opposite. Note that this does not affect the serialized format, it merely affects the number you write for the `bits` meta attribute of bit field members. |
+//! | `repr`          | Any type                      | The type of the bit field storage. Use a byte array like `[u8; 12]` for bit fields wider than the primitive integers. The array is handled as a big-endian integer, so bit `0` (`LSB0`) is the least significant bit of the last byte. See [`ByteArrayBitField`](bit::ByteArrayBitField). |
+//! | `exhaustive`    | None, true, false             | The members must cover all bits of the storage, otherwise the uncovered bits are reported at compile time. Only for storages of primitive integers and byte arrays, whose width is known. |
+//! | `bits`          | Bounded range (`bits=a..b`, `bits=a..=b`), number (`bits=a`) | The bits occupied by the member within the storage. The values must be integer literals. The bits of the members cannot overlap, and, for storages of primitive integers and byte arrays, they must fit into the storage. |
+//! | `scale`, `bias`, `raw` | Expressions; an integer type | The floating-point member is packed as a scaled integer of type `raw`. Same as for regular fields, but `raw` must also fit into the `bits` of the member. |
 //!
 //! While both the bit field members and the bit field storage may be any types,
 //! they are linked by the [`PackInto`](bit::PackInto) and [`UnpackFrom`](bit::UnpackFrom) traits.
@@ -316,12 +481,15 @@
 //!
 //! | Directive       | Values                         | Description |
 //! |-----------------|--------------------------------|-------------|
-//! | `byte_order`    | `big_endian`, `little_endian`  | The byte ordering of the enum's discriminant, as well as the values in the enum's fields. The latter can be overridden by attributes on the variant itself. |
+//! | `byte_order`    | `big_endian`, `little_endian`, `middle_endian` | The byte ordering of the enum's discriminant, as well as the values in the enum's fields. The latter can be overridden by attributes on the variant itself. |
 //! | `repr`          | A primitive type               | The type used to represent and serialize the discriminant. See the [language documentation](https://doc.rust-lang.org/nomicon/other-reprs.html). |
+//! | `bound`         | Where predicates in a string   | Replaces the inferred bounds of the generic parameters, like for structures. |
 //! | `catch_all`     | - (`true` or `false` accepted) | Mark the variant as a catch all for unrecognized discriminant during deserialization. |
+//! | `tag`           | An expression                  | The discriminant of the variant when serialized. An alternative to Rust's explicit discriminant, which lets variants with fields have tags without a `#[repr]` on the enum. Cannot be combined with an explicit discriminant. |
 //!
 //! The enum's repr is chosen as `isize` unless specified otherwise. This
-//! follows the Rust language's specification.
+//! follows the Rust language's specification. Like all `isize` values, the
+//! discriminant is then serialized as 64 bits on all platforms.
 //!
 //! The enum's variants may have unnamed (tuple-like) and named (struct-like)
 //! fields. In such cases, the variant is treated and parsed as a `struct`. All
@@ -356,6 +524,39 @@
 //!   object you deserialize, you can ignore the bytes of the unhandled variants,
 //!   but this is not recommended.
 //!
+//! #### Untagged enumerations
+//!
+//! Some formats have no discriminant at all, and the alternatives can only be
+//! told apart by attempting to parse them. With `#[sorbit(untagged)]`, the
+//! variants are serialized without a discriminant, and deserialized by trying
+//! each variant in declaration order until one succeeds:
+//!
+//! ```
+//! use sorbit::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[sorbit(magic=b"V2")]
+//! struct HeaderV2 {
+//!     len: u32,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[sorbit(untagged)]
+//! enum Header {
+//!     V2(HeaderV2),
+//!     V1 { len: u16 },
+//! }
+//! ```
+//!
+//! A variant that fails to deserialize is rolled back with
+//! [`transaction`](ser_de::SeekableDeserializer::transaction), so the enum
+//! implements [`MultiPassDeserialize`](ser_de::MultiPassDeserialize) and needs a
+//! seekable deserializer. Put the more specific variants first, as unit variants
+//! and variants without validation never fail. If none of the variants match,
+//! deserialization fails with [`ErrorKind::NoMatchingVariant`](crate::error::ErrorKind::NoMatchingVariant),
+//! which holds the errors of all the variants. Untagged variants cannot have a
+//! discriminant, a `tag`, or a `catch_all` attribute.
+//!
 //! #### Bit packing
 //!
 //! Remember the [`PackInto`](bit::PackInto) and [`UnpackFrom`](bit::UnpackFrom) traits
@@ -379,10 +580,68 @@
 //! Once derived, you can use the enumeration in bit fields. Keep in mind that
 //! the packing is forwarded to the enum's repr type. The `catch_all` attribute
 //! is handled the same way as for serialization, other attributes are ignored.
+//! Bit patterns that do not correspond to any variant fail to deserialize with
+//! [`ErrorKind::InvalidEnumVariant`](crate::error::ErrorKind::InvalidEnumVariant).
 //!
 //! The derivation of bit packing is only applicable to unit enums. You can
 //! still derive the traits by hand if it makes sense for you.
 //!
+//! #### Tags
+//!
+//! Protocols often use unit enums as opcodes or tags. For such enums, you can
+//! derive `SorbitTag`, which derives [`Serialize`](ser_de::Serialize) and
+//! [`Deserialize`](ser_de::Deserialize), and also implements [`TryFrom`] for
+//! the enum's repr type:
+//!
+//! ```
+//! use sorbit::SorbitTag;
+//! use sorbit::error::InvalidDiscriminant;
+//!
+//! #[derive(Debug, PartialEq, SorbitTag)]
+//! #[sorbit(byte_order=big_endian)]
+//! #[repr(u16)]
+//! enum Opcode {
+//!     Read = 1,
+//!     Write = 2,
+//! }
+//!
+//! assert_eq!(Opcode::try_from(2u16), Ok(Opcode::Write));
+//! assert_eq!(Opcode::try_from(3u16), Err(InvalidDiscriminant(3)));
+//! ```
+//!
+//! The conversion fails with [`InvalidDiscriminant`](error::InvalidDiscriminant),
+//! which carries the invalid value, unless the enum has a `catch_all` variant.
+//! Like bit packing, tags can only be derived for unit enums. To keep unknown
+//! tags only when inspecting malformed data, use a [`Lenient`](lenient::Lenient)
+//! field with a lenient deserializer instead.
+//!
+//! ### Static layout
+//!
+//! The [`StaticLayout`](layout::StaticLayout) trait exposes the bounds of the
+//! serialized size and the nesting depth of a type as a constant, so you can
+//! check at compile time that the largest encoding of a type fits your buffers.
+//! It can be derived for structures and enumerations, taking into account the
+//! same attributes as serialization:
+//!
+//! ```
+//! use sorbit::layout::StaticLayout;
+//! use sorbit::{Serialize, StaticLayout};
+//!
+//! #[derive(Serialize, StaticLayout)]
+//! #[sorbit(round=8)]
+//! struct Example {
+//!     a: u16,
+//!     #[sorbit(offset=4)]
+//!     b: u8,
+//! }
+//!
+//! const _: () = assert!(Example::LAYOUT.fits_in(8));
+//! ```
+//!
+//! For structures, [`StaticLayout::FIELDS`](layout::StaticLayout::FIELDS) also
+//! lists the offset and the layout of each field, so you can access fields in
+//! raw buffers without deserializing the whole structure.
+//!
 //! ## `no_std`
 //!
 //! Sorbit is designed to fully support `no_std` and no `alloc` environments.
@@ -392,6 +651,11 @@
 //! non-allocating memory streams are also included. Both streams can be used
 //! together with the stream serializers.
 //!
+//! The [`Error`](crate::error::Error) type does not allocate either: without
+//! the `alloc` feature, its trace of members is made of static strings, and
+//! the error is [`Copy`]. With the `alloc` feature, the trace can hold any
+//! number of members.
+//!
 //! ## Sorbit vs. ...
 //!
 //! *To the authors of the libraries mentioned below, please reach out if you
@@ -452,13 +716,30 @@
 extern crate alloc;
 
 pub mod bit;
+pub mod borrow;
 pub mod byte_order;
+pub mod checksum;
 pub mod error;
 pub mod io;
+pub mod layout;
+pub mod lenient;
+pub mod magic;
 pub mod ser_de;
-pub use sorbit_derive::{Deserialize, PackInto, Serialize, UnpackFrom};
+pub use sorbit_derive::{Deserialize, PackInto, Serialize, SorbitTag, StaticLayout, UnpackFrom};
 pub mod collection;
+#[cfg(feature = "alloc")]
+pub mod dynamic;
+pub mod option;
+pub mod pointer;
+pub mod scale;
+#[cfg(feature = "alloc")]
+pub mod schema;
+pub mod size_prefix;
 pub mod stream_ser_de;
+#[cfg(feature = "alloc")]
+pub mod string;
+pub mod time;
+pub mod varint;
 
 mod types;
 
@@ -0,0 +1,89 @@
+//! Utilities for serializing the magic bytes that identify a format.
+//!
+//! Most file formats and many protocols start with a fixed sequence of bytes.
+//! With the derive macros, a structure can be preceded by such bytes, or a
+//! byte array field can be made to hold them:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::error::ErrorKind;
+//! use sorbit::ser_de::{FromBytes, ToBytes};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! #[sorbit(byte_order=little_endian, magic=b"RIFF")]
+//! struct Riff {
+//!     size: u32,
+//!     #[sorbit(magic=b"WAVE")]
+//!     format: [u8; 4],
+//! }
+//!
+//! let riff = Riff { size: 4, format: *b"WAVE" };
+//! assert_eq!(riff.to_bytes().unwrap(), b"RIFF\x04\0\0\0WAVE");
+//! assert_eq!(Riff::from_bytes(b"RIFF\x04\0\0\0WAVE").unwrap(), riff);
+//!
+//! let error = Riff::from_bytes(b"RIFX\x04\0\0\0WAVE").unwrap_err();
+//! assert!(matches!(error.kind(), ErrorKind::BadMagic { expected: b"RIFF", .. }));
+//! ```
+
+use crate::ser_de::{Deserializer, Serializer};
+
+/// Serialize the `magic` bytes.
+pub fn serialize_magic<const N: usize, S: Serializer>(
+    serializer: &mut S,
+    magic: &'static [u8; N],
+) -> Result<S::Success, S::Error> {
+    serializer.serialize_array(magic)
+}
+
+/// Deserialize as many bytes as there are in `magic`, and verify that they
+/// are the `magic` bytes.
+///
+/// If they aren't, the [`bad_magic`](Deserializer::bad_magic) error is
+/// returned.
+pub fn deserialize_magic<const N: usize, D: Deserializer>(
+    deserializer: &mut D,
+    magic: &'static [u8; N],
+) -> Result<[u8; N], D::Error> {
+    let found = deserializer.deserialize_array::<N>()?;
+    match &found == magic {
+        true => Ok(found),
+        false => deserializer.bad_magic(magic, &found),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind, MagicBytes};
+    use crate::io::{FixedMemoryStream, GrowingMemoryStream};
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    #[test]
+    fn serialize() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        serialize_magic(&mut serializer, b"\x89PNG").unwrap();
+        assert_eq!(serializer.take().take(), b"\x89PNG");
+    }
+
+    #[test]
+    fn deserialize() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new(b"\x89PNG"));
+        assert_eq!(deserialize_magic(&mut deserializer, b"\x89PNG"), Ok(*b"\x89PNG"));
+    }
+
+    #[test]
+    fn deserialize_bad() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new(b"GIF8"));
+        assert_eq!(
+            deserialize_magic(&mut deserializer, b"\x89PNG"),
+            Err(Error::from(ErrorKind::BadMagic { expected: b"\x89PNG", found: MagicBytes::new(b"GIF8") }))
+        );
+    }
+
+    #[test]
+    fn magic_bytes_capacity() {
+        assert_eq!(MagicBytes::new(b"RIFF").as_bytes(), b"RIFF");
+        assert_eq!(MagicBytes::new(b"SQLite format 3\0").as_bytes(), b"SQLite f");
+    }
+}
@@ -0,0 +1,319 @@
+//! Utilities for serializing optional values with a specific encoding.
+//!
+//! Binary formats mark the absence of a value in different ways, for example,
+//! with a flag that precedes the value, with a reserved sentinel value, or by
+//! simply omitting the value at the end of a record. With the derive macros,
+//! [`Option`] fields can be annotated with the encoding they are stored as:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::ser_de::{FromBytes, ToBytes};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! #[sorbit(byte_order=big_endian)]
+//! struct Settings {
+//!     #[sorbit(option=flag(u8))]
+//!     timeout: Option<u16>,
+//!     #[sorbit(option=sentinel(0xFF))]
+//!     retries: Option<u8>,
+//! }
+//!
+//! let settings = Settings { timeout: Some(300), retries: None };
+//! let bytes = [0x01, 0x01, 0x2C, 0xFF];
+//! assert_eq!(settings.to_bytes().unwrap(), bytes);
+//! assert_eq!(Settings::from_bytes(&bytes).unwrap(), settings);
+//! ```
+//!
+//! For formats that append optional values to the end of a record, the
+//! [`Remaining`] encoding deserializes the value only if there are bytes left
+//! within the bounds of the deserializer, like within a `size_prefix`.
+
+use core::marker::PhantomData;
+
+use crate::layout::{Layout, StaticLayout};
+use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The way the absence of an optional value is stored.
+pub trait OptionEncoding<T> {
+    /// Serialize the optional value.
+    fn serialize_option<S: Serializer>(&self, option: &Option<T>, serializer: &mut S) -> Result<S::Success, S::Error>;
+
+    /// Deserialize the optional value.
+    fn deserialize_option<D: Deserializer>(&self, deserializer: &mut D) -> Result<Option<T>, D::Error>;
+}
+
+/// The value is preceded by a flag of type `F` that is one if the value is
+/// present, and zero otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Flag<F> {
+    flag: PhantomData<F>,
+}
+
+/// The value is absent if it's equal to the sentinel.
+///
+/// Present values that are equal to the sentinel can not be serialized.
+/// [Canonical](crate::stream_ser_de::StreamSerializer::canonical) serializers
+/// also reject sentinels that are not equal to themselves, like a NaN float,
+/// because the absent value would deserialize as present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sentinel<T>(pub T);
+
+/// The value is present if there are bytes remaining within the bounds of
+/// the deserializer.
+///
+/// Absent values serialize to nothing.
+/// [Canonical](crate::stream_ser_de::StreamSerializer::canonical) serializers
+/// reject present values that also serialize to nothing, because they would
+/// deserialize as absent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Remaining;
+
+impl<F> Flag<F> {
+    /// Create a presence flag encoding.
+    pub const fn new() -> Self {
+        Self { flag: PhantomData }
+    }
+}
+
+impl<F: StaticLayout> Flag<F> {
+    /// Return the layout of the optional value as encoded.
+    pub const fn layout<T: StaticLayout>(&self) -> Layout {
+        F::LAYOUT.then(Layout::EMPTY.or(T::LAYOUT))
+    }
+}
+
+impl<F> Default for Flag<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Sentinel<T> {
+    /// Return the layout of the optional value as encoded.
+    pub const fn layout(&self) -> Layout
+    where
+        T: StaticLayout,
+    {
+        T::LAYOUT
+    }
+}
+
+impl Remaining {
+    /// Return the layout of the optional value as encoded.
+    pub const fn layout<T: StaticLayout>(&self) -> Layout {
+        Layout::EMPTY.or(T::LAYOUT)
+    }
+}
+
+impl<T, F> OptionEncoding<T> for Flag<F>
+where
+    T: Serialize + Deserialize,
+    F: Serialize + Deserialize + From<bool> + PartialEq,
+{
+    fn serialize_option<S: Serializer>(&self, option: &Option<T>, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer
+            .serialize_composite(|serializer| {
+                F::from(option.is_some()).serialize(serializer)?;
+                match option {
+                    Some(value) => value.serialize(serializer),
+                    None => serializer.success(),
+                }
+            })
+            .map(|(span, _)| span)
+    }
+
+    fn deserialize_option<D: Deserializer>(&self, deserializer: &mut D) -> Result<Option<T>, D::Error> {
+        deserializer.deserialize_composite(|deserializer| {
+            let flag = F::deserialize(deserializer)?;
+            if flag == F::from(true) {
+                T::deserialize(deserializer).map(Some)
+            } else if flag == F::from(false) {
+                Ok(None)
+            } else {
+                deserializer.error("the presence flag of the optional value is neither zero nor one")
+            }
+        })
+    }
+}
+
+impl<T> OptionEncoding<T> for Sentinel<T>
+where
+    T: Serialize + Deserialize + PartialEq,
+{
+    fn serialize_option<S: Serializer>(&self, option: &Option<T>, serializer: &mut S) -> Result<S::Success, S::Error> {
+        let sentinel = &self.0;
+        if serializer.is_canonical() && !sentinel.eq(sentinel) {
+            return Err(serializer.error("the sentinel of the optional value is not equal to itself").unwrap_err());
+        }
+        match option {
+            Some(value) if value == &self.0 => {
+                Err(serializer.error("the optional value is equal to the sentinel of its absence").unwrap_err())
+            }
+            Some(value) => value.serialize(serializer),
+            None => self.0.serialize(serializer),
+        }
+    }
+
+    fn deserialize_option<D: Deserializer>(&self, deserializer: &mut D) -> Result<Option<T>, D::Error> {
+        let value = T::deserialize(deserializer)?;
+        Ok((value != self.0).then_some(value))
+    }
+}
+
+impl<T> OptionEncoding<T> for Remaining
+where
+    T: Serialize + Deserialize,
+{
+    fn serialize_option<S: Serializer>(&self, option: &Option<T>, serializer: &mut S) -> Result<S::Success, S::Error> {
+        match option {
+            Some(value) => {
+                let start = serializer.position();
+                let span = value.serialize(serializer)?;
+                if serializer.is_canonical() && serializer.position() == start {
+                    return Err(serializer.error("the present optional value serializes to nothing").unwrap_err());
+                }
+                Ok(span)
+            }
+            None => serializer.serialize_composite(|serializer| serializer.success()).map(|(span, _)| span),
+        }
+    }
+
+    fn deserialize_option<D: Deserializer>(&self, deserializer: &mut D) -> Result<Option<T>, D::Error> {
+        match deserializer.bytes_in_bounds() {
+            Some(0) => Ok(None),
+            Some(_) => T::deserialize(deserializer).map(Some),
+            None => deserializer.error("the presence of the optional value can only be determined within bounds"),
+        }
+    }
+}
+
+/// An optional value serialized with a specific encoding.
+pub struct Encoded<'option, T, Encoding> {
+    option: &'option Option<T>,
+    encoding: Encoding,
+}
+
+impl<'option, T, E: OptionEncoding<T>> Serialize for Encoded<'option, T, E> {
+    /// Serialize the optional value as per its encoding.
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        self.encoding.serialize_option(self.option, serializer)
+    }
+}
+
+/// Serialize the optional value with the given encoding.
+pub fn encoded<T, Encoding>(option: &Option<T>, encoding: Encoding) -> Encoded<'_, T, Encoding> {
+    Encoded { option, encoding }
+}
+
+/// Deserialize an optional value with the given encoding.
+pub fn deserialize_encoded<T, D, Encoding>(deserializer: &mut D, encoding: Encoding) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer,
+    Encoding: OptionEncoding<T>,
+{
+    encoding.deserialize_option(deserializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind, TraceError as _};
+    use crate::io::GrowingMemoryStream;
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    fn serialize(option: Option<u8>, encoding: impl OptionEncoding<u8>) -> Result<Vec<u8>, Error> {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        encoded(&option, encoding).serialize(&mut serializer)?;
+        Ok(serializer.take().take())
+    }
+
+    fn deserialize(bytes: &[u8], encoding: impl OptionEncoding<u8>) -> Result<Option<u8>, Error> {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+        deserialize_encoded(&mut deserializer, encoding)
+    }
+
+    #[test]
+    fn flag() {
+        assert_eq!(serialize(Some(7), Flag::<u8>::new()), Ok(vec![1, 7]));
+        assert_eq!(serialize(None, Flag::<u8>::new()), Ok(vec![0]));
+        assert_eq!(deserialize(&[1, 7], Flag::<u8>::new()), Ok(Some(7)));
+        assert_eq!(deserialize(&[0, 7], Flag::<u8>::new()), Ok(None));
+    }
+
+    #[test]
+    fn flag_invalid() {
+        assert_eq!(
+            deserialize(&[2, 7], Flag::<u8>::new()),
+            Err(Error::from(ErrorKind::Custom("the presence flag of the optional value is neither zero nor one"))
+                .locate(0))
+        );
+    }
+
+    #[test]
+    fn sentinel() {
+        assert_eq!(serialize(Some(7), Sentinel(0xFF)), Ok(vec![7]));
+        assert_eq!(serialize(None, Sentinel(0xFF)), Ok(vec![0xFF]));
+        assert_eq!(deserialize(&[7], Sentinel(0xFF)), Ok(Some(7)));
+        assert_eq!(deserialize(&[0xFF], Sentinel(0xFF)), Ok(None));
+    }
+
+    #[test]
+    fn sentinel_present() {
+        assert_eq!(
+            serialize(Some(0xFF), Sentinel(0xFF)),
+            Err(ErrorKind::Custom("the optional value is equal to the sentinel of its absence").into())
+        );
+    }
+
+    #[test]
+    fn sentinel_canonical() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).canonical();
+        assert_eq!(
+            encoded(&Some(1.0_f32), Sentinel(f32::NAN)).serialize(&mut serializer),
+            Err(ErrorKind::Custom("the sentinel of the optional value is not equal to itself").into())
+        );
+    }
+
+    #[test]
+    fn remaining() {
+        assert_eq!(serialize(Some(7), Remaining), Ok(vec![7]));
+        assert_eq!(serialize(None, Remaining), Ok(vec![]));
+    }
+
+    #[test]
+    fn remaining_canonical() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).canonical();
+        assert_eq!(
+            encoded(&Some([0_u8; 0]), Remaining).serialize(&mut serializer),
+            Err(ErrorKind::Custom("the present optional value serializes to nothing").into())
+        );
+        assert!(encoded(&Some([7_u8; 1]), Remaining).serialize(&mut serializer).is_ok());
+    }
+
+    #[test]
+    fn remaining_bounded() {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![7, 8]));
+        let option = deserializer.deserialize_bounded(1, |deserializer| {
+            let present = deserialize_encoded::<u8, _, _>(deserializer, Remaining)?;
+            let absent = deserialize_encoded::<u8, _, _>(deserializer, Remaining)?;
+            Ok((present, absent))
+        });
+        assert_eq!(option, Ok((Some(7), None)));
+    }
+
+    #[test]
+    fn remaining_unbounded() {
+        assert_eq!(
+            deserialize(&[7], Remaining),
+            Err(ErrorKind::Custom("the presence of the optional value can only be determined within bounds").into())
+        );
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(Flag::<u8>::new().layout::<u16>(), Layout::new(1, Some(3), 0));
+        assert_eq!(Sentinel(0u16).layout(), Layout::fixed(2));
+        assert_eq!(Remaining.layout::<u16>(), Layout::new(0, Some(2), 0));
+    }
+}
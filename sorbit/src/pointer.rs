@@ -0,0 +1,308 @@
+//! Utilities for objects that are stored elsewhere and referred to by their
+//! offset.
+//!
+//! Many formats, like executables and fonts, store tables of offsets that
+//! point to the data, which is laid out after the tables. With the derive
+//! macros, such a field is declared by the type of its offset, and the object
+//! it points to is written after the structure:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::io::GrowingMemoryStream;
+//! use sorbit::ser_de::{MultiPassDeserialize, ToBytes};
+//! use sorbit::stream_ser_de::StreamDeserializer;
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! #[sorbit(byte_order=big_endian)]
+//! struct Header {
+//!     #[sorbit(pointer=u16, base=composite)]
+//!     name: [u8; 3],
+//!     flags: u8,
+//! }
+//!
+//! let header = Header { name: *b"abc", flags: 0x80 };
+//! let bytes = vec![0x00, 0x03, 0x80, b'a', b'b', b'c'];
+//! assert_eq!(header.to_bytes().unwrap(), bytes);
+//! let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+//! assert_eq!(Header::deserialize(&mut deserializer).unwrap(), header);
+//! ```
+//!
+//! Serializing an object behind a pointer requires a [`RevisableSerializer`],
+//! because the offset is only known after the object has been serialized.
+//! Likewise, deserializing it requires a [`SeekableDeserializer`]. Once the
+//! structure is deserialized, the deserializer moves past the objects that
+//! were written after it, so that structures with pointer fields can be
+//! nested like any other.
+//!
+//! When the objects are fields of the structure itself, laid out after the
+//! table of their offsets, the table's fields are declared with `offset_of`
+//! instead. Their offsets are written once the whole structure is serialized,
+//! as in [`serialize_offset`].
+
+use core::cell::Cell;
+
+use crate::ser_de::{Deserialize, RevisableSerializer, SeekableDeserializer, Serialize, Span};
+
+/// The position an offset is interpreted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Base {
+    /// The offset is interpreted from the beginning of the stream.
+    #[default]
+    Absolute,
+    /// The offset is interpreted from the beginning of the composite that
+    /// contains the offset.
+    Composite,
+    /// The offset is interpreted from the beginning of the offset itself.
+    Relative,
+}
+
+/// Serialize an object at the current position, and revise the offset at
+/// `pointer` to point to it.
+///
+/// The offset must have been serialized as a placeholder within `composite`
+/// beforehand, typically as the default value of `Offset`. Unlike the offset,
+/// the object is serialized where the serializer currently is, so it usually
+/// follows the composite.
+///
+/// If the offset can not be represented by `Offset`, an error is returned.
+pub fn serialize_pointee<Offset, S>(
+    serializer: &mut S,
+    base: Base,
+    composite: &S::Success,
+    pointer: &S::Success,
+    serialize_object: impl FnOnce(&mut S) -> Result<S::Success, S::Error>,
+) -> Result<S::Success, S::Error>
+where
+    Offset: Serialize + TryFrom<u64>,
+    S: RevisableSerializer,
+{
+    let object = serialize_object(serializer)?;
+    serializer.revise_span(pointer, |serializer| {
+        serialize_offset::<Offset, _>(serializer, base, composite, pointer, &object)
+    })?;
+    Ok(object)
+}
+
+/// Serialize the offset of `object` as the type `Offset`.
+///
+/// This is the final value of an offset whose placeholder was serialized at
+/// `pointer` within `composite`, and it's meant to be serialized into the
+/// placeholder via [`revise_span`](RevisableSerializer::revise_span), like
+/// the entries of an offset table that precedes the objects. The offset is
+/// interpreted from `base`, and it points to the first byte of the object
+/// itself, after any padding that aligns it.
+///
+/// If the offset can not be represented by `Offset`, or if the object
+/// precedes the base, an error is returned.
+pub fn serialize_offset<Offset, S>(
+    serializer: &mut S,
+    base: Base,
+    composite: &S::Success,
+    pointer: &S::Success,
+    object: &S::Success,
+) -> Result<S::Success, S::Error>
+where
+    Offset: Serialize + TryFrom<u64>,
+    S: RevisableSerializer,
+{
+    let origin = match base {
+        Base::Absolute => 0,
+        Base::Composite => composite.start(),
+        Base::Relative => pointer.start(),
+    };
+    let offset = object.start().checked_sub(origin).and_then(|offset| Offset::try_from(offset).ok());
+    let Some(offset) = offset else {
+        return Err(serializer
+            .error("the offset of the object is too large for its binary representation")
+            .unwrap_err());
+    };
+    Serialize::serialize(&offset, serializer)
+}
+
+/// Deserialize an offset, and the object it points to.
+///
+/// The object is deserialized without moving the deserializer past the
+/// offset, so the fields that follow the offset can be deserialized as usual.
+pub fn deserialize_pointee<Offset, D, O>(
+    deserializer: &mut D,
+    base: Base,
+    deserialize_object: impl FnOnce(&mut D) -> Result<O, D::Error>,
+) -> Result<O, D::Error>
+where
+    Offset: Deserialize,
+    u64: TryFrom<Offset>,
+    D: SeekableDeserializer,
+{
+    let origin = match base {
+        Base::Absolute => 0,
+        Base::Composite => deserializer.position() - deserializer.composite_position(),
+        Base::Relative => deserializer.position(),
+    };
+    let offset = Offset::deserialize(deserializer)?;
+    let Some(position) = u64::try_from(offset).ok().and_then(|offset| origin.checked_add(offset)) else {
+        return deserializer.error("the offset of the object is out of range");
+    };
+    deserializer.deserialize_at(position, deserialize_object)
+}
+
+/// Keeps track of the objects deserialized via the pointers of a composite.
+///
+/// Pointer fields are deserialized via [`deserialize`](Self::deserialize),
+/// after which [`skip`](Self::skip) moves past the objects that end after
+/// the composite.
+#[derive(Debug, Default)]
+pub struct Pointees {
+    end: Cell<u64>,
+}
+
+impl Pointees {
+    /// Create a tracker that hasn't seen any objects yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deserialize an offset, and the object it points to, like
+    /// [`deserialize_pointee`], and remember where the object ends.
+    pub fn deserialize<Offset, D, O>(
+        &self,
+        deserializer: &mut D,
+        base: Base,
+        deserialize_object: impl FnOnce(&mut D) -> Result<O, D::Error>,
+    ) -> Result<O, D::Error>
+    where
+        Offset: Deserialize,
+        u64: TryFrom<Offset>,
+        D: SeekableDeserializer,
+    {
+        deserialize_pointee::<Offset, _, _>(deserializer, base, |deserializer| {
+            let object = deserialize_object(deserializer)?;
+            self.end.set(core::cmp::max(self.end.get(), deserializer.position()));
+            Ok(object)
+        })
+    }
+
+    /// Move past the furthest object, if it ends after the current position.
+    pub fn skip<D: SeekableDeserializer>(&self, deserializer: &mut D) -> Result<(), D::Error> {
+        let position = deserializer.position();
+        match self.end.get().checked_sub(position) {
+            Some(distance) if distance > 0 => deserializer.seek(deserializer.composite_position() + distance),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind};
+    use crate::io::GrowingMemoryStream;
+    use crate::ser_de::{Deserializer, Serializer};
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    fn serialize(base: Base) -> Result<Vec<u8>, Error> {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        serializer.serialize_u8(0xFF)?;
+        let (composite, pointer) = serializer.serialize_composite(|serializer| {
+            serializer.serialize_u8(0xEE)?;
+            u8::default().serialize(serializer)
+        })?;
+        serialize_pointee::<u8, _>(&mut serializer, base, &composite, &pointer, |serializer| {
+            serializer.serialize_array(&[0xAA, 0xBB])
+        })?;
+        Ok(serializer.take().take())
+    }
+
+    fn deserialize(bytes: &[u8], base: Base) -> Result<([u8; 2], u8), Error> {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+        deserializer.deserialize_u8()?;
+        let object = deserializer.deserialize_composite(|deserializer| {
+            deserializer.deserialize_u8()?;
+            deserialize_pointee::<u8, _, _>(deserializer, base, |deserializer| deserializer.deserialize_array())
+        })?;
+        Ok((object, deserializer.deserialize_u8()?))
+    }
+
+    #[test]
+    fn serialize_base() {
+        assert_eq!(serialize(Base::Absolute), Ok(vec![0xFF, 0xEE, 0x03, 0xAA, 0xBB]));
+        assert_eq!(serialize(Base::Composite), Ok(vec![0xFF, 0xEE, 0x02, 0xAA, 0xBB]));
+        assert_eq!(serialize(Base::Relative), Ok(vec![0xFF, 0xEE, 0x01, 0xAA, 0xBB]));
+    }
+
+    #[test]
+    fn serialize_offset_table() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let composite = serializer
+            .serialize_composite(|serializer| {
+                let table = [
+                    u8::default().serialize(serializer)?,
+                    u8::default().serialize(serializer)?,
+                ];
+                serializer.align(4)?;
+                let first = serializer.serialize_u8(0xAA)?;
+                serializer.align(4)?;
+                let second = serializer.serialize_u8(0xBB)?;
+                for (entry, object) in table.iter().zip([first, second]) {
+                    serializer.revise_span(entry, |serializer| {
+                        serialize_offset::<u8, _>(serializer, Base::Relative, entry, entry, &object)
+                    })?;
+                }
+                Ok(())
+            })
+            .map(|(_, table)| table);
+        assert_eq!(composite, Ok(()));
+        assert_eq!(serializer.take().take(), vec![4, 7, 0, 0, 0xAA, 0, 0, 0, 0xBB]);
+    }
+
+    #[test]
+    fn serialize_too_large() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let pointer = u8::default().serialize(&mut serializer).unwrap();
+        let result = serialize_pointee::<u8, _>(&mut serializer, Base::Absolute, &pointer, &pointer, |serializer| {
+            serializer.serialize_slice(&[0; 256])?;
+            serializer.serialize_u8(0)
+        });
+        assert_eq!(
+            result,
+            Err(ErrorKind::Custom("the offset of the object is too large for its binary representation").into())
+        );
+    }
+
+    #[test]
+    fn deserialize_base() {
+        assert_eq!(deserialize(&[0xFF, 0xEE, 0x03, 0xAA, 0xBB], Base::Absolute), Ok(([0xAA, 0xBB], 0xAA)));
+        assert_eq!(deserialize(&[0xFF, 0xEE, 0x02, 0xAA, 0xBB], Base::Composite), Ok(([0xAA, 0xBB], 0xAA)));
+        assert_eq!(deserialize(&[0xFF, 0xEE, 0x01, 0xAA, 0xBB], Base::Relative), Ok(([0xAA, 0xBB], 0xAA)));
+    }
+
+    #[test]
+    fn skip_pointees() {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![0x02, 0x03, 0xAA, 0xBB, 0xCC]));
+        let pointees = Pointees::new();
+        let objects = deserializer.deserialize_composite(|deserializer| {
+            let first = pointees.deserialize::<u8, _, _>(deserializer, Base::Absolute, |d| d.deserialize_u8())?;
+            let second = pointees.deserialize::<u8, _, _>(deserializer, Base::Absolute, |d| d.deserialize_u8())?;
+            pointees.skip(deserializer)?;
+            Ok((first, second))
+        });
+        assert_eq!(objects, Ok((0xAA, 0xBB)));
+        assert_eq!(deserializer.deserialize_u8(), Ok(0xCC));
+    }
+
+    #[test]
+    fn skip_preceding_pointees() {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![0xAA, 0x00, 0xBB]));
+        deserializer.deserialize_u8().unwrap();
+        let pointees = Pointees::new();
+        let object = pointees.deserialize::<u8, _, _>(&mut deserializer, Base::Absolute, |d| d.deserialize_u8());
+        assert_eq!(object, Ok(0xAA));
+        assert_eq!(pointees.skip(&mut deserializer), Ok(()));
+        assert_eq!(deserializer.deserialize_u8(), Ok(0xBB));
+    }
+
+    #[test]
+    fn deserialize_out_of_bounds() {
+        assert!(deserialize(&[0xFF, 0xEE, 0x04, 0xAA, 0xBB], Base::Absolute).is_err());
+    }
+}
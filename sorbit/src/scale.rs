@@ -0,0 +1,132 @@
+//! Utilities for serializing floating-point values as scaled integers.
+//!
+//! Sensors and low-bandwidth protocols often transmit physical quantities as
+//! fixed-point integers: the real value is `raw * scale + bias`. With the
+//! derive macros, [`f32`] and [`f64`] fields can be annotated with the scale,
+//! the bias, and the integer type they are encoded as. This is most useful
+//! for bit field members:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::ser_de::{FromBytes, ToBytes};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! #[sorbit(byte_order=big_endian)]
+//! struct Reading {
+//!     #[sorbit(bit_field=_raw, repr=u16, bits=0..12, scale=0.5, bias=-40.0)]
+//!     temperature: f32,
+//!     #[sorbit(bit_field=_raw, bits=12..16)]
+//!     channel: u8,
+//! }
+//!
+//! let reading = Reading { temperature: 21.5, channel: 3 };
+//! assert_eq!(reading.to_bytes().unwrap(), [0x30, 0x7B]);
+//! assert_eq!(Reading::from_bytes(&[0x30, 0x7B]).unwrap(), reading);
+//! ```
+
+use crate::ser_de::{Deserializer, Serializer};
+
+/// A floating-point type that can be encoded as a scaled integer.
+pub trait Float: Copy {
+    /// Convert the value to an [`f64`].
+    fn to_f64(self) -> f64;
+
+    /// Convert an [`f64`] to the value, rounding if necessary.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Float for f32 {
+    fn to_f64(self) -> f64 {
+        self.into()
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Float for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+/// Return `(value - bias) / scale`, rounded to the nearest integer, in a
+/// specific (integer) type.
+///
+/// If the rounded value cannot be represented by `T`, an error is returned.
+pub fn scaled_as<T, F, S>(serializer: &mut S, value: &F, scale: f64, bias: f64) -> Result<T, S::Error>
+where
+    T: TryFrom<i64>,
+    F: Float,
+    S: Serializer,
+{
+    let scaled = (value.to_f64() - bias) / scale;
+    // Casting to `i64` truncates towards zero, so rounding away from zero is
+    // done by hand. (`f64::round` is not available without `std`.)
+    let rounded = if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 };
+    let in_range = (i64::MIN as f64..i64::MAX as f64).contains(&rounded);
+    in_range
+        .then_some(rounded as i64)
+        .and_then(|raw| T::try_from(raw).ok())
+        .ok_or_else(|| serializer.error("the scaled value does not fit into its binary representation").unwrap_err())
+}
+
+/// Return `raw * scale + bias` as a floating-point value.
+pub fn scaled_from<T, F, D>(deserializer: &mut D, raw: T, scale: f64, bias: f64) -> Result<F, D::Error>
+where
+    T: TryInto<i64>,
+    F: Float,
+    D: Deserializer,
+{
+    match raw.try_into() {
+        Ok(raw) => Ok(F::from_f64(raw as f64 * scale + bias)),
+        Err(_) => deserializer.error("the scaled value is too large to be represented"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind};
+    use crate::io::{FixedMemoryStream, GrowingMemoryStream};
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    #[test]
+    fn scaled_as_rounds() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        assert_eq!(scaled_as::<u16, _, _>(&mut serializer, &1.236f32, 0.01, 0.0), Ok(124));
+        assert_eq!(scaled_as::<i16, _, _>(&mut serializer, &-1.236f64, 0.01, 0.0), Ok(-124));
+        assert_eq!(scaled_as::<u8, _, _>(&mut serializer, &-40.0f64, 0.5, -40.0), Ok(0));
+    }
+
+    #[test]
+    fn scaled_as_overflow() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let error = Err(Error::from(ErrorKind::Custom("the scaled value does not fit into its binary representation")));
+        assert_eq!(scaled_as::<u8, _, _>(&mut serializer, &2.56f64, 0.01, 0.0), error);
+        assert_eq!(scaled_as::<u8, _, _>(&mut serializer, &-0.01f64, 0.01, 0.0), error);
+        assert_eq!(scaled_as::<u8, _, _>(&mut serializer, &f64::NAN, 1.0, 0.0), error);
+    }
+
+    #[test]
+    fn scaled_from_raw() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([]));
+        assert_eq!(scaled_from::<_, f64, _>(&mut deserializer, 124u16, 0.5, -40.0), Ok(22.0));
+        assert_eq!(scaled_from::<_, f32, _>(&mut deserializer, -3i8, 0.25, 0.0), Ok(-0.75));
+    }
+
+    #[test]
+    fn scaled_from_overflow() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([]));
+        assert_eq!(
+            scaled_from::<_, f64, _>(&mut deserializer, u64::MAX, 1.0, 0.0),
+            Err(Error::from(ErrorKind::Custom("the scaled value is too large to be represented")))
+        );
+    }
+}
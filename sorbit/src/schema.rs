@@ -0,0 +1,291 @@
+//! Export the layout of types as a declarative schema.
+//!
+//! A [`Schema`] is a tree that describes where the fields of a structure are
+//! and how large they are, built from the [`FieldLayout`]s of a type with a
+//! [`StaticLayout`]. The schema can be exported to formats that are understood
+//! by existing tools, like the [Kaitai Struct](https://kaitai.io) YAML format,
+//! so that formats defined in Rust can be inspected with visualizers.
+//!
+//! ```
+//! use sorbit::schema::Schema;
+//! use sorbit::{Serialize, StaticLayout};
+//!
+//! #[derive(Serialize, StaticLayout)]
+//! struct Header {
+//!     version: u8,
+//!     flags: u8,
+//! }
+//!
+//! #[derive(Serialize, StaticLayout)]
+//! struct Frame {
+//!     header: Header,
+//!     #[sorbit(align = 4)]
+//!     payload: [u8; 12],
+//! }
+//!
+//! let schema = Schema::of::<Frame>("frame");
+//! let expected = "\
+//! meta:
+//!   id: frame
+//! seq:
+//!   - id: header
+//!     type: header
+//!     size: 2
+//!   - id: padding_2
+//!     size: 2
+//!   - id: payload
+//!     size: 12
+//! types:
+//!   header:
+//!     seq:
+//!       - id: version
+//!         size: 1
+//!       - id: flags
+//!         size: 1
+//! ";
+//! assert_eq!(schema.to_kaitai(), Ok(expected.into()));
+//! ```
+//!
+//! Only the positions and sizes of the fields are known from their layouts,
+//! so the fields are exported as raw bytes, or as nested types for fields
+//! that are structures themselves.
+
+use alloc::string::{String, ToString as _};
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+
+use crate::error::{Error, ErrorKind};
+use crate::layout::{FieldLayout, Layout, StaticLayout};
+
+/// The description of the fields of a type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Schema {
+    id: String,
+    layout: Layout,
+    fields: Vec<SchemaField>,
+}
+
+/// The description of a field within a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SchemaField {
+    id: String,
+    offset: Option<u64>,
+    schema: Schema,
+}
+
+impl Schema {
+    /// Create the schema of the type `T` with the given identifier.
+    pub fn of<T: StaticLayout>(id: &str) -> Self {
+        Self::new(id, T::LAYOUT, T::FIELDS)
+    }
+
+    /// Create the schema of a type with the given `layout` and `fields`.
+    pub fn new(id: &str, layout: Layout, fields: &[FieldLayout]) -> Self {
+        let fields = fields
+            .iter()
+            .map(|field| SchemaField {
+                id: field.name().to_string(),
+                offset: field.offset(),
+                schema: Schema::new(field.name(), field.layout(), field.fields()),
+            })
+            .collect();
+        Self { id: id.to_string(), layout, fields }
+    }
+
+    /// The identifier of the type.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The layout of the type.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// The fields of the type, in serialization order.
+    ///
+    /// Empty if the type is not a structure.
+    pub fn fields(&self) -> &[SchemaField] {
+        &self.fields
+    }
+
+    /// Export the schema as a Kaitai Struct YAML document.
+    ///
+    /// The gaps between fields are exported as padding, and fields that
+    /// precede the end of the previous field, like overlays, are exported as
+    /// instances. Fails if a field other than the last one has a variable
+    /// size, as the position of the subsequent fields can't be described.
+    pub fn to_kaitai(&self) -> Result<String, Error> {
+        let mut output = String::new();
+        writeln!(output, "meta:").unwrap();
+        writeln!(output, "  id: {}", kaitai_id(&self.id)).unwrap();
+        write_kaitai_type(&mut output, self, 0)?;
+        Ok(output)
+    }
+}
+
+impl SchemaField {
+    /// The identifier of the field.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The offset of the field in bytes from the beginning of the enclosing
+    /// type, if it's fixed.
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// The schema of the field's type.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+}
+
+/// Write the `seq`, `instances`, and `types` of the `schema` at `indent`.
+fn write_kaitai_type(output: &mut String, schema: &Schema, indent: usize) -> Result<(), Error> {
+    let pad = " ".repeat(indent);
+    let mut seq = String::new();
+    let mut instances = String::new();
+    let mut types = String::new();
+    let mut position = Some(0);
+
+    for (index, field) in schema.fields.iter().enumerate() {
+        let id = kaitai_id(&field.id);
+        let size = field.schema.layout.size();
+        match (position, field.offset) {
+            (Some(position), Some(offset)) if offset < position => {
+                writeln!(instances, "{pad}  {id}:").unwrap();
+                writeln!(instances, "{pad}    pos: {offset}").unwrap();
+                write_kaitai_field_type(&mut instances, &field.schema, &id, &pad);
+                continue;
+            }
+            (Some(position), Some(offset)) if offset > position => {
+                writeln!(seq, "{pad}  - id: padding_{position}").unwrap();
+                writeln!(seq, "{pad}    size: {}", offset - position).unwrap();
+            }
+            _ => (),
+        }
+        writeln!(seq, "{pad}  - id: {id}").unwrap();
+        write_kaitai_field_type(&mut seq, &field.schema, &id, &pad);
+        if size.is_none() && index + 1 < schema.fields.len() {
+            return Err(ErrorKind::Custom("only the last field can have a variable size").into());
+        }
+        position = field.offset.zip(size).map(|(offset, size)| offset + size);
+
+        if !field.schema.fields.is_empty() {
+            writeln!(types, "{pad}  {id}:").unwrap();
+            write_kaitai_type(&mut types, &field.schema, indent + 4)?;
+        }
+    }
+
+    if let (Some(position), Some(size)) = (position, schema.layout.size())
+        && position < size
+    {
+        writeln!(seq, "{pad}  - id: padding_{position}").unwrap();
+        writeln!(seq, "{pad}    size: {}", size - position).unwrap();
+    }
+
+    for (key, section) in [("seq", seq), ("instances", instances), ("types", types)] {
+        if !section.is_empty() {
+            writeln!(output, "{pad}{key}:").unwrap();
+            output.push_str(&section);
+        }
+    }
+    Ok(())
+}
+
+/// Write the `type` and `size` of a field whose keys are at `pad` + 4.
+fn write_kaitai_field_type(output: &mut String, schema: &Schema, id: &str, pad: &str) {
+    if !schema.fields.is_empty() {
+        writeln!(output, "{pad}    type: {id}").unwrap();
+    }
+    match schema.layout.size() {
+        Some(size) => writeln!(output, "{pad}    size: {size}").unwrap(),
+        None => writeln!(output, "{pad}    size-eos: true").unwrap(),
+    }
+}
+
+/// Turn a field name into a valid Kaitai Struct identifier.
+///
+/// Identifiers must start with a lowercase letter and contain only lowercase
+/// letters, digits, and underscores.
+fn kaitai_id(name: &str) -> String {
+    let name = name.trim_start_matches('_');
+    let id: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '_',
+        })
+        .collect();
+    match id.starts_with(|c: char| c.is_ascii_lowercase()) {
+        true => id,
+        false => alloc::format!("field_{id}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kaitai_id() {
+        assert_eq!(super::kaitai_id("foo_bar"), "foo_bar");
+        assert_eq!(super::kaitai_id("_bits"), "bits");
+        assert_eq!(super::kaitai_id("0"), "field_0");
+        assert_eq!(super::kaitai_id("Mixed"), "mixed");
+    }
+
+    #[test]
+    fn overlay_and_trailing_padding() {
+        const FIELDS: &[FieldLayout] = &[
+            FieldLayout::new("a", Some(0), Layout::fixed(4)),
+            FieldLayout::new("b", Some(1), Layout::fixed(2)),
+        ];
+        let schema = Schema::new("test", Layout::fixed(8), FIELDS);
+        let expected = "\
+meta:
+  id: test
+seq:
+  - id: a
+    size: 4
+  - id: padding_4
+    size: 4
+instances:
+  b:
+    pos: 1
+    size: 2
+";
+        assert_eq!(schema.to_kaitai(), Ok(expected.into()));
+    }
+
+    #[test]
+    fn variable_size_last() {
+        const FIELDS: &[FieldLayout] = &[
+            FieldLayout::new("a", Some(0), Layout::fixed(1)),
+            FieldLayout::new("b", Some(1), Layout::new(0, None, 0)),
+        ];
+        let schema = Schema::new("test", Layout::new(1, None, 0), FIELDS);
+        let expected = "\
+meta:
+  id: test
+seq:
+  - id: a
+    size: 1
+  - id: b
+    size-eos: true
+";
+        assert_eq!(schema.to_kaitai(), Ok(expected.into()));
+    }
+
+    #[test]
+    fn variable_size_middle() {
+        const FIELDS: &[FieldLayout] = &[
+            FieldLayout::new("a", Some(0), Layout::new(0, None, 0)),
+            FieldLayout::new("b", None, Layout::fixed(1)),
+        ];
+        let schema = Schema::new("test", Layout::new(1, None, 0), FIELDS);
+        assert_eq!(schema.to_kaitai(), Err(ErrorKind::Custom("only the last field can have a variable size").into()));
+    }
+}
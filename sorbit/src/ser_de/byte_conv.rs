@@ -5,7 +5,7 @@ use crate::byte_order::ByteOrder;
 use crate::error::Error;
 use crate::io::FixedMemoryStream;
 use crate::ser_de::{Deserialize, MultiPassSerialize, Serialize};
-use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+use crate::stream_ser_de::{SizeSerializer, StreamDeserializer, StreamSerializer};
 
 /// Serialize a value to a blob of bytes.
 ///
@@ -49,6 +49,12 @@ pub trait ToBytes<const MULTI_PASS: bool> {
     #[cfg(feature = "alloc")]
     fn to_xe_bytes(&self, byte_order: ByteOrder) -> Result<Vec<u8>, Error>;
 
+    /// Return the number of bytes the value serializes to, without storing
+    /// the bytes.
+    ///
+    /// This is useful to allocate buffers of the exact size up front.
+    fn serialized_len(&self) -> Result<u64, Error>;
+
     /// Serialize the value into a blob of bytes.
     ///
     /// The byte order is native by default, but it may be overridden by
@@ -98,6 +104,11 @@ where
         let mut serializer = StreamSerializer::new(FixedMemoryStream::new(bytes)).change_byte_order(byte_order);
         self.serialize(&mut serializer).map(move |_| serializer.take().take())
     }
+
+    fn serialized_len(&self) -> Result<u64, Error> {
+        let mut serializer = SizeSerializer::default();
+        self.serialize(&mut serializer).map(move |_| serializer.take().len())
+    }
 }
 
 impl<T> ToBytes<true> for T
@@ -118,6 +129,11 @@ where
         let mut serializer = StreamSerializer::new(FixedMemoryStream::new(bytes)).change_byte_order(byte_order);
         self.serialize(&mut serializer).map(move |_| serializer.take().take())
     }
+
+    fn serialized_len(&self) -> Result<u64, Error> {
+        let mut serializer = SizeSerializer::default();
+        self.serialize(&mut serializer).map(move |_| serializer.take().len())
+    }
 }
 
 /// Deserialize a value from a blob of bytes.
@@ -202,6 +218,13 @@ mod tests {
         assert_eq!(value.to_bytes(), Ok(vec![]));
     }
 
+    #[test]
+    fn serialized_len() {
+        assert_eq!(0xABCD_u16.serialized_len(), Ok(2));
+        assert_eq!([1u8, 2, 3].serialized_len(), Ok(3));
+        assert_eq!(SinglePass.serialized_len(), Ok(0));
+    }
+
     #[test]
     fn to_byte_endianness() {
         let value = 0xABCD_u16;
@@ -1,4 +1,7 @@
-use crate::ser_de::Deserializer;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+use crate::ser_de::{BorrowDeserializer, Deserializer, SeekableDeserializer};
 
 /// The type can be deserialized from a [`Deserializer`].
 ///
@@ -19,3 +22,58 @@ where
     /// changes or to enter an indeterminate state.
     fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error>;
 }
+
+/// The type can be deserialized from a [`SeekableDeserializer`].
+///
+/// This trait is analogous to [`Deserialize`], but is meant for types that
+/// have to move backward in the stream while deserializing, for example,
+/// because some of their fields overlap. See [`Deserialize`] for more
+/// information.
+pub trait MultiPassDeserialize
+where
+    Self: Sized,
+{
+    /// Try to deserialize this object from the `deserializer`.
+    ///
+    /// See [`Deserialize::deserialize`] for more information.
+    fn deserialize<D: SeekableDeserializer>(deserializer: &mut D) -> Result<Self, D::Error>;
+}
+
+/// The type can be deserialized from a [`BorrowDeserializer`].
+///
+/// This trait is analogous to [`Deserialize`], but is meant for types that
+/// borrow from the deserialized bytes, like structures with `&'de [u8]` or
+/// `&'de str` fields. Every type that implements [`Deserialize`] implements
+/// this trait as well. The derive macro implements this trait instead of
+/// [`Deserialize`] for structures that have fields marked with
+/// `#[sorbit(borrow)]`. See [`Deserialize`] for more information.
+pub trait DeserializeBorrowed<'de>
+where
+    Self: Sized,
+{
+    /// Try to deserialize this object from the `deserializer`.
+    ///
+    /// See [`Deserialize::deserialize`] for more information.
+    fn deserialize_borrowed<D: BorrowDeserializer<'de>>(deserializer: &mut D) -> Result<Self, D::Error>;
+}
+
+impl<'de, T: Deserialize> DeserializeBorrowed<'de> for T {
+    fn deserialize_borrowed<D: BorrowDeserializer<'de>>(deserializer: &mut D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
+
+/// The type can be deserialized directly into heap storage.
+///
+/// Deserializing into a [`Box`] via [`Deserialize`] constructs the value on
+/// the stack before moving it to the heap, which can overflow small stacks
+/// when the value is large. Arrays implement this trait to deserialize their
+/// items in place. The derive macro uses this trait for `Box<T>` fields that
+/// are marked with `#[sorbit(boxed)]`.
+#[cfg(feature = "alloc")]
+pub trait DeserializeBoxed {
+    /// Try to deserialize this object from the `deserializer` into a [`Box`].
+    ///
+    /// See [`Deserialize::deserialize`] for more information.
+    fn deserialize_boxed<D: Deserializer>(deserializer: &mut D) -> Result<Box<Self>, D::Error>;
+}
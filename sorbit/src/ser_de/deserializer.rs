@@ -1,9 +1,16 @@
 use crate::bit::Error as BitError;
 use crate::byte_order::ByteOrder;
 use crate::error::{MessageError, TraceError};
+use crate::ser_de::Limits;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 /// Derializers can transform a stream of bytes that can
 /// be sent over the network or stored in files into primitive types.
+///
+/// Like serializers, deserializers are always passed around by mutable
+/// reference. Helper functions should take `&mut D` where `D: Deserializer`.
+/// See [`Serializer`](super::Serializer) for details.
 pub trait Deserializer: Sized {
     /// The error type returned upon deserialization failure.
     type Error: TraceError + MessageError + From<BitError>;
@@ -41,6 +48,39 @@ pub trait Deserializer: Sized {
     /// Deserialize a [`i128`] value according the current byte order.
     fn deserialize_i128(&mut self) -> Result<i128, Self::Error>;
 
+    /// Deserialize a [`f32`] value according the current byte order.
+    fn deserialize_f32(&mut self) -> Result<f32, Self::Error>;
+
+    /// Deserialize a [`f64`] value according the current byte order.
+    fn deserialize_f64(&mut self) -> Result<f64, Self::Error>;
+
+    /// Deserialize a [`u64`] value stored as a variable-length LEB128 integer.
+    ///
+    /// The counterpart of [`Serializer::serialize_varint_u64`](super::Serializer::serialize_varint_u64).
+    /// If the integer does not fit into a [`u64`], an error is returned.
+    fn deserialize_varint_u64(&mut self) -> Result<u64, Self::Error> {
+        let mut value = 0;
+        for index in 0..10 {
+            let byte = self.deserialize_u8()?;
+            if index == 9 && byte > 1 {
+                break;
+            }
+            value |= ((byte & 0x7F) as u64) << (7 * index);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        self.error("the variable-length integer is too large")
+    }
+
+    /// Deserialize an [`i64`] value stored as a zig-zag encoded variable-length integer.
+    ///
+    /// The counterpart of [`Serializer::serialize_varint_i64`](super::Serializer::serialize_varint_i64).
+    fn deserialize_varint_i64(&mut self) -> Result<i64, Self::Error> {
+        let value = self.deserialize_varint_u64()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
     /// Deserialize a [`u8`] array.
     ///
     /// The size of the array should **not** be stored in the byte stream
@@ -70,11 +110,71 @@ pub trait Deserializer: Sized {
     /// multiple of `multiple_of`. (See [`deserialize_composite`](Self::deserialize_composite).)
     fn align(&mut self, multiple_of: u64) -> Result<(), Self::Error>;
 
+    /// Skip the next `byte_count` bytes without deserializing them.
+    ///
+    /// Unlike [`pad`](Self::pad), the skipped bytes are never verified, and
+    /// deserializers may move the stream forward without reading the bytes
+    /// when the stream supports it. This is useful to jump over large embedded
+    /// payloads. The default implementation reads the bytes and discards them.
+    fn skip(&mut self, byte_count: u64) -> Result<(), Self::Error> {
+        let mut buffer = [0u8; 64];
+        let mut remaining = byte_count;
+        while remaining > 0 {
+            let count = core::cmp::min(buffer.len() as u64, remaining) as usize;
+            self.deserialize_slice(&mut buffer[..count])?;
+            remaining -= count as u64;
+        }
+        Ok(())
+    }
+
+    /// Skip bytes up to `offset`, which is interpreted from the beginning of
+    /// the current composite. (See [`deserialize_composite`](Self::deserialize_composite).)
+    ///
+    /// See [`skip`](Self::skip) for how the bytes are skipped.
+    ///
+    /// ## Errors
+    ///
+    /// When the stream has already been read past `offset`, an error is
+    /// returned.
+    fn skip_to(&mut self, offset: u64) -> Result<(), Self::Error> {
+        match offset.checked_sub(self.composite_position()) {
+            Some(byte_count) => self.skip(byte_count),
+            None => self.error("cannot skip to an offset that has already been read past"),
+        }
+    }
+
+    /// Pad so that the [`position`](Self::position) in the stream becomes a
+    /// multiple of `multiple_of`.
+    ///
+    /// Unlike [`align`](Self::align), the alignment doesn't depend on where
+    /// the current composite begins.
+    fn align_absolute(&mut self, multiple_of: u64) -> Result<(), Self::Error> {
+        let padding = self.position().div_ceil(multiple_of) * multiple_of - self.position();
+        self.pad(self.composite_position() + padding)
+    }
+
+    /// Return the current position in the stream.
+    ///
+    /// This is where the next object will be deserialized from.
+    fn position(&self) -> u64;
+
+    /// Return the current position relative to the beginning of the current
+    /// composite. (See [`deserialize_composite`](Self::deserialize_composite).)
+    ///
+    /// This is the position [`pad`](Self::pad) and [`align`](Self::align)
+    /// are interpreted against.
+    fn composite_position(&self) -> u64;
+
+    /// Return the number of composites the deserializer is currently within.
+    /// (See [`deserialize_composite`](Self::deserialize_composite).)
+    fn depth(&self) -> u32;
+
     /// Deserialize a composite object (e.g. a struct).
     ///
     /// This does not affect the underlying stream and serves only as a marker
     /// for the [`pad`](Self::pad) and [`align`](Self::align) functions.
-    /// This call can be nested as necessary (i.e. composite of composites).
+    /// This call can be nested as necessary (i.e. composite of composites),
+    /// up to the [`max_depth`](Limits::max_depth) of the [`limits`](Self::limits).
     ///
     /// ## Members of the composite
     ///
@@ -92,13 +192,26 @@ pub trait Deserializer: Sized {
     /// Temporarily change the byte order.
     ///
     /// All items serialized in the `deserialize_members` function will use the
-    /// selected byte order. This call can be nested as necessary.
+    /// selected byte order. This call can be nested as necessary, in which
+    /// case the innermost call determines the byte order.
+    ///
+    /// The semantics are the same as for [`Serializer::with_byte_order`](super::Serializer::with_byte_order).
     fn with_byte_order<O>(
         &mut self,
         byte_order: ByteOrder,
         deserialize_members: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
     ) -> Result<O, Self::Error>;
 
+    /// Temporarily change the fill byte of padding.
+    ///
+    /// The fill byte is only relevant when the deserializer verifies padding.
+    /// The semantics are the same as for [`Serializer::with_fill`](super::Serializer::with_fill).
+    fn with_fill<O>(
+        &mut self,
+        fill: u8,
+        deserialize_members: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<O, Self::Error>;
+
     /// Deserialize an object of known length.
     ///
     /// This is useful when you cannot tell where the object ends based on its
@@ -120,9 +233,297 @@ pub trait Deserializer: Sized {
     /// See [`deserialize_bounded`](Self::deserialize_bounded).
     fn bytes_in_bounds(&self) -> Option<u64>;
 
+    /// Deserialize an object, and return the bytes it was deserialized from
+    /// along with it.
+    ///
+    /// This is useful to verify checksums over the raw bytes of objects. The
+    /// default implementation returns an error, as not all deserializers can
+    /// record the bytes they read.
+    #[cfg(feature = "alloc")]
+    fn deserialize_recorded<O>(
+        &mut self,
+        deserialize_object: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<(O, Vec<u8>), Self::Error> {
+        let _ = deserialize_object;
+        self.error("the deserializer cannot record the bytes it reads")
+    }
+
+    /// Deserialize a field of a composite object (e.g. a member of a struct).
+    ///
+    /// The derive macros call this for every field with its `name`, which
+    /// lets deserializers keep track of where the fields were read from. The
+    /// default implementation simply deserializes the field.
+    fn deserialize_field<O>(
+        &mut self,
+        name: &'static str,
+        deserialize_field: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<O, Self::Error> {
+        let _ = name;
+        deserialize_field(self)
+    }
+
+    /// Return the resource limits of the deserializer.
+    ///
+    /// Implementors of [`Deserialize`](crate::ser_de::Deserialize) should
+    /// respect these limits when the size of the deserialized object is
+    /// determined by the deserialized data.
+    fn limits(&self) -> Limits {
+        Limits::DEFAULT
+    }
+
+    /// Check if malformed values should be kept in their raw form.
+    ///
+    /// Implementors of [`Deserialize`](crate::ser_de::Deserialize) that can
+    /// represent invalid values, like the types in [`lenient`](crate::lenient),
+    /// return them instead of an error in lenient mode.
+    fn is_lenient(&self) -> bool {
+        false
+    }
+
+    /// Check if the input is trusted to be valid.
+    ///
+    /// Implementors of [`Deserialize`](crate::ser_de::Deserialize) may skip
+    /// expensive validation, like verifying checksums, for trusted inputs.
+    /// Invalid inputs may then be deserialized into garbage, but must not lead
+    /// to undefined behavior.
+    fn is_trusted(&self) -> bool {
+        false
+    }
+
     /// Return an error, indicating that deserialization failed.
     ///
     /// This method can be called by implementors of [`Serialize`](crate::ser_de::Serialize)
     /// when an error occurs during serialization.
     fn error<O>(&self, message: &'static str) -> Result<O, Self::Error>;
+
+    /// Return the context value `key`, if it's set.
+    ///
+    /// Context values let objects depend on values deserialized before them,
+    /// possibly by an enclosing object, like the version of a file format
+    /// that's stored in its header. See [`set_context`](Self::set_context).
+    ///
+    /// By default, no context values are set.
+    fn context(&self, key: &str) -> Option<u64> {
+        let _ = key;
+        None
+    }
+
+    /// Set the context value `key` to `value`, or unset it if `value` is
+    /// `None`.
+    ///
+    /// The value is visible to everything deserialized after it in the
+    /// current composite, including nested composites, and the previous value
+    /// is restored when the current composite ends. (See [`deserialize_composite`](Self::deserialize_composite).)
+    ///
+    /// By default, setting context values fails.
+    fn set_context(&mut self, key: &'static str, value: Option<u64>) -> Result<(), Self::Error> {
+        let _ = (key, value);
+        self.error("the deserializer does not support context values")
+    }
+
+    /// Deserialize with `deserialize_fn` while the context value `key` is set
+    /// to `value`.
+    ///
+    /// ```
+    /// # use sorbit::ser_de::Deserializer;
+    /// # use sorbit::stream_ser_de::SliceDeserializer;
+    /// let mut deserializer = SliceDeserializer::from_slice(&[]);
+    /// let version = deserializer.with_context("version", 3, |d| Ok(d.context("version")))?;
+    /// assert_eq!(version, Some(3));
+    /// assert_eq!(deserializer.context("version"), None);
+    /// # Ok::<(), sorbit::error::Error>(())
+    /// ```
+    fn with_context<O>(
+        &mut self,
+        key: &'static str,
+        value: u64,
+        deserialize_fn: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<O, Self::Error> {
+        let previous = self.context(key);
+        self.set_context(key, Some(value))?;
+        let result = deserialize_fn(self);
+        self.set_context(key, previous)?;
+        result
+    }
+
+    /// Return an error, indicating that the deserialized discriminant does not
+    /// correspond to any variant of an enum.
+    fn invalid_enum_variant<O>(&self) -> Result<O, Self::Error> {
+        self.error("the numeric value does not correspond to an enum variant")
+    }
+
+    /// Return an error, indicating that the deserialized value is not valid
+    /// for its type (e.g. zero for a [`NonZeroU32`](core::num::NonZeroU32)),
+    /// or is outside the range of accepted values.
+    fn invalid_value<O>(&self) -> Result<O, Self::Error> {
+        self.error("the value is invalid or outside the accepted range")
+    }
+
+    /// Return an error, indicating that none of the variants of an untagged
+    /// enum could be deserialized.
+    ///
+    /// The `errors` of the variants are passed in the order they were
+    /// attempted, so that implementations can report all of them.
+    fn no_matching_variant<O>(&self, errors: impl IntoIterator<Item = Self::Error>) -> Result<O, Self::Error> {
+        let _ = errors;
+        self.error("the data does not match any variant of the enum")
+    }
+
+    /// Return an error, indicating that the deserialized magic bytes are not
+    /// the `expected` ones.
+    fn bad_magic<O>(&self, expected: &'static [u8], found: &[u8]) -> Result<O, Self::Error> {
+        let _ = (expected, found);
+        self.error("the magic bytes do not match")
+    }
+
+    /// Return an error, indicating that the deserialized composites are
+    /// nested too deep.
+    fn recursion_limit<O>(&self) -> Result<O, Self::Error> {
+        self.error("the data is nested deeper than the recursion limit")
+    }
+
+    /// Return an error, indicating that the length of a collection or a
+    /// string exceeds the [`max_len`](Limits::max_len) of the [`limits`](Self::limits).
+    fn length_limit<O>(&self) -> Result<O, Self::Error> {
+        self.error("the length of the collection exceeds the length limit")
+    }
+}
+
+/// A deserializer that can lend the bytes it reads, without copying them.
+///
+/// Borrowing deserializers read from an in-memory buffer that outlives them,
+/// like [`SliceDeserializer`](crate::stream_ser_de::SliceDeserializer). The
+/// borrowed bytes may be stored in the deserialized object, which makes it
+/// possible to deserialize large payloads without copying them.
+pub trait BorrowDeserializer<'de>: Deserializer {
+    /// Deserialize the next `len` bytes as a slice of the underlying buffer.
+    fn deserialize_borrowed(&mut self, len: usize) -> Result<&'de [u8], Self::Error>;
+}
+
+/// A deserializer that can move backward in the stream.
+///
+/// Seekable deserializers can revisit bytes that have already been
+/// deserialized, which makes it possible to deserialize overlapping fields.
+pub trait SeekableDeserializer: Deserializer {
+    /// Move to `until`, which is interpreted from the beginning of the current
+    /// composite. (See [`deserialize_composite`](Deserializer::deserialize_composite).)
+    ///
+    /// Unlike [`pad`](Deserializer::pad), `until` may precede the current
+    /// position, in which case the stream is rewound.
+    fn seek(&mut self, until: u64) -> Result<(), Self::Error>;
+
+    /// Deserialize with `peek_fn`, then move back to where the deserializer
+    /// was.
+    ///
+    /// The result of `peek_fn` is returned whether it succeeded or not, unless
+    /// moving back fails. This lets [`Deserialize`](crate::ser_de::Deserialize)
+    /// implementations look at a tag before deciding how to deserialize the
+    /// data that follows:
+    ///
+    /// ```
+    /// use sorbit::ser_de::{Deserialize, SeekableDeserializer};
+    ///
+    /// enum Message {
+    ///     Ping(u8),
+    ///     Data([u8; 3]),
+    /// }
+    ///
+    /// fn deserialize_message<D: SeekableDeserializer>(deserializer: &mut D) -> Result<Message, D::Error> {
+    ///     match deserializer.peek(u8::deserialize)? {
+    ///         0 => <[u8; 2]>::deserialize(deserializer).map(|[_, value]| Message::Ping(value)),
+    ///         _ => <[u8; 3]>::deserialize(deserializer).map(Message::Data),
+    ///     }
+    /// }
+    /// # use sorbit::stream_ser_de::SliceDeserializer;
+    /// # let mut deserializer = SliceDeserializer::from_slice(&[1, 2, 3]);
+    /// # assert!(matches!(deserialize_message(&mut deserializer), Ok(Message::Data([1, 2, 3]))));
+    /// ```
+    ///
+    /// The default implementation [`seek`](Self::seek)s back to the position
+    /// where `peek_fn` was called.
+    fn peek<O>(&mut self, peek_fn: impl FnOnce(&mut Self) -> Result<O, Self::Error>) -> Result<O, Self::Error> {
+        let position = self.composite_position();
+        let result = peek_fn(self);
+        self.seek(position)?;
+        result
+    }
+
+    /// Deserialize with `transaction_fn`, and move back to where the
+    /// deserializer was if it fails.
+    ///
+    /// On success, the deserializer stays where `transaction_fn` left it. On
+    /// failure, the error is returned and the deserializer can attempt another
+    /// alternative from the same position. This is useful for formats whose
+    /// versions can only be told apart by trying to parse them:
+    ///
+    /// ```
+    /// use sorbit::ser_de::{Deserialize, SeekableDeserializer};
+    ///
+    /// fn deserialize_version<D: SeekableDeserializer>(deserializer: &mut D) -> Result<u32, D::Error> {
+    ///     deserializer.transaction(u32::deserialize).or_else(|_| u16::deserialize(deserializer).map(u32::from))
+    /// }
+    /// # use sorbit::stream_ser_de::SliceDeserializer;
+    /// # let mut deserializer = SliceDeserializer::from_slice(&[1, 0]);
+    /// # assert!(matches!(deserialize_version(&mut deserializer), Ok(1)));
+    /// ```
+    ///
+    /// The default implementation [`seek`](Self::seek)s back to the position
+    /// where `transaction_fn` was called.
+    fn transaction<O>(
+        &mut self,
+        transaction_fn: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<O, Self::Error> {
+        let position = self.composite_position();
+        let result = transaction_fn(self);
+        if result.is_err() {
+            self.seek(position)?;
+        }
+        result
+    }
+
+    /// Deserialize an object that overlays the bytes starting at `offset`,
+    /// like a member of a C union.
+    ///
+    /// The `offset` is interpreted from the beginning of the current composite.
+    /// Once the object is deserialized, the deserializer is moved back to
+    /// where it was, unless the object extends past that position.
+    fn deserialize_overlay<T>(
+        &mut self,
+        offset: u64,
+        deserialize: impl FnOnce(&mut Self) -> Result<T, Self::Error>,
+    ) -> Result<T, Self::Error> {
+        let position = self.composite_position();
+        self.seek(offset)?;
+        let value = deserialize(self)?;
+        if self.composite_position() < position {
+            self.seek(position)?;
+        }
+        Ok(value)
+    }
+
+    /// Deserialize an object at `position`, then move back to where the
+    /// deserializer was.
+    ///
+    /// Unlike the offset of [`deserialize_overlay`](Self::deserialize_overlay),
+    /// `position` is interpreted from the beginning of the stream, like the
+    /// result of [`position`](Deserializer::position). This is what following
+    /// a pointer or an entry of an offset table takes.
+    ///
+    /// The default implementation [`peek`](Self::peek)s at the object after
+    /// [`seek`](Self::seek)ing to it, and so it can't reach objects that
+    /// precede the current composite.
+    fn deserialize_at<T>(
+        &mut self,
+        position: u64,
+        deserialize: impl FnOnce(&mut Self) -> Result<T, Self::Error>,
+    ) -> Result<T, Self::Error> {
+        let composite_start = self.position() - self.composite_position();
+        let Some(offset) = position.checked_sub(composite_start) else {
+            return self.error("the position precedes the current composite");
+        };
+        self.peek(|deserializer| {
+            deserializer.seek(offset)?;
+            deserialize(deserializer)
+        })
+    }
 }
@@ -0,0 +1,639 @@
+use core::convert::Infallible;
+use core::mem::ManuallyDrop;
+
+use crate::byte_order::ByteOrder;
+use crate::io::Read;
+use crate::ser_de::{RevisableSerializer, Serializer};
+
+/// A cross-cutting concern that is stacked on top of a [`Serializer`].
+///
+/// Layers see every call made to the serializer they wrap, and can inspect,
+/// alter, or reject them before passing them on. This makes it possible to add
+/// byte counting, digesting, tracing, or throttling to any serializer without
+/// writing a full [`Serializer`] implementation for each combination.
+///
+/// All methods pass the call through to the wrapped serializer by default,
+/// so layers only have to implement the methods they are interested in.
+/// Bulk slices, like [`Serializer::serialize_u16_slice`], and variable-length
+/// integers are intercepted as a single call, not value by value.
+/// Layers are applied with [`SerializerExt::with`]:
+///
+/// ```
+/// use sorbit::ser_de::{Layer, Serialize, Serializer, SerializerExt};
+/// use sorbit::stream_ser_de::StreamSerializer;
+/// use sorbit::io::GrowingMemoryStream;
+///
+/// /// Count the number of integers serialized.
+/// struct CountIntegers(usize);
+///
+/// impl<S: Serializer> Layer<S> for CountIntegers {
+///     fn serialize_u16(&mut self, serializer: &mut S, value: u16) -> Result<S::Success, S::Error> {
+///         self.0 += 1;
+///         serializer.serialize_u16(value)
+///     }
+///
+///     fn serialize_u16_slice(&mut self, serializer: &mut S, values: &[u16]) -> Result<S::Success, S::Error> {
+///         self.0 += values.len();
+///         serializer.serialize_u16_slice(values)
+///     }
+/// }
+///
+/// let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).with(CountIntegers(0));
+/// [1u16, 2, 3].serialize(&mut serializer).unwrap();
+/// assert_eq!(serializer.layer().0, 3);
+/// ```
+pub trait Layer<S: Serializer> {
+    /// Intercept [`Serializer::serialize_bool`].
+    fn serialize_bool(&mut self, serializer: &mut S, value: bool) -> Result<S::Success, S::Error> {
+        serializer.serialize_bool(value)
+    }
+
+    /// Intercept [`Serializer::serialize_u8`].
+    fn serialize_u8(&mut self, serializer: &mut S, value: u8) -> Result<S::Success, S::Error> {
+        serializer.serialize_u8(value)
+    }
+
+    /// Intercept [`Serializer::serialize_u16`].
+    fn serialize_u16(&mut self, serializer: &mut S, value: u16) -> Result<S::Success, S::Error> {
+        serializer.serialize_u16(value)
+    }
+
+    /// Intercept [`Serializer::serialize_u32`].
+    fn serialize_u32(&mut self, serializer: &mut S, value: u32) -> Result<S::Success, S::Error> {
+        serializer.serialize_u32(value)
+    }
+
+    /// Intercept [`Serializer::serialize_u64`].
+    fn serialize_u64(&mut self, serializer: &mut S, value: u64) -> Result<S::Success, S::Error> {
+        serializer.serialize_u64(value)
+    }
+
+    /// Intercept [`Serializer::serialize_u128`].
+    fn serialize_u128(&mut self, serializer: &mut S, value: u128) -> Result<S::Success, S::Error> {
+        serializer.serialize_u128(value)
+    }
+
+    /// Intercept [`Serializer::serialize_i8`].
+    fn serialize_i8(&mut self, serializer: &mut S, value: i8) -> Result<S::Success, S::Error> {
+        serializer.serialize_i8(value)
+    }
+
+    /// Intercept [`Serializer::serialize_i16`].
+    fn serialize_i16(&mut self, serializer: &mut S, value: i16) -> Result<S::Success, S::Error> {
+        serializer.serialize_i16(value)
+    }
+
+    /// Intercept [`Serializer::serialize_i32`].
+    fn serialize_i32(&mut self, serializer: &mut S, value: i32) -> Result<S::Success, S::Error> {
+        serializer.serialize_i32(value)
+    }
+
+    /// Intercept [`Serializer::serialize_i64`].
+    fn serialize_i64(&mut self, serializer: &mut S, value: i64) -> Result<S::Success, S::Error> {
+        serializer.serialize_i64(value)
+    }
+
+    /// Intercept [`Serializer::serialize_i128`].
+    fn serialize_i128(&mut self, serializer: &mut S, value: i128) -> Result<S::Success, S::Error> {
+        serializer.serialize_i128(value)
+    }
+
+    /// Intercept [`Serializer::serialize_f32`].
+    fn serialize_f32(&mut self, serializer: &mut S, value: f32) -> Result<S::Success, S::Error> {
+        serializer.serialize_f32(value)
+    }
+
+    /// Intercept [`Serializer::serialize_f64`].
+    fn serialize_f64(&mut self, serializer: &mut S, value: f64) -> Result<S::Success, S::Error> {
+        serializer.serialize_f64(value)
+    }
+
+    /// Intercept [`Serializer::serialize_varint_u64`].
+    fn serialize_varint_u64(&mut self, serializer: &mut S, value: u64) -> Result<S::Success, S::Error> {
+        serializer.serialize_varint_u64(value)
+    }
+
+    /// Intercept [`Serializer::serialize_varint_i64`].
+    fn serialize_varint_i64(&mut self, serializer: &mut S, value: i64) -> Result<S::Success, S::Error> {
+        serializer.serialize_varint_i64(value)
+    }
+
+    /// Intercept [`Serializer::serialize_array`].
+    fn serialize_array<const N: usize>(&mut self, serializer: &mut S, value: &[u8; N]) -> Result<S::Success, S::Error> {
+        serializer.serialize_array(value)
+    }
+
+    /// Intercept [`Serializer::serialize_slice`].
+    fn serialize_slice(&mut self, serializer: &mut S, value: &[u8]) -> Result<S::Success, S::Error> {
+        serializer.serialize_slice(value)
+    }
+
+    /// Intercept [`Serializer::serialize_u16_slice`].
+    fn serialize_u16_slice(&mut self, serializer: &mut S, values: &[u16]) -> Result<S::Success, S::Error> {
+        serializer.serialize_u16_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_u32_slice`].
+    fn serialize_u32_slice(&mut self, serializer: &mut S, values: &[u32]) -> Result<S::Success, S::Error> {
+        serializer.serialize_u32_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_u64_slice`].
+    fn serialize_u64_slice(&mut self, serializer: &mut S, values: &[u64]) -> Result<S::Success, S::Error> {
+        serializer.serialize_u64_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_u128_slice`].
+    fn serialize_u128_slice(&mut self, serializer: &mut S, values: &[u128]) -> Result<S::Success, S::Error> {
+        serializer.serialize_u128_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_i16_slice`].
+    fn serialize_i16_slice(&mut self, serializer: &mut S, values: &[i16]) -> Result<S::Success, S::Error> {
+        serializer.serialize_i16_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_i32_slice`].
+    fn serialize_i32_slice(&mut self, serializer: &mut S, values: &[i32]) -> Result<S::Success, S::Error> {
+        serializer.serialize_i32_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_i64_slice`].
+    fn serialize_i64_slice(&mut self, serializer: &mut S, values: &[i64]) -> Result<S::Success, S::Error> {
+        serializer.serialize_i64_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_i128_slice`].
+    fn serialize_i128_slice(&mut self, serializer: &mut S, values: &[i128]) -> Result<S::Success, S::Error> {
+        serializer.serialize_i128_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_f32_slice`].
+    fn serialize_f32_slice(&mut self, serializer: &mut S, values: &[f32]) -> Result<S::Success, S::Error> {
+        serializer.serialize_f32_slice(values)
+    }
+
+    /// Intercept [`Serializer::serialize_f64_slice`].
+    fn serialize_f64_slice(&mut self, serializer: &mut S, values: &[f64]) -> Result<S::Success, S::Error> {
+        serializer.serialize_f64_slice(values)
+    }
+
+    /// Intercept [`Serializer::pad`].
+    fn pad(&mut self, serializer: &mut S, until: u64) -> Result<S::Success, S::Error> {
+        serializer.pad(until)
+    }
+
+    /// Intercept [`Serializer::align`].
+    fn align(&mut self, serializer: &mut S, multiple_of: u64) -> Result<S::Success, S::Error> {
+        serializer.align(multiple_of)
+    }
+
+    /// Intercept [`Serializer::success`].
+    fn success(&mut self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer.success()
+    }
+
+    /// Intercept [`Serializer::error`].
+    fn error(&mut self, serializer: &mut S, message: &'static str) -> Result<Infallible, S::Error> {
+        serializer.error(message)
+    }
+
+    /// Called before the members of a composite are serialized.
+    ///
+    /// See [`Serializer::serialize_composite`].
+    fn enter_composite(&mut self) {}
+
+    /// Called after the members of a composite are serialized, even if they
+    /// failed to serialize.
+    fn leave_composite(&mut self) {}
+
+    /// Called before the byte order is changed to `byte_order`.
+    ///
+    /// See [`Serializer::with_byte_order`].
+    fn enter_byte_order(&mut self, byte_order: ByteOrder) {
+        let _ = byte_order;
+    }
+
+    /// Called after the previous byte order is restored, even if the members
+    /// failed to serialize.
+    fn leave_byte_order(&mut self) {}
+}
+
+/// Extension methods for [`Serializer`]s.
+pub trait SerializerExt: Serializer + Sized {
+    /// Stack `layer` on top of this serializer.
+    ///
+    /// Layers can be stacked on top of each other, in which case the layer
+    /// applied last sees the calls first.
+    fn with<L: Layer<Self>>(self, layer: L) -> Layered<Self, L> {
+        Layered::new(self, layer)
+    }
+}
+
+impl<S: Serializer> SerializerExt for S {}
+
+/// A [`Serializer`] that passes every call through a [`Layer`] before it
+/// reaches the wrapped serializer.
+///
+/// When the wrapped serializer is a [`RevisableSerializer`], so is the layered
+/// one. Analyzing and revising spans is not intercepted by the layer, but the
+/// calls made while revising a span are.
+pub struct Layered<S, L> {
+    inner: S,
+    layer: L,
+}
+
+impl<S, L> Layered<S, L> {
+    /// Stack `layer` on top of `inner`.
+    pub fn new(inner: S, layer: L) -> Self {
+        Self { inner, layer }
+    }
+
+    /// The wrapped serializer.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// The layer on top of the wrapped serializer.
+    pub fn layer(&self) -> &L {
+        &self.layer
+    }
+
+    /// The layer on top of the wrapped serializer.
+    pub fn layer_mut(&mut self) -> &mut L {
+        &mut self.layer
+    }
+
+    /// Split the layered serializer into the wrapped serializer and the layer.
+    pub fn into_parts(self) -> (S, L) {
+        (self.inner, self.layer)
+    }
+}
+
+macro_rules! intercept {
+    ($($method:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self, value: $ty) -> Result<Self::Success, Self::Error> {
+                self.layer.$method(&mut self.inner, value)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer, L: Layer<S>> Serializer for Layered<S, L> {
+    type Success = S::Success;
+    type Error = S::Error;
+
+    intercept!(
+        serialize_bool: bool,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_varint_u64: u64,
+        serialize_varint_i64: i64,
+        serialize_slice: &[u8],
+        serialize_u16_slice: &[u16],
+        serialize_u32_slice: &[u32],
+        serialize_u64_slice: &[u64],
+        serialize_u128_slice: &[u128],
+        serialize_i16_slice: &[i16],
+        serialize_i32_slice: &[i32],
+        serialize_i64_slice: &[i64],
+        serialize_i128_slice: &[i128],
+        serialize_f32_slice: &[f32],
+        serialize_f64_slice: &[f64],
+        pad: u64,
+        align: u64,
+    );
+
+    fn serialize_array<const N: usize>(&mut self, value: &[u8; N]) -> Result<Self::Success, Self::Error> {
+        self.layer.serialize_array(&mut self.inner, value)
+    }
+
+    fn position(&self) -> u64 {
+        self.inner.position()
+    }
+
+    fn composite_position(&self) -> u64 {
+        self.inner.composite_position()
+    }
+
+    fn is_canonical(&self) -> bool {
+        self.inner.is_canonical()
+    }
+
+    fn context(&self, key: &str) -> Option<u64> {
+        self.inner.context(key)
+    }
+
+    fn set_context(&mut self, key: &'static str, value: Option<u64>) -> Result<(), Self::Error> {
+        self.inner.set_context(key, value)
+    }
+
+    fn serialize_composite<Output>(
+        &mut self,
+        serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<(Self::Success, Output), Self::Error> {
+        let Self { inner, layer } = self;
+        layer.enter_composite();
+        let result = inner.serialize_composite(|inner| reassemble(inner, layer, serialize_members));
+        self.layer.leave_composite();
+        result
+    }
+
+    fn with_byte_order<Output>(
+        &mut self,
+        byte_order: ByteOrder,
+        serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let Self { inner, layer } = self;
+        layer.enter_byte_order(byte_order);
+        let result = inner.with_byte_order(byte_order, |inner| reassemble(inner, layer, serialize_members));
+        self.layer.leave_byte_order();
+        result
+    }
+
+    fn with_fill<Output>(
+        &mut self,
+        fill: u8,
+        serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let Self { inner, layer } = self;
+        inner.with_fill(fill, |inner| reassemble(inner, layer, serialize_members))
+    }
+
+    fn success(&mut self) -> Result<Self::Success, Self::Error> {
+        self.layer.success(&mut self.inner)
+    }
+
+    fn error(&mut self, message: &'static str) -> Result<Infallible, Self::Error> {
+        self.layer.error(&mut self.inner, message)
+    }
+}
+
+impl<S: RevisableSerializer, L: Layer<S>> RevisableSerializer for Layered<S, L> {
+    fn analyze_span<Output, Error, AnalyzeSpanFn>(
+        &mut self,
+        span: &Self::Success,
+        analyze_span_fn: AnalyzeSpanFn,
+    ) -> Result<Output, Self::Error>
+    where
+        AnalyzeSpanFn: for<'analyze> FnOnce(&mut dyn Read) -> Result<Output, Error>,
+        Error: Into<Self::Error>,
+    {
+        self.inner.analyze_span(span, analyze_span_fn)
+    }
+
+    fn revise_span<Output>(
+        &mut self,
+        span: &Self::Success,
+        serialize_span: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let Self { inner, layer } = self;
+        inner.revise_span(span, |inner| reassemble(inner, layer, serialize_span))
+    }
+
+    fn serialize_at<Output>(
+        &mut self,
+        offset: u64,
+        serialize: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let Self { inner, layer } = self;
+        inner.serialize_at(offset, |inner| reassemble(inner, layer, serialize))
+    }
+}
+
+/// Call `f` with a [`Layered`] serializer assembled from `inner` and `layer`.
+///
+/// The callbacks of the wrapped serializer receive the wrapped serializer
+/// only, so the layered serializer has to be put together again for the
+/// callbacks of the layered serializer. The callbacks take `&mut Self`, so
+/// the [`Layered`] cannot hold references to `inner` and `layer` instead of
+/// the values: a `Layered` of shorter-lived references is a different type
+/// than `Self`. The values are therefore moved into a temporary [`Layered`],
+/// and moved back when `f` returns or panics.
+fn reassemble<S, L, Output>(inner: &mut S, layer: &mut L, f: impl FnOnce(&mut Layered<S, L>) -> Output) -> Output {
+    struct Restore<'a, S, L> {
+        inner: &'a mut S,
+        layer: &'a mut L,
+        layered: ManuallyDrop<Layered<S, L>>,
+    }
+
+    impl<S, L> Drop for Restore<'_, S, L> {
+        fn drop(&mut self) {
+            // SAFETY: `layered` holds the only live copies of the values, which
+            // were moved out of `inner` and `layer`. `inner` and `layer` have
+            // not been accessed since, as `Restore` borrows them mutably for
+            // its entire lifetime, and `f` only has access to `layered`. This
+            // runs exactly once, both when `f` returns and when it panics, and
+            // `layered` is never dropped, so the values are neither duplicated
+            // nor dropped twice. Reading and writing the values cannot panic,
+            // so `inner` and `layer` are always valid when `Restore` is gone.
+            unsafe {
+                core::ptr::write(self.inner, core::ptr::read(&self.layered.inner));
+                core::ptr::write(self.layer, core::ptr::read(&self.layered.layer));
+            }
+        }
+    }
+
+    // SAFETY: the values are duplicated bitwise, and the originals behind
+    // `inner` and `layer` are considered moved-from until `Restore` writes the
+    // copies back. Nothing can observe the originals in the meantime, because
+    // `inner` and `layer` are moved into `Restore` before `f` is called, and
+    // nothing between the reads and the construction of `Restore` can panic.
+    let layered = unsafe { Layered { inner: core::ptr::read(inner), layer: core::ptr::read(layer) } };
+    let mut restore = Restore { inner, layer, layered: ManuallyDrop::new(layered) };
+    f(&mut restore.layered)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::error::{Error, ErrorKind, TraceError as _};
+    use crate::io::GrowingMemoryStream;
+    use crate::ser_de::{Serialize, Span};
+    use crate::stream_ser_de::StreamSerializer;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Event {
+        U8(u8),
+        U16(u16),
+        VarintU64(u64),
+        U16Slice(usize),
+        Pad(u64),
+        EnterComposite,
+        LeaveComposite,
+        EnterByteOrder(ByteOrder),
+        LeaveByteOrder,
+    }
+
+    #[derive(Default)]
+    struct Trace(Vec<Event>);
+
+    impl<S: Serializer> Layer<S> for Trace {
+        fn serialize_u8(&mut self, serializer: &mut S, value: u8) -> Result<S::Success, S::Error> {
+            self.0.push(Event::U8(value));
+            serializer.serialize_u8(value)
+        }
+
+        fn serialize_u16(&mut self, serializer: &mut S, value: u16) -> Result<S::Success, S::Error> {
+            self.0.push(Event::U16(value));
+            serializer.serialize_u16(value)
+        }
+
+        fn serialize_varint_u64(&mut self, serializer: &mut S, value: u64) -> Result<S::Success, S::Error> {
+            self.0.push(Event::VarintU64(value));
+            serializer.serialize_varint_u64(value)
+        }
+
+        fn serialize_u16_slice(&mut self, serializer: &mut S, values: &[u16]) -> Result<S::Success, S::Error> {
+            self.0.push(Event::U16Slice(values.len()));
+            serializer.serialize_u16_slice(values)
+        }
+
+        fn pad(&mut self, serializer: &mut S, until: u64) -> Result<S::Success, S::Error> {
+            self.0.push(Event::Pad(until));
+            serializer.pad(until)
+        }
+
+        fn enter_composite(&mut self) {
+            self.0.push(Event::EnterComposite);
+        }
+
+        fn leave_composite(&mut self) {
+            self.0.push(Event::LeaveComposite);
+        }
+
+        fn enter_byte_order(&mut self, byte_order: ByteOrder) {
+            self.0.push(Event::EnterByteOrder(byte_order));
+        }
+
+        fn leave_byte_order(&mut self) {
+            self.0.push(Event::LeaveByteOrder);
+        }
+    }
+
+    struct Reject;
+
+    impl<S: Serializer> Layer<S> for Reject {
+        fn serialize_u16(&mut self, serializer: &mut S, _value: u16) -> Result<S::Success, S::Error> {
+            serializer.error("rejected")?;
+            unreachable!()
+        }
+    }
+
+    struct Double;
+
+    impl<S: Serializer> Layer<S> for Double {
+        fn serialize_u8(&mut self, serializer: &mut S, value: u8) -> Result<S::Success, S::Error> {
+            serializer.serialize_u8(value.wrapping_mul(2))
+        }
+    }
+
+    fn serialize_nested<S: Serializer>(serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer
+            .serialize_composite(|serializer| {
+                serializer.serialize_u8(1)?;
+                serializer.with_byte_order(ByteOrder::BigEndian, |serializer| serializer.serialize_u16(0x0203))?;
+                serializer.pad(4)
+            })
+            .map(|(span, _)| span)
+    }
+
+    #[test]
+    fn pass_through() {
+        struct Nothing;
+        impl<S: Serializer> Layer<S> for Nothing {}
+
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).with(Nothing);
+        let span = serialize_nested(&mut serializer).unwrap();
+        assert_eq!((span.start(), span.end()), (0, 4));
+        assert_eq!(serializer.into_parts().0.take().take(), vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn intercept() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).with(Trace::default());
+        serialize_nested(&mut serializer).unwrap();
+        let expected = [
+            Event::EnterComposite,
+            Event::U8(1),
+            Event::EnterByteOrder(ByteOrder::BigEndian),
+            Event::U16(0x0203),
+            Event::LeaveByteOrder,
+            Event::Pad(4),
+            Event::LeaveComposite,
+        ];
+        assert_eq!(serializer.layer().0, expected);
+    }
+
+    #[test]
+    fn intercept_bulk() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).with(Trace::default());
+        serializer.serialize_varint_u64(300).unwrap();
+        serializer.serialize_u16_slice(&[0x0102, 0x0304]).unwrap();
+        assert_eq!(serializer.layer().0, [Event::VarintU64(300), Event::U16Slice(2)]);
+        assert_eq!(serializer.into_parts().0.take().take(), vec![0xAC, 0x02, 0x02, 0x01, 0x04, 0x03]);
+    }
+
+    #[test]
+    fn stacked() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).with(Double).with(Trace::default());
+        serialize_nested(&mut serializer).unwrap();
+        assert_eq!(serializer.layer().0[1], Event::U8(1));
+        assert_eq!(serializer.into_parts().0.into_parts().0.take().take(), vec![2, 2, 3, 0]);
+    }
+
+    #[test]
+    fn canonical() {
+        let serializer =
+            StreamSerializer::new(GrowingMemoryStream::new()).canonical().with(Double).with(Trace::default());
+        assert!(serializer.is_canonical());
+    }
+
+    #[test]
+    fn reject() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).with(Reject).with(Trace::default());
+        let result = serialize_nested(&mut serializer);
+        assert_eq!(result, Err(Error::from(ErrorKind::Custom("rejected")).locate(0)));
+        assert_eq!(serializer.layer().0.last(), Some(&Event::LeaveComposite));
+    }
+
+    #[test]
+    fn panic_in_composite() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).with(Trace::default());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = serializer.serialize_composite(|serializer| -> Result<(), Error> {
+                serializer.serialize_u8(1)?;
+                panic!("members failed")
+            });
+        }));
+        assert!(result.is_err());
+        assert_eq!(serializer.layer().0, [Event::EnterComposite, Event::U8(1)]);
+        serializer.serialize_u8(2).unwrap();
+        serialize_nested(&mut serializer).unwrap();
+        assert_eq!(serializer.layer().0[..3], [Event::EnterComposite, Event::U8(1), Event::U8(2)]);
+        assert_eq!(serializer.into_parts().0.take().take(), vec![1, 2, 1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn revise() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).with(Trace::default());
+        let span = 0u8.serialize(&mut serializer).unwrap();
+        serializer.revise_span(&span, |serializer| 7u8.serialize(serializer)).unwrap();
+        assert_eq!(serializer.layer().0, [Event::U8(0), Event::U8(7)]);
+        assert_eq!(serializer.into_parts().0.take().take(), vec![7]);
+    }
+}
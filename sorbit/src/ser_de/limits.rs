@@ -0,0 +1,100 @@
+/// Limits on the resources a [`Deserializer`](super::Deserializer) may use.
+///
+/// Deserialized data often contains lengths that are used to size the
+/// deserialized objects. When the data is not trusted, a forged length could
+/// make the deserializer allocate huge amounts of memory before it notices that
+/// the data is not there. Similarly, deeply nested data could exhaust the
+/// stack when deserializing recursive types, and endless data could keep the
+/// deserializer busy forever. The limits keep these in check, which is
+/// especially useful when fuzzing.
+///
+/// New limits may be added in the future, so start from
+/// [`DEFAULT`](Self::DEFAULT) and change the limits with the `with_*` methods:
+///
+/// ```
+/// # use sorbit::ser_de::Limits;
+/// const LIMITS: Limits = Limits::DEFAULT.with_max_depth(16).with_max_len(1024);
+/// assert_eq!(LIMITS.max_depth, 16);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Limits {
+    /// The maximum number of bytes reserved for the items of a collection
+    /// before the items are actually deserialized.
+    ///
+    /// Collections can still grow past this size as the items are deserialized,
+    /// this only limits pre-allocation. When the input is trusted, set it to
+    /// [`usize::MAX`] to allocate the full capacity of collections up front.
+    pub max_preallocation: usize,
+    /// The maximum number of composites that may be nested within each other.
+    ///
+    /// Exceeding the depth results in [`ErrorKind::RecursionLimit`](crate::error::ErrorKind::RecursionLimit).
+    pub max_depth: u32,
+    /// The maximum number of bytes that may be read in total.
+    ///
+    /// Exceeding it results in [`ErrorKind::ByteLimit`](crate::error::ErrorKind::ByteLimit).
+    pub max_bytes: u64,
+    /// The maximum number of items in a collection, or bytes in a string.
+    ///
+    /// The length is checked before any item is deserialized. Exceeding it
+    /// results in [`ErrorKind::LengthLimit`](crate::error::ErrorKind::LengthLimit).
+    pub max_len: usize,
+}
+
+impl Limits {
+    /// The default limits.
+    ///
+    /// Only pre-allocation and nesting are limited by default, the total
+    /// number of bytes and the lengths of collections are unlimited.
+    pub const DEFAULT: Self =
+        Self { max_preallocation: 64 * 1024, max_depth: 128, max_bytes: u64::MAX, max_len: usize::MAX };
+
+    /// Change the [`max_preallocation`](Self::max_preallocation).
+    pub const fn with_max_preallocation(self, max_preallocation: usize) -> Self {
+        Self { max_preallocation, ..self }
+    }
+
+    /// Change the [`max_depth`](Self::max_depth).
+    pub const fn with_max_depth(self, max_depth: u32) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Change the [`max_bytes`](Self::max_bytes).
+    pub const fn with_max_bytes(self, max_bytes: u64) -> Self {
+        Self { max_bytes, ..self }
+    }
+
+    /// Change the [`max_len`](Self::max_len).
+    pub const fn with_max_len(self, max_len: usize) -> Self {
+        Self { max_len, ..self }
+    }
+
+    /// The number of items of type `Item` that may be pre-allocated for a
+    /// collection of `len` items.
+    pub const fn preallocated_items<Item>(&self, len: usize) -> usize {
+        let max_items = match size_of::<Item>() {
+            0 => usize::MAX,
+            item_size => self.max_preallocation / item_size,
+        };
+        if len < max_items { len } else { max_items }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preallocated_items() {
+        let limits = Limits::DEFAULT.with_max_preallocation(16);
+        assert_eq!(limits.preallocated_items::<u32>(2), 2);
+        assert_eq!(limits.preallocated_items::<u32>(100), 4);
+        assert_eq!(limits.preallocated_items::<()>(100), 100);
+    }
+}
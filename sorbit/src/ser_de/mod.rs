@@ -3,11 +3,17 @@
 mod byte_conv;
 mod deserialize;
 mod deserializer;
+mod layer;
+mod limits;
 mod serialize;
 mod serializer;
 
 pub use byte_conv::{FromBytes, ToBytes};
-pub use deserialize::Deserialize;
-pub use deserializer::Deserializer;
-pub use serialize::{MultiPassSerialize, Serialize};
-pub use serializer::{RevisableSerializer, Serializer, Span};
+#[cfg(feature = "alloc")]
+pub use deserialize::DeserializeBoxed;
+pub use deserialize::{Deserialize, DeserializeBorrowed, MultiPassDeserialize};
+pub use deserializer::{BorrowDeserializer, Deserializer, SeekableDeserializer};
+pub use layer::{Layer, Layered, SerializerExt};
+pub use limits::Limits;
+pub use serialize::{DeferredSerialize, MultiPassSerialize, Serialize};
+pub use serializer::{MaybeSpan, RevisableSerializer, Serializer, Span};
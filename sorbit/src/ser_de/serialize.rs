@@ -15,6 +15,21 @@ pub trait Serialize {
     /// In case of a failure, it's up to the `serializer` to roll back partial
     /// changes or to enter an indeterminate state.
     fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error>;
+
+    /// Try to serialize the `items` one after the other.
+    ///
+    /// Arrays use this to serialize their elements. The default implementation
+    /// serializes the items one by one, but primitive numbers override it to
+    /// serialize all of them in bulk, like with [`Serializer::serialize_u32_slice`].
+    fn serialize_many<S: Serializer>(items: &[Self], serializer: &mut S) -> Result<(), S::Error>
+    where
+        Self: Sized,
+    {
+        for item in items {
+            item.serialize(serializer)?;
+        }
+        Ok(())
+    }
 }
 
 /// The type requires multiple passes during serialization and can be
@@ -29,3 +44,31 @@ pub trait MultiPassSerialize {
     /// See [`Serialize::serialize`] for more information.
     fn serialize<S: RevisableSerializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error>;
 }
+
+/// The type's final value depends on where its siblings ended up in the
+/// stream, and is written in a second pass over an already serialized field.
+///
+/// When a field is marked with `#[sorbit(deferred)]`, the derive macro first
+/// serializes it as usual using [`Serialize`] or [`MultiPassSerialize`], which
+/// acts as a placeholder. Once all fields of the struct are serialized, the
+/// placeholder's span is revised by calling [`DeferredSerialize::serialize_deferred`]
+/// with the sections of all fields of the struct. Deferred fields are revised
+/// in declaration order, after the `byte_count` fields have been updated.
+/// Checksums, offsets into the struct, and similar values are typical uses.
+/// Fields marked with `#[sorbit(offset_of = payload)]` are revised in the same
+/// pass, without implementing this trait.
+///
+/// The deferred value must serialize into exactly as many bytes as the
+/// placeholder did.
+pub trait DeferredSerialize {
+    /// Try to serialize the final value of this object into the `serializer`.
+    ///
+    /// The `sections` contain the span of every field of the enclosing struct
+    /// in declaration order, with members of the same bit field sharing a
+    /// single span. The section of the deferred field itself is included.
+    fn serialize_deferred<S: RevisableSerializer>(
+        &self,
+        serializer: &mut S,
+        sections: &[&S::Success],
+    ) -> Result<S::Success, S::Error>;
+}
@@ -9,6 +9,7 @@ use crate::io::Read;
 ///
 /// For example, for the [IPv4 header](https://en.wikipedia.org/wiki/IPv4#Header),
 /// the section that belongs to *Time to Live* would be bytes 8 to 9 (non-inclusive).
+#[allow(clippy::len_without_is_empty)]
 pub trait Span {
     /// Return the length of the span in bytes.
     fn len(&self) -> u64;
@@ -18,11 +19,76 @@ pub trait Span {
     fn end(&self) -> u64;
 }
 
+/// The result of a successful serialization that may or may not record the
+/// [`Span`] of the serialized object.
+///
+/// Single-pass serializers have no use for spans and may succeed with `()`,
+/// while [`RevisableSerializer`]s always succeed with a [`Span`]. Generic code
+/// over any [`Serializer`] can use this trait to inspect the span when there
+/// is one:
+///
+/// ```
+/// use sorbit::ser_de::{MaybeSpan, Serialize, Serializer};
+///
+/// fn serialized_len<S: Serializer, T: Serialize>(serializer: &mut S, value: &T) -> Result<Option<u64>, S::Error> {
+///     let success = value.serialize(serializer)?;
+///     Ok(success.as_span().map(|span| span.len()))
+/// }
+/// ```
+pub trait MaybeSpan {
+    /// Return the span of the serialized object, if the serializer records it.
+    fn as_span(&self) -> Option<&dyn Span>;
+}
+
+impl<T: Span> MaybeSpan for T {
+    fn as_span(&self) -> Option<&dyn Span> {
+        Some(self)
+    }
+}
+
+impl MaybeSpan for () {
+    fn as_span(&self) -> Option<&dyn Span> {
+        None
+    }
+}
+
 /// Serializers can transform primitive types into a stream of bytes that can
 /// be sent over the network or stored in files.
+///
+/// Serializers are always passed around by mutable reference. Helper functions
+/// should take `&mut S` where `S: Serializer` rather than `impl Serializer` by
+/// value, so that they compose with [`Serialize`](super::Serialize) and the
+/// derived implementations:
+///
+/// ```
+/// use sorbit::ser_de::{Serialize, Serializer};
+///
+/// fn serialize_header<S: Serializer>(serializer: &mut S, version: u8) -> Result<S::Success, S::Error> {
+///     serializer.serialize_u8(0xA5)?;
+///     serializer.serialize_u8(version)
+/// }
+///
+/// struct Message(u32);
+///
+/// impl Serialize for Message {
+///     fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+///         serializer
+///             .serialize_composite(|serializer| {
+///                 serialize_header(serializer, 1)?;
+///                 self.0.serialize(serializer)
+///             })
+///             .map(|(span, _)| span)
+///     }
+/// }
+/// ```
+///
+/// `&mut S` itself does not implement [`Serializer`]: methods like
+/// [`serialize_composite`](Self::serialize_composite) hand a `&mut Self` to
+/// their callbacks, which can't be created for `Self = &mut S` from the
+/// shorter-lived reborrow of `S` that the callbacks receive.
 pub trait Serializer {
     /// The type a [`Serializer`] returns if serialization succeeded.
-    type Success;
+    type Success: MaybeSpan;
     /// The type a [`Serializer`] returns if serialization failed.
     type Error: TraceError + MessageError + From<BitError>;
 
@@ -59,6 +125,43 @@ pub trait Serializer {
     /// Serialize an [`i128`] value according to the current byte order.
     fn serialize_i128(&mut self, value: i128) -> Result<Self::Success, Self::Error>;
 
+    /// Serialize an [`f32`] value according to the current byte order.
+    fn serialize_f32(&mut self, value: f32) -> Result<Self::Success, Self::Error>;
+
+    /// Serialize an [`f64`] value according to the current byte order.
+    fn serialize_f64(&mut self, value: f64) -> Result<Self::Success, Self::Error>;
+
+    /// Serialize an [`u64`] value as a variable-length LEB128 integer.
+    ///
+    /// Each byte holds seven bits of the value, starting with the least
+    /// significant ones, and its most significant bit is set if more bytes
+    /// follow. The byte order does not apply. The default implementation
+    /// serializes the bytes as a slice.
+    fn serialize_varint_u64(&mut self, value: u64) -> Result<Self::Success, Self::Error> {
+        let mut bytes = [0u8; 10];
+        let mut len = 0;
+        let mut rest = value;
+        loop {
+            bytes[len] = (rest & 0x7F) as u8;
+            rest >>= 7;
+            len += 1;
+            match rest {
+                0 => break,
+                _ => bytes[len - 1] |= 0x80,
+            }
+        }
+        self.serialize_slice(&bytes[..len])
+    }
+
+    /// Serialize an [`i64`] value as a zig-zag encoded variable-length integer.
+    ///
+    /// Zig-zag encoding maps values of small magnitude to small unsigned
+    /// values (`0, -1, 1, -2, ...` to `0, 1, 2, 3, ...`), which are then
+    /// serialized as per [`serialize_varint_u64`](Self::serialize_varint_u64).
+    fn serialize_varint_i64(&mut self, value: i64) -> Result<Self::Success, Self::Error> {
+        self.serialize_varint_u64(((value << 1) ^ (value >> 63)) as u64)
+    }
+
     /// Serialize an [`u8`] array.
     ///
     /// The size of the array should **not** be stored in the byte stream
@@ -75,8 +178,160 @@ pub trait Serializer {
     /// serialized data structure's specification.
     fn serialize_slice(&mut self, value: &[u8]) -> Result<Self::Success, Self::Error>;
 
-    /// Pad with zeros up to `until`, which is interpreted from the beginning
-    /// of the current composite. (See [`serialize_composite`](Self::serialize_composite).)
+    /// Serialize a slice of [`u16`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_u16_slice(&mut self, values: &[u16]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_u16(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`u32`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_u32_slice(&mut self, values: &[u32]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_u32(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`u64`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_u64_slice(&mut self, values: &[u64]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_u64(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`u128`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_u128_slice(&mut self, values: &[u128]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_u128(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`i16`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_i16_slice(&mut self, values: &[i16]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_i16(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`i32`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_i32_slice(&mut self, values: &[i32]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_i32(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`i64`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_i64_slice(&mut self, values: &[i64]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_i64(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`i128`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_i128_slice(&mut self, values: &[i128]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_i128(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`f32`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_f32_slice(&mut self, values: &[f32]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_f32(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Serialize a slice of [`f64`] values according to the current byte order.
+    ///
+    /// Like [`serialize_slice`](Self::serialize_slice), the length is **not**
+    /// serialized. The default implementation serializes the values one by one
+    /// in a composite, but serializers may write them in bulk.
+    fn serialize_f64_slice(&mut self, values: &[f64]) -> Result<Self::Success, Self::Error> {
+        self.serialize_composite(|serializer| {
+            for value in values {
+                serializer.serialize_f64(*value)?;
+            }
+            serializer.success()
+        })
+        .map(|(span, _)| span)
+    }
+
+    /// Pad with the fill byte up to `until`, which is interpreted from the
+    /// beginning of the current composite. (See [`serialize_composite`](Self::serialize_composite).)
+    ///
+    /// The fill byte is zero, unless changed by [`with_fill`](Self::with_fill).
     ///
     /// ## Errors
     ///
@@ -84,10 +339,83 @@ pub trait Serializer {
     /// returned.
     fn pad(&mut self, until: u64) -> Result<Self::Success, Self::Error>;
 
-    /// Pad with zeros so that the size of the current composite becomes a
-    /// multiple of `multiple_of`. (See [`serialize_composite`](Self::serialize_composite).)
+    /// Pad with the fill byte so that the size of the current composite becomes
+    /// a multiple of `multiple_of`. (See [`serialize_composite`](Self::serialize_composite).)
     fn align(&mut self, multiple_of: u64) -> Result<Self::Success, Self::Error>;
 
+    /// Pad with the fill byte so that the [`position`](Self::position) in the
+    /// stream becomes a multiple of `multiple_of`.
+    ///
+    /// Unlike [`align`](Self::align), the alignment doesn't depend on where
+    /// the current composite begins.
+    fn align_absolute(&mut self, multiple_of: u64) -> Result<Self::Success, Self::Error> {
+        let padding = self.position().div_ceil(multiple_of) * multiple_of - self.position();
+        self.pad(self.composite_position() + padding)
+    }
+
+    /// Return the current position in the stream.
+    ///
+    /// This is where the next object will be serialized, and it's in the same
+    /// coordinates as the [`Span`]s returned by revisable serializers.
+    fn position(&self) -> u64;
+
+    /// Return the current position relative to the beginning of the current
+    /// composite. (See [`serialize_composite`](Self::serialize_composite).)
+    ///
+    /// This is the position [`pad`](Self::pad) and [`align`](Self::align)
+    /// are interpreted against.
+    fn composite_position(&self) -> u64;
+
+    /// Return the context value `key`, if it's set.
+    ///
+    /// Context values let objects depend on values serialized before them,
+    /// possibly by an enclosing object, like the version of a file format
+    /// that's stored in its header. See [`set_context`](Self::set_context).
+    ///
+    /// By default, no context values are set.
+    fn context(&self, key: &str) -> Option<u64> {
+        let _ = key;
+        None
+    }
+
+    /// Set the context value `key` to `value`, or unset it if `value` is
+    /// `None`.
+    ///
+    /// The value is visible to everything serialized after it in the current
+    /// composite, including nested composites, and the previous value is
+    /// restored when the current composite ends. (See [`serialize_composite`](Self::serialize_composite).)
+    ///
+    /// By default, setting context values fails.
+    fn set_context(&mut self, key: &'static str, value: Option<u64>) -> Result<(), Self::Error> {
+        let _ = (key, value);
+        self.error("the serializer does not support context values").map(|never| match never {})
+    }
+
+    /// Serialize with `serialize_fn` while the context value `key` is set to
+    /// `value`.
+    fn with_context<Output>(
+        &mut self,
+        key: &'static str,
+        value: u64,
+        serialize_fn: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let previous = self.context(key);
+        self.set_context(key, Some(value))?;
+        let result = serialize_fn(self);
+        self.set_context(key, previous)?;
+        result
+    }
+
+    /// Check if the serializer produces canonical output.
+    ///
+    /// Canonical serializers produce identical bytes for equal values.
+    /// [`Serialize`](crate::ser_de::Serialize) implementations that have
+    /// multiple encodings for the same value should pick a fixed one, like
+    /// iterating unordered collections in sorted order, or fail if they can't. By default, serializers are not canonical.
+    fn is_canonical(&self) -> bool {
+        false
+    }
+
     /// Serialize a composite object (e.g. a struct).
     ///
     /// This does not affect the underlying stream and serves only as a marker
@@ -110,13 +438,31 @@ pub trait Serializer {
     /// Temporarily change the byte order.
     ///
     /// All items serialized in the `serialize_members` function will use the
-    /// selected byte order. This call can be nested as necessary.
+    /// selected byte order. This call can be nested as necessary, in which
+    /// case the innermost call determines the byte order.
+    ///
+    /// The previous byte order is restored when `serialize_members` returns,
+    /// regardless of whether it succeeded. Byte order changes are independent
+    /// of composites: entering a composite keeps the current byte order, and
+    /// changing the byte order does not start a new composite.
     fn with_byte_order<Output>(
         &mut self,
         byte_order: ByteOrder,
         serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
     ) -> Result<Output, Self::Error>;
 
+    /// Temporarily change the byte that [`pad`](Self::pad) and [`align`](Self::align)
+    /// fill with.
+    ///
+    /// The semantics are the same as for [`with_byte_order`](Self::with_byte_order):
+    /// the innermost call determines the fill byte, and the previous fill
+    /// byte is restored when `serialize_members` returns.
+    fn with_fill<Output>(
+        &mut self,
+        fill: u8,
+        serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error>;
+
     /// Return [`Ok`].
     ///
     /// Use this to exit serialization with a success when you don't have any
@@ -173,4 +519,21 @@ pub trait RevisableSerializer: Serializer<Success: Span> {
         span: &Self::Success,
         serialize_span: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
     ) -> Result<Output, Self::Error>;
+
+    /// Serialize an object at `offset`, which is interpreted from the
+    /// beginning of the current composite. (See [`serialize_composite`](Serializer::serialize_composite).)
+    ///
+    /// Unlike [`pad`](Serializer::pad), `offset` may precede the current
+    /// position, in which case the bytes written there are overwritten, and
+    /// the serializer is moved back to where it was, unless the object extends
+    /// past that position. When `offset` is past the current position, the
+    /// hole is padded.
+    ///
+    /// This function can be used to serialize the fields of a structure in a
+    /// different order than they are laid out in the stream.
+    fn serialize_at<Output>(
+        &mut self,
+        offset: u64,
+        serialize: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error>;
 }
@@ -0,0 +1,169 @@
+//! Utilities for objects that are preceded by their size in bytes.
+//!
+//! Many formats store the size of a record or a chunk right before it, so
+//! that readers can skip the parts they don't understand. With the derive
+//! macros, both structures and fields can be size-prefixed:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::ser_de::{FromBytes, ToBytes};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! #[sorbit(byte_order=big_endian, size_prefix=u16, includes_prefix=false)]
+//! struct Chunk {
+//!     kind: u8,
+//!     value: u32,
+//! }
+//!
+//! let chunk = Chunk { kind: 1, value: 0xDEADBEEF };
+//! let bytes = [0x00, 0x05, 0x01, 0xDE, 0xAD, 0xBE, 0xEF];
+//! assert_eq!(chunk.to_bytes().unwrap(), bytes);
+//! assert_eq!(Chunk::from_bytes(&bytes).unwrap(), chunk);
+//! ```
+//!
+//! Serializing a size-prefixed object requires a
+//! [`RevisableSerializer`], because the size is only known after the object
+//! has been serialized.
+
+use crate::layout::StaticLayout;
+use crate::ser_de::{Deserialize, Deserializer, RevisableSerializer, Serialize, Span};
+
+/// Serialize an object preceded by its size in bytes.
+///
+/// A placeholder is serialized for the size first, which is then revised once
+/// the object is serialized and its size is known. When `includes_prefix` is
+/// set, the size accounts for the size prefix itself as well.
+///
+/// If the size can not be represented by `Size`, an error is returned.
+pub fn serialize_size_prefixed<Size, S>(
+    serializer: &mut S,
+    includes_prefix: bool,
+    serialize_object: impl FnOnce(&mut S) -> Result<S::Success, S::Error>,
+) -> Result<S::Success, S::Error>
+where
+    Size: Serialize + Default + TryFrom<u64>,
+    S: RevisableSerializer,
+{
+    serializer
+        .serialize_composite(|serializer| {
+            let prefix = Serialize::serialize(&Size::default(), serializer)?;
+            let object = serialize_object(serializer)?;
+            let byte_count = match includes_prefix {
+                true => prefix.len() + object.len(),
+                false => object.len(),
+            };
+            let Ok(size) = Size::try_from(byte_count) else {
+                return Err(serializer
+                    .error("the size of the object is too large for its binary representation")
+                    .unwrap_err());
+            };
+            serializer.revise_span(&prefix, |serializer| Serialize::serialize(&size, serializer))
+        })
+        .map(|(span, _)| span)
+}
+
+/// Deserialize an object preceded by its size in bytes.
+///
+/// The object is deserialized within the bounds given by the size. If the
+/// object is shorter than its size, the remaining bytes are skipped. When
+/// `includes_prefix` is set, the size is expected to account for the size
+/// prefix itself as well.
+pub fn deserialize_size_prefixed<Size, D, O>(
+    deserializer: &mut D,
+    includes_prefix: bool,
+    deserialize_object: impl FnOnce(&mut D) -> Result<O, D::Error>,
+) -> Result<O, D::Error>
+where
+    Size: Deserialize + StaticLayout,
+    u64: TryFrom<Size>,
+    D: Deserializer,
+{
+    let size = Size::deserialize(deserializer)?;
+    let Ok(size) = u64::try_from(size) else {
+        return deserializer.error("the size of the object is out of range");
+    };
+    let byte_count = match includes_prefix {
+        true => Size::LAYOUT.size().and_then(|prefix| size.checked_sub(prefix)),
+        false => Some(size),
+    };
+    let Some(byte_count) = byte_count else {
+        return deserializer.error("the size of the object is smaller than its size prefix");
+    };
+    deserializer.deserialize_bounded(byte_count, |deserializer| {
+        deserializer.deserialize_composite(|deserializer| {
+            let object = deserialize_object(deserializer)?;
+            deserializer.pad(byte_count)?;
+            Ok(object)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::byte_order::ByteOrder;
+    use crate::error::{Error, ErrorKind, TraceError as _};
+    use crate::io::GrowingMemoryStream;
+    use crate::ser_de::Serializer;
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    fn serialize(includes_prefix: bool) -> Result<Vec<u8>, Error> {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        serialize_size_prefixed::<u16, _>(&mut serializer, includes_prefix, |serializer| {
+            serializer.serialize_array(&[0xAA, 0xBB, 0xCC])
+        })?;
+        Ok(serializer.take().take())
+    }
+
+    fn deserialize(bytes: &[u8], includes_prefix: bool) -> Result<(u8, u8), Error> {
+        let mut deserializer =
+            StreamDeserializer::new(GrowingMemoryStream::from(bytes)).change_byte_order(ByteOrder::BigEndian);
+        let object = deserialize_size_prefixed::<u16, _, _>(&mut deserializer, includes_prefix, |deserializer| {
+            deserializer.deserialize_u8()
+        })?;
+        Ok((object, deserializer.deserialize_u8()?))
+    }
+
+    #[test]
+    fn serialize_excludes_prefix() {
+        assert_eq!(serialize(false), Ok(vec![0x00, 0x03, 0xAA, 0xBB, 0xCC]));
+    }
+
+    #[test]
+    fn serialize_includes_prefix() {
+        assert_eq!(serialize(true), Ok(vec![0x00, 0x05, 0xAA, 0xBB, 0xCC]));
+    }
+
+    #[test]
+    fn serialize_too_large() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let result = serialize_size_prefixed::<u8, _>(&mut serializer, false, |serializer| {
+            serializer.serialize_slice(&[0; 256])
+        });
+        assert_eq!(
+            result,
+            Err(Error::from(ErrorKind::Custom("the size of the object is too large for its binary representation"))
+                .locate(0))
+        );
+    }
+
+    #[test]
+    fn deserialize_skips_remaining() {
+        assert_eq!(deserialize(&[0x00, 0x03, 0xAA, 0xBB, 0xCC, 0xDD], false), Ok((0xAA, 0xDD)));
+        assert_eq!(deserialize(&[0x00, 0x05, 0xAA, 0xBB, 0xCC, 0xDD], true), Ok((0xAA, 0xDD)));
+    }
+
+    #[test]
+    fn deserialize_out_of_bounds() {
+        assert_eq!(deserialize(&[0x00, 0x00, 0xAA, 0xBB], false), Err(Error::from(ErrorKind::OutOfBounds).locate(2)));
+    }
+
+    #[test]
+    fn deserialize_smaller_than_prefix() {
+        assert_eq!(
+            deserialize(&[0x00, 0x01, 0xAA, 0xBB], true),
+            Err(ErrorKind::Custom("the size of the object is smaller than its size prefix").into())
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::thread;
+
+use crate::error::Error;
+use crate::io::{GrowingMemoryStream, Write};
+use crate::layout::SerializedSize;
+use crate::ser_de::{Serialize, Serializer};
+
+use super::stream_serializer::{RangeSpan, StreamSerializer, Summary};
+
+impl<Stream: Write> StreamSerializer<Stream> {
+    /// Serialize a batch of items on multiple threads.
+    ///
+    /// The items are split into chunks, one for each available thread, and
+    /// each chunk is serialized into its own in-memory buffer. The threads are
+    /// spawned with [`std::thread::scope`] on every call, there is no thread
+    /// pool. The buffers are then written into the stream in order.
+    ///
+    /// When the serialized size of `T` is fixed, every item is serialized at
+    /// its final position, so the output is the same as if the items were
+    /// serialized one after the other. Otherwise, the position of a chunk is
+    /// not known until the chunks before it are serialized, so every chunk is
+    /// serialized as if it started at the start of the batch. The items of
+    /// such types must not depend on their position, for example through
+    /// absolute alignment, or the output differs from serializing them one by
+    /// one.
+    ///
+    /// Returns the span of the entire batch. If serializing a chunk fails, the
+    /// error of the first failing chunk is returned, and the stream is left
+    /// untouched. If writing the buffers into the stream fails, the buffers
+    /// written before the failing one remain in the stream.
+    ///
+    /// ```
+    /// # use sorbit::stream_ser_de::StreamSerializer;
+    /// # use sorbit::io::GrowingMemoryStream;
+    /// # use sorbit::ser_de::Span;
+    /// let items: Vec<u16> = (0..1000).collect();
+    /// let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+    /// let span = serializer.serialize_batch(&items).unwrap();
+    /// assert_eq!(span.len(), 2000);
+    /// assert_eq!(serializer.take().take()[2..4], [1, 0]);
+    /// ```
+    pub fn serialize_batch<T: Serialize + SerializedSize + Sync>(&mut self, items: &[T]) -> Result<RangeSpan, Error> {
+        let start = self.context.absolute_pos();
+        let num_threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = items.len().div_ceil(num_threads).max(1);
+        let branches: Result<Vec<_>, _> = thread::scope(|scope| {
+            let handles: Vec<_> = items
+                .chunks(chunk_size)
+                .enumerate()
+                .map(|(index, chunk)| {
+                    let pos = match T::SIZE {
+                        Some(size) => start + (index * chunk_size * size) as u64,
+                        None => start,
+                    };
+                    let mut branch = self.branch(pos);
+                    scope.spawn(move || {
+                        for item in chunk {
+                            item.serialize(&mut branch)?;
+                        }
+                        Ok::<_, Error>(branch)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                .collect()
+        });
+        for branch in branches? {
+            self.merge(branch)?;
+        }
+        Ok(RangeSpan(start..self.context.absolute_pos()))
+    }
+
+    /// Create a serializer that continues this one at `pos` into a buffer.
+    fn branch(&self, pos: u64) -> StreamSerializer<GrowingMemoryStream> {
+        StreamSerializer {
+            stream: GrowingMemoryStream::new(),
+            context: self.context.clone().advance_to(pos),
+            config: self.config,
+            summary: Summary { start: pos, end: pos, max_depth: self.depth, composites: 0 },
+            depth: self.depth,
+        }
+    }
+
+    /// Write the buffer of a [`branch`](Self::branch) into the stream.
+    fn merge(&mut self, branch: StreamSerializer<GrowingMemoryStream>) -> Result<RangeSpan, Error> {
+        let (stream, summary) = branch.finish();
+        let span = self.serialize_slice(&stream.take())?;
+        self.summary.max_depth = core::cmp::max(self.summary.max_depth, summary.max_depth);
+        self.summary.composites += summary.composites;
+        Ok(span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte_order::ByteOrder;
+    use crate::layout::{Layout, StaticLayout};
+    use crate::ser_de::Span as _;
+
+    struct Bytes(Vec<u8>);
+
+    impl Serialize for Bytes {
+        fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+            serializer.serialize_slice(&self.0)
+        }
+    }
+
+    impl StaticLayout for Bytes {
+        const LAYOUT: Layout = Layout::new(0, None, 0);
+    }
+
+    fn serialize_sequential<T: Serialize>(items: &[T]) -> (Vec<u8>, Summary) {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        for item in items {
+            item.serialize(&mut serializer).unwrap();
+        }
+        let (stream, summary) = serializer.finish();
+        (stream.take(), summary)
+    }
+
+    fn serialize_parallel<T: Serialize + SerializedSize + Sync>(items: &[T]) -> (Vec<u8>, Summary) {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        serializer.serialize_batch(items).unwrap();
+        let (stream, summary) = serializer.finish();
+        (stream.take(), summary)
+    }
+
+    #[test]
+    fn fixed_size() {
+        let items: Vec<(u8, u32)> = (0..1000).map(|i| (i as u8, i)).collect();
+        assert_eq!(serialize_parallel(&items), serialize_sequential(&items));
+    }
+
+    #[test]
+    fn variable_size() {
+        let items: Vec<_> = (0..100).map(|i| Bytes((0..i).collect())).collect();
+        assert_eq!(serialize_parallel(&items), serialize_sequential(&items));
+    }
+
+    #[test]
+    fn empty() {
+        let items: [u32; 0] = [];
+        assert_eq!(serialize_parallel(&items), serialize_sequential(&items));
+    }
+
+    #[test]
+    fn span() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        serializer.serialize_u8(0xFF).unwrap();
+        let span = serializer.serialize_batch(&[1u16, 2, 3]).unwrap();
+        assert_eq!((span.start(), span.end()), (1, 7));
+    }
+
+    #[test]
+    fn byte_order() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        serializer.with_byte_order(ByteOrder::BigEndian, |s| s.serialize_batch(&[1u16, 2])).unwrap();
+        assert_eq!(serializer.take().take(), [0, 1, 0, 2]);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::bit::Error as BitError;
+use crate::error::Error;
+use crate::io::{Read, Write};
+
+/// The order in which the bits of a bit stream fill each byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BitOrder {
+    /// Bytes are filled from the most significant bit, and values are written
+    /// starting with their most significant bit. Used by MPEG and H.264.
+    #[default]
+    MsbFirst,
+    /// Bytes are filled from the least significant bit, and values are written
+    /// starting with their least significant bit. Used by DEFLATE.
+    LsbFirst,
+}
+
+/// Writes values of arbitrary bit width to a stream, across byte boundaries.
+///
+/// Bits are collected until they fill a byte, and only whole bytes are
+/// written to the stream. Call [`align_to_byte`](Self::align_to_byte) to pad
+/// the last byte with zeros and write it out, or [`finish`](Self::finish) to
+/// do the same and get the stream back.
+///
+/// ```
+/// use sorbit::io::GrowingMemoryStream;
+/// use sorbit::stream_ser_de::BitStreamSerializer;
+///
+/// let mut serializer = BitStreamSerializer::new(GrowingMemoryStream::new());
+/// serializer.write_bits(0b101, 3)?;
+/// serializer.write_bits(0b0000_0110_011, 11)?;
+/// let stream = serializer.finish()?;
+/// assert_eq!(stream.take(), vec![0b1010_0000, 0b1100_1100]);
+/// # Ok::<(), sorbit::error::Error>(())
+/// ```
+pub struct BitStreamSerializer<Stream: Write> {
+    stream: Stream,
+    bit_order: BitOrder,
+    buffer: u8,
+    num_bits: u8,
+    bit_position: u64,
+}
+
+/// Reads values of arbitrary bit width from a stream, across byte boundaries.
+///
+/// The counterpart of [`BitStreamSerializer`].
+///
+/// ```
+/// use sorbit::io::FixedMemoryStream;
+/// use sorbit::stream_ser_de::BitStreamDeserializer;
+///
+/// let mut deserializer = BitStreamDeserializer::new(FixedMemoryStream::new([0b1010_0000, 0b1100_1100]));
+/// assert_eq!(deserializer.read_bits(3), Ok(0b101));
+/// assert_eq!(deserializer.read_bits(11), Ok(0b0000_0110_011));
+/// # Ok::<(), sorbit::error::Error>(())
+/// ```
+pub struct BitStreamDeserializer<Stream: Read> {
+    stream: Stream,
+    bit_order: BitOrder,
+    buffer: u8,
+    num_bits: u8,
+    bit_position: u64,
+}
+
+impl<Stream: Write> BitStreamSerializer<Stream> {
+    /// Create a new serializer that fills bytes starting with the most
+    /// significant bit.
+    pub fn new(stream: Stream) -> Self {
+        Self::with_bit_order(stream, BitOrder::MsbFirst)
+    }
+
+    /// Create a new serializer with the specified bit order.
+    pub fn with_bit_order(stream: Stream, bit_order: BitOrder) -> Self {
+        Self { stream, bit_order, buffer: 0, num_bits: 0, bit_position: 0 }
+    }
+
+    /// Return the number of bits written so far, including the bits that are
+    /// not yet written to the stream.
+    pub fn bit_position(&self) -> u64 {
+        self.bit_position
+    }
+
+    /// Check if the next bit starts a new byte.
+    pub fn is_aligned(&self) -> bool {
+        self.num_bits == 0
+    }
+
+    /// Write the lowest `num_bits` bits of `value`.
+    ///
+    /// ## Errors
+    ///
+    /// An error is returned if `value` does not fit into `num_bits` bits, or
+    /// if `num_bits` is more than 64.
+    pub fn write_bits(&mut self, value: u64, num_bits: u32) -> Result<(), Error> {
+        if num_bits > u64::BITS || (num_bits < u64::BITS && value >> num_bits != 0) {
+            return Err(BitError::TooManyBits.into());
+        }
+        for index in 0..num_bits {
+            let bit = match self.bit_order {
+                BitOrder::MsbFirst => value >> (num_bits - 1 - index),
+                BitOrder::LsbFirst => value >> index,
+            };
+            self.write_bit(bit & 1 != 0)?;
+        }
+        Ok(())
+    }
+
+    /// Write `value` in `num_bits` bits, as two's complement.
+    ///
+    /// ## Errors
+    ///
+    /// An error is returned if `value` does not fit into `num_bits` bits, or
+    /// if `num_bits` is more than 64.
+    pub fn write_signed_bits(&mut self, value: i64, num_bits: u32) -> Result<(), Error> {
+        let fits = match num_bits {
+            0 => value == 0,
+            1..64 => (-(1 << (num_bits - 1))..(1 << (num_bits - 1))).contains(&value),
+            _ => num_bits == u64::BITS,
+        };
+        if !fits {
+            return Err(BitError::TooManyBits.into());
+        }
+        let mask = u64::MAX.checked_shr(u64::BITS - num_bits).unwrap_or(0);
+        self.write_bits(value as u64 & mask, num_bits)
+    }
+
+    /// Write a single bit.
+    pub fn write_bool(&mut self, value: bool) -> Result<(), Error> {
+        self.write_bit(value)
+    }
+
+    /// Pad the current byte with zeros, and write it to the stream.
+    ///
+    /// Does nothing if the serializer is already aligned to a byte.
+    pub fn align_to_byte(&mut self) -> Result<(), Error> {
+        while !self.is_aligned() {
+            self.write_bit(false)?;
+        }
+        Ok(())
+    }
+
+    /// Align to a byte, and return the underlying stream.
+    pub fn finish(mut self) -> Result<Stream, Error> {
+        self.align_to_byte()?;
+        Ok(self.stream)
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<(), Error> {
+        let shift = match self.bit_order {
+            BitOrder::MsbFirst => 7 - self.num_bits,
+            BitOrder::LsbFirst => self.num_bits,
+        };
+        self.buffer |= u8::from(bit) << shift;
+        self.num_bits += 1;
+        self.bit_position += 1;
+        if self.num_bits == 8 {
+            self.stream.write(&[self.buffer])?;
+            self.buffer = 0;
+            self.num_bits = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<Stream: Read> BitStreamDeserializer<Stream> {
+    /// Create a new deserializer that consumes bytes starting with the most
+    /// significant bit.
+    pub fn new(stream: Stream) -> Self {
+        Self::with_bit_order(stream, BitOrder::MsbFirst)
+    }
+
+    /// Create a new deserializer with the specified bit order.
+    pub fn with_bit_order(stream: Stream, bit_order: BitOrder) -> Self {
+        Self { stream, bit_order, buffer: 0, num_bits: 0, bit_position: 0 }
+    }
+
+    /// Return the number of bits read so far.
+    pub fn bit_position(&self) -> u64 {
+        self.bit_position
+    }
+
+    /// Check if the next bit starts a new byte.
+    pub fn is_aligned(&self) -> bool {
+        self.num_bits == 0
+    }
+
+    /// Read `num_bits` bits as an unsigned integer.
+    ///
+    /// ## Errors
+    ///
+    /// An error is returned if `num_bits` is more than 64, or if the stream
+    /// ends.
+    pub fn read_bits(&mut self, num_bits: u32) -> Result<u64, Error> {
+        if num_bits > u64::BITS {
+            return Err(BitError::TooManyBits.into());
+        }
+        let mut value = 0;
+        for index in 0..num_bits {
+            let bit = u64::from(self.read_bit()?);
+            match self.bit_order {
+                BitOrder::MsbFirst => value = (value << 1) | bit,
+                BitOrder::LsbFirst => value |= bit << index,
+            }
+        }
+        Ok(value)
+    }
+
+    /// Read `num_bits` bits as a two's complement signed integer.
+    ///
+    /// ## Errors
+    ///
+    /// An error is returned if `num_bits` is more than 64, or if the stream
+    /// ends.
+    pub fn read_signed_bits(&mut self, num_bits: u32) -> Result<i64, Error> {
+        let value = self.read_bits(num_bits)?;
+        let unused_bits = u64::BITS - num_bits;
+        Ok(value.checked_shl(unused_bits).map(|value| value as i64 >> unused_bits).unwrap_or(0))
+    }
+
+    /// Read a single bit.
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        self.read_bit()
+    }
+
+    /// Discard the remaining bits of the current byte.
+    ///
+    /// Does nothing if the deserializer is already aligned to a byte.
+    pub fn align_to_byte(&mut self) {
+        self.bit_position += u64::from(self.num_bits);
+        self.num_bits = 0;
+    }
+
+    /// Return the underlying stream. The remaining bits of the current byte
+    /// are discarded.
+    pub fn finish(self) -> Stream {
+        self.stream
+    }
+
+    fn read_bit(&mut self) -> Result<bool, Error> {
+        if self.num_bits == 0 {
+            let mut byte = [0];
+            self.stream.read(&mut byte)?;
+            self.buffer = byte[0];
+            self.num_bits = 8;
+        }
+        let shift = match self.bit_order {
+            BitOrder::MsbFirst => self.num_bits - 1,
+            BitOrder::LsbFirst => 8 - self.num_bits,
+        };
+        self.num_bits -= 1;
+        self.bit_position += 1;
+        Ok((self.buffer >> shift) & 1 != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::io::{FixedMemoryStream, GrowingMemoryStream};
+
+    fn serialize(bit_order: BitOrder, write: impl FnOnce(&mut BitStreamSerializer<GrowingMemoryStream>)) -> Vec<u8> {
+        let mut serializer = BitStreamSerializer::with_bit_order(GrowingMemoryStream::new(), bit_order);
+        write(&mut serializer);
+        serializer.finish().unwrap().take()
+    }
+
+    #[test]
+    fn write_bits_msb_first() {
+        let bytes = serialize(BitOrder::MsbFirst, |s| {
+            s.write_bits(0b101, 3).unwrap();
+            s.write_bits(0b000_0011_0011, 11).unwrap();
+        });
+        assert_eq!(bytes, [0b1010_0000, 0b1100_1100]);
+    }
+
+    #[test]
+    fn write_bits_lsb_first() {
+        let bytes = serialize(BitOrder::LsbFirst, |s| {
+            s.write_bits(0b101, 3).unwrap();
+            s.write_bits(0b110_0110_0111, 11).unwrap();
+        });
+        assert_eq!(bytes, [0b0011_1101, 0b0011_0011]);
+    }
+
+    #[test]
+    fn write_bits_too_large() {
+        let mut serializer = BitStreamSerializer::new(GrowingMemoryStream::new());
+        assert_eq!(serializer.write_bits(0b100, 2), Err(Error::from(ErrorKind::Bit(BitError::TooManyBits))));
+        assert_eq!(serializer.write_bits(0, 65), Err(Error::from(ErrorKind::Bit(BitError::TooManyBits))));
+        assert_eq!(serializer.write_signed_bits(-3, 2), Err(Error::from(ErrorKind::Bit(BitError::TooManyBits))));
+        assert_eq!(serializer.bit_position(), 0);
+    }
+
+    #[test]
+    fn write_full_width() {
+        let bytes = serialize(BitOrder::MsbFirst, |s| {
+            s.write_bits(u64::MAX, 64).unwrap();
+            s.write_signed_bits(i64::MIN, 64).unwrap();
+        });
+        assert_eq!(bytes, [[0xFF; 8], [0x80, 0, 0, 0, 0, 0, 0, 0]].concat());
+    }
+
+    #[test]
+    fn align_to_byte() {
+        let bytes = serialize(BitOrder::MsbFirst, |s| {
+            s.write_bool(true).unwrap();
+            s.align_to_byte().unwrap();
+            assert!(s.is_aligned());
+            assert_eq!(s.bit_position(), 8);
+            s.align_to_byte().unwrap();
+            s.write_bits(0xAB, 8).unwrap();
+        });
+        assert_eq!(bytes, [0b1000_0000, 0xAB]);
+    }
+
+    #[test]
+    fn read_bits_msb_first() {
+        let mut deserializer = BitStreamDeserializer::new(FixedMemoryStream::new([0b1010_0000, 0b1100_1100]));
+        assert_eq!(deserializer.read_bits(3), Ok(0b101));
+        assert_eq!(deserializer.read_bits(11), Ok(0b000_0011_0011));
+        assert_eq!(deserializer.bit_position(), 14);
+    }
+
+    #[test]
+    fn read_bits_lsb_first() {
+        let stream = FixedMemoryStream::new([0b0011_1101, 0b0011_0011]);
+        let mut deserializer = BitStreamDeserializer::with_bit_order(stream, BitOrder::LsbFirst);
+        assert_eq!(deserializer.read_bits(3), Ok(0b101));
+        assert_eq!(deserializer.read_bits(11), Ok(0b110_0110_0111));
+    }
+
+    #[test]
+    fn read_signed_bits() {
+        let mut deserializer = BitStreamDeserializer::new(FixedMemoryStream::new([0b1110_1100]));
+        assert_eq!(deserializer.read_signed_bits(3), Ok(-1));
+        assert_eq!(deserializer.read_signed_bits(3), Ok(3));
+        assert_eq!(deserializer.read_signed_bits(0), Ok(0));
+    }
+
+    #[test]
+    fn read_past_end() {
+        let mut deserializer = BitStreamDeserializer::new(FixedMemoryStream::new([0xFF]));
+        assert_eq!(deserializer.read_bits(4), Ok(0xF));
+        assert!(deserializer.read_bits(5).is_err());
+    }
+
+    #[test]
+    fn read_align_to_byte() {
+        let mut deserializer = BitStreamDeserializer::new(FixedMemoryStream::new([0xFF, 0xAB]));
+        assert_eq!(deserializer.read_bool(), Ok(true));
+        deserializer.align_to_byte();
+        assert!(deserializer.is_aligned());
+        assert_eq!(deserializer.bit_position(), 8);
+        assert_eq!(deserializer.read_bits(8), Ok(0xAB));
+    }
+
+    #[test]
+    fn round_trip() {
+        for bit_order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let bytes = serialize(bit_order, |s| {
+                s.write_bits(0x5, 3).unwrap();
+                s.write_signed_bits(-100, 9).unwrap();
+                s.write_bits(0x0001_2345_6789, 37).unwrap();
+            });
+            let mut deserializer = BitStreamDeserializer::with_bit_order(FixedMemoryStream::new(bytes), bit_order);
+            assert_eq!(deserializer.read_bits(3), Ok(0x5));
+            assert_eq!(deserializer.read_signed_bits(9), Ok(-100));
+            assert_eq!(deserializer.read_bits(37), Ok(0x0001_2345_6789));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::byte_order::ByteOrder;
+use crate::ser_de::Limits;
+
+/// The configuration of a [`StreamSerializer`](super::StreamSerializer) or a
+/// [`StreamDeserializer`](super::StreamDeserializer).
+///
+/// The same configuration can be used to create any number of serializers and
+/// deserializers:
+///
+/// ```
+/// # use sorbit::byte_order::ByteOrder;
+/// # use sorbit::io::GrowingMemoryStream;
+/// # use sorbit::ser_de::{Deserialize, Serialize};
+/// # use sorbit::stream_ser_de::{Config, StreamDeserializer, StreamSerializer};
+/// const CONFIG: Config = Config::DEFAULT.with_byte_order(ByteOrder::BigEndian);
+///
+/// let mut serializer = StreamSerializer::with_config(GrowingMemoryStream::new(), CONFIG);
+/// 0xCAFE_u16.serialize(&mut serializer).unwrap();
+/// let bytes = serializer.take().take();
+/// assert_eq!(bytes, [0xCA, 0xFE]);
+///
+/// let mut deserializer = StreamDeserializer::with_config(GrowingMemoryStream::from(bytes), CONFIG);
+/// assert_eq!(u16::deserialize(&mut deserializer), Ok(0xCAFE));
+/// ```
+///
+/// The serializer ignores the options that only affect deserialization.
+///
+/// New options may be added in the future, so a configuration cannot be
+/// created with a struct expression outside this crate. Start from
+/// [`DEFAULT`](Self::DEFAULT) and change the options with the `with_*` methods
+/// instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Config {
+    /// The byte order of primitives, unless the serialized objects specify
+    /// their own byte order.
+    pub byte_order: ByteOrder,
+    /// Keep malformed values during deserialization.
+    ///
+    /// See [`StreamDeserializer::lenient`](super::StreamDeserializer::lenient).
+    pub lenient: bool,
+    /// The resource limits of deserialization.
+    pub limits: Limits,
+    /// The byte that padding is filled with, unless the serialized objects
+    /// specify their own fill byte.
+    pub fill: u8,
+    /// Fail deserialization if padding contains any other byte than the fill
+    /// byte.
+    ///
+    /// See [`StreamDeserializer::verify_fill`](super::StreamDeserializer::verify_fill).
+    pub verify_fill: bool,
+    /// Produce the same bytes for equal values during serialization.
+    ///
+    /// See [`StreamSerializer::canonical`](super::StreamSerializer::canonical).
+    pub canonical: bool,
+    /// Skip the validation of inputs that are known to be valid during
+    /// deserialization.
+    ///
+    /// See [`StreamDeserializer::trusted`](super::StreamDeserializer::trusted).
+    pub trusted: bool,
+}
+
+impl Config {
+    /// The default configuration.
+    ///
+    /// Uses the native byte order, strict deserialization, the default
+    /// [`Limits`], zero padding that is not verified, non-canonical
+    /// serialization, and untrusted inputs.
+    pub const DEFAULT: Self = Self {
+        byte_order: ByteOrder::native(),
+        lenient: false,
+        limits: Limits::DEFAULT,
+        fill: 0,
+        verify_fill: false,
+        canonical: false,
+        trusted: false,
+    };
+
+    /// Change the [`byte_order`](Self::byte_order).
+    pub const fn with_byte_order(self, byte_order: ByteOrder) -> Self {
+        Self { byte_order, ..self }
+    }
+
+    /// Change whether deserialization is [`lenient`](Self::lenient).
+    pub const fn with_lenient(self, lenient: bool) -> Self {
+        Self { lenient, ..self }
+    }
+
+    /// Change the resource [`limits`](Self::limits).
+    pub const fn with_limits(self, limits: Limits) -> Self {
+        Self { limits, ..self }
+    }
+
+    /// Change the [`fill`](Self::fill) byte.
+    pub const fn with_fill(self, fill: u8) -> Self {
+        Self { fill, ..self }
+    }
+
+    /// Change whether padding is verified. (See [`verify_fill`](Self::verify_fill).)
+    pub const fn with_verify_fill(self, verify_fill: bool) -> Self {
+        Self { verify_fill, ..self }
+    }
+
+    /// Change whether serialization is [`canonical`](Self::canonical).
+    pub const fn with_canonical(self, canonical: bool) -> Self {
+        Self { canonical, ..self }
+    }
+
+    /// Change whether the input is [`trusted`](Self::trusted).
+    pub const fn with_trusted(self, trusted: bool) -> Self {
+        Self { trusted, ..self }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
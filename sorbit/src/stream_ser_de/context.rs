@@ -2,7 +2,7 @@ use core::ops::Range;
 
 use crate::byte_order::ByteOrder;
 use crate::error::{Error, ErrorKind};
-use crate::io::{Read, Seek, Write};
+use crate::io::{BorrowRead, Read, Seek, Write};
 
 #[derive(Debug, Clone)]
 pub struct Context {
@@ -12,14 +12,25 @@ pub struct Context {
     absolute_pos: u64,
     /// The byte order used to serialize items.
     byte_order: ByteOrder,
+    /// The byte used for padding.
+    fill: u8,
     /// Only bytes in range may be written or read.
     limits: Option<Range<u64>>,
+    /// The context values visible in the current composite.
+    values: ContextValues,
+}
+
+/// The context values set by the serialized objects, looked up by key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ContextValues {
+    entries: [Option<(&'static str, u64)>; ContextValues::CAPACITY],
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[must_use]
 pub struct CompositeScope {
     base_pos: u64,
+    values: ContextValues,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -28,6 +39,12 @@ pub struct ByteOrderScope {
     byte_order: ByteOrder,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct FillScope {
+    fill: u8,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[must_use]
 pub struct BoundedScope {
@@ -44,7 +61,7 @@ pub struct RevisionScope {
 
 impl Context {
     pub fn local_pos(&self) -> u64 {
-        self.absolute_pos - self.base_pos
+        self.absolute_pos.saturating_sub(self.base_pos)
     }
 
     pub fn absolute_pos(&self) -> u64 {
@@ -55,21 +72,69 @@ impl Context {
         self.byte_order
     }
 
+    pub fn fill(&self) -> u8 {
+        self.fill
+    }
+
+    pub fn resume_at(self, pos: u64) -> Self {
+        Self { base_pos: pos, absolute_pos: pos, ..self }
+    }
+
+    /// Move the position where the next write occurs to `pos`, but keep the
+    /// base address of the current composite.
+    #[cfg(feature = "parallel")]
+    pub fn advance_to(self, pos: u64) -> Self {
+        Self { absolute_pos: pos, ..self }
+    }
+
+    /// Move the positions of the context with `shift_start` for the
+    /// beginnings of ranges and `shift_end` for the ends of ranges.
+    #[cfg(feature = "alloc")]
+    pub fn shift(self, shift_start: impl Fn(u64) -> u64, shift_end: impl Fn(u64) -> u64) -> Self {
+        Self {
+            base_pos: shift_start(self.base_pos),
+            absolute_pos: shift_end(self.absolute_pos),
+            limits: self.limits.map(|limits| shift_start(limits.start)..shift_end(limits.end)),
+            ..self
+        }
+    }
+
     pub fn change_byte_order(self, byte_order: ByteOrder) -> Self {
         Self { byte_order, ..self }
     }
 
+    pub fn set_byte_order(&mut self, byte_order: ByteOrder) {
+        self.byte_order = byte_order;
+    }
+
+    pub fn change_fill(self, fill: u8) -> Self {
+        Self { fill, ..self }
+    }
+
+    pub fn set_fill(&mut self, fill: u8) {
+        self.fill = fill;
+    }
+
+    pub fn value(&self, key: &str) -> Option<u64> {
+        self.values.get(key)
+    }
+
+    pub fn set_value(&mut self, key: &'static str, value: Option<u64>) -> Result<(), Error> {
+        self.values.set(key, value)
+    }
+
     pub fn bytes_in_bounds(&self) -> Option<u64> {
         self.limits.as_ref().map(|limits| limits.end - self.absolute_pos)
     }
 
     pub fn composite_scope(&mut self) -> CompositeScope {
         let base_pos = core::mem::replace(&mut self.base_pos, self.absolute_pos);
-        CompositeScope { base_pos }
+        CompositeScope { base_pos, values: self.values }
     }
 
     pub fn close_composite_scope(&mut self, scope: CompositeScope) {
         self.base_pos = scope.base_pos;
+        self.values = scope.values;
     }
 
     pub fn byte_order_scope(&mut self, byte_order: ByteOrder) -> ByteOrderScope {
@@ -81,6 +146,15 @@ impl Context {
         self.byte_order = scope.byte_order;
     }
 
+    pub fn fill_scope(&mut self, fill: u8) -> FillScope {
+        let fill = core::mem::replace(&mut self.fill, fill);
+        FillScope { fill }
+    }
+
+    pub fn close_fill_scope(&mut self, scope: FillScope) {
+        self.fill = scope.fill;
+    }
+
     pub fn bounded_scope(&mut self, num_bytes: u64) -> Result<BoundedScope, Error> {
         let bounds = self.absolute_pos..self.absolute_pos + num_bytes;
         if self.limits.as_ref().is_some_and(|current| !contains_range(current, &bounds)) {
@@ -115,12 +189,26 @@ impl Context {
         Ok(())
     }
 
+    pub fn seek(&mut self, stream: &mut impl Seek, local_pos: u64) -> Result<(), Error> {
+        self.seek_absolute(stream, self.base_pos + local_pos)
+    }
+
+    /// Move to `target`, which is interpreted from the beginning of the stream.
+    pub fn seek_absolute(&mut self, stream: &mut impl Seek, target: u64) -> Result<(), Error> {
+        if self.limits.as_ref().is_some_and(|current| !current.contains(&target) && current.end != target) {
+            return Err(ErrorKind::OutOfBounds.into());
+        }
+        stream.seek_relative(target as i64 - self.absolute_pos as i64)?;
+        self.absolute_pos = target;
+        Ok(())
+    }
+
     pub fn read(&mut self, stream: &mut impl Read, bytes: &mut [u8]) -> Result<Range<u64>, Error> {
         let read_span = self.absolute_pos..self.absolute_pos + bytes.len() as u64;
-        if let Some(bounds) = &self.limits {
-            if !contains_range(bounds, &read_span) {
-                return Err(ErrorKind::OutOfBounds.into());
-            };
+        if let Some(bounds) = &self.limits
+            && !contains_range(bounds, &read_span)
+        {
+            return Err(ErrorKind::OutOfBounds.into());
         };
         match stream.read(bytes) {
             Ok(_) => {
@@ -131,12 +219,36 @@ impl Context {
         }
     }
 
+    pub fn skip(&mut self, stream: &mut impl Read, byte_count: u64) -> Result<Range<u64>, Error> {
+        let skip_span = self.absolute_pos..self.absolute_pos + byte_count;
+        if let Some(bounds) = &self.limits
+            && !contains_range(bounds, &skip_span)
+        {
+            return Err(ErrorKind::OutOfBounds.into());
+        };
+        stream.skip(byte_count)?;
+        self.absolute_pos += byte_count;
+        Ok(skip_span)
+    }
+
+    pub fn read_borrowed<'de>(&mut self, stream: &mut impl BorrowRead<'de>, len: usize) -> Result<&'de [u8], Error> {
+        let read_span = self.absolute_pos..self.absolute_pos + len as u64;
+        if let Some(bounds) = &self.limits
+            && !contains_range(bounds, &read_span)
+        {
+            return Err(ErrorKind::OutOfBounds.into());
+        };
+        let bytes = stream.read_borrowed(len)?;
+        self.absolute_pos += len as u64;
+        Ok(bytes)
+    }
+
     pub fn write(&mut self, stream: &mut impl Write, bytes: &[u8]) -> Result<Range<u64>, Error> {
         let write_span = self.absolute_pos..self.absolute_pos + bytes.len() as u64;
-        if let Some(bounds) = &self.limits {
-            if !contains_range(bounds, &write_span) {
-                return Err(ErrorKind::OutOfBounds.into());
-            };
+        if let Some(bounds) = &self.limits
+            && !contains_range(bounds, &write_span)
+        {
+            return Err(ErrorKind::OutOfBounds.into());
         };
         match stream.write(bytes) {
             Ok(_) => {
@@ -150,7 +262,38 @@ impl Context {
 
 impl Default for Context {
     fn default() -> Self {
-        Self { base_pos: 0, absolute_pos: 0, byte_order: ByteOrder::native(), limits: None }
+        Self {
+            base_pos: 0,
+            absolute_pos: 0,
+            byte_order: ByteOrder::native(),
+            fill: 0,
+            limits: None,
+            values: ContextValues::default(),
+        }
+    }
+}
+
+impl ContextValues {
+    /// The maximum number of context values that can be set at once.
+    pub const CAPACITY: usize = 8;
+
+    pub fn get(&self, key: &str) -> Option<u64> {
+        self.entries.iter().flatten().find(|(entry_key, _)| *entry_key == key).map(|(_, value)| *value)
+    }
+
+    /// Set `key` to `value`, or remove `key` if `value` is `None`.
+    pub fn set(&mut self, key: &'static str, value: Option<u64>) -> Result<(), Error> {
+        let existing = self.entries.iter_mut().find(|entry| matches!(entry, Some((entry_key, _)) if *entry_key == key));
+        let slot = match (existing, value) {
+            (Some(slot), _) => slot,
+            (None, Some(_)) => match self.entries.iter_mut().find(|entry| entry.is_none()) {
+                Some(slot) => slot,
+                None => return Err(ErrorKind::Custom("too many context values are set at once").into()),
+            },
+            (None, None) => return Ok(()),
+        };
+        *slot = value.map(|value| (key, value));
+        Ok(())
     }
 }
 
@@ -159,6 +302,7 @@ fn contains_range(outer: &Range<u64>, inner: &Range<u64>) -> bool {
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
 
@@ -166,11 +310,11 @@ mod tests {
 
     #[test]
     fn test_contains_range() {
-        assert_eq!(contains_range(&(3..6), &(3..6)), true);
-        assert_eq!(contains_range(&(3..6), &(3..5)), true);
-        assert_eq!(contains_range(&(3..6), &(3..7)), false);
-        assert_eq!(contains_range(&(3..6), &(2..6)), false);
-        assert_eq!(contains_range(&(3..6), &(2..7)), false);
+        assert!(contains_range(&(3..6), &(3..6)));
+        assert!(contains_range(&(3..6), &(3..5)));
+        assert!(!contains_range(&(3..6), &(3..7)));
+        assert!(!contains_range(&(3..6), &(2..6)));
+        assert!(!contains_range(&(3..6), &(2..7)));
     }
 
     #[test]
@@ -185,6 +329,56 @@ mod tests {
         assert_eq!(ctx.absolute_pos, 90);
     }
 
+    #[test]
+    fn composite_scope_restores_values() {
+        let mut ctx = Context::default();
+        ctx.set_value("version", Some(1)).unwrap();
+        let scope = ctx.composite_scope();
+        ctx.set_value("version", Some(2)).unwrap();
+        ctx.set_value("flags", Some(3)).unwrap();
+        assert_eq!((ctx.value("version"), ctx.value("flags")), (Some(2), Some(3)));
+        ctx.close_composite_scope(scope);
+        assert_eq!((ctx.value("version"), ctx.value("flags")), (Some(1), None));
+    }
+
+    #[test]
+    fn values_unset() {
+        let mut values = ContextValues::default();
+        values.set("version", Some(1)).unwrap();
+        values.set("version", None).unwrap();
+        values.set("flags", None).unwrap();
+        assert_eq!(values, ContextValues::default());
+    }
+
+    #[test]
+    fn values_capacity() {
+        const KEYS: [&str; ContextValues::CAPACITY] = ["0", "1", "2", "3", "4", "5", "6", "7"];
+        let mut values = ContextValues::default();
+        for (value, key) in KEYS.into_iter().enumerate() {
+            values.set(key, Some(value as u64)).unwrap();
+        }
+        assert!(values.set("8", Some(8)).is_err());
+        assert_eq!(values.set("7", Some(9)), Ok(()));
+        assert_eq!(values.get("7"), Some(9));
+    }
+
+    #[test]
+    fn resume_at() {
+        let mut ctx = Context::default().resume_at(70);
+        assert_eq!(ctx.local_pos(), 0);
+        ctx.absolute_pos += 20;
+        assert_eq!(ctx.local_pos(), 20);
+        assert_eq!(ctx.absolute_pos(), 90);
+    }
+
+    #[test]
+    fn local_pos_saturates() {
+        let mut ctx = Context::default();
+        ctx.base_pos = 70;
+        ctx.absolute_pos = 60;
+        assert_eq!(ctx.local_pos(), 0);
+    }
+
     #[test]
     fn byte_order_scope() {
         let mut ctx = Context::default();
@@ -332,8 +526,8 @@ mod tests {
         ctx.absolute_pos = 3;
         stream.seek(SeekFrom::Start(3)).unwrap();
 
-        let mut buffer = [0u8; 3];
-        ctx.write(&mut stream, &mut buffer).unwrap();
+        let buffer = [0u8; 3];
+        ctx.write(&mut stream, &buffer).unwrap();
         assert_eq!(ctx.absolute_pos, 6);
         assert_eq!(stream.stream_position(), Ok(6));
         assert_eq!(&stream.take(), &[0, 1, 2, 0, 0, 0, 6, 7]);
@@ -348,8 +542,8 @@ mod tests {
         ctx.absolute_pos = 3;
         stream.seek(SeekFrom::Start(3)).unwrap();
 
-        let mut buffer = [0u8; 3];
-        ctx.write(&mut stream, &mut buffer).unwrap();
+        let buffer = [0u8; 3];
+        ctx.write(&mut stream, &buffer).unwrap();
         assert_eq!(ctx.absolute_pos, 6);
         assert_eq!(stream.stream_position(), Ok(6));
         assert_eq!(&stream.take(), &[0, 1, 2, 0, 0, 0, 6, 7]);
@@ -364,8 +558,8 @@ mod tests {
         ctx.absolute_pos = 3;
         stream.seek(SeekFrom::Start(3)).unwrap();
 
-        let mut buffer = [0u8; 3];
-        assert_eq!(ctx.write(&mut stream, &mut buffer), Err(ErrorKind::OutOfBounds.into()));
+        let buffer = [0u8; 3];
+        assert_eq!(ctx.write(&mut stream, &buffer), Err(ErrorKind::OutOfBounds.into()));
         assert_eq!(ctx.absolute_pos, 3);
         assert_eq!(buffer, [0, 0, 0]);
         assert_eq!(stream.stream_position(), Ok(3));
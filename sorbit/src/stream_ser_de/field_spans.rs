@@ -0,0 +1,143 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// The byte ranges that the fields of deserialized structs were read from.
+///
+/// Recorded by a [`StreamDeserializer`](super::StreamDeserializer) with
+/// [`record_field_spans`](super::StreamDeserializer::record_field_spans).
+/// Fields are identified by their path from the outermost struct, with the
+/// names of nested fields separated by dots, like `header.version`. Tuple
+/// struct fields are named by their index, and the members of a bit field
+/// share the span of the bit field, named after the bit field. The padding
+/// that offsets or aligns a field is not part of its span.
+///
+/// The spans are listed in the order the fields started deserializing, so
+/// a struct precedes its own fields. The same path appears multiple times
+/// when it is deserialized multiple times, like the fields of the items of
+/// an array.
+///
+/// ```
+/// use sorbit::Deserialize;
+/// use sorbit::io::GrowingMemoryStream;
+/// use sorbit::ser_de::Deserialize as _;
+/// use sorbit::stream_ser_de::StreamDeserializer;
+///
+/// #[derive(Deserialize)]
+/// struct Header {
+///     version: u8,
+///     flags: u16,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct File {
+///     header: Header,
+///     len: u8,
+/// }
+///
+/// let stream = GrowingMemoryStream::from(vec![1, 0, 0, 4]);
+/// let mut deserializer = StreamDeserializer::new(stream).record_field_spans();
+/// File::deserialize(&mut deserializer).unwrap();
+/// let spans = deserializer.field_spans().unwrap();
+/// assert_eq!(spans.get("header"), Some(0..3));
+/// assert_eq!(spans.get("header.flags"), Some(1..3));
+/// assert_eq!(spans.get("len"), Some(3..4));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldSpans {
+    spans: Vec<(String, Range<u64>)>,
+    path: Vec<(&'static str, usize)>,
+}
+
+impl FieldSpans {
+    /// Create an empty set of field spans.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the span of the first field with the given `path`.
+    pub fn get(&self, path: &str) -> Option<Range<u64>> {
+        self.spans.iter().find(|(field, _)| field == path).map(|(_, span)| span.clone())
+    }
+
+    /// Iterate over the paths and spans of all fields.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Range<u64>)> {
+        self.spans.iter().map(|(path, span)| (path.as_str(), span.clone()))
+    }
+
+    /// Return the number of recorded spans.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Return whether no spans were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Start recording the field `name` at `position`.
+    pub(crate) fn enter(&mut self, name: &'static str, position: u64) {
+        let mut path = String::new();
+        for (parent, _) in &self.path {
+            path.push_str(parent);
+            path.push('.');
+        }
+        path.push_str(name);
+        self.path.push((name, self.spans.len()));
+        self.spans.push((path, position..position));
+    }
+
+    /// Finish recording the innermost field at `position`.
+    pub(crate) fn leave(&mut self, position: u64) {
+        if let Some((_, index)) = self.path.pop() {
+            let span = &mut self.spans[index].1;
+            span.end = core::cmp::max(span.start, position);
+        }
+    }
+
+    /// Forget the spans recorded after the first `len`.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.spans.truncate(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested() {
+        let mut spans = FieldSpans::new();
+        spans.enter("a", 0);
+        spans.enter("b", 1);
+        spans.leave(3);
+        spans.enter("c", 3);
+        spans.leave(4);
+        spans.leave(4);
+        spans.enter("d", 4);
+        spans.leave(6);
+        let expected = [("a", 0..4), ("a.b", 1..3), ("a.c", 3..4), ("d", 4..6)];
+        assert!(spans.iter().eq(expected));
+        assert_eq!(spans.get("a.c"), Some(3..4));
+        assert_eq!(spans.get("c"), None);
+    }
+
+    #[test]
+    fn repeated() {
+        let mut spans = FieldSpans::new();
+        spans.enter("a", 0);
+        spans.leave(1);
+        spans.enter("a", 1);
+        spans.leave(2);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans.get("a"), Some(0..1));
+    }
+
+    #[test]
+    fn backward() {
+        let mut spans = FieldSpans::new();
+        spans.enter("a", 4);
+        spans.leave(2);
+        assert_eq!(spans.get("a"), Some(4..4));
+    }
+}
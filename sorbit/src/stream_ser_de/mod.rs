@@ -1,8 +1,30 @@
 //! A serializer and a deserializer that works with any stream.
 
+#[cfg(feature = "parallel")]
+mod batch;
+mod bit_stream;
+mod config;
 mod context;
+#[cfg(feature = "alloc")]
+mod field_spans;
+mod patcher;
+mod record_file;
+mod record_iter;
+#[cfg(feature = "alloc")]
+mod resizing_serializer;
 mod stream_deserializer;
 mod stream_serializer;
 
-pub use stream_deserializer::StreamDeserializer;
-pub use stream_serializer::StreamSerializer;
+pub use bit_stream::{BitOrder, BitStreamDeserializer, BitStreamSerializer};
+pub use config::Config;
+#[cfg(feature = "alloc")]
+pub use field_spans::FieldSpans;
+pub use patcher::Patcher;
+pub use record_file::{RecordFile, Records};
+pub use record_iter::RecordIter;
+#[cfg(feature = "alloc")]
+pub use resizing_serializer::{ResizingSerializer, ResizingSpan};
+#[cfg(feature = "alloc")]
+pub use stream_deserializer::BufferedDeserializer;
+pub use stream_deserializer::{SliceDeserializer, StreamDeserializer};
+pub use stream_serializer::{RangeSpan, SizeSerializer, StreamSerializer, Summary};
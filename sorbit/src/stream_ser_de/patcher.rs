@@ -0,0 +1,242 @@
+use core::ops::Range;
+
+use crate::error::{Error, ErrorKind};
+use crate::io::{Read, Seek, SeekFrom, Write};
+use crate::ser_de::{Deserialize, Deserializer as _, Serialize};
+use crate::stream_ser_de::{Config, SizeSerializer, StreamDeserializer, StreamSerializer};
+
+/// In-place modification of a serialized value.
+///
+/// The patcher deserializes a value from a stream and remembers where it was
+/// stored. After the value is modified, [`commit`](Self::commit) serializes it
+/// back into the same place, but only writes the bytes that actually changed.
+/// This is much cheaper than rewriting an entire file to update a few fields
+/// of a header.
+///
+/// The modified value must serialize to exactly as many bytes as the original.
+///
+/// ```
+/// use sorbit::byte_order::ByteOrder;
+/// use sorbit::io::GrowingMemoryStream;
+/// use sorbit::stream_ser_de::{Config, Patcher};
+///
+/// const CONFIG: Config = Config::DEFAULT.with_byte_order(ByteOrder::BigEndian);
+///
+/// let stream = GrowingMemoryStream::from(vec![0x01, 0x00, 0x02, 0xAA]);
+/// let mut patcher = Patcher::<(u8, u16), _>::with_config(stream, CONFIG)?;
+/// assert_eq!(patcher.span(), 0..3);
+///
+/// patcher.get_mut().1 = 0x0003;
+/// assert_eq!(patcher.commit(), Ok(1));
+/// assert_eq!(patcher.into_inner().take(), [0x01, 0x00, 0x03, 0xAA]);
+/// # Ok::<(), sorbit::error::Error>(())
+/// ```
+pub struct Patcher<T, Stream> {
+    stream: Stream,
+    value: T,
+    span: Range<u64>,
+    config: Config,
+}
+
+impl<T, Stream> Patcher<T, Stream>
+where
+    T: Deserialize,
+    Stream: Read + Write + Seek,
+{
+    /// Deserialize the value at the current position of the stream.
+    pub fn new(stream: Stream) -> Result<Self, Error> {
+        Self::with_config(stream, Config::DEFAULT)
+    }
+
+    /// Deserialize the value at the current position of the stream, using
+    /// the specified configuration.
+    ///
+    /// The same configuration is used when the value is committed.
+    pub fn with_config(mut stream: Stream, config: Config) -> Result<Self, Error> {
+        let start = stream.stream_position()?;
+        let mut deserializer = StreamDeserializer::with_config(&mut stream, config);
+        let value = T::deserialize(&mut deserializer)?;
+        let end = start + deserializer.position();
+        Ok(Self { stream, value, span: start..end, config })
+    }
+
+    /// Return the deserialized value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Return the deserialized value for modification.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Return the range of stream positions where the value is stored.
+    pub fn span(&self) -> Range<u64> {
+        self.span.clone()
+    }
+
+    /// Return the original stream.
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+}
+
+impl<T, Stream> Patcher<T, Stream>
+where
+    T: Serialize,
+    Stream: Read + Write + Seek,
+{
+    /// Write the changes of the value back into the stream.
+    ///
+    /// Only the bytes that differ from what's in the stream are written.
+    /// Returns the number of bytes written.
+    pub fn commit(&mut self) -> Result<u64, Error> {
+        let mut sizer = SizeSerializer::with_config(Default::default(), self.config);
+        self.value.serialize(&mut sizer)?;
+        if sizer.take().len() != self.span.end - self.span.start {
+            return Err(ErrorKind::Custom("the patched value must serialize to as many bytes as the original").into());
+        }
+
+        self.stream.seek(SeekFrom::Start(self.span.start))?;
+        let mut patch = PatchStream { stream: &mut self.stream, written: 0 };
+        let mut serializer = StreamSerializer::with_config(&mut patch, self.config).resume_at(self.span.start);
+        self.value.serialize(&mut serializer)?;
+        Ok(patch.written)
+    }
+}
+
+/// A stream that only writes the bytes that differ from the bytes already in
+/// the stream.
+struct PatchStream<'stream, Stream> {
+    stream: &'stream mut Stream,
+    written: u64,
+}
+
+impl<Stream: Read + Write + Seek> Write for PatchStream<'_, Stream> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let mut buffer = [0u8; 64];
+        for chunk in bytes.chunks(buffer.len()) {
+            let original = &mut buffer[..chunk.len()];
+            self.stream.read(original)?;
+            let differs = |(new, old): (&u8, &u8)| new != old;
+            let first = chunk.iter().zip(original.iter()).position(differs);
+            let last = chunk.iter().zip(original.iter()).rposition(differs);
+            if let (Some(first), Some(last)) = (first, last) {
+                self.stream.seek_relative(first as i64 - chunk.len() as i64)?;
+                self.stream.write(&chunk[first..=last])?;
+                self.stream.seek_relative((chunk.len() - last - 1) as i64)?;
+                self.written += (last + 1 - first) as u64;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Stream: Read> Read for PatchStream<'_, Stream> {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+        self.stream.read(bytes)
+    }
+}
+
+impl<Stream: Seek> Seek for PatchStream<'_, Stream> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        self.stream.seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::ffi::CString;
+
+    use crate::byte_order::ByteOrder;
+    use crate::io::GrowingMemoryStream;
+
+    const CONFIG: Config = Config::DEFAULT.with_byte_order(ByteOrder::BigEndian);
+
+    /// A stream that counts the write calls.
+    struct CountingStream {
+        stream: GrowingMemoryStream,
+        writes: usize,
+    }
+
+    impl Read for CountingStream {
+        fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+            self.stream.read(bytes)
+        }
+    }
+
+    impl Write for CountingStream {
+        fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+            self.writes += 1;
+            self.stream.write(bytes)
+        }
+    }
+
+    impl Seek for CountingStream {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+            self.stream.seek(pos)
+        }
+    }
+
+    #[test]
+    fn span_at_position() -> Result<(), Error> {
+        let mut stream = GrowingMemoryStream::from(vec![0xFF, 0x01, 0x00, 0x02, 0xFF]);
+        stream.seek(SeekFrom::Start(1))?;
+        let patcher = Patcher::<(u8, u16), _>::with_config(stream, CONFIG)?;
+        assert_eq!(patcher.span(), 1..4);
+        assert_eq!(patcher.get(), &(0x01, 0x0002));
+        Ok(())
+    }
+
+    #[test]
+    fn commit_unchanged() -> Result<(), Error> {
+        let stream = CountingStream { stream: GrowingMemoryStream::from(vec![1, 2, 3, 4]), writes: 0 };
+        let mut patcher = Patcher::<[u8; 4], _>::new(stream)?;
+        assert_eq!(patcher.commit(), Ok(0));
+        assert_eq!(patcher.into_inner().writes, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn commit_changed() -> Result<(), Error> {
+        let mut stream =
+            CountingStream { stream: GrowingMemoryStream::from(vec![0xEE, 1, 2, 3, 4, 5, 0xEE]), writes: 0 };
+        stream.seek(SeekFrom::Start(1))?;
+        let mut patcher = Patcher::<([u8; 2], [u8; 3]), _>::new(stream)?;
+        patcher.get_mut().0[1] = 7;
+        patcher.get_mut().1[1] = 8;
+        assert_eq!(patcher.commit(), Ok(2));
+        let stream = patcher.into_inner();
+        assert_eq!(stream.writes, 2);
+        assert_eq!(stream.stream.take(), [0xEE, 1, 7, 3, 8, 5, 0xEE]);
+        Ok(())
+    }
+
+    #[test]
+    fn patch_stream_chunks() -> Result<(), Error> {
+        let mut stream = GrowingMemoryStream::from(vec![0; 200]);
+        let mut bytes = [0; 200];
+        (bytes[10], bytes[150], bytes[160]) = (1, 2, 3);
+        let mut patch = PatchStream { stream: &mut stream, written: 0 };
+        patch.write(&bytes)?;
+        assert_eq!(patch.written, 12);
+        assert_eq!(stream.stream_position(), Ok(200));
+        assert_eq!(stream.take(), bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn commit_size_changed() -> Result<(), Error> {
+        let stream = GrowingMemoryStream::from(vec![b'a', b'b', 0, 0xEE]);
+        let mut patcher = Patcher::<CString, _>::new(stream)?;
+        *patcher.get_mut() = CString::new("abc").unwrap();
+        assert_eq!(
+            patcher.commit(),
+            Err(ErrorKind::Custom("the patched value must serialize to as many bytes as the original").into())
+        );
+        assert_eq!(patcher.into_inner().take(), [b'a', b'b', 0, 0xEE]);
+        Ok(())
+    }
+}
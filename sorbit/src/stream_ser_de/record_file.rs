@@ -0,0 +1,189 @@
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use crate::byte_order::ByteOrder;
+use crate::error::{Error, ErrorKind};
+use crate::io::{Read, Seek, SeekFrom};
+use crate::layout::{StaticLayout, fixed_size};
+use crate::ser_de::Deserialize;
+use crate::stream_ser_de::StreamDeserializer;
+
+/// Random access to a stream of fixed-size serialized records.
+///
+/// The stream is expected to contain records of type `T` one after the other,
+/// with no gaps between them. Since the serialized size of `T` is fixed, the
+/// offset of each record can be computed from its index, and records are only
+/// deserialized when they are accessed. This makes it possible to work with
+/// large record files without loading them into memory entirely. Using this
+/// type with records that don't have a fixed size is a compile time error.
+///
+/// To read records from a [`std::fs::File`], wrap it in an `io::StdStream`
+/// (requires the `std` feature).
+///
+/// ```
+/// use sorbit::byte_order::ByteOrder;
+/// use sorbit::io::GrowingMemoryStream;
+/// use sorbit::stream_ser_de::RecordFile;
+///
+/// let stream = GrowingMemoryStream::from(vec![1, 0, 2, 0, 3, 0]);
+/// let mut records = RecordFile::<u16, _>::new(stream)?.change_byte_order(ByteOrder::LittleEndian);
+/// assert_eq!(records.len(), 3);
+/// assert_eq!(records.get(1), Ok(2));
+/// assert_eq!(records.iter_range(1..3).collect::<Result<Vec<_>, _>>(), Ok(vec![2, 3]));
+/// # Ok::<(), sorbit::error::Error>(())
+/// ```
+pub struct RecordFile<T, Stream> {
+    stream: Stream,
+    byte_order: ByteOrder,
+    len: u64,
+    record: PhantomData<fn() -> T>,
+}
+
+/// An iterator over the records of a [`RecordFile`].
+///
+/// Created by [`RecordFile::iter`] and [`RecordFile::iter_range`].
+pub struct Records<'file, T, Stream> {
+    file: &'file mut RecordFile<T, Stream>,
+    indices: Range<u64>,
+}
+
+impl<T, Stream> RecordFile<T, Stream>
+where
+    T: Deserialize + StaticLayout,
+    Stream: Read + Seek,
+{
+    /// Create a record file over the given stream.
+    ///
+    /// The number of records is determined from the length of the stream. If
+    /// the stream ends with an incomplete record, that record is ignored.
+    pub fn new(mut stream: Stream) -> Result<Self, Error> {
+        let len = stream.stream_len()?.checked_div(Self::record_size()).unwrap_or(0);
+        Ok(Self { stream, byte_order: ByteOrder::native(), len, record: PhantomData })
+    }
+
+    /// Create a new record file that uses the specified byte order.
+    ///
+    /// The default byte order is native byte order, same as for the
+    /// [`StreamDeserializer`].
+    pub fn change_byte_order(self, byte_order: ByteOrder) -> Self {
+        Self { byte_order, ..self }
+    }
+
+    /// Return the number of records in the file.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Return whether the file has no records.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Deserialize the record at `index`.
+    pub fn get(&mut self, index: u64) -> Result<T, Error> {
+        if index >= self.len {
+            return Err(ErrorKind::OutOfBounds.into());
+        }
+        self.stream.seek(SeekFrom::Start(index * Self::record_size()))?;
+        let mut deserializer = StreamDeserializer::new(&mut self.stream).change_byte_order(self.byte_order);
+        T::deserialize(&mut deserializer)
+    }
+
+    /// Iterate over all records in the file.
+    pub fn iter(&mut self) -> Records<'_, T, Stream> {
+        let len = self.len;
+        self.iter_range(0..len)
+    }
+
+    /// Iterate over the records whose index is in `range`.
+    ///
+    /// Indices past the last record are not visited.
+    pub fn iter_range(&mut self, range: Range<u64>) -> Records<'_, T, Stream> {
+        let indices = range.start..core::cmp::min(range.end, self.len);
+        Records { file: self, indices }
+    }
+
+    /// Return the original stream.
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+
+    fn record_size() -> u64 {
+        const { fixed_size(T::LAYOUT) }
+    }
+}
+
+impl<T, Stream> Iterator for Records<'_, T, Stream>
+where
+    T: Deserialize + StaticLayout,
+    Stream: Read + Seek,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next().map(|index| self.file.get(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::try_from(self.indices.end.saturating_sub(self.indices.start)).ok();
+        (len.unwrap_or(usize::MAX), len)
+    }
+}
+
+impl<T, Stream> ExactSizeIterator for Records<'_, T, Stream>
+where
+    T: Deserialize + StaticLayout,
+    Stream: Read + Seek,
+{
+}
+
+impl<T, Stream> DoubleEndedIterator for Records<'_, T, Stream>
+where
+    T: Deserialize + StaticLayout,
+    Stream: Read + Seek,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.indices.next_back().map(|index| self.file.get(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::FixedMemoryStream;
+
+    const BYTES: [u8; 10] = [0x01, 0x00, 0x0A, 0x02, 0x00, 0x0B, 0x03, 0x00, 0x0C, 0xFF];
+
+    fn records() -> RecordFile<(u8, u16), FixedMemoryStream<[u8; 10]>> {
+        RecordFile::new(FixedMemoryStream::new(BYTES)).unwrap().change_byte_order(ByteOrder::BigEndian)
+    }
+
+    #[test]
+    fn len() {
+        assert_eq!(records().len(), 3);
+        assert!(RecordFile::<u32, _>::new(FixedMemoryStream::new([0u8; 3])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn get() {
+        let mut records = records();
+        assert_eq!(records.get(2), Ok((0x03, 0x000C)));
+        assert_eq!(records.get(0), Ok((0x01, 0x000A)));
+        assert_eq!(records.get(3), Err(ErrorKind::OutOfBounds.into()));
+    }
+
+    #[test]
+    fn iter() {
+        let mut records = records();
+        let items: Result<Vec<_>, _> = records.iter().collect();
+        assert_eq!(items, Ok(vec![(0x01, 0x000A), (0x02, 0x000B), (0x03, 0x000C)]));
+        assert_eq!(records.iter().len(), 3);
+    }
+
+    #[test]
+    fn iter_range() {
+        let mut records = records();
+        let items: Result<Vec<_>, _> = records.iter_range(1..5).rev().collect();
+        assert_eq!(items, Ok(vec![(0x03, 0x000C), (0x02, 0x000B)]));
+    }
+}
@@ -0,0 +1,177 @@
+use core::marker::PhantomData;
+
+use crate::error::{Error, ErrorKind};
+use crate::ser_de::{Deserialize, Deserializer};
+
+/// A lazy iterator over a sequence of records of the same type.
+///
+/// The records are deserialized one at a time as the iterator advances, so
+/// sequences that don't fit in memory, like log files with millions of
+/// entries, can be processed record by record. Unlike the [`RecordFile`](super::RecordFile),
+/// the records may have a variable size, and the stream doesn't have to be
+/// seekable.
+///
+/// The sequence either has a known number of records, or it lasts until the
+/// end of the input. The input ends at the end of the stream, or at the end of
+/// the section when iterating within [`deserialize_bounded`](Deserializer::deserialize_bounded).
+/// If the input ends in the middle of a record, the iterator yields an error.
+/// After the first error, the iterator yields no more records.
+///
+/// ```
+/// use sorbit::io::GrowingMemoryStream;
+/// use sorbit::stream_ser_de::{RecordIter, StreamDeserializer};
+///
+/// let stream = GrowingMemoryStream::from(vec![1, 0, 2, 0, 3, 0]);
+/// let mut deserializer = StreamDeserializer::new(stream);
+/// let mut sum = 0;
+/// for record in RecordIter::<u16, _>::new(&mut deserializer) {
+///     sum += record?;
+/// }
+/// assert_eq!(sum, 6);
+/// # Ok::<(), sorbit::error::Error>(())
+/// ```
+pub struct RecordIter<'de, T, D> {
+    deserializer: &'de mut D,
+    remaining: Option<u64>,
+    finished: bool,
+    record: PhantomData<fn() -> T>,
+}
+
+impl<'de, T, D> RecordIter<'de, T, D>
+where
+    T: Deserialize,
+    D: Deserializer<Error = Error>,
+{
+    /// Iterate over records until the end of the input.
+    pub fn new(deserializer: &'de mut D) -> Self {
+        Self { deserializer, remaining: None, finished: false, record: PhantomData }
+    }
+
+    /// Iterate over exactly `count` records.
+    ///
+    /// If the input ends before `count` records, the iterator yields an error.
+    pub fn with_count(deserializer: &'de mut D, count: u64) -> Self {
+        Self { deserializer, remaining: Some(count), finished: false, record: PhantomData }
+    }
+
+    /// Deserialize the next record, or return `None` at the end of the input.
+    fn next_record(&mut self) -> Option<Result<T, Error>> {
+        if self.deserializer.bytes_in_bounds() == Some(0) {
+            return None;
+        }
+        let start = self.deserializer.position();
+        match T::deserialize(self.deserializer) {
+            Err(error) if is_end_of_input(&error) && self.deserializer.position() == start => None,
+            result => Some(result),
+        }
+    }
+}
+
+impl<T, D> Iterator for RecordIter<'_, T, D>
+where
+    T: Deserialize,
+    D: Deserializer<Error = Error>,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let item = match &mut self.remaining {
+            Some(0) => None,
+            Some(remaining) => {
+                *remaining -= 1;
+                Some(T::deserialize(self.deserializer))
+            }
+            None => self.next_record(),
+        };
+        self.finished = !matches!(item, Some(Ok(_)));
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match (self.finished, self.remaining) {
+            (true, _) => (0, Some(0)),
+            (false, Some(remaining)) => (0, usize::try_from(remaining).ok()),
+            (false, None) => (0, None),
+        }
+    }
+}
+
+impl<T, D> core::iter::FusedIterator for RecordIter<'_, T, D>
+where
+    T: Deserialize,
+    D: Deserializer<Error = Error>,
+{
+}
+
+fn is_end_of_input(error: &Error) -> bool {
+    matches!(error.kind(), ErrorKind::UnexpectedEof { bytes_available: 0, .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::FixedMemoryStream;
+    use crate::stream_ser_de::StreamDeserializer;
+
+    const BYTES: [u8; 7] = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0xFF];
+
+    fn deserializer<const N: usize>(bytes: [u8; N]) -> StreamDeserializer<FixedMemoryStream<[u8; N]>> {
+        StreamDeserializer::new(FixedMemoryStream::new(bytes))
+    }
+
+    #[test]
+    fn until_end() {
+        let mut deserializer = deserializer(BYTES);
+        let records: Result<Vec<_>, _> = RecordIter::<u8, _>::new(&mut deserializer).collect();
+        assert_eq!(records, Ok(vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0xFF]));
+    }
+
+    #[test]
+    fn until_end_empty() {
+        let mut deserializer = deserializer([]);
+        assert_eq!(RecordIter::<u32, _>::new(&mut deserializer).next(), None);
+    }
+
+    #[test]
+    fn until_end_truncated() {
+        let mut deserializer = deserializer(BYTES);
+        let mut records = RecordIter::<(u8, u8), _>::new(&mut deserializer);
+        assert_eq!(records.by_ref().take(3).count(), 3);
+        assert!(matches!(records.next(), Some(Err(_))));
+        assert_eq!(records.next(), None);
+    }
+
+    #[test]
+    fn until_end_bounded() {
+        let mut deserializer = deserializer(BYTES);
+        let records = deserializer.deserialize_bounded(4, |deserializer| {
+            RecordIter::<u16, _>::new(deserializer).collect::<Result<Vec<_>, _>>()
+        });
+        assert_eq!(records, Ok(vec![0x0001, 0x0002]));
+    }
+
+    #[test]
+    fn with_count() {
+        let mut deserializer = deserializer(BYTES);
+        let records: Result<Vec<_>, _> = RecordIter::<u16, _>::with_count(&mut deserializer, 2).collect();
+        assert_eq!(records, Ok(vec![0x0001, 0x0002]));
+        assert_eq!(deserializer.deserialize_u8(), Ok(0x03));
+    }
+
+    #[test]
+    fn with_count_exceeds_input() {
+        let mut deserializer = deserializer(BYTES);
+        let records: Result<Vec<_>, _> = RecordIter::<u16, _>::with_count(&mut deserializer, 5).collect();
+        assert!(records.is_err());
+    }
+
+    #[test]
+    fn size_hint() {
+        let mut deserializer = deserializer(BYTES);
+        assert_eq!(RecordIter::<u16, _>::with_count(&mut deserializer, 2).size_hint(), (0, Some(2)));
+        assert_eq!(RecordIter::<u16, _>::new(&mut deserializer).size_hint(), (0, None));
+    }
+}
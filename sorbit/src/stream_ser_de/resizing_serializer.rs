@@ -0,0 +1,499 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::convert::Infallible;
+use core::ops::Range;
+
+use crate::byte_order::ByteOrder;
+use crate::error::{Error, ErrorKind};
+use crate::io::{GrowingMemoryStream, Read, Seek as _, SeekFrom};
+use crate::ser_de::{RevisableSerializer, Serializer, Span};
+use crate::stream_ser_de::{Config, RangeSpan, StreamSerializer, Summary};
+
+/// A [`RevisableSerializer`] that writes into memory, and whose revisions may
+/// change the length of the revised spans.
+///
+/// The [`StreamSerializer`] can only overwrite the bytes of a span with as
+/// many bytes. This serializer instead cuts the span out of the output and
+/// splices the revised bytes in its place, shifting the bytes after it. This
+/// is needed by formats where the width of a field depends on a value that's
+/// only known later, like a varint that stores the byte count of a table.
+///
+/// The spans returned by the serializer follow the shifts, so spans that were
+/// returned before a revision remain valid after it. Spans that overlap a
+/// revised span partially are clamped to the revised bytes.
+///
+/// ```
+/// use sorbit::ser_de::{RevisableSerializer, Serializer, Span};
+/// use sorbit::stream_ser_de::ResizingSerializer;
+///
+/// let mut serializer = ResizingSerializer::new();
+/// let count = serializer.serialize_varint_u64(0)?;
+/// let table = serializer.serialize_slice(&[0xAA; 200])?;
+/// serializer.revise_span(&count, |s| s.serialize_varint_u64(table.len()))?;
+///
+/// assert_eq!(count.len(), 2);
+/// assert_eq!(table.start(), 2);
+/// assert_eq!(serializer.take().take()[..3], [0xC8, 0x01, 0xAA]);
+/// # Ok::<(), sorbit::error::Error>(())
+/// ```
+pub struct ResizingSerializer {
+    inner: StreamSerializer<GrowingMemoryStream>,
+    resizes: Rc<RefCell<Vec<Resize>>>,
+    revising: bool,
+}
+
+/// The [`Span`] of the bytes a [`ResizingSerializer`] has written.
+///
+/// The span follows the shifts caused by the revisions of other spans.
+#[derive(Clone)]
+pub struct ResizingSpan {
+    range: Range<u64>,
+    resizes: Rc<RefCell<Vec<Resize>>>,
+    num_resizes: usize,
+}
+
+/// A revision that changed the end of the revised span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Resize {
+    start: u64,
+    old_end: u64,
+    new_end: u64,
+}
+
+impl Resize {
+    /// Move a position where a range starts.
+    fn shift_start(&self, pos: u64) -> u64 {
+        match pos <= self.start {
+            true => pos,
+            false => self.shift_end(pos),
+        }
+    }
+
+    /// Move a position where a range ends.
+    fn shift_end(&self, pos: u64) -> u64 {
+        match pos >= self.old_end {
+            true => pos - self.old_end + self.new_end,
+            false => core::cmp::min(pos, self.new_end),
+        }
+    }
+}
+
+impl ResizingSerializer {
+    /// Create a new serializer with the default configuration.
+    pub fn new() -> Self {
+        Self::with_config(Config::DEFAULT)
+    }
+
+    /// Create a new serializer with the specified configuration.
+    pub fn with_config(config: Config) -> Self {
+        let inner = StreamSerializer::with_config(GrowingMemoryStream::new(), config);
+        Self { inner, resizes: Rc::default(), revising: false }
+    }
+
+    /// Return the configuration of the serializer.
+    pub fn config(&self) -> Config {
+        self.inner.config()
+    }
+
+    /// Take the serialized bytes from the serializer.
+    pub fn take(self) -> GrowingMemoryStream {
+        self.inner.take()
+    }
+
+    /// Take the serialized bytes from the serializer along with statistics
+    /// about what was written.
+    pub fn finish(self) -> (GrowingMemoryStream, Summary) {
+        self.inner.finish()
+    }
+
+    fn track(&self, span: RangeSpan) -> ResizingSpan {
+        let num_resizes = self.resizes.borrow().len();
+        ResizingSpan { range: span.0, resizes: self.resizes.clone(), num_resizes }
+    }
+
+    /// Replace the bytes in `range` by the bytes `serialize` writes there.
+    fn splice<Output>(
+        &mut self,
+        range: Range<u64>,
+        serialize: impl FnOnce(&mut Self) -> Result<Output, Error>,
+    ) -> Result<Output, Error> {
+        let mut bytes = core::mem::take(&mut self.inner.stream).take();
+        if range.end > bytes.len() as u64 {
+            self.inner.stream = restore_stream(bytes, self.inner.context.absolute_pos())?;
+            return Err(ErrorKind::OutOfBounds.into());
+        }
+        let tail = bytes.split_off(range.end as usize);
+        bytes.truncate(range.start as usize);
+        self.inner.stream = restore_stream(bytes, range.start)?;
+
+        let context = self.inner.context.clone();
+        self.inner.context = context.clone().resume_at(range.start);
+        self.revising = true;
+        let result = serialize(self);
+        self.revising = false;
+
+        let mut bytes = core::mem::take(&mut self.inner.stream).take();
+        let resize = Resize { start: range.start, old_end: range.end, new_end: bytes.len() as u64 };
+        bytes.extend(tail);
+        self.resizes.borrow_mut().push(resize);
+        self.inner.context = context.shift(|pos| resize.shift_start(pos), |pos| resize.shift_end(pos));
+        self.inner.summary.end = resize.shift_end(self.inner.summary.end);
+        self.inner.stream = restore_stream(bytes, self.inner.context.absolute_pos())?;
+        result
+    }
+}
+
+/// Create a stream of the `bytes` positioned at `pos`.
+fn restore_stream(bytes: Vec<u8>, pos: u64) -> Result<GrowingMemoryStream, Error> {
+    let mut stream = GrowingMemoryStream::from(bytes);
+    stream.seek(SeekFrom::Start(pos))?;
+    Ok(stream)
+}
+
+impl Default for ResizingSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResizingSpan {
+    /// Return the current range of the span.
+    fn range(&self) -> Range<u64> {
+        let resizes = self.resizes.borrow();
+        resizes[self.num_resizes..]
+            .iter()
+            .fold(self.range.clone(), |range, resize| resize.shift_start(range.start)..resize.shift_end(range.end))
+    }
+}
+
+impl Span for ResizingSpan {
+    fn start(&self) -> u64 {
+        self.range().start
+    }
+    fn end(&self) -> u64 {
+        self.range().end
+    }
+    fn len(&self) -> u64 {
+        let range = self.range();
+        range.end - range.start
+    }
+}
+
+impl core::fmt::Debug for ResizingSpan {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ResizingSpan").field(&self.range()).finish()
+    }
+}
+
+impl PartialEq for ResizingSpan {
+    fn eq(&self, other: &Self) -> bool {
+        self.range() == other.range()
+    }
+}
+
+impl Eq for ResizingSpan {}
+
+impl Serializer for ResizingSerializer {
+    type Success = ResizingSpan;
+    type Error = Error;
+
+    fn success(&mut self) -> Result<Self::Success, Self::Error> {
+        self.inner.success().map(|span| self.track(span))
+    }
+
+    fn error(&mut self, message: &'static str) -> Result<Infallible, Self::Error> {
+        self.inner.error(message)
+    }
+
+    fn context(&self, key: &str) -> Option<u64> {
+        self.inner.context(key)
+    }
+
+    fn set_context(&mut self, key: &'static str, value: Option<u64>) -> Result<(), Self::Error> {
+        self.inner.set_context(key, value)
+    }
+
+    fn serialize_bool(&mut self, value: bool) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_bool(value).map(|span| self.track(span))
+    }
+
+    fn serialize_u8(&mut self, value: u8) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u8(value).map(|span| self.track(span))
+    }
+
+    fn serialize_u16(&mut self, value: u16) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u16(value).map(|span| self.track(span))
+    }
+
+    fn serialize_u32(&mut self, value: u32) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u32(value).map(|span| self.track(span))
+    }
+
+    fn serialize_u64(&mut self, value: u64) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u64(value).map(|span| self.track(span))
+    }
+
+    fn serialize_u128(&mut self, value: u128) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u128(value).map(|span| self.track(span))
+    }
+
+    fn serialize_i8(&mut self, value: i8) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i8(value).map(|span| self.track(span))
+    }
+
+    fn serialize_i16(&mut self, value: i16) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i16(value).map(|span| self.track(span))
+    }
+
+    fn serialize_i32(&mut self, value: i32) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i32(value).map(|span| self.track(span))
+    }
+
+    fn serialize_i64(&mut self, value: i64) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i64(value).map(|span| self.track(span))
+    }
+
+    fn serialize_i128(&mut self, value: i128) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i128(value).map(|span| self.track(span))
+    }
+
+    fn serialize_f32(&mut self, value: f32) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_f32(value).map(|span| self.track(span))
+    }
+
+    fn serialize_f64(&mut self, value: f64) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_f64(value).map(|span| self.track(span))
+    }
+
+    fn serialize_varint_u64(&mut self, value: u64) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_varint_u64(value).map(|span| self.track(span))
+    }
+
+    fn serialize_varint_i64(&mut self, value: i64) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_varint_i64(value).map(|span| self.track(span))
+    }
+
+    fn serialize_array<const N: usize>(&mut self, value: &[u8; N]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_array(value).map(|span| self.track(span))
+    }
+
+    fn serialize_slice(&mut self, value: &[u8]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_slice(value).map(|span| self.track(span))
+    }
+
+    fn serialize_u16_slice(&mut self, values: &[u16]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u16_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_u32_slice(&mut self, values: &[u32]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u32_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_u64_slice(&mut self, values: &[u64]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u64_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_u128_slice(&mut self, values: &[u128]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_u128_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_i16_slice(&mut self, values: &[i16]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i16_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_i32_slice(&mut self, values: &[i32]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i32_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_i64_slice(&mut self, values: &[i64]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i64_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_i128_slice(&mut self, values: &[i128]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_i128_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_f32_slice(&mut self, values: &[f32]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_f32_slice(values).map(|span| self.track(span))
+    }
+
+    fn serialize_f64_slice(&mut self, values: &[f64]) -> Result<Self::Success, Self::Error> {
+        self.inner.serialize_f64_slice(values).map(|span| self.track(span))
+    }
+
+    fn pad(&mut self, until: u64) -> Result<Self::Success, Self::Error> {
+        self.inner.pad(until).map(|span| self.track(span))
+    }
+
+    fn align(&mut self, multiple_of: u64) -> Result<Self::Success, Self::Error> {
+        self.inner.align(multiple_of).map(|span| self.track(span))
+    }
+
+    fn position(&self) -> u64 {
+        self.inner.position()
+    }
+
+    fn composite_position(&self) -> u64 {
+        self.inner.composite_position()
+    }
+
+    fn is_canonical(&self) -> bool {
+        self.inner.is_canonical()
+    }
+
+    fn serialize_composite<Output>(
+        &mut self,
+        serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<(Self::Success, Output), Self::Error> {
+        use crate::error::TraceError as _;
+        let scope = self.inner.enter_composite();
+        let result = serialize_members(self).map_err(|error| error.locate(self.position()));
+        let span = self.inner.leave_composite(scope);
+        result.map(|output| (self.track(span), output))
+    }
+
+    fn with_byte_order<Output>(
+        &mut self,
+        byte_order: ByteOrder,
+        serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let scope = self.inner.context.byte_order_scope(byte_order);
+        let result = serialize_members(self);
+        self.inner.context.close_byte_order_scope(scope);
+        result
+    }
+
+    fn with_fill<Output>(
+        &mut self,
+        fill: u8,
+        serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let scope = self.inner.context.fill_scope(fill);
+        let result = serialize_members(self);
+        self.inner.context.close_fill_scope(scope);
+        result
+    }
+}
+
+impl RevisableSerializer for ResizingSerializer {
+    fn analyze_span<Output, Error, AnalyzeSpanFn>(
+        &mut self,
+        span: &Self::Success,
+        analyze_span_fn: AnalyzeSpanFn,
+    ) -> Result<Output, Self::Error>
+    where
+        AnalyzeSpanFn: for<'analyze> FnOnce(&mut dyn Read) -> Result<Output, Error>,
+        Error: Into<Self::Error>,
+    {
+        self.inner.analyze_span(&RangeSpan(span.range()), analyze_span_fn)
+    }
+
+    /// Replace the bytes of a previously serialized item.
+    ///
+    /// Unlike other revisable serializers, the revised span may become longer
+    /// or shorter. Spans cannot be revised while another span is revised.
+    fn revise_span<Output>(
+        &mut self,
+        span: &Self::Success,
+        serialize_span: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        if self.revising {
+            return Err(ErrorKind::Custom("spans cannot be revised while another span is revised").into());
+        }
+        self.splice(span.range(), serialize_span)
+    }
+
+    fn serialize_at<Output>(
+        &mut self,
+        offset: u64,
+        serialize: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let position = self.inner.context.local_pos();
+        if position <= offset {
+            self.pad(offset)?;
+            return serialize(self);
+        }
+        self.inner.context.seek(&mut self.inner.stream, offset)?;
+        let result = serialize(self);
+        if self.inner.context.local_pos() < position {
+            self.inner.context.seek(&mut self.inner.stream, position)?;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: Config = Config::DEFAULT.with_byte_order(ByteOrder::BigEndian);
+
+    #[test]
+    fn revise_grow() -> Result<(), Error> {
+        let mut s = ResizingSerializer::with_config(CONFIG);
+        let head = s.serialize_u8(0xAA)?;
+        let count = s.serialize_u8(0)?;
+        let tail = s.serialize_u16(0xBBCC)?;
+        s.revise_span(&count, |s| s.serialize_u32(0x01020304))?;
+        assert_eq!(head, s.track(RangeSpan(0..1)));
+        assert_eq!(count, s.track(RangeSpan(1..5)));
+        assert_eq!(tail, s.track(RangeSpan(5..7)));
+        assert_eq!(s.position(), 7);
+        s.serialize_u8(0xDD)?;
+        assert_eq!(s.take().take(), [0xAA, 1, 2, 3, 4, 0xBB, 0xCC, 0xDD]);
+        Ok(())
+    }
+
+    #[test]
+    fn revise_shrink() -> Result<(), Error> {
+        let mut s = ResizingSerializer::with_config(CONFIG);
+        let (outer, (inner, tail)) = s.serialize_composite(|s| {
+            let inner = s.serialize_u32(0)?;
+            let tail = s.serialize_u8(0xFF)?;
+            Ok((inner, tail))
+        })?;
+        s.revise_span(&inner, |s| s.serialize_u8(7))?;
+        assert_eq!((outer.start(), outer.end()), (0, 2));
+        assert_eq!((inner.start(), inner.end()), (0, 1));
+        assert_eq!((tail.start(), tail.end()), (1, 2));
+        assert_eq!(s.take().take(), [7, 0xFF]);
+        Ok(())
+    }
+
+    #[test]
+    fn revise_twice() -> Result<(), Error> {
+        let mut s = ResizingSerializer::with_config(CONFIG);
+        let a = s.serialize_u8(1)?;
+        let b = s.serialize_u8(2)?;
+        s.revise_span(&b, |s| s.serialize_u16(0x0203))?;
+        s.revise_span(&a, |s| s.serialize_slice(&[])).map(|_| ())?;
+        s.revise_span(&b, |s| s.serialize_u8(4))?;
+        assert_eq!((b.start(), b.end()), (0, 1));
+        assert_eq!(s.take().take(), [4]);
+        Ok(())
+    }
+
+    #[test]
+    fn revise_nested() -> Result<(), Error> {
+        let mut s = ResizingSerializer::new();
+        let a = s.serialize_u8(1)?;
+        let result = s.revise_span(&a.clone(), |s| s.revise_span(&a, |s| s.serialize_u8(2)));
+        assert_eq!(result, Err(ErrorKind::Custom("spans cannot be revised while another span is revised").into()));
+        Ok(())
+    }
+
+    #[test]
+    fn analyze_shifted() -> Result<(), Error> {
+        let mut s = ResizingSerializer::new();
+        let a = s.serialize_u8(1)?;
+        let b = s.serialize_u8(2)?;
+        s.revise_span(&a, |s| s.serialize_array(&[1, 1, 1]))?;
+        let value = s.analyze_span(&b, |stream| {
+            let mut byte = [0];
+            stream.read(&mut byte).map(|_| byte[0])
+        })?;
+        assert_eq!(value, 2);
+        Ok(())
+    }
+}
@@ -1,11 +1,47 @@
 use crate::{
-    byte_order::ByteOrder,
-    error::{Error, ErrorKind},
-    io::Read,
-    ser_de::Deserializer,
-    stream_ser_de::context::Context,
+    byte_order::{ByteOrder, swap_word_bytes},
+    error::{Error, ErrorKind, MagicBytes, TraceError as _},
+    io::{BorrowRead, FixedMemoryStream, Read, Seek, SliceStream},
+    ser_de::{BorrowDeserializer, Deserializer, Limits, SeekableDeserializer},
+    stream_ser_de::{config::Config, context::Context},
 };
 
+#[cfg(feature = "alloc")]
+use crate::stream_ser_de::FieldSpans;
+
+/// A [`StreamDeserializer`] that reads a forward-only stream, but can still
+/// [`peek`](StreamDeserializer::peek) within a bounded window of recently read bytes.
+///
+/// ```
+/// # use sorbit::byte_order::ByteOrder;
+/// # use sorbit::io::{GrowingMemoryStream, LookbackStream};
+/// # use sorbit::ser_de::Deserialize;
+/// # use sorbit::stream_ser_de::BufferedDeserializer;
+/// # let socket = GrowingMemoryStream::from(vec![0xCA, 0xFE, 0x01]);
+/// let stream = LookbackStream::new(socket, 16);
+/// let mut deserializer = BufferedDeserializer::new(stream).change_byte_order(ByteOrder::BigEndian);
+/// let magic = deserializer.peek(|d| u16::deserialize(d)).unwrap();
+/// assert_eq!(magic, 0xCAFE);
+/// assert_eq!(<[u8; 3]>::deserialize(&mut deserializer), Ok([0xCA, 0xFE, 0x01]));
+/// ```
+#[cfg(feature = "alloc")]
+pub type BufferedDeserializer<Stream> = StreamDeserializer<crate::io::LookbackStream<Stream>>;
+
+/// A [`StreamDeserializer`] that reads an in-memory byte slice, and can lend
+/// the bytes it reads as a [`BorrowDeserializer`].
+///
+/// ```
+/// # use sorbit::ser_de::{BorrowDeserializer, Deserialize};
+/// # use sorbit::stream_ser_de::SliceDeserializer;
+/// let bytes = [3, b'a', b'b', b'c'];
+/// let mut deserializer = SliceDeserializer::from_slice(&bytes);
+/// let len = u8::deserialize(&mut deserializer).unwrap();
+/// let payload = deserializer.deserialize_borrowed(len as usize).unwrap();
+/// assert_eq!(payload, b"abc");
+/// assert!(core::ptr::eq(payload, &bytes[1..]));
+/// ```
+pub type SliceDeserializer<'de> = StreamDeserializer<SliceStream<'de>>;
+
 /// A [`Deserializer`] that works with any [`Read`]-able stream.
 ///
 /// The stream can be anything, a file, a TCP stream, or an in-memory
@@ -13,6 +49,13 @@ use crate::{
 pub struct StreamDeserializer<Stream: Read> {
     stream: Stream,
     context: Context,
+    config: Config,
+    #[cfg(feature = "alloc")]
+    recording: Option<alloc::vec::Vec<u8>>,
+    #[cfg(feature = "alloc")]
+    field_spans: Option<FieldSpans>,
+    depth: u32,
+    bytes_read: u64,
 }
 
 macro_rules! from_xe_bytes {
@@ -20,6 +63,7 @@ macro_rules! from_xe_bytes {
         match $byte_order {
             ByteOrder::BigEndian => <$type>::from_be_bytes($bytes),
             ByteOrder::LittleEndian => <$type>::from_le_bytes($bytes),
+            ByteOrder::MiddleEndian => <$type>::from_be_bytes(swap_word_bytes($bytes)),
         }
     };
 }
@@ -37,13 +81,121 @@ impl<Stream: Read> StreamDeserializer<Stream> {
     /// let serializer = StreamDeserializer::new(stream).change_byte_order(ByteOrder::LittleEndian);
     /// ```
     pub fn new(stream: Stream) -> Self {
-        Self { stream, context: Context::default() }
+        Self::with_config(stream, Config::DEFAULT)
+    }
+
+    /// Create a new deserializer with the specified configuration.
+    pub fn with_config(stream: Stream, config: Config) -> Self {
+        let context = Context::default().change_byte_order(config.byte_order).change_fill(config.fill);
+        Self {
+            stream,
+            context,
+            config,
+            #[cfg(feature = "alloc")]
+            recording: None,
+            #[cfg(feature = "alloc")]
+            field_spans: None,
+            depth: 0,
+            bytes_read: 0,
+        }
+    }
+
+    /// Return the configuration of the deserializer.
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Replace the configuration of the deserializer.
+    ///
+    /// Together with [`config`](Self::config), this lets you temporarily
+    /// change the configuration and restore it later.
+    pub fn set_config(&mut self, config: Config) {
+        self.context.set_byte_order(config.byte_order);
+        self.context.set_fill(config.fill);
+        self.config = config;
     }
 
     /// Create a new deserializer that uses the specified byte order.
     pub fn change_byte_order(self, byte_order: ByteOrder) -> Self {
         let context = self.context.change_byte_order(byte_order);
-        Self { context, ..self }
+        Self { context, config: Config { byte_order, ..self.config }, ..self }
+    }
+
+    /// Create a new deserializer that expects padding to be filled with the
+    /// `fill` byte instead of zeros. (See [`verify_fill`](Self::verify_fill).)
+    pub fn change_fill(self, fill: u8) -> Self {
+        let context = self.context.change_fill(fill);
+        Self { context, config: Config { fill, ..self.config }, ..self }
+    }
+
+    /// Create a new deserializer that keeps malformed values.
+    ///
+    /// By default, deserialization is strict, and invalid values result in an
+    /// error. In lenient mode:
+    /// - [`bool`]: any non-zero byte is decoded as `true`,
+    /// - [`RawBool`](crate::lenient::RawBool): any byte is accepted, and kept as is,
+    /// - [`Lenient`](crate::lenient::Lenient): unknown tags of enumerations are
+    ///   kept in their raw representation.
+    ///
+    /// This is useful for forensic and diagnostic tools that inspect malformed
+    /// data. Implementations of [`Deserialize`](crate::ser_de::Deserialize)
+    /// can check for this mode with [`Deserializer::is_lenient`].
+    pub fn lenient(self) -> Self {
+        Self { config: Config { lenient: true, ..self.config }, ..self }
+    }
+
+    /// Create a new deserializer that trusts its input to be valid.
+    ///
+    /// Use this for inputs that have already been validated, like cache files
+    /// that the program has written itself. A trusted deserializer skips the
+    /// validation work that a valid input doesn't need:
+    /// - [`bool`]: any non-zero byte is decoded as `true`, as in [`lenient`](Self::lenient) mode,
+    /// - padding is not verified, even if [`verify_fill`](Self::verify_fill) is set,
+    /// - checksums are not verified. (See [`verify_checksum`](crate::checksum::verify_checksum).)
+    ///
+    /// Resource limits are still enforced, and invalid inputs never cause
+    /// undefined behavior, but they may be deserialized into garbage instead
+    /// of producing an error. [`Deserialize`](crate::ser_de::Deserialize)
+    /// implementations can check for this mode with [`Deserializer::is_trusted`].
+    pub fn trusted(self) -> Self {
+        Self { config: Config { trusted: true, ..self.config }, ..self }
+    }
+
+    /// Create a new deserializer that uses the specified resource limits.
+    pub fn with_limits(self, limits: Limits) -> Self {
+        Self { config: Config { limits, ..self.config }, ..self }
+    }
+
+    /// Create a new deserializer that verifies padding.
+    ///
+    /// Padding is normally skipped without looking at it. In verifying mode,
+    /// deserialization fails with [`ErrorKind::UnexpectedFill`] if padding
+    /// contains any other byte than the fill byte. (See [`change_fill`](Self::change_fill).)
+    pub fn verify_fill(self) -> Self {
+        Self { config: Config { verify_fill: true, ..self.config }, ..self }
+    }
+
+    /// Create a new deserializer that records the byte range each field of a
+    /// derived struct was read from.
+    ///
+    /// The recorded spans are available through [`field_spans`](Self::field_spans).
+    #[cfg(feature = "alloc")]
+    pub fn record_field_spans(self) -> Self {
+        Self { field_spans: Some(FieldSpans::new()), ..self }
+    }
+
+    /// Return the spans of the fields deserialized so far, if recording was
+    /// enabled with [`record_field_spans`](Self::record_field_spans).
+    #[cfg(feature = "alloc")]
+    pub fn field_spans(&self) -> Option<&FieldSpans> {
+        self.field_spans.as_ref()
+    }
+
+    /// Take the spans of the fields deserialized so far, and start recording
+    /// anew.
+    #[cfg(feature = "alloc")]
+    pub fn take_field_spans(&mut self) -> Option<FieldSpans> {
+        self.field_spans.as_mut().map(core::mem::take)
     }
 
     /// Take the serialized bytes from the serializer.
@@ -57,31 +209,151 @@ impl<Stream: Read> StreamDeserializer<Stream> {
     }
 
     fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
-        self.context.read(&mut self.stream, bytes).map(|_| ())
+        let bytes_read = self.count_bytes_read(bytes.len() as u64)?;
+        self.context.read(&mut self.stream, bytes)?;
+        self.bytes_read = bytes_read;
+        #[cfg(feature = "alloc")]
+        if let Some(recording) = &mut self.recording {
+            recording.extend_from_slice(bytes);
+        }
+        Ok(())
+    }
+
+    fn skip_bytes(&mut self, byte_count: u64) -> Result<(), Error> {
+        if byte_count == 0 {
+            return Ok(());
+        }
+        #[cfg(feature = "alloc")]
+        if self.recording.is_some() {
+            let mut buffer = [0u8; 64];
+            let mut remaining = byte_count;
+            while remaining > 0 {
+                let count = core::cmp::min(buffer.len() as u64, remaining) as usize;
+                self.read(&mut buffer[..count])?;
+                remaining -= count as u64;
+            }
+            return Ok(());
+        }
+        let bytes_read = self.count_bytes_read(byte_count)?;
+        self.context.skip(&mut self.stream, byte_count)?;
+        self.bytes_read = bytes_read;
+        Ok(())
+    }
+
+    fn count_bytes_read(&self, len: u64) -> Result<u64, Error> {
+        let bytes_read = self.bytes_read.saturating_add(len);
+        match bytes_read > self.config.limits.max_bytes {
+            true => Err(ErrorKind::ByteLimit.into()),
+            false => Ok(bytes_read),
+        }
     }
 
     fn read_until(&mut self, until: u64) -> Result<(), Error> {
         let mut padding: [u8; 64] = [0; 64];
-        if until < self.context.local_pos() {
-            return Err(ErrorKind::LengthExceedsPadding.into());
+        let position = self.context.local_pos();
+        if until < position {
+            return Err(ErrorKind::LengthExceedsPadding { offset: until, position }.into());
+        }
+        if !self.config.verify_fill || self.config.trusted {
+            return self.skip_bytes(until - position);
         }
         while self.context.local_pos() < until {
             let count = core::cmp::min(padding.len() as u64, until - self.context.local_pos()) as usize;
             self.read(&mut padding[0..count])?;
+            if padding[0..count].iter().any(|byte| *byte != self.context.fill()) {
+                return Err(ErrorKind::UnexpectedFill.into());
+            }
         }
         Ok(())
     }
 }
 
+impl<Stream: Read + Seek> StreamDeserializer<Stream> {
+    /// Deserialize with `peek_fn`, then rewind the stream to where it was.
+    ///
+    /// The result of `peek_fn` is returned whether it succeeded or not, unless
+    /// rewinding the stream fails. Peeking is useful to look at a magic number
+    /// or a tag before deciding how to deserialize the data that follows.
+    /// Forward-only streams can be peeked by wrapping them in a
+    /// [`LookbackStream`](crate::io::LookbackStream), see [`BufferedDeserializer`].
+    pub fn peek<Output>(&mut self, peek_fn: impl FnOnce(&mut Self) -> Result<Output, Error>) -> Result<Output, Error> {
+        let snapshot = self.snapshot();
+        let result = peek_fn(self);
+        self.rollback(snapshot)?;
+        result
+    }
+
+    /// Deserialize with `transaction_fn`, and rewind the stream to where it
+    /// was if it fails.
+    ///
+    /// On success, the deserializer stays where `transaction_fn` left it. On
+    /// failure, the deserializer is restored as if `transaction_fn` had never
+    /// been called, so another alternative can be attempted from the same
+    /// position. The error of `transaction_fn` is returned, unless rewinding
+    /// the stream fails.
+    pub fn transaction<Output>(
+        &mut self,
+        transaction_fn: impl FnOnce(&mut Self) -> Result<Output, Error>,
+    ) -> Result<Output, Error> {
+        let snapshot = self.snapshot();
+        let result = transaction_fn(self);
+        if result.is_err() {
+            self.rollback(snapshot)?;
+        }
+        result
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            context: self.context.clone(),
+            bytes_read: self.bytes_read,
+            #[cfg(feature = "alloc")]
+            recorded_len: self.recording.as_ref().map(|recording| recording.len()),
+            #[cfg(feature = "alloc")]
+            field_spans_len: self.field_spans.as_ref().map(|field_spans| field_spans.len()),
+        }
+    }
+
+    fn rollback(&mut self, snapshot: Snapshot) -> Result<(), Error> {
+        let offset = snapshot.context.absolute_pos() as i64 - self.context.absolute_pos() as i64;
+        self.stream.seek_relative(offset)?;
+        self.context = snapshot.context;
+        self.bytes_read = snapshot.bytes_read;
+        #[cfg(feature = "alloc")]
+        if let (Some(recording), Some(len)) = (&mut self.recording, snapshot.recorded_len) {
+            recording.truncate(len);
+        }
+        #[cfg(feature = "alloc")]
+        if let (Some(field_spans), Some(len)) = (&mut self.field_spans, snapshot.field_spans_len) {
+            field_spans.truncate(len);
+        }
+        Ok(())
+    }
+}
+
+/// The state of a [`StreamDeserializer`] that is restored after peeking or
+/// a failed transaction.
+struct Snapshot {
+    context: Context,
+    /// The bytes read after the snapshot are read again, so they don't count
+    /// toward [`Limits::max_bytes`] twice.
+    bytes_read: u64,
+    #[cfg(feature = "alloc")]
+    recorded_len: Option<usize>,
+    #[cfg(feature = "alloc")]
+    field_spans_len: Option<usize>,
+}
+
 impl<Stream: Read> Deserializer for StreamDeserializer<Stream> {
     type Error = Error;
 
     fn deserialize_bool(&mut self) -> Result<bool, Self::Error> {
         let byte: [u8; 1] = self.read_fixed()?;
-        match byte[0] {
-            0 => Ok(false),
-            1 => Ok(true),
-            _ => Err(ErrorKind::InvalidEnumVariant.into()),
+        match (byte[0], self.config.lenient || self.config.trusted) {
+            (0, _) => Ok(false),
+            (1, _) => Ok(true),
+            (_, true) => Ok(true),
+            (_, false) => Err(ErrorKind::InvalidEnumVariant.into()),
         }
     }
 
@@ -125,6 +397,14 @@ impl<Stream: Read> Deserializer for StreamDeserializer<Stream> {
         Ok(from_xe_bytes!(i128, self.read_fixed()?, self.context.byte_order()))
     }
 
+    fn deserialize_f32(&mut self) -> Result<f32, Self::Error> {
+        Ok(from_xe_bytes!(f32, self.read_fixed()?, self.context.byte_order()))
+    }
+
+    fn deserialize_f64(&mut self) -> Result<f64, Self::Error> {
+        Ok(from_xe_bytes!(f64, self.read_fixed()?, self.context.byte_order()))
+    }
+
     fn deserialize_array<const N: usize>(&mut self) -> Result<[u8; N], Self::Error> {
         self.read_fixed()
     }
@@ -138,17 +418,43 @@ impl<Stream: Read> Deserializer for StreamDeserializer<Stream> {
     }
 
     fn align(&mut self, multiple_of: u64) -> Result<(), Self::Error> {
-        let until = (self.context.local_pos() + multiple_of - 1) / multiple_of * multiple_of;
+        let until = self.context.local_pos().div_ceil(multiple_of) * multiple_of;
         self.pad(until)
     }
 
+    fn position(&self) -> u64 {
+        self.context.absolute_pos()
+    }
+
+    fn composite_position(&self) -> u64 {
+        self.context.local_pos()
+    }
+
+    fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    fn context(&self, key: &str) -> Option<u64> {
+        self.context.value(key)
+    }
+
+    fn set_context(&mut self, key: &'static str, value: Option<u64>) -> Result<(), Self::Error> {
+        self.context.set_value(key, value)
+    }
+
     fn deserialize_composite<O>(
         &mut self,
         deserialize_members: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
     ) -> Result<O, Self::Error> {
+        if self.depth >= self.config.limits.max_depth {
+            return self.recursion_limit();
+        }
+        self.depth += 1;
+        let start = self.position();
         let scope = self.context.composite_scope();
-        let result = deserialize_members(self);
+        let result = deserialize_members(self).map_err(|error| error.locate(start));
         self.context.close_composite_scope(scope);
+        self.depth -= 1;
         result
     }
 
@@ -163,6 +469,17 @@ impl<Stream: Read> Deserializer for StreamDeserializer<Stream> {
         result
     }
 
+    fn with_fill<O>(
+        &mut self,
+        fill: u8,
+        deserialize_members: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<O, Self::Error> {
+        let scope = self.context.fill_scope(fill);
+        let result = deserialize_members(self);
+        self.context.close_fill_scope(scope);
+        result
+    }
+
     fn deserialize_bounded<O>(
         &mut self,
         byte_count: u64,
@@ -178,9 +495,125 @@ impl<Stream: Read> Deserializer for StreamDeserializer<Stream> {
         self.context.bytes_in_bounds()
     }
 
+    #[cfg(feature = "alloc")]
+    fn deserialize_recorded<O>(
+        &mut self,
+        deserialize_object: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<(O, alloc::vec::Vec<u8>), Self::Error> {
+        let outer = self.recording.replace(alloc::vec::Vec::new());
+        let result = deserialize_object(self);
+        let recorded = core::mem::replace(&mut self.recording, outer).unwrap_or_default();
+        if let Some(outer) = &mut self.recording {
+            outer.extend_from_slice(&recorded);
+        }
+        result.map(|object| (object, recorded))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_field<O>(
+        &mut self,
+        name: &'static str,
+        deserialize_field: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<O, Self::Error> {
+        let position = self.position();
+        if let Some(field_spans) = &mut self.field_spans {
+            field_spans.enter(name, position);
+        }
+        let result = deserialize_field(self);
+        let position = self.position();
+        if let Some(field_spans) = &mut self.field_spans {
+            field_spans.leave(position);
+        }
+        result
+    }
+
+    fn limits(&self) -> Limits {
+        self.config.limits
+    }
+
+    fn is_lenient(&self) -> bool {
+        self.config.lenient
+    }
+
+    fn is_trusted(&self) -> bool {
+        self.config.trusted
+    }
+
     fn error<O>(&self, message: &'static str) -> Result<O, Self::Error> {
         Err(Self::Error::from(ErrorKind::Custom(message)))
     }
+
+    fn invalid_enum_variant<O>(&self) -> Result<O, Self::Error> {
+        Err(Self::Error::from(ErrorKind::InvalidEnumVariant))
+    }
+
+    fn invalid_value<O>(&self) -> Result<O, Self::Error> {
+        Err(Self::Error::from(ErrorKind::InvalidValue))
+    }
+
+    fn no_matching_variant<O>(&self, errors: impl IntoIterator<Item = Self::Error>) -> Result<O, Self::Error> {
+        Err(Error::no_matching_variant(errors))
+    }
+
+    fn bad_magic<O>(&self, expected: &'static [u8], found: &[u8]) -> Result<O, Self::Error> {
+        Err(Self::Error::from(ErrorKind::BadMagic { expected, found: MagicBytes::new(found) }))
+    }
+
+    fn recursion_limit<O>(&self) -> Result<O, Self::Error> {
+        Err(Self::Error::from(ErrorKind::RecursionLimit))
+    }
+
+    fn length_limit<O>(&self) -> Result<O, Self::Error> {
+        Err(Self::Error::from(ErrorKind::LengthLimit))
+    }
+}
+
+impl<'de> SliceDeserializer<'de> {
+    /// Create a new deserializer that reads `bytes`.
+    pub fn from_slice(bytes: &'de [u8]) -> Self {
+        Self::new(FixedMemoryStream::new(bytes))
+    }
+}
+
+impl<'de, Stream: BorrowRead<'de>> BorrowDeserializer<'de> for StreamDeserializer<Stream> {
+    fn deserialize_borrowed(&mut self, len: usize) -> Result<&'de [u8], Self::Error> {
+        let bytes_read = self.count_bytes_read(len as u64)?;
+        let bytes = self.context.read_borrowed(&mut self.stream, len)?;
+        self.bytes_read = bytes_read;
+        #[cfg(feature = "alloc")]
+        if let Some(recording) = &mut self.recording {
+            recording.extend_from_slice(bytes);
+        }
+        Ok(bytes)
+    }
+}
+
+impl<Stream: Read + Seek> SeekableDeserializer for StreamDeserializer<Stream> {
+    fn seek(&mut self, until: u64) -> Result<(), Self::Error> {
+        self.context.seek(&mut self.stream, until)
+    }
+
+    fn peek<O>(&mut self, peek_fn: impl FnOnce(&mut Self) -> Result<O, Self::Error>) -> Result<O, Self::Error> {
+        StreamDeserializer::peek(self, peek_fn)
+    }
+
+    fn transaction<O>(
+        &mut self,
+        transaction_fn: impl FnOnce(&mut Self) -> Result<O, Self::Error>,
+    ) -> Result<O, Self::Error> {
+        StreamDeserializer::transaction(self, transaction_fn)
+    }
+
+    fn deserialize_at<T>(
+        &mut self,
+        position: u64,
+        deserialize: impl FnOnce(&mut Self) -> Result<T, Self::Error>,
+    ) -> Result<T, Self::Error> {
+        self.peek(|s| {
+            s.context.seek_absolute(&mut s.stream, position)?;
+            deserialize(s)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -192,6 +625,53 @@ mod tests {
         io::{FixedMemoryStream, Seek},
     };
 
+    //--------------------------------------------------------------------------
+    // Config
+    //--------------------------------------------------------------------------
+    #[test]
+    fn with_config() {
+        let config = Config::DEFAULT
+            .with_byte_order(ByteOrder::BigEndian)
+            .with_lenient(true)
+            .with_limits(Limits::DEFAULT.with_max_preallocation(16));
+        let mut s = StreamDeserializer::with_config(FixedMemoryStream::new([0xAA, 0xBB, 45u8]), config);
+        assert_eq!(s.config(), config);
+        assert_eq!(s.limits(), config.limits);
+        assert_eq!(s.deserialize_u16(), Ok(0xAABB));
+        assert_eq!(s.deserialize_bool(), Ok(true));
+    }
+
+    #[test]
+    fn builder_methods_update_config() {
+        let limits = Limits::DEFAULT.with_max_preallocation(16);
+        let s = StreamDeserializer::new(FixedMemoryStream::new([0u8]))
+            .change_byte_order(ByteOrder::BigEndian)
+            .lenient()
+            .with_limits(limits)
+            .change_fill(0xFF)
+            .verify_fill()
+            .trusted();
+        let expected = Config::DEFAULT
+            .with_byte_order(ByteOrder::BigEndian)
+            .with_lenient(true)
+            .with_limits(limits)
+            .with_fill(0xFF)
+            .with_verify_fill(true)
+            .with_trusted(true);
+        assert_eq!(s.config(), expected);
+    }
+
+    #[test]
+    fn set_config() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC, 0xDD]))
+            .change_byte_order(ByteOrder::BigEndian);
+        let saved = s.config();
+        s.set_config(saved.with_byte_order(ByteOrder::LittleEndian));
+        assert_eq!(s.deserialize_u16(), Ok(0xBBAA));
+        s.set_config(saved);
+        assert_eq!(s.deserialize_u16(), Ok(0xCCDD));
+    }
+
     //--------------------------------------------------------------------------
     // bool
     //--------------------------------------------------------------------------
@@ -203,6 +683,22 @@ mod tests {
         assert_eq!(s.deserialize_bool(), Err(ErrorKind::InvalidEnumVariant.into()));
     }
 
+    #[test]
+    fn deserialize_bool_lenient() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0u8, 1u8, 45u8])).lenient();
+        assert_eq!(s.deserialize_bool(), Ok(false));
+        assert_eq!(s.deserialize_bool(), Ok(true));
+        assert_eq!(s.deserialize_bool(), Ok(true));
+    }
+
+    #[test]
+    fn deserialize_bool_trusted() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0u8, 45u8])).trusted();
+        assert!(s.is_trusted());
+        assert_eq!(s.deserialize_bool(), Ok(false));
+        assert_eq!(s.deserialize_bool(), Ok(true));
+    }
+
     //--------------------------------------------------------------------------
     // u* be
     //--------------------------------------------------------------------------
@@ -359,6 +855,68 @@ mod tests {
         assert_eq!(s.deserialize_i128(), Ok(0xDEADBEEF_FEEDDEAF_DEADBEEF_FEEDDEAFu128.cast_signed()));
     }
 
+    //--------------------------------------------------------------------------
+    // f*
+    //--------------------------------------------------------------------------
+    #[test]
+    fn deserialize_f32_be() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x3F, 0xC0, 0x00, 0x00]))
+            .change_byte_order(ByteOrder::BigEndian);
+        assert_eq!(s.deserialize_f32(), Ok(1.5));
+    }
+
+    #[test]
+    fn deserialize_f64_be() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x3F, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]))
+            .change_byte_order(ByteOrder::BigEndian);
+        assert_eq!(s.deserialize_f64(), Ok(1.5));
+    }
+
+    #[test]
+    fn deserialize_f32_le() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x00, 0x00, 0xC0, 0x3F]))
+            .change_byte_order(ByteOrder::LittleEndian);
+        assert_eq!(s.deserialize_f32(), Ok(1.5));
+    }
+
+    #[test]
+    fn deserialize_f64_le() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, 0x3F]))
+            .change_byte_order(ByteOrder::LittleEndian);
+        assert_eq!(s.deserialize_f64(), Ok(1.5));
+    }
+
+    //--------------------------------------------------------------------------
+    // Middle endian
+    //--------------------------------------------------------------------------
+    #[test]
+    fn deserialize_u16_me() {
+        let mut s =
+            StreamDeserializer::new(FixedMemoryStream::new([0xEF, 0xBE])).change_byte_order(ByteOrder::MiddleEndian);
+        assert_eq!(s.deserialize_u16(), Ok(0xBEEF));
+    }
+
+    #[test]
+    fn deserialize_u32_me() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xAD, 0xDE, 0xEF, 0xBE]))
+            .change_byte_order(ByteOrder::MiddleEndian);
+        assert_eq!(s.deserialize_u32(), Ok(0xDEADBEEF));
+    }
+
+    #[test]
+    fn deserialize_u64_me() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x02, 0x01, 0x04, 0x03, 0x06, 0x05, 0x08, 0x07]))
+            .change_byte_order(ByteOrder::MiddleEndian);
+        assert_eq!(s.deserialize_u64(), Ok(0x0102030405060708));
+    }
+
+    #[test]
+    fn deserialize_f32_me() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xC0, 0x3F, 0x00, 0x00]))
+            .change_byte_order(ByteOrder::MiddleEndian);
+        assert_eq!(s.deserialize_f32(), Ok(1.5));
+    }
+
     //--------------------------------------------------------------------------
     // Array & slice
     //--------------------------------------------------------------------------
@@ -378,6 +936,39 @@ mod tests {
         assert_eq!(slc, [0xAF, 0xDE, 0xED]);
     }
 
+    //--------------------------------------------------------------------------
+    // Variable-length integers
+    //--------------------------------------------------------------------------
+    #[test]
+    fn deserialize_varint_u64() {
+        let bytes = [
+            0x00, 0xAC, 0x02, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
+        ];
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new(bytes));
+        assert_eq!(s.deserialize_varint_u64(), Ok(0));
+        assert_eq!(s.deserialize_varint_u64(), Ok(300));
+        assert_eq!(s.deserialize_varint_u64(), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn deserialize_varint_u64_too_large() {
+        let bytes = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new(bytes));
+        assert_eq!(
+            s.deserialize_varint_u64(),
+            Err(ErrorKind::Custom("the variable-length integer is too large").into())
+        );
+    }
+
+    #[test]
+    fn deserialize_varint_i64() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x00, 0x01, 0x02, 0xAB, 0x02]));
+        assert_eq!(s.deserialize_varint_i64(), Ok(0));
+        assert_eq!(s.deserialize_varint_i64(), Ok(-1));
+        assert_eq!(s.deserialize_varint_i64(), Ok(1));
+        assert_eq!(s.deserialize_varint_i64(), Ok(-150));
+    }
+
     //--------------------------------------------------------------------------
     // Composites
     //--------------------------------------------------------------------------
@@ -390,6 +981,51 @@ mod tests {
         assert_eq!(s.deserialize_u8(), Ok(0xFF));
     }
 
+    #[test]
+    fn deserialize_composite_locates_error() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0x02]));
+        let result = s.deserialize_composite(|s| {
+            s.deserialize_u8()?;
+            s.deserialize_composite(|s| s.deserialize_bool())?;
+            s.deserialize_u8()
+        });
+        assert_eq!(result.map_err(|error| error.offset()), Err(Some(1)));
+    }
+
+    #[test]
+    fn deserialize_composite_depth() {
+        let limits = Limits::DEFAULT.with_max_depth(2);
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE])).with_limits(limits);
+        assert_eq!(s.deserialize_composite(|s| s.deserialize_composite(|s| Ok(s.depth()))), Ok(2));
+        let nested =
+            s.deserialize_composite(|s| s.deserialize_composite(|s| s.deserialize_composite(|s| Ok(s.depth()))));
+        assert_eq!(nested, Err(Error::from(ErrorKind::RecursionLimit).locate(0)));
+        assert_eq!(s.depth(), 0);
+        assert_eq!(s.deserialize_u8(), Ok(0xEE));
+    }
+
+    #[test]
+    fn context_scoped_to_composite() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([]));
+        s.set_context("version", Some(1)).unwrap();
+        let inner = s.deserialize_composite(|s| {
+            s.set_context("version", Some(2))?;
+            s.deserialize_composite(|s| Ok(s.context("version")))
+        });
+        assert_eq!(inner, Ok(Some(2)));
+        assert_eq!(s.context("version"), Some(1));
+    }
+
+    #[test]
+    fn deserialize_byte_limit() {
+        let limits = Limits::DEFAULT.with_max_bytes(3);
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xAA, 0xBB, 0xFF])).with_limits(limits);
+        assert_eq!(s.deserialize_u16().map(|_| ()), Ok(()));
+        assert_eq!(s.deserialize_u16(), Err(ErrorKind::ByteLimit.into()));
+        assert_eq!(s.deserialize_u8(), Ok(0xBB));
+        assert_eq!(s.deserialize_u8(), Err(ErrorKind::ByteLimit.into()));
+    }
+
     //--------------------------------------------------------------------------
     // Byte order
     //--------------------------------------------------------------------------
@@ -402,6 +1038,31 @@ mod tests {
         assert_eq!(s.deserialize_u16(), Ok(0xFFEE));
     }
 
+    #[test]
+    fn nested_byte_order() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xBB, 0xAA, 0xCC, 0xDD, 0xBB, 0xAA]));
+        let result = s.with_byte_order(ByteOrder::LittleEndian, |s| {
+            let first = s.deserialize_u16()?;
+            let second =
+                s.deserialize_composite(|s| s.with_byte_order(ByteOrder::BigEndian, |s| s.deserialize_u16()))?;
+            let third = s.deserialize_u16()?;
+            Ok((first, second, third))
+        });
+        assert_eq!(result, Ok((0xAABB, 0xCCDD, 0xAABB)));
+    }
+
+    #[test]
+    fn byte_order_restored_on_error() {
+        let mut s =
+            StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC])).change_byte_order(ByteOrder::BigEndian);
+        let result = s.with_byte_order(ByteOrder::LittleEndian, |s| {
+            s.deserialize_u8()?;
+            s.deserialize_bounded(1, |s| s.deserialize_u16())
+        });
+        assert_eq!(result, Err(ErrorKind::OutOfBounds.into()));
+        assert_eq!(s.deserialize_u16(), Ok(0xBBCC));
+    }
+
     //--------------------------------------------------------------------------
     // Deserialize bounded
     //--------------------------------------------------------------------------
@@ -445,7 +1106,7 @@ mod tests {
         let mut s =
             StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC])).change_byte_order(ByteOrder::BigEndian);
         s.deserialize_array::<3>()?;
-        assert_eq!(s.pad(2), Err(ErrorKind::LengthExceedsPadding.into()));
+        assert_eq!(s.pad(2), Err(ErrorKind::LengthExceedsPadding { offset: 2, position: 3 }.into()));
         Ok(())
     }
 
@@ -464,6 +1125,136 @@ mod tests {
         assert_eq!(s.deserialize_u8(), Ok(0xAF));
     }
 
+    #[test]
+    fn pad_verify_fill() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xFF, 0x20, 0x20, 0x20]))
+            .change_fill(0xFF)
+            .verify_fill();
+        assert_eq!(s.deserialize_u8(), Ok(0xEE));
+        assert_eq!(s.pad(2), Ok(()));
+        assert_eq!(s.with_fill(0x20, |s| s.pad(4)), Ok(()));
+        assert_eq!(s.pad(5), Err(ErrorKind::UnexpectedFill.into()));
+    }
+
+    #[test]
+    fn pad_trusted() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0x20, 0x20]))
+            .change_fill(0xFF)
+            .verify_fill()
+            .trusted();
+        assert_eq!(s.deserialize_u8(), Ok(0xEE));
+        assert_eq!(s.pad(3), Ok(()));
+    }
+
+    #[test]
+    fn pad_unverified_fill() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xFF, 0x20])).change_fill(0xFF);
+        assert_eq!(s.deserialize_u8(), Ok(0xEE));
+        assert_eq!(s.pad(3), Ok(()));
+    }
+
+    //--------------------------------------------------------------------------
+    // Skip
+    //--------------------------------------------------------------------------
+    #[test]
+    fn skip() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xFF, 0x20, 0x28]));
+        assert_eq!(s.skip(2), Ok(()));
+        assert_eq!(s.position(), 2);
+        assert_eq!(s.deserialize_u8(), Ok(0x20));
+        assert_eq!(s.skip(2), Err(ErrorKind::UnexpectedEof { bytes_needed: 2, bytes_available: 1 }.into()));
+    }
+
+    #[test]
+    fn skip_to() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xFF, 0x20, 0x28]));
+        assert_eq!(s.deserialize_u8(), Ok(0xEE));
+        let value = s.deserialize_composite(|s| {
+            s.skip_to(2)?;
+            s.deserialize_u8()
+        });
+        assert_eq!(value, Ok(0x28));
+        assert!(s.skip_to(1).is_err());
+    }
+
+    #[test]
+    fn skip_unverified() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xFF, 0x20])).verify_fill();
+        assert_eq!(s.skip(2), Ok(()));
+        assert_eq!(s.deserialize_u8(), Ok(0x20));
+    }
+
+    #[test]
+    fn skip_byte_limit() {
+        let limits = Limits::DEFAULT.with_max_bytes(2);
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xFF, 0x20])).with_limits(limits);
+        assert_eq!(s.skip(3), Err(ErrorKind::ByteLimit.into()));
+    }
+
+    #[test]
+    fn skip_bounded() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xFF, 0x20]));
+        assert_eq!(s.deserialize_bounded(2, |s| s.skip(3)), Err(ErrorKind::OutOfBounds.into()));
+        assert_eq!(s.deserialize_bounded(2, |s| s.skip(2).and_then(|_| s.skip(0))), Ok(()));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn skip_recorded() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xEE, 0xFF, 0x20]));
+        assert_eq!(s.deserialize_recorded(|s| s.skip(2)), Ok(((), vec![0xEE, 0xFF])));
+        assert_eq!(s.deserialize_u8(), Ok(0x20));
+    }
+
+    //--------------------------------------------------------------------------
+    // Seek
+    //--------------------------------------------------------------------------
+    #[test]
+    fn seek_backward() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC, 0xDD]));
+        assert_eq!(s.deserialize_array(), Ok([0xAA]));
+        assert_eq!(
+            s.deserialize_composite(|s| {
+                let first = s.deserialize_array::<2>()?;
+                s.seek(1)?;
+                let second = s.deserialize_array::<2>()?;
+                Ok((first, second))
+            }),
+            Ok(([0xBB, 0xCC], [0xCC, 0xDD]))
+        );
+        assert_eq!(s.take().stream_position(), Ok(4));
+    }
+
+    #[test]
+    fn seek_forward() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC, 0xDD]));
+        assert_eq!(s.seek(3), Ok(()));
+        assert_eq!(s.deserialize_array(), Ok([0xDD]));
+    }
+
+    #[test]
+    fn seek_out_of_bounds() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC, 0xDD]));
+        assert_eq!(s.deserialize_array(), Ok([0xAA]));
+        assert_eq!(s.deserialize_bounded(2, |s| s.seek(0)), Err(ErrorKind::OutOfBounds.into()));
+    }
+
+    #[test]
+    fn deserialize_overlay_within() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC, 0xDD]));
+        assert_eq!(s.deserialize_array(), Ok([0xAA, 0xBB, 0xCC]));
+        assert_eq!(s.deserialize_overlay(1, |s| s.deserialize_array()), Ok([0xBB]));
+        assert_eq!(s.deserialize_array(), Ok([0xDD]));
+    }
+
+    #[test]
+    fn deserialize_overlay_past_end() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC, 0xDD]));
+        assert_eq!(s.deserialize_array(), Ok([0xAA, 0xBB]));
+        assert_eq!(s.deserialize_overlay(1, |s| s.deserialize_array()), Ok([0xBB, 0xCC]));
+        assert_eq!(s.deserialize_array(), Ok([0xDD]));
+    }
+
     //--------------------------------------------------------------------------
     // Alignment
     //--------------------------------------------------------------------------
@@ -491,4 +1282,190 @@ mod tests {
         );
         assert_eq!(s.deserialize_bool(), Ok(true));
     }
+
+    #[test]
+    fn align_absolute_composite() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x01, 0x62, 0x85, 0x28, 0x00, 0x01]));
+        assert_eq!(s.deserialize_bool(), Ok(true));
+        assert_eq!(
+            s.deserialize_composite(|s| {
+                let value = s.deserialize_array()?;
+                s.align_absolute(5).map(|_| value)
+            }),
+            Ok([0x62, 0x85, 0x28])
+        );
+        assert_eq!(s.deserialize_bool(), Ok(true));
+    }
+
+    //--------------------------------------------------------------------------
+    // Position
+    //--------------------------------------------------------------------------
+    #[test]
+    fn position() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0xAA, 0xBB, 0xCC]));
+        assert_eq!(s.deserialize_u8(), Ok(0xAA));
+        assert_eq!((s.position(), s.composite_position()), (1, 1));
+        let positions = s.deserialize_composite(|s| {
+            let start = (s.position(), s.composite_position());
+            s.deserialize_u16()?;
+            Ok((start, (s.position(), s.composite_position())))
+        });
+        assert_eq!(positions, Ok(((1, 0), (3, 2))));
+        assert_eq!((s.position(), s.composite_position()), (3, 3));
+    }
+
+    //--------------------------------------------------------------------------
+    // Peek
+    //--------------------------------------------------------------------------
+    #[test]
+    fn peek_rewinds() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x01, 0x62, 0x85]));
+        assert_eq!(s.deserialize_bool(), Ok(true));
+        assert_eq!(s.peek(|s| s.deserialize_composite(|s| s.deserialize_array())), Ok([0x62, 0x85]));
+        assert_eq!(s.deserialize_array(), Ok([0x62, 0x85]));
+    }
+
+    #[test]
+    fn peek_rewinds_on_error() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x62, 0x85]));
+        assert!(s.peek(|s| s.deserialize_u32()).is_err());
+        assert_eq!(s.deserialize_array(), Ok([0x62, 0x85]));
+    }
+
+    #[test]
+    fn peek_byte_limit() {
+        let limits = Limits::DEFAULT.with_max_bytes(2);
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x62, 0x85, 0xFF])).with_limits(limits);
+        assert_eq!(s.peek(|s| s.deserialize_u8()), Ok(0x62));
+        assert_eq!(s.peek(|s| s.deserialize_u16()), Ok(0x8562));
+        assert_eq!(s.deserialize_array(), Ok([0x62, 0x85]));
+        assert_eq!(s.deserialize_u8(), Err(ErrorKind::ByteLimit.into()));
+    }
+
+    #[test]
+    fn peek_generic() {
+        fn peek_tag<D: SeekableDeserializer>(deserializer: &mut D) -> Result<u8, D::Error> {
+            deserializer.peek(|deserializer| deserializer.deserialize_u8())
+        }
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x62, 0x85]));
+        assert_eq!(peek_tag(&mut s), Ok(0x62));
+        assert_eq!(s.deserialize_array(), Ok([0x62, 0x85]));
+    }
+
+    //--------------------------------------------------------------------------
+    // Transaction
+    //--------------------------------------------------------------------------
+    #[test]
+    fn transaction_commits() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x01, 0x62, 0x85]));
+        assert_eq!(s.transaction(|s| s.deserialize_bool()), Ok(true));
+        assert_eq!(s.deserialize_array(), Ok([0x62, 0x85]));
+    }
+
+    #[test]
+    fn transaction_rolls_back() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x01, 0x62, 0x85]));
+        assert!(s.transaction(|s| s.deserialize_array::<3>().and_then(|_| s.deserialize_u8())).is_err());
+        assert_eq!(s.deserialize_array(), Ok([0x01, 0x62, 0x85]));
+    }
+
+    #[test]
+    fn transaction_rolls_back_byte_limit() {
+        let limits = Limits::DEFAULT.with_max_bytes(2);
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x62, 0x85])).with_limits(limits);
+        assert!(s.transaction(|s| s.deserialize_u8().and_then(|_| s.deserialize_u16())).is_err());
+        assert_eq!(s.deserialize_array(), Ok([0x62, 0x85]));
+    }
+
+    #[test]
+    fn transaction_alternatives() {
+        fn deserialize_either<D: SeekableDeserializer>(deserializer: &mut D) -> Result<u32, D::Error> {
+            deserializer
+                .transaction(|deserializer| deserializer.deserialize_u32())
+                .or_else(|_| deserializer.deserialize_u16().map(u32::from))
+        }
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x62, 0x85]));
+        assert_eq!(deserialize_either(&mut s), Ok(0x8562));
+    }
+
+    //--------------------------------------------------------------------------
+    // Deserialize at
+    //--------------------------------------------------------------------------
+    #[test]
+    fn deserialize_at_restores_position() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x01, 0x62, 0x85, 0x28]));
+        assert_eq!(s.deserialize_u8(), Ok(0x01));
+        let value = s.deserialize_composite(|s| {
+            s.deserialize_u8()?;
+            s.deserialize_at(0, |s| s.deserialize_u8())
+        });
+        assert_eq!(value, Ok(0x01));
+        assert_eq!(s.deserialize_array(), Ok([0x85, 0x28]));
+    }
+
+    #[test]
+    fn deserialize_at_generic() {
+        fn deserialize_pointee<D: SeekableDeserializer>(deserializer: &mut D) -> Result<u8, D::Error> {
+            let position = deserializer.deserialize_u8()?;
+            deserializer.deserialize_at(position.into(), |deserializer| deserializer.deserialize_u8())
+        }
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x02, 0x62, 0x85]));
+        assert_eq!(deserialize_pointee(&mut s), Ok(0x85));
+        assert_eq!(s.deserialize_u8(), Ok(0x62));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn peek_buffered() {
+        struct Forward<'a>(&'a [u8]);
+        impl Read for Forward<'_> {
+            fn read(&mut self, bytes: &mut [u8]) -> Result<(), Error> {
+                let (head, tail) = self.0.split_at_checked(bytes.len()).ok_or(ErrorKind::OutOfBounds)?;
+                bytes.copy_from_slice(head);
+                self.0 = tail;
+                Ok(())
+            }
+        }
+
+        let stream = crate::io::LookbackStream::new(Forward(&[0x01, 0x62, 0x85]), 2);
+        let mut s = BufferedDeserializer::new(stream);
+        assert_eq!(s.deserialize_bool(), Ok(true));
+        assert_eq!(s.peek(|s| s.deserialize_array()), Ok([0x62, 0x85]));
+        assert_eq!(s.deserialize_array(), Ok([0x62, 0x85]));
+    }
+
+    //--------------------------------------------------------------------------
+    // Record
+    //--------------------------------------------------------------------------
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn record() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x01, 0x62, 0x85, 0x28]));
+        assert_eq!(s.deserialize_bool(), Ok(true));
+        assert_eq!(s.deserialize_recorded(|s| s.deserialize_array::<2>()), Ok(([0x62, 0x85], vec![0x62, 0x85])));
+        assert_eq!(s.deserialize_u8(), Ok(0x28));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn record_nested() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x01, 0x62, 0x85]));
+        let recorded = s.deserialize_recorded(|s| {
+            let first = s.deserialize_u8()?;
+            let (second, inner) = s.deserialize_recorded(|s| s.deserialize_u8())?;
+            Ok((first, second, inner))
+        });
+        assert_eq!(recorded, Ok(((0x01, 0x62, vec![0x62]), vec![0x01, 0x62])));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn record_peek() {
+        let mut s = StreamDeserializer::new(FixedMemoryStream::new([0x01, 0x62]));
+        let recorded = s.deserialize_recorded(|s| {
+            s.peek(|s| s.deserialize_u8())?;
+            s.deserialize_u8()
+        });
+        assert_eq!(recorded, Ok((0x01, vec![0x01])));
+    }
 }
@@ -1,12 +1,39 @@
 use core::convert::Infallible;
 
-use crate::io::{Read, Seek, SeekFrom, StreamSection, Write};
+use crate::io::{NullStream, Read, Seek, SeekFrom, StreamSection, Write};
 use crate::ser_de::RevisableSerializer;
 
-use crate::byte_order::ByteOrder;
-use crate::error::{Error, ErrorKind};
+use crate::byte_order::{ByteOrder, swap_word_bytes};
+use crate::error::{Error, ErrorKind, TraceError as _};
 use crate::ser_de::Serializer;
-use crate::stream_ser_de::context::Context;
+use crate::stream_ser_de::config::Config;
+use crate::stream_ser_de::context::{CompositeScope, Context};
+
+/// A [`StreamSerializer`] that doesn't store the serialized bytes, but only
+/// measures their size.
+///
+/// The size includes padding and alignment, and the spans of the serialized
+/// objects are available as usual. As the underlying [`NullStream`] can be
+/// revised, multi-pass objects can be measured too.
+///
+/// ```
+/// # use sorbit::ser_de::{Serialize, Serializer, Span};
+/// # use sorbit::stream_ser_de::SizeSerializer;
+/// let mut serializer = SizeSerializer::default();
+/// let span = serializer.serialize_composite(|s| {
+///     0u8.serialize(s)?;
+///     s.align(4)
+/// }).unwrap().0;
+/// assert_eq!(span.len(), 4);
+/// assert_eq!(serializer.take().len(), 4);
+/// ```
+pub type SizeSerializer = StreamSerializer<NullStream>;
+
+impl Default for SizeSerializer {
+    fn default() -> Self {
+        Self::new(NullStream::new())
+    }
+}
 
 /// A [`Serializer`] that works with any [`Write`]-able stream.
 ///
@@ -16,19 +43,77 @@ use crate::stream_ser_de::context::Context;
 /// For streams that also implement both [`Read`] and [`Seek`], the serializer
 /// is also a [`RevisableSerializer`](sorbit::ser_de::RevisableSerializer).
 pub struct StreamSerializer<Stream: Write> {
-    stream: Stream,
+    pub(super) stream: Stream,
     // The current length of the stream.
-    context: Context,
+    pub(super) context: Context,
+    pub(super) config: Config,
+    pub(super) summary: Summary,
+    pub(super) depth: u32,
+}
+
+/// Statistics about the data written by a [`StreamSerializer`].
+///
+/// Returned by [`StreamSerializer::finish`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Summary {
+    /// The position in the stream where the serializer started writing.
+    pub start: u64,
+    /// The position in the stream after the last byte written.
+    ///
+    /// Use this to [resume](StreamSerializer::resume_at) serialization into
+    /// the same stream.
+    pub end: u64,
+    /// The maximum number of composites that were nested inside each other.
+    pub max_depth: u32,
+    /// The number of composites serialized, including nested ones.
+    pub composites: u64,
 }
 
+impl Summary {
+    /// The total number of bytes written.
+    pub fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// Check if no bytes were written.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The [`Span`](crate::ser_de::Span) of the bytes a [`StreamSerializer`] has
+/// written, as a range of stream positions.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct RangeSpan(core::ops::Range<u64>);
+pub struct RangeSpan(pub(super) core::ops::Range<u64>);
+
+/// Reinterpret a slice of primitive numbers as bytes in native byte order.
+macro_rules! native_bytes {
+    ($values:expr) => {
+        // SAFETY: primitive numbers have no padding, and any alignment is valid for bytes.
+        unsafe { core::slice::from_raw_parts($values.as_ptr().cast::<u8>(), core::mem::size_of_val($values)) }
+    };
+}
+
+/// Implement the bulk slice methods of [`Serializer`] for primitive integers.
+macro_rules! serialize_words {
+    ($($method:ident: $type:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self, values: &[$type]) -> Result<Self::Success, Self::Error> {
+                match self.context.byte_order() == ByteOrder::native() {
+                    true => self.write(native_bytes!(values)),
+                    false => self.write_words(values, |value, byte_order| to_xe_bytes!(value, byte_order)),
+                }
+            }
+        )*
+    };
+}
 
 macro_rules! to_xe_bytes {
     ($value:expr, $byte_order:expr) => {
         match $byte_order {
             ByteOrder::BigEndian => $value.to_be_bytes(),
             ByteOrder::LittleEndian => $value.to_le_bytes(),
+            ByteOrder::MiddleEndian => swap_word_bytes($value.to_be_bytes()),
         }
     };
 }
@@ -46,13 +131,92 @@ impl<Stream: Write> StreamSerializer<Stream> {
     /// let serializer = StreamSerializer::new(stream).change_byte_order(ByteOrder::LittleEndian);
     /// ```
     pub fn new(stream: Stream) -> Self {
-        Self { stream, context: Context::default() }
+        Self::with_config(stream, Config::DEFAULT)
+    }
+
+    /// Create a new serializer with the specified configuration.
+    pub fn with_config(stream: Stream, config: Config) -> Self {
+        let context = Context::default().change_byte_order(config.byte_order).change_fill(config.fill);
+        Self { stream, context, config, summary: Summary::default(), depth: 0 }
+    }
+
+    /// Return the configuration of the serializer.
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Replace the configuration of the serializer.
+    ///
+    /// Together with [`config`](Self::config), this lets you temporarily
+    /// change the configuration and restore it later.
+    pub fn set_config(&mut self, config: Config) {
+        self.context.set_byte_order(config.byte_order);
+        self.context.set_fill(config.fill);
+        self.config = config;
+    }
+
+    /// Create a serializer that continues writing a stream that's already
+    /// positioned at `pos`.
+    ///
+    /// This is necessary when appending to a partially written stream, like
+    /// a file that already contains some frames. The stream itself is not
+    /// moved, it has to be positioned at `pos` already. The spans returned
+    /// by the serializer are then positions in the entire stream, and padding
+    /// and alignment is relative to `pos`.
+    ///
+    /// ```
+    /// # use sorbit::stream_ser_de::StreamSerializer;
+    /// # use sorbit::io::{GrowingMemoryStream, Seek, SeekFrom};
+    /// # use sorbit::ser_de::{Serializer, Span};
+    /// let serializer = StreamSerializer::new(GrowingMemoryStream::new());
+    /// let (mut stream, summary) = serializer.finish();
+    /// stream.seek(SeekFrom::Start(summary.end)).unwrap();
+    ///
+    /// let mut serializer = StreamSerializer::new(stream).resume_at(summary.end);
+    /// # let _ = serializer.success();
+    /// ```
+    pub fn resume_at(self, pos: u64) -> Self {
+        let context = self.context.resume_at(pos);
+        let summary = Summary { start: pos, end: pos, ..self.summary };
+        Self { context, summary, ..self }
     }
 
     /// Create a new serializer that uses the specified byte order.
     pub fn change_byte_order(self, byte_order: ByteOrder) -> Self {
         let context = self.context.change_byte_order(byte_order);
-        Self { context, ..self }
+        Self { context, config: Config { byte_order, ..self.config }, ..self }
+    }
+
+    /// Create a new serializer that pads with the `fill` byte instead of zeros.
+    pub fn change_fill(self, fill: u8) -> Self {
+        let context = self.context.change_fill(fill);
+        Self { context, config: Config { fill, ..self.config }, ..self }
+    }
+
+    /// Create a new serializer that produces canonical output.
+    ///
+    /// In canonical mode, equal values always serialize to identical bytes,
+    /// which makes the output suitable for hashing and signing:
+    /// - padding is always filled with zeros, regardless of the fill byte,
+    /// - NaN floats are written as the canonical quiet NaN,
+    /// - optional values are rejected if their encoding is ambiguous. (See
+    ///   [`Sentinel`](crate::option::Sentinel) and [`Remaining`](crate::option::Remaining).)
+    ///
+    /// The flag applies to all nested composites, and is visible to
+    /// [`Serialize`](crate::ser_de::Serialize) implementations through
+    /// [`Serializer::is_canonical`].
+    ///
+    /// ```
+    /// # use sorbit::stream_ser_de::StreamSerializer;
+    /// # use sorbit::io::GrowingMemoryStream;
+    /// # use sorbit::ser_de::Serializer;
+    /// let mut serializer = StreamSerializer::new(GrowingMemoryStream::new()).change_fill(0xFF).canonical();
+    /// serializer.serialize_u8(0xAA).unwrap();
+    /// serializer.align(4).unwrap();
+    /// assert_eq!(serializer.take().take(), [0xAA, 0x00, 0x00, 0x00]);
+    /// ```
+    pub fn canonical(self) -> Self {
+        Self { config: Config { canonical: true, ..self.config }, ..self }
     }
 
     /// Take the serialized bytes from the serializer.
@@ -60,14 +224,59 @@ impl<Stream: Write> StreamSerializer<Stream> {
         self.stream
     }
 
+    /// Take the serialized bytes from the serializer along with statistics
+    /// about what was written.
+    pub fn finish(self) -> (Stream, Summary) {
+        (self.stream, self.summary)
+    }
+
+    /// Start a composite, and return the scope that ends it.
+    pub(super) fn enter_composite(&mut self) -> (CompositeScope, u64) {
+        let scope = self.context.composite_scope();
+        self.depth += 1;
+        self.summary.max_depth = core::cmp::max(self.summary.max_depth, self.depth);
+        self.summary.composites += 1;
+        (scope, self.context.absolute_pos())
+    }
+
+    /// End the composite of the `scope`, and return its span.
+    pub(super) fn leave_composite(&mut self, (scope, start): (CompositeScope, u64)) -> RangeSpan {
+        let end = self.context.absolute_pos();
+        self.depth -= 1;
+        self.context.close_composite_scope(scope);
+        RangeSpan(start..end)
+    }
+
     fn write(&mut self, bytes: &[u8]) -> Result<RangeSpan, Error> {
-        self.context.write(&mut self.stream, bytes).map(|range| RangeSpan(range))
+        let span = self.context.write(&mut self.stream, bytes)?;
+        self.summary.end = core::cmp::max(self.summary.end, span.end);
+        Ok(RangeSpan(span))
+    }
+
+    /// Write the `values` in the current byte order, converting them in chunks
+    /// so that each chunk is written at once.
+    fn write_words<Word: Copy, const N: usize>(
+        &mut self,
+        values: &[Word],
+        to_bytes: impl Fn(Word, ByteOrder) -> [u8; N],
+    ) -> Result<RangeSpan, Error> {
+        let byte_order = self.context.byte_order();
+        let start = self.context.absolute_pos();
+        let mut buffer = [0u8; 256];
+        for chunk in values.chunks(buffer.len() / N) {
+            for (value, bytes) in chunk.iter().zip(buffer.chunks_exact_mut(N)) {
+                bytes.copy_from_slice(&to_bytes(*value, byte_order));
+            }
+            self.write(&buffer[..chunk.len() * N])?;
+        }
+        Ok(RangeSpan(start..self.context.absolute_pos()))
     }
 
     fn write_until(&mut self, until: u64, value: u8) -> Result<RangeSpan, Error> {
         let padding: [u8; 64] = [value; 64];
-        if until < self.context.local_pos() {
-            return Err(ErrorKind::LengthExceedsPadding.into());
+        let position = self.context.local_pos();
+        if until < position {
+            return Err(ErrorKind::LengthExceedsPadding { offset: until, position }.into());
         }
         let start = self.context.absolute_pos();
         while self.context.local_pos() < until {
@@ -136,6 +345,16 @@ impl<Stream: Write> Serializer for StreamSerializer<Stream> {
         self.write(&to_xe_bytes!(value, self.context.byte_order()))
     }
 
+    fn serialize_f32(&mut self, value: f32) -> Result<Self::Success, Self::Error> {
+        let value = if self.config.canonical && value.is_nan() { f32::NAN } else { value };
+        self.write(&to_xe_bytes!(value, self.context.byte_order()))
+    }
+
+    fn serialize_f64(&mut self, value: f64) -> Result<Self::Success, Self::Error> {
+        let value = if self.config.canonical && value.is_nan() { f64::NAN } else { value };
+        self.write(&to_xe_bytes!(value, self.context.byte_order()))
+    }
+
     fn serialize_array<const N: usize>(&mut self, value: &[u8; N]) -> Result<Self::Success, Self::Error> {
         self.write(value)
     }
@@ -144,25 +363,76 @@ impl<Stream: Write> Serializer for StreamSerializer<Stream> {
         self.write(value)
     }
 
+    serialize_words!(
+        serialize_u16_slice: u16,
+        serialize_u32_slice: u32,
+        serialize_u64_slice: u64,
+        serialize_u128_slice: u128,
+        serialize_i16_slice: i16,
+        serialize_i32_slice: i32,
+        serialize_i64_slice: i64,
+        serialize_i128_slice: i128,
+    );
+
+    fn serialize_f32_slice(&mut self, values: &[f32]) -> Result<Self::Success, Self::Error> {
+        let canonical = self.config.canonical && values.iter().any(|value| value.is_nan());
+        match self.context.byte_order() == ByteOrder::native() && !canonical {
+            true => self.write(native_bytes!(values)),
+            false => self.write_words(values, |value, byte_order| {
+                let value = if canonical && value.is_nan() { f32::NAN } else { value };
+                to_xe_bytes!(value, byte_order)
+            }),
+        }
+    }
+
+    fn serialize_f64_slice(&mut self, values: &[f64]) -> Result<Self::Success, Self::Error> {
+        let canonical = self.config.canonical && values.iter().any(|value| value.is_nan());
+        match self.context.byte_order() == ByteOrder::native() && !canonical {
+            true => self.write(native_bytes!(values)),
+            false => self.write_words(values, |value, byte_order| {
+                let value = if canonical && value.is_nan() { f64::NAN } else { value };
+                to_xe_bytes!(value, byte_order)
+            }),
+        }
+    }
+
     fn pad(&mut self, until: u64) -> Result<Self::Success, Self::Error> {
-        self.write_until(until, 0)
+        let fill = if self.config.canonical { 0 } else { self.context.fill() };
+        self.write_until(until, fill)
     }
 
     fn align(&mut self, multiple_of: u64) -> Result<Self::Success, Self::Error> {
-        let until = (self.context.local_pos() + multiple_of - 1) / multiple_of * multiple_of;
+        let until = self.context.local_pos().div_ceil(multiple_of) * multiple_of;
         self.pad(until)
     }
 
+    fn position(&self) -> u64 {
+        self.context.absolute_pos()
+    }
+
+    fn composite_position(&self) -> u64 {
+        self.context.local_pos()
+    }
+
+    fn is_canonical(&self) -> bool {
+        self.config.canonical
+    }
+
+    fn context(&self, key: &str) -> Option<u64> {
+        self.context.value(key)
+    }
+
+    fn set_context(&mut self, key: &'static str, value: Option<u64>) -> Result<(), Self::Error> {
+        self.context.set_value(key, value)
+    }
+
     fn serialize_composite<Output>(
         &mut self,
         serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
     ) -> Result<(Self::Success, Output), Self::Error> {
-        let scope = self.context.composite_scope();
-        let start = self.context.absolute_pos();
-        let result = serialize_members(self);
-        let end = self.context.absolute_pos();
-        self.context.close_composite_scope(scope);
-        let span = RangeSpan(start..end);
+        let scope = self.enter_composite();
+        let result = serialize_members(self).map_err(|error| error.locate(scope.1));
+        let span = self.leave_composite(scope);
         result.map(|output| (span, output))
     }
 
@@ -176,6 +446,17 @@ impl<Stream: Write> Serializer for StreamSerializer<Stream> {
         self.context.close_byte_order_scope(scope);
         result
     }
+
+    fn with_fill<Output>(
+        &mut self,
+        fill: u8,
+        serialize_members: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let scope = self.context.fill_scope(fill);
+        let result = serialize_members(self);
+        self.context.close_fill_scope(scope);
+        result
+    }
 }
 
 impl<Stream> RevisableSerializer for StreamSerializer<Stream>
@@ -193,6 +474,24 @@ where
         result
     }
 
+    fn serialize_at<Output>(
+        &mut self,
+        offset: u64,
+        serialize: impl FnOnce(&mut Self) -> Result<Output, Self::Error>,
+    ) -> Result<Output, Self::Error> {
+        let position = self.context.local_pos();
+        if position <= offset {
+            self.pad(offset)?;
+            return serialize(self);
+        }
+        self.context.seek(&mut self.stream, offset)?;
+        let result = serialize(self);
+        if self.context.local_pos() < position {
+            self.context.seek(&mut self.stream, position)?;
+        }
+        result
+    }
+
     fn analyze_span<Output, Error, AnalyzeSpanFn>(
         &mut self,
         section: &Self::Success,
@@ -205,7 +504,7 @@ where
         let range = &section.0;
         let stream_pos = self.stream.stream_position()?;
         let mut partial_stream =
-            StreamSection::new(&mut self.stream, range.clone()).map_err(|_| ErrorKind::UnexpectedEof)?;
+            StreamSection::new(&mut self.stream, range.clone()).map_err(|_| ErrorKind::OutOfBounds)?;
         let result = analyze_span_fn(&mut partial_stream);
         self.stream.seek(SeekFrom::Start(stream_pos))?;
         result.map_err(|err| err.into())
@@ -232,6 +531,31 @@ mod tests {
 
     use super::*;
 
+    //--------------------------------------------------------------------------
+    // Config
+    //--------------------------------------------------------------------------
+    #[test]
+    fn with_config() -> Result<(), Error> {
+        let config = Config::DEFAULT.with_byte_order(ByteOrder::BigEndian);
+        let mut s = StreamSerializer::with_config(GrowingMemoryStream::new(), config);
+        assert_eq!(s.config(), config);
+        s.serialize_u16(0xAABB)?;
+        assert_eq!(s.take().take(), vec![0xAA, 0xBB]);
+        Ok(())
+    }
+
+    #[test]
+    fn set_config() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        let saved = s.config();
+        s.set_config(saved.with_byte_order(ByteOrder::LittleEndian));
+        s.serialize_u16(0xAABB)?;
+        s.set_config(saved);
+        s.serialize_u16(0xCCDD)?;
+        assert_eq!(s.take().take(), vec![0xBB, 0xAA, 0xCC, 0xDD]);
+        Ok(())
+    }
+
     //--------------------------------------------------------------------------
     // bool
     //--------------------------------------------------------------------------
@@ -436,6 +760,76 @@ mod tests {
         Ok(())
     }
 
+    //--------------------------------------------------------------------------
+    // f*
+    //--------------------------------------------------------------------------
+    #[test]
+    fn serialize_f32_be() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        s.serialize_f32(1.5)?;
+        assert_eq!(s.take().take(), vec![0x3F, 0xC0, 0x00, 0x00]);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_f64_be() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        s.serialize_f64(1.5)?;
+        assert_eq!(s.take().take(), vec![0x3F, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_f32_le() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::LittleEndian);
+        s.serialize_f32(1.5)?;
+        assert_eq!(s.take().take(), vec![0x00, 0x00, 0xC0, 0x3F]);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_f64_le() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::LittleEndian);
+        s.serialize_f64(1.5)?;
+        assert_eq!(s.take().take(), vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, 0x3F]);
+        Ok(())
+    }
+
+    //--------------------------------------------------------------------------
+    // Middle endian
+    //--------------------------------------------------------------------------
+    #[test]
+    fn serialize_u16_me() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::MiddleEndian);
+        s.serialize_u16(0xBEEF)?;
+        assert_eq!(s.take().take(), vec![0xEF, 0xBE]);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_u32_me() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::MiddleEndian);
+        s.serialize_u32(0xDEADBEEF)?;
+        assert_eq!(s.take().take(), vec![0xAD, 0xDE, 0xEF, 0xBE]);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_u64_me() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::MiddleEndian);
+        s.serialize_u64(0x0102030405060708)?;
+        assert_eq!(s.take().take(), vec![0x02, 0x01, 0x04, 0x03, 0x06, 0x05, 0x08, 0x07]);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_f32_me() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::MiddleEndian);
+        s.serialize_f32(1.5)?;
+        assert_eq!(s.take().take(), vec![0xC0, 0x3F, 0x00, 0x00]);
+        Ok(())
+    }
+
     //--------------------------------------------------------------------------
     // Array & slice
     //--------------------------------------------------------------------------
@@ -455,6 +849,71 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
+    #[case(ByteOrder::LittleEndian)]
+    #[case(ByteOrder::BigEndian)]
+    #[case(ByteOrder::MiddleEndian)]
+    fn serialize_word_slice(#[case] byte_order: ByteOrder) -> Result<(), Error> {
+        let values: Vec<u32> = (0..1000).map(|i: u32| i.wrapping_mul(0x0102_0304)).collect();
+        let mut bulk = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(byte_order);
+        let span = bulk.serialize_u32_slice(&values)?;
+        let mut single = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(byte_order);
+        for value in &values {
+            single.serialize_u32(*value)?;
+        }
+        assert_eq!(span, RangeSpan(0..4000));
+        assert_eq!(bulk.take().take(), single.take().take());
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_word_slice_empty() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new());
+        s.serialize_u8(0xEE)?;
+        assert_eq!(s.serialize_i64_slice(&[])?, RangeSpan(1..1));
+        assert_eq!(s.take().take(), vec![0xEE]);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_float_slice_canonical() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).canonical();
+        s.serialize_f64_slice(&[1.0, -f64::NAN])?;
+        let mut expected = Vec::new();
+        expected.extend(1.0_f64.to_le_bytes());
+        expected.extend(f64::NAN.to_le_bytes());
+        assert_eq!(s.take().take(), expected);
+        Ok(())
+    }
+
+    //--------------------------------------------------------------------------
+    // Variable-length integers
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn serialize_varint_u64() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new());
+        s.serialize_varint_u64(0)?;
+        s.serialize_varint_u64(300)?;
+        s.serialize_varint_u64(u64::MAX)?;
+        let mut expected = vec![0x00, 0xAC, 0x02];
+        expected.extend([0xFF; 9]);
+        expected.push(0x01);
+        assert_eq!(s.take().take(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_varint_i64() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new());
+        s.serialize_varint_i64(0)?;
+        s.serialize_varint_i64(-1)?;
+        s.serialize_varint_i64(1)?;
+        s.serialize_varint_i64(-150)?;
+        assert_eq!(s.take().take(), vec![0x00, 0x01, 0x02, 0xAB, 0x02]);
+        Ok(())
+    }
+
     //--------------------------------------------------------------------------
     // Padding
     //--------------------------------------------------------------------------
@@ -472,7 +931,7 @@ mod tests {
     fn pad_length_exceeds_padding() -> Result<(), Error> {
         let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
         s.serialize_array(&[0xAA, 0xBB, 0xCC])?;
-        assert_eq!(s.pad(2), Err(ErrorKind::LengthExceedsPadding.into()));
+        assert_eq!(s.pad(2), Err(ErrorKind::LengthExceedsPadding { offset: 2, position: 3 }.into()));
         Ok(())
     }
 
@@ -489,6 +948,62 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn pad_with_fill() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_fill(0xFF);
+        s.serialize_u8(0xEE)?;
+        s.pad(2)?;
+        s.with_fill(0x20, |s| s.pad(4))?;
+        s.align(6)?;
+        assert_eq!(s.take().take(), vec![0xEE, 0xFF, 0x20, 0x20, 0xFF, 0xFF]);
+        Ok(())
+    }
+
+    //--------------------------------------------------------------------------
+    // Canonical
+    //--------------------------------------------------------------------------
+    #[test]
+    fn canonical_pad() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_fill(0xFF).canonical();
+        s.serialize_u8(0xEE)?;
+        s.pad(2)?;
+        s.serialize_composite(|s| s.with_fill(0x20, |s| s.pad(2)))?;
+        assert_eq!(s.take().take(), vec![0xEE, 0x00, 0x00, 0x00]);
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_nan() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new())
+            .change_byte_order(ByteOrder::BigEndian)
+            .canonical();
+        s.serialize_f32(f32::from_bits(0xFFC0_0001))?;
+        s.serialize_f64(-f64::NAN)?;
+        s.serialize_f32(-0.0)?;
+        let mut expected = Vec::new();
+        expected.extend(f32::NAN.to_be_bytes());
+        expected.extend(f64::NAN.to_be_bytes());
+        expected.extend((-0.0_f32).to_be_bytes());
+        assert_eq!(s.take().take(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_nan_preserved() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        s.serialize_f32(f32::from_bits(0xFFC0_0001))?;
+        assert_eq!(s.take().take(), vec![0xFF, 0xC0, 0x00, 0x01]);
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_config() {
+        assert!(!StreamSerializer::new(GrowingMemoryStream::new()).is_canonical());
+        let s = StreamSerializer::new(GrowingMemoryStream::new()).canonical();
+        assert!(s.is_canonical());
+        assert!(s.config().canonical);
+    }
+
     //--------------------------------------------------------------------------
     // Alignment
     //--------------------------------------------------------------------------
@@ -515,6 +1030,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn align_absolute_composite() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new());
+        s.serialize_bool(true)?;
+        s.serialize_composite(|s| {
+            s.serialize_array(&[0x62, 0x85, 0x28])?;
+            s.align_absolute(5)
+        })?;
+        s.serialize_bool(true)?;
+        assert_eq!(s.take().take(), vec![0x01, 0x62, 0x85, 0x28, 0x00, 0x01]);
+        Ok(())
+    }
+
+    //--------------------------------------------------------------------------
+    // Position
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn position() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).resume_at(2);
+        s.serialize_u8(0xAA)?;
+        assert_eq!((s.position(), s.composite_position()), (3, 1));
+        s.serialize_composite(|s| {
+            assert_eq!((s.position(), s.composite_position()), (3, 0));
+            s.serialize_u16(0xBBCC)?;
+            assert_eq!((s.position(), s.composite_position()), (5, 2));
+            s.success()
+        })?;
+        assert_eq!((s.position(), s.composite_position()), (5, 3));
+        Ok(())
+    }
+
     //--------------------------------------------------------------------------
     // Composites
     //--------------------------------------------------------------------------
@@ -531,6 +1078,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn context_scoped_to_composite() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new());
+        let (_, inner) = s.with_context("version", 1, |s| {
+            s.serialize_composite(|s| {
+                s.set_context("version", Some(2))?;
+                Ok(s.context("version"))
+            })
+        })?;
+        assert_eq!(inner, Some(2));
+        assert_eq!(s.context("version"), None);
+        Ok(())
+    }
+
     //--------------------------------------------------------------------------
     // Byte order
     //--------------------------------------------------------------------------
@@ -547,6 +1108,37 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
+    #[case(ByteOrder::LittleEndian, ByteOrder::BigEndian, vec![0xBB, 0xAA, 0xCC, 0xDD, 0xBB, 0xAA])]
+    #[case(ByteOrder::BigEndian, ByteOrder::LittleEndian, vec![0xAA, 0xBB, 0xDD, 0xCC, 0xAA, 0xBB])]
+    fn nested_byte_order(
+        #[case] outer: ByteOrder,
+        #[case] inner: ByteOrder,
+        #[case] expected: Vec<u8>,
+    ) -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new());
+        s.with_byte_order(outer, |s| {
+            s.serialize_u16(0xAABB)?;
+            s.serialize_composite(|s| s.with_byte_order(inner, |s| s.serialize_u16(0xCCDD)))?;
+            s.serialize_u16(0xAABB)
+        })?;
+        assert_eq!(s.take().take(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn byte_order_restored_on_error() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        let result = s.with_byte_order(ByteOrder::LittleEndian, |s| {
+            s.serialize_u16(0xAABB)?;
+            s.pad(0)
+        });
+        assert_eq!(result, Err(ErrorKind::LengthExceedsPadding { offset: 0, position: 2 }.into()));
+        s.serialize_u16(0xCCDD)?;
+        assert_eq!(s.take().take(), vec![0xBB, 0xAA, 0xCC, 0xDD]);
+        Ok(())
+    }
+
     //--------------------------------------------------------------------------
     // Revise span
     //--------------------------------------------------------------------------
@@ -561,4 +1153,89 @@ mod tests {
         assert_eq!(s.take().take(), expected);
         Ok(())
     }
+
+    //--------------------------------------------------------------------------
+    // Serialize at
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn serialize_at_out_of_order() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        s.serialize_u8(0xAA)?;
+        s.serialize_composite(|s| {
+            s.serialize_at(3, |s| s.serialize_u16(0xDDEE))?;
+            s.serialize_at(1, |s| s.serialize_u8(0xCC))?;
+            s.serialize_at(0, |s| s.serialize_u8(0xBB))?;
+            s.serialize_u8(0xFF)
+        })?;
+        assert_eq!(s.take().take(), vec![0xAA, 0xBB, 0xCC, 0x00, 0xDD, 0xEE, 0xFF]);
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_at_past_end() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        s.serialize_u16(0xAABB)?;
+        s.serialize_at(1, |s| s.serialize_u16(0xCCDD))?;
+        s.serialize_u8(0xEE)?;
+        assert_eq!(s.take().take(), vec![0xAA, 0xCC, 0xDD, 0xEE]);
+        Ok(())
+    }
+
+    //--------------------------------------------------------------------------
+    // Finish and resume
+    //--------------------------------------------------------------------------
+
+    #[test]
+    fn success_as_span() -> Result<(), Error> {
+        use crate::ser_de::MaybeSpan as _;
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new());
+        s.serialize_u8(0)?;
+        let success = s.serialize_u16(0)?;
+        let span = success.as_span().unwrap();
+        assert_eq!((span.start(), span.end(), span.len()), (1, 3, 2));
+        assert!(().as_span().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn finish() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new());
+        s.serialize_composite(|s| {
+            s.serialize_u8(0xAA)?;
+            s.serialize_composite(|s| s.serialize_u16(0xBBCC))?;
+            s.serialize_composite(|s| s.serialize_u8(0xDD))
+        })?;
+        let span = s.serialize_u8(0)?;
+        s.revise_span(&span, |s| s.serialize_u8(0xEE))?;
+        let (stream, summary) = s.finish();
+        assert_eq!(stream.take().len(), 5);
+        assert_eq!(summary, Summary { start: 0, end: 5, max_depth: 2, composites: 3 });
+        assert_eq!(summary.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn resume_at() -> Result<(), Error> {
+        let mut s = StreamSerializer::new(GrowingMemoryStream::new()).change_byte_order(ByteOrder::BigEndian);
+        s.serialize_u8(0xAA)?;
+        let (stream, first) = s.finish();
+
+        let mut s = StreamSerializer::new(stream).change_byte_order(ByteOrder::BigEndian).resume_at(first.end);
+        let (span, _) = s.serialize_composite(|s| {
+            s.serialize_u8(0xBB)?;
+            s.align(2)
+        })?;
+        let length = s.serialize_u8(0)?;
+        let len = s.analyze_span(&span, |stream| {
+            let mut bytes = [0u8; 2];
+            stream.read(&mut bytes).map(|_| bytes.len() as u8)
+        })?;
+        s.revise_span(&length, |s| s.serialize_u8(len))?;
+        let (stream, second) = s.finish();
+        assert_eq!(span, RangeSpan(1..3));
+        assert_eq!(second, Summary { start: 1, end: 4, max_depth: 1, composites: 1 });
+        assert_eq!(stream.take(), vec![0xAA, 0xBB, 0x00, 0x02]);
+        Ok(())
+    }
 }
@@ -0,0 +1,360 @@
+//! Utilities for serializing text with a specific encoding.
+//!
+//! Binary formats store text in many different ways, for example, as a
+//! null-terminated string, as a fixed-size buffer padded with spaces, or
+//! preceded by its length. With the derive macros, [`String`] fields can be
+//! annotated with the encoding they are stored as:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::ser_de::{FromBytes, ToBytes};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! struct Record {
+//!     #[sorbit(string=null_terminated)]
+//!     name: String,
+//!     #[sorbit(string=fixed(4), pad=0x20)]
+//!     kind: String,
+//!     #[sorbit(string=len_prefix(u8))]
+//!     comment: String,
+//! }
+//!
+//! let record = Record { name: "ab".into(), kind: "xy".into(), comment: "c".into() };
+//! let bytes = [b'a', b'b', 0, b'x', b'y', b' ', b' ', 1, b'c'];
+//! assert_eq!(record.to_bytes().unwrap(), bytes);
+//! assert_eq!(Record::from_bytes(&bytes).unwrap(), record);
+//! ```
+//!
+//! The encoded text is the UTF-8 representation of the string. Deserializing
+//! text that is not valid UTF-8 results in an error.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::layout::{Layout, StaticLayout};
+use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The way a string is stored in its binary representation.
+pub trait StringEncoding {
+    /// Serialize the bytes of the string.
+    fn serialize_str<S: Serializer>(&self, string: &str, serializer: &mut S) -> Result<S::Success, S::Error>;
+
+    /// Deserialize the bytes of the string.
+    fn deserialize_string<D: Deserializer>(&self, deserializer: &mut D) -> Result<String, D::Error>;
+}
+
+/// The string is followed by a null byte.
+///
+/// The string itself must not contain null characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NullTerminated;
+
+/// The string is stored in exactly `len` bytes.
+///
+/// Shorter strings are padded with the `pad` byte, and the trailing `pad`
+/// bytes are removed when deserializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fixed {
+    /// The number of bytes the string is stored in.
+    pub len: usize,
+    /// The byte the string is padded with.
+    pub pad: u8,
+}
+
+/// The string is preceded by the number of its bytes, stored as `Len`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LenPrefixed<Len> {
+    len: PhantomData<Len>,
+}
+
+impl NullTerminated {
+    /// Return the layout of the string as encoded.
+    pub const fn layout(&self) -> Layout {
+        Layout::new(1, None, 0)
+    }
+}
+
+impl Fixed {
+    /// Return the layout of the string as encoded.
+    pub const fn layout(&self) -> Layout {
+        Layout::fixed(self.len as u64)
+    }
+}
+
+impl<Len> LenPrefixed<Len> {
+    /// Create a length-prefixed encoding.
+    pub const fn new() -> Self {
+        Self { len: PhantomData }
+    }
+}
+
+impl<Len: StaticLayout> LenPrefixed<Len> {
+    /// Return the layout of the string as encoded.
+    pub const fn layout(&self) -> Layout {
+        Len::LAYOUT.then(Layout::new(0, None, 0))
+    }
+}
+
+impl<Len> Default for LenPrefixed<Len> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StringEncoding for NullTerminated {
+    fn serialize_str<S: Serializer>(&self, string: &str, serializer: &mut S) -> Result<S::Success, S::Error> {
+        if string.bytes().any(|byte| byte == 0) {
+            match serializer.error("the string contains a null character")? {}
+        }
+        serializer
+            .serialize_composite(|serializer| {
+                serializer.serialize_slice(string.as_bytes())?;
+                serializer.serialize_u8(0)
+            })
+            .map(|(span, _)| span)
+    }
+
+    fn deserialize_string<D: Deserializer>(&self, deserializer: &mut D) -> Result<String, D::Error> {
+        deserializer.deserialize_composite(|deserializer| {
+            let max_len = deserializer.limits().max_len;
+            let mut bytes = Vec::new();
+            loop {
+                match deserializer.deserialize_u8()? {
+                    0 => break,
+                    _ if bytes.len() == max_len => return deserializer.length_limit(),
+                    byte => bytes.push(byte),
+                }
+            }
+            from_utf8(deserializer, bytes)
+        })
+    }
+}
+
+impl StringEncoding for Fixed {
+    fn serialize_str<S: Serializer>(&self, string: &str, serializer: &mut S) -> Result<S::Success, S::Error> {
+        if string.len() > self.len {
+            match serializer.error("the string is too long for its fixed length")? {}
+        }
+        serializer
+            .serialize_composite(|serializer| {
+                serializer.serialize_slice(string.as_bytes())?;
+                // The padding is part of the value, so it's written even in
+                // canonical mode, unlike the padding of `Serializer::pad`.
+                let padding = [self.pad; 64];
+                let mut remaining = self.len - string.len();
+                while remaining > 0 {
+                    let count = remaining.min(padding.len());
+                    serializer.serialize_slice(&padding[..count])?;
+                    remaining -= count;
+                }
+                serializer.success()
+            })
+            .map(|(span, _)| span)
+    }
+
+    fn deserialize_string<D: Deserializer>(&self, deserializer: &mut D) -> Result<String, D::Error> {
+        deserializer.deserialize_composite(|deserializer| {
+            let mut bytes = deserialize_bytes(deserializer, self.len)?;
+            let len = bytes.iter().rposition(|byte| *byte != self.pad).map(|last| last + 1).unwrap_or(0);
+            bytes.truncate(len);
+            from_utf8(deserializer, bytes)
+        })
+    }
+}
+
+impl<Len> StringEncoding for LenPrefixed<Len>
+where
+    Len: Serialize + Deserialize + TryFrom<usize>,
+    usize: TryFrom<Len>,
+{
+    fn serialize_str<S: Serializer>(&self, string: &str, serializer: &mut S) -> Result<S::Success, S::Error> {
+        let Ok(len) = Len::try_from(string.len()) else {
+            match serializer.error("the length of the string is too large for its binary representation")? {}
+        };
+        serializer
+            .serialize_composite(|serializer| {
+                len.serialize(serializer)?;
+                serializer.serialize_slice(string.as_bytes())
+            })
+            .map(|(span, _)| span)
+    }
+
+    fn deserialize_string<D: Deserializer>(&self, deserializer: &mut D) -> Result<String, D::Error> {
+        deserializer.deserialize_composite(|deserializer| {
+            let Ok(len) = usize::try_from(Len::deserialize(deserializer)?) else {
+                return deserializer.error("the length of the string can not be converted into a `usize`");
+            };
+            if len > deserializer.limits().max_len {
+                return deserializer.length_limit();
+            }
+            let bytes = deserialize_bytes(deserializer, len)?;
+            from_utf8(deserializer, bytes)
+        })
+    }
+}
+
+/// A string serialized with a specific encoding.
+pub struct Encoded<'string, Encoding> {
+    string: &'string str,
+    encoding: Encoding,
+}
+
+impl<'string, E: StringEncoding> Serialize for Encoded<'string, E> {
+    /// Serialize the string as per its encoding.
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        self.encoding.serialize_str(self.string, serializer)
+    }
+}
+
+/// Serialize the string with the given encoding.
+pub fn encoded<'string, Encoding>(
+    string: &'string (impl AsRef<str> + ?Sized),
+    encoding: Encoding,
+) -> Encoded<'string, Encoding> {
+    Encoded { string: string.as_ref(), encoding }
+}
+
+/// Deserialize a string with the given encoding.
+pub fn deserialize_encoded<D, Encoding>(deserializer: &mut D, encoding: Encoding) -> Result<String, D::Error>
+where
+    D: Deserializer,
+    Encoding: StringEncoding,
+{
+    encoding.deserialize_string(deserializer)
+}
+
+/// Deserialize `len` bytes.
+///
+/// The storage reserved for the bytes up front is capped by the
+/// [`Limits`](crate::ser_de::Limits) of the deserializer.
+fn deserialize_bytes<D: Deserializer>(deserializer: &mut D, len: usize) -> Result<Vec<u8>, D::Error> {
+    let mut bytes = Vec::with_capacity(deserializer.limits().preallocated_items::<u8>(len));
+    let mut chunk = [0u8; 64];
+    while bytes.len() < len {
+        let chunk_len = chunk.len().min(len - bytes.len());
+        deserializer.deserialize_slice(&mut chunk[..chunk_len])?;
+        bytes.extend_from_slice(&chunk[..chunk_len]);
+    }
+    Ok(bytes)
+}
+
+fn from_utf8<D: Deserializer>(deserializer: &mut D, bytes: Vec<u8>) -> Result<String, D::Error> {
+    String::from_utf8(bytes).or_else(|_| deserializer.error("the string is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind, TraceError as _};
+    use crate::io::GrowingMemoryStream;
+    use crate::ser_de::Limits;
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    fn serialize(string: &str, encoding: impl StringEncoding) -> Result<Vec<u8>, Error> {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        encoded(string, encoding).serialize(&mut serializer)?;
+        Ok(serializer.take().take())
+    }
+
+    fn deserialize(bytes: &[u8], encoding: impl StringEncoding) -> Result<String, Error> {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+        deserialize_encoded(&mut deserializer, encoding)
+    }
+
+    #[test]
+    fn null_terminated() {
+        assert_eq!(serialize("ab", NullTerminated), Ok(vec![b'a', b'b', 0]));
+        assert_eq!(deserialize(b"ab\0c", NullTerminated), Ok("ab".into()));
+    }
+
+    #[test]
+    fn null_terminated_inner_null() {
+        assert_eq!(
+            serialize("a\0b", NullTerminated),
+            Err(ErrorKind::Custom("the string contains a null character").into())
+        );
+    }
+
+    #[test]
+    fn null_terminated_missing_terminator() {
+        assert!(deserialize(b"ab", NullTerminated).is_err());
+    }
+
+    #[test]
+    fn fixed() {
+        let encoding = Fixed { len: 4, pad: b' ' };
+        assert_eq!(serialize("ab", encoding), Ok(vec![b'a', b'b', b' ', b' ']));
+        assert_eq!(deserialize(b"ab  ", encoding), Ok("ab".into()));
+        assert_eq!(deserialize(b"    ", encoding), Ok("".into()));
+    }
+
+    #[test]
+    fn fixed_long_padding() {
+        let encoding = Fixed { len: 100, pad: b' ' };
+        let mut expected = vec![b' '; 100];
+        expected[0] = b'a';
+        assert_eq!(serialize("a", encoding), Ok(expected));
+    }
+
+    #[test]
+    fn fixed_too_long() {
+        assert_eq!(
+            serialize("abc", Fixed { len: 2, pad: 0 }),
+            Err(ErrorKind::Custom("the string is too long for its fixed length").into())
+        );
+    }
+
+    #[test]
+    fn len_prefixed() {
+        assert_eq!(serialize("ab", LenPrefixed::<u8>::new()), Ok(vec![2, b'a', b'b']));
+        assert_eq!(deserialize(&[2, b'a', b'b', b'c'], LenPrefixed::<u8>::new()), Ok("ab".into()));
+    }
+
+    #[test]
+    fn len_prefixed_too_long() {
+        let string = String::from_utf8(vec![b'a'; 256]).unwrap();
+        assert_eq!(
+            serialize(&string, LenPrefixed::<u8>::new()),
+            Err(ErrorKind::Custom("the length of the string is too large for its binary representation").into())
+        );
+    }
+
+    #[test]
+    fn len_prefixed_length_limit() {
+        let limits = Limits::DEFAULT.with_max_len(2);
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(&b"\x03abc"[..])).with_limits(limits);
+        assert_eq!(
+            deserialize_encoded(&mut deserializer, LenPrefixed::<u8>::new()),
+            Err(Error::from(ErrorKind::LengthLimit).locate(0))
+        );
+    }
+
+    #[test]
+    fn null_terminated_length_limit() {
+        let limits = Limits::DEFAULT.with_max_len(2);
+        let mut deserializer =
+            StreamDeserializer::new(GrowingMemoryStream::from(&b"ab\0abc\0"[..])).with_limits(limits);
+        assert_eq!(deserialize_encoded(&mut deserializer, NullTerminated), Ok(String::from("ab")));
+        assert_eq!(
+            deserialize_encoded(&mut deserializer, NullTerminated),
+            Err(Error::from(ErrorKind::LengthLimit).locate(3))
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        assert_eq!(
+            deserialize(&[2, 0xC3, 0x28], LenPrefixed::<u8>::new()),
+            Err(Error::from(ErrorKind::Custom("the string is not valid UTF-8")).locate(0))
+        );
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(NullTerminated.layout(), Layout::new(1, None, 0));
+        assert_eq!(Fixed { len: 4, pad: 0 }.layout(), Layout::fixed(4));
+        assert_eq!(LenPrefixed::<u16>::new().layout(), Layout::new(2, None, 0));
+    }
+}
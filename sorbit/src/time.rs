@@ -0,0 +1,127 @@
+//! Utilities for serializing durations as integers of a given unit.
+//!
+//! Protocols usually encode timeouts and intervals as plain integers with an
+//! implicit unit. With the derive macros, [`Duration`] fields can be annotated
+//! with the unit and the integer type they are encoded as:
+//!
+//! ```
+//! use core::time::Duration;
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::ser_de::{FromBytes, ToBytes};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! #[sorbit(byte_order=big_endian)]
+//! struct KeepAlive {
+//!     #[sorbit(unit="ms", repr=u16)]
+//!     interval: Duration,
+//! }
+//!
+//! let keep_alive = KeepAlive { interval: Duration::from_millis(1500) };
+//! assert_eq!(keep_alive.to_bytes().unwrap(), [0x05, 0xDC]);
+//! assert_eq!(KeepAlive::from_bytes(&[0x05, 0xDC]).unwrap(), keep_alive);
+//! ```
+
+use core::time::Duration;
+
+use crate::ser_de::{Deserializer, Serializer};
+
+/// The unit of the integer a [`Duration`] is encoded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeUnit {
+    /// Nanoseconds (`ns`).
+    Nanoseconds,
+    /// Microseconds (`us`).
+    Microseconds,
+    /// Milliseconds (`ms`).
+    Milliseconds,
+    /// Seconds (`s`).
+    Seconds,
+    /// Minutes (`min`).
+    Minutes,
+    /// Hours (`h`).
+    Hours,
+}
+
+impl TimeUnit {
+    /// Return the number of nanoseconds in one unit.
+    pub const fn nanos(self) -> u128 {
+        match self {
+            TimeUnit::Nanoseconds => 1,
+            TimeUnit::Microseconds => 1_000,
+            TimeUnit::Milliseconds => 1_000_000,
+            TimeUnit::Seconds => 1_000_000_000,
+            TimeUnit::Minutes => 60_000_000_000,
+            TimeUnit::Hours => 3_600_000_000_000,
+        }
+    }
+}
+
+/// Return the duration as a number of `unit`s in a specific (integer) type.
+///
+/// The fraction of the last unit is truncated. If the number of units can
+/// not be represented by `T`, an error is returned.
+pub fn duration_as<T, S>(serializer: &mut S, duration: &Duration, unit: TimeUnit) -> Result<T, S::Error>
+where
+    T: TryFrom<u128>,
+    S: Serializer,
+{
+    T::try_from(duration.as_nanos() / unit.nanos())
+        .map_err(|_| serializer.error("the duration is too long for its binary representation").unwrap_err())
+}
+
+/// Return the duration that is `value` number of `unit`s.
+///
+/// If the duration is too long to be represented by [`Duration`], an error is
+/// returned.
+pub fn duration_from<T, D>(deserializer: &mut D, value: T, unit: TimeUnit) -> Result<Duration, D::Error>
+where
+    T: Into<u128>,
+    D: Deserializer,
+{
+    let nanos = value.into().checked_mul(unit.nanos());
+    let secs = nanos.and_then(|nanos| u64::try_from(nanos / TimeUnit::Seconds.nanos()).ok());
+    match (nanos, secs) {
+        (Some(nanos), Some(secs)) => Ok(Duration::new(secs, (nanos % TimeUnit::Seconds.nanos()) as u32)),
+        _ => deserializer.error("the duration is too long to be represented"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind};
+    use crate::io::{FixedMemoryStream, GrowingMemoryStream};
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    #[test]
+    fn duration_as_truncates() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let duration = Duration::from_micros(1999);
+        assert_eq!(duration_as::<u8, _>(&mut serializer, &duration, TimeUnit::Milliseconds), Ok(1));
+    }
+
+    #[test]
+    fn duration_as_overflow() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        let duration = Duration::from_secs(256);
+        assert_eq!(
+            duration_as::<u8, _>(&mut serializer, &duration, TimeUnit::Seconds),
+            Err(Error::from(ErrorKind::Custom("the duration is too long for its binary representation")))
+        );
+    }
+
+    #[test]
+    fn duration_from_units() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([]));
+        assert_eq!(duration_from(&mut deserializer, 1500u16, TimeUnit::Milliseconds), Ok(Duration::from_millis(1500)));
+        assert_eq!(duration_from(&mut deserializer, 2u8, TimeUnit::Hours), Ok(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn duration_from_overflow() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([]));
+        assert!(duration_from(&mut deserializer, u128::MAX, TimeUnit::Nanoseconds).is_err());
+        assert!(duration_from(&mut deserializer, u64::MAX, TimeUnit::Hours).is_err());
+    }
+}
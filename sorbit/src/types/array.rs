@@ -1,5 +1,6 @@
 use core::mem::MaybeUninit;
 
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{Deserialize, Deserializer, MultiPassSerialize, RevisableSerializer, Serialize, Serializer};
 
 impl<T, const N: usize> Serialize for [T; N]
@@ -7,13 +8,10 @@ where
     T: Serialize,
 {
     fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
-        // TODO: specialize this for [u8; N] when specialization is available in stable.
         serializer
             .serialize_composite(|serializer| {
-                for value in &self[0..(N - 1)] {
-                    value.serialize(serializer)?;
-                }
-                self[N - 1].serialize(serializer)
+                T::serialize_many(self, serializer)?;
+                serializer.success()
             })
             .map(|(span, _)| span)
     }
@@ -27,10 +25,10 @@ where
         // TODO: specialize this for [u8; N] when specialization is available in stable.
         serializer
             .serialize_composite(|serializer| {
-                for value in &self[0..(N - 1)] {
+                for value in self {
                     value.serialize(serializer)?;
                 }
-                self[N - 1].serialize(serializer)
+                serializer.success()
             })
             .map(|(span, _)| span)
     }
@@ -42,21 +40,58 @@ where
 {
     fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
         // TODO: specialize this for [u8; N] when specialization is available in stable.
-        // TODO: use core::array::try_from_fn when available in stable.
-        let mut array = [const { MaybeUninit::<T>::uninit() }; N];
-        for last_idx in 0..N {
-            match T::deserialize(deserializer) {
-                Ok(value) => array[last_idx].write(value),
-                Err(err) => {
-                    for inited_idx in 0..last_idx {
-                        unsafe { array[inited_idx].assume_init_drop() };
-                    }
-                    return Err(err);
-                }
-            };
+        try_array_from_fn(|| T::deserialize(deserializer))
+    }
+}
+
+/// Create an array by calling `f` for each element in order.
+///
+/// When `f` fails, the elements created so far are dropped, and the error is
+/// returned. The elements don't have to be [`Copy`] or [`Default`].
+pub(crate) fn try_array_from_fn<T, E, const N: usize>(f: impl FnMut() -> Result<T, E>) -> Result<[T; N], E> {
+    // TODO: use core::array::try_from_fn when available in stable.
+    let mut array = [const { MaybeUninit::<T>::uninit() }; N];
+    try_init_items(&mut array, f)?;
+    // SAFETY: all items have been initialized, and `[MaybeUninit<T>; N]` has
+    // the same layout as `[T; N]`. Reading avoids the copies of `array.map`.
+    Ok(unsafe { (&raw const array).cast::<[T; N]>().read() })
+}
+
+/// Initialize the `items` by calling `f` for each in order.
+///
+/// When `f` fails or panics, the items initialized so far are dropped, and all
+/// items are left uninitialized.
+pub(crate) fn try_init_items<T, E>(items: &mut [MaybeUninit<T>], mut f: impl FnMut() -> Result<T, E>) -> Result<(), E> {
+    /// Drops the initialized prefix of the items unless disarmed.
+    struct Guard<'items, T> {
+        items: &'items mut [MaybeUninit<T>],
+        initialized: usize,
+    }
+
+    impl<T> Drop for Guard<'_, T> {
+        fn drop(&mut self) {
+            for inited in &mut self.items[0..self.initialized] {
+                // SAFETY: the first `initialized` items have been written.
+                unsafe { inited.assume_init_drop() };
+            }
         }
-        Ok(array.map(|maybe_uninit| unsafe { maybe_uninit.assume_init() }))
     }
+
+    let mut guard = Guard { items, initialized: 0 };
+    while guard.initialized < guard.items.len() {
+        let value = f()?;
+        guard.items[guard.initialized].write(value);
+        guard.initialized += 1;
+    }
+    core::mem::forget(guard);
+    Ok(())
+}
+
+impl<T, const N: usize> StaticLayout for [T; N]
+where
+    T: StaticLayout,
+{
+    const LAYOUT: Layout = T::LAYOUT.repeat(N as u64).nest();
 }
 
 #[cfg(test)]
@@ -68,7 +103,7 @@ mod tests {
     use super::*;
 
     thread_local! {
-        static NUM_CONSTRUCTED: AtomicIsize = AtomicIsize::new(0);
+        static NUM_CONSTRUCTED: AtomicIsize = const { AtomicIsize::new(0) };
     }
 
     mod instrumented {
@@ -93,7 +128,7 @@ mod tests {
 
         impl Deserialize for Instrumented {
             fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
-                u8::deserialize(deserializer).map(|x| Self::new(x))
+                u8::deserialize(deserializer).map(Self::new)
             }
         }
 
@@ -117,6 +152,12 @@ mod tests {
         assert_eq!(NUM_CONSTRUCTED.with(|x| x.load(Ordering::Relaxed)), 0);
     }
 
+    #[test]
+    fn serialize_numbers() {
+        assert_eq!([0x0102_u16, 0x0304].to_bytes().unwrap(), [0x02, 0x01, 0x04, 0x03]);
+        assert_eq!([1.0_f32].to_bytes().unwrap(), 1.0_f32.to_le_bytes());
+    }
+
     #[test]
     fn deserialize_success() {
         assert_eq!(NUM_CONSTRUCTED.with(|x| x.load(Ordering::Relaxed)), 0);
@@ -137,4 +178,26 @@ mod tests {
         }
         assert_eq!(NUM_CONSTRUCTED.with(|x| x.load(Ordering::Relaxed)), 0);
     }
+
+    #[test]
+    fn serialize_empty() {
+        let value: [u16; 0] = [];
+        assert_eq!(value.to_bytes().unwrap(), []);
+    }
+
+    #[test]
+    fn deserialize_empty() {
+        assert_eq!(<[u16; 0]>::from_bytes(&[]).unwrap(), []);
+    }
+
+    #[test]
+    fn try_array_from_fn_panic() {
+        assert_eq!(NUM_CONSTRUCTED.with(|x| x.load(Ordering::Relaxed)), 0);
+        let result = std::panic::catch_unwind(|| {
+            let mut values = [1, 2].into_iter();
+            try_array_from_fn::<_, (), 3>(|| Ok(Instrumented::new(values.next().unwrap())))
+        });
+        assert!(result.is_err());
+        assert_eq!(NUM_CONSTRUCTED.with(|x| x.load(Ordering::Relaxed)), 0);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
 
 impl Serialize for bool {
@@ -11,3 +12,7 @@ impl Deserialize for bool {
         deserializer.deserialize_bool()
     }
 }
+
+impl StaticLayout for bool {
+    const LAYOUT: Layout = Layout::fixed(1);
+}
@@ -0,0 +1,95 @@
+use alloc::boxed::Box;
+use core::mem::MaybeUninit;
+
+use super::array::try_init_items;
+use crate::layout::{Layout, StaticLayout};
+use crate::ser_de::{
+    Deserialize, DeserializeBoxed, Deserializer, MultiPassSerialize, RevisableSerializer, Serialize, Serializer,
+};
+
+/// Blanket implementation of serialize for boxes.
+impl<T: Serialize> Serialize for Box<T> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+/// Blanket implementation of multi-pass serialize for boxes.
+impl<T: MultiPassSerialize> MultiPassSerialize for Box<T> {
+    fn serialize<S: RevisableSerializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+/// Blanket implementation of deserialize for boxes.
+///
+/// The value is deserialized on the stack and moved to the heap afterwards.
+/// Use [`DeserializeBoxed`] to deserialize large arrays directly into heap
+/// storage.
+impl<T: Deserialize> Deserialize for Box<T> {
+    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Box::new)
+    }
+}
+
+/// Blanket implementation of the static layout for boxes.
+impl<T: StaticLayout> StaticLayout for Box<T> {
+    const LAYOUT: Layout = T::LAYOUT;
+}
+
+impl<T, const N: usize> DeserializeBoxed for [T; N]
+where
+    T: Deserialize,
+{
+    fn deserialize_boxed<D: Deserializer>(deserializer: &mut D) -> Result<Box<Self>, D::Error> {
+        let mut array = Box::<[T; N]>::new_uninit();
+        // SAFETY: `[MaybeUninit<T>; N]` has the same layout as `MaybeUninit<[T; N]>`.
+        let items = unsafe { &mut *array.as_mut_ptr().cast::<[MaybeUninit<T>; N]>() };
+        try_init_items(items, || T::deserialize(deserializer))?;
+        Ok(unsafe { array.assume_init() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::Error;
+    use crate::io::FixedMemoryStream;
+    use crate::ser_de::{FromBytes, ToBytes};
+    use crate::stream_ser_de::StreamDeserializer;
+
+    #[test]
+    fn serialize() {
+        let value = Box::new([1u8, 2, 3]);
+        assert_eq!(value.to_bytes().unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn deserialize() {
+        assert_eq!(<Box<[u8; 3]>>::from_bytes(&[1, 2, 3]).unwrap(), Box::new([1, 2, 3]));
+    }
+
+    #[test]
+    fn deserialize_boxed_success() -> Result<(), Error> {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([1u8, 2, 3]));
+        assert_eq!(<[u8; 3]>::deserialize_boxed(&mut deserializer)?, Box::new([1, 2, 3]));
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_boxed_failure() {
+        let mut deserializer = StreamDeserializer::new(FixedMemoryStream::new([1u8, 2]));
+        assert!(<[u8; 3]>::deserialize_boxed(&mut deserializer).is_err());
+    }
+
+    #[test]
+    fn deserialize_boxed_large() -> Result<(), Error> {
+        const LEN: usize = 1 << 20;
+        let bytes = alloc::vec![0xAB; LEN];
+        let mut deserializer = StreamDeserializer::new(crate::io::GrowingMemoryStream::from(bytes));
+        let array = <[u8; LEN]>::deserialize_boxed(&mut deserializer)?;
+        assert!(array.iter().all(|byte| *byte == 0xAB));
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(feature = "alloc")]
+use alloc::{ffi::CString, vec::Vec};
+use core::ffi::CStr;
+
+#[cfg(feature = "alloc")]
+use crate::ser_de::{Deserialize, Deserializer};
+use crate::ser_de::{Serialize, Serializer};
+
+impl Serialize for CStr {
+    /// Serialize the bytes of the string followed by a null byte.
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer.serialize_slice(self.to_bytes_with_nul())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Serialize for CString {
+    /// Serialize the bytes of the string followed by a null byte.
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        self.as_c_str().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Deserialize for CString {
+    /// Deserialize the bytes of the string until and including a null byte.
+    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+        let mut bytes = Vec::new();
+        loop {
+            match deserializer.deserialize_u8()? {
+                0 => break,
+                byte => bytes.push(byte),
+            }
+        }
+        // SAFETY: the bytes contain no null bytes as the loop stops at the first one.
+        Ok(unsafe { CString::from_vec_unchecked(bytes) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::io::GrowingMemoryStream;
+    use crate::ser_de::{FromBytes, ToBytes};
+    use crate::stream_ser_de::StreamSerializer;
+
+    #[test]
+    fn serialize_c_str() {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        assert!(c"ab".serialize(&mut serializer).is_ok());
+        assert_eq!(serializer.take().take(), [b'a', b'b', 0]);
+    }
+
+    #[test]
+    fn serialize_c_string() {
+        assert_eq!(CString::from(c"ab").to_bytes().unwrap(), [b'a', b'b', 0]);
+    }
+
+    #[test]
+    fn deserialize_c_string() {
+        assert_eq!(CString::from_bytes(b"ab\0").unwrap(), CString::from(c"ab"));
+    }
+
+    #[test]
+    fn deserialize_c_string_missing_terminator() {
+        assert!(CString::from_bytes(b"ab").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::MessageError;
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
 
 impl Serialize for char {
@@ -13,11 +14,14 @@ impl Deserialize for char {
     fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
         deserializer
             .deserialize_u32()
-            .map(|x| char::try_from(x).map_err(|_| D::Error::message("invalid code point")))
-            .flatten()
+            .and_then(|x| char::try_from(x).map_err(|_| D::Error::message("invalid code point")))
     }
 }
 
+impl StaticLayout for char {
+    const LAYOUT: Layout = Layout::fixed(4);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::ser_de::{FromBytes, ToBytes};
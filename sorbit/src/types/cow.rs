@@ -0,0 +1,51 @@
+use alloc::borrow::{Cow, ToOwned};
+
+use crate::layout::{Layout, StaticLayout};
+use crate::ser_de::{Deserialize, Deserializer, MultiPassSerialize, RevisableSerializer, Serialize, Serializer};
+
+/// Blanket implementation of serialize for clone-on-write pointers.
+impl<T: ?Sized + ToOwned + Serialize> Serialize for Cow<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+/// Blanket implementation of multi-pass serialize for clone-on-write pointers.
+impl<T: ?Sized + ToOwned + MultiPassSerialize> MultiPassSerialize for Cow<'_, T> {
+    fn serialize<S: RevisableSerializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+/// Blanket implementation of deserialize for clone-on-write pointers.
+///
+/// The value is always deserialized as [`Cow::Owned`].
+impl<T: ?Sized + ToOwned<Owned: Deserialize>> Deserialize for Cow<'_, T> {
+    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+        T::Owned::deserialize(deserializer).map(Cow::Owned)
+    }
+}
+
+/// Blanket implementation of the static layout for clone-on-write pointers.
+impl<T: ?Sized + ToOwned + StaticLayout> StaticLayout for Cow<'_, T> {
+    const LAYOUT: Layout = T::LAYOUT;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ser_de::{FromBytes, ToBytes};
+
+    #[test]
+    fn serialize_borrowed() {
+        let value = 0x0102u16;
+        let value: Cow<u16> = Cow::Borrowed(&value);
+        assert_eq!(ToBytes::to_be_bytes(&value).unwrap(), [0x01, 0x02]);
+    }
+
+    #[test]
+    fn deserialize_owned() {
+        let value = <Cow<u16> as FromBytes>::from_be_bytes(&[0x01, 0x02]).unwrap();
+        assert!(matches!(value, Cow::Owned(0x0102)));
+    }
+}
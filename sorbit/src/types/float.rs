@@ -1,47 +1,54 @@
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
 
 impl Serialize for f32 {
-    /// Serialize the floating point object.
-    ///
-    /// The floating point type is first converted to its raw bits using
-    /// [`to_bits`](f32::to_bits), then serialized as an integer using the
-    /// current byte order.
+    /// Serialize the floating point object in IEEE 754 binary32 format
+    /// using the current byte order.
     fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
-        serializer.serialize_u32(self.to_bits())
+        serializer.serialize_f32(*self)
+    }
+
+    fn serialize_many<S: Serializer>(items: &[Self], serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_f32_slice(items).map(|_| ())
     }
 }
 
 impl Deserialize for f32 {
-    /// Deserialize a floating point object.
-    ///
-    /// The bits of the floating point object are first deserialized as an
-    /// integer, and then converted to a float using [`from_bits`](f32::from_bits).
+    /// Deserialize a floating point object in IEEE 754 binary32 format
+    /// using the current byte order.
     fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
-        deserializer.deserialize_u32().map(|bits| f32::from_bits(bits))
+        deserializer.deserialize_f32()
     }
 }
 
 impl Serialize for f64 {
-    /// Serialize the floating point object.
-    ///
-    /// The floating point type is first converted to its raw bits using
-    /// [`to_bits`](f64::to_bits), then serialized as an integer using the
-    /// current byte order.
+    /// Serialize the floating point object in IEEE 754 binary64 format
+    /// using the current byte order.
     fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
-        serializer.serialize_u64(self.to_bits())
+        serializer.serialize_f64(*self)
+    }
+
+    fn serialize_many<S: Serializer>(items: &[Self], serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_f64_slice(items).map(|_| ())
     }
 }
 
 impl Deserialize for f64 {
-    /// Deserialize a floating point object.
-    ///
-    /// The bits of the floating point object are first deserialized as an
-    /// integer, and then converted to a float using [`from_bits`](f64::from_bits).
+    /// Deserialize a floating point object in IEEE 754 binary64 format
+    /// using the current byte order.
     fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
-        deserializer.deserialize_u64().map(|bits| f64::from_bits(bits))
+        deserializer.deserialize_f64()
     }
 }
 
+impl StaticLayout for f32 {
+    const LAYOUT: Layout = Layout::fixed(4);
+}
+
+impl StaticLayout for f64 {
+    const LAYOUT: Layout = Layout::fixed(8);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::ser_de::{FromBytes, ToBytes};
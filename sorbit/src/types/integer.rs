@@ -1,3 +1,4 @@
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
 
 macro_rules! impl_serialize {
@@ -8,6 +9,17 @@ macro_rules! impl_serialize {
             }
         }
     };
+    ($type:ty, $func:ident, $slice_func:ident) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+                serializer.$func(*self)
+            }
+
+            fn serialize_many<S: Serializer>(items: &[Self], serializer: &mut S) -> Result<(), S::Error> {
+                serializer.$slice_func(items).map(|_| ())
+            }
+        }
+    };
 }
 
 macro_rules! impl_deserialize {
@@ -21,15 +33,23 @@ macro_rules! impl_deserialize {
 }
 
 impl_serialize!(u8, serialize_u8);
-impl_serialize!(u16, serialize_u16);
-impl_serialize!(u32, serialize_u32);
-impl_serialize!(u64, serialize_u64);
-impl_serialize!(u128, serialize_u128);
+impl_serialize!(u16, serialize_u16, serialize_u16_slice);
+impl_serialize!(u32, serialize_u32, serialize_u32_slice);
+impl_serialize!(u64, serialize_u64, serialize_u64_slice);
+impl_serialize!(u128, serialize_u128, serialize_u128_slice);
 impl_serialize!(i8, serialize_i8);
-impl_serialize!(i16, serialize_i16);
-impl_serialize!(i32, serialize_i32);
-impl_serialize!(i64, serialize_i64);
-impl_serialize!(i128, serialize_i128);
+impl_serialize!(i16, serialize_i16, serialize_i16_slice);
+impl_serialize!(i32, serialize_i32, serialize_i32_slice);
+impl_serialize!(i64, serialize_i64, serialize_i64_slice);
+impl_serialize!(i128, serialize_i128, serialize_i128_slice);
+
+macro_rules! impl_static_layout {
+    ($type:ty) => {
+        impl StaticLayout for $type {
+            const LAYOUT: Layout = Layout::fixed(size_of::<$type>() as u64);
+        }
+    };
+}
 
 impl_deserialize!(u8, deserialize_u8);
 impl_deserialize!(u16, deserialize_u16);
@@ -42,66 +62,51 @@ impl_deserialize!(i32, deserialize_i32);
 impl_deserialize!(i64, deserialize_i64);
 impl_deserialize!(i128, deserialize_i128);
 
-impl Serialize for isize {
-    /// `isize` is serialized as its original size. The serialized data is not
-    /// sharable between different platforms.
-    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
-        match size_of::<Self>() {
-            1 => (*self as i8).serialize(serializer),
-            2 => (*self as i16).serialize(serializer),
-            4 => (*self as i32).serialize(serializer),
-            8 => (*self as i64).serialize(serializer),
-            16 => (*self as i128).serialize(serializer),
-            x => panic!("size_of::<isize>() == {x}, can not find equivalent fixed-size integer type"),
-        }
-    }
-}
+impl_static_layout!(u8);
+impl_static_layout!(u16);
+impl_static_layout!(u32);
+impl_static_layout!(u64);
+impl_static_layout!(u128);
+impl_static_layout!(i8);
+impl_static_layout!(i16);
+impl_static_layout!(i32);
+impl_static_layout!(i64);
+impl_static_layout!(i128);
 
-impl Deserialize for isize {
-    /// `isize` is serialized as its original size. The serialized data is not
-    /// sharable between different platforms.
-    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
-        match size_of::<Self>() {
-            1 => i8::deserialize(deserializer).map(|x| x as Self),
-            2 => i16::deserialize(deserializer).map(|x| x as Self),
-            4 => i32::deserialize(deserializer).map(|x| x as Self),
-            8 => i64::deserialize(deserializer).map(|x| x as Self),
-            16 => i128::deserialize(deserializer).map(|x| x as Self),
-            x => panic!("size_of::<isize>() == {x}, can not find equivalent fixed-size integer type"),
+macro_rules! impl_size {
+    ($type:ty, $repr:ty) => {
+        impl Serialize for $type {
+            /// The value is always serialized as 64 bits, regardless of the
+            /// platform, so that the serialized data is sharable between platforms.
+            fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+                match <$repr>::try_from(*self) {
+                    Ok(value) => value.serialize(serializer),
+                    Err(_) => match serializer.error("the value does not fit into 64 bits")? {},
+                }
+            }
         }
-    }
-}
 
-impl Serialize for usize {
-    /// `usize` is serialized as its original size. The serialized data is not
-    /// sharable between different platforms.
-    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
-        match size_of::<Self>() {
-            1 => (*self as u8).serialize(serializer),
-            2 => (*self as u16).serialize(serializer),
-            4 => (*self as u32).serialize(serializer),
-            8 => (*self as u64).serialize(serializer),
-            16 => (*self as u128).serialize(serializer),
-            x => panic!("size_of::<isize>() == {x}, can not find equivalent fixed-size integer type"),
+        impl Deserialize for $type {
+            /// The value is always deserialized from 64 bits. An error is
+            /// returned if the value does not fit into the platform's size.
+            fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+                let value = <$repr>::deserialize(deserializer)?;
+                match <$type>::try_from(value) {
+                    Ok(value) => Ok(value),
+                    Err(_) => deserializer.invalid_value(),
+                }
+            }
         }
-    }
-}
 
-impl Deserialize for usize {
-    /// `usize` is serialized as its original size. The serialized data is not
-    /// sharable between different platforms.
-    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
-        match size_of::<Self>() {
-            1 => u8::deserialize(deserializer).map(|x| x as Self),
-            2 => u16::deserialize(deserializer).map(|x| x as Self),
-            4 => u32::deserialize(deserializer).map(|x| x as Self),
-            8 => u64::deserialize(deserializer).map(|x| x as Self),
-            16 => u128::deserialize(deserializer).map(|x| x as Self),
-            x => panic!("size_of::<isize>() == {x}, can not find equivalent fixed-size integer type"),
+        impl StaticLayout for $type {
+            const LAYOUT: Layout = <$repr as StaticLayout>::LAYOUT;
         }
-    }
+    };
 }
 
+impl_size!(usize, u64);
+impl_size!(isize, i64);
+
 #[cfg(test)]
 mod tests {
     use crate::ser_de::{FromBytes, ToBytes};
@@ -113,7 +118,7 @@ mod tests {
     #[case(isize::MIN)]
     #[case(isize::MAX)]
     pub fn serialize_isize(#[case] value: isize) {
-        let bytes = value.to_be_bytes();
+        let bytes = (value as i64).to_be_bytes();
         assert_eq!(ToBytes::to_be_bytes(&value).unwrap(), bytes);
         assert_eq!(<isize as FromBytes>::from_be_bytes(&bytes).unwrap(), value);
     }
@@ -123,7 +128,7 @@ mod tests {
     #[case(usize::MIN)]
     #[case(usize::MAX)]
     pub fn serialize_usize(#[case] value: usize) {
-        let bytes = value.to_be_bytes();
+        let bytes = (value as u64).to_be_bytes();
         assert_eq!(ToBytes::to_be_bytes(&value).unwrap(), bytes);
         assert_eq!(<usize as FromBytes>::from_be_bytes(&bytes).unwrap(), value);
     }
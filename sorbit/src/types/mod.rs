@@ -1,8 +1,18 @@
 mod array;
 mod boolean;
+#[cfg(feature = "alloc")]
+mod boxed;
+mod c_string;
 mod char;
+#[cfg(feature = "alloc")]
+mod cow;
 mod float;
 mod integer;
+mod non_zero;
 mod phantom_data;
 mod reference;
+#[cfg(feature = "alloc")]
+mod shared;
 mod tuple;
+
+pub(crate) use array::try_array_from_fn;
@@ -0,0 +1,74 @@
+use core::num::{
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize, NonZeroU8, NonZeroU16, NonZeroU32,
+    NonZeroU64, NonZeroU128, NonZeroUsize,
+};
+
+use crate::layout::{Layout, StaticLayout};
+use crate::ser_de::{Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! impl_non_zero {
+    ($type:ty, $underlying:ty) => {
+        impl Serialize for $type {
+            /// Serialize the value as its underlying integer.
+            fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+                self.get().serialize(serializer)
+            }
+        }
+
+        impl Deserialize for $type {
+            /// Deserialize the value as its underlying integer. An error is
+            /// returned if the integer is zero.
+            fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+                let value = <$underlying>::deserialize(deserializer)?;
+                match <$type>::new(value) {
+                    Some(value) => Ok(value),
+                    None => deserializer.invalid_value(),
+                }
+            }
+        }
+
+        impl StaticLayout for $type {
+            const LAYOUT: Layout = <$underlying as StaticLayout>::LAYOUT;
+        }
+    };
+}
+
+impl_non_zero!(NonZeroU8, u8);
+impl_non_zero!(NonZeroU16, u16);
+impl_non_zero!(NonZeroU32, u32);
+impl_non_zero!(NonZeroU64, u64);
+impl_non_zero!(NonZeroU128, u128);
+impl_non_zero!(NonZeroUsize, usize);
+impl_non_zero!(NonZeroI8, i8);
+impl_non_zero!(NonZeroI16, i16);
+impl_non_zero!(NonZeroI32, i32);
+impl_non_zero!(NonZeroI64, i64);
+impl_non_zero!(NonZeroI128, i128);
+impl_non_zero!(NonZeroIsize, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{Error, ErrorKind};
+    use crate::ser_de::{FromBytes, ToBytes};
+
+    #[test]
+    pub fn serialize_non_zero() {
+        let value = NonZeroU16::new(0x1234).unwrap();
+        assert_eq!(ToBytes::to_be_bytes(&value).unwrap(), [0x12, 0x34]);
+        assert_eq!(<NonZeroU16 as FromBytes>::from_be_bytes(&[0x12, 0x34]).unwrap(), value);
+    }
+
+    #[test]
+    pub fn serialize_non_zero_signed() {
+        let value = NonZeroI8::new(-1).unwrap();
+        assert_eq!(ToBytes::to_be_bytes(&value).unwrap(), [0xFF]);
+        assert_eq!(<NonZeroI8 as FromBytes>::from_be_bytes(&[0xFF]).unwrap(), value);
+    }
+
+    #[test]
+    pub fn deserialize_zero() {
+        let result = <NonZeroU32 as FromBytes>::from_be_bytes(&[0, 0, 0, 0]);
+        assert_eq!(result, Err(Error::from(ErrorKind::InvalidValue)));
+    }
+}
@@ -1,5 +1,6 @@
 use core::marker::PhantomData;
 
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{Deserialize, Serialize};
 
 impl<T> Serialize for PhantomData<T> {
@@ -14,6 +15,10 @@ impl<T> Deserialize for PhantomData<T> {
     }
 }
 
+impl<T> StaticLayout for PhantomData<T> {
+    const LAYOUT: Layout = Layout::EMPTY;
+}
+
 #[cfg(test)]
 mod tests {
     use core::marker::PhantomData;
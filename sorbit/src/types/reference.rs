@@ -1,3 +1,4 @@
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{MultiPassSerialize, RevisableSerializer, Serialize, Serializer};
 
 /// Blanket implementation of serialize for references.
@@ -27,3 +28,13 @@ impl<T: MultiPassSerialize> MultiPassSerialize for &mut T {
         (*self as &T).serialize(serializer)
     }
 }
+
+/// Blanket implementation of the static layout for references.
+impl<T: StaticLayout> StaticLayout for &T {
+    const LAYOUT: Layout = T::LAYOUT;
+}
+
+/// Blanket implementation of the static layout for mutable references.
+impl<T: StaticLayout> StaticLayout for &mut T {
+    const LAYOUT: Layout = T::LAYOUT;
+}
@@ -0,0 +1,64 @@
+use alloc::rc::Rc;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+
+use crate::layout::{Layout, StaticLayout};
+use crate::ser_de::{Deserialize, Deserializer, MultiPassSerialize, RevisableSerializer, Serialize, Serializer};
+
+macro_rules! impl_shared {
+    ($pointer:ident) => {
+        /// Blanket implementation of serialize for shared pointers.
+        impl<T: Serialize> Serialize for $pointer<T> {
+            fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+                self.as_ref().serialize(serializer)
+            }
+        }
+
+        /// Blanket implementation of multi-pass serialize for shared pointers.
+        impl<T: MultiPassSerialize> MultiPassSerialize for $pointer<T> {
+            fn serialize<S: RevisableSerializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+                self.as_ref().serialize(serializer)
+            }
+        }
+
+        /// Blanket implementation of deserialize for shared pointers.
+        ///
+        /// Each deserialized pointer owns a new allocation, sharing is not
+        /// preserved.
+        impl<T: Deserialize> Deserialize for $pointer<T> {
+            fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+                T::deserialize(deserializer).map($pointer::new)
+            }
+        }
+
+        /// Blanket implementation of the static layout for shared pointers.
+        impl<T: StaticLayout> StaticLayout for $pointer<T> {
+            const LAYOUT: Layout = T::LAYOUT;
+        }
+    };
+}
+
+impl_shared!(Rc);
+#[cfg(target_has_atomic = "ptr")]
+impl_shared!(Arc);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ser_de::{FromBytes, ToBytes};
+
+    #[test]
+    fn serialize_rc() {
+        let value = Rc::new(0x0102u16);
+        assert_eq!(ToBytes::to_be_bytes(&value).unwrap(), [0x01, 0x02]);
+        assert_eq!(<Rc<u16> as FromBytes>::from_be_bytes(&[0x01, 0x02]).unwrap(), value);
+    }
+
+    #[test]
+    #[cfg(target_has_atomic = "ptr")]
+    fn serialize_arc() {
+        let value = Arc::new(0x0102u16);
+        assert_eq!(ToBytes::to_be_bytes(&value).unwrap(), [0x01, 0x02]);
+        assert_eq!(<Arc<u16> as FromBytes>::from_be_bytes(&[0x01, 0x02]).unwrap(), value);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::layout::{Layout, StaticLayout};
 use crate::ser_de::{Deserialize, Deserializer, MultiPassSerialize, RevisableSerializer, Serialize, Serializer};
 
 // The normal and multi-pass serializers here are not complete. There should be
@@ -40,6 +41,12 @@ macro_rules! impl_tuple {
                 ))
             }
         }
+
+        impl<$($members,)*> StaticLayout for ($($members,)*)
+            where $($members: StaticLayout),*
+        {
+            const LAYOUT: Layout = Layout::EMPTY$(.then($members::LAYOUT))*.nest();
+        }
     };
 }
 
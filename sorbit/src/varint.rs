@@ -0,0 +1,150 @@
+//! Utilities for serializing integers as variable-length integers.
+//!
+//! Formats like Protocol Buffers and many network protocols store integers in
+//! as few bytes as their value requires, using the LEB128 encoding. Signed
+//! integers are zig-zag encoded first, so that values of small magnitude take
+//! few bytes regardless of their sign. With the derive macros, integer fields
+//! can be annotated to be stored as variable-length integers:
+//!
+//! ```
+//! use sorbit::{Deserialize, Serialize};
+//! use sorbit::ser_de::{FromBytes, ToBytes};
+//!
+//! #[derive(Debug, Serialize, Deserialize, PartialEq)]
+//! struct Update {
+//!     #[sorbit(varint)]
+//!     id: u32,
+//!     #[sorbit(varint)]
+//!     delta: i64,
+//! }
+//!
+//! let update = Update { id: 300, delta: -2 };
+//! let bytes = [0xAC, 0x02, 0x03];
+//! assert_eq!(update.to_bytes().unwrap(), bytes);
+//! assert_eq!(Update::from_bytes(&bytes).unwrap(), update);
+//! ```
+//!
+//! See [`Serializer::serialize_varint_u64`] and
+//! [`Serializer::serialize_varint_i64`] for the details of the encodings.
+
+use crate::layout::Layout;
+use crate::ser_de::{Deserializer, Serialize, Serializer};
+
+/// An integer that can be stored as a variable-length integer.
+pub trait VarInt: Sized {
+    /// The layout of the integer as encoded.
+    const LAYOUT: Layout;
+
+    /// Serialize the integer as a variable-length integer.
+    fn serialize_varint<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error>;
+
+    /// Deserialize the integer from a variable-length integer.
+    ///
+    /// If the deserialized value does not fit into the integer, an error is
+    /// returned.
+    fn deserialize_varint<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error>;
+}
+
+macro_rules! impl_varint {
+    ($ty:ty, $wide:ty, $serialize:ident, $deserialize:ident) => {
+        impl VarInt for $ty {
+            const LAYOUT: Layout = Layout::new(1, Some((<$ty>::BITS as u64).div_ceil(7)), 0);
+
+            fn serialize_varint<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+                serializer.$serialize(*self as $wide)
+            }
+
+            fn deserialize_varint<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+                let value = deserializer.$deserialize()?;
+                <$ty>::try_from(value)
+                    .or_else(|_| deserializer.error("the variable-length integer is out of range for its type"))
+            }
+        }
+    };
+}
+
+impl_varint!(u8, u64, serialize_varint_u64, deserialize_varint_u64);
+impl_varint!(u16, u64, serialize_varint_u64, deserialize_varint_u64);
+impl_varint!(u32, u64, serialize_varint_u64, deserialize_varint_u64);
+impl_varint!(u64, u64, serialize_varint_u64, deserialize_varint_u64);
+impl_varint!(i8, i64, serialize_varint_i64, deserialize_varint_i64);
+impl_varint!(i16, i64, serialize_varint_i64, deserialize_varint_i64);
+impl_varint!(i32, i64, serialize_varint_i64, deserialize_varint_i64);
+impl_varint!(i64, i64, serialize_varint_i64, deserialize_varint_i64);
+
+/// An integer serialized as a variable-length integer.
+pub struct Encoded<'value, T> {
+    value: &'value T,
+}
+
+impl<'value, T: VarInt> Serialize for Encoded<'value, T> {
+    /// Serialize the integer as a variable-length integer.
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        self.value.serialize_varint(serializer)
+    }
+}
+
+/// Serialize the integer as a variable-length integer.
+pub fn encoded<T: VarInt>(value: &T) -> Encoded<'_, T> {
+    Encoded { value }
+}
+
+/// Deserialize an integer stored as a variable-length integer.
+pub fn deserialize_encoded<T, D>(deserializer: &mut D) -> Result<T, D::Error>
+where
+    T: VarInt,
+    D: Deserializer,
+{
+    T::deserialize_varint(deserializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::{Error, ErrorKind};
+    use crate::io::GrowingMemoryStream;
+    use crate::stream_ser_de::{StreamDeserializer, StreamSerializer};
+
+    fn serialize<T: VarInt>(value: T) -> Result<Vec<u8>, Error> {
+        let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+        encoded(&value).serialize(&mut serializer)?;
+        Ok(serializer.take().take())
+    }
+
+    fn deserialize<T: VarInt>(bytes: &[u8]) -> Result<T, Error> {
+        let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+        deserialize_encoded(&mut deserializer)
+    }
+
+    #[test]
+    fn unsigned() {
+        assert_eq!(serialize(300u16), Ok(vec![0xAC, 0x02]));
+        assert_eq!(deserialize::<u16>(&[0xAC, 0x02]), Ok(300));
+    }
+
+    #[test]
+    fn signed() {
+        assert_eq!(serialize(-150i32), Ok(vec![0xAB, 0x02]));
+        assert_eq!(deserialize::<i32>(&[0xAB, 0x02]), Ok(-150));
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(
+            deserialize::<u8>(&[0xAC, 0x02]),
+            Err(ErrorKind::Custom("the variable-length integer is out of range for its type").into())
+        );
+        assert_eq!(
+            deserialize::<i8>(&[0xAB, 0x02]),
+            Err(ErrorKind::Custom("the variable-length integer is out of range for its type").into())
+        );
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(u8::LAYOUT, Layout::new(1, Some(2), 0));
+        assert_eq!(u32::LAYOUT, Layout::new(1, Some(5), 0));
+        assert_eq!(i64::LAYOUT, Layout::new(1, Some(10), 0));
+    }
+}
@@ -0,0 +1,98 @@
+//! Regression tests for binary samples, like the inputs found by fuzzing.
+//!
+//! Every `tests/corpus/<name>/*.bin` file is deserialized as the type that is
+//! checked against the `<name>` directory. Deserialization must not panic, and
+//! must either fail with the error in the `.err` file next to the sample, or
+//! succeed and serialize back into the same bytes if there is no such file.
+//! Run the tests with `SORBIT_CORPUS=overwrite` to record the errors of new
+//! samples.
+
+use std::ffi::OsStr;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::Path;
+use std::{env, fs};
+
+use sorbit::io::GrowingMemoryStream;
+use sorbit::ser_de::{self, Deserializer as _};
+use sorbit::stream_ser_de::{StreamDeserializer, StreamSerializer};
+use sorbit::{Deserialize, Serialize, SorbitTag};
+
+#[derive(Debug, PartialEq, SorbitTag)]
+#[repr(u8)]
+enum Kind {
+    Data = 1,
+    Control = 2,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[sorbit(byte_order=big_endian, magic=b"SMPL")]
+struct Sample {
+    version: u8,
+    #[sorbit(len_prefix=u8)]
+    items: Vec<u16>,
+    urgent: bool,
+    kind: Kind,
+}
+
+#[test]
+fn corpus() {
+    check_corpus::<Sample>("sample");
+}
+
+fn check_corpus<T: ser_de::Serialize + ser_de::Deserialize>(name: &str) {
+    let corpus_dir = env::current_dir().unwrap().join("tests").join("corpus").join(name);
+    let mut samples: Vec<_> = fs::read_dir(&corpus_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some(OsStr::new("bin")))
+        .collect();
+    samples.sort();
+    assert!(!samples.is_empty(), "no samples found, the test configuration is wrong");
+
+    let overwrite = env::var("SORBIT_CORPUS").is_ok_and(|value| value == "overwrite");
+    let failures: Vec<_> = samples.iter().filter(|sample| !check_sample::<T>(sample, overwrite)).collect();
+    assert!(failures.is_empty(), "samples behave differently than expected: {failures:#?}");
+}
+
+fn check_sample<T: ser_de::Serialize + ser_de::Deserialize>(sample: &Path, overwrite: bool) -> bool {
+    let bytes = fs::read(sample).unwrap();
+    let Ok(actual) = catch_unwind(AssertUnwindSafe(|| round_trip::<T>(&bytes))) else {
+        eprintln!("{}: panicked", sample.display());
+        return false;
+    };
+
+    let expected_path = sample.with_extension("err");
+    if overwrite {
+        match &actual {
+            Err(error) => fs::write(&expected_path, error).unwrap(),
+            Ok(_) if expected_path.exists() => fs::remove_file(&expected_path).unwrap(),
+            Ok(_) => (),
+        }
+    }
+    let expected = fs::read_to_string(&expected_path).ok();
+    match (actual, expected) {
+        (Ok(serialized), None) if serialized == bytes => true,
+        (Ok(serialized), None) => {
+            eprintln!("{}: serialized into different bytes: {serialized:02X?}", sample.display());
+            false
+        }
+        (Err(actual), Some(expected)) if actual == expected => true,
+        (actual, expected) => {
+            eprintln!("{}: EXPECTED: {expected:?}, ACTUAL: {actual:?}", sample.display());
+            false
+        }
+    }
+}
+
+/// Deserialize a value from `bytes`, and serialize it back into the bytes
+/// that were consumed.
+fn round_trip<T: ser_de::Serialize + ser_de::Deserialize>(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+    let value = T::deserialize(&mut deserializer).map_err(|error| error.to_string())?;
+    if deserializer.position() != bytes.len() as u64 {
+        return Err(format!("the value ends at offset {}, before the end of the sample", deserializer.position()));
+    }
+    let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+    value.serialize(&mut serializer).map_err(|error| error.to_string())?;
+    Ok(serializer.take().take())
+}
@@ -23,6 +23,10 @@ fn expand_src_file(file: &Path) {
         let file = fs::File::open(file).unwrap();
         emulate_derive_macro_expansion(file, &[("Deserialize", expand_deserialize)]).unwrap();
     }
+    {
+        let file = fs::File::open(file).unwrap();
+        emulate_derive_macro_expansion(file, &[("StaticLayout", expand_static_layout)]).unwrap();
+    }
     {
         let file = fs::File::open(file).unwrap();
         emulate_derive_macro_expansion(file, &[("PackInto", expand_pack_into)]).unwrap();
@@ -45,6 +49,12 @@ fn expand_deserialize(input: TokenStream) -> TokenStream {
     derive_object.derive_deserialize()
 }
 
+fn expand_static_layout(input: TokenStream) -> TokenStream {
+    let derive_input = syn::parse2(input).unwrap();
+    let derive_object = DeriveObject::parse(derive_input).unwrap();
+    derive_object.derive_static_layout()
+}
+
 fn expand_pack_into(input: TokenStream) -> TokenStream {
     let derive_input = syn::parse2(input).unwrap();
     let derive_object = DeriveObject::parse(derive_input).unwrap();
@@ -1,6 +1,6 @@
 use sorbit::{
     PackInto, UnpackFrom,
-    bit::{PackInto, UnpackFrom},
+    bit::{Error as BitError, PackInto, UnpackFrom},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PackInto, UnpackFrom)]
@@ -26,6 +26,24 @@ enum CatchAllTuple {
     CatchAll(u8),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PackInto, UnpackFrom)]
+#[repr(u8)]
+enum CatchAllStruct {
+    A = 0,
+    #[sorbit(catch_all)]
+    CatchAll {
+        discr: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PackInto, UnpackFrom)]
+#[repr(u16)]
+enum Wide {
+    A = 0x100,
+    #[sorbit(catch_all)]
+    CatchAll(u16),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,6 +63,7 @@ mod tests {
     #[test]
     fn invalid() {
         assert_eq!(Strict::unpack_from(3u16, 2), Err(3u16));
+        assert_eq!(<Strict as UnpackFrom<u16>>::unpack_error(), BitError::InvalidEnumVariant);
     }
 
     #[rstest]
@@ -67,4 +86,24 @@ mod tests {
         let backward = CatchAllTuple::unpack_from(packed, 2).unwrap();
         assert_eq!(value, backward);
     }
+
+    #[rstest]
+    #[case(CatchAllStruct::A, 0x00_u16)]
+    #[case(CatchAllStruct::CatchAll { discr: 3 }, 3_u16)]
+    fn catch_all_struct(#[case] value: CatchAllStruct, #[case] packed: u16) {
+        let forward: u16 = value.pack_into(2).unwrap();
+        assert_eq!(forward, packed);
+        let backward = CatchAllStruct::unpack_from(packed, 2).unwrap();
+        assert_eq!(value, backward);
+    }
+
+    #[rstest]
+    #[case(Wide::A, 0x100_u16)]
+    #[case(Wide::CatchAll(0x1FF), 0x1FF_u16)]
+    fn wide(#[case] value: Wide, #[case] packed: u16) {
+        let forward: u16 = value.pack_into(9).unwrap();
+        assert_eq!(forward, packed);
+        let backward = Wide::unpack_from(packed, 9).unwrap();
+        assert_eq!(value, backward);
+    }
 }
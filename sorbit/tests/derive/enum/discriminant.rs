@@ -1,5 +1,6 @@
 use crate::utility::{from_bytes, to_bytes};
 use rstest::rstest;
+use sorbit::error::ErrorKind;
 use sorbit::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -30,7 +31,6 @@ fn deserialize(#[case] value: Enum, #[case] bytes: [u8; 1]) {
 }
 
 #[test]
-#[should_panic]
 fn deserialize_invalid() {
-    from_bytes::<Enum>(&[0xFF]).unwrap();
+    assert_eq!(from_bytes::<Enum>(&[0xFF]), Err(ErrorKind::InvalidEnumVariant.into()));
 }
@@ -19,5 +19,5 @@ enum Enum {
 #[case(Enum::CatchAll(0x93), &[0x93])]
 fn serialize(#[case] value: Enum, #[case] bytes: &[u8]) {
     assert_eq!(to_bytes(&value), Ok(bytes.into()));
-    assert_eq!(from_bytes::<Enum>(&bytes), Ok(value));
+    assert_eq!(from_bytes::<Enum>(bytes), Ok(value));
 }
@@ -3,3 +3,6 @@ mod byte_order;
 mod catch_all;
 mod discriminant;
 mod fielded_enum;
+mod tag;
+mod tagged_variant;
+mod untagged;
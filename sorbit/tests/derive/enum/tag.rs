@@ -0,0 +1,45 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::SorbitTag;
+use sorbit::error::InvalidDiscriminant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SorbitTag)]
+#[sorbit(byte_order=big_endian)]
+#[repr(u16)]
+enum Opcode {
+    Read = 0x0102,
+    Write = 0x0304,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SorbitTag)]
+#[repr(u8)]
+enum CatchAll {
+    A = 1,
+    #[sorbit(catch_all)]
+    Other(u8),
+}
+
+#[test]
+fn serialize() {
+    assert_eq!(to_bytes(&Opcode::Write), Ok(vec![0x03, 0x04]));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(from_bytes::<Opcode>(&[0x01, 0x02]), Ok(Opcode::Read));
+}
+
+#[test]
+fn try_from_valid() {
+    assert_eq!(Opcode::try_from(0x0304), Ok(Opcode::Write));
+}
+
+#[test]
+fn try_from_invalid() {
+    assert_eq!(Opcode::try_from(0x0506), Err(InvalidDiscriminant(0x0506)));
+}
+
+#[test]
+fn try_from_catch_all() {
+    assert_eq!(CatchAll::try_from(1), Ok(CatchAll::A));
+    assert_eq!(CatchAll::try_from(7), Ok(CatchAll::Other(7)));
+}
@@ -0,0 +1,52 @@
+use crate::utility::{from_bytes, to_bytes};
+use rstest::rstest;
+use sorbit::error::ErrorKind;
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian, repr=u16)]
+enum Message {
+    #[sorbit(tag = 0x0003)]
+    Ping,
+    #[sorbit(tag = 0x0010)]
+    Data(u8, u8),
+    #[sorbit(tag = 0x0020)]
+    Ack {
+        sequence: u32,
+    },
+    Nack {
+        sequence: u32,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(repr=u8)]
+enum Fallback {
+    #[sorbit(tag = 0x01)]
+    Known(u8),
+    #[sorbit(catch_all)]
+    Unknown(u8, u8),
+}
+
+#[rstest]
+#[case(Message::Ping, &[0x00, 0x03])]
+#[case(Message::Data(0xAA, 0xBB), &[0x00, 0x10, 0xAA, 0xBB])]
+#[case(Message::Ack { sequence: 0x01020304 }, &[0x00, 0x20, 0x01, 0x02, 0x03, 0x04])]
+#[case(Message::Nack { sequence: 0x01020304 }, &[0x00, 0x21, 0x01, 0x02, 0x03, 0x04])]
+fn round_trip(#[case] value: Message, #[case] bytes: &[u8]) {
+    assert_eq!(to_bytes(&value), Ok(bytes.into()));
+    assert_eq!(from_bytes::<Message>(bytes), Ok(value));
+}
+
+#[test]
+fn deserialize_unknown_tag() {
+    assert_eq!(from_bytes::<Message>(&[0x00, 0x04]), Err(ErrorKind::InvalidEnumVariant.into()));
+}
+
+#[rstest]
+#[case(Fallback::Known(0xAA), &[0x01, 0xAA])]
+#[case(Fallback::Unknown(0x07, 0xAA), &[0x07, 0xAA])]
+fn fallback(#[case] value: Fallback, #[case] bytes: &[u8]) {
+    assert_eq!(to_bytes(&value), Ok(bytes.into()));
+    assert_eq!(from_bytes::<Fallback>(bytes), Ok(value));
+}
@@ -0,0 +1,86 @@
+use rstest::rstest;
+use sorbit::error::{Error, ErrorKind};
+use sorbit::io::GrowingMemoryStream;
+use sorbit::layout::{Layout, StaticLayout as _};
+use sorbit::ser_de::{Deserializer as _, MultiPassDeserialize};
+use sorbit::stream_ser_de::StreamDeserializer;
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+use crate::utility::to_bytes;
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order = big_endian, magic = b"V2")]
+struct HeaderV2 {
+    len: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order = big_endian, magic = b"V1")]
+struct HeaderV1 {
+    len: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(untagged)]
+enum Header {
+    V2(HeaderV2),
+    V1(HeaderV1),
+    Legacy { len: u8 },
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(untagged)]
+enum Optional {
+    Present(HeaderV1),
+    Absent,
+}
+
+fn from_bytes<T: MultiPassDeserialize>(bytes: &[u8]) -> Result<T, Error> {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+    T::deserialize(&mut deserializer)
+}
+
+#[rstest]
+#[case(Header::V2(HeaderV2 { len: 0x01020304 }), b"V2\x01\x02\x03\x04")]
+#[case(Header::V1(HeaderV1 { len: 0x0102 }), b"V1\x01\x02")]
+#[case(Header::Legacy { len: 0x56 }, b"\x56")]
+fn serialize(#[case] value: Header, #[case] bytes: &[u8]) {
+    assert_eq!(to_bytes(&value), Ok(bytes.into()));
+}
+
+#[rstest]
+#[case(Header::V2(HeaderV2 { len: 0x01020304 }), b"V2\x01\x02\x03\x04")]
+#[case(Header::V1(HeaderV1 { len: 0x0102 }), b"V1\x01\x02")]
+#[case(Header::Legacy { len: 0x56 }, b"\x56")]
+#[case(Header::Legacy { len: b'V' }, b"V2\x01")]
+fn deserialize(#[case] value: Header, #[case] bytes: &[u8]) {
+    assert_eq!(from_bytes::<Header>(bytes), Ok(value));
+}
+
+#[test]
+fn deserialize_rolls_back_failed_variants() {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(b"V1\x01\x02\xFF".as_slice()));
+    assert_eq!(Header::deserialize(&mut deserializer), Ok(Header::V1(HeaderV1 { len: 0x0102 })));
+    assert_eq!(deserializer.deserialize_u8(), Ok(0xFF));
+}
+
+#[test]
+fn deserialize_unit_variant() {
+    assert_eq!(from_bytes::<Optional>(b"V1\x01\x02"), Ok(Optional::Present(HeaderV1 { len: 0x0102 })));
+    assert_eq!(from_bytes::<Optional>(b"V2"), Ok(Optional::Absent));
+    assert_eq!(to_bytes(&Optional::Absent), Ok(vec![]));
+}
+
+#[test]
+fn deserialize_no_matching_variant() {
+    let error = from_bytes::<Header>(b"").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NoMatchingVariant);
+    let causes: Vec<_> = error.causes().iter().map(|cause| cause.kind()).collect();
+    assert_eq!(causes.len(), 3);
+    assert!(causes.iter().all(|kind| matches!(kind, ErrorKind::UnexpectedEof { .. })));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Header::LAYOUT, Layout::new(1, Some(6), 2));
+}
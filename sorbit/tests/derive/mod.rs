@@ -1,3 +1,4 @@
 mod r#enum;
 mod ipv4_header;
+mod static_layout;
 mod r#struct;
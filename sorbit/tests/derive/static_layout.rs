@@ -0,0 +1,215 @@
+use std::marker::PhantomData;
+
+use crate::utility::to_bytes;
+use sorbit::layout::{Layout, StaticLayout, find_field};
+use sorbit::{Serialize, StaticLayout};
+
+#[derive(Serialize, StaticLayout)]
+struct Fixed {
+    a: u8,
+    b: u32,
+    c: [u16; 2],
+}
+
+#[derive(Serialize, StaticLayout)]
+#[sorbit(round = 8)]
+struct FieldLayout {
+    a: u8,
+    #[sorbit(offset = 3)]
+    b: u8,
+    #[sorbit(align = 4)]
+    c: u8,
+    #[sorbit(round = 2)]
+    d: u8,
+}
+
+#[derive(Serialize, StaticLayout)]
+struct AlignAbsolute {
+    a: u8,
+    #[sorbit(align_absolute = 4)]
+    b: u16,
+}
+
+#[derive(Serialize, StaticLayout)]
+#[sorbit(len = 6)]
+struct Len {
+    a: u16,
+}
+
+#[derive(Serialize, StaticLayout)]
+#[sorbit(len = 6, assert_size = 6)]
+struct AssertSize {
+    a: u16,
+}
+
+#[derive(Serialize, StaticLayout)]
+struct BitFields {
+    #[sorbit(bit_field=_bits, repr=u16, bits=0..4)]
+    a: u8,
+    #[sorbit(bit_field=_bits, bits=4..8)]
+    b: u8,
+    c: u8,
+}
+
+#[derive(Serialize, StaticLayout)]
+struct Collection {
+    #[sorbit(value=len(items))]
+    len: PhantomData<u16>,
+    #[sorbit(value=len_by(len))]
+    items: Vec<u32>,
+}
+
+#[derive(Serialize, StaticLayout)]
+struct Nested {
+    a: Fixed,
+    b: (u8, Len),
+}
+
+#[derive(Serialize, StaticLayout)]
+struct Tuple(u8, #[sorbit(value=len_by(0))] Vec<u8>, u16);
+
+#[derive(StaticLayout)]
+#[allow(unused)]
+struct Generic<T> {
+    value: T,
+}
+
+#[derive(Serialize, StaticLayout)]
+#[repr(u8)]
+#[allow(unused)]
+enum Enum {
+    A = 1,
+    B { a: u32 } = 2,
+    C(u16, Fixed) = 3,
+}
+
+#[test]
+fn fixed() {
+    assert_eq!(Fixed::LAYOUT, Layout::new(9, Some(9), 2));
+}
+
+#[test]
+fn field_layout() {
+    assert_eq!(FieldLayout::LAYOUT, Layout::new(8, Some(8), 2));
+}
+
+#[test]
+fn align_absolute() {
+    assert_eq!(AlignAbsolute::LAYOUT, Layout::new(3, Some(6), 1));
+}
+
+#[test]
+fn len() {
+    assert_eq!(Len::LAYOUT, Layout::new(6, Some(6), 1));
+}
+
+#[test]
+fn assert_size() {
+    assert_eq!(AssertSize::LAYOUT.size(), Some(6));
+}
+
+#[test]
+fn bit_fields() {
+    assert_eq!(BitFields::LAYOUT, Layout::new(3, Some(3), 1));
+}
+
+#[test]
+fn collection() {
+    assert_eq!(Collection::LAYOUT, Layout::new(2, None, 2));
+}
+
+#[test]
+fn nested() {
+    assert_eq!(Nested::LAYOUT, Layout::new(16, Some(16), 3));
+}
+
+#[test]
+fn generic() {
+    assert_eq!(Generic::<u64>::LAYOUT, Layout::new(8, Some(8), 1));
+    assert_eq!(Generic::<Nested>::LAYOUT.max_depth(), 4);
+}
+
+#[test]
+fn enumeration() {
+    assert_eq!(Enum::LAYOUT, Layout::new(1, Some(12), 4));
+}
+
+#[test]
+fn zeroed_wire() {
+    let value = FieldLayout { a: 1, b: 2, c: 3, d: 4 };
+    assert_eq!(FieldLayout::zeroed_wire().len(), to_bytes(&value).unwrap().len());
+    assert_eq!(FieldLayout::zeroed_wire(), vec![0; 8]);
+}
+
+#[test]
+fn static_verification() {
+    const _: () = assert!(Fixed::LAYOUT.fits_in(9));
+    const _: () = assert!(!Fixed::LAYOUT.fits_in(8));
+    const _: () = assert!(Enum::LAYOUT.max_depth() <= 4);
+}
+
+#[test]
+fn field_offsets() {
+    let ranges: Vec<_> = FieldLayout::FIELDS.iter().map(|field| (field.name(), field.range())).collect();
+    assert_eq!(
+        ranges,
+        [
+            ("a", Some(0..1)),
+            ("b", Some(3..4)),
+            ("c", Some(4..5)),
+            ("d", Some(5..7))
+        ]
+    );
+}
+
+#[test]
+fn field_offsets_match_serializer() {
+    let bytes = to_bytes(&FieldLayout { a: 1, b: 2, c: 3, d: 4 }).unwrap();
+    for (field, value) in FieldLayout::FIELDS.iter().zip([1, 2, 3, 4]) {
+        assert_eq!(bytes[field.offset().unwrap() as usize], value);
+    }
+}
+
+#[test]
+fn field_offsets_bit_fields() {
+    let ranges: Vec<_> = BitFields::FIELDS.iter().map(|field| (field.name(), field.range())).collect();
+    assert_eq!(ranges, [("_bits", Some(0..2)), ("c", Some(2..3))]);
+}
+
+#[test]
+fn field_offsets_variable_size() {
+    let offsets: Vec<_> = Tuple::FIELDS.iter().map(|field| (field.name(), field.offset())).collect();
+    assert_eq!(offsets, [("0", Some(0)), ("1", Some(1)), ("2", None)]);
+    assert_eq!(Tuple::FIELDS[1].layout().size(), None);
+}
+
+#[test]
+fn field_offsets_align_absolute() {
+    let offsets: Vec<_> = AlignAbsolute::FIELDS.iter().map(|field| (field.name(), field.offset())).collect();
+    assert_eq!(offsets, [("a", Some(0)), ("b", None)]);
+}
+
+#[test]
+fn field_offsets_generic() {
+    assert_eq!(Generic::<u64>::FIELDS[0].range(), Some(0..8));
+}
+
+#[test]
+fn field_offsets_enumeration() {
+    assert!(Enum::FIELDS.is_empty());
+}
+
+#[test]
+fn field_offsets_nested() {
+    let nested: Vec<_> = Nested::FIELDS.iter().map(|field| field.fields().len()).collect();
+    assert_eq!(nested, [3, 0]);
+    assert_eq!(Nested::FIELDS[0].fields()[1].range(), Some(1..5));
+    assert!(Collection::FIELDS[0].fields().is_empty());
+}
+
+#[test]
+fn field_offsets_const() {
+    const C: u64 = find_field(FieldLayout::FIELDS, "c").unwrap().offset().unwrap();
+    const _: () = assert!(C == 4);
+    const _: () = assert!(find_field(FieldLayout::FIELDS, "e").is_none());
+}
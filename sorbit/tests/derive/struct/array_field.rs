@@ -0,0 +1,63 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::{Deserialize, Serialize};
+
+/// Neither [`Copy`] nor [`Default`], so arrays of it can't be built from
+/// default values.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Item {
+    id: u8,
+    value: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Arrays {
+    numbers: [u16; 3],
+    items: [Item; 2],
+    empty: [u32; 0],
+    nested: [[i8; 2]; 2],
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = little_endian)]
+struct Large {
+    values: [u32; 4096],
+}
+
+fn arrays_value() -> Arrays {
+    Arrays {
+        numbers: [0x0102, 0x0304, 0x0506],
+        items: [
+            Item { id: 7, value: 0x0809 },
+            Item { id: 10, value: 0x0B0C },
+        ],
+        empty: [],
+        nested: [[-1, 2], [3, -4]],
+    }
+}
+const ARRAYS_BYTES: [u8; 16] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 0xFF, 2, 3, 0xFC];
+
+#[test]
+fn serialize() {
+    assert_eq!(to_bytes(&arrays_value()), Ok(ARRAYS_BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(from_bytes::<Arrays>(&ARRAYS_BYTES), Ok(arrays_value()));
+}
+
+#[test]
+fn deserialize_truncated() {
+    assert!(from_bytes::<Arrays>(&ARRAYS_BYTES[..9]).is_err());
+}
+
+#[test]
+fn round_trip_large() {
+    let value = Large { values: core::array::from_fn(|index| index as u32) };
+    let bytes = to_bytes(&value).unwrap();
+    assert_eq!(bytes.len(), 4 * 4096);
+    assert_eq!(bytes[4..8], [1, 0, 0, 0]);
+    assert_eq!(from_bytes::<Large>(&bytes), Ok(value));
+}
@@ -0,0 +1,59 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::error::{Error, ErrorKind, TraceError};
+use sorbit::io::GrowingMemoryStream;
+use sorbit::ser_de::MultiPassDeserialize;
+use sorbit::stream_ser_de::StreamDeserializer;
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Behind {
+    pre: u32,
+    #[sorbit(offset = 2)]
+    subject: u8,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Overlay {
+    word: u32,
+    #[sorbit(offset = 2, allow_backward = seek)]
+    low: u16,
+    post: u8,
+}
+
+const BEHIND_VALUE: Behind = Behind { pre: 0, subject: 0xFE };
+const BEHIND_BYTES: [u8; 5] = [0, 0, 0, 0, 0xFE];
+
+const OVERLAY_VALUE: Overlay = Overlay { word: 0xAABBCCDD, low: 0xCCDD, post: 0xFF };
+const OVERLAY_BYTES: [u8; 5] = [0xAA, 0xBB, 0xCC, 0xDD, 0xFF];
+
+fn behind_error() -> Error {
+    Error::from(ErrorKind::LengthExceedsPadding { offset: 2, position: 4 })
+        .annotate("subject")
+        .locate(4)
+}
+
+#[test]
+fn serialize_behind() {
+    assert_eq!(to_bytes(&BEHIND_VALUE), Err(behind_error()));
+}
+
+#[test]
+fn deserialize_behind() {
+    assert_eq!(from_bytes::<Behind>(&BEHIND_BYTES), Err(behind_error()));
+}
+
+#[test]
+fn deserialize_behind_message() {
+    let message = from_bytes::<Behind>(&BEHIND_BYTES).unwrap_err().to_string();
+    assert_eq!(
+        message,
+        ".subject @ offset 0x4: cannot pad to offset 2 because the current position 4 is already past it"
+    );
+}
+
+#[test]
+fn deserialize_overlay() {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(&OVERLAY_BYTES[..]));
+    assert_eq!(<Overlay as MultiPassDeserialize>::deserialize(&mut deserializer), Ok(OVERLAY_VALUE));
+}
@@ -1,5 +1,9 @@
 use crate::utility::{from_bytes, to_bytes};
-use sorbit::{Deserialize, Serialize, bit::Error as BitError, error::Error};
+use sorbit::{
+    Deserialize, PackInto, Serialize, UnpackFrom,
+    bit::Error as BitError,
+    error::{Error, ErrorKind, TraceError as _},
+};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[sorbit(byte_order = big_endian)]
@@ -17,6 +21,22 @@ struct Layout {
     a: u8,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, PackInto, UnpackFrom)]
+#[repr(u8)]
+enum Mode {
+    Off = 0,
+    Standby = 1,
+    On = 5,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct EnumMember {
+    #[sorbit(bit_field=_b, repr=u8, exhaustive, bits=0..5)]
+    level: u8,
+    #[sorbit(bit_field=_b, bits=5..8)]
+    mode: Mode,
+}
+
 const PACKING_VALUE: Packing = Packing { a: 0b110011, b: true };
 const PACKING_BYTES: [u8; 2] = 0b0100_0011_0011_0000_u16.to_be_bytes();
 
@@ -36,15 +56,33 @@ fn deserialize_packing() {
 #[test]
 fn serialize_packing_bit_overflow() {
     let faulty_value = Packing { a: 255, b: true };
-    assert_eq!(to_bytes(&faulty_value), Err(Error::from(BitError::TooManyBits)));
+    assert_eq!(
+        to_bytes(&faulty_value),
+        Err(Error::from(BitError::TooManyBits).annotate("a").annotate("_b").locate(0))
+    );
 }
 
 #[test]
 fn deserialize_packing_invalid_variant() {
     let faulty_bytes = 0b1000_0011_0011_0000_u16.to_be_bytes();
-    // TODO: this is not quite right, it should return an InvalidVariant or
-    // similar error as `2` does not qualify as either `true` or `false`.
-    assert_eq!(from_bytes::<Packing>(&faulty_bytes), Err(Error::from(BitError::TooManyBits)));
+    let error = Error::from(ErrorKind::InvalidEnumVariant).annotate("b").annotate("_b").locate(0);
+    assert_eq!(from_bytes::<Packing>(&faulty_bytes), Err(error));
+}
+
+#[test]
+fn serialize_enum_member() {
+    assert_eq!(to_bytes(&EnumMember { level: 3, mode: Mode::On }), Ok(vec![0b101_00011]));
+}
+
+#[test]
+fn deserialize_enum_member() {
+    assert_eq!(from_bytes::<EnumMember>(&[0b001_00011]), Ok(EnumMember { level: 3, mode: Mode::Standby }));
+}
+
+#[test]
+fn deserialize_enum_member_invalid_variant() {
+    let error = Error::from(ErrorKind::InvalidEnumVariant).annotate("mode").annotate("_b").locate(0);
+    assert_eq!(from_bytes::<EnumMember>(&[0b111_00011]), Err(error));
 }
 
 #[test]
@@ -21,9 +21,25 @@ struct BitNumberMSB0 {
     b: u8,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian, bit_numbering = MSB0)]
+struct BitNumberStruct {
+    #[sorbit(bit_field=_b, repr=u8, bits=4..8)]
+    a: u8,
+    #[sorbit(bit_field=_b, bits=0..4)]
+    b: u8,
+    #[sorbit(bit_field=_c, repr=u8, bit_numbering = LSB0)]
+    #[sorbit(bit_field=_c, bits=0..4)]
+    c: u8,
+    #[sorbit(bit_field=_c, bits=4..8)]
+    d: u8,
+}
+
 const LSB0_VALUE: BitNumberLSB0 = BitNumberLSB0 { a: 0b1010, b: 0b1010 };
 const MSB0_VALUE: BitNumberMSB0 = BitNumberMSB0 { a: 0b1010, b: 0b1010 };
+const STRUCT_VALUE: BitNumberStruct = BitNumberStruct { a: 0b1010, b: 0b1010, c: 0b1010, d: 0b1010 };
 const BYTES: [u8; 1] = [0b10101010];
+const STRUCT_BYTES: [u8; 2] = [0b10101010, 0b10101010];
 
 #[test]
 fn serialize_lsb0() {
@@ -44,3 +60,13 @@ fn serialize_msb0() {
 fn deserialize_msb0() {
     assert_eq!(from_bytes::<BitNumberMSB0>(&BYTES), Ok(MSB0_VALUE));
 }
+
+#[test]
+fn serialize_struct_default() {
+    assert_eq!(to_bytes(&STRUCT_VALUE), Ok(STRUCT_BYTES.into()));
+}
+
+#[test]
+fn deserialize_struct_default() {
+    assert_eq!(from_bytes::<BitNumberStruct>(&STRUCT_BYTES), Ok(STRUCT_VALUE));
+}
@@ -0,0 +1,59 @@
+use sorbit::{
+    Deserialize, Serialize,
+    error::ErrorKind,
+    ser_de::{DeserializeBorrowed, ToBytes},
+    stream_ser_de::SliceDeserializer,
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Packet<'a> {
+    #[sorbit(value=len(payload))]
+    len: u8,
+    #[sorbit(borrow)]
+    payload: &'a [u8],
+    #[sorbit(borrow, len_prefix=u8)]
+    tail: &'a [u8],
+    #[sorbit(borrow, string=len_prefix(u8))]
+    name: &'a str,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Envelope<'a> {
+    id: u8,
+    #[sorbit(borrow)]
+    packet: Packet<'a>,
+}
+
+const BYTES: [u8; 9] = [2, 0xAA, 0xBB, 1, 0xCC, 3, b'a', b'b', b'c'];
+const VALUE: Packet<'static> = Packet { len: 2, payload: &[0xAA, 0xBB], tail: &[0xCC], name: "abc" };
+
+#[test]
+fn serialize() {
+    assert_eq!(VALUE.to_bytes(), Ok(BYTES.to_vec()));
+}
+
+#[test]
+fn deserialize() {
+    let mut deserializer = SliceDeserializer::from_slice(&BYTES);
+    let value = Packet::deserialize_borrowed(&mut deserializer).unwrap();
+    assert_eq!(value, VALUE);
+    assert!(core::ptr::eq(value.payload, &BYTES[1..3]));
+    assert!(core::ptr::eq(value.name.as_bytes(), &BYTES[6..9]));
+}
+
+#[test]
+fn deserialize_nested() {
+    let mut bytes = vec![7];
+    bytes.extend_from_slice(&BYTES);
+    let mut deserializer = SliceDeserializer::from_slice(&bytes);
+    let value = Envelope::deserialize_borrowed(&mut deserializer).unwrap();
+    assert_eq!(value, Envelope { id: 7, packet: VALUE });
+    assert_eq!(value.to_bytes(), Ok(bytes.clone()));
+}
+
+#[test]
+fn deserialize_truncated() {
+    let mut deserializer = SliceDeserializer::from_slice(&BYTES[..4]);
+    let result = Packet::deserialize_borrowed(&mut deserializer).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::UnexpectedEof { bytes_needed: 1, bytes_available: 0 }));
+}
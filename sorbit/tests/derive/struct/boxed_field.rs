@@ -0,0 +1,73 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::layout::StaticLayout;
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+const LEN: usize = 1 << 20;
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+struct Boxed {
+    pre: u8,
+    #[sorbit(boxed)]
+    subject: Box<[u8; 4]>,
+    post: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Unboxed {
+    pre: u8,
+    subject: Box<[u8; 4]>,
+    post: u8,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Large {
+    #[sorbit(boxed)]
+    subject: Box<[u8; LEN]>,
+}
+
+const BYTES: [u8; 6] = [0xFD, 1, 2, 3, 4, 0xFF];
+
+fn boxed_value() -> Boxed {
+    Boxed { pre: 0xFD, subject: Box::new([1, 2, 3, 4]), post: 0xFF }
+}
+
+fn unboxed_value() -> Unboxed {
+    Unboxed { pre: 0xFD, subject: Box::new([1, 2, 3, 4]), post: 0xFF }
+}
+
+#[test]
+fn serialize_boxed() {
+    assert_eq!(to_bytes(&boxed_value()), Ok(BYTES.into()));
+}
+
+#[test]
+fn deserialize_boxed() {
+    assert_eq!(from_bytes::<Boxed>(&BYTES), Ok(boxed_value()));
+}
+
+#[test]
+fn layout_boxed() {
+    assert_eq!(Boxed::LAYOUT.size(), Some(6));
+}
+
+#[test]
+fn serialize_unboxed() {
+    assert_eq!(to_bytes(&unboxed_value()), Ok(BYTES.into()));
+}
+
+#[test]
+fn deserialize_unboxed() {
+    assert_eq!(from_bytes::<Unboxed>(&BYTES), Ok(unboxed_value()));
+}
+
+#[test]
+fn deserialize_large() {
+    let bytes = vec![0xAB; LEN];
+    let value = std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(move || from_bytes::<Large>(&bytes).map(|large| large.subject.iter().all(|byte| *byte == 0xAB)))
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(value, Ok(true));
+}
@@ -0,0 +1,57 @@
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+struct StatusWordLSB0 {
+    #[sorbit(bit_field=_w, repr=[u8; 12], bits=0..4)]
+    a: u8,
+    #[sorbit(bit_field=_w, bits=4..68)]
+    b: u64,
+    #[sorbit(bit_field=_w, bits=90..96)]
+    c: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(bit_numbering=MSB0)]
+struct StatusWordMSB0 {
+    #[sorbit(bit_field=_w, repr=[u8; 12], bits=92..96)]
+    a: u8,
+    #[sorbit(bit_field=_w, bits=28..92)]
+    b: u64,
+    #[sorbit(bit_field=_w, bits=0..6)]
+    c: u8,
+}
+
+const LSB0_VALUE: StatusWordLSB0 = StatusWordLSB0 { a: 0xA, b: 0x0123_4567_89AB_CDEF, c: 0b10_1010 };
+const MSB0_VALUE: StatusWordMSB0 = StatusWordMSB0 { a: 0xA, b: 0x0123_4567_89AB_CDEF, c: 0b10_1010 };
+const BYTES: [u8; 12] = [
+    0xA8, 0x00, 0x00, 0x00, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xFA,
+];
+
+#[test]
+fn serialize_lsb0() {
+    assert_eq!(LSB0_VALUE.to_bytes(), Ok(BYTES.into()));
+}
+
+#[test]
+fn deserialize_lsb0() {
+    assert_eq!(StatusWordLSB0::from_bytes(&BYTES), Ok(LSB0_VALUE));
+}
+
+#[test]
+fn serialize_msb0() {
+    assert_eq!(MSB0_VALUE.to_bytes(), Ok(BYTES.into()));
+}
+
+#[test]
+fn deserialize_msb0() {
+    assert_eq!(StatusWordMSB0::from_bytes(&BYTES), Ok(MSB0_VALUE));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(StatusWordLSB0::LAYOUT, Layout::new(12, Some(12), 1));
+}
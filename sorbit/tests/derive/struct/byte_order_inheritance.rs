@@ -0,0 +1,78 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Inherits {
+    value: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=little_endian)]
+struct OverridesLittle {
+    value: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct OverridesBig {
+    value: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[repr(u16)]
+enum InheritsEnum {
+    A { value: u16 } = 0xFF00,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=little_endian)]
+struct Matrix {
+    struct_order: u16,
+    #[sorbit(byte_order=big_endian)]
+    field_order: u16,
+    inherits_struct: Inherits,
+    #[sorbit(byte_order=big_endian)]
+    inherits_field: Inherits,
+    #[sorbit(byte_order=big_endian)]
+    overrides_field: OverridesLittle,
+    overrides_struct: OverridesBig,
+    #[sorbit(byte_order=big_endian)]
+    enum_inherits_field: InheritsEnum,
+    #[sorbit(byte_order=big_endian)]
+    nested: (u16, Inherits, OverridesLittle),
+    after_nested: u16,
+}
+
+const VALUE: Matrix = Matrix {
+    struct_order: 0xFF00,
+    field_order: 0xFF00,
+    inherits_struct: Inherits { value: 0xFF00 },
+    inherits_field: Inherits { value: 0xFF00 },
+    overrides_field: OverridesLittle { value: 0xFF00 },
+    overrides_struct: OverridesBig { value: 0xFF00 },
+    enum_inherits_field: InheritsEnum::A { value: 0xFF00 },
+    nested: (0xFF00, Inherits { value: 0xFF00 }, OverridesLittle { value: 0xFF00 }),
+    after_nested: 0xFF00,
+};
+
+const BYTES: [u8; 24] = [
+    0x00, 0xFF, // struct_order: struct attribute.
+    0xFF, 0x00, // field_order: field attribute overrides struct attribute.
+    0x00, 0xFF, // inherits_struct: inherited from struct attribute.
+    0xFF, 0x00, // inherits_field: inherited from field attribute.
+    0x00, 0xFF, // overrides_field: nested type's attribute overrides field attribute.
+    0xFF, 0x00, // overrides_struct: nested type's attribute overrides struct attribute.
+    0xFF, 0x00, 0xFF, 0x00, // enum_inherits_field: discriminant and content inherit.
+    0xFF, 0x00, 0xFF, 0x00, 0x00, 0xFF, // nested: inherited through the tuple.
+    0x00, 0xFF, // after_nested: restored after the nested scopes.
+];
+
+#[test]
+fn serialize() {
+    assert_eq!(to_bytes(&VALUE), Ok(BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(from_bytes::<Matrix>(&BYTES), Ok(VALUE));
+}
@@ -0,0 +1,66 @@
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    error::ErrorKind,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Trailer {
+    kind: u8,
+    payload: [u8; 4],
+    #[sorbit(checksum=crc32(kind, payload))]
+    crc: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=little_endian)]
+struct Header {
+    #[sorbit(checksum=crc16(body, ready))]
+    crc: u16,
+    #[sorbit(bit_field=_flags, repr=u8, bits=0)]
+    ready: bool,
+    body: u16,
+}
+
+#[test]
+fn serialize_after_covered() {
+    let value = Trailer { kind: 1, payload: *b"data", crc: 0 };
+    assert_eq!(value.to_bytes(), Ok(vec![1, b'd', b'a', b't', b'a', 0x77, 0xF5, 0xF2, 0xD2]));
+}
+
+#[test]
+fn deserialize_after_covered() {
+    let bytes = [1, b'd', b'a', b't', b'a', 0x77, 0xF5, 0xF2, 0xD2];
+    let expected = Trailer { kind: 1, payload: *b"data", crc: 0x77F5F2D2 };
+    assert_eq!(Trailer::from_bytes(&bytes), Ok(expected));
+}
+
+#[test]
+fn serialize_before_covered() {
+    // The covered fields are checksummed in the order they are listed in.
+    let value = Header { crc: 0, ready: true, body: 0x1234 };
+    assert_eq!(value.to_bytes(), Ok(vec![0xC9, 0xA0, 0x01, 0x34, 0x12]));
+}
+
+#[test]
+fn deserialize_before_covered() {
+    let bytes = [0xC9, 0xA0, 0x01, 0x34, 0x12];
+    let expected = Header { crc: 0xA0C9, ready: true, body: 0x1234 };
+    assert_eq!(Header::from_bytes(&bytes), Ok(expected));
+}
+
+#[test]
+fn deserialize_mismatch() {
+    let bytes = [1, b'd', b'a', b't', b'b', 0x77, 0xF5, 0xF2, 0xD2];
+    assert_eq!(
+        Trailer::from_bytes(&bytes).map_err(|error| error.kind()),
+        Err(ErrorKind::Custom("the checksum does not match the data"))
+    );
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Trailer::LAYOUT, Layout::new(9, Some(9), 2));
+}
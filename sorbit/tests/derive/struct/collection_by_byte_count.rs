@@ -1,6 +1,8 @@
 use sorbit::{
     Deserialize, Serialize,
-    ser_de::{FromBytes, ToBytes},
+    io::StdStream,
+    ser_de::{FromBytes, Serialize as _, ToBytes},
+    stream_ser_de::StreamSerializer,
 };
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -25,6 +27,24 @@ struct ByByteCountBit {
     collection_2: Vec<u16>,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian, multi_pass)]
+struct ByByteCountMeasured {
+    #[sorbit(bit_field=b, repr=u16, bits=0..12)]
+    #[sorbit(value=byte_count(collection_1))]
+    byte_count_1: u16,
+    #[sorbit(bit_field=b, bits=12..16)]
+    #[sorbit(value=byte_count(collection_2))]
+    byte_count_2: u8,
+    #[sorbit(value=byte_count(collection_3))]
+    byte_count_3: u8,
+
+    collection_1: Vec<u16>,
+    collection_2: Vec<u16>,
+    #[sorbit(round = 4)]
+    collection_3: Vec<u8>,
+}
+
 fn by_byte_count_value(synchronize_len: bool) -> ByByteCount {
     ByByteCount { byte_count: if synchronize_len { 4 } else { 0 }, collection: vec![1, 2] }
 }
@@ -38,6 +58,36 @@ fn by_byte_count_value_bit(synchronize_len: bool) -> ByByteCountBit {
         collection_2: vec![3, 4, 5],
     }
 }
+fn by_byte_count_value_measured(synchronize_len: bool) -> ByByteCountMeasured {
+    ByByteCountMeasured {
+        byte_count_1: if synchronize_len { 4 } else { 0 },
+        byte_count_2: if synchronize_len { 6 } else { 0 },
+        byte_count_3: if synchronize_len { 3 } else { 0 },
+        collection_1: vec![1, 2],
+        collection_2: vec![3, 4, 5],
+        collection_3: vec![6, 7, 8],
+    }
+}
+const BY_BYTE_COUNT_MEASURED_BYTES: [u8; 17] = [
+    0b0110_0000,
+    0b0000_0100,
+    3,
+    0,
+    1,
+    0,
+    2,
+    0,
+    3,
+    0,
+    4,
+    0,
+    5,
+    6,
+    7,
+    8,
+    0,
+];
+
 const BY_BYTE_COUNT_BIT_BYTES: [u8; 12] = [0b0110_0000, 0b0000_0100, 0, 1, 0, 2, 0, 3, 0, 4, 0, 5];
 
 #[test]
@@ -59,3 +109,24 @@ fn serialize_bit() {
 fn deserialize_bit() {
     assert_eq!(ByByteCountBit::from_bytes(&BY_BYTE_COUNT_BIT_BYTES), Ok(by_byte_count_value_bit(true)));
 }
+
+#[test]
+fn serialize_measured() {
+    assert_eq!(by_byte_count_value_measured(false).to_bytes(), Ok(BY_BYTE_COUNT_MEASURED_BYTES.into()));
+}
+
+#[test]
+fn serialize_measured_not_seekable() {
+    // `Vec<u8>` can't seek, so the serializer can't revise the byte counts.
+    let mut serializer = StreamSerializer::new(StdStream::new(Vec::new()));
+    by_byte_count_value_measured(false).serialize(&mut serializer).unwrap();
+    assert_eq!(serializer.take().into_inner(), BY_BYTE_COUNT_MEASURED_BYTES);
+}
+
+#[test]
+fn deserialize_measured() {
+    assert_eq!(
+        ByByteCountMeasured::from_bytes(&BY_BYTE_COUNT_MEASURED_BYTES),
+        Ok(by_byte_count_value_measured(true))
+    );
+}
@@ -0,0 +1,90 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::error::ErrorKind;
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Header {
+    magic: u8,
+    num_entries: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct BySibling {
+    header: Header,
+    #[sorbit(count = "self.header.num_entries")]
+    entries: Vec<u16>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Directory {
+    id: u8,
+    #[sorbit(count = ctx("num_entries"))]
+    entries: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct ByAncestor {
+    #[sorbit(ctx = "num_entries")]
+    num_entries: u8,
+    directory: Directory,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct ByExpression {
+    num_pairs: u8,
+    #[sorbit(count = self.num_pairs * 2)]
+    items: Vec<u8>,
+}
+
+fn by_sibling_value() -> BySibling {
+    BySibling { header: Header { magic: 0xAA, num_entries: 2 }, entries: vec![0x0102, 0x0304] }
+}
+const BY_SIBLING_BYTES: [u8; 7] = [0xAA, 0, 2, 1, 2, 3, 4];
+
+fn by_ancestor_value() -> ByAncestor {
+    ByAncestor { num_entries: 3, directory: Directory { id: 7, entries: vec![1, 2, 3] } }
+}
+const BY_ANCESTOR_BYTES: [u8; 5] = [3, 7, 1, 2, 3];
+
+#[test]
+fn serialize_by_sibling() {
+    assert_eq!(to_bytes(&by_sibling_value()), Ok(BY_SIBLING_BYTES.into()));
+}
+
+#[test]
+fn deserialize_by_sibling() {
+    assert_eq!(from_bytes::<BySibling>(&BY_SIBLING_BYTES), Ok(by_sibling_value()));
+}
+
+#[test]
+fn serialize_by_ancestor() {
+    assert_eq!(to_bytes(&by_ancestor_value()), Ok(BY_ANCESTOR_BYTES.into()));
+}
+
+#[test]
+fn deserialize_by_ancestor() {
+    assert_eq!(from_bytes::<ByAncestor>(&BY_ANCESTOR_BYTES), Ok(by_ancestor_value()));
+}
+
+#[test]
+fn by_expression() {
+    let value = ByExpression { num_pairs: 2, items: vec![1, 2, 3, 4] };
+    assert_eq!(to_bytes(&value), Ok(vec![2, 1, 2, 3, 4]));
+    assert_eq!(from_bytes(&[2, 1, 2, 3, 4]), Ok(value));
+}
+
+#[test]
+fn serialize_count_mismatch() {
+    let mut value = by_sibling_value();
+    value.header.num_entries = 3;
+    let error = to_bytes(&value).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Custom("the length of the collection differs from its count"));
+}
+
+#[test]
+fn deserialize_without_context() {
+    let error = from_bytes::<Directory>(&[7, 1, 2, 3]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Custom("the context value `num_entries` is not set"));
+}
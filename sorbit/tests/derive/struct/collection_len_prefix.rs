@@ -0,0 +1,72 @@
+use sorbit::{
+    Deserialize, Serialize,
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct LenPrefixed {
+    tag: u8,
+    #[sorbit(len_prefix=u16)]
+    collection: Vec<u16>,
+    trailer: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct MultiPassItem {
+    #[sorbit(value=byte_count(c))]
+    a: u8,
+    c: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct LenPrefixedMultiPass {
+    #[sorbit(len_prefix=u8, multi_pass)]
+    collection: Vec<MultiPassItem>,
+}
+
+fn len_prefixed_value() -> LenPrefixed {
+    LenPrefixed { tag: 0xAA, collection: vec![1, 2], trailer: 0xBB }
+}
+const LEN_PREFIXED_BYTES: [u8; 8] = [0xAA, 0, 2, 0, 1, 0, 2, 0xBB];
+
+#[test]
+fn serialize() {
+    assert_eq!(len_prefixed_value().to_bytes(), Ok(LEN_PREFIXED_BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(LenPrefixed::from_bytes(&LEN_PREFIXED_BYTES), Ok(len_prefixed_value()));
+}
+
+#[test]
+fn serialize_empty() {
+    let value = LenPrefixed { tag: 0xAA, collection: vec![], trailer: 0xBB };
+    assert_eq!(value.to_bytes(), Ok(vec![0xAA, 0, 0, 0xBB]));
+}
+
+#[test]
+fn serialize_too_long() {
+    let value = LenPrefixed { tag: 0xAA, collection: vec![0; 65536], trailer: 0xBB };
+    assert!(value.to_bytes().is_err());
+}
+
+fn len_prefixed_multi_pass_value(synchronize_len: bool) -> LenPrefixedMultiPass {
+    let a = if synchronize_len { 2 } else { 0 };
+    LenPrefixedMultiPass { collection: vec![MultiPassItem { a, c: vec![1, 2] }] }
+}
+const LEN_PREFIXED_MULTI_PASS_BYTES: [u8; 4] = [1, 2, 1, 2];
+
+#[test]
+fn serialize_multi_pass() {
+    assert_eq!(len_prefixed_multi_pass_value(false).to_bytes(), Ok(LEN_PREFIXED_MULTI_PASS_BYTES.into()));
+}
+
+#[test]
+fn deserialize_multi_pass() {
+    assert_eq!(
+        LenPrefixedMultiPass::from_bytes(&LEN_PREFIXED_MULTI_PASS_BYTES),
+        Ok(len_prefixed_multi_pass_value(true))
+    );
+}
@@ -0,0 +1,71 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::error::{Error, ErrorKind, TraceError as _};
+use sorbit::layout::{Layout, StaticLayout};
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Header {
+    flags: u8,
+    #[sorbit(if = "self.flags & 0x80 != 0")]
+    length: u16,
+    #[sorbit(if = self.flags & 0x01 != 0)]
+    checksum: Option<u8>,
+    tail: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct BitFlags {
+    #[sorbit(bit_field=_flags, repr=u8, bits=0)]
+    extended: bool,
+    #[sorbit(if = "self.extended")]
+    extension: u8,
+}
+
+#[test]
+fn serialize_present() {
+    let value = Header { flags: 0x81, length: 0x1234, checksum: Some(0xCC), tail: 0xFF };
+    assert_eq!(to_bytes(&value), Ok(vec![0x81, 0x12, 0x34, 0xCC, 0xFF]));
+}
+
+#[test]
+fn serialize_absent() {
+    let value = Header { flags: 0x00, length: 0x1234, checksum: Some(0xCC), tail: 0xFF };
+    assert_eq!(to_bytes(&value), Ok(vec![0x00, 0xFF]));
+}
+
+#[test]
+fn serialize_present_without_value() {
+    let value = Header { flags: 0x01, length: 0, checksum: None, tail: 0xFF };
+    assert_eq!(
+        to_bytes(&value),
+        Err(Error::from(ErrorKind::Custom("the field is present but has no value"))
+            .annotate("checksum")
+            .locate(1))
+    );
+}
+
+#[test]
+fn deserialize_present() {
+    let value = Header { flags: 0x81, length: 0x1234, checksum: Some(0xCC), tail: 0xFF };
+    assert_eq!(from_bytes(&[0x81, 0x12, 0x34, 0xCC, 0xFF]), Ok(value));
+}
+
+#[test]
+fn deserialize_absent() {
+    let value = Header { flags: 0x00, length: 0, checksum: None, tail: 0xFF };
+    assert_eq!(from_bytes(&[0x00, 0xFF]), Ok(value));
+}
+
+#[test]
+fn condition_on_bit_field() {
+    let value = BitFlags { extended: true, extension: 0xEE };
+    assert_eq!(to_bytes(&value), Ok(vec![0x01, 0xEE]));
+    assert_eq!(from_bytes(&[0x01, 0xEE]), Ok(value));
+    assert_eq!(from_bytes(&[0x00]), Ok(BitFlags { extended: false, extension: 0 }));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Header::LAYOUT, Layout::new(2, Some(5), 1));
+}
@@ -0,0 +1,121 @@
+use sorbit::error::MessageError as _;
+use sorbit::io::Read;
+use sorbit::ser_de::{
+    DeferredSerialize, Deserialize, Deserializer, FromBytes, RevisableSerializer, Serialize, Serializer, Span, ToBytes,
+};
+use sorbit::{Deserialize, Serialize};
+
+/// The XOR of all bytes of the enclosing struct.
+#[derive(Debug, PartialEq, Eq)]
+struct Checksum(u8);
+
+impl Serialize for Checksum {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer.serialize_u8(0)
+    }
+}
+
+impl Deserialize for Checksum {
+    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+        Ok(Self(u8::deserialize(deserializer)?))
+    }
+}
+
+impl DeferredSerialize for Checksum {
+    fn serialize_deferred<S: RevisableSerializer>(
+        &self,
+        serializer: &mut S,
+        sections: &[&S::Success],
+    ) -> Result<S::Success, S::Error> {
+        let mut checksum = 0;
+        for section in sections {
+            checksum ^= serializer.analyze_span(section, |reader: &mut dyn Read| {
+                let mut bytes = vec![0; section.len() as usize];
+                reader.read(&mut bytes).map_err(|_| S::Error::message("failed to read section"))?;
+                Ok::<_, S::Error>(bytes.into_iter().fold(0, |acc, byte| acc ^ byte))
+            })?;
+        }
+        serializer.serialize_u8(checksum)
+    }
+}
+
+/// The number of bytes the enclosing struct occupies.
+#[derive(Debug, PartialEq, Eq)]
+struct Size(u8);
+
+impl Serialize for Size {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer.serialize_u8(0)
+    }
+}
+
+impl Deserialize for Size {
+    fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<Self, D::Error> {
+        Ok(Self(u8::deserialize(deserializer)?))
+    }
+}
+
+impl DeferredSerialize for Size {
+    fn serialize_deferred<S: RevisableSerializer>(
+        &self,
+        serializer: &mut S,
+        sections: &[&S::Success],
+    ) -> Result<S::Success, S::Error> {
+        let start = sections.first().map(|section| section.start()).unwrap_or(0);
+        let end = sections.last().map(|section| section.end()).unwrap_or(0);
+        serializer.serialize_u8((end - start) as u8)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Deferred {
+    #[sorbit(deferred)]
+    checksum: Checksum,
+    a: u8,
+    #[sorbit(align = 4)]
+    b: u16,
+    #[sorbit(deferred)]
+    size: Size,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct DeferredWithByteCount {
+    #[sorbit(value=byte_count(items))]
+    byte_count: u8,
+    items: Vec<u8>,
+    #[sorbit(deferred)]
+    checksum: Checksum,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct DeferredTuple(#[sorbit(deferred)] Size, u8, #[sorbit(bit_field=_flags, repr=u8, bits=0)] bool);
+
+#[test]
+fn serialize_deferred() {
+    let value = Deferred { checksum: Checksum(0), a: 0x12, b: 0x3456, size: Size(0) };
+    // The checksum is revised before the size, so it still sees the size's placeholder.
+    let expected = [0x12 ^ 0x34 ^ 0x56, 0x12, 0, 0, 0x34, 0x56, 7];
+    assert_eq!(value.to_bytes(), Ok(expected.into()));
+}
+
+#[test]
+fn deserialize_deferred() {
+    let bytes = [0x12 ^ 0x34 ^ 0x56 ^ 7, 0x12, 0, 0, 0x34, 0x56, 7];
+    let expected = Deferred { checksum: Checksum(0x12 ^ 0x34 ^ 0x56 ^ 7), a: 0x12, b: 0x3456, size: Size(7) };
+    assert_eq!(Deferred::from_bytes(&bytes), Ok(expected));
+}
+
+#[test]
+fn serialize_deferred_after_byte_count() {
+    let value = DeferredWithByteCount { byte_count: 0, items: vec![0x10, 0x20, 0x40], checksum: Checksum(0) };
+    let expected = [3, 0x10, 0x20, 0x40, 3 ^ 0x10 ^ 0x20 ^ 0x40];
+    assert_eq!(value.to_bytes(), Ok(expected.into()));
+}
+
+#[test]
+fn serialize_deferred_tuple() {
+    let value = DeferredTuple(Size(0), 0xFF, true);
+    let expected = [3, 0xFF, 1];
+    assert_eq!(value.to_bytes(), Ok(expected.into()));
+}
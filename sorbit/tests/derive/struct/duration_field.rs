@@ -0,0 +1,43 @@
+use core::time::Duration;
+
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::layout::StaticLayout;
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Test {
+    #[sorbit(unit="ms", repr=u16)]
+    millis: Duration,
+    #[sorbit(unit=min, repr=u8)]
+    minutes: Duration,
+}
+
+#[test]
+fn serialize() {
+    let value = Test { millis: Duration::from_millis(1500), minutes: Duration::from_secs(180) };
+    assert_eq!(to_bytes(&value), Ok(vec![0x05, 0xDC, 0x03]));
+}
+
+#[test]
+fn serialize_truncated() {
+    let value = Test { millis: Duration::from_micros(1999), minutes: Duration::from_secs(119) };
+    assert_eq!(to_bytes(&value), Ok(vec![0x00, 0x01, 0x01]));
+}
+
+#[test]
+fn serialize_overflow() {
+    let value = Test { millis: Duration::from_secs(66), minutes: Duration::ZERO };
+    assert!(to_bytes(&value).is_err());
+}
+
+#[test]
+fn deserialize() {
+    let value = Test { millis: Duration::from_millis(1500), minutes: Duration::from_secs(180) };
+    assert_eq!(from_bytes::<Test>(&[0x05, 0xDC, 0x03]), Ok(value));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Test::LAYOUT.size(), Some(3));
+}
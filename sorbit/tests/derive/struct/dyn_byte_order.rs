@@ -0,0 +1,65 @@
+use sorbit::io::{FixedMemoryStream, GrowingMemoryStream};
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[sorbit(dyn_byte_order)]
+struct Header {
+    order: [u8; 2],
+    magic: u16,
+    offset: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[sorbit(dyn_byte_order)]
+struct Generic<T: sorbit::ser_de::Serialize + sorbit::ser_de::Deserialize> {
+    value: T,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[sorbit(dyn_byte_order)]
+struct MultiPass {
+    #[sorbit(value=byte_count(items))]
+    byte_count: u8,
+    #[sorbit(value=byte_count_by(byte_count))]
+    items: Vec<u16>,
+}
+
+const LE_BYTES: [u8; 8] = [b'I', b'I', 42, 0, 8, 0, 0, 0];
+const BE_BYTES: [u8; 8] = [b'M', b'M', 0, 42, 0, 0, 0, 8];
+
+#[test]
+fn read() {
+    let le = Header::read_le(FixedMemoryStream::new(LE_BYTES)).unwrap();
+    let be = Header::read_be(FixedMemoryStream::new(BE_BYTES)).unwrap();
+    assert_eq!(le, Header { order: *b"II", magic: 42, offset: 8 });
+    assert_eq!(be, Header { order: *b"MM", magic: 42, offset: 8 });
+}
+
+#[test]
+fn write() {
+    let mut stream = GrowingMemoryStream::new();
+    Header { order: *b"II", magic: 42, offset: 8 }.write_le(&mut stream).unwrap();
+    Header { order: *b"MM", magic: 42, offset: 8 }.write_be(&mut stream).unwrap();
+    assert_eq!(stream.take(), [LE_BYTES, BE_BYTES].concat());
+}
+
+#[test]
+fn generic() {
+    let mut stream = GrowingMemoryStream::new();
+    Generic { value: 0x0102u16 }.write_be(&mut stream).unwrap();
+    assert_eq!(stream.take(), [1, 2]);
+    assert_eq!(Generic::<u16>::read_le(FixedMemoryStream::new([1, 2])), Ok(Generic { value: 0x0201 }));
+}
+
+#[test]
+fn multi_pass() {
+    let value = MultiPass { byte_count: 4, items: vec![1, 2] };
+    let mut stream = GrowingMemoryStream::new();
+    value.write_le(&mut stream).unwrap();
+    assert_eq!(stream.take(), [4, 1, 0, 2, 0]);
+}
+
+#[test]
+fn eof() {
+    assert!(Header::read_le(FixedMemoryStream::new([0u8; 4])).is_err());
+}
@@ -0,0 +1,53 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::error::{Error, ErrorKind, TraceError as _};
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Header {
+    version: u8,
+    flags: Flags,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Flags {
+    reserved: u8,
+    enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Padded {
+    #[sorbit(offset = 2)]
+    low: u8,
+    #[sorbit(if = "self.low != 0")]
+    high: Option<u8>,
+}
+
+#[test]
+fn deserialize_nested() {
+    let error = Error::from(ErrorKind::InvalidEnumVariant).annotate("enabled").annotate("flags").locate(2);
+    assert_eq!(from_bytes::<Header>(&[1, 0, 7]), Err(error));
+}
+
+#[test]
+fn deserialize_nested_message() {
+    let message = from_bytes::<Header>(&[1, 0, 7]).unwrap_err().to_string();
+    assert_eq!(
+        message,
+        ".flags.enabled @ offset 0x2: the numeric value does not correspond to an enum or bool variant"
+    );
+}
+
+#[test]
+fn deserialize_eof() {
+    let error = Error::from(ErrorKind::UnexpectedEof { bytes_needed: 1, bytes_available: 0 })
+        .annotate("reserved")
+        .annotate("flags")
+        .locate(1);
+    assert_eq!(from_bytes::<Header>(&[1]), Err(error));
+}
+
+#[test]
+fn serialize_padded() {
+    let error = Error::from(ErrorKind::Custom("the field is present but has no value")).annotate("high").locate(3);
+    assert_eq!(to_bytes(&Padded { low: 1, high: None }), Err(error));
+}
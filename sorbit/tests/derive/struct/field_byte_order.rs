@@ -50,3 +50,24 @@ fn serialize_with_layout() {
 fn deserialize_with_layout() {
     assert_eq!(from_bytes::<WithLayout>(&WITH_LAYOUT_BYTES), Ok(WITH_LAYOUT_VALUE));
 }
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct MiddleEndian {
+    #[sorbit(byte_order=middle_endian)]
+    me: u32,
+    #[sorbit(bit_field=_me, repr=u32, bits=0..32, byte_order=middle_endian)]
+    me_bit: u32,
+}
+
+const MIDDLE_ENDIAN_VALUE: MiddleEndian = MiddleEndian { me: 0xDEADBEEF, me_bit: 0x01020304 };
+const MIDDLE_ENDIAN_BYTES: [u8; 8] = [0xAD, 0xDE, 0xEF, 0xBE, 0x02, 0x01, 0x04, 0x03];
+
+#[test]
+fn serialize_middle_endian() {
+    assert_eq!(to_bytes(&MIDDLE_ENDIAN_VALUE), Ok(MIDDLE_ENDIAN_BYTES.into()));
+}
+
+#[test]
+fn deserialize_middle_endian() {
+    assert_eq!(from_bytes::<MiddleEndian>(&MIDDLE_ENDIAN_BYTES), Ok(MIDDLE_ENDIAN_VALUE));
+}
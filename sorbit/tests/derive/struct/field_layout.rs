@@ -17,6 +17,26 @@ struct Align {
     post: u8,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct AlignAbsolute {
+    pre: u8,
+    inner: Align,
+    #[sorbit(align_absolute = 4)]
+    subject: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct AlignNested {
+    pre: u8,
+    inner: AlignAbsoluteInner,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct AlignAbsoluteInner {
+    #[sorbit(align_absolute = 4)]
+    subject: u8,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 struct Round {
     pre: u8,
@@ -39,6 +59,12 @@ const OFFSET_BYTES: [u8; 6] = [0xFD, 0, 0, 0, 0xFE, 0xFF];
 const ALIGN_VALUE: Align = Align { pre: 0xFD, subject: 0xFE, post: 0xFF };
 const ALIGN_BYTES: [u8; 6] = [0xFD, 0, 0, 0, 0xFE, 0xFF];
 
+const ALIGN_ABSOLUTE_VALUE: AlignAbsolute = AlignAbsolute { pre: 0xFC, inner: ALIGN_VALUE, subject: 0xFE };
+const ALIGN_ABSOLUTE_BYTES: [u8; 9] = [0xFC, 0xFD, 0, 0, 0, 0xFE, 0xFF, 0, 0xFE];
+
+const ALIGN_NESTED_VALUE: AlignNested = AlignNested { pre: 0xFD, inner: AlignAbsoluteInner { subject: 0xFE } };
+const ALIGN_NESTED_BYTES: [u8; 5] = [0xFD, 0, 0, 0, 0xFE];
+
 const ROUND_VALUE: Round = Round { pre: 0xFD, subject: 0xFE, post: 0xFF };
 const ROUND_BYTES: [u8; 6] = [0xFD, 0xFE, 0, 0, 0, 0xFF];
 
@@ -65,6 +91,26 @@ fn deserialize_align() {
     assert_eq!(from_bytes::<Align>(&ALIGN_BYTES), Ok(ALIGN_VALUE));
 }
 
+#[test]
+fn serialize_align_absolute() {
+    assert_eq!(to_bytes(&ALIGN_ABSOLUTE_VALUE), Ok(ALIGN_ABSOLUTE_BYTES.into()));
+}
+
+#[test]
+fn deserialize_align_absolute() {
+    assert_eq!(from_bytes::<AlignAbsolute>(&ALIGN_ABSOLUTE_BYTES), Ok(ALIGN_ABSOLUTE_VALUE));
+}
+
+#[test]
+fn serialize_align_nested() {
+    assert_eq!(to_bytes(&ALIGN_NESTED_VALUE), Ok(ALIGN_NESTED_BYTES.into()));
+}
+
+#[test]
+fn deserialize_align_nested() {
+    assert_eq!(from_bytes::<AlignNested>(&ALIGN_NESTED_BYTES), Ok(ALIGN_NESTED_VALUE));
+}
+
 #[test]
 fn serialize_round() {
     assert_eq!(to_bytes(&ROUND_VALUE), Ok(ROUND_BYTES.into()));
@@ -0,0 +1,66 @@
+use sorbit::io::GrowingMemoryStream;
+use sorbit::ser_de::Deserialize as _;
+use sorbit::stream_ser_de::{FieldSpans, StreamDeserializer};
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Header {
+    #[sorbit(bit_field=_flags, repr=u8, bits=0..4)]
+    kind: u8,
+    #[sorbit(bit_field=_flags, bits=4..8)]
+    version: u8,
+    #[sorbit(align = 4)]
+    len: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Pair(u8, u16);
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct File {
+    header: Header,
+    pairs: [Pair; 2],
+}
+
+fn field_spans(bytes: &[u8]) -> FieldSpans {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes)).record_field_spans();
+    File::deserialize(&mut deserializer).unwrap();
+    deserializer.take_field_spans().unwrap()
+}
+
+#[test]
+fn nested_fields() {
+    let spans = field_spans(&[0x21, 0, 0, 0, 0, 6, 1, 0, 2, 3, 0, 4]);
+    let expected = [
+        ("header", 0..6),
+        ("header._flags", 0..1),
+        ("header.len", 4..6),
+        ("pairs", 6..12),
+        ("pairs.0", 6..7),
+        ("pairs.1", 7..9),
+        ("pairs.0", 9..10),
+        ("pairs.1", 10..12),
+    ];
+    assert!(spans.iter().eq(expected), "{spans:?}");
+}
+
+#[test]
+fn not_recorded() {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(vec![0, 0, 0, 0, 0, 0]));
+    Header::deserialize(&mut deserializer).unwrap();
+    assert_eq!(deserializer.field_spans(), None);
+}
+
+#[test]
+fn failed_field() {
+    let mut deserializer =
+        StreamDeserializer::new(GrowingMemoryStream::from(vec![0x21, 0, 0, 0, 0])).record_field_spans();
+    assert!(File::deserialize(&mut deserializer).is_err());
+    let spans = deserializer.field_spans().unwrap();
+    assert_eq!(spans.get("header"), Some(0..4));
+    assert_eq!(spans.get("header.len"), Some(4..4));
+    assert_eq!(spans.get("pairs"), None);
+}
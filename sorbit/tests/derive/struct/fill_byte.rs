@@ -0,0 +1,76 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::error::ErrorKind;
+use sorbit::io::GrowingMemoryStream;
+use sorbit::stream_ser_de::StreamDeserializer;
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian, fill = 0xFF, len = 8)]
+struct Filled {
+    a: u8,
+    #[sorbit(offset = 2)]
+    b: u8,
+    #[sorbit(align = 2)]
+    c: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(fill = 0xAA)]
+struct Outer {
+    #[sorbit(align = 2)]
+    a: u8,
+    #[sorbit(align = 2)]
+    inner: Inner,
+    #[sorbit(align = 2)]
+    b: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(round = 2)]
+struct Inner {
+    value: u8,
+}
+
+const FILLED_VALUE: Filled = Filled { a: 0x01, b: 0x02, c: 0x0304 };
+const FILLED_BYTES: [u8; 8] = [0x01, 0xFF, 0x02, 0xFF, 0x03, 0x04, 0xFF, 0xFF];
+
+const OUTER_VALUE: Outer = Outer { a: 0x01, inner: Inner { value: 0x02 }, b: 0x03 };
+const OUTER_BYTES: [u8; 5] = [0x01, 0xAA, 0x02, 0xAA, 0x03];
+
+fn from_bytes_verified<T: sorbit::ser_de::Deserialize>(bytes: &[u8]) -> Result<T, ErrorKind> {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes)).verify_fill();
+    T::deserialize(&mut deserializer).map_err(|error| error.kind())
+}
+
+#[test]
+fn serialize() {
+    assert_eq!(to_bytes(&FILLED_VALUE), Ok(FILLED_BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(from_bytes::<Filled>(&FILLED_BYTES), Ok(FILLED_VALUE));
+}
+
+#[test]
+fn deserialize_verified() {
+    assert_eq!(from_bytes_verified::<Filled>(&FILLED_BYTES), Ok(FILLED_VALUE));
+}
+
+#[test]
+fn deserialize_verified_mismatch() {
+    let mut bytes = FILLED_BYTES;
+    bytes[6] = 0x00;
+    assert_eq!(from_bytes::<Filled>(&bytes), Ok(FILLED_VALUE));
+    assert_eq!(from_bytes_verified::<Filled>(&bytes), Err(ErrorKind::UnexpectedFill));
+}
+
+#[test]
+fn serialize_inherited() {
+    assert_eq!(to_bytes(&OUTER_VALUE), Ok(OUTER_BYTES.into()));
+}
+
+#[test]
+fn deserialize_inherited() {
+    assert_eq!(from_bytes_verified::<Outer>(&OUTER_BYTES), Ok(OUTER_VALUE));
+}
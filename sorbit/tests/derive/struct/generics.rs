@@ -29,6 +29,35 @@ enum GenericEnumField {
     },
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian, bound = "T: Into<u16> + From<u16> + Copy")]
+struct Bounded<T> {
+    #[sorbit(with = "converted")]
+    value: T,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian, bound = "")]
+#[repr(u8)]
+enum BoundedEnum<T> {
+    Variant { value: GenericValue<T> },
+}
+
+mod converted {
+    use sorbit::ser_de::{Deserialize as _, Deserializer, Serialize as _, Serializer};
+
+    pub fn serialize<T: Into<u16> + Copy, S: Serializer>(
+        value: &T,
+        serializer: &mut S,
+    ) -> Result<S::Success, S::Error> {
+        (*value).into().serialize(serializer)
+    }
+
+    pub fn deserialize<T: From<u16>, D: Deserializer>(deserializer: &mut D) -> Result<T, D::Error> {
+        u16::deserialize(deserializer).map(T::from)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 struct GenericValue<T> {
     value: u8,
@@ -78,6 +107,33 @@ fn deserialize() {
     assert_eq!(Generic::<i32>::from_bytes(&BYTES), Ok(VALUE));
 }
 
+const VALUE_BOUNDED: Bounded<u16> = Bounded { value: 0xABCD };
+const BYTES_BOUNDED: [u8; 2] = [0xAB, 0xCD];
+
+const VALUE_BOUNDED_ENUM: BoundedEnum<String> =
+    BoundedEnum::Variant { value: GenericValue { value: 0xAB, _type: PhantomData } };
+const BYTES_BOUNDED_ENUM: [u8; 2] = [0x00, 0xAB];
+
+#[test]
+fn serialize_bounded() {
+    assert_eq!(VALUE_BOUNDED.to_bytes(), Ok(BYTES_BOUNDED.into()));
+}
+
+#[test]
+fn deserialize_bounded() {
+    assert_eq!(Bounded::<u16>::from_bytes(&BYTES_BOUNDED), Ok(VALUE_BOUNDED));
+}
+
+#[test]
+fn serialize_bounded_enum() {
+    assert_eq!(VALUE_BOUNDED_ENUM.to_bytes(), Ok(BYTES_BOUNDED_ENUM.into()));
+}
+
+#[test]
+fn deserialize_bounded_enum() {
+    assert_eq!(BoundedEnum::<String>::from_bytes(&BYTES_BOUNDED_ENUM), Ok(VALUE_BOUNDED_ENUM));
+}
+
 #[test]
 fn serialize_struct_field() {
     assert_eq!(VALUE_STRUCT_FIELD.to_bytes(), Ok(BYTES_STRUCT_FIELD.into()));
@@ -0,0 +1,54 @@
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    error::{ErrorKind, MagicBytes},
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian, magic=b"\x89PNG")]
+struct Header {
+    version: u8,
+    #[sorbit(magic = b"IHDR")]
+    chunk: [u8; 4],
+}
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(magic = b"END")]
+struct Empty {}
+
+const VALUE: Header = Header { version: 1, chunk: *b"IHDR" };
+const BYTES: [u8; 9] = [0x89, b'P', b'N', b'G', 1, b'I', b'H', b'D', b'R'];
+
+#[test]
+fn serialize() {
+    assert_eq!(VALUE.to_bytes(), Ok(BYTES.into()));
+    assert_eq!(Header { version: 1, chunk: [0; 4] }.to_bytes(), Ok(BYTES.into()));
+    assert_eq!(Empty {}.to_bytes(), Ok(b"END".into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(Header::from_bytes(&BYTES), Ok(VALUE));
+    assert_eq!(Empty::from_bytes(b"END"), Ok(Empty {}));
+}
+
+#[test]
+fn deserialize_bad_struct_magic() {
+    let result = Header::from_bytes(b"GIF8\x01IHDR").map_err(|error| error.kind());
+    let expected = ErrorKind::BadMagic { expected: b"\x89PNG", found: MagicBytes::new(b"GIF8") };
+    assert_eq!(result, Err(expected));
+}
+
+#[test]
+fn deserialize_bad_field_magic() {
+    let result = Header::from_bytes(b"\x89PNG\x01IDAT").map_err(|error| error.kind());
+    let expected = ErrorKind::BadMagic { expected: b"IHDR", found: MagicBytes::new(b"IDAT") };
+    assert_eq!(result, Err(expected));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Header::LAYOUT, Layout::new(9, Some(9), 1));
+    assert_eq!(Empty::LAYOUT, Layout::new(3, Some(3), 1));
+}
@@ -1,14 +1,50 @@
+mod array_field;
+mod backward_offset;
 mod bit_fields;
 mod bit_numbering;
+mod borrowed_field;
+mod boxed_field;
+mod byte_array_bit_field;
+mod byte_order_inheritance;
+mod checksum_field;
 mod collection_by_byte_count;
+mod collection_by_count;
 mod collection_by_length;
+mod collection_len_prefix;
+mod conditional_field;
 mod constant_field;
+mod deferred_field;
+mod duration_field;
+mod dyn_byte_order;
 mod empty;
+mod error_trace;
 mod field_byte_order;
 mod field_layout;
+mod field_spans;
+mod fill_byte;
 mod generics;
+mod magic_field;
+mod offset_table;
+mod option_field;
+mod overlay_field;
 mod phantom_field;
+mod pointer_field;
+mod pointer_offset;
+mod range_field;
+mod recursive;
+mod reordered_field;
+mod scaled_field;
+mod size_prefix;
+mod skipped_field;
+mod strided_field;
+mod string_field;
 mod struct_byte_order;
 mod struct_layout;
 mod struct_multi_pass;
+mod transparent;
 mod tuple_struct;
+mod validated_struct;
+mod varint_field;
+mod versioned_field;
+mod wide_primitives;
+mod with_field;
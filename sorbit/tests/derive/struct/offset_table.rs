@@ -0,0 +1,68 @@
+use sorbit::error::ErrorKind;
+use sorbit::io::GrowingMemoryStream;
+use sorbit::ser_de::{FromBytes, MultiPassSerialize, ToBytes};
+use sorbit::stream_ser_de::StreamSerializer;
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=little_endian)]
+struct Table {
+    #[sorbit(offset_of=name)]
+    name_offset: u8,
+    #[sorbit(offset_of=data, base=relative)]
+    data_offset: u8,
+    #[sorbit(align = 4)]
+    name: [u8; 3],
+    #[sorbit(align = 4)]
+    data: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Entry {
+    id: u8,
+    #[sorbit(offset_of=payload, base=composite)]
+    offset: u8,
+    #[sorbit(align = 4)]
+    payload: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Narrow {
+    #[sorbit(offset_of=value)]
+    offset: u8,
+    padding: [u8; 255],
+    value: u8,
+}
+
+fn table_value(name_offset: u8, data_offset: u8) -> Table {
+    Table { name_offset, data_offset, name: *b"abc", data: 0x1234 }
+}
+const TABLE_BYTES: [u8; 10] = [4, 7, 0, 0, b'a', b'b', b'c', 0, 0x34, 0x12];
+
+#[test]
+fn serialize_offset_table() {
+    assert_eq!(table_value(0, 0).to_bytes(), Ok(TABLE_BYTES.into()));
+}
+
+#[test]
+fn deserialize_offset_table() {
+    assert_eq!(Table::from_bytes(&TABLE_BYTES), Ok(table_value(4, 7)));
+}
+
+#[test]
+fn serialize_consecutive() {
+    let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+    for id in [1, 2] {
+        Entry { id, offset: 0, payload: 0xAA }.serialize(&mut serializer).unwrap();
+    }
+    assert_eq!(serializer.take().take(), [1, 4, 0, 0, 0xAA, 2, 4, 0, 0, 0xAA]);
+}
+
+#[test]
+fn serialize_offset_too_large() {
+    let error = Narrow { offset: 0, padding: [0; 255], value: 0 }.to_bytes().unwrap_err();
+    assert_eq!(
+        error.kind(),
+        ErrorKind::Custom("the offset of the object is too large for its binary representation")
+    );
+}
@@ -0,0 +1,73 @@
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Optionals {
+    #[sorbit(option=flag(u8))]
+    flagged: Option<u16>,
+    #[sorbit(option=sentinel(0xFFFF))]
+    sentinel: Option<u16>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian, size_prefix=u8)]
+struct Trailing {
+    value: u8,
+    #[sorbit(option=remaining)]
+    trailing: Option<u16>,
+}
+
+#[test]
+fn serialize_present() {
+    let value = Optionals { flagged: Some(1), sentinel: Some(2) };
+    assert_eq!(value.to_bytes(), Ok(vec![1, 0, 1, 0, 2]));
+}
+
+#[test]
+fn serialize_absent() {
+    let value = Optionals { flagged: None, sentinel: None };
+    assert_eq!(value.to_bytes(), Ok(vec![0, 0xFF, 0xFF]));
+}
+
+#[test]
+fn deserialize_present() {
+    let value = Optionals { flagged: Some(1), sentinel: Some(2) };
+    assert_eq!(Optionals::from_bytes(&[1, 0, 1, 0, 2]), Ok(value));
+}
+
+#[test]
+fn deserialize_absent() {
+    let value = Optionals { flagged: None, sentinel: None };
+    assert_eq!(Optionals::from_bytes(&[0, 0xFF, 0xFF]), Ok(value));
+}
+
+#[test]
+fn serialize_sentinel_present() {
+    let value = Optionals { flagged: None, sentinel: Some(0xFFFF) };
+    assert!(value.to_bytes().is_err());
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Optionals::LAYOUT, Layout::new(3, Some(5), 1));
+}
+
+#[test]
+fn trailing_present() {
+    let value = Trailing { value: 1, trailing: Some(2) };
+    let bytes = [3, 1, 0, 2];
+    assert_eq!(value.to_bytes(), Ok(bytes.into()));
+    assert_eq!(Trailing::from_bytes(&bytes), Ok(value));
+}
+
+#[test]
+fn trailing_absent() {
+    let value = Trailing { value: 1, trailing: None };
+    let bytes = [1, 1];
+    assert_eq!(value.to_bytes(), Ok(bytes.into()));
+    assert_eq!(Trailing::from_bytes(&bytes), Ok(value));
+}
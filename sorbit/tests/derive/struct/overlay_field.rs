@@ -0,0 +1,68 @@
+use sorbit::error::Error;
+use sorbit::io::GrowingMemoryStream;
+use sorbit::layout::{Layout, StaticLayout as _};
+use sorbit::ser_de::MultiPassDeserialize;
+use sorbit::stream_ser_de::StreamDeserializer;
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+use crate::utility::to_bytes;
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Register {
+    word: u32,
+    #[sorbit(offset = 0, allow_backward = overlay)]
+    high: u16,
+    #[sorbit(offset = 2, allow_backward = overlay)]
+    low: u16,
+    #[sorbit(bit_field = _flags, repr = u8, bits = 0..4, offset = 3, allow_backward = overlay)]
+    nibble: u8,
+    post: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Extending {
+    head: u8,
+    #[sorbit(offset = 0, allow_backward = overlay)]
+    view: [u8; 2],
+}
+
+const REGISTER_VALUE: Register = Register { word: 0xAABBCCDD, high: 0xAABB, low: 0xCCDD, nibble: 0xD, post: 0xFF };
+const REGISTER_BYTES: [u8; 5] = [0xAA, 0xBB, 0xCC, 0xDD, 0xFF];
+
+fn from_bytes<T: MultiPassDeserialize>(bytes: &[u8]) -> Result<T, Error> {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+    T::deserialize(&mut deserializer)
+}
+
+#[test]
+fn serialize_from_source_fields() {
+    let value = Register { high: 0, low: 0, nibble: 0, ..REGISTER_VALUE };
+    assert_eq!(to_bytes(&value), Ok(REGISTER_BYTES.into()));
+}
+
+#[test]
+fn deserialize_all_views() {
+    assert_eq!(from_bytes::<Register>(&REGISTER_BYTES), Ok(REGISTER_VALUE));
+}
+
+#[test]
+fn deserialize_past_preceding() {
+    assert_eq!(from_bytes::<Extending>(&[0x12, 0x34]), Ok(Extending { head: 0x12, view: [0x12, 0x34] }));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Register::LAYOUT, Layout::fixed(5).nest());
+    let ranges: Vec<_> = Register::FIELDS.iter().map(|field| (field.name(), field.range())).collect();
+    assert_eq!(
+        ranges,
+        [
+            ("word", Some(0..4)),
+            ("high", Some(0..2)),
+            ("low", Some(2..4)),
+            ("_flags", Some(3..4)),
+            ("post", Some(4..5))
+        ]
+    );
+}
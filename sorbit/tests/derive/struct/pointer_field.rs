@@ -0,0 +1,67 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use sorbit::{
+    Deserialize, Serialize,
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Tree {
+    value: u8,
+    #[sorbit(option=flag(u8))]
+    child: Option<Box<Tree>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Pointers<'a, T: Clone> {
+    boxed: Box<T>,
+    rc: Rc<T>,
+    arc: Arc<T>,
+    cow: Cow<'a, T>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+#[repr(u8)]
+enum Choice<T> {
+    Pointer { value: Rc<T> } = 1,
+}
+
+#[test]
+fn serialize_recursive() {
+    let value = Tree { value: 1, child: Some(Box::new(Tree { value: 2, child: None })) };
+    assert_eq!(value.to_bytes(), Ok(vec![1, 1, 2, 0]));
+}
+
+#[test]
+fn deserialize_recursive() {
+    let value = Tree { value: 1, child: Some(Box::new(Tree { value: 2, child: None })) };
+    assert_eq!(Tree::from_bytes(&[1, 1, 2, 0]), Ok(value));
+}
+
+#[test]
+fn serialize_generic() {
+    let inner = 0x0304u16;
+    let value =
+        Pointers { boxed: Box::new(0x0102), rc: Rc::new(0x0203), arc: Arc::new(0x0304), cow: Cow::Borrowed(&inner) };
+    assert_eq!(value.to_bytes(), Ok(vec![0x01, 0x02, 0x02, 0x03, 0x03, 0x04, 0x03, 0x04]));
+}
+
+#[test]
+fn deserialize_generic() {
+    let value = Pointers::<u16>::from_bytes(&[0x01, 0x02, 0x02, 0x03, 0x03, 0x04, 0x03, 0x04]).unwrap();
+    assert_eq!(*value.boxed, 0x0102);
+    assert_eq!(*value.rc, 0x0203);
+    assert_eq!(*value.arc, 0x0304);
+    assert_eq!(*value.cow, 0x0304);
+}
+
+#[test]
+fn serialize_generic_enum() {
+    let value = Choice::Pointer { value: Rc::new(0x0102u16) };
+    assert_eq!(value.to_bytes(), Ok(vec![1, 0x01, 0x02]));
+    assert_eq!(Choice::from_bytes(&[1, 0x01, 0x02]), Ok(value));
+}
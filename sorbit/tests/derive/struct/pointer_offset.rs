@@ -0,0 +1,105 @@
+use sorbit::error::{Error, ErrorKind};
+use sorbit::io::GrowingMemoryStream;
+use sorbit::ser_de::{MultiPassDeserialize, MultiPassSerialize};
+use sorbit::stream_ser_de::{StreamDeserializer, StreamSerializer};
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Absolute {
+    #[sorbit(pointer=u16)]
+    name: [u8; 2],
+    #[sorbit(pointer=u16)]
+    data: u32,
+    flags: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+struct Based {
+    id: u8,
+    #[sorbit(pointer=u8, base=composite)]
+    composite: u8,
+    #[sorbit(pointer=u8, base=relative)]
+    relative: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Generic<T> {
+    #[sorbit(pointer=u8)]
+    value: T,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Narrow {
+    padding: [u8; 255],
+    #[sorbit(pointer=u8)]
+    value: u8,
+}
+
+fn absolute_value() -> Absolute {
+    Absolute { name: *b"ab", data: 0x01020304, flags: 0x80 }
+}
+const ABSOLUTE_BYTES: [u8; 11] = [0, 5, 0, 7, 0x80, b'a', b'b', 1, 2, 3, 4];
+
+const BASED_VALUES: [Based; 2] = [
+    Based { id: 0x11, composite: 0xAA, relative: 0xBB },
+    Based { id: 0x22, composite: 0xCC, relative: 0xDD },
+];
+const BASED_BYTES: [u8; 10] = [0x11, 3, 2, 0xAA, 0xBB, 0x22, 3, 2, 0xCC, 0xDD];
+
+fn to_bytes<T: MultiPassSerialize>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+    value.serialize(&mut serializer)?;
+    Ok(serializer.take().take())
+}
+
+fn from_bytes<T: MultiPassDeserialize>(bytes: &[u8]) -> Result<T, Error> {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+    T::deserialize(&mut deserializer)
+}
+
+#[test]
+fn serialize_absolute() {
+    assert_eq!(to_bytes(&absolute_value()), Ok(ABSOLUTE_BYTES.into()));
+}
+
+#[test]
+fn deserialize_absolute() {
+    assert_eq!(from_bytes::<Absolute>(&ABSOLUTE_BYTES), Ok(absolute_value()));
+}
+
+#[test]
+fn serialize_consecutive() {
+    let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+    for value in &BASED_VALUES {
+        value.serialize(&mut serializer).unwrap();
+    }
+    assert_eq!(serializer.take().take(), BASED_BYTES);
+}
+
+#[test]
+fn deserialize_consecutive() {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(BASED_BYTES.as_slice()));
+    assert_eq!(Based::deserialize(&mut deserializer), Ok(BASED_VALUES[0]));
+    assert_eq!(Based::deserialize(&mut deserializer), Ok(BASED_VALUES[1]));
+}
+
+#[test]
+fn generic() {
+    assert_eq!(to_bytes(&Generic { value: 0x0102u16 }), Ok(vec![1, 2, 1]));
+    assert_eq!(from_bytes(&[1, 2, 1]), Ok(Generic { value: 0x0102u16 }));
+}
+
+#[test]
+fn serialize_offset_too_large() {
+    let error = to_bytes(&Narrow { padding: [0; 255], value: 0 }).unwrap_err();
+    assert_eq!(
+        error.kind(),
+        ErrorKind::Custom("the offset of the object is too large for its binary representation")
+    );
+}
+
+#[test]
+fn deserialize_out_of_bounds() {
+    assert!(from_bytes::<Absolute>(&[0, 5, 0, 9, 0x80, b'a', b'b', 1, 2, 3, 4]).is_err());
+}
@@ -0,0 +1,55 @@
+use std::num::NonZeroU16;
+
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    error::ErrorKind,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Record {
+    #[sorbit(min = 1, max = 100)]
+    percent: u8,
+    #[sorbit(max=-1)]
+    negative: i8,
+    id: NonZeroU16,
+}
+
+#[test]
+fn serialize() {
+    let value = Record { percent: 100, negative: -1, id: NonZeroU16::new(0x0102).unwrap() };
+    assert_eq!(value.to_bytes(), Ok(vec![100, 0xFF, 0x01, 0x02]));
+}
+
+#[test]
+fn deserialize() {
+    let value = Record { percent: 1, negative: -128, id: NonZeroU16::new(0x0102).unwrap() };
+    assert_eq!(Record::from_bytes(&[1, 0x80, 0x01, 0x02]), Ok(value));
+}
+
+#[test]
+fn deserialize_below_min() {
+    let result = Record::from_bytes(&[0, 0xFF, 0x01, 0x02]).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::InvalidValue));
+}
+
+#[test]
+fn deserialize_above_max() {
+    let result = Record::from_bytes(&[101, 0xFF, 0x01, 0x02]).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::InvalidValue));
+    let result = Record::from_bytes(&[1, 0x00, 0x01, 0x02]).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::InvalidValue));
+}
+
+#[test]
+fn deserialize_zero() {
+    let result = Record::from_bytes(&[1, 0xFF, 0x00, 0x00]).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::InvalidValue));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Record::LAYOUT, Layout::new(4, Some(4), 1));
+}
@@ -0,0 +1,50 @@
+use sorbit::{
+    Deserialize, Serialize,
+    error::ErrorKind,
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+// Each node forms a composite, and so does the `option` encoding of the next node.
+#[sorbit(max_depth = 5)]
+struct Node {
+    value: u8,
+    #[sorbit(option=flag(u8))]
+    next: Option<Box<Node>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Unlimited {
+    #[sorbit(option=flag(u8))]
+    next: Option<Box<Unlimited>>,
+}
+
+fn chain(len: u8) -> Option<Box<Node>> {
+    (0..len).rev().fold(None, |next, value| Some(Box::new(Node { value, next })))
+}
+
+fn chain_bytes(len: u8) -> Vec<u8> {
+    (0..len).flat_map(|value| [value, u8::from(value + 1 < len)]).collect()
+}
+
+#[test]
+fn serialize() {
+    assert_eq!(chain(3).unwrap().to_bytes(), Ok(chain_bytes(3)));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(Node::from_bytes(&chain_bytes(3)), Ok(*chain(3).unwrap()));
+}
+
+#[test]
+fn deserialize_too_deep() {
+    let result = Node::from_bytes(&chain_bytes(4)).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::RecursionLimit));
+}
+
+#[test]
+fn deserialize_default_limit() {
+    let result = Unlimited::from_bytes(&[1; 1000]).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::RecursionLimit));
+}
@@ -0,0 +1,85 @@
+use sorbit::error::Error;
+use sorbit::io::GrowingMemoryStream;
+use sorbit::layout::{Layout, StaticLayout as _};
+use sorbit::ser_de::{MultiPassDeserialize, MultiPassSerialize};
+use sorbit::stream_ser_de::{StreamDeserializer, StreamSerializer};
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Header {
+    #[sorbit(offset = 4, allow_backward = reorder)]
+    len: u16,
+    #[sorbit(offset = 0, allow_backward = reorder)]
+    magic: u16,
+    tail: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Body {
+    a: u16,
+    #[sorbit(align = 4)]
+    b: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Nested {
+    #[sorbit(offset = 2, allow_backward = reorder)]
+    body: Body,
+    #[sorbit(offset = 0, allow_backward = reorder)]
+    version: u8,
+    tail: u8,
+}
+
+const HEADER_VALUE: Header = Header { len: 0xCCDD, magic: 0xAABB, tail: 0xEE };
+const HEADER_BYTES: [u8; 7] = [0xAA, 0xBB, 0x00, 0x00, 0xCC, 0xDD, 0xEE];
+
+const NESTED_VALUE: Nested = Nested { body: Body { a: 0xAABB, b: 0xCC }, version: 0x01, tail: 0xFF };
+const NESTED_BYTES: [u8; 8] = [0x01, 0x00, 0xAA, 0xBB, 0x00, 0x00, 0xCC, 0xFF];
+
+fn to_bytes<T: MultiPassSerialize>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut serializer = StreamSerializer::new(GrowingMemoryStream::new());
+    value.serialize(&mut serializer)?;
+    Ok(serializer.take().take())
+}
+
+fn from_bytes<T: MultiPassDeserialize>(bytes: &[u8]) -> Result<T, Error> {
+    let mut deserializer = StreamDeserializer::new(GrowingMemoryStream::from(bytes));
+    T::deserialize(&mut deserializer)
+}
+
+#[test]
+fn serialize() {
+    assert_eq!(to_bytes(&HEADER_VALUE), Ok(HEADER_BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(from_bytes::<Header>(&HEADER_BYTES), Ok(HEADER_VALUE));
+}
+
+#[test]
+fn serialize_nested() {
+    assert_eq!(to_bytes(&NESTED_VALUE), Ok(NESTED_BYTES.into()));
+}
+
+#[test]
+fn deserialize_nested() {
+    assert_eq!(from_bytes::<Nested>(&NESTED_BYTES), Ok(NESTED_VALUE));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Header::LAYOUT, Layout::fixed(7).nest());
+    let ranges: Vec<_> = Header::FIELDS.iter().map(|field| (field.name(), field.range())).collect();
+    assert_eq!(
+        ranges,
+        [
+            ("len", Some(4..6)),
+            ("magic", Some(0..2)),
+            ("tail", Some(6..7))
+        ]
+    );
+}
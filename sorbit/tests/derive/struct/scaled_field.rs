@@ -0,0 +1,48 @@
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Reading {
+    #[sorbit(bit_field=_packed, repr=u16, bits=0..12, scale=0.25)]
+    voltage: f32,
+    #[sorbit(bit_field=_packed, bits=12..16, scale=0.5, raw=i8)]
+    offset: f32,
+    #[sorbit(scale=0.5, bias=-40.0, raw=u16)]
+    temperature: f64,
+}
+
+const VALUE: Reading = Reading { voltage: 12.5, offset: -1.5, temperature: 21.5 };
+const BYTES: [u8; 4] = [0xD0, 0x32, 0x00, 0x7B];
+
+#[test]
+fn serialize() {
+    assert_eq!(VALUE.to_bytes(), Ok(BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(Reading::from_bytes(&BYTES), Ok(VALUE));
+}
+
+#[test]
+fn serialize_rounds() {
+    let value = Reading { voltage: 12.6, offset: -1.4, temperature: 21.6 };
+    assert_eq!(value.to_bytes(), Ok(BYTES.into()));
+}
+
+#[test]
+fn serialize_out_of_range() {
+    let value = Reading { voltage: 1024.0, ..VALUE };
+    assert!(value.to_bytes().is_err());
+    let value = Reading { temperature: -41.0, ..VALUE };
+    assert!(value.to_bytes().is_err());
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Reading::LAYOUT, Layout::new(4, Some(4), 1));
+}
@@ -0,0 +1,78 @@
+use sorbit::layout::StaticLayout;
+use sorbit::ser_de::{FromBytes, ToBytes};
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian, size_prefix=u16)]
+struct Excluding {
+    a: u8,
+    b: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian, size_prefix=u16, includes_prefix=true)]
+struct Including {
+    a: u8,
+    b: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Field {
+    pre: u8,
+    #[sorbit(size_prefix=u8)]
+    items: [u8; 3],
+    post: u8,
+}
+
+#[test]
+fn serialize_excluding() {
+    assert_eq!(Excluding { a: 0xAA, b: 0xBBCC }.to_bytes(), Ok(vec![0x00, 0x03, 0xAA, 0xBB, 0xCC]));
+}
+
+#[test]
+fn serialize_including() {
+    assert_eq!(Including { a: 0xAA, b: 0xBBCC }.to_bytes(), Ok(vec![0x00, 0x05, 0xAA, 0xBB, 0xCC]));
+}
+
+#[test]
+fn deserialize_excluding() {
+    let bytes = [0x00, 0x03, 0xAA, 0xBB, 0xCC];
+    assert_eq!(Excluding::from_bytes(&bytes), Ok(Excluding { a: 0xAA, b: 0xBBCC }));
+}
+
+#[test]
+fn deserialize_including() {
+    let bytes = [0x00, 0x05, 0xAA, 0xBB, 0xCC];
+    assert_eq!(Including::from_bytes(&bytes), Ok(Including { a: 0xAA, b: 0xBBCC }));
+}
+
+#[test]
+fn deserialize_too_short() {
+    let bytes = [0x00, 0x02, 0xAA, 0xBB, 0xCC];
+    assert!(Excluding::from_bytes(&bytes).is_err());
+}
+
+#[test]
+fn serialize_field() {
+    let value = Field { pre: 0xFD, items: [1, 2, 3], post: 0xFF };
+    assert_eq!(value.to_bytes(), Ok(vec![0xFD, 0x03, 1, 2, 3, 0xFF]));
+}
+
+#[test]
+fn deserialize_field() {
+    let value = Field { pre: 0xFD, items: [1, 2, 3], post: 0xFF };
+    assert_eq!(Field::from_bytes(&[0xFD, 0x03, 1, 2, 3, 0xFF]), Ok(value));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Excluding::LAYOUT.size(), Some(5));
+    assert_eq!(Including::LAYOUT.size(), Some(5));
+}
+
+#[test]
+fn deserialize_field_skips_unknown() {
+    let value = Field { pre: 0xFD, items: [1, 2, 3], post: 0xFF };
+    assert_eq!(Field::from_bytes(&[0xFD, 0x05, 1, 2, 3, 0xEE, 0xEE, 0xFF]), Ok(value));
+}
@@ -0,0 +1,64 @@
+use std::cell::Cell;
+
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Default, PartialEq)]
+struct Cache(Cell<u32>);
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Record {
+    #[sorbit(skip)]
+    cache: Cache,
+    value: u16,
+    #[sorbit(skip, default = 5)]
+    version: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Trailing(u8, #[sorbit(skip)] Cache);
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[repr(u8)]
+enum Message {
+    Data {
+        value: u8,
+        #[sorbit(skip)]
+        cache: Cache,
+    } = 1,
+}
+
+#[test]
+fn serialize() {
+    let value = Record { cache: Cache(Cell::new(7)), value: 0x0102, version: 3 };
+    assert_eq!(value.to_bytes(), Ok(vec![0x01, 0x02]));
+}
+
+#[test]
+fn deserialize() {
+    let value = Record { cache: Cache::default(), value: 0x0102, version: 5 };
+    assert_eq!(Record::from_bytes(&[0x01, 0x02]), Ok(value));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Record::LAYOUT, Layout::new(2, Some(2), 1));
+}
+
+#[test]
+fn tuple_struct() {
+    let value = Trailing(1, Cache(Cell::new(7)));
+    assert_eq!(value.to_bytes(), Ok(vec![1]));
+    assert_eq!(Trailing::from_bytes(&[1]), Ok(Trailing(1, Cache::default())));
+}
+
+#[test]
+fn enum_variant() {
+    let value = Message::Data { value: 2, cache: Cache(Cell::new(7)) };
+    assert_eq!(value.to_bytes(), Ok(vec![1, 2]));
+    assert_eq!(Message::from_bytes(&[1, 2]), Ok(Message::Data { value: 2, cache: Cache::default() }));
+}
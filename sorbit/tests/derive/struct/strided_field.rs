@@ -0,0 +1,106 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::error::ErrorKind;
+use sorbit::layout::{Layout, StaticLayout};
+use sorbit::ser_de::{FromBytes, ToBytes};
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Record {
+    id: u8,
+    value: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order = big_endian)]
+struct Array {
+    #[sorbit(stride = 4)]
+    records: [u16; 2],
+    post: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct ByLen {
+    #[sorbit(value = len(records))]
+    len: u8,
+    #[sorbit(stride = 4)]
+    records: Vec<Record>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct ByByteCount {
+    #[sorbit(value = byte_count(records))]
+    byte_count: u8,
+    #[sorbit(stride = 4)]
+    records: Vec<Record>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Overflow {
+    #[sorbit(stride = 1)]
+    records: [u16; 2],
+}
+
+const ARRAY_VALUE: Array = Array { records: [0x0102, 0x0304], post: 0xFF };
+const ARRAY_BYTES: [u8; 9] = [0x01, 0x02, 0, 0, 0x03, 0x04, 0, 0, 0xFF];
+
+fn by_len_value(synchronize_len: bool) -> ByLen {
+    let records = vec![
+        Record { id: 1, value: 0x0203 },
+        Record { id: 4, value: 0x0506 },
+    ];
+    ByLen { len: if synchronize_len { 2 } else { 0 }, records }
+}
+const BY_LEN_BYTES: [u8; 9] = [2, 1, 0x02, 0x03, 0, 4, 0x05, 0x06, 0];
+
+fn by_byte_count_value(synchronize_len: bool) -> ByByteCount {
+    let records = vec![
+        Record { id: 1, value: 0x0203 },
+        Record { id: 4, value: 0x0506 },
+    ];
+    ByByteCount { byte_count: if synchronize_len { 8 } else { 0 }, records }
+}
+const BY_BYTE_COUNT_BYTES: [u8; 9] = [8, 1, 0x02, 0x03, 0, 4, 0x05, 0x06, 0];
+
+#[test]
+fn serialize_array() {
+    assert_eq!(to_bytes(&ARRAY_VALUE), Ok(ARRAY_BYTES.into()));
+}
+
+#[test]
+fn deserialize_array() {
+    assert_eq!(from_bytes::<Array>(&ARRAY_BYTES), Ok(ARRAY_VALUE));
+}
+
+#[test]
+fn layout_array() {
+    assert_eq!(Array::LAYOUT, Layout::new(9, Some(9), 3));
+    assert_eq!(Array::FIELDS[1].offset(), Some(8));
+}
+
+#[test]
+fn serialize_by_len() {
+    assert_eq!(to_bytes(&by_len_value(false)), Ok(BY_LEN_BYTES.into()));
+}
+
+#[test]
+fn deserialize_by_len() {
+    assert_eq!(from_bytes::<ByLen>(&BY_LEN_BYTES), Ok(by_len_value(true)));
+}
+
+#[test]
+fn serialize_by_byte_count() {
+    assert_eq!(by_byte_count_value(false).to_bytes(), Ok(BY_BYTE_COUNT_BYTES.into()));
+}
+
+#[test]
+fn deserialize_by_byte_count() {
+    assert_eq!(ByByteCount::from_bytes(&BY_BYTE_COUNT_BYTES), Ok(by_byte_count_value(true)));
+}
+
+#[test]
+fn serialize_overflow() {
+    let value = Overflow { records: [1, 2] };
+    let error = to_bytes(&value).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::LengthExceedsPadding { offset: 1, position: 2 });
+}
@@ -0,0 +1,46 @@
+use sorbit::{
+    Deserialize, Serialize,
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Strings {
+    #[sorbit(string=null_terminated)]
+    null_terminated: String,
+    #[sorbit(string=fixed(4), pad=0x20)]
+    fixed: String,
+    #[sorbit(string=len_prefix(u16))]
+    len_prefixed: String,
+    trailer: u8,
+}
+
+fn strings_value() -> Strings {
+    Strings { null_terminated: "ab".into(), fixed: "c".into(), len_prefixed: "de".into(), trailer: 0xFF }
+}
+const STRINGS_BYTES: [u8; 12] = [
+    b'a', b'b', 0, b'c', b' ', b' ', b' ', 0, 2, b'd', b'e', 0xFF,
+];
+
+#[test]
+fn serialize() {
+    assert_eq!(strings_value().to_bytes(), Ok(STRINGS_BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(Strings::from_bytes(&STRINGS_BYTES), Ok(strings_value()));
+}
+
+#[test]
+fn serialize_fixed_too_long() {
+    let value = Strings { fixed: "cdefg".into(), ..strings_value() };
+    assert!(value.to_bytes().is_err());
+}
+
+#[test]
+fn deserialize_invalid_utf8() {
+    let mut bytes = STRINGS_BYTES;
+    bytes[0] = 0xFF;
+    assert!(Strings::from_bytes(&bytes).is_err());
+}
@@ -36,3 +36,9 @@ fn serialize_multi_pass_single() {
 fn serialize_multi_pass_collection() {
     assert_eq!(MULTI_PASS_COLLECTION_VALUE.to_bytes(), Ok(MULTI_PASS_COLLECTION_BYTES.into()));
 }
+
+#[test]
+fn serialized_len_multi_pass() {
+    let value = MultiPassSingle { inner: ImplicitMultiPass { a: 0, c: vec![1, 2, 3] } };
+    assert_eq!(value.serialized_len(), Ok(4));
+}
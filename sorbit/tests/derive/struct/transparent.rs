@@ -0,0 +1,46 @@
+use std::marker::PhantomData;
+
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    layout::StaticLayout as _,
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(transparent)]
+struct PacketId(u16);
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(transparent)]
+struct Tagged<T> {
+    value: u32,
+    #[sorbit(skip)]
+    tag: PhantomData<T>,
+}
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Packet {
+    id: PacketId,
+    payload: Tagged<u8>,
+}
+
+const VALUE: Packet = Packet { id: PacketId(0x0102), payload: Tagged { value: 0x03040506, tag: PhantomData } };
+const BYTES: [u8; 6] = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+
+#[test]
+fn serialize() {
+    assert_eq!(VALUE.to_bytes(), Ok(BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(Packet::from_bytes(&BYTES), Ok(VALUE));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(PacketId::LAYOUT, u16::LAYOUT);
+    assert_eq!(Tagged::<u8>::LAYOUT, u32::LAYOUT);
+    assert_eq!(Packet::FIELDS[1].layout(), u32::LAYOUT);
+}
@@ -0,0 +1,57 @@
+use sorbit::{
+    Deserialize, Serialize,
+    error::ErrorKind,
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian, validate=Self::check)]
+struct Bounds {
+    lower: u16,
+    upper: u16,
+}
+
+impl Bounds {
+    fn check(&self) -> Result<(), &'static str> {
+        match self.lower <= self.upper {
+            true => Ok(()),
+            false => Err("the lower bound is greater than the upper bound"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(validate = "check_not_empty")]
+struct Message(u8, u8);
+
+fn check_not_empty(message: &Message) -> Result<(), &'static str> {
+    match message {
+        Message(0, 0) => Err("the message is empty"),
+        _ => Ok(()),
+    }
+}
+
+#[test]
+fn serialize() {
+    let value = Bounds { lower: 0x0102, upper: 0x0304 };
+    assert_eq!(value.to_bytes(), Ok(vec![0x01, 0x02, 0x03, 0x04]));
+}
+
+#[test]
+fn deserialize() {
+    let value = Bounds { lower: 0x0102, upper: 0x0304 };
+    assert_eq!(Bounds::from_bytes(&[0x01, 0x02, 0x03, 0x04]), Ok(value));
+}
+
+#[test]
+fn deserialize_invalid() {
+    let result = Bounds::from_bytes(&[0x03, 0x04, 0x01, 0x02]).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::Custom("the lower bound is greater than the upper bound")));
+}
+
+#[test]
+fn deserialize_free_function() {
+    assert_eq!(Message::from_bytes(&[0, 1]), Ok(Message(0, 1)));
+    let result = Message::from_bytes(&[0, 0]).map_err(|error| error.kind());
+    assert_eq!(result, Err(ErrorKind::Custom("the message is empty")));
+}
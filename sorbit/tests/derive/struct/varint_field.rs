@@ -0,0 +1,43 @@
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Record {
+    #[sorbit(varint)]
+    unsigned: u32,
+    #[sorbit(varint)]
+    signed: i16,
+    fixed: u16,
+}
+
+#[test]
+fn serialize() {
+    let value = Record { unsigned: 300, signed: -150, fixed: 0x0102 };
+    assert_eq!(value.to_bytes(), Ok(vec![0xAC, 0x02, 0xAB, 0x02, 0x01, 0x02]));
+}
+
+#[test]
+fn serialize_single_byte() {
+    let value = Record { unsigned: 1, signed: -1, fixed: 0x0102 };
+    assert_eq!(value.to_bytes(), Ok(vec![0x01, 0x01, 0x01, 0x02]));
+}
+
+#[test]
+fn deserialize() {
+    let value = Record { unsigned: 300, signed: -150, fixed: 0x0102 };
+    assert_eq!(Record::from_bytes(&[0xAC, 0x02, 0xAB, 0x02, 0x01, 0x02]), Ok(value));
+}
+
+#[test]
+fn deserialize_out_of_range() {
+    assert!(Record::from_bytes(&[0x80, 0x80, 0x80, 0x80, 0x10, 0x00, 0x01, 0x02]).is_err());
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Record::LAYOUT, Layout::new(4, Some(10), 1));
+}
@@ -0,0 +1,79 @@
+use crate::utility::{from_bytes, to_bytes};
+use sorbit::layout::{Layout, StaticLayout};
+use sorbit::ser_de::{Deserialize as _, Deserializer as _};
+use sorbit::stream_ser_de::SliceDeserializer;
+use sorbit::{Deserialize, Serialize, StaticLayout};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Record {
+    id: u8,
+    #[sorbit(if_version = ">= 2")]
+    flags: Option<u8>,
+    #[sorbit(if_version = "< 3")]
+    short_length: Option<u16>,
+    #[sorbit(if_version = ">= 3")]
+    long_length: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct File {
+    #[sorbit(ctx = "version")]
+    version: u8,
+    record: Record,
+}
+
+#[test]
+fn serialize_old_version() {
+    let value =
+        File { version: 1, record: Record { id: 7, flags: None, short_length: Some(0x1234), long_length: None } };
+    assert_eq!(to_bytes(&value), Ok(vec![0x01, 0x07, 0x12, 0x34]));
+}
+
+#[test]
+fn serialize_new_version() {
+    let record = Record { id: 7, flags: Some(0xFF), short_length: None, long_length: Some(0x1234) };
+    let value = File { version: 3, record };
+    assert_eq!(to_bytes(&value), Ok(vec![0x03, 0x07, 0xFF, 0x00, 0x00, 0x12, 0x34]));
+}
+
+#[test]
+fn deserialize_old_version() {
+    let value =
+        File { version: 1, record: Record { id: 7, flags: None, short_length: Some(0x1234), long_length: None } };
+    assert_eq!(from_bytes(&[0x01, 0x07, 0x12, 0x34]), Ok(value));
+}
+
+#[test]
+fn deserialize_intermediate_version() {
+    let record = Record { id: 7, flags: Some(0xFF), short_length: Some(0x1234), long_length: None };
+    let value = File { version: 2, record };
+    assert_eq!(from_bytes(&[0x02, 0x07, 0xFF, 0x12, 0x34]), Ok(value));
+}
+
+#[test]
+fn deserialize_new_version() {
+    let record = Record { id: 7, flags: Some(0xFF), short_length: None, long_length: Some(0x1234) };
+    let value = File { version: 3, record };
+    assert_eq!(from_bytes(&[0x03, 0x07, 0xFF, 0x00, 0x00, 0x12, 0x34]), Ok(value));
+}
+
+#[test]
+fn deserialize_with_context() {
+    let record = Record { id: 7, flags: Some(0xFF), short_length: Some(0x1234), long_length: None };
+    let mut deserializer = SliceDeserializer::from_slice(&[0x07, 0xFF, 0x12, 0x34]);
+    assert_eq!(deserializer.with_context("version", 2, Record::deserialize), Ok(record));
+}
+
+#[test]
+fn deserialize_without_version() {
+    let record = Record { id: 7, flags: None, short_length: None, long_length: None };
+    let mut deserializer = SliceDeserializer::from_slice(&[0x07]);
+    assert_eq!(Record::deserialize(&mut deserializer), Ok(record));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Record::LAYOUT, Layout::new(1, Some(8), 1));
+}
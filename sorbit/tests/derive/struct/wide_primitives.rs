@@ -0,0 +1,44 @@
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+#[sorbit(byte_order=big_endian)]
+struct Record {
+    id: u128,
+    delta: i128,
+    letter: char,
+    len: usize,
+}
+
+const VALUE: Record = Record { id: 0x0102, delta: -1, letter: 'A', len: 0x0304 };
+const BYTES: [u8; 44] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x02, //
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, //
+    0, 0, 0, 0x41, //
+    0, 0, 0, 0, 0, 0, 0x03, 0x04,
+];
+
+#[test]
+fn serialize() {
+    assert_eq!(VALUE.to_bytes(), Ok(BYTES.into()));
+}
+
+#[test]
+fn deserialize() {
+    assert_eq!(Record::from_bytes(&BYTES), Ok(VALUE));
+}
+
+#[test]
+fn deserialize_invalid_char() {
+    let mut bytes = BYTES;
+    bytes[32..36].copy_from_slice(&[0, 0, 0xD8, 0]);
+    assert!(Record::from_bytes(&bytes).is_err());
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Record::LAYOUT, Layout::new(44, Some(44), 1));
+}
@@ -0,0 +1,61 @@
+use sorbit::ser_de::{Deserializer, Serializer};
+use sorbit::{
+    Deserialize, Serialize, StaticLayout,
+    layout::{Layout, StaticLayout as _},
+    ser_de::{FromBytes, ToBytes},
+};
+
+mod bcd {
+    use sorbit::ser_de::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u8, serializer: &mut S) -> Result<S::Success, S::Error> {
+        serializer.serialize_u8((value / 10) << 4 | (value % 10))
+    }
+
+    pub fn deserialize<D: Deserializer>(deserializer: &mut D) -> Result<u8, D::Error> {
+        let byte = deserializer.deserialize_u8()?;
+        match (byte >> 4, byte & 0x0F) {
+            (tens @ 0..10, ones @ 0..10) => Ok(tens * 10 + ones),
+            _ => deserializer.error("invalid binary-coded decimal"),
+        }
+    }
+}
+
+fn serialize_inverted<S: Serializer>(value: &u8, serializer: &mut S) -> Result<S::Success, S::Error> {
+    serializer.serialize_u8(!value)
+}
+
+fn deserialize_inverted<D: Deserializer>(deserializer: &mut D) -> Result<u8, D::Error> {
+    deserializer.deserialize_u8().map(|value| !value)
+}
+
+#[derive(Debug, Serialize, Deserialize, StaticLayout, PartialEq)]
+struct Record {
+    plain: u8,
+    #[sorbit(with=bcd)]
+    decimal: u8,
+    #[sorbit(serialize_with = "serialize_inverted", deserialize_with = "deserialize_inverted")]
+    inverted: u8,
+}
+
+#[test]
+fn serialize() {
+    let value = Record { plain: 42, decimal: 42, inverted: 0x0F };
+    assert_eq!(value.to_bytes(), Ok(vec![42, 0x42, 0xF0]));
+}
+
+#[test]
+fn deserialize() {
+    let value = Record { plain: 42, decimal: 42, inverted: 0x0F };
+    assert_eq!(Record::from_bytes(&[42, 0x42, 0xF0]), Ok(value));
+}
+
+#[test]
+fn deserialize_invalid() {
+    assert!(Record::from_bytes(&[42, 0x4A, 0xF0]).is_err());
+}
+
+#[test]
+fn layout() {
+    assert_eq!(Record::LAYOUT, Layout::new(1, None, 1));
+}
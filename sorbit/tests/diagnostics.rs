@@ -0,0 +1,136 @@
+//! Compile-fail tests for the derive macros.
+//!
+//! Every `tests/ui/*.rs` file is expanded with the derive macros it requests,
+//! and the diagnostics are rendered similarly to rustc and compared against
+//! the `.stderr` file next to it. The wording and the spans of the diagnostics
+//! are part of the API, so changing them requires updating the `.stderr` files.
+//! Run the tests with `SORBIT_UI=overwrite` to regenerate them.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use syn::{Attribute, DeriveInput, Item};
+
+use sorbit_derive_impl::DeriveObject;
+
+#[test]
+fn diagnostics() {
+    let ui_dir = env::current_dir().unwrap().join("tests").join("ui");
+    let mut cases: Vec<_> = fs::read_dir(&ui_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some(OsStr::new("rs")))
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty(), "no test cases found, the test configuration is wrong");
+
+    let overwrite = env::var("SORBIT_UI").is_ok_and(|value| value == "overwrite");
+    let mismatches: Vec<_> = cases.iter().filter(|case| !check_case(case, overwrite)).collect();
+    assert!(mismatches.is_empty(), "diagnostics differ from the expected output for: {mismatches:#?}");
+}
+
+fn check_case(case: &Path, overwrite: bool) -> bool {
+    let source = fs::read_to_string(case).unwrap();
+    let file_name = PathBuf::from("tests").join("ui").join(case.file_name().unwrap());
+    let actual = expand_diagnostics(&source, &file_name);
+    assert!(!actual.is_empty(), "{} compiled without errors", file_name.display());
+
+    let expected_path = case.with_extension("stderr");
+    if overwrite {
+        fs::write(&expected_path, &actual).unwrap();
+        return true;
+    }
+    let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+    if actual != expected {
+        eprintln!("EXPECTED:\n{expected}\nACTUAL:\n{actual}");
+    }
+    actual == expected
+}
+
+fn expand_diagnostics(source: &str, file_name: &Path) -> String {
+    let file = syn::parse_file(source).unwrap();
+    let mut diagnostics = Vec::new();
+    for item in file.items {
+        let derive_input: DeriveInput = match item {
+            Item::Struct(item) => item.into(),
+            Item::Enum(item) => item.into(),
+            Item::Union(item) => item.into(),
+            _ => continue,
+        };
+        let derives = derived_macros(&derive_input.attrs);
+        if derives.is_empty() {
+            continue;
+        }
+        match DeriveObject::parse(derive_input) {
+            Err(err) => diagnostics.extend(err.into_iter().map(|err| (err.to_string(), err.span()))),
+            Ok(object) => {
+                for derive in derives {
+                    let tokens = match derive.as_str() {
+                        "Serialize" => object.derive_serialize(),
+                        "Deserialize" => object.derive_deserialize(),
+                        "StaticLayout" => object.derive_static_layout(),
+                        "PackInto" => object.derive_pack_into(),
+                        "UnpackFrom" => object.derive_unpack_from(),
+                        "SorbitTag" => object.derive_tag(),
+                        _ => continue,
+                    };
+                    find_compile_errors(tokens, &mut diagnostics);
+                }
+            }
+        }
+    }
+    diagnostics.dedup_by(|a, b| a.0 == b.0 && format!("{:?}", a.1) == format!("{:?}", b.1));
+    diagnostics.into_iter().map(|(message, span)| render(source, file_name, &message, span)).collect()
+}
+
+fn derived_macros(attrs: &[Attribute]) -> Vec<String> {
+    let mut derives = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+        attr.parse_nested_meta(|meta| {
+            derives.push(meta.path.segments.last().unwrap().ident.to_string());
+            Ok(())
+        })
+        .unwrap();
+    }
+    derives
+}
+
+/// Collect the messages of the `compile_error!` invocations in the expanded code.
+fn find_compile_errors(tokens: TokenStream, diagnostics: &mut Vec<(String, Span)>) {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if ident == "compile_error" => {
+                let Some(TokenTree::Group(args)) = tokens.get(index + 2) else { continue };
+                let Some(TokenTree::Literal(message)) = args.stream().into_iter().next() else {
+                    continue;
+                };
+                let message: syn::LitStr = syn::parse_quote!(#message);
+                diagnostics.push((message.value(), ident.span()));
+            }
+            TokenTree::Group(group) => find_compile_errors(group.stream(), diagnostics),
+            _ => (),
+        }
+    }
+}
+
+fn render(source: &str, file_name: &Path, message: &str, span: Span) -> String {
+    let (start, end) = (span.start(), span.end());
+    if start.line == 0 {
+        return format!("error: {message}\n\n");
+    }
+    let line = source.lines().nth(start.line - 1).unwrap_or_default();
+    let num_carets = if start.line == end.line { (end.column - start.column).max(1) } else { 1 };
+    let line_number = start.line.to_string();
+    let gutter = " ".repeat(line_number.len());
+    format!(
+        "error: {message}\n{gutter}--> {}:{}:{}\n{gutter} |\n{line_number} | {line}\n{gutter} | {}{}\n\n",
+        file_name.display(),
+        start.line,
+        start.column + 1,
+        " ".repeat(start.column),
+        "^".repeat(num_carets),
+    )
+}
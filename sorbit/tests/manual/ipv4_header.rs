@@ -39,7 +39,7 @@ impl IPv4Header {
                     checksum += word as u32;
                     checksum = (checksum >> 16) + (checksum & 0xFFFF);
                 }
-                Err(err) if err.kind() == ErrorKind::UnexpectedEof => break Ok(!(checksum as u16)),
+                Err(err) if matches!(err.kind(), ErrorKind::UnexpectedEof { .. }) => break Ok(!(checksum as u16)),
                 Err(err) => break Err(err),
             }
         }
@@ -1,4 +1,6 @@
+mod corpus;
 mod coverage;
 mod derive;
+mod diagnostics;
 mod manual;
 mod utility;
@@ -0,0 +1,8 @@
+use sorbit::Deserialize;
+
+#[derive(Deserialize)]
+struct Struct {
+    a: u16,
+    #[sorbit(allow_backward=seek)]
+    b: u8,
+}
@@ -0,0 +1,7 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(bit_field=_flags, repr=u8)]
+    a: bool,
+}
@@ -0,0 +1,9 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(bit_field=_flags, bits=0)]
+    a: bool,
+    #[sorbit(bit_field=_flags, bits=1)]
+    b: bool,
+}
@@ -0,0 +1,10 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(bit_field=_flags, repr=u8, bits=0)]
+    a: bool,
+    b: u8,
+    #[sorbit(bit_field=_flags, bits=1)]
+    c: bool,
+}
@@ -0,0 +1,9 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(bit_field=_flags, repr=u16, exhaustive, bits=0..4)]
+    a: u8,
+    #[sorbit(bit_field=_flags, bits=6..12)]
+    b: u8,
+}
@@ -0,0 +1,9 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(bit_field=_flags, repr=u8, bits=0)]
+    a: bool,
+    #[sorbit(bit_field=_flags, repr=u16, bits=1)]
+    b: bool,
+}
@@ -0,0 +1,10 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(bit_numbering = MSB0)]
+struct Struct {
+    #[sorbit(bit_field=_flags, repr=u8, bit_numbering=LSB0, bits=0)]
+    a: bool,
+    #[sorbit(bit_field=_flags, bit_numbering=MSB0, bits=1)]
+    b: bool,
+}
@@ -0,0 +1,7 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct<'a> {
+    #[sorbit(borrow)]
+    data: &'a [u8],
+}
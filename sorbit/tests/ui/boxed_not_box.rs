@@ -0,0 +1,7 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(boxed)]
+    data: [u8; 4096],
+}
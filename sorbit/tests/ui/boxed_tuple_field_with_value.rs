@@ -0,0 +1,4 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct(#[sorbit(boxed, value=constant(0u8))] Box<u8>);
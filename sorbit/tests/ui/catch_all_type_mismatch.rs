@@ -0,0 +1,9 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[repr(u8)]
+enum Enum {
+    A = 1,
+    #[sorbit(catch_all)]
+    B(u16),
+}
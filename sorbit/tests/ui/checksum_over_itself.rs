@@ -0,0 +1,8 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    value: u8,
+    #[sorbit(checksum=crc16(value, crc))]
+    crc: u16,
+}
@@ -0,0 +1,8 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(if = self.flags != 0)]
+    data: u32,
+    flags: u8,
+}
@@ -0,0 +1,8 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    flags: u8,
+    #[sorbit(value=constant(7u8), if = self.flags != 0)]
+    version: u8,
+}
@@ -0,0 +1,7 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(byte_order=big_endian, dyn_byte_order)]
+struct Struct {
+    a: u16,
+}
@@ -0,0 +1,8 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(count = self.len)]
+    items: Vec<u8>,
+    len: u8,
+}
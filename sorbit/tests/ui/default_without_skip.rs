@@ -0,0 +1,7 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(default = 5)]
+    value: u8,
+}
@@ -0,0 +1,4 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct(#[sorbit(skip)] u8, u16);
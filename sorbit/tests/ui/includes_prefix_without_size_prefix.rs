@@ -0,0 +1,7 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(includes_prefix=true)]
+struct Struct {
+    data: u32,
+}
@@ -0,0 +1,8 @@
+use sorbit::Deserialize;
+
+#[derive(Deserialize)]
+struct Struct {
+    a: u16,
+    #[sorbit(offset=0, allow_backward=rewind)]
+    b: u8,
+}
@@ -0,0 +1,7 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(bound = "T Serialize")]
+struct Struct<T> {
+    a: T,
+}
@@ -0,0 +1,8 @@
+use core::time::Duration;
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(unit="days", repr=u16)]
+    timeout: Duration,
+}
@@ -0,0 +1,8 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    items: Vec<u8>,
+    #[sorbit(value=len(items))]
+    len: u8,
+}
@@ -0,0 +1,8 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    len: u8,
+    #[sorbit(len_prefix=u16, value=len_by(len))]
+    items: Vec<u8>,
+}
@@ -0,0 +1,7 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(magic=b"RIFF", value=constant(*b"RIFF"))]
+    magic: [u8; 4],
+}
@@ -0,0 +1,11 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[repr(u8)]
+enum Enum {
+    A = 1,
+    #[sorbit(catch_all)]
+    B(u8),
+    #[sorbit(catch_all)]
+    C(u8),
+}
@@ -0,0 +1,8 @@
+use sorbit::{PackInto, UnpackFrom};
+
+#[derive(PackInto, UnpackFrom)]
+#[repr(u8)]
+enum Enum {
+    A = 1,
+    B { a: u8 } = 2,
+}
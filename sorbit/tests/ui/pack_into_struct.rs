@@ -0,0 +1,6 @@
+use sorbit::PackInto;
+
+#[derive(PackInto)]
+struct Struct {
+    a: u8,
+}
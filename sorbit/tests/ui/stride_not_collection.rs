@@ -0,0 +1,7 @@
+use sorbit::Serialize;
+
+#[derive(Serialize)]
+struct Struct {
+    #[sorbit(stride=4)]
+    a: u16,
+}
@@ -0,0 +1,8 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[repr(u8)]
+enum Enum {
+    #[sorbit(tag=3)]
+    A(u8) = 4,
+}
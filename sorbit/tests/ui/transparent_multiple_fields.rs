@@ -0,0 +1,5 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(transparent)]
+struct Pair(u8, u8);
@@ -0,0 +1,5 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(transparent)]
+struct Struct(#[sorbit(align = 4)] u8);
@@ -0,0 +1,7 @@
+use sorbit::Serialize;
+
+#[derive(Serialize)]
+union Union {
+    a: u8,
+    b: i8,
+}
@@ -0,0 +1,9 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(untagged)]
+enum Enum {
+    A(u16),
+    #[sorbit(catch_all)]
+    B,
+}
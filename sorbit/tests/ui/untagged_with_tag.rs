@@ -0,0 +1,9 @@
+use sorbit::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(untagged)]
+enum Enum {
+    #[sorbit(tag = 1)]
+    A(u16),
+    B(u8),
+}
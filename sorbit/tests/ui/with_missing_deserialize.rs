@@ -0,0 +1,11 @@
+use sorbit::{Deserialize, Serialize};
+
+fn to_bcd<S: sorbit::ser_de::Serializer>(value: &u8, serializer: &mut S) -> Result<S::Success, S::Error> {
+    serializer.serialize_u8(*value)
+}
+
+#[derive(Serialize, Deserialize)]
+struct Struct {
+    #[sorbit(serialize_with=to_bcd)]
+    value: u8,
+}
@@ -29,6 +29,19 @@ pub fn derive_deserialize(tokens: TokenStream) -> TokenStream {
     object.derive_deserialize().into()
 }
 
+#[proc_macro_derive(StaticLayout, attributes(sorbit))]
+pub fn derive_static_layout(tokens: TokenStream) -> TokenStream {
+    let input: DeriveInput = match syn::parse(tokens) {
+        Ok(input) => input,
+        Err(err) => return err.into_compile_error().into(),
+    };
+    let object = match DeriveObject::parse(input) {
+        Ok(object) => object,
+        Err(err) => return err.into_compile_error().into(),
+    };
+    object.derive_static_layout().into()
+}
+
 #[proc_macro_derive(PackInto, attributes(sorbit))]
 pub fn derive_pack_into(tokens: TokenStream) -> TokenStream {
     let input: DeriveInput = match syn::parse(tokens) {
@@ -64,3 +77,20 @@ pub fn derive_unpack_from(tokens: TokenStream) -> TokenStream {
             .into()
     }
 }
+
+#[proc_macro_derive(SorbitTag, attributes(sorbit))]
+pub fn derive_tag(tokens: TokenStream) -> TokenStream {
+    let input: DeriveInput = match syn::parse(tokens) {
+        Ok(input) => input,
+        Err(err) => return err.into_compile_error().into(),
+    };
+    if let syn::Data::Enum(_) = input.data {
+        let object = match DeriveObject::parse(input) {
+            Ok(object) => object,
+            Err(err) => return err.into_compile_error().into(),
+        };
+        object.derive_tag().into()
+    } else {
+        syn::Error::new(input.span(), "SorbitTag can only be derived for enums").into_compile_error().into()
+    }
+}
@@ -4,18 +4,21 @@ use std::ops::{Add, Range};
 use std::str::FromStr;
 
 use quote::ToTokens;
+use syn::parse::ParseStream;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
 use syn::{
-    Attribute, Expr, ExprCall, ExprLit, ExprRange, Ident, Lit, LitBool, Member, Meta, Path, RangeLimits, Type,
-    TypePath, parse_quote,
+    Attribute, BinOp, Expr, ExprBinary, ExprCall, ExprLit, ExprRange, Ident, Lit, LitBool, LitByteStr, Member, Meta,
+    MetaNameValue, Path, RangeLimits, Token, Type, TypePath, WherePredicate, parenthesized, parse_quote,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
 pub enum ByteOrder {
     BigEndian,
     LittleEndian,
+    MiddleEndian,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -25,7 +28,89 @@ pub enum BitNumbering {
     LSB0,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backward {
+    /// Rewind the stream when the field's offset precedes the current position.
+    Seek,
+    /// Deserialize the field over the bytes at its offset, then move back to
+    /// where the stream was. The field is not serialized.
+    Overlay,
+    /// (De)serialize the field at its offset, then move on from the end of
+    /// the furthest field, so that fields may be laid out in any order.
+    Reorder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+    Minutes,
+    Hours,
+}
+
+/// The way the bytes of a string field are stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum StringEncoding {
+    /// The string is followed by a null byte.
+    NullTerminated,
+    /// The string is stored in exactly `len` bytes, padded with `pad`.
+    Fixed { len: u64, pad: u8 },
+    /// The string is preceded by the number of its bytes, stored as the given type.
+    LengthPrefix(Type),
+}
+
+/// The way the absence of an optional field is stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum OptionEncoding {
+    /// The value is preceded by a presence flag of the given type.
+    Flag(Type),
+    /// The value is absent if it's equal to the given expression.
+    Sentinel(Expr),
+    /// The value is present if there are bytes remaining within bounds.
+    Remaining,
+}
+
+/// Where the number of items of a collection comes from when it's not stored
+/// in a sibling field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum LengthSource {
+    /// An expression of the preceding fields, like `self.header.num_entries`.
+    Expr(Expr),
+    /// The context value of the given name.
+    Context(String),
+}
+
+/// The position the offset of a pointer or `offset_of` field is interpreted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PointerBase {
+    #[default]
+    Absolute,
+    Composite,
+    Relative,
+}
+
+/// The algorithm that computes the checksum of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Crc16,
+    Crc32,
+}
+
+/// The object is preceded by its size in bytes, stored as `ty`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizePrefix {
+    pub ty: Type,
+    /// The size accounts for the size prefix itself as well.
+    pub includes_prefix: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[allow(clippy::large_enum_variant)]
 pub enum Transform {
     /// Leave the value of this field as is.
     #[default]
@@ -42,8 +127,39 @@ pub enum Transform {
     /// Set the byte count of this field as the value given by another field.
     /// This field should be a sequential collection.
     ByteCountBy(Member),
+    /// Set the length of this field as the value given by an expression or a
+    /// context value. This field should be a sequential collection.
+    LengthFrom(LengthSource),
     /// The value of this field will always be this constant when serialized.
     Constant(syn::Expr),
+    /// Store this [`core::time::Duration`] field as an integer number of `unit`s.
+    Duration { unit: TimeUnit, repr: Type },
+    /// Precede the items of this field by their number, stored as the given type.
+    /// This field should be a sequential collection.
+    LengthPrefix(Type),
+    /// Store this string field with the given encoding.
+    String(StringEncoding),
+    /// Store this optional field with the given encoding.
+    Option(OptionEncoding),
+    /// Set the value of this field to the checksum of the other fields.
+    Checksum { algorithm: ChecksumAlgorithm, over: Vec<Member> },
+    /// (De)serialize this field with the given functions instead of its traits.
+    With { serialize: Path, deserialize: Path },
+    /// Store this integer field as a variable-length integer.
+    VarInt,
+    /// Reject deserialized values that are outside the inclusive bounds.
+    Range { min: Option<Expr>, max: Option<Expr> },
+    /// Store this floating-point field as the integer `(value - bias) / scale`.
+    Scale { scale: Expr, bias: Option<Expr>, raw: Type },
+    /// Store these magic bytes in place of the field, and verify them when
+    /// deserializing. The field should be a byte array of the same length.
+    Magic(LitByteStr),
+    /// Store the offset of this field as the given type in its place, and the
+    /// field itself after the structure.
+    Pointer { offset: Type, base: PointerBase },
+    /// Set the value of this field to the offset of another field, once that
+    /// field is serialized.
+    OffsetOf { member: Member, base: PointerBase },
 }
 
 impl std::fmt::Display for Transform {
@@ -54,7 +170,33 @@ impl std::fmt::Display for Transform {
             Transform::ByteCount(member) => write!(f, "byte_count({})", member.to_token_stream()),
             Transform::LengthBy(member) => write!(f, "len_by({})", member.to_token_stream()),
             Transform::ByteCountBy(member) => write!(f, "byte_count_by({})", member.to_token_stream()),
+            Transform::LengthFrom(LengthSource::Expr(expr)) => write!(f, "count({})", expr.to_token_stream()),
+            Transform::LengthFrom(LengthSource::Context(key)) => write!(f, "count(ctx({key:?}))"),
             Transform::Constant(expr) => write!(f, "constant({})", expr.to_token_stream()),
+            Transform::Duration { unit, repr } => write!(f, "duration({unit}, {})", repr.to_token_stream()),
+            Transform::LengthPrefix(ty) => write!(f, "len_prefix({})", ty.to_token_stream()),
+            Transform::String(encoding) => write!(f, "string({encoding})"),
+            Transform::Option(encoding) => write!(f, "option({encoding})"),
+            Transform::Checksum { algorithm, over } => {
+                let over: Vec<_> = over.iter().map(|member| member.to_token_stream().to_string()).collect();
+                write!(f, "{algorithm}({})", over.join(", "))
+            }
+            Transform::VarInt => write!(f, "varint"),
+            Transform::Range { min, max } => {
+                let min = min.as_ref().map(|min| min.to_token_stream().to_string()).unwrap_or_default();
+                let max = max.as_ref().map(|max| max.to_token_stream().to_string()).unwrap_or_default();
+                write!(f, "range({min}..={max})")
+            }
+            Transform::With { serialize, deserialize } => {
+                write!(f, "with({}, {})", serialize.to_token_stream(), deserialize.to_token_stream())
+            }
+            Transform::Scale { scale, bias, raw } => {
+                let bias = bias.as_ref().map(|bias| bias.to_token_stream().to_string()).unwrap_or("0".into());
+                write!(f, "scale({}, {bias}, {})", scale.to_token_stream(), raw.to_token_stream())
+            }
+            Transform::Magic(bytes) => write!(f, "magic({})", bytes.to_token_stream()),
+            Transform::Pointer { offset, base } => write!(f, "pointer({}, {base})", offset.to_token_stream()),
+            Transform::OffsetOf { member, base } => write!(f, "offset_of({}, {base})", member.to_token_stream()),
         }
     }
 }
@@ -67,14 +209,82 @@ pub mod path {
         parse_quote!(sorbit)
     }
 
+    pub fn bound() -> Path {
+        parse_quote!(bound)
+    }
+
+    pub fn exhaustive() -> Path {
+        parse_quote!(exhaustive)
+    }
+
     pub fn multi_pass() -> Path {
         parse_quote!(multi_pass)
     }
 
+    pub fn deferred() -> Path {
+        parse_quote!(deferred)
+    }
+
+    pub fn skip() -> Path {
+        parse_quote!(skip)
+    }
+
+    pub fn default() -> Path {
+        parse_quote!(default)
+    }
+
+    pub fn boxed() -> Path {
+        parse_quote!(boxed)
+    }
+
+    pub fn borrow() -> Path {
+        parse_quote!(borrow)
+    }
+
+    pub fn varint() -> Path {
+        parse_quote!(varint)
+    }
+
+    pub fn min() -> Path {
+        parse_quote!(min)
+    }
+
+    pub fn max() -> Path {
+        parse_quote!(max)
+    }
+
+    pub fn scale() -> Path {
+        parse_quote!(scale)
+    }
+
+    pub fn bias() -> Path {
+        parse_quote!(bias)
+    }
+
+    pub fn raw() -> Path {
+        parse_quote!(raw)
+    }
+
+    pub fn with() -> Path {
+        parse_quote!(with)
+    }
+
+    pub fn serialize_with() -> Path {
+        parse_quote!(serialize_with)
+    }
+
+    pub fn deserialize_with() -> Path {
+        parse_quote!(deserialize_with)
+    }
+
     pub fn value() -> Path {
         parse_quote!(value)
     }
 
+    pub fn condition() -> Path {
+        Path::from(syn::Ident::new("if", proc_macro2::Span::call_site()))
+    }
+
     pub fn storage_id() -> Path {
         parse_quote!(bit_field)
     }
@@ -91,10 +301,26 @@ pub mod path {
         parse_quote!(offset)
     }
 
+    pub fn allow_backward() -> Path {
+        parse_quote!(allow_backward)
+    }
+
+    pub fn unit() -> Path {
+        parse_quote!(unit)
+    }
+
     pub fn align() -> Path {
         parse_quote!(align)
     }
 
+    pub fn align_absolute() -> Path {
+        parse_quote!(align_absolute)
+    }
+
+    pub fn stride() -> Path {
+        parse_quote!(stride)
+    }
+
     pub fn round() -> Path {
         parse_quote!(round)
     }
@@ -103,6 +329,34 @@ pub mod path {
         parse_quote!(len)
     }
 
+    pub fn len_prefix() -> Path {
+        parse_quote!(len_prefix)
+    }
+
+    pub fn string() -> Path {
+        parse_quote!(string)
+    }
+
+    pub fn option() -> Path {
+        parse_quote!(option)
+    }
+
+    pub fn checksum() -> Path {
+        parse_quote!(checksum)
+    }
+
+    pub fn pad() -> Path {
+        parse_quote!(pad)
+    }
+
+    pub fn size_prefix() -> Path {
+        parse_quote!(size_prefix)
+    }
+
+    pub fn includes_prefix() -> Path {
+        parse_quote!(includes_prefix)
+    }
+
     pub fn byte_order() -> Path {
         parse_quote!(byte_order)
     }
@@ -114,27 +368,128 @@ pub mod path {
     pub fn catch_all() -> Path {
         parse_quote!(catch_all)
     }
+
+    pub fn tag() -> Path {
+        parse_quote!(tag)
+    }
+
+    pub fn dyn_byte_order() -> Path {
+        parse_quote!(dyn_byte_order)
+    }
+
+    pub fn assert_size() -> Path {
+        parse_quote!(assert_size)
+    }
+
+    pub fn max_depth() -> Path {
+        parse_quote!(max_depth)
+    }
+
+    pub fn fill() -> Path {
+        parse_quote!(fill)
+    }
+
+    pub fn transparent() -> Path {
+        parse_quote!(transparent)
+    }
+
+    pub fn untagged() -> Path {
+        parse_quote!(untagged)
+    }
+
+    pub fn validate() -> Path {
+        parse_quote!(validate)
+    }
+
+    pub fn magic() -> Path {
+        parse_quote!(magic)
+    }
+
+    pub fn context() -> Path {
+        parse_quote!(ctx)
+    }
+
+    pub fn if_version() -> Path {
+        parse_quote!(if_version)
+    }
+
+    pub fn count() -> Path {
+        parse_quote!(count)
+    }
+
+    pub fn pointer() -> Path {
+        parse_quote!(pointer)
+    }
+
+    pub fn offset_of() -> Path {
+        parse_quote!(offset_of)
+    }
+
+    pub fn base() -> Path {
+        parse_quote!(base)
+    }
 }
 
-pub fn parse_nvp_attribute(attribute: &Attribute) -> Result<HashMap<Path, Expr>, syn::Error> {
+/// How a parameter is merged with an earlier definition of the same parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Redefining the parameter with a different value is an error.
+    Strict,
+    /// The parameter replaces the earlier definition. Parameters are
+    /// overridden by wrapping them as `override(name = value)`.
+    Override,
+}
+
+pub fn parse_nvp_attribute(attribute: &Attribute) -> Result<Vec<(Path, Expr, MergePolicy)>, syn::Error> {
     let meta_list = attribute.meta.require_list()?;
-    let metas = meta_list.parse_args_with(|parse_buffer: &syn::parse::ParseBuffer<'_>| {
-        Punctuated::<Meta, Comma>::parse_terminated(parse_buffer)
+    let metas = meta_list.parse_args_with(|parse_buffer: ParseStream| {
+        let mut metas = Vec::new();
+        while !parse_buffer.is_empty() {
+            if parse_buffer.peek(Token![override]) {
+                parse_buffer.parse::<Token![override]>()?;
+                let content;
+                parenthesized!(content in parse_buffer);
+                let overrides = Punctuated::<Meta, Comma>::parse_terminated_with(&content, parse_meta)?;
+                metas.extend(overrides.into_iter().map(|meta| (meta, MergePolicy::Override)));
+            } else {
+                metas.push((parse_meta(parse_buffer)?, MergePolicy::Strict));
+            }
+            if !parse_buffer.is_empty() {
+                parse_buffer.parse::<Comma>()?;
+            }
+        }
+        Ok(metas)
     })?;
 
-    let mut name_values = HashMap::new();
-    for meta in metas {
-        match meta {
-            Meta::Path(path) => name_values.insert(
-                path.clone(),
-                Expr::Lit(ExprLit { attrs: vec![], lit: Lit::Bool(LitBool { value: true, span: path.span() }) }),
-            ),
-            Meta::List(list) => return Err(syn::Error::new(list.span(), "expected a name value pair or a path")),
-            Meta::NameValue(name_value) => name_values.insert(name_value.path.clone(), name_value.value.clone()),
-        };
+    metas
+        .into_iter()
+        .map(|(meta, policy)| as_name_value(meta).map(|(name, value)| (name, value, policy)))
+        .collect()
+}
+
+/// Parse a [`Meta`], also accepting the `if` keyword as the name of a parameter.
+fn parse_meta(input: ParseStream) -> Result<Meta, syn::Error> {
+    if input.peek(Token![if]) {
+        let keyword = input.parse::<Token![if]>()?;
+        let eq_token = input.parse()?;
+        let value = input.parse()?;
+        let path = Path::from(Ident::new("if", keyword.span));
+        Ok(Meta::NameValue(MetaNameValue { path, eq_token, value }))
+    } else {
+        input.parse()
     }
+}
 
-    Ok(name_values)
+fn as_name_value(meta: Meta) -> Result<(Path, Expr), syn::Error> {
+    match meta {
+        Meta::Path(path) => {
+            let value =
+                Expr::Lit(ExprLit { attrs: vec![], lit: Lit::Bool(LitBool { value: true, span: path.span() }) });
+            Ok((path, value))
+        }
+        Meta::List(list) => Err(syn::Error::new(list.span(), "expected a name value pair or a path")),
+        Meta::NameValue(name_value) => Ok((name_value.path, name_value.value)),
+    }
 }
 
 pub fn parse_nvp_attribute_group<'attr>(
@@ -145,20 +500,20 @@ pub fn parse_nvp_attribute_group<'attr>(
     let mut merged = HashMap::new();
     for attribute in attributes {
         let nvps = parse_nvp_attribute(attribute)?;
-        for (name, value) in nvps {
-            match merged.entry(name) {
-                Occupied(entry) => {
+        for (name, value, policy) in nvps {
+            match (merged.entry(name), policy) {
+                (Occupied(mut entry), MergePolicy::Override) => {
+                    entry.insert(value);
+                }
+                (Occupied(entry), MergePolicy::Strict) => {
                     if entry.get() != &value {
                         return Err(syn::Error::new(
                             value.span(),
-                            format!(
-                                "parameter `{}` redefined with a different value",
-                                entry.key().to_token_stream().to_string()
-                            ),
+                            format!("parameter `{}` redefined with a different value", entry.key().to_token_stream()),
                         ));
                     }
                 }
-                Vacant(entry) => {
+                (Vacant(entry), _) => {
                     entry.insert(value);
                 }
             };
@@ -170,7 +525,7 @@ pub fn parse_nvp_attribute_group<'attr>(
 pub fn parse_repr_attribute(attr: &syn::Attribute) -> Result<Option<Type>, syn::Error> {
     let list = attr.meta.require_list()?;
     let ty: syn::Type = list.parse_args()?;
-    Ok::<_, syn::Error>((ty != parse_quote!(C)).then(|| ty))
+    Ok::<_, syn::Error>((ty != parse_quote!(C)).then_some(ty))
 }
 
 pub fn as_ident(expr: &Expr) -> Result<Ident, syn::Error> {
@@ -186,17 +541,46 @@ pub fn as_member(expr: &Expr) -> Result<Member, syn::Error> {
     match expr {
         Expr::Path(field) => Ok(Member::from(field.path.get_ident().ok_or_else(error)?.clone())),
         Expr::Lit(ExprLit { lit: Lit::Int(index), .. }) => Ok(Member::from(index.base10_parse::<usize>()?)),
-        _ => return Err(error()),
+        _ => Err(error()),
     }
 }
 
 pub fn as_type(expr: &Expr) -> Result<Type, syn::Error> {
     match expr {
         Expr::Path(path) => Ok(Type::from(TypePath { qself: None, path: path.path.clone() })),
+        // Array types like `[u8; 12]` are parsed as repeat expressions.
+        Expr::Repeat(_) => syn::parse2(expr.to_token_stream()),
         _ => Err(syn::Error::new(expr.span(), "expected a type")),
     }
 }
 
+pub fn as_byte_string(expr: &Expr) -> Result<LitByteStr, syn::Error> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::ByteStr(bytes), .. }) if !bytes.value().is_empty() => Ok(bytes.clone()),
+        _ => Err(syn::Error::new(expr.span(), "expected a non-empty byte string like `b\"RIFF\"`")),
+    }
+}
+
+/// The path may be given either as is or as a string literal that contains it.
+pub fn as_path(expr: &Expr) -> Result<Path, syn::Error> {
+    match expr {
+        Expr::Path(path) => Ok(path.path.clone()),
+        Expr::Lit(ExprLit { lit: Lit::Str(path), .. }) => path.parse(),
+        _ => Err(syn::Error::new(expr.span(), "expected a path")),
+    }
+}
+
+/// The predicates are given as a string literal like `"T: Serialize, U: Copy"`.
+pub fn as_where_predicates(expr: &Expr) -> Result<Vec<WherePredicate>, syn::Error> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Str(predicates), .. }) => {
+            let predicates = predicates.parse_with(Punctuated::<WherePredicate, Comma>::parse_terminated)?;
+            Ok(predicates.into_iter().collect())
+        }
+        _ => Err(syn::Error::new(expr.span(), "expected where predicates in a string like `\"T: Serialize\"`")),
+    }
+}
+
 pub fn as_literal_int<N>(expr: &Expr) -> Result<N, syn::Error>
 where
     N: FromStr<Err: Display> + Display,
@@ -230,7 +614,7 @@ where
             };
             Ok(start..end)
         }
-        _ => return Err(syn::Error::new(expr.span(), "expected a bounded literal integer range (e.g. 1..4, 1..=3")),
+        _ => Err(syn::Error::new(expr.span(), "expected a bounded literal integer range (e.g. 1..4, 1..=3")),
     }
 }
 
@@ -243,7 +627,10 @@ pub fn as_byte_order(expr: &Expr) -> Result<ByteOrder, syn::Error> {
         "le" => Ok(ByteOrder::LittleEndian),
         "little" => Ok(ByteOrder::LittleEndian),
         "little_endian" => Ok(ByteOrder::LittleEndian),
-        _ => Err(syn::Error::new(expr.span(), "byte order may be `big_endian`, `little_endian`, or `inherited`")),
+        "middle" => Ok(ByteOrder::MiddleEndian),
+        "middle_endian" => Ok(ByteOrder::MiddleEndian),
+        "pdp_endian" => Ok(ByteOrder::MiddleEndian),
+        _ => Err(syn::Error::new(expr.span(), "byte order may be `big_endian`, `little_endian`, or `middle_endian`")),
     }
 }
 
@@ -256,6 +643,232 @@ pub fn as_bit_numbering(expr: &Expr) -> Result<BitNumbering, syn::Error> {
     }
 }
 
+pub fn as_backward(expr: &Expr) -> Result<Backward, syn::Error> {
+    let ident = as_ident(expr)?;
+    match ident.to_string().as_str() {
+        "seek" => Ok(Backward::Seek),
+        "overlay" => Ok(Backward::Overlay),
+        "reorder" => Ok(Backward::Reorder),
+        _ => Err(syn::Error::new(
+            expr.span(),
+            "moving backward is only possible with `seek`, `overlay`, or `reorder`",
+        )),
+    }
+}
+
+pub fn as_pointer_base(expr: &Expr) -> Result<PointerBase, syn::Error> {
+    let ident = as_ident(expr)?;
+    match ident.to_string().as_str() {
+        "absolute" => Ok(PointerBase::Absolute),
+        "composite" => Ok(PointerBase::Composite),
+        "relative" => Ok(PointerBase::Relative),
+        _ => Err(syn::Error::new(expr.span(), "the base may be `absolute`, `composite`, or `relative`")),
+    }
+}
+
+pub fn parse_size_prefix(parameters: &HashMap<Path, Expr>) -> Result<Option<SizePrefix>, syn::Error> {
+    let ty = parameters.get(&path::size_prefix()).map(as_type).transpose()?;
+    let includes_prefix = parameters.get(&path::includes_prefix()).map(as_literal_bool).transpose()?;
+    match (ty, includes_prefix) {
+        (Some(ty), includes_prefix) => Ok(Some(SizePrefix { ty, includes_prefix: includes_prefix.unwrap_or(false) })),
+        (None, None) => Ok(None),
+        (None, Some(_)) => Err(syn::Error::new(
+            parameters[&path::includes_prefix()].span(),
+            "`includes_prefix` requires a `size_prefix`",
+        )),
+    }
+}
+
+pub fn as_time_unit(expr: &Expr) -> Result<TimeUnit, syn::Error> {
+    let unit = match expr {
+        Expr::Lit(ExprLit { lit: Lit::Str(unit), .. }) => Some(unit.value()),
+        Expr::Path(path) => path.path.get_ident().map(|ident| ident.to_string()),
+        _ => None,
+    };
+    match unit.as_deref() {
+        Some("ns") => Ok(TimeUnit::Nanoseconds),
+        Some("us") => Ok(TimeUnit::Microseconds),
+        Some("ms") => Ok(TimeUnit::Milliseconds),
+        Some("s") => Ok(TimeUnit::Seconds),
+        Some("min") => Ok(TimeUnit::Minutes),
+        Some("h") => Ok(TimeUnit::Hours),
+        _ => Err(syn::Error::new(expr.span(), "the unit may be `ns`, `us`, `ms`, `s`, `min`, or `h`")),
+    }
+}
+
+/// The encoding of a string may be `null_terminated`, `fixed(<LEN>)`, or
+/// `len_prefix(<TYPE>)`. The padding of fixed-length strings is given
+/// separately, and defaults to zero.
+pub fn as_string_encoding(expr: &Expr) -> Result<StringEncoding, syn::Error> {
+    const MESSAGE: &str = "expected `null_terminated`, `fixed(<LEN>)`, or `len_prefix(<TYPE>)`";
+    match expr {
+        Expr::Path(path) => (path == &parse_quote!(null_terminated))
+            .then_some(StringEncoding::NullTerminated)
+            .ok_or_else(|| syn::Error::new(path.span(), MESSAGE)),
+        Expr::Call(ExprCall { func, args, .. }) if args.len() == 1 => {
+            if func.as_ref() == &parse_quote!(fixed) {
+                Ok(StringEncoding::Fixed { len: as_literal_int(&args[0])?, pad: 0 })
+            } else if func.as_ref() == &parse_quote!(len_prefix) {
+                Ok(StringEncoding::LengthPrefix(as_type(&args[0])?))
+            } else {
+                Err(syn::Error::new(func.span(), MESSAGE))
+            }
+        }
+        _ => Err(syn::Error::new(expr.span(), MESSAGE)),
+    }
+}
+
+/// The encoding of an optional value may be `flag(<TYPE>)`, `sentinel(<EXPR>)`,
+/// or `remaining`.
+pub fn as_option_encoding(expr: &Expr) -> Result<OptionEncoding, syn::Error> {
+    const MESSAGE: &str = "expected `flag(<TYPE>)`, `sentinel(<EXPR>)`, or `remaining`";
+    match expr {
+        Expr::Path(path) => (path == &parse_quote!(remaining))
+            .then_some(OptionEncoding::Remaining)
+            .ok_or_else(|| syn::Error::new(path.span(), MESSAGE)),
+        Expr::Call(ExprCall { func, args, .. }) if args.len() == 1 => {
+            if func.as_ref() == &parse_quote!(flag) {
+                Ok(OptionEncoding::Flag(as_type(&args[0])?))
+            } else if func.as_ref() == &parse_quote!(sentinel) {
+                Ok(OptionEncoding::Sentinel(args[0].clone()))
+            } else {
+                Err(syn::Error::new(func.span(), MESSAGE))
+            }
+        }
+        _ => Err(syn::Error::new(expr.span(), MESSAGE)),
+    }
+}
+
+/// The checksum of a field may be `crc16(<FIELDS>)` or `crc32(<FIELDS>)`,
+/// where the fields are the ones the checksum is computed over.
+pub fn as_checksum(expr: &Expr) -> Result<Transform, syn::Error> {
+    const MESSAGE: &str = "expected `crc16(<FIELDS>)` or `crc32(<FIELDS>)`";
+    let Expr::Call(ExprCall { func, args, .. }) = expr else {
+        return Err(syn::Error::new(expr.span(), MESSAGE));
+    };
+    let algorithm = if func.as_ref() == &parse_quote!(crc16) {
+        ChecksumAlgorithm::Crc16
+    } else if func.as_ref() == &parse_quote!(crc32) {
+        ChecksumAlgorithm::Crc32
+    } else {
+        return Err(syn::Error::new(func.span(), MESSAGE));
+    };
+    if args.is_empty() {
+        return Err(syn::Error::new(expr.span(), "the checksum must cover at least one field"));
+    }
+    let over = args.iter().map(as_member).collect::<Result<_, _>>()?;
+    Ok(Transform::Checksum { algorithm, over })
+}
+
+/// The condition of a field may be given either as an expression or as a
+/// string literal that contains the expression.
+pub fn as_condition(expr: &Expr) -> Result<Expr, syn::Error> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Str(condition), .. }) => condition.parse(),
+        _ => Ok(expr.clone()),
+    }
+}
+
+/// The key of a context value is given as a string literal like `"version"`.
+pub fn as_context_key(expr: &Expr) -> Result<String, syn::Error> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Str(key), .. }) if !key.value().is_empty() => Ok(key.value()),
+        _ => Err(syn::Error::new(expr.span(), "expected a non-empty string literal like `\"version\"`")),
+    }
+}
+
+/// The version requirement is given as a string literal like `">= 3"`, and is
+/// returned as a comparison of `version`, like `version >= 3`.
+pub fn as_version_requirement(expr: &Expr) -> Result<Expr, syn::Error> {
+    let error = || syn::Error::new(expr.span(), "expected a version requirement like `\">= 3\"`");
+    let Expr::Lit(ExprLit { lit: Lit::Str(requirement), .. }) = expr else {
+        return Err(error());
+    };
+    let comparison: Expr = syn::parse_str(&format!("version {}", requirement.value())).map_err(|_| error())?;
+    match &comparison {
+        Expr::Binary(ExprBinary {
+            op: BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_),
+            ..
+        }) => Ok(comparison),
+        _ => Err(error()),
+    }
+}
+
+/// The number of items is given either as an expression like `count` or as a
+/// context value like `ctx("entries")`.
+pub fn as_length_source(expr: &Expr) -> Result<LengthSource, syn::Error> {
+    match as_condition(expr)? {
+        Expr::Call(ExprCall { func, args, .. }) if *func == parse_quote!(ctx) => match args.len() {
+            1 => Ok(LengthSource::Context(as_context_key(&args[0])?)),
+            _ => Err(syn::Error::new(args.span(), "expected exactly 1 argument")),
+        },
+        expr => Ok(LengthSource::Expr(expr)),
+    }
+}
+
+impl std::fmt::Display for SizePrefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.ty.to_token_stream())?;
+        if self.includes_prefix {
+            write!(f, " including prefix")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            TimeUnit::Nanoseconds => "ns",
+            TimeUnit::Microseconds => "us",
+            TimeUnit::Milliseconds => "ms",
+            TimeUnit::Seconds => "s",
+            TimeUnit::Minutes => "min",
+            TimeUnit::Hours => "h",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl std::fmt::Display for StringEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringEncoding::NullTerminated => write!(f, "null_terminated"),
+            StringEncoding::Fixed { len, pad } => write!(f, "fixed({len}), pad={pad:#04x}"),
+            StringEncoding::LengthPrefix(ty) => write!(f, "len_prefix({})", ty.to_token_stream()),
+        }
+    }
+}
+
+impl std::fmt::Display for OptionEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionEncoding::Flag(ty) => write!(f, "flag({})", ty.to_token_stream()),
+            OptionEncoding::Sentinel(expr) => write!(f, "sentinel({})", expr.to_token_stream()),
+            OptionEncoding::Remaining => write!(f, "remaining"),
+        }
+    }
+}
+
+impl std::fmt::Display for PointerBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PointerBase::Absolute => write!(f, "absolute"),
+            PointerBase::Composite => write!(f, "composite"),
+            PointerBase::Relative => write!(f, "relative"),
+        }
+    }
+}
+
+impl std::fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChecksumAlgorithm::Crc16 => write!(f, "crc16"),
+            ChecksumAlgorithm::Crc32 => write!(f, "crc32"),
+        }
+    }
+}
+
 impl std::fmt::Display for ByteOrder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
@@ -1,35 +1,83 @@
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
 use syn::spanned::Spanned;
-use syn::{BinOp, Expr, ExprBinary, ExprLit, Generics, Ident, Lit, LitInt, Member, Pat, Token, Type, parse_quote};
+use syn::{
+    BinOp, Expr, ExprBinary, ExprLit, Generics, Ident, Lifetime, Lit, LitInt, Member, Pat, Token, Type, WherePredicate,
+    parse_quote,
+};
 
 use crate::attribute::ByteOrder;
 use crate::r#enum::ast::variant::{CatchAll, Variant};
 use crate::r#enum::parse;
 use crate::ir::{Region, ToDeserializeOp, ToSerializeOp, Value};
 use crate::ops::algorithm::with_maybe_byte_order;
+use crate::ops::constants::{LAYOUT_TYPE, STATIC_LAYOUT_TRAIT};
 use crate::ops::{
-    self, custom_expr, declare_struct, deserialize_object, error, impl_deserialize, impl_serialize, match_, member, ok,
-    ref_, self_, serialize_composite, serialize_object, struct_, symref, try_, use_,
+    self, custom_expr, declare_struct, deserialize_object, impl_deserialize, impl_serialize, invalid_enum_variant,
+    match_, member, ok, ref_, self_, serialize_composite, serialize_object, struct_, success, symref, try_,
+    try_variants, use_,
 };
 use crate::r#struct::ast::Struct;
-use crate::utility::{deconstruct_pattern_explicit, member_to_ident};
+use crate::utility::{deconstruct_pattern_explicit, member_to_ident, uses_type_params};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Enum {
     pub ident: Ident,
     pub storage_ty: Type,
     pub generics: Generics,
+    pub bound: Option<Vec<WherePredicate>>,
     pub byte_order: Option<ByteOrder>,
+    pub untagged: bool,
     pub variants: Vec<Variant>,
 }
 
 impl Enum {
     pub fn is_multi_pass(&self) -> bool {
-        self.variants
-            .iter()
-            .filter_map(|variant| variant.content.as_ref())
-            .any(|content| content.is_multi_pass())
+        self.contents().any(|content| content.is_multi_pass())
+    }
+
+    pub fn is_seeking(&self) -> bool {
+        self.contents().any(|content| content.is_seeking())
+    }
+
+    pub fn borrowed_lifetime(&self) -> Option<&Lifetime> {
+        self.contents().find_map(|content| content.borrowed_lifetime())
+    }
+
+    /// Return the bounds of the generic parameters for the layout.
+    ///
+    /// The bounds specified by the `bound` parameter replace the inferred
+    /// bounds for all derived traits.
+    fn layout_predicates(&self) -> Vec<WherePredicate> {
+        if let Some(bound) = &self.bound {
+            return bound.clone();
+        }
+        let storage_ty = &self.storage_ty;
+        let discriminant = uses_type_params(storage_ty, &self.generics)
+            .then(|| parse_quote!(#storage_ty: #STATIC_LAYOUT_TRAIT))
+            .into_iter();
+        discriminant
+            .chain(self.contents().flat_map(|content| content.layout_predicates(&self.generics)))
+            .collect()
+    }
+
+    fn serialize_predicates(&self) -> Vec<WherePredicate> {
+        if let Some(bound) = &self.bound {
+            return bound.clone();
+        }
+        self.contents().flat_map(|content| content.serialize_predicates(&self.generics)).collect()
+    }
+
+    fn deserialize_predicates(&self) -> Vec<WherePredicate> {
+        if let Some(bound) = &self.bound {
+            return bound.clone();
+        }
+        self.contents().flat_map(|content| content.deserialize_predicates(&self.generics)).collect()
+    }
+
+    /// Return the fields of the variants that have any.
+    fn contents(&self) -> impl Iterator<Item = &Struct> {
+        self.variants.iter().filter_map(|variant| variant.content.as_ref())
     }
 
     fn regular_variants(&self) -> impl Iterator<Item = &Variant> {
@@ -40,6 +88,36 @@ impl Enum {
         self.variants.iter().filter(|variant| variant.catch_all != CatchAll::None)
     }
 
+    pub fn to_static_layout_tokens(&self) -> TokenStream {
+        let ident = &self.ident;
+        let storage_ty = &self.storage_ty;
+
+        let discriminant = match self.untagged {
+            false => quote! { <#storage_ty as #STATIC_LAYOUT_TRAIT>::LAYOUT },
+            true => quote! { #LAYOUT_TYPE::EMPTY },
+        };
+        let variants = self.variants.iter().map(|variant| match (&variant.content, self.untagged) {
+            (Some(content), false) => {
+                let content = content.layout_tokens();
+                quote! { #discriminant.then(#content).nest() }
+            }
+            (Some(content), true) => content.layout_tokens(),
+            (None, _) => discriminant.clone(),
+        });
+        let layout = variants.reduce(|lhs, rhs| quote! { #lhs.or(#rhs) }).unwrap_or(discriminant);
+
+        let mut generics = self.generics.clone();
+        generics.make_where_clause().predicates.extend(self.layout_predicates());
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #STATIC_LAYOUT_TRAIT for #ident #ty_generics #where_clause {
+                const LAYOUT: #LAYOUT_TYPE = #layout;
+            }
+        }
+    }
+
     pub fn to_pack_into_tokens(&self) -> TokenStream {
         let ident = &self.ident;
         let storage_ty = &self.storage_ty;
@@ -52,27 +130,33 @@ impl Enum {
             .into_compile_error();
         }
 
+        if self.untagged {
+            return syn::Error::new(ident.span(), "`PackInto` cannot be derived for untagged enums")
+                .into_compile_error();
+        }
+
         let regular_arms = self.regular_variants().map(|variant| {
             let ident = &variant.ident;
             let discr_expr = &variant.discriminant;
-            quote! { Self::#ident => { ((#discr_expr) as #storage_ty).pack_into(num_bits) } }
+            quote! { Self::#ident => { ::sorbit::bit::PackInto::pack_into(&((#discr_expr) as #storage_ty), num_bits) } }
         });
         let catch_all_arm = self.catch_all_variants().map(|variant| {
             let ident = &variant.ident;
             let discr_expr = &variant.discriminant;
             match &variant.catch_all {
                 CatchAll::None | CatchAll::Blanket => {
-                    quote! { Self::#ident => { ((#discr_expr) as #storage_ty).pack_into(num_bits) } }
+                    quote! { Self::#ident => { ::sorbit::bit::PackInto::pack_into(&((#discr_expr) as #storage_ty), num_bits) } }
                 }
                 CatchAll::Discriminant(member) => match member {
-                    Member::Named(ident) => quote! { Self::#ident{ #ident } => { #ident.pack_into(num_bits) } },
-                    Member::Unnamed(_) => quote! { Self::#ident(discr) => { discr.pack_into(num_bits) } },
+                    Member::Named(member) => quote! { Self::#ident{ #member } => { ::sorbit::bit::PackInto::pack_into(#member, num_bits) } },
+                    Member::Unnamed(_) => quote! { Self::#ident(discr) => { ::sorbit::bit::PackInto::pack_into(discr, num_bits) } },
                 },
             }
         });
         let arms = regular_arms.chain(catch_all_arm);
 
         quote! {
+            #[automatically_derived]
             impl<Packed> ::sorbit::bit::PackInto<Packed> for #ident
             where
                 #storage_ty: ::sorbit::bit::PackInto<Packed>,
@@ -98,6 +182,70 @@ impl Enum {
             .into_compile_error();
         }
 
+        if self.untagged {
+            return syn::Error::new(ident.span(), "`UnpackFrom` cannot be derived for untagged enums")
+                .into_compile_error();
+        }
+
+        let arms = self.discriminant_arms(quote! { ::core::result::Result::Err(value) });
+
+        quote! {
+            #[automatically_derived]
+            impl<Packed> ::sorbit::bit::UnpackFrom<Packed> for #ident
+            where
+                #storage_ty: ::sorbit::bit::UnpackFrom<Packed>,
+                Packed: ::core::clone::Clone,
+            {
+                fn unpack_from(value: Packed, num_bits: usize) -> ::core::result::Result<Self, Packed> {
+                    match <#storage_ty as ::sorbit::bit::UnpackFrom<Packed>>::unpack_from(::core::clone::Clone::clone(&value), num_bits)? {
+                        #(#arms)*
+                    }
+                }
+
+                fn unpack_error() -> ::sorbit::bit::Error {
+                    ::sorbit::bit::Error::InvalidEnumVariant
+                }
+            }
+        }
+    }
+    pub fn to_try_from_tokens(&self) -> TokenStream {
+        let ident = &self.ident;
+        let storage_ty = &self.storage_ty;
+
+        if self.variants.iter().any(|variant| variant.content.is_some()) {
+            return syn::Error::new(
+                ident.span(),
+                "`SorbitTag` cannot be derived for enums with variants that have fields",
+            )
+            .into_compile_error();
+        }
+
+        if self.untagged {
+            return syn::Error::new(ident.span(), "`SorbitTag` cannot be derived for untagged enums")
+                .into_compile_error();
+        }
+
+        let arms =
+            self.discriminant_arms(quote! { ::core::result::Result::Err(::sorbit::error::InvalidDiscriminant(value)) });
+
+        quote! {
+            #[automatically_derived]
+            impl ::core::convert::TryFrom<#storage_ty> for #ident {
+                type Error = ::sorbit::error::InvalidDiscriminant<#storage_ty>;
+
+                fn try_from(value: #storage_ty) -> ::core::result::Result<Self, Self::Error> {
+                    match value {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the match arms that convert the discriminant `n` into the
+    /// enum, and `unmatched` if no variant matches the discriminant.
+    fn discriminant_arms(&self, unmatched: TokenStream) -> Vec<TokenStream> {
+        let storage_ty = &self.storage_ty;
         let regular_arms = self.regular_variants().map(|variant| {
             let ident = &variant.ident;
             let discr_expr = &variant.discriminant;
@@ -117,24 +265,8 @@ impl Enum {
                 },
             }
         });
-        let unmatched_arm = (self.catch_all_variants().count() == 0).then(|| {
-            quote! { _ => { Err(value) } }
-        });
-        let arms = regular_arms.chain(catch_all_arm).chain(unmatched_arm);
-
-        quote! {
-            impl<Packed> ::sorbit::bit::UnpackFrom<Packed> for #ident
-            where
-                #storage_ty: ::sorbit::bit::UnpackFrom<Packed>,
-                Packed: Clone,
-            {
-                fn unpack_from(value: Packed, num_bits: usize) -> ::core::result::Result<Self, Packed> {
-                    match u8::unpack_from(value.clone(), num_bits)? {
-                        #(#arms)*
-                    }
-                }
-            }
-        }
+        let unmatched_arm = (self.catch_all_variants().count() == 0).then(|| quote! { _ => { #unmatched } });
+        regular_arms.chain(catch_all_arm).chain(unmatched_arm).collect()
     }
 }
 
@@ -143,16 +275,30 @@ impl TryFrom<parse::Enum> for Enum {
     fn try_from(mut value: parse::Enum) -> Result<Self, Self::Error> {
         let storage_ty = value.storage_ty.unwrap_or(parse_quote!(isize));
 
-        let catch_all_variants = value.variants.iter().filter(|variant| variant.catch_all != parse::CatchAll::None);
-        if let Some(repeat_catch_all) = catch_all_variants.skip(1).next() {
+        let mut catch_all_variants = value.variants.iter().filter(|variant| variant.catch_all != parse::CatchAll::None);
+        if let Some(repeat_catch_all) = catch_all_variants.nth(1) {
             return Err(syn::Error::new(
                 repeat_catch_all.ident.span(),
                 "second catch_all variant defined here is not allowed, there must be zero or one catch_all variants",
             ));
         }
 
+        if value.untagged {
+            let tagged_variant = value.variants.iter().find(|variant| variant.discriminant.is_some());
+            if let Some(variant) = tagged_variant {
+                return Err(syn::Error::new(
+                    variant.ident.span(),
+                    "variants of untagged enums cannot have a discriminant or a `tag`",
+                ));
+            }
+            let catch_all_variant = value.variants.iter().find(|variant| variant.catch_all != parse::CatchAll::None);
+            if let Some(variant) = catch_all_variant {
+                return Err(syn::Error::new(variant.ident.span(), "untagged enums cannot have a catch_all variant"));
+            }
+        }
+
         let discriminants = compute_discriminants(value.variants.iter_mut().map(|variant| variant.discriminant.take()));
-        let variants = std::iter::zip(value.variants.into_iter(), discriminants.into_iter())
+        let variants = std::iter::zip(value.variants, discriminants)
             .map(|(variant, discriminant)| -> Result<Variant, syn::Error> {
                 let catch_all = match variant.catch_all {
                     parse::CatchAll::None => CatchAll::None,
@@ -165,29 +311,42 @@ impl TryFrom<parse::Enum> for Enum {
                         }
                     }
                 };
-                let content = variant.content.map(|content| Struct::try_from(content)).transpose()?;
+                let content = variant.content.map(Struct::try_from).transpose()?;
                 Ok(Variant { ident: variant.ident, discriminant, catch_all, content })
             })
             .collect::<Result<Vec<_>, _>>()?;
-        Ok(Self { ident: value.ident, storage_ty, generics: value.generics, byte_order: value.byte_order, variants })
+        let enum_ = Self {
+            ident: value.ident,
+            storage_ty,
+            generics: value.generics,
+            bound: value.bound,
+            byte_order: value.byte_order,
+            untagged: value.untagged,
+            variants,
+        };
+        if let Some(lifetime) = enum_.borrowed_lifetime().filter(|_| enum_.untagged) {
+            return Err(syn::Error::new(lifetime.span(), "untagged enums cannot borrow from the input"));
+        }
+        Ok(enum_)
     }
 }
 
 impl ToSerializeOp for Enum {
     type Args = ();
     fn to_serialize_op(&self, region: &mut Region, _: Self::Args) -> Vec<Value> {
+        let mut generics = self.generics.clone();
+        generics.make_where_clause().predicates.extend(self.serialize_predicates());
         impl_serialize(
             region,
             self.ident.clone(),
-            self.generics.clone(),
+            generics,
             self.is_multi_pass(),
             Region::build(|region, [serializer]| {
                 let result = with_maybe_byte_order(region, serializer, self.byte_order, true, |region, serializer| {
                     let self_ = self_(region);
-                    let arms = self
-                        .variants
-                        .iter()
-                        .map(|variant| serialize_arm(&self.ident, &self.storage_ty, serializer, variant));
+                    let arms = self.variants.iter().map(|variant| {
+                        serialize_arm(&self.ident, &self.storage_ty, self.untagged, serializer, variant)
+                    });
                     match_(region, self_, arms.collect())
                 });
                 vec![result]
@@ -200,13 +359,24 @@ impl ToSerializeOp for Enum {
 impl ToDeserializeOp for Enum {
     type Args = ();
     fn to_deserialize_op(&self, region: &mut Region, _: Self::Args) -> Vec<Value> {
+        let mut generics = self.generics.clone();
+        generics.make_where_clause().predicates.extend(self.deserialize_predicates());
         impl_deserialize(
             region,
             self.ident.clone(),
-            self.generics.clone(),
+            generics,
+            self.is_seeking() || self.untagged,
+            self.borrowed_lifetime().cloned(),
             Region::build(|region, [deserializer]| {
                 let result =
                     with_maybe_byte_order(region, deserializer, self.byte_order, false, |region, deserializer| {
+                        if self.untagged {
+                            let variants = self
+                                .variants
+                                .iter()
+                                .map(|variant| (variant.ident.to_string(), deserialize_untagged(&self.ident, variant)));
+                            return try_variants(region, deserializer, variants.collect());
+                        }
                         let maybe_discriminant = deserialize_object(region, deserializer, self.storage_ty.clone());
                         let discriminant = try_(region, maybe_discriminant);
                         let normal_arms =
@@ -229,13 +399,19 @@ impl ToDeserializeOp for Enum {
 fn serialize_arm(
     self_ident: &Ident,
     storage_ty: &Type,
+    untagged: bool,
     serializer: Value,
     variant: &Variant,
 ) -> (syn::Pat, Option<Expr>, Region) {
     let pattern = serialize_arm_pattern(self_ident, variant);
     let content = variant.content.as_ref();
     let body = Region::build(move |region: &mut Region, []| {
-        if let Some(content) = content {
+        if untagged {
+            match content {
+                Some(content) => vec![content.serialize_members(region, serializer)],
+                None => vec![success(region, serializer)],
+            }
+        } else if let Some(content) = content {
             let result_comp = serialize_composite(
                 region,
                 serializer,
@@ -269,8 +445,8 @@ fn serialize_arm_pattern(self_ident: &Ident, variant: &Variant) -> Pat {
         CatchAll::Blanket => (),
         CatchAll::Discriminant(member) => pattern_members.push((member.clone(), format_ident!("discr"))),
     }
-    match &variant.content {
-        Some(content) => pattern_members.extend(content.members().iter().map(|member| {
+    if let Some(content) = &variant.content {
+        pattern_members.extend(content.members().iter().map(|member| {
             (
                 match member {
                     Member::Named(ident) => Member::Named(ident.clone()),
@@ -278,12 +454,14 @@ fn serialize_arm_pattern(self_ident: &Ident, variant: &Variant) -> Pat {
                 },
                 member_to_ident((*member).clone()),
             )
-        })),
-        None => (),
+        }))
     }
-    match pattern_members.len() {
-        0 => parse_quote!(#self_ident::#variant_ident),
-        _ => deconstruct_pattern_explicit(&parse_quote!(#self_ident::#variant_ident), pattern_members.into_iter()),
+    let rest = variant.content.as_ref().is_some_and(|content| !content.skipped.is_empty());
+    match (pattern_members.len(), rest) {
+        (0, false) => parse_quote!(#self_ident::#variant_ident),
+        _ => {
+            deconstruct_pattern_explicit(&parse_quote!(#self_ident::#variant_ident), pattern_members.into_iter(), rest)
+        }
     }
 }
 
@@ -361,15 +539,30 @@ fn deserialize_arm(self_ident: &Ident, variant: &Variant, deserializer: Value) -
     (pat, guard_expr, body)
 }
 
+/// Return the region that deserializes the variant of an untagged enum from
+/// its argument.
+fn deserialize_untagged(self_ident: &Ident, variant: &Variant) -> Region {
+    let variant_ident = variant.ident.clone();
+    let self_ident = self_ident.clone();
+    let content = variant.content.as_ref();
+    Region::build(move |region, [deserializer]| {
+        let result = match content {
+            Some(content) => {
+                use_(region, parse_quote!(#self_ident::#variant_ident));
+                content.deserialize_members(region, deserializer)
+            }
+            None => {
+                let value = struct_(region, parse_quote!(#self_ident::#variant_ident), vec![]);
+                ok(region, value)
+            }
+        };
+        vec![result]
+    })
+}
+
 fn deserialize_unmatched_arm(deserializer: Value) -> (syn::Pat, Option<Expr>, Region) {
     let pat = parse_quote!(_);
-    let body = Region::build(move |region: &mut Region, []| {
-        vec![error(
-            region,
-            deserializer,
-            "invalid enum discriminant".into(),
-        )]
-    });
+    let body = Region::build(move |region: &mut Region, []| vec![invalid_enum_variant(region, deserializer)]);
     (pat, None, body)
 }
 
@@ -438,7 +631,9 @@ mod tests {
             ident: parse_quote!(Test),
             storage_ty: parse_quote!(u16),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![
                 Variant {
                     ident: parse_quote!(A),
@@ -461,7 +656,9 @@ mod tests {
             ident: parse_quote!(Test),
             storage_ty: parse_quote!(u16),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![
                 Variant {
                     ident: parse_quote!(A),
@@ -484,7 +681,9 @@ mod tests {
             ident: parse_quote!(Test),
             storage_ty: parse_quote!(u16),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![
                 Variant {
                     ident: parse_quote!(A),
@@ -507,7 +706,9 @@ mod tests {
             ident: parse_quote!(Test),
             storage_ty: parse_quote!(u16),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![
                 Variant {
                     ident: parse_quote!(A),
@@ -530,7 +731,9 @@ mod tests {
             ident: parse_quote!(Test),
             storage_ty: parse_quote!(u16),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![
                 Variant {
                     ident: parse_quote!(A),
@@ -545,16 +748,31 @@ mod tests {
                     content: Some(Struct {
                         ident: parse_quote!(CatchAll),
                         generics: Generics::default(),
+                        bound: None,
                         byte_order: None,
                         len: None,
                         round: None,
+                        dyn_byte_order: false,
+                        transparent: false,
+                        multi_pass: false,
+                        max_depth: None,
+                        assert_size: None,
+                        fill: None,
+                        validate: None,
+                        magic: None,
                         fields: vec![Field::Direct {
                             member: parse_quote!(0),
                             ty: parse_quote!(i8),
                             multi_pass: None,
+                            deferred: false,
+                            boxed: false,
+                            borrow: false,
+                            condition: None,
                             transform: Transform::None,
                             layout_properties: Default::default(),
                         }],
+                        size_prefix: None,
+                        skipped: vec![],
                     }),
                 },
             ],
@@ -566,7 +784,9 @@ mod tests {
             ident: parse_quote!(Test),
             storage_ty: parse_quote!(u16),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![
                 Variant {
                     ident: parse_quote!(A),
@@ -581,16 +801,31 @@ mod tests {
                     content: Some(Struct {
                         ident: parse_quote!(CatchAll),
                         generics: Generics::default(),
+                        bound: None,
                         byte_order: None,
                         len: None,
                         round: None,
+                        dyn_byte_order: false,
+                        transparent: false,
+                        multi_pass: false,
+                        max_depth: None,
+                        assert_size: None,
+                        fill: None,
+                        validate: None,
+                        magic: None,
                         fields: vec![Field::Direct {
                             member: parse_quote!(b),
                             ty: parse_quote!(i8),
                             multi_pass: None,
+                            deferred: false,
+                            boxed: false,
+                            borrow: false,
+                            condition: None,
                             transform: Transform::None,
                             layout_properties: Default::default(),
                         }],
+                        size_prefix: None,
+                        skipped: vec![],
                     }),
                 },
             ],
@@ -602,7 +837,9 @@ mod tests {
             ident: parse_quote!(Test),
             storage_ty: parse_quote!(u16),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![
                 Variant {
                     ident: parse_quote!(A),
@@ -611,16 +848,31 @@ mod tests {
                     content: Some(Struct {
                         ident: parse_quote!(A),
                         generics: Generics::default(),
+                        bound: None,
                         byte_order: None,
                         len: None,
                         round: None,
+                        dyn_byte_order: false,
+                        transparent: false,
+                        multi_pass: false,
+                        max_depth: None,
+                        assert_size: None,
+                        fill: None,
+                        validate: None,
+                        magic: None,
                         fields: vec![Field::Direct {
                             member: parse_quote!(0),
                             ty: parse_quote!(u8),
                             multi_pass: None,
+                            deferred: false,
+                            boxed: false,
+                            borrow: false,
+                            condition: None,
                             transform: Transform::None,
                             layout_properties: Default::default(),
                         }],
+                        size_prefix: None,
+                        skipped: vec![],
                     }),
                 },
                 Variant {
@@ -630,16 +882,31 @@ mod tests {
                     content: Some(Struct {
                         ident: parse_quote!(B),
                         generics: Generics::default(),
+                        bound: None,
                         byte_order: None,
                         len: None,
                         round: None,
+                        dyn_byte_order: false,
+                        transparent: false,
+                        multi_pass: false,
+                        max_depth: None,
+                        assert_size: None,
+                        fill: None,
+                        validate: None,
+                        magic: None,
                         fields: vec![Field::Direct {
                             member: parse_quote!(b),
                             ty: parse_quote!(i8),
                             multi_pass: None,
+                            deferred: false,
+                            boxed: false,
+                            borrow: false,
+                            condition: None,
                             transform: Transform::None,
                             layout_properties: Default::default(),
                         }],
+                        size_prefix: None,
+                        skipped: vec![],
                     }),
                 },
             ],
@@ -689,7 +956,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_deserialize [ Test ] |%deserializer| {
+            impl_deserialize [ Test, false, none ] |%deserializer| {
                 %maybe_discriminant = deserialize_object [u16] %deserializer
                 %discriminant = try %maybe_discriminant
                 %result = match %discriminant {
@@ -704,7 +971,7 @@ mod tests {
                         yield %result_b_ok
                     }
                     _ => {
-                        %result_err = error [invalid enum discriminant] %deserializer
+                        %result_err = invalid_enum_variant %deserializer
                         yield %result_err
                     }
                 }
@@ -822,7 +1089,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_serialize [ Test, false ] |%serializer| {
+            impl_serialize [Test, , false] |%serializer| {
                 %self = self
                 %span = match %self {
                     Test :: A => {
@@ -831,17 +1098,19 @@ mod tests {
                         %result_a = serialize_object [false] %serializer, %disc_a_ref
                         yield %result_a
                     }
-                    Test :: CatchAll { 0 : discr, 1 : m0 } => {
+                    Test :: CatchAll { 0 : discr , 1 : m0 } => {
                         %result_comp_b = serialize_composite %serializer |%se_inner_b| {
                             %disc_ca = symref [discr]
                             %result_ca = serialize_object [false] %se_inner_b, %disc_ca
                             %span_ca = try %result_ca
-
                             %result_cont_b = serialize_composite %se_inner_b |%se_cont_b| {
-                                %m0 = symref [m0]
-                                %maybe_span_m0 = serialize_object [false] %se_cont_b, %m0
-
-                                %span_m0 = try %maybe_span_m0
+                                %pos = position [true] %se_cont_b
+                                %traced_0 = trace [0, true] %se_cont_b, %pos |%se_0| {
+                                    %m0 = symref [m0]
+                                    %maybe_span_m0 = serialize_object [false] %se_0, %m0
+                                    yield %maybe_span_m0
+                                }
+                                %span_m0 = try %traced_0
                                 %spans_b = tuple %span_m0
                                 %result_spans_b = ok %spans_b
                                 yield %result_spans_b
@@ -874,7 +1143,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_serialize [ Test, false ] |%serializer| {
+            impl_serialize [Test, , false] |%serializer| {
                 %self = self
                 %span = match %self {
                     Test :: A => {
@@ -883,17 +1152,19 @@ mod tests {
                         %result_a = serialize_object [false] %serializer, %disc_a_ref
                         yield %result_a
                     }
-                    Test :: CatchAll { ca : discr, b } => {
+                    Test :: CatchAll { ca : discr , b } => {
                         %result_comp_b = serialize_composite %serializer |%se_inner_b| {
                             %disc_ca = symref [discr]
                             %result_ca = serialize_object [false] %se_inner_b, %disc_ca
                             %span_ca = try %result_ca
-
                             %result_cont_b = serialize_composite %se_inner_b |%se_cont_b| {
-                                %b = symref [b]
-                                %maybe_span_b = serialize_object [false] %se_cont_b, %b
-
-                                %span_b = try %maybe_span_b
+                                %pos = position [true] %se_cont_b
+                                %traced_b = trace [b, true] %se_cont_b, %pos |%se_b| {
+                                    %b = symref [b]
+                                    %maybe_span_b = serialize_object [false] %se_b, %b
+                                    yield %maybe_span_b
+                                }
+                                %span_b = try %traced_b
                                 %spans_b = tuple %span_b
                                 %result_spans_b = ok %spans_b
                                 yield %result_spans_b
@@ -926,7 +1197,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_deserialize [ Test ] |%deserializer| {
+            impl_deserialize [ Test, false, none ] |%deserializer| {
                 %maybe_discriminant = deserialize_object [u16] %deserializer
                 %discriminant = try %maybe_discriminant
                 %result = match %discriminant {
@@ -958,7 +1229,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_deserialize [ Test ] |%deserializer| {
+            impl_deserialize [ Test, false, none ] |%deserializer| {
                 %maybe_discriminant = deserialize_object [u16] %deserializer
                 %discriminant = try %maybe_discriminant
                 %result = match %discriminant {
@@ -991,7 +1262,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_deserialize [ Test ] |%deserializer| {
+            impl_deserialize [ Test, false, none ] |%deserializer| {
                 %maybe_discriminant = deserialize_object [u16] %deserializer
                 %discriminant = try %maybe_discriminant
                 %result = match %discriminant {
@@ -1025,7 +1296,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_deserialize [ Test ] |%deserializer| {
+            impl_deserialize [Test, , false, none] |%deserializer| {
                 %maybe_discriminant = deserialize_object [u16] %deserializer
                 %discriminant = try %maybe_discriminant
                 %result = match %discriminant {
@@ -1035,13 +1306,18 @@ mod tests {
                         yield %result_a_ok
                     }
                     discr => {
-                        declare_struct [CatchAll, 0: i8 ]
-
+                        declare_struct [CatchAll, 0: i8]
                         %discr_pat = symref [discr]
-                        
                         %result_temp_struct = deserialize_composite %deserializer |%de_temp_struct| {
-                            %result_m0 = deserialize_object [i8] %de_temp_struct
-                            %m0 = try %result_m0
+                            %pos = position [false] %de_temp_struct
+                            %traced_0 = trace [0, false] %de_temp_struct, %pos |%de_0| {
+                                %result_m0 = deserialize_field [0] %de_0 |%de_field_m0| {
+                                    %result_field_m0 = deserialize_object [i8] %de_field_m0
+                                    yield %result_field_m0
+                                }
+                                yield %result_m0
+                            }
+                            %m0 = try %traced_0
                             sym [m0] %m0
                             %struct_b = struct [CatchAll, 0] %m0
                             %result_struct_b = ok %struct_b
@@ -1049,8 +1325,7 @@ mod tests {
                         }
                         %temp_struct = try %result_temp_struct
                         %temp_struct_b = member [0, false] %temp_struct
-                            
-                        %result_ca = struct [Test::CatchAll, 0, 1] %discr_pat %temp_struct_b
+                        %result_ca = struct [Test::CatchAll, 0, 1] %discr_pat, %temp_struct_b
                         %result_ca_ok = ok %result_ca
                         yield %result_ca_ok
                     }
@@ -1072,7 +1347,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_deserialize [ Test ] |%deserializer| {
+            impl_deserialize [Test, , false, none] |%deserializer| {
                 %maybe_discriminant = deserialize_object [u16] %deserializer
                 %discriminant = try %maybe_discriminant
                 %result = match %discriminant {
@@ -1082,13 +1357,18 @@ mod tests {
                         yield %result_a_ok
                     }
                     discr => {
-                        declare_struct [CatchAll, b: i8 ]
-
+                        declare_struct [CatchAll, b: i8]
                         %discr_pat = symref [discr]
-                        
                         %result_temp_struct = deserialize_composite %deserializer |%de_temp_struct| {
-                            %result_b = deserialize_object [i8] %de_temp_struct
-                            %b = try %result_b
+                            %pos = position [false] %de_temp_struct
+                            %traced_b = trace [b, false] %de_temp_struct, %pos |%de_b| {
+                                %result_b = deserialize_field [b] %de_b |%de_field_b| {
+                                    %result_field_b = deserialize_object [i8] %de_field_b
+                                    yield %result_field_b
+                                }
+                                yield %result_b
+                            }
+                            %b = try %traced_b
                             sym [b] %b
                             %struct_b = struct [CatchAll, b] %b
                             %result_struct_b = ok %struct_b
@@ -1096,8 +1376,7 @@ mod tests {
                         }
                         %temp_struct = try %result_temp_struct
                         %temp_struct_b = member [b, false] %temp_struct
-                            
-                        %result_ca = struct [Test::CatchAll, ca, b] %discr_pat %temp_struct_b
+                        %result_ca = struct [Test::CatchAll, ca, b] %discr_pat, %temp_struct_b
                         %result_ca_ok = ok %result_ca
                         yield %result_ca_ok
                     }
@@ -1119,7 +1398,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_serialize [ Test, false ] |%serializer| {
+            impl_serialize [Test, , false] |%serializer| {
                 %self = self
                 %span = match %self {
                     Test :: A { 0 : m0 } => {
@@ -1128,12 +1407,14 @@ mod tests {
                             %disc_a_ref = ref %disc_a
                             %result_disc_a = serialize_object [false] %se_inner_a, %disc_a_ref
                             %span_disc_a = try %result_disc_a
-
                             %result_cont_a = serialize_composite %se_inner_a |%se_cont_a| {
-                                %m0 = symref [m0]
-                                %maybe_span_m0 = serialize_object [false] %se_cont_a, %m0
-
-                                %span_m0 = try %maybe_span_m0
+                                %pos = position [true] %se_cont_a
+                                %traced_0 = trace [0, true] %se_cont_a, %pos |%se_0| {
+                                    %m0 = symref [m0]
+                                    %maybe_span_m0 = serialize_object [false] %se_0, %m0
+                                    yield %maybe_span_m0
+                                }
+                                %span_m0 = try %traced_0
                                 %spans_a = tuple %span_m0
                                 %result_spans_a = ok %spans_a
                                 yield %result_spans_a
@@ -1154,12 +1435,14 @@ mod tests {
                             %disc_b_ref = ref %disc_b
                             %result_disc_b = serialize_object [false] %se_inner_b, %disc_b_ref
                             %span_disc_b = try %result_disc_b
-
                             %result_cont_b = serialize_composite %se_inner_b |%se_cont_b| {
-                                %b = symref [b]
-                                %maybe_span_b = serialize_object [false] %se_cont_b, %b
-
-                                %span_b = try %maybe_span_b
+                                %pos_2 = position [true] %se_cont_b
+                                %traced_b = trace [b, true] %se_cont_b, %pos_2 |%se_b| {
+                                    %b = symref [b]
+                                    %maybe_span_b = serialize_object [false] %se_b, %b
+                                    yield %maybe_span_b
+                                }
+                                %span_b = try %traced_b
                                 %spans_b = tuple %span_b
                                 %result_spans_b = ok %spans_b
                                 yield %result_spans_b
@@ -1192,15 +1475,22 @@ mod tests {
 
         let pattern = "
         {
-            impl_deserialize [ Test ] |%deserializer| {
+            impl_deserialize [Test, , false, none] |%deserializer| {
                 %maybe_discriminant = deserialize_object [u16] %deserializer
                 %discriminant = try %maybe_discriminant
                 %result = match %discriminant {
                     discr if discr == 0 => {
                         use [Test::A]
                         %result_cont_a = deserialize_composite %deserializer |%de_cont_a| {
-                            %result_m0 = deserialize_object [u8] %de_cont_a
-                            %m0 = try %result_m0
+                            %pos = position [false] %de_cont_a
+                            %traced_0 = trace [0, false] %de_cont_a, %pos |%de_0| {
+                                %result_m0 = deserialize_field [0] %de_0 |%de_field_m0| {
+                                    %result_field_m0 = deserialize_object [u8] %de_field_m0
+                                    yield %result_field_m0
+                                }
+                                yield %result_m0
+                            }
+                            %m0 = try %traced_0
                             sym [m0] %m0
                             %struct_a = struct [A, 0] %m0
                             %result_struct_a = ok %struct_a
@@ -1211,8 +1501,15 @@ mod tests {
                     discr if discr == 1 => {
                         use [Test::B]
                         %result_cont_b = deserialize_composite %deserializer |%de_cont_b| {
-                            %result_b = deserialize_object [i8] %de_cont_b
-                            %b = try %result_b
+                            %pos_2 = position [false] %de_cont_b
+                            %traced_b = trace [b, false] %de_cont_b, %pos_2 |%de_b| {
+                                %result_b = deserialize_field [b] %de_b |%de_field_b| {
+                                    %result_field_b = deserialize_object [i8] %de_field_b
+                                    yield %result_field_b
+                                }
+                                yield %result_b
+                            }
+                            %b = try %traced_b
                             sym [b] %b
                             %struct_b = struct [B, b] %b
                             %result_struct_b = ok %struct_b
@@ -1221,7 +1518,7 @@ mod tests {
                         yield %result_cont_b
                     }
                     _ => {
-                        %result_err = error [invalid enum discriminant] %deserializer
+                        %result_err = invalid_enum_variant %deserializer
                         yield %result_err
                     }
                 }
@@ -1231,4 +1528,116 @@ mod tests {
         ";
         assert_matches!(op, pattern);
     }
+
+    #[test]
+    fn to_serialize_op_untagged() {
+        let input = Enum { untagged: true, ..create_fielded() };
+
+        let mut region = Region::new(0);
+        input.to_serialize_op(&mut region, ());
+        let op = format!("{:#}", region);
+
+        let pattern = "
+        {
+            impl_serialize [Test, , false] |%serializer| {
+                %self = self
+                %span = match %self {
+                    Test :: A { 0 : m0 } => {
+                        %result_cont_a = serialize_composite %serializer |%se_cont_a| {
+                            %pos = position [true] %se_cont_a
+                            %traced_0 = trace [0, true] %se_cont_a, %pos |%se_0| {
+                                %m0 = symref [m0]
+                                %maybe_span_m0 = serialize_object [false] %se_0, %m0
+                                yield %maybe_span_m0
+                            }
+                            %span_m0 = try %traced_0
+                            %spans_a = tuple %span_m0
+                            %result_spans_a = ok %spans_a
+                            yield %result_spans_a
+                        }
+                        %span_cont_a = try %result_cont_a
+                        %span_cont_a0 = member [0, false] %span_cont_a
+                        %result_a = ok %span_cont_a0
+                        yield %result_a
+                    }
+                    Test :: B { b } => {
+                        %result_cont_b = serialize_composite %serializer |%se_cont_b| {
+                            %pos_2 = position [true] %se_cont_b
+                            %traced_b = trace [b, true] %se_cont_b, %pos_2 |%se_b| {
+                                %b = symref [b]
+                                %maybe_span_b = serialize_object [false] %se_b, %b
+                                yield %maybe_span_b
+                            }
+                            %span_b = try %traced_b
+                            %spans_b = tuple %span_b
+                            %result_spans_b = ok %spans_b
+                            yield %result_spans_b
+                        }
+                        %span_cont_b = try %result_cont_b
+                        %span_cont_b0 = member [0, false] %span_cont_b
+                        %result_b = ok %span_cont_b0
+                        yield %result_b
+                    }
+                }
+                yield %span
+            }
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
+
+    #[test]
+    fn to_deserialize_op_untagged() {
+        let input = Enum { untagged: true, ..create_fielded() };
+
+        let mut region = Region::new(0);
+        input.to_deserialize_op(&mut region, ());
+        let op = format!("{:#}", region);
+
+        let pattern = "
+        {
+            impl_deserialize [Test, , true, none] |%deserializer| {
+                %result = try_variants [A, B] %deserializer |%de_a| {
+                    use [Test::A]
+                    %result_cont_a = deserialize_composite %de_a |%de_cont_a| {
+                        %pos = position [false] %de_cont_a
+                        %traced_0 = trace [0, false] %de_cont_a, %pos |%de_0| {
+                            %result_m0 = deserialize_field [0] %de_0 |%de_field_m0| {
+                                %result_field_m0 = deserialize_object [u8] %de_field_m0
+                                yield %result_field_m0
+                            }
+                            yield %result_m0
+                        }
+                        %m0 = try %traced_0
+                        sym [m0] %m0
+                        %struct_a = struct [A, 0] %m0
+                        %result_struct_a = ok %struct_a
+                        yield %result_struct_a
+                    }
+                    yield %result_cont_a
+                } |%de_b| {
+                    use [Test::B]
+                    %result_cont_b = deserialize_composite %de_b |%de_cont_b| {
+                        %pos_2 = position [false] %de_cont_b
+                        %traced_b = trace [b, false] %de_cont_b, %pos_2 |%de_b_2| {
+                            %result_b = deserialize_field [b] %de_b_2 |%de_field_b| {
+                                %result_field_b = deserialize_object [i8] %de_field_b
+                                yield %result_field_b
+                            }
+                            yield %result_b
+                        }
+                        %b = try %traced_b
+                        sym [b] %b
+                        %struct_b = struct [B, b] %b
+                        %result_struct_b = ok %struct_b
+                        yield %result_struct_b
+                    }
+                    yield %result_cont_b
+                }
+                yield %result
+            }
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
 }
@@ -1,4 +1,5 @@
 use proc_macro2::TokenStream;
+use quote::quote;
 use syn::DeriveInput;
 
 use crate::ir::{Region, ToDeserializeOp as _, ToSerializeOp as _};
@@ -23,6 +24,10 @@ impl Enum {
         region.to_token_stream_formatted(false)
     }
 
+    pub fn derive_static_layout(&self) -> TokenStream {
+        self.inner.to_static_layout_tokens()
+    }
+
     pub fn derive_pack_into(&self) -> TokenStream {
         self.inner.to_pack_into_tokens()
     }
@@ -30,6 +35,21 @@ impl Enum {
     pub fn derive_unpack_from(&self) -> TokenStream {
         self.inner.to_unpack_from_tokens()
     }
+
+    pub fn derive_tag(&self) -> TokenStream {
+        let serialize = self.derive_serialize();
+        let deserialize = self.derive_deserialize();
+        let try_from = self.inner.to_try_from_tokens();
+        // The regions are emitted as statements, so they are put in a block
+        // where the trailing semicolons don't end the macro's output.
+        quote! {
+            const _: () = {
+                #serialize
+                #deserialize
+                #try_from
+            };
+        }
+    }
 }
 
 impl TryFrom<DeriveInput> for Enum {
@@ -1,6 +1,9 @@
-use syn::{DeriveInput, Generics, Ident, Type, spanned::Spanned as _};
+use syn::{DeriveInput, Generics, Ident, Type, WherePredicate, spanned::Spanned as _};
 
-use crate::attribute::{ByteOrder, as_byte_order, as_type, parse_nvp_attribute_group, parse_repr_attribute, path};
+use crate::attribute::{
+    ByteOrder, as_byte_order, as_literal_bool, as_type, as_where_predicates, parse_nvp_attribute_group,
+    parse_repr_attribute, path,
+};
 use crate::r#enum::parse::Variant;
 use crate::utility::check_invalid_parameters;
 
@@ -9,7 +12,12 @@ pub struct Enum {
     pub ident: Ident,
     pub storage_ty: Option<Type>,
     pub generics: Generics,
+    /// The bounds that replace the inferred bounds of the generic parameters.
+    pub bound: Option<Vec<WherePredicate>>,
     pub byte_order: Option<ByteOrder>,
+    /// The variants are not preceded by a discriminant, and are told apart by
+    /// trying to deserialize each of them in order.
+    pub untagged: bool,
     pub variants: Vec<Variant>,
 }
 
@@ -21,7 +29,12 @@ impl TryFrom<DeriveInput> for Enum {
                 let sorbit_attrs = value.attrs.iter().filter(|attr| attr.path() == &path::sorbit_attribute());
                 let parameters = parse_nvp_attribute_group(sorbit_attrs)?;
 
-                let accepted_parameters = [path::byte_order(), path::storage_ty()];
+                let accepted_parameters = [
+                    path::byte_order(),
+                    path::storage_ty(),
+                    path::bound(),
+                    path::untagged(),
+                ];
                 check_invalid_parameters(&parameters, accepted_parameters.iter())?;
 
                 let repr = value
@@ -31,19 +44,19 @@ impl TryFrom<DeriveInput> for Enum {
                     .map(parse_repr_attribute)
                     .transpose()?
                     .flatten();
-                let byte_order = parameters.get(&path::byte_order()).map(|expr| as_byte_order(expr)).transpose()?;
-                let storage_ty = parameters.get(&path::storage_ty()).map(|expr| as_type(expr)).transpose()?;
-                let variants = data_enum
-                    .variants
-                    .into_iter()
-                    .map(|field| Variant::try_from(field))
-                    .collect::<Result<Vec<_>, _>>()?;
+                let bound = parameters.get(&path::bound()).map(as_where_predicates).transpose()?;
+                let byte_order = parameters.get(&path::byte_order()).map(as_byte_order).transpose()?;
+                let storage_ty = parameters.get(&path::storage_ty()).map(as_type).transpose()?;
+                let untagged = parameters.get(&path::untagged()).map(as_literal_bool).transpose()?.unwrap_or(false);
+                let variants = data_enum.variants.into_iter().map(Variant::try_from).collect::<Result<Vec<_>, _>>()?;
 
                 Ok(Self {
                     ident: value.ident,
                     storage_ty: storage_ty.or(repr),
                     generics: value.generics,
+                    bound,
                     byte_order,
+                    untagged,
                     variants,
                 })
             }
@@ -69,7 +82,9 @@ mod tests {
             ident: parse_quote!(Enum),
             storage_ty: None,
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![],
         };
         assert_eq!(actual, expected);
@@ -86,7 +101,9 @@ mod tests {
             ident: parse_quote!(Enum),
             storage_ty: None,
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![],
         };
         assert_eq!(actual, expected);
@@ -103,7 +120,9 @@ mod tests {
             ident: parse_quote!(Enum),
             storage_ty: Some(parse_quote!(u8)),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![],
         };
         assert_eq!(actual, expected);
@@ -120,7 +139,9 @@ mod tests {
             ident: parse_quote!(Enum),
             storage_ty: Some(parse_quote!(u8)),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![],
         };
         assert_eq!(actual, expected);
@@ -138,7 +159,9 @@ mod tests {
             ident: parse_quote!(Enum),
             storage_ty: Some(parse_quote!(u8)),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
+            untagged: false,
             variants: vec![],
         };
         assert_eq!(actual, expected);
@@ -155,7 +178,28 @@ mod tests {
             ident: parse_quote!(Enum),
             storage_ty: None,
             generics: Generics::default(),
+            bound: None,
             byte_order: Some(ByteOrder::BigEndian),
+            untagged: false,
+            variants: vec![],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn untagged() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(untagged)]
+            enum Enum {}
+        );
+        let actual = Enum::try_from(input).unwrap();
+        let expected = Enum {
+            ident: parse_quote!(Enum),
+            storage_ty: None,
+            generics: Generics::default(),
+            bound: None,
+            byte_order: None,
+            untagged: true,
             variants: vec![],
         };
         assert_eq!(actual, expected);
@@ -1,5 +1,6 @@
 use proc_macro2::Span;
 use syn::punctuated::Punctuated;
+use syn::spanned::Spanned as _;
 use syn::{Attribute, DeriveInput, Field, Fields, FieldsNamed, FieldsUnnamed, Generics, Member, Token};
 use syn::{Expr, Ident, Type};
 
@@ -23,15 +24,24 @@ impl TryFrom<syn::Variant> for Variant {
 
         let accepted_parameters = [
             path::catch_all(),
+            path::tag(),
             path::byte_order(),
             path::len(),
             path::round(),
         ];
         check_invalid_parameters(&parameters, accepted_parameters.iter())?;
 
-        let discriminant = value.discriminant.map(|(_, expr)| expr);
-        let catch_all_tag =
-            parameters.get(&path::catch_all()).map(|expr| as_literal_bool(expr)).transpose()?.unwrap_or(false);
+        let tag = parameters.get(&path::tag()).cloned();
+        let discriminant = match (value.discriminant, tag) {
+            (Some((_, discriminant)), Some(_)) => {
+                return Err(syn::Error::new(
+                    discriminant.span(),
+                    "the variant has both a discriminant and a `tag`, specify only one of them",
+                ));
+            }
+            (discriminant, tag) => tag.or(discriminant.map(|(_, expr)| expr)),
+        };
+        let catch_all_tag = parameters.get(&path::catch_all()).map(as_literal_bool).transpose()?.unwrap_or(false);
         let (catch_all, content) = if !catch_all_tag {
             parse_regular(value.ident.clone(), value.attrs, value.fields)?
         } else {
@@ -43,6 +53,7 @@ impl TryFrom<syn::Variant> for Variant {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
 pub enum CatchAll {
     None,
     Blanket,
@@ -56,9 +67,7 @@ fn parse_catch_all(
 ) -> Result<(CatchAll, Option<Struct>), syn::Error> {
     let (discr_field, rest) = pop_first_field(fields);
     let catch_all = discr_field
-        .map(|field| {
-            CatchAll::Discriminant(field.ident.map(|ident| Member::from(ident)).unwrap_or(Member::from(0)), field.ty)
-        })
+        .map(|field| CatchAll::Discriminant(field.ident.map(Member::from).unwrap_or(Member::from(0)), field.ty))
         .unwrap_or(CatchAll::Blanket);
     let content = parse_content(ident, attrs, rest)?;
     Ok((catch_all, content))
@@ -87,7 +96,11 @@ fn parse_content(ident: Ident, attrs: Vec<Attribute>, fields: Fields) -> Result<
                 semi_token: None,
             }),
         };
-        Some(Struct::try_from(input)).transpose()
+        let content = Struct::try_from(input)?;
+        if content.transparent {
+            return Err(syn::Error::new(content.ident.span(), "enum variants cannot be transparent"));
+        }
+        Ok(Some(content))
     }
 }
 
@@ -131,6 +144,31 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn tag() {
+        let input: syn::Variant = parse_quote!(
+            #[sorbit(tag = 0x03)]
+            A
+        );
+        let actual = Variant::try_from(input).unwrap();
+        let expected = Variant {
+            ident: parse_quote!(A),
+            discriminant: Some(parse_quote!(0x03)),
+            catch_all: CatchAll::None,
+            content: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn tag_and_discriminant() {
+        let input: syn::Variant = parse_quote!(
+            #[sorbit(tag = 0x03)]
+            A = 0x04
+        );
+        assert!(Variant::try_from(input).is_err());
+    }
+
     #[test]
     fn catch_all_empty() {
         let input: syn::Variant = parse_quote!(
@@ -189,16 +227,32 @@ mod tests {
             content: Some(Struct {
                 ident: parse_quote!(A),
                 generics: Generics::default(),
+                bound: None,
                 byte_order: None,
                 len: None,
                 round: None,
+                dyn_byte_order: false,
+                transparent: false,
+                multi_pass: false,
+                max_depth: None,
+                assert_size: None,
+                fill: None,
+                bit_numbering: None,
+                validate: None,
+                magic: None,
                 fields: vec![Field::Direct {
                     ident: None,
                     ty: parse_quote!(u16),
                     multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: Default::default(),
                 }],
+                size_prefix: None,
+                skipped: vec![],
             }),
         };
         assert_eq!(actual, expected);
@@ -223,16 +277,32 @@ mod tests {
             content: Some(Struct {
                 ident: parse_quote!(A),
                 generics: Generics::default(),
+                bound: None,
                 byte_order: Some(ByteOrder::BigEndian),
                 len: None,
                 round: None,
+                dyn_byte_order: false,
+                transparent: false,
+                multi_pass: false,
+                max_depth: None,
+                assert_size: None,
+                fill: None,
+                bit_numbering: None,
+                validate: None,
+                magic: None,
                 fields: vec![Field::Direct {
                     ident: Some(parse_quote!(field)),
                     ty: parse_quote!(u16),
                     multi_pass: Some(true),
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: Default::default(),
                 }],
+                size_prefix: None,
+                skipped: vec![],
             }),
         };
         assert_eq!(actual, expected);
@@ -268,16 +338,32 @@ mod tests {
             content: Some(Struct {
                 ident: parse_quote!(A),
                 generics: Generics::default(),
+                bound: None,
                 byte_order: None,
                 len: Some(12),
                 round: None,
+                dyn_byte_order: false,
+                transparent: false,
+                multi_pass: false,
+                max_depth: None,
+                assert_size: None,
+                fill: None,
+                bit_numbering: None,
+                validate: None,
+                magic: None,
                 fields: vec![Field::Direct {
                     ident: parse_quote!(a),
                     ty: parse_quote!(u8),
                     multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: FieldLayoutProperties { offset: Some(2), ..Default::default() },
                 }],
+                size_prefix: None,
+                skipped: vec![],
             }),
         };
         assert_eq!(actual, expected);
@@ -2,7 +2,9 @@ use std::fmt::Display;
 
 use quote::ToTokens;
 
-use crate::attribute::{BitNumbering, ByteOrder};
+use crate::attribute::{
+    BitNumbering, ByteOrder, ChecksumAlgorithm, OptionEncoding, PointerBase, SizePrefix, StringEncoding, TimeUnit,
+};
 
 pub trait Attribute {
     fn display(&self) -> String;
@@ -54,6 +56,15 @@ impl<T: Display> Attribute for std::ops::Range<T> {
     }
 }
 
+impl<T: Attribute> Attribute for Option<T> {
+    fn display(&self) -> String {
+        match self {
+            Some(value) => value.display(),
+            None => String::from("none"),
+        }
+    }
+}
+
 impl Attribute for syn::Member {
     fn display(&self) -> String {
         match self {
@@ -82,7 +93,15 @@ impl_attribute_for_display!(u8);
 impl_attribute_for_display!(u16);
 impl_attribute_for_display!(u32);
 impl_attribute_for_display!(u64);
+impl_attribute_for_display!(usize);
 impl_attribute_for_display!(BitNumbering);
 impl_attribute_for_display!(ByteOrder);
+impl_attribute_for_display!(TimeUnit);
+impl_attribute_for_display!(SizePrefix);
+impl_attribute_for_display!(StringEncoding);
+impl_attribute_for_display!(OptionEncoding);
+impl_attribute_for_display!(ChecksumAlgorithm);
+impl_attribute_for_display!(PointerBase);
 impl_attribute_for_display!(String);
 impl_attribute_for_display!(syn::Ident);
+impl_attribute_for_display!(syn::Lifetime);
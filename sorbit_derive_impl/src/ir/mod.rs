@@ -1,6 +1,8 @@
 mod attribute;
 pub mod operation;
 #[cfg(test)]
+pub mod parse;
+#[cfg(test)]
 pub mod pattern_match;
 mod region;
 mod value;
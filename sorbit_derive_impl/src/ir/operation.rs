@@ -13,31 +13,42 @@ pub trait Operation: ToTokens {
     fn regions(&self) -> Vec<&Region>;
     fn attributes(&self) -> Vec<String>;
     fn to_string(&self, alternate: bool) -> String {
-        let outputs = self.outputs().iter().map(|output| format!("{output}")).collect::<Vec<_>>().join(", ");
-        let inputs = self.inputs().iter().map(|input| format!("{input}")).collect::<Vec<_>>().join(", ");
-        let attributes = self.attributes().join(", ");
         let regions = self
             .regions()
             .iter()
             .map(|region| if alternate { format!("{region:#}") } else { format!("{region}") })
-            .collect::<Vec<_>>()
-            .join(" ");
-        let mut s = String::new();
-        if !outputs.is_empty() {
-            s.push_str(&format!("{outputs} = "));
-        };
-        s.push_str(self.name());
-        if !attributes.is_empty() {
-            s.push_str(&format!(" [{attributes}]"));
-        };
-        if !inputs.is_empty() {
-            s.push_str(&format!(" {inputs}"));
-        };
-        if !regions.is_empty() {
-            s.push_str(&format!(" {regions}"));
-        }
-        s
+            .collect::<Vec<_>>();
+        format_operation(self.name(), &self.outputs(), &self.inputs(), &self.attributes(), &regions)
+    }
+}
+
+/// Print an operation from its parts, with its regions already printed.
+pub fn format_operation(
+    name: &str,
+    outputs: &[Value],
+    inputs: &[Value],
+    attributes: &[String],
+    regions: &[String],
+) -> String {
+    let outputs = outputs.iter().map(|output| format!("{output}")).collect::<Vec<_>>().join(", ");
+    let inputs = inputs.iter().map(|input| format!("{input}")).collect::<Vec<_>>().join(", ");
+    let attributes = attributes.join(", ");
+    let regions = regions.join(" ");
+    let mut s = String::new();
+    if !outputs.is_empty() {
+        s.push_str(&format!("{outputs} = "));
+    };
+    s.push_str(name);
+    if !attributes.is_empty() {
+        s.push_str(&format!(" [{attributes}]"));
+    };
+    if !inputs.is_empty() {
+        s.push_str(&format!(" {inputs}"));
+    };
+    if !regions.is_empty() {
+        s.push_str(&format!(" {regions}"));
     }
+    s
 }
 
 macro_rules! value {
@@ -47,11 +58,13 @@ macro_rules! value {
 }
 
 pub trait IntoValueTuple<Tuple> {
+    #[allow(clippy::wrong_self_convention)]
     fn into_value_tuple(&self) -> Tuple;
 }
 
 macro_rules! impl_into_value_tuple {
     ($tuple:ty, $($indices:expr),*) => {
+        #[allow(clippy::unused_unit)]
         impl<C> IntoValueTuple<$tuple> for C
             where Self: Index<usize, Output = Value>
         {
@@ -0,0 +1,377 @@
+#![allow(unused)]
+
+use std::collections::HashMap;
+
+use crate::ir::Value;
+use crate::ir::operation::format_operation;
+use crate::ir::region::format_region;
+
+/// Parse the textual form of a region, as printed by its [`Display`](std::fmt::Display) impl.
+///
+/// The operations are reconstructed as [`ParsedOperation`]s, which only carry
+/// the structure of the IR: names, values, regions, and attributes as text.
+/// Printing the parsed region again reproduces the original text, except for
+/// the numbering of the values. Parsed regions are not [`Region`](crate::ir::Region)s,
+/// so they can never be lowered into code.
+pub fn parse_region(text: impl AsRef<str>) -> Result<ParsedRegion, String> {
+    let tokens = tokenize(text.as_ref())?;
+    let mut parser = Parser { tokens: &tokens, pos: 0, values: HashMap::new() };
+    let region = parser.region()?;
+    parser.skip_newlines();
+    match parser.peek() {
+        None => Ok(region),
+        Some(token) => Err(format!("unexpected `{token}` after the region")),
+    }
+}
+
+/// A region reconstructed from its textual form.
+pub struct ParsedRegion {
+    arguments: Vec<Value>,
+    operations: Vec<ParsedOperation>,
+}
+
+impl std::fmt::Display for ParsedRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operations: Vec<_> = self.operations.iter().map(|operation| operation.to_string(f.alternate())).collect();
+        format_region(f, &self.arguments, &operations)
+    }
+}
+
+/// An operation reconstructed from its textual form.
+pub struct ParsedOperation {
+    name: String,
+    inputs: Vec<Value>,
+    outputs: Vec<Value>,
+    attributes: Vec<String>,
+    regions: Vec<ParsedRegion>,
+}
+
+impl ParsedOperation {
+    fn is_terminator(&self) -> bool {
+        self.name == "yield"
+    }
+
+    fn to_string(&self, alternate: bool) -> String {
+        let regions: Vec<_> = self
+            .regions
+            .iter()
+            .map(|region| if alternate { format!("{region:#}") } else { format!("{region}") })
+            .collect();
+        format_operation(&self.name, &self.outputs, &self.inputs, &self.attributes, &regions)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Newline,
+    Pipe,
+    Comma,
+    Equals,
+    BraceOpen,
+    BraceClose,
+    Attributes(Vec<String>),
+    Symbol(String),
+    Value(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Newline => write!(f, "\\n"),
+            Token::Pipe => write!(f, "|"),
+            Token::Comma => write!(f, ","),
+            Token::Equals => write!(f, "="),
+            Token::BraceOpen => write!(f, "{{"),
+            Token::BraceClose => write!(f, "}}"),
+            Token::Attributes(attributes) => write!(f, "[{}]", attributes.join(", ")),
+            Token::Symbol(s) => write!(f, "{s}"),
+            Token::Value(s) => write!(f, "%{s}"),
+        }
+    }
+}
+
+struct Parser<'tokens> {
+    tokens: &'tokens [Token],
+    pos: usize,
+    values: HashMap<String, Value>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if *token == expected => Ok(()),
+            Some(token) => Err(format!("expected `{expected}`, found `{token}`")),
+            None => Err(format!("expected `{expected}`, found end of input")),
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.peek() == Some(&Token::Newline) {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self, name: &str) -> Value {
+        *self.values.entry(name.into()).or_insert_with(Value::new)
+    }
+
+    fn value_list(&mut self) -> Result<Vec<Value>, String> {
+        let mut values = Vec::new();
+        while let Some(Token::Value(name)) = self.peek() {
+            let name = name.clone();
+            values.push(self.value(&name));
+            self.pos += 1;
+            if self.peek() != Some(&Token::Comma) {
+                break;
+            }
+            self.pos += 1;
+        }
+        Ok(values)
+    }
+
+    fn has_outputs(&self) -> bool {
+        self.tokens[self.pos..]
+            .iter()
+            .find(|token| !matches!(token, Token::Value(_) | Token::Comma))
+            .is_some_and(|token| *token == Token::Equals)
+    }
+
+    fn region(&mut self) -> Result<ParsedRegion, String> {
+        self.skip_newlines();
+        let argument_names = match self.peek() {
+            Some(Token::Pipe) => {
+                self.pos += 1;
+                let start = self.pos;
+                self.value_list()?;
+                self.expect(Token::Pipe)?;
+                self.tokens[start..self.pos - 1]
+                    .iter()
+                    .filter_map(|token| match token {
+                        Token::Value(name) => Some(name.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let arguments: Vec<_> = argument_names
+            .into_iter()
+            .map(|name| *self.values.entry(name).insert_entry(Value::new()).get())
+            .collect();
+        let mut region = ParsedRegion { arguments, operations: Vec::new() };
+        self.expect(Token::BraceOpen)?;
+        let mut terminated = false;
+        loop {
+            self.skip_newlines();
+            match self.peek() {
+                Some(Token::BraceClose) => {
+                    self.pos += 1;
+                    return Ok(region);
+                }
+                Some(_) if terminated => return Err("operation after the terminator of the region".into()),
+                Some(_) => {
+                    let operation = self.operation()?;
+                    terminated = operation.is_terminator();
+                    region.operations.push(operation);
+                }
+                None => return Err("expected `}`, found end of input".into()),
+            }
+        }
+    }
+
+    fn operation(&mut self) -> Result<ParsedOperation, String> {
+        let outputs = match self.has_outputs() {
+            true => {
+                let outputs = self.value_list()?;
+                self.expect(Token::Equals)?;
+                outputs
+            }
+            false => Vec::new(),
+        };
+        let name = match self.next() {
+            Some(Token::Symbol(name)) => name.clone(),
+            Some(token) => return Err(format!("expected operation name, found `{token}`")),
+            None => return Err("expected operation name, found end of input".into()),
+        };
+        let attributes = match self.peek() {
+            Some(Token::Attributes(attributes)) => {
+                let attributes = attributes.clone();
+                self.pos += 1;
+                attributes
+            }
+            _ => Vec::new(),
+        };
+        let inputs = self.value_list()?;
+        let mut regions = Vec::new();
+        while matches!(self.peek(), Some(Token::Pipe | Token::BraceOpen)) {
+            regions.push(self.region()?);
+        }
+        match self.peek() {
+            None | Some(Token::Newline | Token::BraceClose) => {
+                Ok(ParsedOperation { name, inputs, outputs, attributes, regions })
+            }
+            Some(token) => Err(format!("unexpected `{token}` in operation `{name}`")),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '\n' => tokens.push(Token::Newline),
+            ' ' | '\t' | '\r' => (),
+            '|' => tokens.push(Token::Pipe),
+            ',' => tokens.push(Token::Comma),
+            '=' => tokens.push(Token::Equals),
+            '{' => tokens.push(Token::BraceOpen),
+            '}' => tokens.push(Token::BraceClose),
+            '[' => tokens.push(Token::Attributes(attributes(&mut chars)?)),
+            '%' => {
+                let mut name = String::new();
+                while let Some(ch) = chars.next_if(|ch| ch.is_alphanumeric() || *ch == '_') {
+                    name.push(ch);
+                }
+                if name.is_empty() {
+                    return Err("expected value name after `%`".into());
+                }
+                tokens.push(Token::Value(name));
+            }
+            ch => {
+                let mut symbol = String::from(ch);
+                while let Some(ch) = chars.next_if(|ch| !ch.is_whitespace() && !"|,={}[%".contains(*ch)) {
+                    symbol.push(ch);
+                }
+                tokens.push(Token::Symbol(symbol));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Read the attribute list up to the closing bracket, and split it at the
+/// top-level commas.
+fn attributes(chars: &mut impl Iterator<Item = char>) -> Result<Vec<String>, String> {
+    let mut attributes = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for ch in chars.by_ref() {
+        match ch {
+            _ if in_string => {
+                in_string = escaped || ch != '"';
+                escaped = !escaped && ch == '\\';
+            }
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ']' if depth == 0 => {
+                if !current.trim().is_empty() || !attributes.is_empty() {
+                    attributes.push(current.trim().into());
+                }
+                return Ok(attributes);
+            }
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                attributes.push(current.trim().into());
+                current.clear();
+                continue;
+            }
+            _ => (),
+        }
+        current.push(ch);
+    }
+    Err("expected `]`, found end of input".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::Region;
+    use crate::ir::pattern_match::assert_matches;
+
+    #[test]
+    fn empty_region() {
+        let region = parse_region("{\n}").unwrap();
+        assert_eq!(format!("{region}"), "{\n\n}");
+    }
+
+    #[test]
+    fn operations() {
+        let text = "
+        |%se| {
+            %foo = symref [foo]
+            %res = serialize_object [false] %se, %foo
+            yield %res
+        }
+        ";
+        let region = parse_region(text).unwrap();
+        assert_matches!(format!("{region:#}"), text);
+    }
+
+    #[test]
+    fn nested_regions() {
+        let text = "
+        {
+            %res = byte_order [BigEndian, true] %serializer |%se_inner| {
+                %foo = symref [foo]
+                %res_inner = serialize_object [false] %se_inner, %foo
+                yield %res_inner
+            }
+            %a, %b = branch %res {
+                yield %res
+            } {
+                yield
+            }
+            yield %res
+        }
+        ";
+        let region = parse_region(text).unwrap();
+        assert_matches!(format!("{region:#}"), text);
+    }
+
+    #[test]
+    fn attributes() {
+        let text = "{\n    %x = custom_expr [f(a, b), [u8; 4], \"],\"]\n    yield %x\n}";
+        let region = parse_region(text).unwrap();
+        assert_matches!(format!("{region:#}"), text);
+    }
+
+    #[test]
+    fn round_trip_generated() {
+        let region = Region::build(|region, [se]| {
+            let foo = crate::ops::symref(region, syn::parse_quote!(foo));
+            vec![crate::ops::serialize_object(region, se, foo, false)]
+        });
+        let text = format!("{region:#}");
+        assert_matches!(format!("{:#}", parse_region(&text).unwrap()), text);
+    }
+
+    #[test]
+    fn operation_after_terminator() {
+        let text = "{\n    yield\n    %a = self\n}";
+        assert!(parse_region(text).is_err());
+    }
+
+    #[test]
+    fn unclosed_region() {
+        assert!(parse_region("{\n    %a = self\n").is_err());
+    }
+
+    #[test]
+    fn invalid_operation() {
+        assert!(parse_region("{\n    %a = \n}").is_err());
+        assert!(parse_region("{\n    %a = self = \n}").is_err());
+    }
+}
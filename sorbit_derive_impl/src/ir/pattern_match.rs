@@ -33,7 +33,7 @@ pub fn matches(operation: impl AsRef<str>, pattern: impl AsRef<str>) -> Result<(
             (Value(o), Value(p)) => {
                 if sym_pat_to_op.get(p) == Some(&o) && sym_op_to_pat.get(o) == Some(&p) {
                     Ok(())
-                } else if sym_pat_to_op.get(p) == None && sym_op_to_pat.get(o) == None {
+                } else if !sym_pat_to_op.contains_key(p) && !sym_op_to_pat.contains_key(o) {
                     sym_pat_to_op.insert(p, o);
                     sym_op_to_pat.insert(o, p);
                     Ok(())
@@ -51,8 +51,8 @@ pub fn matches(operation: impl AsRef<str>, pattern: impl AsRef<str>) -> Result<(
             let following_pat_section =
                 pat_tokens[token_idx + 1..std::cmp::min(pat_tokens.len(), token_idx + 6)].iter().join(" ");
             let found = op_tokens[token_idx].to_string();
-            let padding = String::from_iter(std::iter::repeat(' ').take(matching_section.len()));
-            let caret = String::from_iter(std::iter::repeat('^').take(found.len()));
+            let padding = String::from_iter(std::iter::repeat_n(' ', matching_section.len()));
+            let caret = String::from_iter(std::iter::repeat_n('^', found.len()));
             let message = format!(
                 "operation does not match pattern:\n   expected: {matching_section} {expected} {following_pat_section}\n   found:    {matching_section} {found} {following_op_section}\n             {padding} {caret}",
             );
@@ -63,24 +63,22 @@ impl Region {
 
 impl std::fmt::Display for Region {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let arguments = self.arguments.iter().map(|arg| format!("{arg}")).collect::<Vec<_>>().join(", ");
-        let prefix = if f.alternate() { "    " } else { "" };
-        let operations = textwrap::indent(
-            &self
-                .operations
-                .iter()
-                .map(|operation| operation.to_string(f.alternate()))
-                .collect::<Vec<_>>()
-                .join("\n"),
-            prefix,
-        );
-        if !arguments.is_empty() {
-            write!(f, "|{arguments}| ")?;
-        };
-        write!(f, "{{\n{operations}\n}}")
+        let operations: Vec<_> = self.operations.iter().map(|operation| operation.to_string(f.alternate())).collect();
+        format_region(f, &self.arguments, &operations)
     }
 }
 
+/// Print a region from its arguments and its already printed operations.
+pub fn format_region(f: &mut std::fmt::Formatter<'_>, arguments: &[Value], operations: &[String]) -> std::fmt::Result {
+    let arguments = arguments.iter().map(|arg| format!("{arg}")).collect::<Vec<_>>().join(", ");
+    let prefix = if f.alternate() { "    " } else { "" };
+    let operations = textwrap::indent(&operations.join("\n"), prefix);
+    if !arguments.is_empty() {
+        write!(f, "|{arguments}| ")?;
+    };
+    write!(f, "{{\n{operations}\n}}")
+}
+
 impl ToTokens for Region {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.extend(self.to_token_stream_formatted(true));
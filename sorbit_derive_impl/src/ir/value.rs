@@ -34,6 +34,7 @@ impl Value {
         Self { id: Id::new() }
     }
 
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_ident(&self) -> syn::Ident {
         format_ident!("v{}", self.id.0)
     }
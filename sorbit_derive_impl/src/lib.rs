@@ -5,13 +5,13 @@ mod ops;
 mod r#struct;
 mod utility;
 
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::TokenStream;
 use syn::DeriveInput;
-use syn::spanned::Spanned;
 
 use r#enum::Enum;
 use r#struct::Struct;
 
+#[allow(clippy::large_enum_variant)]
 pub enum DeriveObject {
     Struct(Struct),
     Enum(Enum),
@@ -22,7 +22,7 @@ impl DeriveObject {
         match &input.data {
             syn::Data::Struct(_) => Ok(Self::Struct(Struct::try_from(input)?)),
             syn::Data::Enum(_) => Ok(Self::Enum(Enum::try_from(input)?)),
-            syn::Data::Union(_) => Err(syn::Error::new(input.span(), "unions are not supported")),
+            syn::Data::Union(_) => Err(syn::Error::new(input.ident.span(), "unions are not supported")),
         }
     }
 
@@ -40,10 +40,17 @@ impl DeriveObject {
         }
     }
 
+    pub fn derive_static_layout(&self) -> TokenStream {
+        match self {
+            DeriveObject::Struct(item) => item.derive_static_layout(),
+            DeriveObject::Enum(item) => item.derive_static_layout(),
+        }
+    }
+
     pub fn derive_pack_into(&self) -> TokenStream {
         match self {
-            DeriveObject::Struct(_) => {
-                syn::Error::new(Span::call_site(), "PackInto can only be derived for enums").into_compile_error()
+            DeriveObject::Struct(item) => {
+                syn::Error::new(item.ident().span(), "PackInto can only be derived for enums").into_compile_error()
             }
             DeriveObject::Enum(item) => item.derive_pack_into(),
         }
@@ -51,10 +58,19 @@ impl DeriveObject {
 
     pub fn derive_unpack_from(&self) -> TokenStream {
         match self {
-            DeriveObject::Struct(_) => {
-                syn::Error::new(Span::call_site(), "UnpackFrom can only be derived for enums").into_compile_error()
+            DeriveObject::Struct(item) => {
+                syn::Error::new(item.ident().span(), "UnpackFrom can only be derived for enums").into_compile_error()
             }
             DeriveObject::Enum(item) => item.derive_unpack_from(),
         }
     }
+
+    pub fn derive_tag(&self) -> TokenStream {
+        match self {
+            DeriveObject::Struct(item) => {
+                syn::Error::new(item.ident().span(), "SorbitTag can only be derived for enums").into_compile_error()
+            }
+            DeriveObject::Enum(item) => item.derive_tag(),
+        }
+    }
 }
@@ -1,6 +1,11 @@
-use crate::attribute::ByteOrder;
+use syn::{Expr, LitByteStr, parse_quote};
+
+use crate::attribute::{Backward, ByteOrder, SizePrefix};
 use crate::ir::{Region, Value};
-use crate::ops::{self as ops, align, deserialize_composite, member, ok, pad, serialize_composite, try_};
+use crate::ops::{
+    self as ops, align, custom_expr, deserialize_composite, match_, member, ok, overlay, pad, seek, serialize_at,
+    serialize_composite, success, try_,
+};
 
 pub fn with_maybe_offset(region: &mut Region, serializer: Value, offset: Option<u64>, serializing: bool) {
     if let Some(offset) = offset {
@@ -9,6 +14,31 @@ pub fn with_maybe_offset(region: &mut Region, serializer: Value, offset: Option<
     }
 }
 
+/// Write or verify the `magic` bytes, if any.
+pub fn with_maybe_magic(region: &mut Region, serializer: Value, magic: Option<&LitByteStr>, serializing: bool) {
+    if let Some(bytes) = magic {
+        let maybe_magic = ops::magic(region, serializer, parse_quote!(#bytes), serializing);
+        let _ = try_(region, maybe_magic);
+    }
+}
+
+/// Pad or seek to the `offset` of a field.
+pub fn with_maybe_field_offset(
+    region: &mut Region,
+    serializer: Value,
+    offset: Option<u64>,
+    allow_backward: Option<Backward>,
+    serializing: bool,
+) {
+    if let Some(offset) = offset {
+        let maybe_offset = match (allow_backward, serializing) {
+            (Some(Backward::Seek), false) => seek(region, serializer, offset),
+            _ => pad(region, serializer, offset, serializing),
+        };
+        let _ = try_(region, maybe_offset);
+    }
+}
+
 pub fn with_maybe_alignment(region: &mut Region, serializer: Value, align: Option<u64>, serializing: bool) {
     if let Some(align) = align {
         let align = ops::align(region, serializer, align, serializing);
@@ -16,6 +46,13 @@ pub fn with_maybe_alignment(region: &mut Region, serializer: Value, align: Optio
     }
 }
 
+pub fn with_maybe_absolute_alignment(region: &mut Region, serializer: Value, align: Option<u64>, serializing: bool) {
+    if let Some(align) = align {
+        let align = ops::align_absolute(region, serializer, align, serializing);
+        let _ = try_(region, align);
+    }
+}
+
 pub fn with_maybe_rounding(
     region: &mut Region,
     serializer: Value,
@@ -26,7 +63,7 @@ pub fn with_maybe_rounding(
     if let Some(round) = round {
         let composite_body = Region::build(|region: &mut Region, [deserializer]| {
             let maybe_deserialized = body(region, deserializer);
-            let maybe_round = align(region, deserializer.clone(), round, is_serializing);
+            let maybe_round = align(region, deserializer, round, is_serializing);
             let _ = try_(region, maybe_round);
             vec![maybe_deserialized]
         });
@@ -63,21 +100,123 @@ pub fn with_maybe_byte_order(
     }
 }
 
+pub fn with_maybe_fill(
+    region: &mut Region,
+    serializer: Value,
+    fill: Option<u8>,
+    is_serializing: bool,
+    body: impl FnOnce(&mut Region, Value) -> Value,
+) -> Value {
+    match fill {
+        Some(fill) => ops::fill(
+            region,
+            serializer,
+            fill,
+            is_serializing,
+            Region::build(|region, [serializer]| vec![body(region, serializer)]),
+        ),
+        None => (body)(region, serializer),
+    }
+}
+
+pub fn with_maybe_size_prefix(
+    region: &mut Region,
+    serializer: Value,
+    size_prefix: Option<&SizePrefix>,
+    is_serializing: bool,
+    body: impl FnOnce(&mut Region, Value) -> Value,
+) -> Value {
+    match size_prefix {
+        Some(size_prefix) => ops::size_prefixed(
+            region,
+            serializer,
+            size_prefix.clone(),
+            is_serializing,
+            Region::build(|region, [serializer]| vec![body(region, serializer)]),
+        ),
+        None => (body)(region, serializer),
+    }
+}
+
+/// Run `body` only if the `condition` holds.
+///
+/// When the condition does not hold, nothing is serialized, and the default
+/// value is deserialized.
+pub fn with_maybe_condition(
+    region: &mut Region,
+    serializer: Value,
+    condition: Option<&Expr>,
+    is_serializing: bool,
+    body: impl FnOnce(&mut Region, Value) -> Value,
+) -> Value {
+    match condition {
+        Some(condition) => {
+            let condition = custom_expr(region, condition.clone());
+            let present = Region::build(|region, []| vec![body(region, serializer)]);
+            let absent = Region::build(|region, []| match is_serializing {
+                true => vec![success(region, serializer)],
+                false => {
+                    let default = custom_expr(region, parse_quote!(::core::default::Default::default()));
+                    vec![ok(region, default)]
+                }
+            });
+            match_(
+                region,
+                condition,
+                vec![
+                    (parse_quote!(true), None, present),
+                    (parse_quote!(false), None, absent),
+                ],
+            )
+        }
+        None => (body)(region, serializer),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn with_field_layout(
     region: &mut Region,
     serializer: Value,
     is_serializing: bool,
     byte_order: Option<ByteOrder>,
     offset: Option<u64>,
+    allow_backward: Option<Backward>,
     align: Option<u64>,
+    align_absolute: Option<u64>,
     round: Option<u64>,
+    size_prefix: Option<&SizePrefix>,
+    condition: Option<&Expr>,
     body: impl FnOnce(&mut Region, Value) -> Value,
 ) -> Value {
-    with_maybe_offset(region, serializer.clone(), offset, is_serializing);
-    with_maybe_alignment(region, serializer.clone(), align, is_serializing);
-    with_maybe_rounding(region, serializer, round, is_serializing, |region, serializer| {
-        with_maybe_byte_order(region, serializer, byte_order, is_serializing, |region, serializer| {
-            body(region, serializer)
+    let field_body = |region: &mut Region, serializer: Value| {
+        with_maybe_alignment(region, serializer, align, is_serializing);
+        with_maybe_absolute_alignment(region, serializer, align_absolute, is_serializing);
+        with_maybe_condition(region, serializer, condition, is_serializing, |region, serializer| {
+            with_maybe_rounding(region, serializer, round, is_serializing, |region, serializer| {
+                with_maybe_byte_order(region, serializer, byte_order, is_serializing, |region, serializer| {
+                    with_maybe_size_prefix(region, serializer, size_prefix, is_serializing, body)
+                })
+            })
         })
-    })
+    };
+    match (allow_backward, offset) {
+        // Overlays only view the bytes of other fields, so they are not serialized.
+        (Some(Backward::Overlay), Some(_)) if is_serializing => success(region, serializer),
+        (Some(Backward::Reorder), Some(offset)) if is_serializing => serialize_at(
+            region,
+            serializer,
+            offset,
+            Region::build(|region, [serializer]| vec![field_body(region, serializer)]),
+        ),
+        (Some(Backward::Overlay | Backward::Reorder), Some(offset)) => overlay(
+            region,
+            serializer,
+            offset,
+            Region::build(|region, [deserializer]| vec![field_body(region, deserializer)]),
+        ),
+        _ => {
+            with_maybe_field_offset(region, serializer, offset, allow_backward, is_serializing);
+            field_body(region, serializer)
+        }
+    }
 }
@@ -4,6 +4,17 @@ use quote::{ToTokens, quote};
 use crate::attribute::BitNumbering;
 use crate::ir::op;
 use crate::ops::constants::BIT_FIELD_TYPE;
+use crate::utility::byte_array_len;
+
+/// Return the runtime bit field type for the storage type `packed_ty`.
+///
+/// Byte arrays use a dedicated bit field type, as they are not integers.
+pub fn bit_field_type(packed_ty: &syn::Type) -> TokenStream {
+    match byte_array_len(packed_ty) {
+        Some(len) => quote! { ::sorbit::bit::ByteArrayBitField<#len> },
+        None => quote! { #BIT_FIELD_TYPE<#packed_ty> },
+    }
+}
 
 op!(
     name: "empty_bit_field",
@@ -18,8 +29,8 @@ op!(
 
 impl ToTokens for EmptyBitFieldOp {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let packed_ty = &self.packed_ty;
-        tokens.extend(quote! { #BIT_FIELD_TYPE::<#packed_ty>::new() })
+        let bit_field_ty = bit_field_type(&self.packed_ty);
+        tokens.extend(quote! { <#bit_field_ty>::new() })
     }
 }
 
@@ -75,11 +86,10 @@ impl ToTokens for UnpackBitFieldOp {
 }
 
 fn bit_range_to_token_stream(bit_field: impl ToTokens, start: u8, end: u8, bit_numbering: BitNumbering) -> TokenStream {
-    let bit_range = match bit_numbering {
+    match bit_numbering {
         BitNumbering::MSB0 => {
             quote! { (#bit_field.bit_size_of() as u8 - #end)..(#bit_field.bit_size_of() as u8 - #start) }
         }
         BitNumbering::LSB0 => quote! { #start..#end },
-    };
-    bit_range
+    }
 }
@@ -0,0 +1,65 @@
+use crate::ir::op;
+use crate::ops::constants::DESERIALIZE_BORROWED_TRAIT;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "deserialize_borrowed_object",
+    builder: deserialize_borrowed_object,
+    op: DeserializeBorrowedObjectOp,
+    inputs: {deserializer},
+    outputs: {deserialized_object},
+    attributes: {ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeBorrowedObjectOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let ty = &self.ty;
+        tokens.extend(quote! { <#ty as #DESERIALIZE_BORROWED_TRAIT<'_>>::deserialize_borrowed(#deserializer) })
+    }
+}
+
+op!(
+    name: "deserialize_borrowed_by_len",
+    builder: deserialize_borrowed_by_len,
+    op: DeserializeBorrowedByLenOp,
+    inputs: {deserializer, len},
+    outputs: {borrowed},
+    attributes: {ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeBorrowedByLenOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let len = &self.len;
+        let ty = &self.ty;
+        tokens.extend(quote! { ::sorbit::borrow::deserialize_borrowed_by_len::<#ty, _, _>(#deserializer, #len) })
+    }
+}
+
+op!(
+    name: "deserialize_borrowed_len_prefixed",
+    builder: deserialize_borrowed_len_prefixed,
+    op: DeserializeBorrowedLenPrefixedOp,
+    inputs: {deserializer},
+    outputs: {borrowed},
+    attributes: {ty: syn::Type, len_ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeBorrowedLenPrefixedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let ty = &self.ty;
+        let len_ty = &self.len_ty;
+        tokens.extend(quote! {
+            ::sorbit::borrow::deserialize_borrowed_len_prefixed::<#ty, _, #len_ty>(#deserializer)
+        })
+    }
+}
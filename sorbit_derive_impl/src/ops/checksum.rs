@@ -0,0 +1,85 @@
+use crate::attribute::ChecksumAlgorithm;
+use crate::ir::op;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "serialize_checksum",
+    builder: serialize_checksum,
+    op: SerializeChecksumOp,
+    inputs: {serializer, sections},
+    outputs: {serialized_checksum},
+    attributes: {algorithm: ChecksumAlgorithm, num_sections: usize},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for SerializeChecksumOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let sections = &self.sections;
+        let algorithm = checksum_algorithm_tokens(self.algorithm);
+        let indices = (0..self.num_sections).map(syn::Index::from);
+        tokens.extend(quote! {
+            ::sorbit::checksum::serialize_checksum::<#algorithm, _>(#serializer, &[#(#sections.#indices),*])
+        })
+    }
+}
+
+op!(
+    name: "deserialize_recorded",
+    builder: deserialize_recorded,
+    op: DeserializeRecordedOp,
+    inputs: {deserializer},
+    outputs: {result},
+    attributes: {},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for DeserializeRecordedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let body = &self.body;
+        let inner = self.body.arguments()[0];
+        tokens.extend(quote! {
+            ::sorbit::ser_de::Deserializer::deserialize_recorded(#deserializer, |#inner| { #body })
+        })
+    }
+}
+
+op!(
+    name: "verify_checksum",
+    builder: verify_checksum,
+    op: VerifyChecksumOp,
+    inputs: {deserializer, checksum, sections},
+    outputs: {result},
+    attributes: {algorithm: ChecksumAlgorithm, num_sections: usize},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for VerifyChecksumOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let checksum = &self.checksum;
+        let sections = &self.sections;
+        let algorithm = checksum_algorithm_tokens(self.algorithm);
+        let indices = (0..self.num_sections).map(syn::Index::from);
+        tokens.extend(quote! {
+            ::sorbit::checksum::verify_checksum::<#algorithm, _>(
+                #deserializer,
+                #checksum,
+                &[#(#sections.#indices.as_slice()),*]
+            )
+        })
+    }
+}
+
+/// Return the type that implements the checksum algorithm.
+pub fn checksum_algorithm_tokens(algorithm: ChecksumAlgorithm) -> TokenStream {
+    match algorithm {
+        ChecksumAlgorithm::Crc16 => quote! { ::sorbit::checksum::Crc16 },
+        ChecksumAlgorithm::Crc32 => quote! { ::sorbit::checksum::Crc32 },
+    }
+}
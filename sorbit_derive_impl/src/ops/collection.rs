@@ -22,6 +22,26 @@ impl ToTokens for LenOp {
     }
 }
 
+op!(
+    name: "check_len",
+    builder: check_len,
+    op: CheckLenOp,
+    inputs: {serializer, collection, len},
+    outputs: {result},
+    attributes: {},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for CheckLenOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let collection = &self.collection;
+        let len = &self.len;
+        tokens.extend(quote! { ::sorbit::collection::check_len(#serializer, #collection, #len) })
+    }
+}
+
 op!(
     name: "byte_count",
     builder: byte_count,
@@ -42,6 +62,31 @@ impl ToTokens for ByteCountOp {
     }
 }
 
+op!(
+    name: "measure_byte_count",
+    builder: measure_byte_count,
+    op: MeasureByteCountOp,
+    inputs: {serializer},
+    outputs: {byte_count},
+    attributes: {byte_count_ty: syn::Type},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for MeasureByteCountOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let byte_count_ty = &self.byte_count_ty;
+        let body = &self.body;
+        let sizer = body.arguments()[0];
+        tokens.extend(quote! {
+            ::sorbit::collection::measure_byte_count::<#byte_count_ty, _>(#serializer, |#sizer| {
+                #body
+            })
+        })
+    }
+}
+
 op!(
     name: "items",
     builder: items,
@@ -60,6 +105,25 @@ impl ToTokens for ItemsOp {
     }
 }
 
+op!(
+    name: "len_prefixed",
+    builder: len_prefixed,
+    op: LenPrefixedOp,
+    inputs: {collection},
+    outputs: {len_prefixed},
+    attributes: {len_ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for LenPrefixedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let collection = &self.collection;
+        let len_ty = &self.len_ty;
+        tokens.extend(quote! { ::sorbit::collection::len_prefixed::<#len_ty, _>(#collection) })
+    }
+}
+
 op!(
     name: "deserialize_items_by_len",
     builder: deserialize_items_by_len,
@@ -109,3 +173,121 @@ impl ToTokens for DeserializeItemsByByteCountOp {
         })
     }
 }
+
+op!(
+    name: "deserialize_len_prefixed",
+    builder: deserialize_len_prefixed,
+    op: DeserializeLenPrefixedOp,
+    inputs: {deserializer},
+    outputs: {collection_value},
+    attributes: {collection_ty: syn::Type, len_ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeLenPrefixedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let collection_ty = &self.collection_ty;
+        let len_ty = &self.len_ty;
+        tokens.extend(quote! {
+            ::sorbit::collection::deserialize_len_prefixed::<#collection_ty, _, _, #len_ty>(#deserializer)
+        })
+    }
+}
+
+op!(
+    name: "strided",
+    builder: strided,
+    op: StridedOp,
+    inputs: {collection},
+    outputs: {strided},
+    attributes: {stride: u64},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for StridedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let collection = &self.collection;
+        let stride = self.stride;
+        tokens.extend(quote! { ::sorbit::collection::strided(#collection, #stride) })
+    }
+}
+
+op!(
+    name: "deserialize_strided_array",
+    builder: deserialize_strided_array,
+    op: DeserializeStridedArrayOp,
+    inputs: {deserializer},
+    outputs: {array_value},
+    attributes: {item_ty: syn::Type, len: syn::Expr, stride: u64},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeStridedArrayOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let item_ty = &self.item_ty;
+        let len = &self.len;
+        let stride = self.stride;
+        tokens.extend(quote! {
+            ::sorbit::collection::deserialize_strided_array::<#item_ty, _, { #len }>(#deserializer, #stride)
+        })
+    }
+}
+
+op!(
+    name: "deserialize_strided_items_by_len",
+    builder: deserialize_strided_items_by_len,
+    op: DeserializeStridedItemsByLenOp,
+    inputs: {deserializer, len},
+    outputs: {collection_value},
+    attributes: {collection_ty: syn::Type, stride: u64},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeStridedItemsByLenOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let len = &self.len;
+        let collection_ty = &self.collection_ty;
+        let stride = self.stride;
+        tokens.extend(quote! {
+            ::sorbit::collection::deserialize_strided_items_by_len::<#collection_ty, _, _, _>(
+                #deserializer,
+                #len,
+                #stride
+            )
+        })
+    }
+}
+
+op!(
+    name: "deserialize_strided_items_by_byte_count",
+    builder: deserialize_strided_items_by_byte_count,
+    op: DeserializeStridedItemsByByteCountOp,
+    inputs: {deserializer, byte_count},
+    outputs: {collection_value},
+    attributes: {collection_ty: syn::Type, stride: u64},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeStridedItemsByByteCountOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let byte_count = &self.byte_count;
+        let collection_ty = &self.collection_ty;
+        let stride = self.stride;
+        tokens.extend(quote! {
+            ::sorbit::collection::deserialize_strided_items_by_byte_count::<#collection_ty, _, _, _>(
+                #deserializer,
+                #byte_count,
+                #stride
+            )
+        })
+    }
+}
@@ -2,7 +2,6 @@ use quote::{ToTokens, quote};
 
 pub struct BitFieldType;
 
-#[allow(unused)]
 pub struct TraceErrorTrait;
 
 pub struct SerializerTrait;
@@ -10,17 +9,30 @@ pub struct RevisableSerializerTrait;
 pub struct SerializerType;
 pub struct SerializeTrait;
 pub struct MultiPassSerializeTrait;
+pub struct DeferredSerializeTrait;
 
 pub struct DeserializerTrait;
+pub struct SeekableDeserializerTrait;
 pub struct DeserializerType;
 pub struct DeserializeTrait;
+pub struct MultiPassDeserializeTrait;
+pub struct DeserializeBoxedTrait;
+pub struct BorrowDeserializerTrait;
+pub struct DeserializeBorrowedTrait;
+
+pub struct StaticLayoutTrait;
+pub struct LayoutType;
+pub struct FieldLayoutType;
+pub struct ItemsLayoutFn;
+pub struct StridedItemsLayoutFn;
+pub struct StridedArrayLayoutFn;
 
 pub struct BigEndian;
 pub struct LittleEndian;
+pub struct MiddleEndian;
 
 pub const BIT_FIELD_TYPE: BitFieldType = BitFieldType {};
 
-#[allow(unused)]
 pub const TRACE_ERROR_TRAIT: TraceErrorTrait = TraceErrorTrait {};
 
 pub const SERIALIZER_TRAIT: SerializerTrait = SerializerTrait {};
@@ -28,13 +40,27 @@ pub const REVISABLE_SERIALIZER_TRAIT: RevisableSerializerTrait = RevisableSerial
 pub const SERIALIZER_TYPE: SerializerType = SerializerType {};
 pub const SERIALIZE_TRAIT: SerializeTrait = SerializeTrait {};
 pub const MULTI_PASS_SERIALIZE_TRAIT: MultiPassSerializeTrait = MultiPassSerializeTrait {};
+pub const DEFERRED_SERIALIZE_TRAIT: DeferredSerializeTrait = DeferredSerializeTrait {};
 
 pub const DESERIALIZER_TRAIT: DeserializerTrait = DeserializerTrait {};
+pub const SEEKABLE_DESERIALIZER_TRAIT: SeekableDeserializerTrait = SeekableDeserializerTrait {};
 pub const DESERIALIZER_TYPE: DeserializerType = DeserializerType {};
 pub const DESERIALIZE_TRAIT: DeserializeTrait = DeserializeTrait {};
+pub const MULTI_PASS_DESERIALIZE_TRAIT: MultiPassDeserializeTrait = MultiPassDeserializeTrait {};
+pub const DESERIALIZE_BOXED_TRAIT: DeserializeBoxedTrait = DeserializeBoxedTrait {};
+pub const BORROW_DESERIALIZER_TRAIT: BorrowDeserializerTrait = BorrowDeserializerTrait {};
+pub const DESERIALIZE_BORROWED_TRAIT: DeserializeBorrowedTrait = DeserializeBorrowedTrait {};
+
+pub const STATIC_LAYOUT_TRAIT: StaticLayoutTrait = StaticLayoutTrait {};
+pub const LAYOUT_TYPE: LayoutType = LayoutType {};
+pub const FIELD_LAYOUT_TYPE: FieldLayoutType = FieldLayoutType {};
+pub const ITEMS_LAYOUT_FN: ItemsLayoutFn = ItemsLayoutFn {};
+pub const STRIDED_ITEMS_LAYOUT_FN: StridedItemsLayoutFn = StridedItemsLayoutFn {};
+pub const STRIDED_ARRAY_LAYOUT_FN: StridedArrayLayoutFn = StridedArrayLayoutFn {};
 
 pub const BIG_ENDIAN: BigEndian = BigEndian {};
 pub const LITTLE_ENDIAN: LittleEndian = LittleEndian {};
+pub const MIDDLE_ENDIAN: MiddleEndian = MiddleEndian {};
 
 impl ToTokens for BitFieldType {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
@@ -78,12 +104,24 @@ impl ToTokens for MultiPassSerializeTrait {
     }
 }
 
+impl ToTokens for DeferredSerializeTrait {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::ser_de::DeferredSerialize});
+    }
+}
+
 impl ToTokens for DeserializerTrait {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         tokens.extend(quote! {::sorbit::ser_de::Deserializer});
     }
 }
 
+impl ToTokens for SeekableDeserializerTrait {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::ser_de::SeekableDeserializer});
+    }
+}
+
 impl ToTokens for DeserializerType {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         tokens.extend(quote! {D});
@@ -95,6 +133,67 @@ impl ToTokens for DeserializeTrait {
         tokens.extend(quote! {::sorbit::ser_de::Deserialize});
     }
 }
+
+impl ToTokens for MultiPassDeserializeTrait {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::ser_de::MultiPassDeserialize});
+    }
+}
+
+impl ToTokens for DeserializeBoxedTrait {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::ser_de::DeserializeBoxed});
+    }
+}
+
+impl ToTokens for BorrowDeserializerTrait {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::ser_de::BorrowDeserializer});
+    }
+}
+
+impl ToTokens for DeserializeBorrowedTrait {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::ser_de::DeserializeBorrowed});
+    }
+}
+
+impl ToTokens for StaticLayoutTrait {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::layout::StaticLayout});
+    }
+}
+
+impl ToTokens for LayoutType {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::layout::Layout});
+    }
+}
+
+impl ToTokens for FieldLayoutType {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::layout::FieldLayout});
+    }
+}
+
+impl ToTokens for ItemsLayoutFn {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::layout::items});
+    }
+}
+
+impl ToTokens for StridedItemsLayoutFn {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::layout::strided_items});
+    }
+}
+
+impl ToTokens for StridedArrayLayoutFn {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::layout::strided_array});
+    }
+}
+
 impl ToTokens for BigEndian {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         tokens.extend(quote! {::sorbit::byte_order::ByteOrder::BigEndian});
@@ -105,3 +204,8 @@ impl ToTokens for LittleEndian {
         tokens.extend(quote! {::sorbit::byte_order::ByteOrder::LittleEndian});
     }
 }
+impl ToTokens for MiddleEndian {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(quote! {::sorbit::byte_order::ByteOrder::MiddleEndian});
+    }
+}
@@ -173,6 +173,28 @@ impl ToTokens for SymrefOp {
     }
 }
 
+//------------------------------------------------------------------------------
+// UnrefSymOp
+//------------------------------------------------------------------------------
+
+op!(
+    name: "unref_sym",
+    builder: unref_sym,
+    op: UnrefSymOp,
+    inputs: {},
+    outputs: {},
+    attributes: {sym: Ident},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for UnrefSymOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let sym = &self.sym;
+        tokens.extend(quote! { #[allow(unused)] let #sym = *#sym })
+    }
+}
+
 //------------------------------------------------------------------------------
 // Yield
 //------------------------------------------------------------------------------
@@ -280,7 +302,7 @@ pub struct MatchOp {
     result: Value,
 }
 
-pub fn match_<'a>(region: &mut Region, expr: Value, arms: Vec<(syn::Pat, Option<syn::Expr>, Region)>) -> Value {
+pub fn match_(region: &mut Region, expr: Value, arms: Vec<(syn::Pat, Option<syn::Expr>, Region)>) -> Value {
     region.append(MatchOp { expr, arms, result: Value::new() })[0]
 }
 
@@ -404,6 +426,28 @@ impl ToTokens for OkOp {
     }
 }
 
+//------------------------------------------------------------------------------
+// Some
+//------------------------------------------------------------------------------
+
+op!(
+    name: "some",
+    builder: some,
+    op: SomeOp,
+    inputs: {value},
+    outputs: {some_value},
+    attributes: {},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for SomeOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = &self.value;
+        tokens.extend(quote! { ::core::option::Option::Some(#value) })
+    }
+}
+
 //------------------------------------------------------------------------------
 // Tuple
 //------------------------------------------------------------------------------
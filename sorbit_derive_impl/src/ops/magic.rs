@@ -0,0 +1,25 @@
+use crate::ir::op;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "magic",
+    builder: magic,
+    op: MagicOp,
+    inputs: {serializer},
+    outputs: {result},
+    attributes: {bytes: syn::Expr, serializing: bool},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for MagicOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let bytes = &self.bytes;
+        match self.serializing {
+            true => tokens.extend(quote! { ::sorbit::magic::serialize_magic(#serializer, #bytes) }),
+            false => tokens.extend(quote! { ::sorbit::magic::deserialize_magic(#serializer, #bytes) }),
+        }
+    }
+}
@@ -1,13 +1,31 @@
 pub mod algorithm;
 mod bit_field;
+mod borrow;
+mod checksum;
 mod collection;
 pub mod constants;
 mod language;
+mod magic;
+mod option;
+mod pointer;
+mod scale;
 mod serialization;
+mod string;
+mod time;
 mod trait_impl;
+mod varint;
 
 pub use bit_field::*;
+pub use borrow::*;
+pub use checksum::*;
 pub use collection::*;
 pub use language::*;
+pub use magic::*;
+pub use option::*;
+pub use pointer::*;
+pub use scale::*;
 pub use serialization::*;
+pub use string::*;
+pub use time::*;
 pub use trait_impl::*;
+pub use varint::*;
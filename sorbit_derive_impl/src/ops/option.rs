@@ -0,0 +1,52 @@
+use crate::attribute::OptionEncoding;
+use crate::ir::op;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "encoded_option",
+    builder: encoded_option,
+    op: EncodedOptionOp,
+    inputs: {option},
+    outputs: {encoded},
+    attributes: {encoding: OptionEncoding, value_ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for EncodedOptionOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let option = &self.option;
+        let encoding = option_encoding_tokens(&self.encoding, &self.value_ty);
+        tokens.extend(quote! { ::sorbit::option::encoded(#option, #encoding) })
+    }
+}
+
+op!(
+    name: "deserialize_encoded_option",
+    builder: deserialize_encoded_option,
+    op: DeserializeEncodedOptionOp,
+    inputs: {deserializer},
+    outputs: {option},
+    attributes: {encoding: OptionEncoding, value_ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeEncodedOptionOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let encoding = option_encoding_tokens(&self.encoding, &self.value_ty);
+        let value_ty = &self.value_ty;
+        tokens.extend(quote! { ::sorbit::option::deserialize_encoded::<#value_ty, _, _>(#deserializer, #encoding) })
+    }
+}
+
+/// Return the expression of the encoding of an optional `value_ty`.
+pub fn option_encoding_tokens(encoding: &OptionEncoding, value_ty: &syn::Type) -> TokenStream {
+    match encoding {
+        OptionEncoding::Flag(ty) => quote! { ::sorbit::option::Flag::<#ty>::new() },
+        OptionEncoding::Sentinel(expr) => quote! { ::sorbit::option::Sentinel::<#value_ty>(#expr) },
+        OptionEncoding::Remaining => quote! { ::sorbit::option::Remaining },
+    }
+}
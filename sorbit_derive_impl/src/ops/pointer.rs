@@ -0,0 +1,128 @@
+use crate::attribute::PointerBase;
+use crate::ir::op;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "pointees",
+    builder: pointees,
+    op: PointeesOp,
+    inputs: {},
+    outputs: {pointees},
+    attributes: {},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for PointeesOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(quote! { ::sorbit::pointer::Pointees::new() })
+    }
+}
+
+op!(
+    name: "serialize_pointee",
+    builder: serialize_pointee,
+    op: SerializePointeeOp,
+    inputs: {serializer, composite, pointer},
+    outputs: {result},
+    attributes: {offset: syn::Type, base: PointerBase},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for SerializePointeeOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let se = &self.serializer;
+        let composite = &self.composite;
+        let pointer = &self.pointer;
+        let offset = &self.offset;
+        let base = pointer_base_tokens(self.base);
+        let body = &self.body;
+        let inner = self.body.arguments()[0];
+        tokens.extend(quote! {
+            ::sorbit::pointer::serialize_pointee::<#offset, _>(#se, #base, #composite, #pointer, |#inner| {
+                #body
+            })
+        })
+    }
+}
+
+op!(
+    name: "serialize_offset",
+    builder: serialize_offset,
+    op: SerializeOffsetOp,
+    inputs: {serializer, composite, pointer, object},
+    outputs: {result},
+    attributes: {offset: syn::Type, base: PointerBase},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for SerializeOffsetOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let se = &self.serializer;
+        let composite = &self.composite;
+        let pointer = &self.pointer;
+        let object = &self.object;
+        let offset = &self.offset;
+        let base = pointer_base_tokens(self.base);
+        tokens.extend(quote! {
+            ::sorbit::pointer::serialize_offset::<#offset, _>(#se, #base, #composite, #pointer, #object)
+        })
+    }
+}
+
+op!(
+    name: "deserialize_pointee",
+    builder: deserialize_pointee,
+    op: DeserializePointeeOp,
+    inputs: {deserializer, pointees},
+    outputs: {result},
+    attributes: {offset: syn::Type, base: PointerBase},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for DeserializePointeeOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let de = &self.deserializer;
+        let pointees = &self.pointees;
+        let offset = &self.offset;
+        let base = pointer_base_tokens(self.base);
+        let body = &self.body;
+        let inner = self.body.arguments()[0];
+        tokens.extend(quote! {
+            ::sorbit::pointer::Pointees::deserialize::<#offset, _, _>(#pointees, #de, #base, |#inner| {
+                #body
+            })
+        })
+    }
+}
+
+op!(
+    name: "skip_pointees",
+    builder: skip_pointees,
+    op: SkipPointeesOp,
+    inputs: {deserializer, pointees},
+    outputs: {result},
+    attributes: {},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for SkipPointeesOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let de = &self.deserializer;
+        let pointees = &self.pointees;
+        tokens.extend(quote! { ::sorbit::pointer::Pointees::skip(#pointees, #de) })
+    }
+}
+
+fn pointer_base_tokens(base: PointerBase) -> TokenStream {
+    match base {
+        PointerBase::Absolute => quote! { ::sorbit::pointer::Base::Absolute },
+        PointerBase::Composite => quote! { ::sorbit::pointer::Base::Composite },
+        PointerBase::Relative => quote! { ::sorbit::pointer::Base::Relative },
+    }
+}
@@ -0,0 +1,54 @@
+use crate::ir::op;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "scaled_as",
+    builder: scaled_as,
+    op: ScaledAsOp,
+    inputs: {serializer, value},
+    outputs: {raw},
+    attributes: {scale: syn::Expr, bias: Option<syn::Expr>, raw_ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for ScaledAsOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let value = &self.value;
+        let scale = &self.scale;
+        let bias = bias_tokens(self.bias.as_ref());
+        let raw_ty = &self.raw_ty;
+        tokens.extend(quote! { ::sorbit::scale::scaled_as::<#raw_ty, _, _>(#serializer, #value, #scale, #bias) })
+    }
+}
+
+op!(
+    name: "scaled_from",
+    builder: scaled_from,
+    op: ScaledFromOp,
+    inputs: {deserializer, raw},
+    outputs: {value},
+    attributes: {scale: syn::Expr, bias: Option<syn::Expr>, ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for ScaledFromOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let raw = &self.raw;
+        let scale = &self.scale;
+        let bias = bias_tokens(self.bias.as_ref());
+        let ty = &self.ty;
+        tokens.extend(quote! { ::sorbit::scale::scaled_from::<_, #ty, _>(#deserializer, #raw, #scale, #bias) })
+    }
+}
+
+fn bias_tokens(bias: Option<&syn::Expr>) -> TokenStream {
+    match bias {
+        Some(bias) => bias.to_token_stream(),
+        None => quote! { 0.0 },
+    }
+}
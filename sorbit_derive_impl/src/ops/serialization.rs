@@ -1,11 +1,13 @@
 use proc_macro2::TokenStream;
 use quote::{ToTokens, quote};
+use syn::Expr;
 
-use crate::attribute::ByteOrder;
-use crate::ir::op;
+use crate::attribute::{ByteOrder, SizePrefix};
+use crate::ir::{Operation, Region, Value, op};
 use crate::ops::constants::{
-    BIG_ENDIAN, DESERIALIZE_TRAIT, DESERIALIZER_TRAIT, LITTLE_ENDIAN, MULTI_PASS_SERIALIZE_TRAIT,
-    REVISABLE_SERIALIZER_TRAIT, SERIALIZE_TRAIT, SERIALIZER_TRAIT,
+    BIG_ENDIAN, DEFERRED_SERIALIZE_TRAIT, DESERIALIZE_BOXED_TRAIT, DESERIALIZE_TRAIT, DESERIALIZER_TRAIT,
+    LITTLE_ENDIAN, MIDDLE_ENDIAN, MULTI_PASS_SERIALIZE_TRAIT, REVISABLE_SERIALIZER_TRAIT, SEEKABLE_DESERIALIZER_TRAIT,
+    SERIALIZE_TRAIT, SERIALIZER_TRAIT, TRACE_ERROR_TRAIT,
 };
 
 //------------------------------------------------------------------------------
@@ -31,30 +33,58 @@ impl ToTokens for SuccessOp {
 }
 
 //------------------------------------------------------------------------------
-// Error
+// Invalid enum variant
 //------------------------------------------------------------------------------
 
 op!(
-    name: "error",
-    builder: error,
-    op: ErrorOp,
+    name: "invalid_enum_variant",
+    builder: invalid_enum_variant,
+    op: InvalidEnumVariantOp,
     inputs: {deserializer},
     outputs: {error_result},
-    attributes: {message: String},
+    attributes: {},
     regions: {},
     terminator: false
 );
 
-impl ToTokens for ErrorOp {
+impl ToTokens for InvalidEnumVariantOp {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let deserializer = &self.deserializer;
-        let message = &self.message;
-        tokens.extend(quote! { #DESERIALIZER_TRAIT::error(#deserializer, #message) })
+        tokens.extend(quote! { #DESERIALIZER_TRAIT::invalid_enum_variant(#deserializer) })
     }
 }
 
 //------------------------------------------------------------------------------
-// Error
+// Expect some
+//------------------------------------------------------------------------------
+
+op!(
+    name: "expect_some",
+    builder: expect_some,
+    op: ExpectSomeOp,
+    inputs: {serializer, optional},
+    outputs: {some_result},
+    attributes: {},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for ExpectSomeOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let optional = &self.optional;
+        tokens.extend(quote! {
+            match #optional {
+                ::core::option::Option::Some(value) => ::core::result::Result::Ok(value),
+                ::core::option::Option::None => #SERIALIZER_TRAIT::error(#serializer, "the field is present but has no value")
+                    .map(|never| match never {}),
+            }
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Check equal
 //------------------------------------------------------------------------------
 
 op!(
@@ -81,6 +111,95 @@ impl ToTokens for CheckEqOp {
     }
 }
 
+//------------------------------------------------------------------------------
+// Check range
+//------------------------------------------------------------------------------
+
+op!(
+    name: "check_range",
+    builder: check_range,
+    op: CheckRangeOp,
+    inputs: {deserializer, value},
+    outputs: {},
+    attributes: {min: Option<Expr>, max: Option<Expr>},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for CheckRangeOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let value = &self.value;
+        let min = &self.min;
+        // Either bound may be missing, which is left out of the range.
+        let range = match &self.max {
+            Some(max) => quote! { #min..=#max },
+            None => quote! { #min.. },
+        };
+        tokens.extend(quote! {
+            if !(#range).contains(&#value) {
+                let _ = #DESERIALIZER_TRAIT::invalid_value(#deserializer)?;
+            };
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Check depth
+//------------------------------------------------------------------------------
+
+op!(
+    name: "check_depth",
+    builder: check_depth,
+    op: CheckDepthOp,
+    inputs: {deserializer},
+    outputs: {},
+    attributes: {max_depth: u32},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for CheckDepthOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let max_depth = self.max_depth;
+        tokens.extend(quote! {
+            if #DESERIALIZER_TRAIT::depth(#deserializer) >= #max_depth {
+                let _ = #DESERIALIZER_TRAIT::recursion_limit(#deserializer)?;
+            };
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Validate
+//------------------------------------------------------------------------------
+
+op!(
+    name: "validate",
+    builder: validate,
+    op: ValidateOp,
+    inputs: {deserializer, value},
+    outputs: {validated_result},
+    attributes: {validator: syn::Path},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for ValidateOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let value = &self.value;
+        let validator = &self.validator;
+        tokens.extend(quote! {
+            match #validator(&#value) {
+                ::core::result::Result::Ok(()) => ::core::result::Result::Ok(#value),
+                ::core::result::Result::Err(message) => #DESERIALIZER_TRAIT::error(#deserializer, message),
+            }
+        })
+    }
+}
+
 //------------------------------------------------------------------------------
 // Pad
 //------------------------------------------------------------------------------
@@ -107,6 +226,149 @@ impl ToTokens for PadOp {
     }
 }
 
+//------------------------------------------------------------------------------
+// Seek
+//------------------------------------------------------------------------------
+
+op!(
+    name: "seek",
+    builder: seek,
+    op: SeekOp,
+    inputs: {deserializer},
+    outputs: {sought_deserializer},
+    attributes: {until: u64},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for SeekOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let until = self.until;
+        tokens.extend(quote! { #SEEKABLE_DESERIALIZER_TRAIT::seek(#deserializer, #until) })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Overlay
+//------------------------------------------------------------------------------
+
+op!(
+    name: "overlay",
+    builder: overlay,
+    op: OverlayOp,
+    inputs: {deserializer},
+    outputs: {result},
+    attributes: {offset: u64},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for OverlayOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let de = &self.deserializer;
+        let offset = self.offset;
+        let body = &self.body;
+        let inner = self.body.arguments()[0];
+        tokens.extend(quote! {
+            #SEEKABLE_DESERIALIZER_TRAIT::deserialize_overlay(#de, #offset, |#inner| {
+                #body
+            })
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Try variants
+//------------------------------------------------------------------------------
+
+/// Deserialize the first of the variants that succeeds, trying them in order.
+///
+/// Each variant is deserialized in a transaction, so a failing variant leaves
+/// the deserializer where it was for the next one. If all of them fail, their
+/// errors are reported together.
+pub struct TryVariantsOp {
+    deserializer: Value,
+    variants: Vec<(String, Region)>,
+    result: Value,
+}
+
+pub fn try_variants(region: &mut Region, deserializer: Value, variants: Vec<(String, Region)>) -> Value {
+    region.append(TryVariantsOp { deserializer, variants, result: Value::new() })[0]
+}
+
+impl Operation for TryVariantsOp {
+    fn name(&self) -> &str {
+        "try_variants"
+    }
+
+    fn inputs(&self) -> Vec<Value> {
+        vec![self.deserializer]
+    }
+
+    fn outputs(&self) -> Vec<Value> {
+        vec![self.result]
+    }
+
+    fn regions(&self) -> Vec<&Region> {
+        self.variants.iter().map(|(_, region)| region).collect()
+    }
+
+    fn attributes(&self) -> Vec<String> {
+        self.variants.iter().map(|(name, _)| name.clone()).collect()
+    }
+}
+
+impl ToTokens for TryVariantsOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let de = &self.deserializer;
+        let attempts = self.variants.iter().map(|(name, body)| {
+            let inner = body.arguments()[0];
+            quote! {
+                match #SEEKABLE_DESERIALIZER_TRAIT::transaction(#de, |#inner| { #body }) {
+                    ::core::result::Result::Ok(value) => break 'variants ::core::result::Result::Ok(value),
+                    ::core::result::Result::Err(error) => #TRACE_ERROR_TRAIT::annotate(error, #name),
+                }
+            }
+        });
+        tokens.extend(quote! {
+            'variants: {
+                let errors = [#(#attempts),*];
+                #DESERIALIZER_TRAIT::no_matching_variant(#de, errors)
+            }
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Serialize at
+//------------------------------------------------------------------------------
+
+op!(
+    name: "serialize_at",
+    builder: serialize_at,
+    op: SerializeAtOp,
+    inputs: {serializer},
+    outputs: {result},
+    attributes: {offset: u64},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for SerializeAtOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let se = &self.serializer;
+        let offset = self.offset;
+        let body = &self.body;
+        let inner = self.body.arguments()[0];
+        tokens.extend(quote! {
+            #REVISABLE_SERIALIZER_TRAIT::serialize_at(#se, #offset, |#inner| {
+                #body
+            })
+        })
+    }
+}
+
 //------------------------------------------------------------------------------
 // Align
 //------------------------------------------------------------------------------
@@ -133,31 +395,171 @@ impl ToTokens for AlignOp {
     }
 }
 
+op!(
+    name: "align_absolute",
+    builder: align_absolute,
+    op: AlignAbsoluteOp,
+    inputs: {serializer},
+    outputs: {aligned_serializer},
+    attributes: {multiple_of: u64, serializing: bool},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for AlignAbsoluteOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let multiple_of = self.multiple_of;
+        match self.serializing {
+            true => tokens.extend(quote! { #SERIALIZER_TRAIT::align_absolute(#serializer, #multiple_of) }),
+            false => tokens.extend(quote! { #DESERIALIZER_TRAIT::align_absolute(#serializer, #multiple_of) }),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Context values
+//------------------------------------------------------------------------------
+
+op!(
+    name: "context_value",
+    builder: context_value,
+    op: ContextValueOp,
+    inputs: {serializer},
+    outputs: {value},
+    attributes: {key: String, serializing: bool},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for ContextValueOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let key = &self.key;
+        match self.serializing {
+            true => tokens.extend(quote! { #SERIALIZER_TRAIT::context(#serializer, #key) }),
+            false => tokens.extend(quote! { #DESERIALIZER_TRAIT::context(#serializer, #key) }),
+        }
+    }
+}
+
+op!(
+    name: "expect_context_value",
+    builder: expect_context_value,
+    op: ExpectContextValueOp,
+    inputs: {serializer},
+    outputs: {value_result},
+    attributes: {key: String, serializing: bool},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for ExpectContextValueOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let key = &self.key;
+        let message = format!("the context value `{key}` is not set");
+        let (value, error) = match self.serializing {
+            true => (
+                quote! { #SERIALIZER_TRAIT::context(#serializer, #key) },
+                quote! { #SERIALIZER_TRAIT::error(#serializer, #message).map(|never| match never {}) },
+            ),
+            false => (
+                quote! { #DESERIALIZER_TRAIT::context(#serializer, #key) },
+                quote! { #DESERIALIZER_TRAIT::error(#serializer, #message) },
+            ),
+        };
+        tokens.extend(quote! {
+            match #value {
+                ::core::option::Option::Some(value) => ::core::result::Result::Ok(value),
+                ::core::option::Option::None => #error,
+            }
+        })
+    }
+}
+
+op!(
+    name: "set_context",
+    builder: set_context,
+    op: SetContextOp,
+    inputs: {serializer, value},
+    outputs: {result},
+    attributes: {key: String, serializing: bool},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for SetContextOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let value = &self.value;
+        let key = &self.key;
+        let value = quote! { ::core::option::Option::Some(::core::convert::Into::<u64>::into(::core::clone::Clone::clone(#value))) };
+        match self.serializing {
+            true => tokens.extend(quote! { #SERIALIZER_TRAIT::set_context(#serializer, #key, #value) }),
+            false => tokens.extend(quote! { #DESERIALIZER_TRAIT::set_context(#serializer, #key, #value) }),
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
-// Annotate result
+// Position
 //------------------------------------------------------------------------------
 
-/*
 op!(
-    name: "annotate_result",
-    builder: annotate_result,
-    op: AnnotateResultOp,
-    inputs: {result},
-    outputs: {annotated_result},
-    attributes: {annotation: String},
+    name: "position",
+    builder: position,
+    op: PositionOp,
+    inputs: {serializer},
+    outputs: {position},
+    attributes: {serializing: bool},
     regions: {},
     terminator: false
 );
 
-#[allow(unused)]
-impl ToTokens for AnnotateResultOp {
+impl ToTokens for PositionOp {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let result = &self.result;
-        let annotation = &self.annotation;
-        tokens.extend(quote! { #result.map_err(|err| #TRACE_ERROR_TRAIT::annotate(err, #annotation)) })
+        let serializer = &self.serializer;
+        match self.serializing {
+            true => tokens.extend(quote! { #SERIALIZER_TRAIT::position(#serializer) }),
+            false => tokens.extend(quote! { #DESERIALIZER_TRAIT::position(#serializer) }),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Trace
+//------------------------------------------------------------------------------
+
+op!(
+    name: "trace",
+    builder: trace,
+    op: TraceOp,
+    inputs: {serializer, position},
+    outputs: {result},
+    attributes: {member: String, serializing: bool},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for TraceOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let position = &self.position;
+        let member = &self.member;
+        let body = &self.body;
+        let inner_serializer = self.body.arguments()[0];
+        let trace_fn = match self.serializing {
+            true => quote! { ::sorbit::error::trace_serialized },
+            false => quote! { ::sorbit::error::trace_deserialized },
+        };
+        tokens.extend(quote! {
+            #trace_fn(#serializer, #member, #position, |#inner_serializer| {
+                #body
+            })
+        })
     }
 }
-*/
 
 //------------------------------------------------------------------------------
 // Serialize object
@@ -186,6 +588,57 @@ impl ToTokens for SerializeObjectOp {
     }
 }
 
+//------------------------------------------------------------------------------
+// Serialize deferred
+//------------------------------------------------------------------------------
+
+op!(
+    name: "serialize_deferred",
+    builder: serialize_deferred,
+    op: SerializeDeferredOp,
+    inputs: {serializer, object, sections},
+    outputs: {serialized_object},
+    attributes: {num_sections: usize},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for SerializeDeferredOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let object = &self.object;
+        let sections = &self.sections;
+        let indices = (0..self.num_sections).map(syn::Index::from);
+        tokens.extend(quote! {
+            #DEFERRED_SERIALIZE_TRAIT::serialize_deferred(#object, #serializer, &[#(&#sections.#indices),*])
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Serialize with
+//------------------------------------------------------------------------------
+
+op!(
+    name: "serialize_with",
+    builder: serialize_with,
+    op: SerializeWithOp,
+    inputs: {serializer, object},
+    outputs: {serialized_object},
+    attributes: {function: syn::Path},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for SerializeWithOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let object = &self.object;
+        let function = &self.function;
+        tokens.extend(quote! { #function(#object, #serializer) })
+    }
+}
+
 //------------------------------------------------------------------------------
 // Serialize composite
 //------------------------------------------------------------------------------
@@ -237,6 +690,52 @@ impl ToTokens for DeserializeObjectOp {
     }
 }
 
+//------------------------------------------------------------------------------
+// Deserialize with
+//------------------------------------------------------------------------------
+
+op!(
+    name: "deserialize_with",
+    builder: deserialize_with,
+    op: DeserializeWithOp,
+    inputs: {deserializer},
+    outputs: {deserialized_object},
+    attributes: {function: syn::Path},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeWithOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let function = &self.function;
+        tokens.extend(quote! { #function(#deserializer) })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Deserialize boxed
+//------------------------------------------------------------------------------
+
+op!(
+    name: "deserialize_boxed",
+    builder: deserialize_boxed,
+    op: DeserializeBoxedOp,
+    inputs: {deserializer},
+    outputs: {deserialized_box},
+    attributes: {ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeBoxedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let ty = &self.ty;
+        tokens.extend(quote! { <#ty as #DESERIALIZE_BOXED_TRAIT>::deserialize_boxed(#deserializer)})
+    }
+}
+
 //------------------------------------------------------------------------------
 // Deserialize composite
 //------------------------------------------------------------------------------
@@ -265,6 +764,71 @@ impl ToTokens for DeserializeCompositeOp {
     }
 }
 
+//------------------------------------------------------------------------------
+// Deserialize field
+//------------------------------------------------------------------------------
+
+op!(
+    name: "deserialize_field",
+    builder: deserialize_field,
+    op: DeserializeFieldOp,
+    inputs: {deserializer},
+    outputs: {field_result},
+    attributes: {field: String},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for DeserializeFieldOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let field = &self.field;
+        let body = &self.body;
+        let inner_deserializer = self.body.arguments()[0];
+        tokens.extend(quote! {
+            #DESERIALIZER_TRAIT::deserialize_field(#deserializer, #field, |#inner_deserializer| {
+                #body
+            })
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+// Size prefix
+//------------------------------------------------------------------------------
+
+op!(
+    name: "size_prefixed",
+    builder: size_prefixed,
+    op: SizePrefixedOp,
+    inputs: {serializer},
+    outputs: {result},
+    attributes: {size_prefix: SizePrefix, is_serializing: bool},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for SizePrefixedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let se = &self.serializer;
+        let body = &self.body;
+        let inner = self.body.arguments()[0];
+        let SizePrefix { ty, includes_prefix } = &self.size_prefix;
+        match self.is_serializing {
+            true => tokens.extend(quote! {
+                ::sorbit::size_prefix::serialize_size_prefixed::<#ty, _>(#se, #includes_prefix, |#inner| {
+                    #body
+                })
+            }),
+            false => tokens.extend(quote! {
+                ::sorbit::size_prefix::deserialize_size_prefixed::<#ty, _, _>(#se, #includes_prefix, |#inner| {
+                    #body
+                })
+            }),
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 // Serialize/deserialize with byte order
 //------------------------------------------------------------------------------
@@ -301,10 +865,48 @@ impl ToTokens for ByteOrderOp {
                     #body
                 })
             }),
+            MiddleEndian => tokens.extend(quote! {
+                #trait_::with_byte_order(#se, #MIDDLE_ENDIAN, |#inner| {
+                    #body
+                })
+            }),
         }
     }
 }
 
+//------------------------------------------------------------------------------
+// Serialize/deserialize with fill byte
+//------------------------------------------------------------------------------
+
+op!(
+    name: "fill",
+    builder: fill,
+    op: FillOp,
+    inputs: {serializer},
+    outputs: {result},
+    attributes: {fill: u8, is_serializing: bool},
+    regions: {body},
+    terminator: false
+);
+
+impl ToTokens for FillOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let se = &self.serializer;
+        let fill = self.fill;
+        let body = &self.body;
+        let inner = self.body.arguments()[0];
+        let trait_ = match self.is_serializing {
+            true => quote! { #SERIALIZER_TRAIT },
+            false => quote! { #DESERIALIZER_TRAIT },
+        };
+        tokens.extend(quote! {
+            #trait_::with_fill(#se, #fill, |#inner| {
+                #body
+            })
+        });
+    }
+}
+
 //------------------------------------------------------------------------------
 // Serialize/deserialize with byte order
 //------------------------------------------------------------------------------
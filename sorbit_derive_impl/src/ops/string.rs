@@ -0,0 +1,54 @@
+use crate::attribute::StringEncoding;
+use crate::ir::op;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "encoded",
+    builder: encoded,
+    op: EncodedOp,
+    inputs: {string},
+    outputs: {encoded},
+    attributes: {encoding: StringEncoding},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for EncodedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let string = &self.string;
+        let encoding = string_encoding_tokens(&self.encoding);
+        tokens.extend(quote! { ::sorbit::string::encoded(#string, #encoding) })
+    }
+}
+
+op!(
+    name: "deserialize_encoded",
+    builder: deserialize_encoded,
+    op: DeserializeEncodedOp,
+    inputs: {deserializer},
+    outputs: {string},
+    attributes: {encoding: StringEncoding},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeEncodedOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let encoding = string_encoding_tokens(&self.encoding);
+        tokens.extend(quote! { ::sorbit::string::deserialize_encoded(#deserializer, #encoding) })
+    }
+}
+
+/// Return the constant expression of the string encoding.
+pub fn string_encoding_tokens(encoding: &StringEncoding) -> TokenStream {
+    match encoding {
+        StringEncoding::NullTerminated => quote! { ::sorbit::string::NullTerminated },
+        StringEncoding::Fixed { len, pad } => {
+            let len = *len as usize;
+            quote! { ::sorbit::string::Fixed { len: #len, pad: #pad } }
+        }
+        StringEncoding::LengthPrefix(ty) => quote! { ::sorbit::string::LenPrefixed::<#ty>::new() },
+    }
+}
@@ -0,0 +1,56 @@
+use crate::attribute::TimeUnit;
+use crate::ir::op;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "duration_as",
+    builder: duration_as,
+    op: DurationAsOp,
+    inputs: {serializer, duration},
+    outputs: {value},
+    attributes: {unit: TimeUnit, repr: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DurationAsOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let serializer = &self.serializer;
+        let duration = &self.duration;
+        let unit = time_unit_tokens(self.unit);
+        let repr = &self.repr;
+        tokens.extend(quote! { ::sorbit::time::duration_as::<#repr, _>(#serializer, #duration, #unit) })
+    }
+}
+
+op!(
+    name: "duration_from",
+    builder: duration_from,
+    op: DurationFromOp,
+    inputs: {deserializer, value},
+    outputs: {duration},
+    attributes: {unit: TimeUnit},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DurationFromOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let value = &self.value;
+        let unit = time_unit_tokens(self.unit);
+        tokens.extend(quote! { ::sorbit::time::duration_from(#deserializer, #value, #unit) })
+    }
+}
+
+fn time_unit_tokens(unit: TimeUnit) -> TokenStream {
+    match unit {
+        TimeUnit::Nanoseconds => quote! { ::sorbit::time::TimeUnit::Nanoseconds },
+        TimeUnit::Microseconds => quote! { ::sorbit::time::TimeUnit::Microseconds },
+        TimeUnit::Milliseconds => quote! { ::sorbit::time::TimeUnit::Milliseconds },
+        TimeUnit::Seconds => quote! { ::sorbit::time::TimeUnit::Seconds },
+        TimeUnit::Minutes => quote! { ::sorbit::time::TimeUnit::Minutes },
+        TimeUnit::Hours => quote! { ::sorbit::time::TimeUnit::Hours },
+    }
+}
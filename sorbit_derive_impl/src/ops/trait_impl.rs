@@ -1,6 +1,7 @@
 use crate::ir::op;
 use crate::ops::constants::{
-    DESERIALIZE_TRAIT, DESERIALIZER_TRAIT, DESERIALIZER_TYPE, MULTI_PASS_SERIALIZE_TRAIT, REVISABLE_SERIALIZER_TRAIT,
+    BORROW_DESERIALIZER_TRAIT, DESERIALIZE_BORROWED_TRAIT, DESERIALIZE_TRAIT, DESERIALIZER_TRAIT, DESERIALIZER_TYPE,
+    MULTI_PASS_DESERIALIZE_TRAIT, MULTI_PASS_SERIALIZE_TRAIT, REVISABLE_SERIALIZER_TRAIT, SEEKABLE_DESERIALIZER_TRAIT,
     SERIALIZE_TRAIT, SERIALIZER_TRAIT, SERIALIZER_TYPE,
 };
 use proc_macro2::TokenStream;
@@ -66,7 +67,7 @@ op!(
     op: ImplDeserializeOp,
     inputs: {},
     outputs: {},
-    attributes: {name: syn::Ident, generics: syn::Generics},
+    attributes: {name: syn::Ident, generics: syn::Generics, seeking: bool, borrowed: Option<syn::Lifetime>},
     regions: {body},
     terminator: false
 );
@@ -78,10 +79,27 @@ impl ToTokens for ImplDeserializeOp {
         let body = &self.body;
         let deserializer = body.arguments()[0];
 
+        let deserialize_trait = match (self.seeking, &self.borrowed) {
+            (_, Some(lifetime)) => quote! { #DESERIALIZE_BORROWED_TRAIT<#lifetime> },
+            (false, None) => quote! { #DESERIALIZE_TRAIT },
+            (true, None) => quote! { #MULTI_PASS_DESERIALIZE_TRAIT },
+        };
+
+        let deserializer_trait = match (self.seeking, &self.borrowed) {
+            (_, Some(lifetime)) => quote! { #BORROW_DESERIALIZER_TRAIT<#lifetime> },
+            (false, None) => quote! { #DESERIALIZER_TRAIT },
+            (true, None) => quote! { #SEEKABLE_DESERIALIZER_TRAIT },
+        };
+
+        let deserialize_fn = match &self.borrowed {
+            Some(_) => quote! { deserialize_borrowed },
+            None => quote! { deserialize },
+        };
+
         tokens.extend(quote! {
             #[automatically_derived]
-            impl #impl_generics #DESERIALIZE_TRAIT for #name #ty_generics #where_clause{
-                fn deserialize<#DESERIALIZER_TYPE: #DESERIALIZER_TRAIT>(
+            impl #impl_generics #deserialize_trait for #name #ty_generics #where_clause{
+                fn #deserialize_fn<#DESERIALIZER_TYPE: #deserializer_trait>(
                     #deserializer: &mut #DESERIALIZER_TYPE
                 ) -> ::core::result::Result<
                         Self,
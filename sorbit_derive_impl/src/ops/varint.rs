@@ -0,0 +1,40 @@
+use crate::ir::op;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+
+op!(
+    name: "encoded_varint",
+    builder: encoded_varint,
+    op: EncodedVarIntOp,
+    inputs: {value},
+    outputs: {encoded},
+    attributes: {},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for EncodedVarIntOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = &self.value;
+        tokens.extend(quote! { ::sorbit::varint::encoded(#value) })
+    }
+}
+
+op!(
+    name: "deserialize_varint",
+    builder: deserialize_varint,
+    op: DeserializeVarIntOp,
+    inputs: {deserializer},
+    outputs: {value},
+    attributes: {ty: syn::Type},
+    regions: {},
+    terminator: false
+);
+
+impl ToTokens for DeserializeVarIntOp {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let deserializer = &self.deserializer;
+        let ty = &self.ty;
+        tokens.extend(quote! { ::sorbit::varint::deserialize_encoded::<#ty, _>(#deserializer) })
+    }
+}
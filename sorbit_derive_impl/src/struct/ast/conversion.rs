@@ -2,15 +2,16 @@ use std::collections::{HashMap, HashSet};
 use std::ops::Range;
 
 use proc_macro2::Span;
+use quote::ToTokens;
 use syn::spanned::Spanned;
-use syn::{Ident, Member, Type};
+use syn::{Expr, Ident, Member, Type};
 
 use super::super::parse;
 use super::field::Field;
-use crate::attribute::{BitNumbering, ByteOrder, Transform};
+use crate::attribute::{Backward, BitNumbering, ByteOrder, LengthSource, SizePrefix, Transform, path};
 use crate::r#struct::ast::field::BitFieldMember;
 use crate::r#struct::parse::{BitFieldStorageProperties, FieldLayoutProperties};
-use crate::utility::to_member;
+use crate::utility::{bit_width, borrowed_lifetime, detach_expr, to_member};
 
 pub fn add_symmetric_transforms(mut fields: Vec<parse::Field>) -> Result<Vec<parse::Field>, syn::Error> {
     let members: Vec<_> = fields
@@ -30,24 +31,46 @@ pub fn add_symmetric_transforms(mut fields: Vec<parse::Field>) -> Result<Vec<par
 
     for field_idx in 0..fields.len() {
         use Transform::{ByteCount, ByteCountBy, Length, LengthBy};
-        let (pair_idx, pair_follows, pair_desired_transform) = match fields[field_idx].transform() {
+        let (pair_idx, pair_follows, pair_desired_transform, pair_span) = match fields[field_idx].transform() {
             Transform::None => continue,
-            Length(member) => (find_pair(member)?, true, LengthBy(members[field_idx].clone())),
-            ByteCount(member) => (find_pair(member)?, true, ByteCountBy(members[field_idx].clone())),
-            LengthBy(member) => (find_pair(member)?, false, Length(members[field_idx].clone())),
-            ByteCountBy(member) => (find_pair(member)?, false, ByteCount(members[field_idx].clone())),
+            Length(member) => (find_pair(member)?, true, LengthBy(members[field_idx].clone()), member.span()),
+            ByteCount(member) => (find_pair(member)?, true, ByteCountBy(members[field_idx].clone()), member.span()),
+            LengthBy(member) => (find_pair(member)?, false, Length(members[field_idx].clone()), member.span()),
+            ByteCountBy(member) => (find_pair(member)?, false, ByteCount(members[field_idx].clone()), member.span()),
+            Transform::LengthFrom(_) => continue,
             Transform::Constant(_) => continue,
+            Transform::Duration { .. } => continue,
+            Transform::LengthPrefix(_) => continue,
+            Transform::String(_) => continue,
+            Transform::Option(_) => continue,
+            Transform::VarInt => continue,
+            Transform::Range { .. } => continue,
+            Transform::Scale { .. } => continue,
+            Transform::Magic(_) => continue,
+            Transform::Pointer { .. } => continue,
+            Transform::OffsetOf { member, .. } => {
+                if find_pair(member)? == field_idx {
+                    return Err(syn::Error::new(member.span(), "an offset cannot refer to its own field"));
+                }
+                continue;
+            }
+            Transform::With { .. } => continue,
+            Transform::Checksum { over, .. } => {
+                for member in over {
+                    if find_pair(member)? == field_idx {
+                        return Err(syn::Error::new(member.span(), "a checksum cannot cover its own field"));
+                    }
+                }
+                continue;
+            }
         };
 
-        if pair_follows && !(field_idx < pair_idx) {
-            return Err(syn::Error::new(
-                fields[field_idx].span(),
-                "`len` or `byte_count` must always precede the collection field",
-            ));
+        if pair_follows && field_idx >= pair_idx {
+            return Err(syn::Error::new(pair_span, "`len` or `byte_count` must always precede the collection field"));
         }
-        if !pair_follows && !(pair_idx < field_idx) {
+        if !pair_follows && pair_idx >= field_idx {
             return Err(syn::Error::new(
-                fields[field_idx].span(),
+                pair_span,
                 "`len_by` or `byte_count_by` must always follow the length or byte count field",
             ));
         }
@@ -70,6 +93,14 @@ pub fn add_symmetric_transforms(mut fields: Vec<parse::Field>) -> Result<Vec<par
 pub fn check_transforms<'a>(fields: impl Iterator<Item = &'a Field>) -> Result<(), syn::Error> {
     for field in fields {
         match field {
+            Field::Direct { member, condition: Some(_), transform, layout_properties, .. }
+                if *transform != Transform::None =>
+            {
+                return Err(syn::Error::new(
+                    layout_properties.spans.get_or(&path::condition(), member.span()),
+                    format!("a conditional field cannot also have its value set to `{transform}`"),
+                ));
+            }
             Field::Direct { .. } => (),
             Field::Bit { members, .. } => {
                 for member in members {
@@ -95,15 +126,104 @@ pub fn check_transforms<'a>(fields: impl Iterator<Item = &'a Field>) -> Result<(
     Ok(())
 }
 
+/// Check that only arrays and collections whose length or byte count is given
+/// by another field have a `stride`.
+pub fn check_strides<'a>(fields: impl Iterator<Item = &'a Field>) -> Result<(), syn::Error> {
+    for field in fields {
+        let Field::Direct { member, ty, deferred, boxed, borrow, transform, layout_properties, .. } = field else {
+            continue;
+        };
+        if layout_properties.stride.is_none() {
+            continue;
+        }
+        let strideable = match transform {
+            Transform::None => matches!(ty, Type::Array(_)),
+            Transform::LengthBy(_) | Transform::ByteCountBy(_) | Transform::LengthFrom(_) => true,
+            _ => false,
+        };
+        if !strideable || *deferred || *boxed || *borrow {
+            return Err(syn::Error::new(
+                layout_properties.spans.get_or(&path::stride(), member.span()),
+                "only arrays and collections with their length or byte count in another field can have a `stride`",
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_borrowed<'a>(fields: impl Iterator<Item = &'a Field> + Clone) -> Result<(), syn::Error> {
+    let mut lifetime = None;
+    for field in fields.clone() {
+        let Field::Direct { member, ty, borrow: true, transform, .. } = field else {
+            continue;
+        };
+        if matches!(ty, Type::Reference(_)) && *transform == Transform::None {
+            return Err(syn::Error::new(
+                member.span(),
+                "a borrowed slice or string must have its length given by another field or by `len_prefix`",
+            ));
+        }
+        match (borrowed_lifetime(ty), lifetime) {
+            (None, _) => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "a borrowed field must have a named lifetime, like `&'a [u8]` or `Packet<'a>`",
+                ));
+            }
+            (Some(current), Some(previous)) if current != previous => {
+                return Err(syn::Error::new(current.span(), "all borrowed fields must have the same lifetime"));
+            }
+            (Some(current), _) => lifetime = Some(current),
+        }
+    }
+    let backward = fields
+        .filter(|field| field.layout_properties().allow_backward.is_some())
+        .find_map(|field| field.members().first().map(|member| member.span()));
+    if let (Some(_), Some(span)) = (lifetime, backward) {
+        return Err(syn::Error::new(span, "a structure with borrowed fields cannot move backward"));
+    }
+    Ok(())
+}
+
 pub fn to_layout_fields(fields: impl Iterator<Item = parse::Field>) -> Result<Vec<LayoutField>, syn::Error> {
     let mut layout_fields = Vec::new();
     let mut layout_field_idents = HashSet::new();
+    let mut preceding_members = HashSet::new();
 
     for (index, field) in fields.enumerate() {
         match field {
-            parse::Field::Direct { ident, ty, multi_pass, transform, layout_properties } => {
+            parse::Field::Direct {
+                ident,
+                ty,
+                multi_pass,
+                deferred,
+                boxed,
+                borrow,
+                condition,
+                transform,
+                layout_properties,
+            } => {
                 let member = to_member(ident, index, ty.span());
-                layout_fields.push(LayoutField::Direct { member, ty, multi_pass, transform, layout_properties });
+                let condition =
+                    condition.map(|condition| detach_expr(&condition, &preceding_members, "condition")).transpose()?;
+                let transform = match transform {
+                    Transform::LengthFrom(LengthSource::Expr(len)) => {
+                        Transform::LengthFrom(LengthSource::Expr(detach_expr(&len, &preceding_members, "count")?))
+                    }
+                    transform => transform,
+                };
+                preceding_members.insert(member.clone());
+                layout_fields.push(LayoutField::Direct {
+                    member,
+                    ty,
+                    multi_pass,
+                    deferred,
+                    boxed,
+                    borrow,
+                    condition,
+                    transform,
+                    layout_properties,
+                });
             }
             parse::Field::Bit {
                 ident,
@@ -116,6 +236,7 @@ pub fn to_layout_fields(fields: impl Iterator<Item = parse::Field>) -> Result<Ve
                 layout_properties,
             } => {
                 let member = to_member(ident, index, ty.span());
+                preceding_members.insert(member.clone());
                 match layout_fields.last_mut() {
                     Some(LayoutField::Bit { ident, sub_fields }) if *ident == storage_ident => {
                         let sub_field = LayoutSubField {
@@ -156,11 +277,16 @@ pub fn to_layout_fields(fields: impl Iterator<Item = parse::Field>) -> Result<Ve
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
 pub enum LayoutField {
     Direct {
         member: Member,
         ty: Type,
         multi_pass: Option<bool>,
+        deferred: bool,
+        boxed: bool,
+        borrow: bool,
+        condition: Option<Expr>,
         transform: Transform,
         layout_properties: FieldLayoutProperties,
     },
@@ -182,20 +308,57 @@ pub struct LayoutSubField {
 }
 
 impl LayoutField {
-    pub fn into_field(self) -> Result<Field, syn::Error> {
+    pub fn into_field(self, default_bit_numbering: Option<BitNumbering>) -> Result<Field, syn::Error> {
         match self {
-            LayoutField::Direct { member, ty, multi_pass, transform, layout_properties } => {
-                Ok(Field::Direct { member, ty, multi_pass, transform, layout_properties })
-            }
+            LayoutField::Direct {
+                member,
+                ty,
+                multi_pass,
+                deferred,
+                boxed,
+                borrow,
+                condition,
+                transform,
+                layout_properties,
+            } => Ok(Field::Direct {
+                member,
+                ty,
+                multi_pass,
+                deferred,
+                boxed,
+                borrow,
+                condition,
+                transform,
+                layout_properties,
+            }),
             LayoutField::Bit { ident, sub_fields } => {
                 let ty = Self::find_storage_ty(sub_fields.iter(), ident.span())?;
-                let bit_numbering = Self::find_bit_numbering(sub_fields.iter())?.unwrap_or(BitNumbering::LSB0);
+                Self::check_overlapping_bits(&sub_fields)?;
+                Self::check_bit_width(&sub_fields, &ty)?;
+                let bit_numbering = Self::find_bit_numbering(sub_fields.iter())?
+                    .or(default_bit_numbering)
+                    .unwrap_or(BitNumbering::LSB0);
 
                 let byte_order = Self::find_byte_order(sub_fields.iter())?;
                 let offset = Self::find_offset(sub_fields.iter())?;
+                let allow_backward = Self::find_allow_backward(sub_fields.iter())?;
                 let align = Self::find_align(sub_fields.iter())?;
+                let align_absolute = Self::find_align_absolute(sub_fields.iter())?;
                 let round = Self::find_round(sub_fields.iter())?;
-                let layout_properties = FieldLayoutProperties { byte_order, offset, align, round };
+                let size_prefix = Self::find_size_prefix(sub_fields.iter())?;
+                let layout_properties = FieldLayoutProperties {
+                    byte_order,
+                    offset,
+                    allow_backward,
+                    align,
+                    align_absolute,
+                    round,
+                    stride: None,
+                    size_prefix,
+                    context: None,
+                    if_version: None,
+                    spans: Default::default(),
+                };
 
                 let members = sub_fields
                     .into_iter()
@@ -217,9 +380,88 @@ impl LayoutField {
         ty.cloned().ok_or(syn::Error::new(span, "the storage type of the bit field is not specified"))
     }
 
+    fn check_overlapping_bits(items: &[LayoutSubField]) -> Result<(), syn::Error> {
+        for (index, item) in items.iter().enumerate() {
+            let overlapped = items[..index]
+                .iter()
+                .find(|preceding| preceding.bits.start < item.bits.end && item.bits.start < preceding.bits.end);
+            if let Some(preceding) = overlapped {
+                return Err(syn::Error::new(
+                    item.layout_properties.spans.get_or(&path::bit_range(), item.member.span()),
+                    format!(
+                        "bits {}..{} of this member overlap with bits {}..{} of `{}`",
+                        item.bits.start,
+                        item.bits.end,
+                        preceding.bits.start,
+                        preceding.bits.end,
+                        preceding.member.to_token_stream()
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the members fit into the storage, and that they cover all of
+    /// its bits if the bit field is exhaustive.
+    ///
+    /// The width is only known for primitive integers and byte arrays, other
+    /// storage types are checked by their `PackInto` implementation.
+    fn check_bit_width(items: &[LayoutSubField], storage_ty: &Type) -> Result<(), syn::Error> {
+        let exhaustive = items.iter().find(|item| item.storage_properties.exhaustive);
+        let Some(width) = bit_width(storage_ty) else {
+            return match exhaustive {
+                Some(item) => Err(syn::Error::new(
+                    item.layout_properties.spans.get_or(&path::exhaustive(), item.member.span()),
+                    format!(
+                        "the width of the storage type `{}` is unknown, only primitive integers and byte arrays can be exhaustive",
+                        storage_ty.to_token_stream()
+                    ),
+                )),
+                None => Ok(()),
+            };
+        };
+        for item in items {
+            if u64::from(item.bits.end) > width {
+                return Err(syn::Error::new(
+                    item.layout_properties.spans.get_or(&path::bit_range(), item.member.span()),
+                    format!(
+                        "bits {}..{} of this member exceed the {width} bits of the storage type `{}`",
+                        item.bits.start,
+                        item.bits.end,
+                        storage_ty.to_token_stream()
+                    ),
+                ));
+            }
+        }
+        if let Some(item) = exhaustive {
+            let mut bits: Vec<_> =
+                items.iter().map(|item| u64::from(item.bits.start)..u64::from(item.bits.end)).collect();
+            bits.sort_by_key(|bits| bits.start);
+            let mut gaps = Vec::new();
+            let mut covered = 0;
+            for bits in bits.into_iter().chain(std::iter::once(width..width)) {
+                if covered < bits.start {
+                    gaps.push(format!("{covered}..{}", bits.start));
+                }
+                covered = covered.max(bits.end);
+            }
+            if !gaps.is_empty() {
+                return Err(syn::Error::new(
+                    item.layout_properties.spans.get_or(&path::exhaustive(), item.member.span()),
+                    format!("bits {} of the exhaustive bit field are not covered by any member", gaps.join(", ")),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn find_byte_order<'a>(items: impl Iterator<Item = &'a LayoutSubField>) -> Result<Option<ByteOrder>, syn::Error> {
-        let iter = items
-            .filter_map(|item| item.layout_properties.byte_order.map(|byte_order| (byte_order, item.member.span())));
+        let iter = items.filter_map(|item| {
+            item.layout_properties.byte_order.map(|byte_order| {
+                (byte_order, item.layout_properties.spans.get_or(&path::byte_order(), item.member.span()))
+            })
+        });
         all_same_or_error(iter, "the byte order of the bit field is redefined with a different value")
     }
 
@@ -227,36 +469,81 @@ impl LayoutField {
         items: impl Iterator<Item = &'a LayoutSubField>,
     ) -> Result<Option<BitNumbering>, syn::Error> {
         let iter = items.filter_map(|item| {
-            item.storage_properties.bit_numbering.map(|bit_numbering| (bit_numbering, item.member.span()))
+            item.storage_properties.bit_numbering.map(|bit_numbering| {
+                (bit_numbering, item.layout_properties.spans.get_or(&path::bit_numbering(), item.member.span()))
+            })
         });
         all_same_or_error(iter, "the bit numbering of the bit field is redefined with a different value")
     }
 
     fn find_offset<'a>(items: impl Iterator<Item = &'a LayoutSubField>) -> Result<Option<u64>, syn::Error> {
-        let iter = items.filter_map(|item| item.layout_properties.offset.map(|offset| (offset, item.member.span())));
+        let iter = items.filter_map(|item| {
+            item.layout_properties
+                .offset
+                .map(|offset| (offset, item.layout_properties.spans.get_or(&path::offset(), item.member.span())))
+        });
         all_same_or_error(iter, "the offset of the bit field is redefined with a different value")
     }
 
+    fn find_allow_backward<'a>(
+        items: impl Iterator<Item = &'a LayoutSubField>,
+    ) -> Result<Option<Backward>, syn::Error> {
+        let iter = items.filter_map(|item| {
+            item.layout_properties.allow_backward.map(|allow_backward| {
+                (allow_backward, item.layout_properties.spans.get_or(&path::allow_backward(), item.member.span()))
+            })
+        });
+        all_same_or_error(iter, "moving backward in the bit field is redefined with a different value")
+    }
+
     fn find_align<'a>(items: impl Iterator<Item = &'a LayoutSubField>) -> Result<Option<u64>, syn::Error> {
-        let iter = items.filter_map(|item| item.layout_properties.align.map(|align| (align, item.member.span())));
+        let iter = items.filter_map(|item| {
+            item.layout_properties
+                .align
+                .map(|align| (align, item.layout_properties.spans.get_or(&path::align(), item.member.span())))
+        });
         all_same_or_error(iter, "alignment of the bit field is redefined with a different value")
     }
 
+    fn find_align_absolute<'a>(items: impl Iterator<Item = &'a LayoutSubField>) -> Result<Option<u64>, syn::Error> {
+        let iter = items.filter_map(|item| {
+            item.layout_properties.align_absolute.map(|align_absolute| {
+                (align_absolute, item.layout_properties.spans.get_or(&path::align_absolute(), item.member.span()))
+            })
+        });
+        all_same_or_error(iter, "absolute alignment of the bit field is redefined with a different value")
+    }
+
     fn find_round<'a>(items: impl Iterator<Item = &'a LayoutSubField>) -> Result<Option<u64>, syn::Error> {
-        let iter = items.filter_map(|item| item.layout_properties.round.map(|round| (round, item.member.span())));
+        let iter = items.filter_map(|item| {
+            item.layout_properties
+                .round
+                .map(|round| (round, item.layout_properties.spans.get_or(&path::round(), item.member.span())))
+        });
         all_same_or_error(iter, "rounding of the bit field is redefined with a different value")
     }
+
+    fn find_size_prefix<'a>(items: impl Iterator<Item = &'a LayoutSubField>) -> Result<Option<SizePrefix>, syn::Error> {
+        let iter = items.filter_map(|item| {
+            item.layout_properties.size_prefix.clone().map(|size_prefix| {
+                (size_prefix, item.layout_properties.spans.get_or(&path::size_prefix(), item.member.span()))
+            })
+        });
+        all_same_or_error(iter, "the size prefix of the bit field is redefined with a different value")
+    }
 }
 
 fn all_same_or_error<T: PartialEq>(
     mut iter: impl Iterator<Item = (T, Span)>,
     message: &str,
 ) -> Result<Option<T>, syn::Error> {
-    let Some((value, _)) = iter.next() else {
+    let Some((value, first_span)) = iter.next() else {
         return Ok(None);
     };
     if let Some((_, span)) = iter.find(|(maybe_different, _)| *maybe_different != value) {
-        Err(syn::Error::new(span, message))
+        let mut error = syn::Error::new(span, message);
+        error.combine(syn::Error::new(first_span, "the previous definition is here"));
+        Err(error)
     } else {
         Ok(Some(value))
     }
@@ -278,6 +565,10 @@ mod tests {
                 ident: Some(parse_quote!(value)),
                 ty: parse_quote!(u8),
                 multi_pass: None,
+                deferred: false,
+                boxed: false,
+                borrow: false,
+                condition: None,
                 transform,
                 layout_properties: Default::default(),
             }
@@ -288,6 +579,10 @@ mod tests {
                 ident: Some(parse_quote!(collection)),
                 ty: parse_quote!(u8),
                 multi_pass: None,
+                deferred: false,
+                boxed: false,
+                borrow: false,
+                condition: None,
                 transform,
                 layout_properties: Default::default(),
             }
@@ -373,6 +668,10 @@ mod tests {
                     ident: Some(parse_quote!(foo)),
                     ty: parse_quote!(u8),
                     multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: Default::default(),
                 },
@@ -390,6 +689,10 @@ mod tests {
                     ident: None,
                     ty: parse_quote!(u32),
                     multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: Default::default(),
                 },
@@ -400,6 +703,10 @@ mod tests {
                     member: parse_quote!(foo),
                     ty: parse_quote!(u8),
                     multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: Default::default(),
                 },
@@ -419,6 +726,10 @@ mod tests {
                     member: parse_quote!(2),
                     ty: parse_quote!(u32),
                     multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: Default::default(),
                 },
@@ -579,6 +890,50 @@ mod tests {
             assert!(LayoutField::find_storage_ty(items.iter(), Span::call_site()).is_err());
         }
 
+        #[test]
+        fn check_overlapping_bits_disjoint() {
+            let mut items = make_items();
+            items[1].bits = 4..6;
+            items[2].bits = 6..7;
+            assert!(LayoutField::check_overlapping_bits(&items).is_ok());
+        }
+
+        #[test]
+        fn check_overlapping_bits_overlapping() {
+            let mut items = make_items();
+            items[1].bits = 4..6;
+            items[2].bits = 5..7;
+            assert!(LayoutField::check_overlapping_bits(&items).is_err());
+        }
+
+        #[test]
+        fn check_bit_width_fits() {
+            let mut items = make_items();
+            items[1].bits = 4..6;
+            items[2].bits = 6..8;
+            assert!(LayoutField::check_bit_width(&items, &parse_quote!(u8)).is_ok());
+        }
+
+        #[test]
+        fn check_bit_width_exceeds() {
+            let mut items = make_items();
+            items[2].bits = 6..9;
+            assert!(LayoutField::check_bit_width(&items, &parse_quote!(u8)).is_err());
+            assert!(LayoutField::check_bit_width(&items, &parse_quote!([u8; 2])).is_ok());
+        }
+
+        #[test]
+        fn check_bit_width_exhaustive() {
+            let mut items = make_items();
+            items[0].storage_properties.exhaustive = true;
+            items[1].bits = 4..6;
+            items[2].bits = 6..8;
+            assert!(LayoutField::check_bit_width(&items, &parse_quote!(u8)).is_ok());
+            items[1].bits = 5..6;
+            assert!(LayoutField::check_bit_width(&items, &parse_quote!(u8)).is_err());
+            assert!(LayoutField::check_bit_width(&items, &parse_quote!(Flags)).is_err());
+        }
+
         #[test]
         fn find_offset_none() {
             let items = make_items();
@@ -1,21 +1,33 @@
 use std::ops::Range;
 
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::ext::IdentExt as _;
 use syn::parse_quote;
 use syn::spanned::Spanned;
-use syn::{Ident, Member, Type};
+use syn::{Expr, Generics, Ident, Member, Type, WherePredicate};
 
-use crate::attribute::BitNumbering;
-use crate::attribute::Transform;
+use crate::attribute::{Backward, BitNumbering};
+use crate::attribute::{LengthSource, OptionEncoding, SizePrefix, StringEncoding, Transform};
 use crate::ir::{Region, ToDeserializeOp, ToSerializeOp, Value};
 use crate::ops::algorithm::with_field_layout;
-use crate::ops::constants::BIT_FIELD_TYPE;
+use crate::ops::constants::{
+    DESERIALIZE_BORROWED_TRAIT, DESERIALIZE_BOXED_TRAIT, DESERIALIZE_TRAIT, ITEMS_LAYOUT_FN, LAYOUT_TYPE,
+    MULTI_PASS_SERIALIZE_TRAIT, SERIALIZE_TRAIT, STATIC_LAYOUT_TRAIT, STRIDED_ARRAY_LAYOUT_FN, STRIDED_ITEMS_LAYOUT_FN,
+};
 use crate::ops::{
-    check_eq, custom_expr, deserialize_items_by_byte_count, deserialize_items_by_len, deserialize_object,
-    empty_bit_field, items, len, ok, pack_bit_field, ref_, serialize_object, symref, try_, unpack_bit_field,
+    bit_field_type, check_eq, check_len, check_range, context_value, custom_expr, deserialize_borrowed_by_len,
+    deserialize_borrowed_len_prefixed, deserialize_borrowed_object, deserialize_boxed, deserialize_encoded,
+    deserialize_encoded_option, deserialize_field, deserialize_items_by_byte_count, deserialize_items_by_len,
+    deserialize_len_prefixed, deserialize_object, deserialize_pointee, deserialize_strided_array,
+    deserialize_strided_items_by_byte_count, deserialize_strided_items_by_len, deserialize_varint, deserialize_with,
+    duration_as, duration_from, empty_bit_field, encoded, encoded_option, encoded_varint, expect_context_value,
+    expect_some, items, len, len_prefixed, magic, ok, option_encoding_tokens, pack_bit_field, position, ref_,
+    scaled_as, scaled_from, serialize_object, serialize_with, set_context, some, strided, string_encoding_tokens, sym,
+    symref, trace, try_, unpack_bit_field,
 };
 use crate::r#struct::parse::FieldLayoutProperties;
-use crate::utility::{PhantomType, member_to_ident};
+use crate::utility::{PhantomType, borrowed_lifetime, boxed_type, member_to_ident, optional_type, uses_type_params};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BitFieldMember {
@@ -35,16 +47,20 @@ impl BitFieldMember {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
 pub enum Field {
     Direct {
         member: Member,
         ty: Type,
         multi_pass: Option<bool>,
+        deferred: bool,
+        boxed: bool,
+        borrow: bool,
+        condition: Option<Expr>,
         transform: Transform,
         layout_properties: FieldLayoutProperties,
     },
     Bit {
-        #[allow(unused)]
         ident: Ident,
         ty: Type,
         bit_numbering: BitNumbering,
@@ -67,6 +83,252 @@ impl Field {
             Field::Bit { members, .. } => members.iter().map(|member| &member.ty).collect(),
         }
     }
+
+    /// Return the name of the field in its `FieldLayout`.
+    pub fn layout_name(&self) -> String {
+        match self {
+            Field::Direct { member, .. } => member_name(member),
+            Field::Bit { ident, .. } => ident.unraw().to_string(),
+        }
+    }
+
+    /// Return the expression of the layout of the `preceding` fields followed by this field.
+    ///
+    /// Overlays are not serialized, so they don't extend the preceding fields,
+    /// and reordered fields overlap them.
+    pub fn to_layout_tokens(&self, preceding: TokenStream) -> TokenStream {
+        let value = self.to_layout_value_tokens();
+        match self.layout_properties().allow_backward {
+            Some(Backward::Overlay) => preceding,
+            Some(Backward::Reorder) => {
+                let start = self.to_layout_start_tokens(quote! { #LAYOUT_TYPE::EMPTY });
+                quote! { #preceding .overlap(#start .then(#value)) }
+            }
+            _ => {
+                let start = self.to_layout_start_tokens(preceding);
+                quote! { #start .then(#value) }
+            }
+        }
+    }
+
+    /// Return the expression of the layout of the `preceding` fields padded
+    /// until the beginning of this field.
+    ///
+    /// Overlays and reordered fields begin at their offset regardless of the
+    /// preceding fields.
+    pub fn to_layout_start_tokens(&self, preceding: TokenStream) -> TokenStream {
+        let FieldLayoutProperties { offset, allow_backward, align, align_absolute, .. } = self.layout_properties();
+        let align = align.map(|align| quote! { .align(#align) });
+        let align_absolute = align_absolute.map(|align_absolute| quote! { .align_absolute(#align_absolute) });
+        let align = quote! { #align #align_absolute };
+        match offset {
+            Some(offset) if matches!(allow_backward, Some(Backward::Overlay | Backward::Reorder)) => {
+                quote! { #LAYOUT_TYPE::fixed(#offset) #align }
+            }
+            Some(offset) => quote! { #preceding .pad(#offset) #align },
+            None => quote! { #preceding #align },
+        }
+    }
+
+    /// Return whether the field views the bytes of other fields via
+    /// `allow_backward = overlay`.
+    pub fn is_overlay(&self) -> bool {
+        self.layout_properties().allow_backward == Some(Backward::Overlay)
+    }
+
+    /// Store the value of the field in the context value given by `ctx`, if
+    /// any, once the field has been (de)serialized.
+    pub fn set_maybe_context(&self, region: &mut Region, serializer: Value, is_serializing: bool) {
+        if let Field::Direct { member, layout_properties: FieldLayoutProperties { context: Some(key), .. }, .. } = self
+        {
+            let value = symref(region, member_to_ident(member.clone()));
+            let result = set_context(region, serializer, value, key.clone(), is_serializing);
+            try_(region, result);
+        }
+    }
+
+    /// Return the expression of the layout of this field alone.
+    pub fn to_layout_value_tokens(&self) -> TokenStream {
+        let value = match self {
+            Field::Direct { ty, condition, transform, .. } => match transform {
+                Transform::None => {
+                    let ty = present_type(ty, condition.as_ref());
+                    quote! { <#ty as #STATIC_LAYOUT_TRAIT>::LAYOUT }
+                }
+                Transform::Length(_) | Transform::ByteCount(_) | Transform::Constant(_) => {
+                    let ty = ty.phantom_underlying_type();
+                    quote! { <#ty as #STATIC_LAYOUT_TRAIT>::LAYOUT }
+                }
+                Transform::Checksum { .. } | Transform::Range { .. } | Transform::OffsetOf { .. } => {
+                    quote! { <#ty as #STATIC_LAYOUT_TRAIT>::LAYOUT }
+                }
+                Transform::VarInt => quote! { <#ty as ::sorbit::varint::VarInt>::LAYOUT },
+                // The encoding of custom functions is unknown.
+                Transform::With { .. } => quote! { #LAYOUT_TYPE::new(0, None, 0) },
+                Transform::LengthBy(_) | Transform::ByteCountBy(_) | Transform::LengthFrom(_) => {
+                    quote! { #ITEMS_LAYOUT_FN::<#ty>() }
+                }
+                Transform::Duration { repr, .. } => quote! { <#repr as #STATIC_LAYOUT_TRAIT>::LAYOUT },
+                Transform::Scale { raw, .. } => quote! { <#raw as #STATIC_LAYOUT_TRAIT>::LAYOUT },
+                // The object itself is laid out after the structure.
+                Transform::Pointer { offset, .. } => quote! { <#offset as #STATIC_LAYOUT_TRAIT>::LAYOUT },
+                Transform::Magic(bytes) => {
+                    let len = bytes.value().len() as u64;
+                    quote! { <u8 as #STATIC_LAYOUT_TRAIT>::LAYOUT.repeat(#len) }
+                }
+                Transform::LengthPrefix(len_ty) => {
+                    quote! { <#len_ty as #STATIC_LAYOUT_TRAIT>::LAYOUT.then(#ITEMS_LAYOUT_FN::<#ty>()) }
+                }
+                Transform::String(encoding) => {
+                    let encoding = string_encoding_tokens(encoding);
+                    quote! { #encoding.layout() }
+                }
+                Transform::Option(encoding) => {
+                    let value_ty = option_value_type(ty);
+                    let tokens = option_encoding_tokens(encoding, value_ty);
+                    match encoding {
+                        OptionEncoding::Sentinel(_) => quote! { #tokens.layout() },
+                        _ => quote! { #tokens.layout::<#value_ty>() },
+                    }
+                }
+            },
+            Field::Bit { ty, .. } => {
+                let bit_field_ty = bit_field_type(ty);
+                quote! { <#bit_field_ty as #STATIC_LAYOUT_TRAIT>::LAYOUT }
+            }
+        };
+        let value = match (self, self.layout_properties().stride) {
+            (Field::Direct { ty: Type::Array(array), transform: Transform::None, .. }, Some(stride)) => {
+                let (item_ty, len) = (&array.elem, &array.len);
+                quote! { #STRIDED_ARRAY_LAYOUT_FN::<#item_ty, { #len }>(#stride) }
+            }
+            (Field::Direct { ty, .. }, Some(stride)) => quote! { #STRIDED_ITEMS_LAYOUT_FN::<#ty>(#stride) },
+            _ => value,
+        };
+        let value = match &self.layout_properties().size_prefix {
+            Some(SizePrefix { ty, .. }) => quote! { <#ty as #STATIC_LAYOUT_TRAIT>::LAYOUT.then(#value).nest() },
+            None => value,
+        };
+        let value = match self.layout_properties().round {
+            Some(round) => quote! { #value.align(#round).nest() },
+            None => value,
+        };
+        match self {
+            Field::Direct { condition: Some(_), .. } => quote! { #LAYOUT_TYPE::EMPTY.or(#value) },
+            _ => value,
+        }
+    }
+
+    /// Return the expression of the layouts of the fields nested in this field,
+    /// if the field's type is serialized as is.
+    pub fn to_layout_fields_tokens(&self) -> Option<TokenStream> {
+        match self {
+            Field::Direct { ty, condition: None, transform: Transform::None, layout_properties, .. }
+                if layout_properties.stride.is_none() && layout_properties.size_prefix.is_none() =>
+            {
+                Some(quote! { <#ty as #STATIC_LAYOUT_TRAIT>::FIELDS })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn layout_properties(&self) -> &FieldLayoutProperties {
+        match self {
+            Field::Direct { layout_properties, .. } => layout_properties,
+            Field::Bit { layout_properties, .. } => layout_properties,
+        }
+    }
+
+    /// Return the bound that the field's type must satisfy for the layout to
+    /// be known, if the type is generic.
+    pub fn layout_predicate(&self, generics: &Generics) -> Option<WherePredicate> {
+        let (ty, predicate): (_, WherePredicate) = match self {
+            Field::Direct { ty, condition, transform, .. } => match transform {
+                Transform::None => {
+                    let ty = present_type(ty, condition.as_ref());
+                    (ty, parse_quote!(#ty: #STATIC_LAYOUT_TRAIT))
+                }
+                Transform::Length(_) | Transform::ByteCount(_) | Transform::Constant(_) => {
+                    let ty = ty.phantom_underlying_type();
+                    (ty, parse_quote!(#ty: #STATIC_LAYOUT_TRAIT))
+                }
+                Transform::Checksum { .. } | Transform::Range { .. } | Transform::OffsetOf { .. } => {
+                    (ty, parse_quote!(#ty: #STATIC_LAYOUT_TRAIT))
+                }
+                Transform::VarInt => (ty, parse_quote!(#ty: ::sorbit::varint::VarInt)),
+                Transform::LengthBy(_)
+                | Transform::ByteCountBy(_)
+                | Transform::LengthFrom(_)
+                | Transform::LengthPrefix(_) => {
+                    (ty, parse_quote!(#ty: ::core::iter::IntoIterator<Item: #STATIC_LAYOUT_TRAIT>))
+                }
+                Transform::Duration { repr, .. } => (repr, parse_quote!(#repr: #STATIC_LAYOUT_TRAIT)),
+                Transform::Scale { raw, .. } => (raw, parse_quote!(#raw: #STATIC_LAYOUT_TRAIT)),
+                Transform::Pointer { offset, .. } => (offset, parse_quote!(#offset: #STATIC_LAYOUT_TRAIT)),
+                Transform::String(StringEncoding::LengthPrefix(len_ty)) => {
+                    (len_ty, parse_quote!(#len_ty: #STATIC_LAYOUT_TRAIT))
+                }
+                Transform::String(_) | Transform::With { .. } | Transform::Magic(_) => return None,
+                Transform::Option(_) => {
+                    let value_ty = option_value_type(ty);
+                    (value_ty, parse_quote!(#value_ty: #STATIC_LAYOUT_TRAIT))
+                }
+            },
+            Field::Bit { ty, .. } => (ty, parse_quote!(#ty: #STATIC_LAYOUT_TRAIT)),
+        };
+        uses_type_params(ty, generics).then_some(predicate)
+    }
+
+    /// Return the bound that the field's type must satisfy to be serialized,
+    /// if the type is generic.
+    ///
+    /// Only fields that are serialized via their own traits are bounded, which
+    /// excludes overlays.
+    pub fn serialize_predicate(&self, generics: &Generics) -> Option<WherePredicate> {
+        let Field::Direct {
+            ty,
+            multi_pass,
+            deferred: false,
+            condition,
+            transform: Transform::None | Transform::Pointer { .. },
+            ..
+        } = self
+        else {
+            return None;
+        };
+        if self.is_overlay() {
+            return None;
+        }
+        let ty = present_type(ty, condition.as_ref());
+        let predicate = match multi_pass {
+            Some(true) => parse_quote!(#ty: #MULTI_PASS_SERIALIZE_TRAIT),
+            _ => parse_quote!(#ty: #SERIALIZE_TRAIT),
+        };
+        uses_type_params(ty, generics).then_some(predicate)
+    }
+
+    /// Return the bound that the field's type must satisfy to be deserialized,
+    /// if the type is generic.
+    ///
+    /// Only fields that are deserialized via their own traits are bounded.
+    pub fn deserialize_predicate(&self, generics: &Generics) -> Option<WherePredicate> {
+        let Field::Direct {
+            ty, boxed, borrow, condition, transform: Transform::None | Transform::Pointer { .. }, ..
+        } = self
+        else {
+            return None;
+        };
+        let lifetime = borrowed_lifetime(ty).filter(|_| *borrow);
+        let (ty, predicate): (_, WherePredicate) = match (boxed_type(ty).filter(|_| *boxed), lifetime) {
+            (Some(ty), _) => (ty, parse_quote!(#ty: #DESERIALIZE_BOXED_TRAIT)),
+            (None, Some(lifetime)) => (ty, parse_quote!(#ty: #DESERIALIZE_BORROWED_TRAIT<#lifetime>)),
+            (None, None) => {
+                let ty = present_type(ty, condition.as_ref());
+                (ty, parse_quote!(#ty: #DESERIALIZE_TRAIT))
+            }
+        };
+        uses_type_params(ty, generics).then_some(predicate)
+    }
 }
 
 impl ToSerializeOp for Field {
@@ -74,31 +336,78 @@ impl ToSerializeOp for Field {
 
     fn to_serialize_op(&self, region: &mut Region, (serializer, use_padding): (Value, bool)) -> Vec<Value> {
         match self {
-            Field::Direct { member, ty, multi_pass, transform, layout_properties, .. } => {
+            Field::Direct { member, ty, multi_pass, condition, transform, layout_properties, .. } => {
                 let layout = &conditionally_padded_layout(layout_properties, use_padding);
-                let result = with_layout(region, serializer, true, layout, |region, serializer| {
-                    let field = symref(region, member_to_ident(member.clone()));
-                    let transformed = serialize_transform(region, serializer, field, ty, transform);
-                    serialize_object(region, serializer, transformed, multi_pass.unwrap_or(false))
-                });
+                let name = &self.layout_name();
+                let condition = condition.as_ref();
+                let position = position(region, serializer, true);
+                let result =
+                    with_layout(region, serializer, position, true, name, layout, condition, |region, serializer| {
+                        let field = symref(region, member_to_ident(member.clone()));
+                        let field = match condition.and(optional_type(ty)) {
+                            Some(_) => {
+                                let result_present = expect_some(region, serializer, field);
+                                try_(region, result_present)
+                            }
+                            None => field,
+                        };
+                        match transform {
+                            Transform::With { serialize, .. } => {
+                                serialize_with(region, serializer, field, serialize.clone())
+                            }
+                            Transform::Magic(bytes) => magic(region, serializer, parse_quote!(#bytes), true),
+                            Transform::Pointer { offset, .. } => {
+                                // The offset is revised once the object is serialized after the structure.
+                                let placeholder =
+                                    custom_expr(region, parse_quote!(<#offset as ::core::default::Default>::default()));
+                                let placeholder = ref_(region, placeholder);
+                                serialize_object(region, serializer, placeholder, false)
+                            }
+                            _ => {
+                                let transformed = match layout.stride {
+                                    Some(stride) => {
+                                        if let Transform::LengthFrom(source) = transform {
+                                            check_length_from(region, serializer, field, source);
+                                        }
+                                        let strided = strided(region, field, stride);
+                                        ref_(region, strided)
+                                    }
+                                    None => serialize_transform(region, serializer, field, ty, transform),
+                                };
+                                serialize_object(region, serializer, transformed, multi_pass.unwrap_or(false))
+                            }
+                        }
+                    });
                 vec![result]
             }
             Field::Bit { ty, bit_numbering, layout_properties, members, .. } => {
                 let layout = &conditionally_padded_layout(layout_properties, use_padding);
-                let result = with_layout(region, serializer, true, layout, |region, serializer| {
-                    let mut bit_field = empty_bit_field(region, ty.clone());
-
-                    for BitFieldMember { member, ty, transform, bits, .. } in members {
-                        let field = symref(region, member_to_ident(member.clone()));
-                        let transformed = serialize_transform(region, serializer, field, ty, transform);
-                        let result_new_bit_field =
-                            pack_bit_field(region, transformed, bit_field, bits.clone(), *bit_numbering);
-                        bit_field = try_(region, result_new_bit_field);
-                    }
+                let name = &self.layout_name();
+                let position = position(region, serializer, true);
+                let result =
+                    with_layout(region, serializer, position, true, name, layout, None, |region, serializer| {
+                        let mut bit_field = empty_bit_field(region, ty.clone());
+
+                        for BitFieldMember { member, ty, transform, bits, .. } in members {
+                            let body = Region::build(|region, [serializer]| {
+                                let field = symref(region, member_to_ident(member.clone()));
+                                let transformed = serialize_transform(region, serializer, field, ty, transform);
+                                vec![pack_bit_field(
+                                    region,
+                                    transformed,
+                                    bit_field,
+                                    bits.clone(),
+                                    *bit_numbering,
+                                )]
+                            });
+                            let result_new_bit_field =
+                                trace(region, serializer, position, member_name(member), true, body);
+                            bit_field = try_(region, result_new_bit_field);
+                        }
 
-                    let bit_field_ref = ref_(region, bit_field);
-                    serialize_object(region, serializer, bit_field_ref, false)
-                });
+                        let bit_field_ref = ref_(region, bit_field);
+                        serialize_object(region, serializer, bit_field_ref, false)
+                    });
                 vec![result]
             }
         }
@@ -110,69 +419,314 @@ impl ToDeserializeOp for Field {
 
     fn to_deserialize_op(&self, region: &mut Region, deserializer: Value) -> Vec<Value> {
         match self {
-            Field::Direct { ty, transform, layout_properties, .. } => {
-                let result =
-                    with_layout(region, deserializer, false, layout_properties, |region, de| match transform {
-                        Transform::None => deserialize_object(region, de, ty.clone()),
-                        Transform::Length(_) => deserialize_object(region, de, ty.phantom_underlying_type().clone()),
-                        Transform::ByteCount(_) => deserialize_object(region, de, ty.phantom_underlying_type().clone()),
-                        Transform::LengthBy(len_by) => {
-                            let len = symref(region, member_to_ident(len_by.clone()));
-                            deserialize_items_by_len(region, de, len, ty.clone())
+            Field::Direct { ty, boxed, borrow, condition, transform, layout_properties, .. } => {
+                let name = &self.layout_name();
+                let condition = condition.as_ref();
+                let position = position(region, deserializer, false);
+                let result = with_layout(
+                    region,
+                    deserializer,
+                    position,
+                    false,
+                    name,
+                    layout_properties,
+                    condition,
+                    |region, de| {
+                        if let Some(stride) = layout_properties.stride {
+                            return deserialize_strided(region, de, ty, transform, stride);
                         }
-                        Transform::ByteCountBy(byte_count_by) => {
-                            let byte_count = symref(region, member_to_ident(byte_count_by.clone()));
-                            deserialize_items_by_byte_count(region, de, byte_count, ty.clone())
+                        match transform {
+                            _ if *borrow => deserialize_borrowed(region, de, ty, transform),
+                            Transform::None => {
+                                match (boxed_type(ty).filter(|_| *boxed), condition.and(optional_type(ty))) {
+                                    (Some(ty), _) => deserialize_boxed(region, de, ty.clone()),
+                                    (None, Some(ty)) => {
+                                        let result = deserialize_object(region, de, ty.clone());
+                                        let value = try_(region, result);
+                                        let present = some(region, value);
+                                        ok(region, present)
+                                    }
+                                    (None, None) => deserialize_object(region, de, ty.clone()),
+                                }
+                            }
+                            Transform::Length(_) => {
+                                deserialize_object(region, de, ty.phantom_underlying_type().clone())
+                            }
+                            Transform::ByteCount(_) => {
+                                deserialize_object(region, de, ty.phantom_underlying_type().clone())
+                            }
+                            Transform::Checksum { .. } => deserialize_object(region, de, ty.clone()),
+                            Transform::OffsetOf { .. } => deserialize_object(region, de, ty.clone()),
+                            Transform::VarInt => deserialize_varint(region, de, ty.clone()),
+                            Transform::Range { min, max } => {
+                                let result = deserialize_object(region, de, ty.clone());
+                                let value = try_(region, result);
+                                check_range(region, de, value, min.clone(), max.clone());
+                                ok(region, value)
+                            }
+                            Transform::With { deserialize, .. } => deserialize_with(region, de, deserialize.clone()),
+                            Transform::Magic(bytes) => magic(region, de, parse_quote!(#bytes), false),
+                            Transform::Pointer { offset, base } => {
+                                let pointees = symref(region, parse_quote!(__sorbit_pointees));
+                                deserialize_pointee(
+                                    region,
+                                    de,
+                                    pointees,
+                                    offset.clone(),
+                                    *base,
+                                    Region::build(|region, [de]| vec![deserialize_object(region, de, ty.clone())]),
+                                )
+                            }
+                            Transform::LengthBy(len_by) => {
+                                let len = symref(region, member_to_ident(len_by.clone()));
+                                deserialize_items_by_len(region, de, len, ty.clone())
+                            }
+                            Transform::ByteCountBy(byte_count_by) => {
+                                let byte_count = symref(region, member_to_ident(byte_count_by.clone()));
+                                deserialize_items_by_byte_count(region, de, byte_count, ty.clone())
+                            }
+                            Transform::LengthFrom(source) => {
+                                let len = length_from(region, de, source, false);
+                                deserialize_items_by_len(region, de, len, ty.clone())
+                            }
+                            Transform::Constant(expr) => {
+                                let result = deserialize_object(region, de, ty.phantom_underlying_type().clone());
+                                let value = try_(region, result);
+                                let expected = custom_expr(region, expr.clone());
+                                check_eq(region, de, value, expected);
+                                ok(region, value)
+                            }
+                            Transform::Duration { unit, repr } => {
+                                let result = deserialize_object(region, de, repr.clone());
+                                let value = try_(region, result);
+                                duration_from(region, de, value, *unit)
+                            }
+                            Transform::Scale { scale, bias, raw } => {
+                                let result = deserialize_object(region, de, raw.clone());
+                                let value = try_(region, result);
+                                scaled_from(region, de, value, scale.clone(), bias.clone(), ty.clone())
+                            }
+                            Transform::LengthPrefix(len_ty) => {
+                                deserialize_len_prefixed(region, de, ty.clone(), len_ty.clone())
+                            }
+                            Transform::String(encoding) => deserialize_encoded(region, de, encoding.clone()),
+                            Transform::Option(encoding) => {
+                                let value_ty = option_value_type(ty).clone();
+                                deserialize_encoded_option(region, de, encoding.clone(), value_ty)
+                            }
                         }
-                        Transform::Constant(expr) => {
-                            let result = deserialize_object(region, de, ty.phantom_underlying_type().clone());
-                            let value = try_(region, result);
-                            let expected = custom_expr(region, expr.clone());
-                            check_eq(region, deserializer, value, expected);
-                            ok(region, value)
-                        }
-                    });
+                    },
+                );
                 vec![result]
             }
             Field::Bit { ty, bit_numbering, layout_properties, members, .. } => {
-                let result_raw_bits = with_layout(region, deserializer, false, layout_properties, |region, de| {
-                    deserialize_object(region, de, parse_quote!(#BIT_FIELD_TYPE <#ty>))
-                });
+                let name = &self.layout_name();
+                let bit_field_ty = bit_field_type(ty);
+                let position = position(region, deserializer, false);
+                let result_raw_bits =
+                    with_layout(region, deserializer, position, false, name, layout_properties, None, |region, de| {
+                        deserialize_object(region, de, parse_quote!(#bit_field_ty))
+                    });
                 let bit_field = try_(region, result_raw_bits);
 
-                let unpacked = members
+                // The members are traced within the bit field they are packed
+                // into, and located at its start, as when serializing.
+                members
                     .iter()
-                    .map(|BitFieldMember { ty, bits, .. }| {
-                        unpack_bit_field(
-                            region,
-                            bit_field,
-                            ty.phantom_underlying_type().clone(),
-                            bits.clone(),
-                            *bit_numbering,
-                        )
+                    .map(|BitFieldMember { member, ty, transform, bits }| {
+                        let member_body = Region::build(|region, [de]| {
+                            let result = match transform {
+                                Transform::Scale { scale, bias, raw } => {
+                                    let result =
+                                        unpack_bit_field(region, bit_field, raw.clone(), bits.clone(), *bit_numbering);
+                                    let value = try_(region, result);
+                                    scaled_from(region, de, value, scale.clone(), bias.clone(), ty.clone())
+                                }
+                                _ => unpack_bit_field(
+                                    region,
+                                    bit_field,
+                                    ty.phantom_underlying_type().clone(),
+                                    bits.clone(),
+                                    *bit_numbering,
+                                ),
+                            };
+                            vec![result]
+                        });
+                        let body = Region::build(|region, [de]| {
+                            vec![trace(
+                                region,
+                                de,
+                                position,
+                                member_name(member),
+                                false,
+                                member_body,
+                            )]
+                        });
+                        trace(region, deserializer, position, name.clone(), false, body)
                     })
-                    .collect();
-
-                unpacked
+                    .collect()
             }
         }
     }
 }
 
+fn deserialize_strided(
+    region: &mut Region,
+    deserializer: Value,
+    ty: &Type,
+    transform: &Transform,
+    stride: u64,
+) -> Value {
+    match (transform, ty) {
+        (Transform::LengthBy(len_by), _) => {
+            let len = symref(region, member_to_ident(len_by.clone()));
+            deserialize_strided_items_by_len(region, deserializer, len, ty.clone(), stride)
+        }
+        (Transform::ByteCountBy(byte_count_by), _) => {
+            let byte_count = symref(region, member_to_ident(byte_count_by.clone()));
+            deserialize_strided_items_by_byte_count(region, deserializer, byte_count, ty.clone(), stride)
+        }
+        (Transform::LengthFrom(source), _) => {
+            let len = length_from(region, deserializer, source, false);
+            deserialize_strided_items_by_len(region, deserializer, len, ty.clone(), stride)
+        }
+        // Only arrays can be strided without a transform, this is checked when parsed.
+        (_, Type::Array(array)) => {
+            deserialize_strided_array(region, deserializer, (*array.elem).clone(), array.len.clone(), stride)
+        }
+        _ => unreachable!("only arrays and collections by length or byte count can be strided"),
+    }
+}
+
+fn deserialize_borrowed(region: &mut Region, deserializer: Value, ty: &Type, transform: &Transform) -> Value {
+    match transform {
+        Transform::LengthBy(len_by) | Transform::ByteCountBy(len_by) => {
+            // The items of borrowed slices and strings are bytes, so their
+            // length and byte count are the same.
+            let len = symref(region, member_to_ident(len_by.clone()));
+            deserialize_borrowed_by_len(region, deserializer, len, ty.clone())
+        }
+        Transform::LengthFrom(source) => {
+            let len = length_from(region, deserializer, source, false);
+            deserialize_borrowed_by_len(region, deserializer, len, ty.clone())
+        }
+        Transform::LengthPrefix(len_ty) | Transform::String(StringEncoding::LengthPrefix(len_ty)) => {
+            deserialize_borrowed_len_prefixed(region, deserializer, ty.clone(), len_ty.clone())
+        }
+        _ => deserialize_borrowed_object(region, deserializer, ty.clone()),
+    }
+}
+
+/// Return a reference to the length of a collection given by `source`.
+fn length_from(region: &mut Region, serializer: Value, source: &LengthSource, is_serializing: bool) -> Value {
+    let len = match source {
+        LengthSource::Expr(expr) => custom_expr(region, expr.clone()),
+        LengthSource::Context(key) => {
+            let result = expect_context_value(region, serializer, key.clone(), is_serializing);
+            try_(region, result)
+        }
+    };
+    ref_(region, len)
+}
+
+/// Check that the length of the `collection` matches the length given by `source`.
+fn check_length_from(region: &mut Region, serializer: Value, collection: Value, source: &LengthSource) {
+    let len = length_from(region, serializer, source, true);
+    let result = check_len(region, serializer, collection, len);
+    try_(region, result);
+}
+
+/// Lay out the field named `field` around its `body`.
+///
+/// Errors are annotated with the name of the field, and located at the
+/// `position` where the field starts.
+#[allow(clippy::too_many_arguments)]
 fn with_layout(
     region: &mut Region,
     serializer: Value,
+    position: Value,
     is_serializing: bool,
+    field: &str,
     layout_properties: &FieldLayoutProperties,
+    condition: Option<&Expr>,
     body: impl FnOnce(&mut Region, Value) -> Value,
 ) -> Value {
-    let FieldLayoutProperties { byte_order, offset, align, round } = layout_properties;
-    with_field_layout(region, serializer, is_serializing, *byte_order, *offset, *align, *round, body)
+    let FieldLayoutProperties {
+        byte_order,
+        offset,
+        allow_backward,
+        align,
+        align_absolute,
+        round,
+        size_prefix,
+        if_version,
+        ..
+    } = layout_properties;
+    // The version requirement is desugared into a condition on the version.
+    if if_version.is_some() {
+        let version = context_value(region, serializer, "version".into(), is_serializing);
+        sym(region, version, parse_quote!(__sorbit_version));
+    }
+    // Deserializers are told which field they deserialize, so that they can
+    // track where it was read from.
+    let body = |region: &mut Region, serializer: Value| match is_serializing {
+        true => body(region, serializer),
+        false => deserialize_field(
+            region,
+            serializer,
+            field.into(),
+            Region::build(|region, [deserializer]| vec![body(region, deserializer)]),
+        ),
+    };
+    let traced_body = Region::build(|region, [serializer]| {
+        vec![with_field_layout(
+            region,
+            serializer,
+            is_serializing,
+            *byte_order,
+            *offset,
+            *allow_backward,
+            *align,
+            *align_absolute,
+            *round,
+            size_prefix.as_ref(),
+            condition,
+            body,
+        )]
+    });
+    trace(region, serializer, position, field.into(), is_serializing, traced_body)
+}
+
+/// Return the name of a `member` as it appears in error traces and layouts.
+fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+/// Return the type that is serialized for the field when it's present.
+///
+/// Conditional fields of type `Option<T>` serialize `T` when present.
+fn present_type<'ty>(ty: &'ty Type, condition: Option<&Expr>) -> &'ty Type {
+    condition.and(optional_type(ty)).unwrap_or(ty)
+}
+
+/// Return the type of the value of an optional field.
+///
+/// Fields with an option encoding are checked to be `Option<T>` when parsed.
+fn option_value_type(ty: &Type) -> &Type {
+    optional_type(ty).unwrap_or(ty)
 }
 
 fn conditionally_padded_layout(layout: &FieldLayoutProperties, use_padding: bool) -> FieldLayoutProperties {
     match use_padding {
-        false => FieldLayoutProperties { byte_order: layout.byte_order, ..Default::default() },
+        false => FieldLayoutProperties {
+            byte_order: layout.byte_order,
+            stride: layout.stride,
+            size_prefix: layout.size_prefix.clone(),
+            ..Default::default()
+        },
         true => layout.clone(),
     }
 }
@@ -186,6 +740,22 @@ pub fn serialize_transform(
 ) -> Value {
     match transform {
         Transform::None => value,
+        // The checksum is revised once the fields it covers are serialized.
+        Transform::Checksum { .. } => value,
+        // The range is only checked when deserializing.
+        Transform::Range { .. } => value,
+        Transform::VarInt => {
+            let encoded = encoded_varint(region, value);
+            ref_(region, encoded)
+        }
+        // Fields with custom functions are not serialized via their traits.
+        Transform::With { .. } => value,
+        // The magic bytes are serialized instead of the field.
+        Transform::Magic(_) => value,
+        // A placeholder is serialized for the offset instead of the field.
+        Transform::Pointer { .. } => value,
+        // The offset is revised once the field it refers to is serialized.
+        Transform::OffsetOf { .. } => value,
         Transform::Length(member) => {
             // Get the length of the collection referred to by `member`.
             let ty = ty.phantom_underlying_type();
@@ -213,11 +783,40 @@ pub fn serialize_transform(
             let items = items(region, value);
             ref_(region, items)
         }
+        Transform::LengthFrom(source) => {
+            // Items without the length, which must match the collection.
+            check_length_from(region, serializer, value, source);
+            let items = items(region, value);
+            ref_(region, items)
+        }
         Transform::Constant(expr) => {
             let ty = ty.phantom_underlying_type();
             let value = custom_expr(region, parse_quote!( <#ty>::from(#expr) ));
             ref_(region, value)
         }
+        Transform::Duration { unit, repr } => {
+            let result = duration_as(region, serializer, value, *unit, repr.clone());
+            let value = try_(region, result);
+            ref_(region, value)
+        }
+        Transform::Scale { scale, bias, raw } => {
+            let result = scaled_as(region, serializer, value, scale.clone(), bias.clone(), raw.clone());
+            let raw = try_(region, result);
+            ref_(region, raw)
+        }
+        Transform::LengthPrefix(len_ty) => {
+            // Items preceded by the length.
+            let len_prefixed = len_prefixed(region, value, len_ty.clone());
+            ref_(region, len_prefixed)
+        }
+        Transform::String(encoding) => {
+            let encoded = encoded(region, value, encoding.clone());
+            ref_(region, encoded)
+        }
+        Transform::Option(encoding) => {
+            let encoded = encoded_option(region, value, encoding.clone(), option_value_type(ty).clone());
+            ref_(region, encoded)
+        }
     }
 }
 
@@ -225,7 +824,7 @@ pub fn serialize_transform(
 mod tests {
     use super::*;
 
-    use crate::attribute::ByteOrder;
+    use crate::attribute::{ByteOrder, PointerBase};
     use crate::ir::pattern_match::assert_matches;
     use crate::ops::yield_;
 
@@ -237,6 +836,10 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(i32),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: Default::default(),
         };
@@ -249,9 +852,13 @@ mod tests {
 
         let pattern = "
         {
-            %foo = symref [foo]
-            %res = serialize_object [false] %serializer, %foo
-            yield %res
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %foo = symref [foo]
+                %res = serialize_object [false] %se_foo, %foo
+                yield %res
+            }
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -263,6 +870,10 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(i32),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: FieldLayoutProperties { byte_order: Some(ByteOrder::BigEndian), ..Default::default() },
         };
@@ -275,12 +886,16 @@ mod tests {
 
         let pattern = "
         {
-            %res = byte_order[BigEndian, true] %serializer |%se_inner| {
-                %foo = symref [foo]
-                %res_inner = serialize_object [false] %se_inner, %foo
-                yield %res_inner
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %res = byte_order [BigEndian, true] %se_foo |%se_inner| {
+                    %foo = symref [foo]
+                    %res_inner = serialize_object [false] %se_inner, %foo
+                    yield %res_inner
+                }
+                yield %res
             }
-            yield %res
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -292,12 +907,23 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(i32),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: FieldLayoutProperties {
                 byte_order: None,
                 offset: Some(1),
+                allow_backward: None,
                 align: Some(2),
+                align_absolute: None,
                 round: Some(3),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
             },
         };
 
@@ -309,23 +935,25 @@ mod tests {
 
         let pattern = "
         {
-            %offset = pad [1, true] %serializer
-            %try_offset = try %offset
-
-            %align = align [2, true] %serializer
-            %try_align = try %align
-            
-            %res = serialize_composite %serializer |%s_inner| {
-                %foo = symref [foo]
-                %res_inner = serialize_object [false] %s_inner, %foo
-                %round = align [3, true] %s_inner
-                %try_round = try %round
-                yield %res_inner
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %offset = pad [1, true] %se_foo
+                %try_offset = try %offset
+                %align = align [2, true] %se_foo
+                %try_align = try %align
+                %res = serialize_composite %se_foo |%s_inner| {
+                    %foo = symref [foo]
+                    %res_inner = serialize_object [false] %s_inner, %foo
+                    %round = align [3, true] %s_inner
+                    %try_round = try %round
+                    yield %res_inner
+                }
+                %res_try = try %res
+                %res_1 = member [1, false] %res_try
+                %res_ok = ok %res_1
+                yield %res_ok
             }
-            %res_try = try %res
-            %res_1 = member [1, false] %res_try
-            %res_ok = ok %res_1
-            yield %res_ok
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -337,12 +965,23 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(i32),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: FieldLayoutProperties {
                 byte_order: Some(ByteOrder::BigEndian),
                 offset: Some(1),
+                allow_backward: None,
                 align: Some(2),
+                align_absolute: None,
                 round: Some(3),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
             },
         };
 
@@ -354,26 +993,28 @@ mod tests {
 
         let pattern = "
         {
-            %offset = pad [1, true] %serializer
-            %try_offset = try %offset
-
-            %align = align [2, true] %serializer
-            %try_align = try %align
-            
-            %res = serialize_composite %serializer |%s_inner| {
-                %res_inner = byte_order[BigEndian, true] %s_inner |%se_bo| {
-                    %foo = symref [foo]
-                    %res_bo = serialize_object [false] %se_bo, %foo
-                    yield %res_bo
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %offset = pad [1, true] %se_foo
+                %try_offset = try %offset
+                %align = align [2, true] %se_foo
+                %try_align = try %align
+                %res = serialize_composite %se_foo |%s_inner| {
+                    %res_inner = byte_order [BigEndian, true] %s_inner |%se_bo| {
+                        %foo = symref [foo]
+                        %res_bo = serialize_object [false] %se_bo, %foo
+                        yield %res_bo
+                    }
+                    %round = align [3, true] %s_inner
+                    %try_round = try %round
+                    yield %res_inner
                 }
-                %round = align [3, true] %s_inner
-                %try_round = try %round
-                yield %res_inner
+                %res_try = try %res
+                %res_1 = member [1, false] %res_try
+                %res_ok = ok %res_1
+                yield %res_ok
             }
-            %res_try = try %res
-            %res_1 = member [1, false] %res_try
-            %res_ok = ok %res_1
-            yield %res_ok
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -385,6 +1026,272 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(i32),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: Default::default(),
+        };
+
+        let serializer = Value::new();
+        let mut region = Region::new(0);
+        let results = input.to_deserialize_op(&mut region, serializer);
+        yield_(&mut region, results);
+        let op = format!("{:#}", region);
+
+        let pattern = "
+        {
+            %pos = position [false] %serializer
+            %traced_foo = trace [foo, false] %serializer, %pos |%de_foo| {
+                %res = deserialize_field [foo] %de_foo |%de_field| {
+                    %res_field = deserialize_object [i32] %de_field
+                    yield %res_field
+                }
+                yield %res
+            }
+            yield %traced_foo
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
+    #[test]
+    fn to_serialize_op_direct_condition() {
+        let input = Field::Direct {
+            member: parse_quote!(foo),
+            ty: parse_quote!(Option<i32>),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: Some(parse_quote!(*flags != 0)),
+            transform: Transform::None,
+            layout_properties: Default::default(),
+        };
+
+        let serializer = Value::new();
+        let mut region = Region::new(0);
+        let results = input.to_serialize_op(&mut region, (serializer, true));
+        yield_(&mut region, results);
+        let op = format!("{:#}", region);
+
+        let pattern = "
+        {
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %cond = custom_expr [* flags != 0]
+                %res = match %cond {
+                    true => {
+                        %foo = symref [foo]
+                        %result_some = expect_some %se_foo, %foo
+                        %some = try %result_some
+                        %res_present = serialize_object [false] %se_foo, %some
+                        yield %res_present
+                    }
+                    false => {
+                        %res_absent = success %se_foo
+                        yield %res_absent
+                    }
+                }
+                yield %res
+            }
+            yield %traced_foo
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
+
+    #[test]
+    fn to_deserialize_op_direct_condition() {
+        let input = Field::Direct {
+            member: parse_quote!(foo),
+            ty: parse_quote!(Option<i32>),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: Some(parse_quote!(*flags != 0)),
+            transform: Transform::None,
+            layout_properties: Default::default(),
+        };
+
+        let serializer = Value::new();
+        let mut region = Region::new(0);
+        let results = input.to_deserialize_op(&mut region, serializer);
+        yield_(&mut region, results);
+        let op = format!("{:#}", region);
+
+        let pattern = "
+        {
+            %pos = position [false] %serializer
+            %traced_foo = trace [foo, false] %serializer, %pos |%de_foo| {
+                %cond = custom_expr [* flags != 0]
+                %res = match %cond {
+                    true => {
+                        %result_field = deserialize_field [foo] %de_foo |%de_field| {
+                            %result_value = deserialize_object [i32] %de_field
+                            %value = try %result_value
+                            %some = some %value
+                            %res_present = ok %some
+                            yield %res_present
+                        }
+                        yield %result_field
+                    }
+                    false => {
+                        %default = custom_expr [:: core :: default :: Default :: default ()]
+                        %res_absent = ok %default
+                        yield %res_absent
+                    }
+                }
+                yield %res
+            }
+            yield %traced_foo
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
+
+    #[test]
+    fn to_serialize_op_direct_pointer() {
+        let input = Field::Direct {
+            member: parse_quote!(foo),
+            ty: parse_quote!(i32),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::Pointer { offset: parse_quote!(u16), base: PointerBase::Relative },
+            layout_properties: Default::default(),
+        };
+
+        let serializer = Value::new();
+        let mut region = Region::new(0);
+        let results = input.to_serialize_op(&mut region, (serializer, true));
+        yield_(&mut region, results);
+        let op = format!("{:#}", region);
+
+        let pattern = "
+        {
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %foo = symref [foo]
+                %placeholder = custom_expr [< u16 as :: core :: default :: Default > :: default ()]
+                %placeholder_ref = ref %placeholder
+                %res = serialize_object [false] %se_foo, %placeholder_ref
+                yield %res
+            }
+            yield %traced_foo
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
+
+    #[test]
+    fn to_deserialize_op_direct_pointer() {
+        let input = Field::Direct {
+            member: parse_quote!(foo),
+            ty: parse_quote!(i32),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::Pointer { offset: parse_quote!(u16), base: PointerBase::Relative },
+            layout_properties: Default::default(),
+        };
+
+        let serializer = Value::new();
+        let mut region = Region::new(0);
+        let results = input.to_deserialize_op(&mut region, serializer);
+        yield_(&mut region, results);
+        let op = format!("{:#}", region);
+
+        let pattern = "
+        {
+            %pos = position [false] %serializer
+            %traced_foo = trace [foo, false] %serializer, %pos |%de_foo| {
+                %result_field = deserialize_field [foo] %de_foo |%de_field| {
+                    %pointees = symref [__sorbit_pointees]
+                    %result_value = deserialize_pointee [u16, relative] %de_field, %pointees |%de_object| {
+                        %result_object = deserialize_object [i32] %de_object
+                        yield %result_object
+                    }
+                    yield %result_value
+                }
+                yield %result_field
+            }
+            yield %traced_foo
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
+
+    #[test]
+    fn to_deserialize_op_direct_if_version() {
+        let input = Field::Direct {
+            member: parse_quote!(foo),
+            ty: parse_quote!(Option<i32>),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: Some(parse_quote!((*__sorbit_version).is_some_and(|version| version >= 3))),
+            transform: Transform::None,
+            layout_properties: FieldLayoutProperties {
+                if_version: Some(parse_quote!(version >= 3)),
+                ..Default::default()
+            },
+        };
+
+        let serializer = Value::new();
+        let mut region = Region::new(0);
+        let results = input.to_deserialize_op(&mut region, serializer);
+        yield_(&mut region, results);
+        let op = format!("{:#}", region);
+
+        let pattern = "
+        {
+            %pos = position [false] %serializer
+            %version = context_value [version, false] %serializer
+            sym [__sorbit_version] %version
+            %traced_foo = trace [foo, false] %serializer, %pos |%de_foo| {
+                %cond = custom_expr [(* __sorbit_version) . is_some_and (| version | version >= 3)]
+                %res = match %cond {
+                    true => {
+                        %result_field = deserialize_field [foo] %de_foo |%de_field| {
+                            %result_value = deserialize_object [i32] %de_field
+                            %value = try %result_value
+                            %some = some %value
+                            %res_present = ok %some
+                            yield %res_present
+                        }
+                        yield %result_field
+                    }
+                    false => {
+                        %default = custom_expr [:: core :: default :: Default :: default ()]
+                        %res_absent = ok %default
+                        yield %res_absent
+                    }
+                }
+                yield %res
+            }
+            yield %traced_foo
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
+
+    #[test]
+    fn to_deserialize_op_direct_boxed() {
+        let input = Field::Direct {
+            member: parse_quote!(foo),
+            ty: parse_quote!(Box<[u8; 4]>),
+            multi_pass: None,
+            deferred: false,
+            boxed: true,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: Default::default(),
         };
@@ -397,18 +1304,30 @@ mod tests {
 
         let pattern = "
         {
-            %res = deserialize_object [i32] %serializer
-            yield %res
+            %pos = position [false] %serializer
+            %traced_foo = trace [foo, false] %serializer, %pos |%de_foo| {
+                %res = deserialize_field [foo] %de_foo |%de_field| {
+                    %res_field = deserialize_boxed [[u8 ; 4]] %de_field
+                    yield %res_field
+                }
+                yield %res
+            }
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
     }
+
     #[test]
     fn to_deserialize_op_direct_byte_order() {
         let input = Field::Direct {
             member: parse_quote!(foo),
             ty: parse_quote!(i32),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: FieldLayoutProperties { byte_order: Some(ByteOrder::BigEndian), ..Default::default() },
         };
@@ -421,11 +1340,18 @@ mod tests {
 
         let pattern = "
         {
-            %res = byte_order[BigEndian, false] %de |%de_bo| {
-                %res_bo = deserialize_object [i32] %de_bo
-                yield %res_bo
+            %pos = position [false] %de
+            %traced_foo = trace [foo, false] %de, %pos |%de_foo| {
+                %res = byte_order [BigEndian, false] %de_foo |%de_bo| {
+                    %res_bo = deserialize_field [foo] %de_bo |%de_field| {
+                        %res_field = deserialize_object [i32] %de_field
+                        yield %res_field
+                    }
+                    yield %res_bo
+                }
+                yield %res
             }
-            yield %res
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -437,12 +1363,23 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(i32),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: FieldLayoutProperties {
                 byte_order: None,
                 offset: Some(1),
+                allow_backward: None,
                 align: Some(2),
+                align_absolute: None,
                 round: Some(3),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
             },
         };
 
@@ -454,19 +1391,24 @@ mod tests {
 
         let pattern = "
         {
-            %offset = pad [1, false] %deserializer
-            %try_offset = try %offset
-
-            %align = align [2, false] %deserializer
-            %try_align = try %align
-
-            %res = deserialize_composite %deserializer |%des_inner| {
-                %res_inner = deserialize_object [i32] %des_inner
-                %round = align [3, false] %des_inner
-                %try_round = try %round
-                yield %res_inner
+            %pos = position [false] %deserializer
+            %traced_foo = trace [foo, false] %deserializer, %pos |%de_foo| {
+                %offset = pad [1, false] %de_foo
+                %try_offset = try %offset
+                %align = align [2, false] %de_foo
+                %try_align = try %align
+                %res = deserialize_composite %de_foo |%des_inner| {
+                    %res_inner = deserialize_field [foo] %des_inner |%de_field| {
+                        %res_field = deserialize_object [i32] %de_field
+                        yield %res_field
+                    }
+                    %round = align [3, false] %des_inner
+                    %try_round = try %round
+                    yield %res_inner
+                }
+                yield %res
             }
-            yield %res
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -478,12 +1420,23 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(i32),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: FieldLayoutProperties {
                 byte_order: Some(ByteOrder::BigEndian),
                 offset: Some(1),
+                allow_backward: None,
                 align: Some(2),
+                align_absolute: None,
                 round: Some(3),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
             },
         };
 
@@ -495,22 +1448,27 @@ mod tests {
 
         let pattern = "
         {
-            %offset = pad [1, false] %deserializer
-            %try_offset = try %offset
-
-            %align = align [2, false] %deserializer
-            %try_align = try %align
-
-            %res = deserialize_composite %deserializer |%des_inner| {
-                %res_inner = byte_order[BigEndian, false] %des_inner |%de_bo| {
-                    %res_bo = deserialize_object [i32] %de_bo
-                    yield %res_bo
+            %pos = position [false] %deserializer
+            %traced_foo = trace [foo, false] %deserializer, %pos |%de_foo| {
+                %offset = pad [1, false] %de_foo
+                %try_offset = try %offset
+                %align = align [2, false] %de_foo
+                %try_align = try %align
+                %res = deserialize_composite %de_foo |%des_inner| {
+                    %res_inner = byte_order [BigEndian, false] %des_inner |%de_bo| {
+                        %res_bo = deserialize_field [foo] %de_bo |%de_field| {
+                            %res_field = deserialize_object [i32] %de_field
+                            yield %res_field
+                        }
+                        yield %res_bo
+                    }
+                    %round = align [3, false] %des_inner
+                    %try_round = try %round
+                    yield %res_inner
                 }
-                %round = align [3, false] %des_inner
-                %try_round = try %round
-                yield %res_inner
+                yield %res
             }
-            yield %res
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -561,10 +1519,14 @@ mod tests {
 
         let pattern = "
         {
-            %bf = empty_bit_field [u16]
-            %ref_bf = ref %bf
-            %s = serialize_object [false] %serializer %ref_bf
-            yield %s
+            %pos = position [true] %serializer
+            %traced__bit_field = trace [_bit_field, true] %serializer, %pos |%se__bit_field| {
+                %bf = empty_bit_field [u16]
+                %ref_bf = ref %bf
+                %s = serialize_object [false] %se__bit_field, %ref_bf
+                yield %s
+            }
+            yield %traced__bit_field
         }
         ";
         assert_matches!(op, pattern);
@@ -582,19 +1544,26 @@ mod tests {
 
         let pattern = "
         {
-            %bf0 = empty_bit_field [u16]
-            
-            %foo = symref [foo]
-            %maybe_bf1 = pack_bit_field [4..7, LSB0] %foo %bf0
-            %bf1 = try %maybe_bf1
-
-            %bar = symref [bar]
-            %maybe_bf2 = pack_bit_field [0..4, LSB0] %bar %bf1
-            %bf2 = try %maybe_bf2
-
-            %ref_bf2 = ref %bf2
-            %s = serialize_object [false] %serializer %ref_bf2
-            yield %s
+            %pos = position [true] %serializer
+            %traced__bit_field = trace [_bit_field, true] %serializer, %pos |%se__bit_field| {
+                %bf0 = empty_bit_field [u16]
+                %traced_foo = trace [foo, true] %se__bit_field, %pos |%se_foo| {
+                    %foo = symref [foo]
+                    %maybe_bf1 = pack_bit_field [4..7, LSB0] %foo, %bf0
+                    yield %maybe_bf1
+                }
+                %bf1 = try %traced_foo
+                %traced_bar = trace [bar, true] %se__bit_field, %pos |%se_bar| {
+                    %bar = symref [bar]
+                    %maybe_bf2 = pack_bit_field [0..4, LSB0] %bar, %bf1
+                    yield %maybe_bf2
+                }
+                %bf2 = try %traced_bar
+                %ref_bf2 = ref %bf2
+                %s = serialize_object [false] %se__bit_field, %ref_bf2
+                yield %s
+            }
+            yield %traced__bit_field
         }
         ";
         assert_matches!(op, pattern);
@@ -612,8 +1581,15 @@ mod tests {
 
         let pattern = "
         {
-            %s = deserialize_object [::sorbit::bit::BitField < u16 > ] %deserializer
-            %bf = try %s
+            %pos = position [false] %deserializer
+            %traced__bit_field = trace [_bit_field, false] %deserializer, %pos |%de__bit_field| {
+                %s = deserialize_field [_bit_field] %de__bit_field |%de_field| {
+                    %s_field = deserialize_object [::sorbit::bit::BitField < u16 >] %de_field
+                    yield %s_field
+                }
+                yield %s
+            }
+            %bf = try %traced__bit_field
             yield
         }
         ";
@@ -632,13 +1608,30 @@ mod tests {
 
         let pattern = "
         {
-            %s = deserialize_object [::sorbit::bit::BitField < u16 >] %deserializer
-            %bf = try %s
-
-            %maybe_foo = unpack_bit_field [u8, 4..7, LSB0] %bf
-            %maybe_bar = unpack_bit_field [i8, 0..4, LSB0] %bf
-
-            yield %maybe_foo, %maybe_bar
+            %pos = position [false] %deserializer
+            %traced__bit_field = trace [_bit_field, false] %deserializer, %pos |%de__bit_field| {
+                %s = deserialize_field [_bit_field] %de__bit_field |%de_field| {
+                    %s_field = deserialize_object [::sorbit::bit::BitField < u16 >] %de_field
+                    yield %s_field
+                }
+                yield %s
+            }
+            %bf = try %traced__bit_field
+            %traced__bit_field_2 = trace [_bit_field, false] %deserializer, %pos |%de__bit_field_2| {
+                %traced_foo = trace [foo, false] %de__bit_field_2, %pos |%de_foo| {
+                    %maybe_foo = unpack_bit_field [u8, 4..7, LSB0] %bf
+                    yield %maybe_foo
+                }
+                yield %traced_foo
+            }
+            %traced__bit_field_3 = trace [_bit_field, false] %deserializer, %pos |%de__bit_field_3| {
+                %traced_bar = trace [bar, false] %de__bit_field_3, %pos |%de_bar| {
+                    %maybe_bar = unpack_bit_field [i8, 0..4, LSB0] %bf
+                    yield %maybe_bar
+                }
+                yield %traced_bar
+            }
+            yield %traced__bit_field_2, %traced__bit_field_3
         }
         ";
         assert_matches!(op, pattern);
@@ -650,6 +1643,10 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::Length(parse_quote!(bar)),
             layout_properties: Default::default(),
         };
@@ -662,13 +1659,17 @@ mod tests {
 
         let pattern = "
         {
-            %foo = symref [foo]
-            %bar = symref [bar]
-            %len_result = len [u8] %serializer %bar
-            %len = try %len_result
-            %len_ref = ref %len
-            %res = serialize_object [false] %serializer, %len_ref
-            yield %res
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %foo = symref [foo]
+                %bar = symref [bar]
+                %len_result = len [u8] %se_foo, %bar
+                %len = try %len_result
+                %len_ref = ref %len
+                %res = serialize_object [false] %se_foo, %len_ref
+                yield %res
+            }
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -680,6 +1681,10 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::ByteCount(parse_quote!(bar)),
             layout_properties: Default::default(),
         };
@@ -692,9 +1697,13 @@ mod tests {
 
         let pattern = "
         {
-            %foo = symref [foo]
-            %res = serialize_object [false] %serializer, %foo
-            yield %res
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %foo = symref [foo]
+                %res = serialize_object [false] %se_foo, %foo
+                yield %res
+            }
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -706,6 +1715,10 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::LengthBy(parse_quote!(bar)),
             layout_properties: Default::default(),
         };
@@ -718,11 +1731,15 @@ mod tests {
 
         let pattern = "
         {
-            %foo = symref [foo]
-            %items = items %foo
-            %items_ref = ref %items
-            %res = serialize_object [false] %serializer, %items_ref
-            yield %res
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %foo = symref [foo]
+                %items = items %foo
+                %items_ref = ref %items
+                %res = serialize_object [false] %se_foo, %items_ref
+                yield %res
+            }
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -734,6 +1751,10 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::ByteCountBy(parse_quote!(bar)),
             layout_properties: Default::default(),
         };
@@ -746,11 +1767,15 @@ mod tests {
 
         let pattern = "
         {
-            %foo = symref [foo]
-            %items = items %foo
-            %items_ref = ref %items
-            %res = serialize_object [false] %serializer, %items_ref
-            yield %res
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %foo = symref [foo]
+                %items = items %foo
+                %items_ref = ref %items
+                %res = serialize_object [false] %se_foo, %items_ref
+                yield %res
+            }
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -762,6 +1787,10 @@ mod tests {
             member: parse_quote!(foo),
             ty: parse_quote!(u8),
             multi_pass: Some(true),
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::ByteCountBy(parse_quote!(bar)),
             layout_properties: Default::default(),
         };
@@ -774,11 +1803,15 @@ mod tests {
 
         let pattern = "
         {
-            %foo = symref [foo]
-            %items = items %foo
-            %items_ref = ref %items
-            %res = serialize_object [true] %serializer, %items_ref
-            yield %res
+            %pos = position [true] %serializer
+            %traced_foo = trace [foo, true] %serializer, %pos |%se_foo| {
+                %foo = symref [foo]
+                %items = items %foo
+                %items_ref = ref %items
+                %res = serialize_object [true] %se_foo, %items_ref
+                yield %res
+            }
+            yield %traced_foo
         }
         ";
         assert_matches!(op, pattern);
@@ -1,17 +1,29 @@
 use std::collections::{HashMap, HashSet};
 
-use syn::{Generics, Ident, Member, Type, parse_quote};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Generics, Ident, Lifetime, LitByteStr, Member, Path, Type, WherePredicate, parse_quote};
 
-use crate::attribute::{ByteOrder, Transform};
+use crate::attribute::{Backward, ByteOrder, ChecksumAlgorithm, PointerBase, SizePrefix, Transform};
 use crate::ir::{Region, Value};
-use crate::ops::algorithm::{with_maybe_alignment, with_maybe_byte_order, with_maybe_offset};
+use crate::ops::algorithm::{
+    with_maybe_alignment, with_maybe_byte_order, with_maybe_fill, with_maybe_magic, with_maybe_offset,
+    with_maybe_size_prefix,
+};
+use crate::ops::constants::{
+    BIG_ENDIAN, DESERIALIZE_TRAIT, FIELD_LAYOUT_TYPE, LAYOUT_TYPE, LITTLE_ENDIAN, MULTI_PASS_SERIALIZE_TRAIT,
+    SERIALIZE_TRAIT, STATIC_LAYOUT_TRAIT,
+};
 use crate::ops::{
-    self, custom_expr, deserialize_composite, destructure, impl_deserialize, impl_serialize, member, ok, revise_span,
-    self_, serialize_composite, struct_, success, sym, try_, tuple,
+    self, check_depth, custom_expr, deserialize_composite, deserialize_object, deserialize_recorded, destructure,
+    impl_deserialize, impl_serialize, measure_byte_count, member, ok, pointees, ref_, revise_span, self_,
+    serialize_checksum, serialize_composite, serialize_deferred, serialize_object, serialize_offset, serialize_pointee,
+    skip_pointees, struct_, success, sym, symref, try_, tuple, unref_sym, validate, verify_checksum,
 };
-use crate::r#struct::ast::conversion::{add_symmetric_transforms, check_transforms};
+use crate::r#struct::ast::conversion::{add_symmetric_transforms, check_borrowed, check_strides, check_transforms};
 use crate::r#struct::ast::field::BitFieldMember;
-use crate::utility::{PhantomType, ident_to_type, member_to_ident};
+use crate::r#struct::parse::SkippedField;
+use crate::utility::{PhantomType, borrowed_lifetime, ident_to_type, member_to_ident};
 
 use super::super::parse;
 use super::conversion::to_layout_fields;
@@ -22,10 +34,21 @@ use crate::ir::{ToDeserializeOp, ToSerializeOp};
 pub struct Struct {
     pub ident: Ident,
     pub generics: Generics,
+    pub bound: Option<Vec<WherePredicate>>,
     pub byte_order: Option<ByteOrder>,
     pub len: Option<u64>,
     pub round: Option<u64>,
+    pub dyn_byte_order: bool,
+    pub transparent: bool,
+    pub multi_pass: bool,
+    pub max_depth: Option<u32>,
+    pub assert_size: Option<u64>,
+    pub fill: Option<u8>,
+    pub validate: Option<Path>,
+    pub magic: Option<LitByteStr>,
+    pub size_prefix: Option<SizePrefix>,
     pub fields: Vec<Field>,
+    pub skipped: Vec<SkippedField>,
 }
 
 impl TryFrom<parse::Struct> for Struct {
@@ -35,16 +58,29 @@ impl TryFrom<parse::Struct> for Struct {
         let layout_fields = to_layout_fields(symmetric_fields.into_iter())?;
         let fields = layout_fields
             .into_iter()
-            .map(|field_group| field_group.into_field())
+            .map(|field_group| field_group.into_field(value.bit_numbering))
             .collect::<Result<Vec<_>, _>>()?;
         check_transforms(fields.iter())?;
+        check_borrowed(fields.iter())?;
+        check_strides(fields.iter())?;
         Ok(Self {
             ident: value.ident,
             generics: value.generics,
+            bound: value.bound,
             byte_order: value.byte_order,
             len: value.len,
             round: value.round,
+            dyn_byte_order: value.dyn_byte_order,
+            transparent: value.transparent,
+            multi_pass: value.multi_pass,
+            max_depth: value.max_depth,
+            assert_size: value.assert_size,
+            fill: value.fill,
+            validate: value.validate,
+            magic: value.magic,
+            size_prefix: value.size_prefix,
             fields,
+            skipped: value.skipped,
         })
     }
 }
@@ -53,14 +89,19 @@ impl ToSerializeOp for Struct {
     type Args = ();
 
     fn to_serialize_op(&self, region: &mut Region, _: Self::Args) -> Vec<Value> {
+        let mut generics = self.generics.clone();
+        generics.make_where_clause().predicates.extend(self.serialize_predicates(&self.generics));
         impl_serialize(
             region,
             self.ident.clone(),
-            self.generics.clone(),
+            generics,
             self.is_multi_pass(),
             Region::build(|region, [serializer]| {
                 self.destructure(region);
-                vec![self.serialize_members(region, serializer)]
+                match self.transparent {
+                    true => vec![self.serialize_transparent(region, serializer)],
+                    false => vec![self.serialize_members(region, serializer)],
+                }
             }),
         );
         vec![]
@@ -71,170 +112,667 @@ impl ToDeserializeOp for Struct {
     type Args = ();
 
     fn to_deserialize_op(&self, region: &mut Region, _: Self::Args) -> Vec<Value> {
+        let mut generics = self.generics.clone();
+        generics.make_where_clause().predicates.extend(self.deserialize_predicates(&self.generics));
         impl_deserialize(
             region,
             self.ident.clone(),
-            self.generics.clone(),
-            Region::build(|region, [deserializer]| vec![self.deserialize_members(region, deserializer)]),
+            generics,
+            self.is_seeking(),
+            self.borrowed_lifetime().cloned(),
+            Region::build(|region, [deserializer]| {
+                let result = match self.transparent {
+                    true => self.deserialize_transparent(region, deserializer),
+                    false => self.deserialize_members(region, deserializer),
+                };
+                match &self.validate {
+                    Some(validator) => {
+                        let value = try_(region, result);
+                        vec![validate(region, deserializer, value, validator.clone())]
+                    }
+                    None => vec![result],
+                }
+            }),
         );
         vec![]
     }
 }
 
 impl Struct {
-    pub fn is_multi_pass(&self) -> bool {
-        self.fields.iter().any(|field| match field {
-            Field::Direct { transform, multi_pass, .. } => {
-                matches!(transform, Transform::ByteCount(_)) || *multi_pass == Some(true)
+    pub fn to_static_layout_tokens(&self) -> TokenStream {
+        let ident = &self.ident;
+        let mut generics = self.generics.clone();
+        generics.make_where_clause().predicates.extend(self.layout_predicates(&self.generics));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        if let Some((_, ty)) = self.fields().first().filter(|_| self.transparent) {
+            return quote! {
+                #[automatically_derived]
+                impl #impl_generics #STATIC_LAYOUT_TRAIT for #ident #ty_generics #where_clause {
+                    const LAYOUT: #LAYOUT_TYPE = <#ty as #STATIC_LAYOUT_TRAIT>::LAYOUT;
+                    const FIELDS: &'static [#FIELD_LAYOUT_TYPE] = <#ty as #STATIC_LAYOUT_TRAIT>::FIELDS;
+                }
+            };
+        }
+
+        let layout = self.layout_tokens();
+        let fields = self.field_layout_tokens();
+        let size_assertion = self.assert_size.map(|size| {
+            let message = format!("the size of `{ident}` is not fixed to {size} bytes as asserted");
+            quote! {
+                const _: () = ::core::assert!(
+                    ::core::matches!(<#ident as #STATIC_LAYOUT_TRAIT>::LAYOUT.size(), ::core::option::Option::Some(#size)),
+                    #message
+                );
             }
-            Field::Bit { members, .. } => {
-                members.iter().any(|member| matches!(member.transform, Transform::ByteCount(_)))
+        });
+
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #STATIC_LAYOUT_TRAIT for #ident #ty_generics #where_clause {
+                const LAYOUT: #LAYOUT_TYPE = #layout;
+                const FIELDS: &'static [#FIELD_LAYOUT_TYPE] = &[#(#fields),*];
             }
-        })
+            #size_assertion
+        }
     }
 
-    pub fn serialize_members(&self, region: &mut Region, serializer: Value) -> Value {
-        with_maybe_byte_order(region, serializer, self.byte_order, true, |region, serializer| {
-            let composite_result = serialize_composite(
-                region,
-                serializer,
-                Region::build(|region, [serializer]| {
-                    if self.fields.is_empty() {
-                        let success_ = success(region, serializer.clone());
-                        with_maybe_offset(region, serializer, self.len, true);
-                        with_maybe_alignment(region, serializer, self.round, true);
-                        vec![success_]
-                    } else {
-                        let maybe_spans: Vec<_> = self
-                            .fields
-                            .iter()
-                            .map(|field| field.to_serialize_op(region, (serializer, true)))
-                            .flatten()
-                            .collect();
-                        let spans: Vec<_> =
-                            maybe_spans.into_iter().map(|maybe_span| try_(region, maybe_span)).collect();
-                        with_maybe_offset(region, serializer, self.len, true);
-                        with_maybe_alignment(region, serializer, self.round, true);
-                        let span_tuple = tuple(region, spans);
-                        let result = ok(region, span_tuple);
-                        vec![result]
-                    }
-                }),
-            );
-            let composite = try_(region, composite_result);
-            let composite_span = member(region, composite, syn::Member::from(0), false);
-
-            // Update byte count fields.
-            let revise_byte_count: Vec<_> = self
-                .fields
-                .iter()
-                .enumerate()
-                .filter_map(|(idx, field)| match field {
-                    Field::Direct { transform: Transform::ByteCountBy(byte_count), .. } => Some((byte_count, idx)),
-                    _ => None,
-                })
-                .collect();
+    /// Generate the `write_le` and `write_be` methods of `dyn_byte_order` structs.
+    pub fn to_byte_order_writer_tokens(&self) -> TokenStream {
+        if !self.dyn_byte_order {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let (serialize_trait, stream_bounds) = match self.is_multi_pass() {
+            false => (quote! { #SERIALIZE_TRAIT }, quote! { ::sorbit::io::Write }),
+            true => (
+                quote! { #MULTI_PASS_SERIALIZE_TRAIT },
+                quote! { ::sorbit::io::Read + ::sorbit::io::Write + ::sorbit::io::Seek },
+            ),
+        };
+        let methods = [
+            (quote! { write_le }, quote! { #LITTLE_ENDIAN }),
+            (quote! { write_be }, quote! { #BIG_ENDIAN }),
+        ];
+        let methods = methods.into_iter().map(|(name, byte_order)| {
+            quote! {
+                pub fn #name<Stream: #stream_bounds>(
+                    &self,
+                    stream: Stream,
+                ) -> ::core::result::Result<(), ::sorbit::error::Error>
+                where
+                    Self: #serialize_trait,
+                {
+                    let mut serializer =
+                        ::sorbit::stream_ser_de::StreamSerializer::new(stream).change_byte_order(#byte_order);
+                    #serialize_trait::serialize(self, &mut serializer).map(|_| ())
+                }
+            }
+        });
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #(#methods)*
+            }
+        }
+    }
 
-            if !revise_byte_count.is_empty() {
-                let field_spans = member(region, composite, syn::Member::from(1), false);
+    /// Generate the `read_le` and `read_be` methods of `dyn_byte_order` structs.
+    pub fn to_byte_order_reader_tokens(&self) -> TokenStream {
+        if !self.dyn_byte_order {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let methods = [
+            (quote! { read_le }, quote! { #LITTLE_ENDIAN }),
+            (quote! { read_be }, quote! { #BIG_ENDIAN }),
+        ];
+        let methods = methods.into_iter().map(|(name, byte_order)| {
+            quote! {
+                pub fn #name<Stream: ::sorbit::io::Read>(
+                    stream: Stream,
+                ) -> ::core::result::Result<Self, ::sorbit::error::Error>
+                where
+                    Self: #DESERIALIZE_TRAIT,
+                {
+                    let mut deserializer =
+                        ::sorbit::stream_ser_de::StreamDeserializer::new(stream).change_byte_order(#byte_order);
+                    <Self as #DESERIALIZE_TRAIT>::deserialize(&mut deserializer)
+                }
+            }
+        });
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #(#methods)*
+            }
+        }
+    }
 
-                let mut field_tys = HashMap::new();
-                self.fields.iter().for_each(|field| match field {
-                    Field::Direct { member, ty, .. } => {
-                        let _ = field_tys.insert(member, ty.phantom_underlying_type());
-                    }
-                    Field::Bit { members, .. } => members.iter().for_each(|member| {
-                        let _ = field_tys.insert(&member.member, member.ty.phantom_underlying_type());
-                    }),
-                });
+    pub fn layout_tokens(&self) -> TokenStream {
+        let fields = self
+            .fields
+            .iter()
+            .fold(self.magic_layout_tokens(), |preceding, field| field.to_layout_tokens(preceding));
+        let len = self.len.map(|len| quote! { .pad(#len) });
+        let round = self.round.map(|round| quote! { .align(#round) });
+        // The objects of pointer fields follow the structure, and their layout is not tracked.
+        let pointees = (!self.pointers().is_empty()).then(|| quote! { .then(#LAYOUT_TYPE::new(0, None, 0)) });
+        match &self.size_prefix {
+            Some(SizePrefix { ty, .. }) => {
+                quote! { <#ty as #STATIC_LAYOUT_TRAIT>::LAYOUT.then(#fields #len #round .nest() #pointees).nest() }
+            }
+            None => quote! { #fields #len #round .nest() #pointees },
+        }
+    }
 
-                let mut field_storages = HashMap::new();
-                self.fields.iter().enumerate().for_each(|(index, field)| match field {
-                    Field::Direct { member, .. } => {
-                        let _ = field_storages.insert(member, index);
-                    }
-                    Field::Bit { members, .. } => members.iter().for_each(|member| {
-                        let _ = field_storages.insert(&member.member, index);
-                    }),
-                });
+    /// Return the layout of the magic bytes that precede the fields.
+    fn magic_layout_tokens(&self) -> TokenStream {
+        match &self.magic {
+            Some(bytes) => {
+                let len = bytes.value().len() as u64;
+                quote! { <u8 as #STATIC_LAYOUT_TRAIT>::LAYOUT.repeat(#len) }
+            }
+            None => quote! { #LAYOUT_TYPE::EMPTY },
+        }
+    }
 
-                for (byte_count, of_idx) in &revise_byte_count {
-                    let byte_count_ty = field_tys[byte_count];
-                    let field_span = ops::member(region, field_spans, syn::Member::from(*of_idx), true);
-                    let result_byte_count = ops::byte_count(region, serializer, field_span, byte_count_ty.clone());
-                    let byte_count_val = try_(region, result_byte_count);
-                    sym(region, byte_count_val, member_to_ident((*byte_count).clone()));
+    /// Return the `FieldLayout` expression of each field.
+    pub fn field_layout_tokens(&self) -> Vec<TokenStream> {
+        let mut preceding = self.magic_layout_tokens();
+        let mut field_layouts = Vec::new();
+        for field in &self.fields {
+            let name = field.layout_name();
+            let start = field.to_layout_start_tokens(preceding.clone());
+            let value = field.to_layout_value_tokens();
+            let fields = field.to_layout_fields_tokens().map(|fields| quote! { .with_fields(#fields) });
+            field_layouts.push(quote! { #FIELD_LAYOUT_TYPE::new(#name, (#start).size(), #value) #fields });
+            preceding = field.to_layout_tokens(preceding);
+        }
+        field_layouts
+    }
+
+    /// Return the bounds of the generic parameters for the layout.
+    ///
+    /// The bounds specified by the `bound` parameter replace the inferred
+    /// bounds for all derived traits.
+    pub fn layout_predicates(&self, generics: &Generics) -> Vec<WherePredicate> {
+        if let Some(bound) = &self.bound {
+            return bound.clone();
+        }
+        self.fields.iter().filter_map(|field| field.layout_predicate(generics)).collect()
+    }
+
+    pub fn serialize_predicates(&self, generics: &Generics) -> Vec<WherePredicate> {
+        if let Some(bound) = &self.bound {
+            return bound.clone();
+        }
+        self.fields.iter().filter_map(|field| field.serialize_predicate(generics)).collect()
+    }
+
+    pub fn deserialize_predicates(&self, generics: &Generics) -> Vec<WherePredicate> {
+        if let Some(bound) = &self.bound {
+            return bound.clone();
+        }
+        self.fields.iter().filter_map(|field| field.deserialize_predicate(generics)).collect()
+    }
+
+    pub fn is_multi_pass(&self) -> bool {
+        self.size_prefix.is_some()
+            || self.fields.iter().any(|field| match field {
+                Field::Direct { transform, multi_pass, deferred, layout_properties, .. } => {
+                    matches!(transform, Transform::ByteCount(_)) && !self.multi_pass
+                        || matches!(
+                            transform,
+                            Transform::Checksum { .. } | Transform::Pointer { .. } | Transform::OffsetOf { .. }
+                        )
+                        || *multi_pass == Some(true)
+                        || *deferred
+                        || layout_properties.size_prefix.is_some()
+                        || layout_properties.allow_backward == Some(Backward::Reorder)
                 }
+                Field::Bit { members, layout_properties, .. } => {
+                    members.iter().any(|member| matches!(member.transform, Transform::ByteCount(_)) && !self.multi_pass)
+                        || layout_properties.size_prefix.is_some()
+                        || layout_properties.allow_backward == Some(Backward::Reorder)
+                }
+            })
+    }
+
+    /// Return the member, the algorithm, and the covered members of each
+    /// checksum field.
+    fn checksums(&self) -> Vec<(&Member, ChecksumAlgorithm, &Vec<Member>)> {
+        self.fields
+            .iter()
+            .filter_map(|field| match field {
+                Field::Direct { member, transform: Transform::Checksum { algorithm, over }, .. } => {
+                    Some((member, *algorithm, over))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Return the index and the field of each pointer field.
+    fn pointers(&self) -> Vec<(usize, &Field)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| matches!(field, Field::Direct { transform: Transform::Pointer { .. }, .. }))
+            .collect()
+    }
+
+    /// Return the type of each member, with phantom types replaced by their
+    /// underlying types.
+    fn member_types(&self) -> HashMap<&Member, &Type> {
+        let mut member_tys = HashMap::new();
+        self.fields.iter().for_each(|field| match field {
+            Field::Direct { member, ty, .. } => {
+                let _ = member_tys.insert(member, ty.phantom_underlying_type());
+            }
+            Field::Bit { members, .. } => members.iter().for_each(|member| {
+                let _ = member_tys.insert(&member.member, member.ty.phantom_underlying_type());
+            }),
+        });
+        member_tys
+    }
+
+    /// Return the index of the field that stores `member`.
+    ///
+    /// Members of a bit field share the index of their storage. The members
+    /// are checked to exist when the struct is converted.
+    fn storage_index(&self, member: &Member) -> usize {
+        self.fields
+            .iter()
+            .position(|field| field.members().contains(&member))
+            .expect("the member to be checked")
+    }
+
+    pub fn is_seeking(&self) -> bool {
+        !self.pointers().is_empty()
+            || self.fields.iter().any(|field| field.layout_properties().allow_backward.is_some())
+    }
+
+    /// Return the lifetime of the fields that borrow from the deserializer.
+    pub fn borrowed_lifetime(&self) -> Option<&Lifetime> {
+        self.fields.iter().find_map(|field| match field {
+            Field::Direct { ty, borrow: true, .. } => borrowed_lifetime(ty),
+            _ => None,
+        })
+    }
+
+    pub fn serialize_members(&self, region: &mut Region, serializer: Value) -> Value {
+        // Borrowed slices and strings are serialized like owned collections,
+        // so the reference to the reference is stripped.
+        for field in &self.fields {
+            if let Field::Direct { member, ty: Type::Reference(_), borrow: true, .. } = field {
+                unref_sym(region, member_to_ident(member.clone()));
+            }
+        }
+        with_maybe_byte_order(region, serializer, self.byte_order, true, |region, serializer| {
+            with_maybe_fill(region, serializer, self.fill, true, |region, serializer| {
+                with_maybe_size_prefix(region, serializer, self.size_prefix.as_ref(), true, |region, serializer| {
+                    self.serialize_composite(region, serializer)
+                })
+            })
+        })
+    }
 
-                let reserialize_storages: HashSet<_> =
-                    revise_byte_count.iter().map(|(byte_count, _)| field_storages[byte_count]).collect();
+    fn serialize_composite(&self, region: &mut Region, serializer: Value) -> Value {
+        let byte_counts: Vec<_> = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, field)| match field {
+                Field::Direct { transform: Transform::ByteCountBy(byte_count), .. } => Some((byte_count, idx)),
+                _ => None,
+            })
+            .collect();
 
-                for field_idx in reserialize_storages {
-                    let field = &self.fields[field_idx];
-                    let span = member(region, field_spans, syn::Member::from(field_idx), true);
-                    revise_span(
+        // Measure byte count fields up front, so that they need not be revised.
+        let revise_byte_count = match self.multi_pass {
+            true => {
+                let field_tys = self.member_types();
+                for (byte_count, of_idx) in &byte_counts {
+                    let field = &self.fields[*of_idx];
+                    let result_byte_count = measure_byte_count(
                         region,
                         serializer,
-                        span,
-                        Region::build(|region, [serializer]| {
-                            let results = field.to_serialize_op(region, (serializer, false));
-                            for result in results {
-                                try_(region, result);
-                            }
-                            vec![success(region, serializer)]
-                        }),
+                        field_tys[byte_count].clone(),
+                        Region::build(|region, [sizer]| field.to_serialize_op(region, (sizer, false))),
                     );
+                    let byte_count_val = try_(region, result_byte_count);
+                    sym(region, byte_count_val, member_to_ident((*byte_count).clone()));
                 }
+                Vec::new()
             }
+            false => byte_counts,
+        };
 
-            ok(region, composite_span)
-        })
-    }
-
-    pub fn deserialize_members(&self, region: &mut Region, deserializer: Value) -> Value {
-        with_maybe_byte_order(region, deserializer, self.byte_order, false, |region, deserializer| {
-            deserialize_composite(
-                region,
-                deserializer,
-                Region::build(|region, [deserializer]| {
-                    let fields: Vec<_> = self
+        let composite_result = serialize_composite(
+            region,
+            serializer,
+            Region::build(|region, [serializer]| {
+                with_maybe_magic(region, serializer, self.magic.as_ref(), true);
+                if self.fields.is_empty() {
+                    let success_ = success(region, serializer);
+                    with_maybe_offset(region, serializer, self.len, true);
+                    with_maybe_alignment(region, serializer, self.round, true);
+                    vec![success_]
+                } else {
+                    let maybe_spans: Vec<_> = self
                         .fields
                         .iter()
-                        .map(|field| {
-                            let results = field.to_deserialize_op(region, deserializer);
-                            let values: Vec<_> = results.iter().map(|result| try_(region, *result)).collect();
-                            std::iter::zip(field.members(), &values)
-                                .for_each(|(member, value)| sym(region, *value, member_to_ident(member.clone())));
-                            let values: Vec<_> = std::iter::zip(field.types(), values)
-                                .map(|(ty, value)| {
-                                    if ty.is_phantom() {
-                                        custom_expr(region, parse_quote!(PhantomData))
-                                    } else {
-                                        value
-                                    }
-                                })
-                                .collect();
-                            values
+                        .flat_map(|field| {
+                            let results = field.to_serialize_op(region, (serializer, true));
+                            field.set_maybe_context(region, serializer, true);
+                            results
                         })
-                        .flatten()
                         .collect();
-                    let members = self.members();
+                    let spans: Vec<_> = maybe_spans.into_iter().map(|maybe_span| try_(region, maybe_span)).collect();
+                    with_maybe_offset(region, serializer, self.len, true);
+                    with_maybe_alignment(region, serializer, self.round, true);
+                    let span_tuple = tuple(region, spans);
+                    let result = ok(region, span_tuple);
+                    vec![result]
+                }
+            }),
+        );
+        let composite = try_(region, composite_result);
+        let composite_span = member(region, composite, syn::Member::from(0), false);
 
-                    with_maybe_offset(region, deserializer, self.len, false);
-                    with_maybe_alignment(region, deserializer, self.round, false);
+        let deferred: Vec<_> = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, field)| match field {
+                Field::Direct { member, deferred: true, .. } => Some((member, idx, Revision::Deferred)),
+                Field::Direct { member, transform: Transform::Checksum { algorithm, over }, .. } => {
+                    Some((member, idx, Revision::Checksum(*algorithm, over)))
+                }
+                Field::Direct { member, ty, transform: Transform::OffsetOf { member: object, base }, .. } => {
+                    Some((member, idx, Revision::OffsetOf(ty, object, *base)))
+                }
+                _ => None,
+            })
+            .collect();
 
-                    let struct_ = struct_(
-                        region,
-                        syn::TypePath { qself: None, path: syn::Path::from(self.ident.clone()) }.into(),
-                        members.into_iter().cloned().zip(fields.into_iter()).collect(),
-                    );
-                    let result = ok(region, struct_);
-                    vec![result]
+        let pointers = self.pointers();
+
+        let field_spans = (!revise_byte_count.is_empty() || !deferred.is_empty() || !pointers.is_empty())
+            .then(|| member(region, composite, syn::Member::from(1), false));
+
+        // Update byte count fields.
+        if let Some(field_spans) = field_spans
+            && !revise_byte_count.is_empty()
+        {
+            let field_tys = self.member_types();
+            let mut field_storages = HashMap::new();
+            self.fields.iter().enumerate().for_each(|(index, field)| match field {
+                Field::Direct { member, .. } => {
+                    let _ = field_storages.insert(member, index);
+                }
+                Field::Bit { members, .. } => members.iter().for_each(|member| {
+                    let _ = field_storages.insert(&member.member, index);
                 }),
-            )
+            });
+
+            for (byte_count, of_idx) in &revise_byte_count {
+                let byte_count_ty = field_tys[byte_count];
+                let field_span = ops::member(region, field_spans, syn::Member::from(*of_idx), true);
+                let result_byte_count = ops::byte_count(region, serializer, field_span, byte_count_ty.clone());
+                let byte_count_val = try_(region, result_byte_count);
+                sym(region, byte_count_val, member_to_ident((*byte_count).clone()));
+            }
+
+            let reserialize_storages: HashSet<_> =
+                revise_byte_count.iter().map(|(byte_count, _)| field_storages[byte_count]).collect();
+
+            for field_idx in reserialize_storages {
+                let field = &self.fields[field_idx];
+                let span = member(region, field_spans, syn::Member::from(field_idx), true);
+                revise_span(
+                    region,
+                    serializer,
+                    span,
+                    Region::build(|region, [serializer]| {
+                        let results = field.to_serialize_op(region, (serializer, false));
+                        for result in results {
+                            try_(region, result);
+                        }
+                        vec![success(region, serializer)]
+                    }),
+                );
+            }
+        }
+
+        // Serialize the objects of pointer fields after the structure, and
+        // revise their offsets. This precedes the checksums, which may cover
+        // the offsets.
+        if let Some(field_spans) = field_spans
+            && !pointers.is_empty()
+        {
+            let composite_span = ref_(region, composite_span);
+            for (field_idx, field) in pointers {
+                let Field::Direct {
+                    member: member_, multi_pass, transform: Transform::Pointer { offset, base }, ..
+                } = field
+                else {
+                    unreachable!("pointer fields are filtered to be direct");
+                };
+                let pointer = member(region, field_spans, syn::Member::from(field_idx), true);
+                let result = serialize_pointee(
+                    region,
+                    serializer,
+                    composite_span,
+                    pointer,
+                    offset.clone(),
+                    *base,
+                    Region::build(|region, [serializer]| {
+                        let field = symref(region, member_to_ident(member_.clone()));
+                        vec![serialize_object(
+                            region,
+                            serializer,
+                            field,
+                            multi_pass.unwrap_or(false),
+                        )]
+                    }),
+                );
+                try_(region, result);
+            }
+        }
+
+        // Update deferred, checksum, and offset fields.
+        if let Some(field_spans) = field_spans
+            && !deferred.is_empty()
+        {
+            let composite_span = ref_(region, composite_span);
+            for (member_, field_idx, revision) in deferred {
+                let span = member(region, field_spans, syn::Member::from(field_idx), true);
+                let result = revise_span(
+                    region,
+                    serializer,
+                    span,
+                    Region::build(|region, [serializer]| {
+                        let result = match revision {
+                            Revision::Checksum(algorithm, over) => {
+                                let sections: Vec<_> = over
+                                    .iter()
+                                    .map(|covered| {
+                                        let index = self.storage_index(covered);
+                                        member(region, field_spans, syn::Member::from(index), true)
+                                    })
+                                    .collect();
+                                let sections = tuple(region, sections);
+                                serialize_checksum(region, serializer, sections, algorithm, over.len())
+                            }
+                            Revision::OffsetOf(ty, object, base) => {
+                                let index = self.storage_index(object);
+                                let object = member(region, field_spans, syn::Member::from(index), true);
+                                serialize_offset(region, serializer, composite_span, span, object, ty.clone(), base)
+                            }
+                            Revision::Deferred => {
+                                let field = symref(region, member_to_ident(member_.clone()));
+                                serialize_deferred(region, serializer, field, field_spans, self.fields.len())
+                            }
+                        };
+                        vec![result]
+                    }),
+                );
+                try_(region, result);
+            }
+        }
+
+        ok(region, composite_span)
+    }
+
+    /// Serialize the only field of a transparent struct as if it was the
+    /// struct itself.
+    fn serialize_transparent(&self, region: &mut Region, serializer: Value) -> Value {
+        let Field::Direct { member, multi_pass, .. } = &self.fields[0] else {
+            unreachable!("the field of a transparent struct is checked to be direct");
+        };
+        let field = symref(region, member_to_ident(member.clone()));
+        serialize_object(region, serializer, field, multi_pass.unwrap_or(false))
+    }
+
+    /// Deserialize the only field of a transparent struct, and wrap it into
+    /// the struct.
+    fn deserialize_transparent(&self, region: &mut Region, deserializer: Value) -> Value {
+        let (member, ty) = self.fields()[0];
+        let result = deserialize_object(region, deserializer, ty.clone());
+        let value = try_(region, result);
+        let members = std::iter::once((member.clone(), value)).chain(self.skipped_defaults(region)).collect();
+        let struct_ = struct_(region, ident_to_type(self.ident.clone()), members);
+        ok(region, struct_)
+    }
+
+    /// Return the default value of each skipped field.
+    fn skipped_defaults(&self, region: &mut Region) -> Vec<(Member, Value)> {
+        self.skipped
+            .iter()
+            .map(|SkippedField { member, default }| {
+                let default = default.clone().unwrap_or_else(|| parse_quote!(::core::default::Default::default()));
+                (member.clone(), custom_expr(region, default))
+            })
+            .collect()
+    }
+
+    pub fn deserialize_members(&self, region: &mut Region, deserializer: Value) -> Value {
+        if let Some(max_depth) = self.max_depth {
+            check_depth(region, deserializer, max_depth);
+        }
+        with_maybe_byte_order(region, deserializer, self.byte_order, false, |region, deserializer| {
+            with_maybe_fill(region, deserializer, self.fill, false, |region, deserializer| {
+                with_maybe_size_prefix(
+                    region,
+                    deserializer,
+                    self.size_prefix.as_ref(),
+                    false,
+                    |region, deserializer| self.deserialize_composite(region, deserializer),
+                )
+            })
         })
     }
 
+    fn deserialize_composite(&self, region: &mut Region, deserializer: Value) -> Value {
+        deserialize_composite(
+            region,
+            deserializer,
+            Region::build(|region, [deserializer]| {
+                with_maybe_magic(region, deserializer, self.magic.as_ref(), false);
+                let has_pointers = !self.pointers().is_empty();
+                if has_pointers {
+                    let pointees = pointees(region);
+                    sym(region, pointees, parse_quote!(__sorbit_pointees));
+                }
+                let checksums = self.checksums();
+                let covered: HashSet<_> = checksums
+                    .iter()
+                    .flat_map(|(_, _, over)| over.iter().map(|covered| self.storage_index(covered)))
+                    .collect();
+                let mut recorded = HashMap::new();
+                let fields: Vec<_> = self
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, field)| {
+                        let values: Vec<_> = match covered.contains(&index) {
+                            true => {
+                                let result_recorded = deserialize_recorded(
+                                    region,
+                                    deserializer,
+                                    Region::build(|region, [deserializer]| {
+                                        let results = field.to_deserialize_op(region, deserializer);
+                                        let values = results.iter().map(|result| try_(region, *result)).collect();
+                                        let values = tuple(region, values);
+                                        vec![ok(region, values)]
+                                    }),
+                                );
+                                let values_and_bytes = try_(region, result_recorded);
+                                let values = member(region, values_and_bytes, syn::Member::from(0), false);
+                                let bytes = member(region, values_and_bytes, syn::Member::from(1), false);
+                                recorded.insert(index, bytes);
+                                (0..field.members().len())
+                                    .map(|member_idx| member(region, values, syn::Member::from(member_idx), false))
+                                    .collect()
+                            }
+                            false => {
+                                let results = field.to_deserialize_op(region, deserializer);
+                                results.iter().map(|result| try_(region, *result)).collect()
+                            }
+                        };
+                        std::iter::zip(field.members(), &values)
+                            .for_each(|(member, value)| sym(region, *value, member_to_ident(member.clone())));
+                        field.set_maybe_context(region, deserializer, false);
+                        let values: Vec<_> = std::iter::zip(field.types(), values)
+                            .map(|(ty, value)| {
+                                if ty.is_phantom() {
+                                    custom_expr(region, parse_quote!(PhantomData))
+                                } else {
+                                    value
+                                }
+                            })
+                            .collect();
+                        values
+                    })
+                    .collect();
+                let members = self.members();
+
+                for (member_, algorithm, over) in checksums {
+                    let checksum = symref(region, member_to_ident(member_.clone()));
+                    let sections: Vec<_> =
+                        over.iter().map(|covered| ref_(region, recorded[&self.storage_index(covered)])).collect();
+                    let sections = tuple(region, sections);
+                    let result = verify_checksum(region, deserializer, checksum, sections, algorithm, over.len());
+                    try_(region, result);
+                }
+
+                with_maybe_offset(region, deserializer, self.len, false);
+                with_maybe_alignment(region, deserializer, self.round, false);
+
+                // Move past the objects of pointer fields, as they are serialized after the structure.
+                if has_pointers {
+                    let pointees = symref(region, parse_quote!(__sorbit_pointees));
+                    let result = skip_pointees(region, deserializer, pointees);
+                    try_(region, result);
+                }
+
+                let skipped = self.skipped_defaults(region);
+                let members = members.into_iter().cloned().zip(fields).chain(skipped).collect();
+                let struct_ = struct_(
+                    region,
+                    syn::TypePath { qself: None, path: syn::Path::from(self.ident.clone()) }.into(),
+                    members,
+                );
+                let result = ok(region, struct_);
+                vec![result]
+            }),
+        )
+    }
+
     pub fn members(&self) -> Vec<&Member> {
         self.fields().iter().map(|(member, _)| *member).collect()
     }
@@ -262,6 +800,17 @@ impl Struct {
     }
 }
 
+/// How a field is revised after the structure is serialized.
+#[derive(Clone, Copy)]
+enum Revision<'a> {
+    /// The field is serialized again via `DeferredSerialize`.
+    Deferred,
+    /// The field is set to the checksum of the covered members.
+    Checksum(ChecksumAlgorithm, &'a Vec<Member>),
+    /// The field of the given type is set to the offset of the member.
+    OffsetOf(&'a Type, &'a Member, PointerBase),
+}
+
 #[cfg(test)]
 mod tests {
     use syn::{DeriveInput, parse_quote};
@@ -283,10 +832,21 @@ mod tests {
         let input = Struct {
             ident: parse_quote!(Test),
             generics: input.generics,
+            bound: None,
             byte_order: None,
             len: None,
             round: None,
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            validate: None,
+            magic: None,
             fields: vec![],
+            size_prefix: None,
+            skipped: vec![],
         };
 
         let mut region = Region::new(0);
@@ -317,10 +877,21 @@ mod tests {
         let input = Struct {
             ident: parse_quote!(Test),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
             len: Some(12),
             round: Some(8),
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            validate: None,
+            magic: None,
             fields: vec![],
+            size_prefix: None,
+            skipped: vec![],
         };
 
         let mut region = Region::new(0);
@@ -355,14 +926,27 @@ mod tests {
         let input = Struct {
             ident: parse_quote!(Test),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
             len: None,
             round: None,
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            validate: None,
+            magic: None,
             fields: vec![
                 Field::Direct {
                     member: parse_quote!(foo),
                     ty: parse_quote!(u8),
                     multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: Default::default(),
                 },
@@ -370,10 +954,16 @@ mod tests {
                     member: parse_quote!(bar),
                     ty: parse_quote!(i8),
                     multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
                     transform: Transform::None,
                     layout_properties: Default::default(),
                 },
             ],
+            size_prefix: None,
+            skipped: vec![],
         };
 
         let mut region = Region::new(0);
@@ -382,19 +972,123 @@ mod tests {
 
         let pattern = "
         {
-            impl_serialize [ Test, false ] |%serializer| {
+            impl_serialize [Test, , false] |%serializer| {
                 %self = self
                 destructure [Test, foo: foo, bar: bar] %self
                 %maybe_composite = serialize_composite %serializer |%s_inner| {
-                    %foo = symref [foo]
-                    %maybe_span_foo = serialize_object [false] %s_inner, %foo
+                    %pos = position [true] %s_inner
+                    %traced_foo = trace [foo, true] %s_inner, %pos |%se_foo| {
+                        %foo = symref [foo]
+                        %maybe_span_foo = serialize_object [false] %se_foo, %foo
+                        yield %maybe_span_foo
+                    }
+                    %pos_2 = position [true] %s_inner
+                    %traced_bar = trace [bar, true] %s_inner, %pos_2 |%se_bar| {
+                        %bar = symref [bar]
+                        %maybe_span_bar = serialize_object [false] %se_bar, %bar
+                        yield %maybe_span_bar
+                    }
+                    %span_foo = try %traced_foo
+                    %span_bar = try %traced_bar
+                    %spans = tuple %span_foo, %span_bar
+                    %ok_spans = ok %spans
+                    yield %ok_spans
+                }
+                %composite = try %maybe_composite
+                %span = member [0, false] %composite
+                %ok_span = ok %span
+                yield %ok_span
+            }
+        }
+        ";
+        assert_matches!(op, pattern);
+    }
+
+    #[test]
+    fn to_serialize_op_multi_pass() {
+        let input = Struct {
+            ident: parse_quote!(Test),
+            generics: Generics::default(),
+            bound: None,
+            byte_order: None,
+            len: None,
+            round: None,
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: true,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            validate: None,
+            magic: None,
+            fields: vec![
+                Field::Direct {
+                    member: parse_quote!(count),
+                    ty: parse_quote!(u8),
+                    multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
+                    transform: Transform::ByteCount(parse_quote!(items)),
+                    layout_properties: Default::default(),
+                },
+                Field::Direct {
+                    member: parse_quote!(items),
+                    ty: parse_quote!(Vec<u8>),
+                    multi_pass: None,
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
+                    transform: Transform::ByteCountBy(parse_quote!(count)),
+                    layout_properties: Default::default(),
+                },
+            ],
+            size_prefix: None,
+            skipped: vec![],
+        };
 
-                    %bar = symref [bar]
-                    %maybe_span_bar = serialize_object [false] %s_inner, %bar
+        let mut region = Region::new(0);
+        input.to_serialize_op(&mut region, ());
+        let op = format!("{:#}", region);
 
-                    %span_foo = try %maybe_span_foo
-                    %span_bar = try %maybe_span_bar
-                    %spans = tuple %span_foo, %span_bar
+        let pattern = "
+        {
+            impl_serialize [Test, , false] |%serializer| {
+                %self = self
+                destructure [Test, count: count, items: items] %self
+                %maybe_measured = measure_byte_count [u8] %serializer |%sizer| {
+                    %pos = position [true] %sizer
+                    %traced_items = trace [items, true] %sizer, %pos |%se_items| {
+                        %items_sized = symref [items]
+                        %items_only_sized = items %items_sized
+                        %items_only_ref_sized = ref %items_only_sized
+                        %maybe_span_sized = serialize_object [false] %se_items, %items_only_ref_sized
+                        yield %maybe_span_sized
+                    }
+                    yield %traced_items
+                }
+                %measured = try %maybe_measured
+                sym [count] %measured
+                %maybe_composite = serialize_composite %serializer |%s_inner| {
+                    %pos_2 = position [true] %s_inner
+                    %traced_count = trace [count, true] %s_inner, %pos_2 |%se_count| {
+                        %count = symref [count]
+                        %maybe_span_count = serialize_object [false] %se_count, %count
+                        yield %maybe_span_count
+                    }
+                    %pos_3 = position [true] %s_inner
+                    %traced_items_2 = trace [items, true] %s_inner, %pos_3 |%se_items_2| {
+                        %items = symref [items]
+                        %items_only = items %items
+                        %items_only_ref = ref %items_only
+                        %maybe_span_items = serialize_object [false] %se_items_2, %items_only_ref
+                        yield %maybe_span_items
+                    }
+                    %span_count = try %traced_count
+                    %span_items = try %traced_items_2
+                    %spans = tuple %span_count, %span_items
                     %ok_spans = ok %spans
                     yield %ok_spans
                 }
@@ -403,7 +1097,7 @@ mod tests {
                 %ok_span = ok %span
                 yield %ok_span
             }
-        }     
+        }
         ";
         assert_matches!(op, pattern);
     }
@@ -420,10 +1114,21 @@ mod tests {
         let input = Struct {
             ident: parse_quote!(Test),
             generics: input.generics,
+            bound: None,
             byte_order: None,
             len: None,
             round: None,
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            validate: None,
+            magic: None,
             fields: vec![],
+            size_prefix: None,
+            skipped: vec![],
         };
 
         let mut region = Region::new(0);
@@ -432,7 +1137,7 @@ mod tests {
 
         let pattern = "
         {
-            impl_deserialize [ Test, < 'x T : Clone > ] |%deserializer| {
+            impl_deserialize [ Test, < 'x T : Clone >, false, none ] |%deserializer| {
                 %maybe_composite = deserialize_composite %deserializer |%de_inner| {
                     %struct = struct [Test]
                     %ok_struct = ok %struct
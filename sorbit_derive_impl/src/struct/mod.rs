@@ -1,5 +1,6 @@
 use proc_macro2::TokenStream;
-use syn::DeriveInput;
+use quote::quote;
+use syn::{DeriveInput, Ident};
 
 pub mod ast;
 pub mod parse;
@@ -11,16 +12,28 @@ pub struct Struct {
 }
 
 impl Struct {
+    pub fn ident(&self) -> &Ident {
+        &self.inner.ident
+    }
+
     pub fn derive_serialize(&self) -> TokenStream {
         let mut region = Region::new(0);
         self.inner.to_serialize_op(&mut region, ());
-        region.to_token_stream_formatted(false)
+        let serialize = region.to_token_stream_formatted(false);
+        let writers = self.inner.to_byte_order_writer_tokens();
+        quote! { #writers #serialize }
     }
 
     pub fn derive_deserialize(&self) -> TokenStream {
         let mut region = Region::new(0);
         self.inner.to_deserialize_op(&mut region, ());
-        region.to_token_stream_formatted(false)
+        let deserialize = region.to_token_stream_formatted(false);
+        let readers = self.inner.to_byte_order_reader_tokens();
+        quote! { #readers #deserialize }
+    }
+
+    pub fn derive_static_layout(&self) -> TokenStream {
+        self.inner.to_static_layout_tokens()
     }
 }
 
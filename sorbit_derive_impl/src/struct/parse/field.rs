@@ -1,35 +1,65 @@
 use proc_macro2::Span;
+use quote::ToTokens;
 use std::{collections::HashMap, ops::Range};
-use syn::{Expr, Ident, Path, Type, spanned::Spanned};
+use syn::{Expr, Ident, Member, Path, Type, parse_quote, spanned::Spanned};
 
 use crate::{
     attribute::{
-        BitNumbering, ByteOrder, Transform, as_bit_numbering, as_byte_order, as_ident, as_literal_bool, as_literal_int,
-        as_literal_int_range, as_transform, as_type, parse_nvp_attribute_group, path,
+        Backward, BitNumbering, ByteOrder, SizePrefix, StringEncoding, Transform, as_backward, as_bit_numbering,
+        as_byte_order, as_byte_string, as_checksum, as_condition, as_context_key, as_ident, as_length_source,
+        as_literal_bool, as_literal_int, as_literal_int_range, as_member, as_option_encoding, as_path, as_pointer_base,
+        as_string_encoding, as_time_unit, as_transform, as_type, as_version_requirement, parse_nvp_attribute_group,
+        parse_size_prefix, path,
     },
-    utility::check_invalid_parameters,
+    utility::{boxed_type, check_invalid_parameters, optional_type, to_member},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct FieldLayoutProperties {
     pub byte_order: Option<ByteOrder>,
     pub offset: Option<u64>,
+    pub allow_backward: Option<Backward>,
     pub align: Option<u64>,
+    pub align_absolute: Option<u64>,
     pub round: Option<u64>,
+    /// The size of the slot each item of a collection is serialized into.
+    pub stride: Option<u64>,
+    pub size_prefix: Option<SizePrefix>,
+    /// The key of the context value that the field's value is stored in for
+    /// the fields that follow it.
+    pub context: Option<String>,
+    /// The comparison of the `version` context value that the field is
+    /// present under, like `version >= 3`.
+    pub if_version: Option<Expr>,
+    /// The spans of all parameters of the field, for diagnostics.
+    pub spans: ParameterSpans,
 }
 
+/// The spans of the values of the parameters in a field's attributes.
+///
+/// Spans carry no meaning beyond diagnostics, so all instances compare equal.
+#[derive(Debug, Clone, Default)]
+pub struct ParameterSpans(HashMap<Path, Span>);
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct BitFieldStorageProperties {
     pub storage_ty: Option<Type>,
     pub bit_numbering: Option<BitNumbering>,
+    /// The members must cover all bits of the storage.
+    pub exhaustive: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
 pub enum Field {
     Direct {
         ident: Option<Ident>,
         ty: Type,
         multi_pass: Option<bool>,
+        deferred: bool,
+        boxed: bool,
+        borrow: bool,
+        condition: Option<Expr>,
         transform: Transform,
         layout_properties: FieldLayoutProperties,
     },
@@ -45,6 +75,36 @@ pub enum Field {
     },
 }
 
+/// A field that is neither serialized nor deserialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedField {
+    pub member: Member,
+    /// The value of the field when deserialized. [`Default::default()`] if not given.
+    pub default: Option<Expr>,
+}
+
+impl SkippedField {
+    /// Parse the field at `index` if it's marked with `skip`.
+    pub fn parse(field: &syn::Field, index: usize) -> Result<Option<Self>, syn::Error> {
+        let sorbit_attrs = field.attrs.iter().filter(|attr| attr.path() == &path::sorbit_attribute());
+        let parameters = parse_nvp_attribute_group(sorbit_attrs)?;
+        let skip = parameters.get(&path::skip()).map(as_literal_bool).transpose()?.unwrap_or(false);
+        let default = parameters.get(&path::default()).map(as_condition).transpose()?;
+        if !skip {
+            return match default {
+                Some(_) => Err(syn::Error::new(
+                    parameters[&path::default()].span(),
+                    "only skipped fields can have a default value, add `skip` to the attribute",
+                )),
+                None => Ok(None),
+            };
+        }
+        check_invalid_parameters(&parameters, [path::skip(), path::default()].iter())?;
+        let member = to_member(field.ident.clone(), index, field.ty.span());
+        Ok(Some(Self { member, default }))
+    }
+}
+
 impl Field {
     pub fn ident(&self) -> Option<&Ident> {
         match self {
@@ -98,27 +158,298 @@ impl Field {
         parameters: HashMap<Path, Expr>,
     ) -> Result<Field, syn::Error> {
         let accepted_parameters = [
-            &[path::multi_pass(), path::value()] as &[Path],
+            &[
+                path::multi_pass(),
+                path::deferred(),
+                path::boxed(),
+                path::borrow(),
+                path::condition(),
+                path::value(),
+                path::unit(),
+                path::storage_ty(),
+                path::len_prefix(),
+                path::count(),
+                path::string(),
+                path::pad(),
+                path::option(),
+                path::checksum(),
+                path::varint(),
+                path::min(),
+                path::max(),
+                path::scale(),
+                path::bias(),
+                path::raw(),
+                path::with(),
+                path::serialize_with(),
+                path::deserialize_with(),
+                path::magic(),
+                path::pointer(),
+                path::offset_of(),
+                path::base(),
+                path::context(),
+                path::if_version(),
+            ] as &[Path],
             &FieldLayoutProperties::accepted_parameters() as &[Path],
         ];
         check_invalid_parameters(&parameters, accepted_parameters.into_iter().flatten())?;
 
         let multi_pass = parameters.get(&path::multi_pass()).map(as_literal_bool).transpose()?;
-        let transform = parameters.get(&path::value()).map(as_transform).transpose()?.unwrap_or_default();
+        let deferred = parameters.get(&path::deferred()).map(as_literal_bool).transpose()?.unwrap_or(false);
+        check_transform_conflicts(
+            &parameters,
+            &[
+                &[path::unit(), path::storage_ty()],
+                &[path::len_prefix()],
+                &[path::count()],
+                &[path::string()],
+                &[path::option()],
+                &[path::checksum()],
+                &[path::varint()],
+                &[path::min(), path::max()],
+                &[path::scale(), path::bias(), path::raw()],
+                &[
+                    path::with(),
+                    path::serialize_with(),
+                    path::deserialize_with(),
+                ],
+                &[path::magic()],
+                &[path::pointer(), path::offset_of()],
+                &[path::value()],
+            ],
+        )?;
+        let varint = parameters.get(&path::varint()).map(as_literal_bool).transpose()?.unwrap_or(false);
+        let min = parameters.get(&path::min()).cloned();
+        let max = parameters.get(&path::max()).cloned();
+        let transform = if let Some(duration) = Self::parse_duration(&parameters)? {
+            duration
+        } else if let Some(len_ty) = parameters.get(&path::len_prefix()).map(as_type).transpose()? {
+            Transform::LengthPrefix(len_ty)
+        } else if let Some(source) = parameters.get(&path::count()).map(as_length_source).transpose()? {
+            Transform::LengthFrom(source)
+        } else if let Some(string) = Self::parse_string(&parameters)? {
+            string
+        } else if let Some(encoding) = parameters.get(&path::option()).map(as_option_encoding).transpose()? {
+            if optional_type(&ty).is_none() {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "a field with an `option` encoding must have the type `Option<T>`",
+                ));
+            }
+            Transform::Option(encoding)
+        } else if let Some(checksum) = parameters.get(&path::checksum()).map(as_checksum).transpose()? {
+            checksum
+        } else if varint {
+            Transform::VarInt
+        } else if min.is_some() || max.is_some() {
+            Transform::Range { min, max }
+        } else if let Some(scale) = Self::parse_scale(&ident, &parameters)? {
+            scale
+        } else if let Some(with) = Self::parse_with(&parameters)? {
+            with
+        } else if let Some(bytes) = parameters.get(&path::magic()).map(as_byte_string).transpose()? {
+            Transform::Magic(bytes)
+        } else if let Some(pointer) = Self::parse_pointer(&parameters)? {
+            pointer
+        } else {
+            parameters.get(&path::value()).map(as_transform).transpose()?.unwrap_or_default()
+        };
+        if deferred && transform != Transform::None {
+            return Err(syn::Error::new(
+                parameters[&path::deferred()].span(),
+                format!("a deferred field cannot also have its value set to `{transform}`"),
+            ));
+        }
+        let boxed = parameters.get(&path::boxed()).map(as_literal_bool).transpose()?.unwrap_or(false);
+        if boxed && transform != Transform::None {
+            return Err(syn::Error::new(
+                parameters[&path::boxed()].span(),
+                format!("a boxed field cannot also have its value set to `{transform}`"),
+            ));
+        }
+        if boxed && boxed_type(&ty).is_none() {
+            return Err(syn::Error::new(ty.span(), "a boxed field must have the type `Box<T>`"));
+        }
+        let borrow = parameters.get(&path::borrow()).map(as_literal_bool).transpose()?.unwrap_or(false);
+        let borrowable = matches!(
+            transform,
+            Transform::None
+                | Transform::LengthBy(_)
+                | Transform::ByteCountBy(_)
+                | Transform::LengthFrom(_)
+                | Transform::LengthPrefix(_)
+                | Transform::String(StringEncoding::LengthPrefix(_))
+        );
+        if borrow && !borrowable {
+            return Err(syn::Error::new(
+                parameters[&path::borrow()].span(),
+                format!("a borrowed field cannot also have its value set to `{transform}`"),
+            ));
+        }
+        if borrow && (boxed || deferred) {
+            return Err(syn::Error::new(
+                parameters[&path::borrow()].span(),
+                "a borrowed field cannot also be boxed or deferred",
+            ));
+        }
         let layout_properties = FieldLayoutProperties::from_parameters(&parameters)?;
-        Ok(Self::Direct { ident, ty, multi_pass, transform, layout_properties })
+        if matches!(transform, Transform::Pointer { .. }) && layout_properties.allow_backward.is_some() {
+            return Err(syn::Error::new(
+                parameters[&path::allow_backward()].span(),
+                "a pointer field cannot also move backward",
+            ));
+        }
+        let condition = parameters.get(&path::condition()).map(as_condition).transpose()?;
+        let condition = match (condition, &layout_properties.if_version) {
+            (Some(_), Some(_)) => {
+                return Err(syn::Error::new(
+                    parameters[&path::if_version()].span(),
+                    "a conditional field cannot also depend on the version",
+                ));
+            }
+            (None, Some(if_version)) => Some(parse_quote!((*__sorbit_version).is_some_and(|version| #if_version))),
+            (condition, None) => condition,
+        };
+        let condition_span =
+            || parameters.get(&path::condition()).unwrap_or_else(|| &parameters[&path::if_version()]).span();
+        if deferred && condition.is_some() {
+            return Err(syn::Error::new(condition_span(), "a deferred field cannot also be conditional"));
+        }
+        if borrow && condition.is_some() {
+            return Err(syn::Error::new(condition_span(), "a borrowed field cannot also be conditional"));
+        }
+        if let (Some(_), Some(_)) = (&condition, &layout_properties.context) {
+            return Err(syn::Error::new(
+                parameters[&path::context()].span(),
+                "a conditional field cannot also set a context value",
+            ));
+        }
+        Ok(Self::Direct { ident, ty, multi_pass, deferred, boxed, borrow, condition, transform, layout_properties })
+    }
+
+    fn parse_duration(parameters: &HashMap<Path, Expr>) -> Result<Option<Transform>, syn::Error> {
+        let unit = parameters.get(&path::unit()).map(as_time_unit).transpose()?;
+        let repr = parameters.get(&path::storage_ty()).map(as_type).transpose()?;
+        match (unit, repr) {
+            (Some(unit), Some(repr)) => Ok(Some(Transform::Duration { unit, repr })),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(syn::Error::new(
+                parameters[&path::unit()].span(),
+                "this duration is missing its binary representation, add `repr=<TYPE>` to the attribute",
+            )),
+            (None, Some(_)) => Err(syn::Error::new(
+                parameters[&path::storage_ty()].span(),
+                "this duration is missing its unit, add `unit=<UNIT>` to the attribute",
+            )),
+        }
+    }
+
+    fn parse_scale(ident: &Option<Ident>, parameters: &HashMap<Path, Expr>) -> Result<Option<Transform>, syn::Error> {
+        let scale = parameters.get(&path::scale()).cloned();
+        let bias = parameters.get(&path::bias()).cloned();
+        let raw = parameters.get(&path::raw()).map(as_type).transpose()?;
+        match (scale, bias, raw) {
+            (Some(scale), bias, raw) => {
+                Ok(Some(Transform::Scale { scale, bias, raw: raw.unwrap_or_else(|| parse_quote!(u64)) }))
+            }
+            (None, None, None) => Ok(None),
+            (None, _, _) => Err(syn::Error::new(
+                parameters
+                    .get(&path::bias())
+                    .or(parameters.get(&path::raw()))
+                    .map(Spanned::span)
+                    .unwrap_or(ident.span()),
+                "this scaled field is missing its scale, add `scale=<VALUE>` to the attribute",
+            )),
+        }
+    }
+
+    fn parse_string(parameters: &HashMap<Path, Expr>) -> Result<Option<Transform>, syn::Error> {
+        let encoding = parameters.get(&path::string()).map(as_string_encoding).transpose()?;
+        let pad = parameters.get(&path::pad()).map(as_literal_int::<u8>).transpose()?;
+        match (encoding, pad) {
+            (Some(StringEncoding::Fixed { len, .. }), pad) => {
+                Ok(Some(Transform::String(StringEncoding::Fixed { len, pad: pad.unwrap_or(0) })))
+            }
+            (Some(_), Some(_)) | (None, Some(_)) => Err(syn::Error::new(
+                parameters[&path::pad()].span(),
+                "only fixed-length strings can be padded, add `string=fixed(<LEN>)` to the attribute",
+            )),
+            (Some(encoding), None) => Ok(Some(Transform::String(encoding))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn parse_pointer(parameters: &HashMap<Path, Expr>) -> Result<Option<Transform>, syn::Error> {
+        let offset = parameters.get(&path::pointer()).map(as_type).transpose()?;
+        let offset_of = parameters.get(&path::offset_of()).map(as_member).transpose()?;
+        let base = parameters.get(&path::base()).map(as_pointer_base).transpose()?;
+        match (offset, offset_of, base) {
+            (Some(_), Some(_), _) => Err(syn::Error::new(
+                parameters[&path::offset_of()].span(),
+                "a pointer field cannot also store the offset of another field",
+            )),
+            (Some(offset), None, base) => Ok(Some(Transform::Pointer { offset, base: base.unwrap_or_default() })),
+            (None, Some(member), base) => Ok(Some(Transform::OffsetOf { member, base: base.unwrap_or_default() })),
+            (None, None, None) => Ok(None),
+            (None, None, Some(_)) => {
+                Err(syn::Error::new(parameters[&path::base()].span(), "`base` requires a `pointer` or an `offset_of`"))
+            }
+        }
+    }
+
+    fn parse_with(parameters: &HashMap<Path, Expr>) -> Result<Option<Transform>, syn::Error> {
+        let module = parameters.get(&path::with()).map(as_path).transpose()?;
+        let serialize = parameters.get(&path::serialize_with()).map(as_path).transpose()?;
+        let deserialize = parameters.get(&path::deserialize_with()).map(as_path).transpose()?;
+        match (module, serialize, deserialize) {
+            (Some(module), None, None) => Ok(Some(Transform::With {
+                serialize: parse_quote!(#module::serialize),
+                deserialize: parse_quote!(#module::deserialize),
+            })),
+            (Some(_), _, _) => Err(syn::Error::new(
+                parameters[&path::with()].span(),
+                "`with` cannot be combined with `serialize_with` or `deserialize_with`",
+            )),
+            (None, Some(serialize), Some(deserialize)) => Ok(Some(Transform::With { serialize, deserialize })),
+            (None, Some(_), None) => Err(syn::Error::new(
+                parameters[&path::serialize_with()].span(),
+                "this field is missing its deserialization function, add `deserialize_with=<PATH>` to the attribute",
+            )),
+            (None, None, Some(_)) => Err(syn::Error::new(
+                parameters[&path::deserialize_with()].span(),
+                "this field is missing its serialization function, add `serialize_with=<PATH>` to the attribute",
+            )),
+            (None, None, None) => Ok(None),
+        }
     }
 
     fn parse_bit_field(ident: Option<Ident>, ty: Type, parameters: HashMap<Path, Expr>) -> Result<Field, syn::Error> {
         let accepted_parameters = [
-            &[path::bit_range(), path::storage_id(), path::value()] as &[Path],
+            &[
+                path::bit_range(),
+                path::storage_id(),
+                path::value(),
+                path::scale(),
+                path::bias(),
+                path::raw(),
+            ] as &[Path],
             &BitFieldStorageProperties::accepted_parameters() as &[Path],
             &FieldLayoutProperties::accepted_parameters() as &[Path],
         ];
         check_invalid_parameters(&parameters, accepted_parameters.into_iter().flatten())?;
 
         let multi_pass = parameters.get(&path::multi_pass()).map(as_literal_bool).transpose()?;
-        let transform = parameters.get(&path::value()).map(as_transform).transpose()?.unwrap_or_default();
+        check_transform_conflicts(
+            &parameters,
+            &[
+                &[path::scale(), path::bias(), path::raw()],
+                &[path::value()],
+            ],
+        )?;
+        let transform = match Self::parse_scale(&ident, &parameters)? {
+            Some(scale) => scale,
+            None => parameters.get(&path::value()).map(as_transform).transpose()?.unwrap_or_default(),
+        };
         let bits = parameters
             .get(&path::bit_range())
             .map(|expr| {
@@ -127,48 +458,123 @@ impl Field {
                     .map_err(|err| syn::Error::new(err.span(), "expected either a literal range or an integer literal"))
             })
             .ok_or(syn::Error::new(
-                ident.span(),
+                parameters.get(&path::storage_id()).map(Spanned::span).unwrap_or(ident.span()),
                 "this bit field is missing the bit range, add `bits=<S>..<E>` or `bits=<B>` to the attribute",
             ))??;
         let storage_ident = parameters.get(&path::storage_id()).map(as_ident).ok_or(syn::Error::new(
-            ident.span(),
+            parameters.get(&path::bit_range()).map(Spanned::span).unwrap_or(ident.span()),
             "this bit field is missing the storage identifier, add `bit_field=<IDENTIFIER>` to the attribute",
         ))??;
         let storage_properties = BitFieldStorageProperties::from_parameters(&parameters)?;
         let layout_properties = FieldLayoutProperties::from_parameters(&parameters)?;
+        if let Some(expr) = parameters.get(&path::stride()) {
+            return Err(syn::Error::new(expr.span(), "a bit field cannot have a `stride`"));
+        }
 
         Ok(Self::Bit { ident, ty, multi_pass, transform, bits, storage_ident, storage_properties, layout_properties })
     }
 }
 
+/// Check that at most one of the `options` that determine the transform of a
+/// field is given. Each option is a group of related parameters, any of which
+/// selects it. The conflict is reported at the option that comes later in
+/// `options`.
+fn check_transform_conflicts(parameters: &HashMap<Path, Expr>, options: &[&[Path]]) -> Result<(), syn::Error> {
+    let is_given = |name: &&Path| match parameters.get(name) {
+        Some(expr) if **name == path::varint() => as_literal_bool(expr).unwrap_or(true),
+        Some(_) => true,
+        None => false,
+    };
+    let mut given = options.iter().filter_map(|option| option.iter().find(is_given));
+    match (given.next(), given.next()) {
+        (Some(first), Some(second)) => Err(syn::Error::new(
+            parameters[second].span(),
+            format!("`{}` cannot be combined with `{}`", first.to_token_stream(), second.to_token_stream()),
+        )),
+        _ => Ok(()),
+    }
+}
+
 impl FieldLayoutProperties {
     pub fn from_parameters(parameters: &HashMap<Path, Expr>) -> Result<Self, syn::Error> {
         let byte_order = parameters.get(&path::byte_order()).map(as_byte_order).transpose()?;
         let offset = parameters.get(&path::offset()).map(as_literal_int).transpose()?;
+        let allow_backward = parameters.get(&path::allow_backward()).map(as_backward).transpose()?;
         let align = parameters.get(&path::align()).map(as_literal_int).transpose()?;
+        let align_absolute = parameters.get(&path::align_absolute()).map(as_literal_int).transpose()?;
         let round = parameters.get(&path::round()).map(as_literal_int).transpose()?;
-        Ok(Self { byte_order, offset, align, round })
+        let stride = parameters.get(&path::stride()).map(as_literal_int).transpose()?;
+        if let (Some(expr), None) = (parameters.get(&path::allow_backward()), offset) {
+            return Err(syn::Error::new(expr.span(), "moving backward requires an `offset` to move to"));
+        }
+        let size_prefix = parse_size_prefix(parameters)?;
+        let context = parameters.get(&path::context()).map(as_context_key).transpose()?;
+        let if_version = parameters.get(&path::if_version()).map(as_version_requirement).transpose()?;
+        let spans = ParameterSpans::new(parameters);
+        Ok(Self {
+            byte_order,
+            offset,
+            allow_backward,
+            align,
+            align_absolute,
+            round,
+            stride,
+            size_prefix,
+            context,
+            if_version,
+            spans,
+        })
     }
 
-    pub fn accepted_parameters() -> [Path; 4] {
+    pub fn accepted_parameters() -> [Path; 9] {
         [
             path::byte_order(),
             path::offset(),
+            path::allow_backward(),
             path::align(),
+            path::align_absolute(),
             path::round(),
+            path::stride(),
+            path::size_prefix(),
+            path::includes_prefix(),
         ]
     }
 }
 
+impl ParameterSpans {
+    pub fn new(parameters: &HashMap<Path, Expr>) -> Self {
+        Self(parameters.iter().map(|(name, value)| (name.clone(), value.span())).collect())
+    }
+
+    /// Return the span of the parameter's value, or `fallback` if the
+    /// parameter is not given.
+    pub fn get_or(&self, name: &Path, fallback: Span) -> Span {
+        self.0.get(name).copied().unwrap_or(fallback)
+    }
+}
+
+impl PartialEq for ParameterSpans {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ParameterSpans {}
+
 impl BitFieldStorageProperties {
     pub fn from_parameters(parameters: &HashMap<Path, Expr>) -> Result<Self, syn::Error> {
         let storage_ty = parameters.get(&path::storage_ty()).map(as_type).transpose()?;
         let bit_numbering = parameters.get(&path::bit_numbering()).map(as_bit_numbering).transpose()?;
-        Ok(Self { storage_ty, bit_numbering })
+        let exhaustive = parameters.get(&path::exhaustive()).map(as_literal_bool).transpose()?.unwrap_or(false);
+        Ok(Self { storage_ty, bit_numbering, exhaustive })
     }
 
-    pub fn accepted_parameters() -> [Path; 2] {
-        [path::storage_ty(), path::bit_numbering()]
+    pub fn accepted_parameters() -> [Path; 3] {
+        [
+            path::storage_ty(),
+            path::bit_numbering(),
+            path::exhaustive(),
+        ]
     }
 }
 
@@ -176,6 +582,10 @@ impl BitFieldStorageProperties {
 mod tests {
     use super::*;
 
+    use crate::attribute::{ChecksumAlgorithm, LengthSource, OptionEncoding, PointerBase, TimeUnit};
+    use proc_macro2::TokenStream;
+    use rstest::rstest;
+
     use syn::parse_quote;
 
     #[test]
@@ -188,6 +598,10 @@ mod tests {
             ident: parse_quote!(field),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: Default::default(),
         };
@@ -205,6 +619,10 @@ mod tests {
             ident: parse_quote!(field),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: Default::default(),
         };
@@ -222,23 +640,53 @@ mod tests {
             ident: parse_quote!(field),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: FieldLayoutProperties {
                 byte_order: None,
                 offset: Some(1),
+                allow_backward: None,
                 align: Some(2),
+                align_absolute: None,
                 round: Some(3),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
             },
         };
         assert_eq!(actual.unwrap(), expected);
     }
 
     #[test]
-    fn direct_with_layout_split() {
+    fn direct_with_align_absolute() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(offset=1)]
-            #[sorbit(align=2)]
-            #[sorbit(round=3)]
+            #[sorbit(align_absolute=512)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: FieldLayoutProperties { align_absolute: Some(512), ..Default::default() },
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_with_size_prefix() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(size_prefix=u16, includes_prefix=true)]
             field: u8
         };
         let actual = Field::try_from(input);
@@ -246,222 +694,1197 @@ mod tests {
             ident: parse_quote!(field),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
             layout_properties: FieldLayoutProperties {
-                byte_order: None,
-                offset: Some(1),
-                align: Some(2),
-                round: Some(3),
+                size_prefix: Some(SizePrefix { ty: parse_quote!(u16), includes_prefix: true }),
+                ..Default::default()
             },
         };
         assert_eq!(actual.unwrap(), expected);
     }
 
     #[test]
-    #[should_panic]
-    fn direct_with_layout_redefined() {
+    fn direct_with_includes_prefix_only() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(align=2)]
-            #[sorbit(align=3)]
+            #[sorbit(includes_prefix=true)]
             field: u8
         };
-        Field::try_from(input).unwrap();
+        assert!(Field::try_from(input).is_err());
     }
 
     #[test]
-    #[should_panic]
-    fn direct_invalid_meta_key() {
+    fn direct_condition() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(align=2, invalid_key=4)]
+            #[sorbit(if = "self.flags & 0x80 != 0")]
             field: u8
         };
-        Field::try_from(input).unwrap();
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: Some(parse_quote!(self.flags & 0x80 != 0)),
+            transform: Transform::None,
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
     }
 
     #[test]
-    #[should_panic]
-    fn direct_invalid_meta_value() {
+    fn direct_condition_deferred() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(align=invalid_value)]
+            #[sorbit(if = self.present, deferred)]
             field: u8
         };
-        Field::try_from(input).unwrap();
+        assert!(Field::try_from(input).is_err());
     }
 
     #[test]
-    fn bit_default_merged() {
+    fn direct_if_version() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field, bits=1..3)]
-            field: u8
+            #[sorbit(if_version = ">= 3")]
+            field: Option<u8>
         };
         let actual = Field::try_from(input);
-        let expected = Field::Bit {
+        let expected = Field::Direct {
             ident: parse_quote!(field),
-            ty: parse_quote!(u8),
+            ty: parse_quote!(Option<u8>),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: Some(parse_quote!((*__sorbit_version).is_some_and(|version| version >= 3))),
             transform: Transform::None,
-            bits: 1..3,
-            storage_ident: parse_quote!(_bit_field),
-            storage_properties: Default::default(),
-            layout_properties: Default::default(),
+            layout_properties: FieldLayoutProperties {
+                if_version: Some(parse_quote!(version >= 3)),
+                ..Default::default()
+            },
         };
         assert_eq!(actual.unwrap(), expected);
     }
 
+    #[rstest]
+    #[case::not_a_string(parse_quote!(#[sorbit(if_version = 3)] field: u8))]
+    #[case::not_a_comparison(parse_quote!(#[sorbit(if_version = "3")] field: u8))]
+    #[case::not_a_single_comparison(parse_quote!(#[sorbit(if_version = ">= 3 && true")] field: u8))]
+    #[case::with_condition(parse_quote!(#[sorbit(if_version = ">= 3", if = "true")] field: u8))]
+    #[case::deferred(parse_quote!(#[sorbit(if_version = ">= 3", deferred)] field: u8))]
+    #[case::bit_field(parse_quote!(#[sorbit(if_version = ">= 3", bit_field=_flags, repr=u8, bits=0)] field: bool))]
+    fn direct_if_version_invalid(#[case] input: syn::Field) {
+        assert!(Field::try_from(input).is_err());
+    }
+
     #[test]
-    fn bit_default_split() {
+    fn direct_context() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field)]
-            #[sorbit(bits=1..3)]
+            #[sorbit(ctx = "version")]
             field: u8
         };
         let actual = Field::try_from(input);
-        let expected = Field::Bit {
+        let expected = Field::Direct {
             ident: parse_quote!(field),
             ty: parse_quote!(u8),
             multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
             transform: Transform::None,
-            bits: 1..3,
-            storage_ident: parse_quote!(_bit_field),
-            storage_properties: Default::default(),
-            layout_properties: Default::default(),
+            layout_properties: FieldLayoutProperties { context: Some("version".into()), ..Default::default() },
         };
         assert_eq!(actual.unwrap(), expected);
     }
 
+    #[rstest]
+    #[case::not_a_string(parse_quote!(#[sorbit(ctx = version)] field: u8))]
+    #[case::empty(parse_quote!(#[sorbit(ctx = "")] field: u8))]
+    #[case::conditional(parse_quote!(#[sorbit(ctx = "version", if = "true")] field: u8))]
+    fn direct_context_invalid(#[case] input: syn::Field) {
+        assert!(Field::try_from(input).is_err());
+    }
+
     #[test]
-    fn bit_foreign_attribute() {
+    fn direct_duration() {
         let input: syn::Field = parse_quote! {
-            #[derive(Debug)]
-            #[sorbit(bit_field=_bit_field, bits=1..3)]
-            field: u8
+            #[sorbit(unit="ms", repr=u32)]
+            field: Duration
         };
         let actual = Field::try_from(input);
-        let expected = Field::Bit {
+        let expected = Field::Direct {
             ident: parse_quote!(field),
-            ty: parse_quote!(u8),
+            ty: parse_quote!(Duration),
             multi_pass: None,
-            transform: Transform::None,
-            bits: 1..3,
-            storage_ident: parse_quote!(_bit_field),
-            storage_properties: Default::default(),
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::Duration { unit: TimeUnit::Milliseconds, repr: parse_quote!(u32) },
             layout_properties: Default::default(),
         };
         assert_eq!(actual.unwrap(), expected);
     }
 
     #[test]
-    fn bit_with_layout_merged() {
+    fn direct_duration_missing_repr() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field, bits=1..3, offset=1, align=2, round=3)]
-            field: u8
+            #[sorbit(unit=s)]
+            field: Duration
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_duration_invalid_unit() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(unit="days", repr=u32)]
+            field: Duration
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_duration_with_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(unit=s, repr=u32, value=same)]
+            field: Duration
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_len_prefix() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(len_prefix=u16)]
+            field: Vec<u8>
         };
         let actual = Field::try_from(input);
-        let expected = Field::Bit {
+        let expected = Field::Direct {
             ident: parse_quote!(field),
-            ty: parse_quote!(u8),
+            ty: parse_quote!(Vec<u8>),
             multi_pass: None,
-            transform: Transform::None,
-            bits: 1..3,
-            storage_ident: parse_quote!(_bit_field),
-            storage_properties: Default::default(),
-            layout_properties: FieldLayoutProperties {
-                byte_order: None,
-                offset: Some(1),
-                align: Some(2),
-                round: Some(3),
-            },
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::LengthPrefix(parse_quote!(u16)),
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::expression(parse_quote!(count = "self.header.len"), LengthSource::Expr(parse_quote!(self.header.len)))]
+    #[case::context(parse_quote!(count = ctx("len")), LengthSource::Context("len".into()))]
+    fn direct_count(#[case] parameter: syn::Meta, #[case] source: LengthSource) {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(#parameter)]
+            field: Vec<u8>
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(Vec<u8>),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::LengthFrom(source),
+            layout_properties: Default::default(),
         };
         assert_eq!(actual.unwrap(), expected);
     }
 
+    #[rstest]
+    #[case::with_value(parse_quote!(#[sorbit(count = self.len, value = len_by(len))] field: Vec<u8>))]
+    #[case::with_len_prefix(parse_quote!(#[sorbit(count = self.len, len_prefix = u8)] field: Vec<u8>))]
+    #[case::context_without_key(parse_quote!(#[sorbit(count = ctx())] field: Vec<u8>))]
+    fn direct_count_invalid(#[case] input: syn::Field) {
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[rstest]
+    #[case::default_base(parse_quote!(pointer = u32), PointerBase::Absolute)]
+    #[case::composite(parse_quote!(pointer = u32, base = composite), PointerBase::Composite)]
+    #[case::relative(parse_quote!(pointer = u32, base = relative), PointerBase::Relative)]
+    fn direct_pointer(#[case] parameters: TokenStream, #[case] base: PointerBase) {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(#parameters)]
+            field: [u8; 4]
+        };
+        let actual = Field::try_from(input).unwrap();
+        assert_eq!(actual.transform(), &Transform::Pointer { offset: parse_quote!(u32), base });
+    }
+
+    #[rstest]
+    #[case::base_without_pointer(parse_quote!(#[sorbit(base = relative)] field: u8))]
+    #[case::unknown_base(parse_quote!(#[sorbit(pointer = u32, base = file)] field: u8))]
+    #[case::with_value(parse_quote!(#[sorbit(pointer = u32, value = constant(1))] field: u8))]
+    #[case::with_varint(parse_quote!(#[sorbit(pointer = u32, varint)] field: u8))]
+    #[case::deferred(parse_quote!(#[sorbit(pointer = u32, deferred)] field: u8))]
+    #[case::overlay(parse_quote!(#[sorbit(pointer = u32, offset = 0, allow_backward = overlay)] field: u8))]
+    fn direct_pointer_invalid(#[case] input: syn::Field) {
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[rstest]
+    #[case::default_base(parse_quote!(offset_of = payload), PointerBase::Absolute)]
+    #[case::composite(parse_quote!(offset_of = payload, base = composite), PointerBase::Composite)]
+    #[case::relative(parse_quote!(offset_of = payload, base = relative), PointerBase::Relative)]
+    fn direct_offset_of(#[case] parameters: TokenStream, #[case] base: PointerBase) {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(#parameters)]
+            field: u32
+        };
+        let actual = Field::try_from(input).unwrap();
+        assert_eq!(actual.transform(), &Transform::OffsetOf { member: parse_quote!(payload), base });
+    }
+
+    #[rstest]
+    #[case::not_a_member(parse_quote!(#[sorbit(offset_of = self.payload)] field: u32))]
+    #[case::with_pointer(parse_quote!(#[sorbit(offset_of = payload, pointer = u32)] field: u32))]
+    #[case::with_value(parse_quote!(#[sorbit(offset_of = payload, value = constant(1))] field: u32))]
+    #[case::with_varint(parse_quote!(#[sorbit(offset_of = payload, varint)] field: u32))]
+    #[case::deferred(parse_quote!(#[sorbit(offset_of = payload, deferred)] field: u32))]
+    fn direct_offset_of_invalid(#[case] input: syn::Field) {
+        assert!(Field::try_from(input).is_err());
+    }
+
     #[test]
-    fn bit_with_layout_split() {
+    fn direct_len_prefix_with_value() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field, bits=1..3)]
-            #[sorbit(offset=1)]
-            #[sorbit(align=2)]
-            #[sorbit(round=3)]
-            field: u8
+            #[sorbit(len_prefix=u16, value=same)]
+            field: Vec<u8>
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_string_fixed() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(string=fixed(32), pad=0x20)]
+            field: String
         };
         let actual = Field::try_from(input);
-        let expected = Field::Bit {
+        let expected = Field::Direct {
             ident: parse_quote!(field),
-            ty: parse_quote!(u8),
+            ty: parse_quote!(String),
             multi_pass: None,
-            transform: Transform::None,
-            bits: 1..3,
-            storage_ident: parse_quote!(_bit_field),
-            storage_properties: Default::default(),
-            layout_properties: FieldLayoutProperties {
-                byte_order: None,
-                offset: Some(1),
-                align: Some(2),
-                round: Some(3),
-            },
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::String(StringEncoding::Fixed { len: 32, pad: 0x20 }),
+            layout_properties: Default::default(),
         };
         assert_eq!(actual.unwrap(), expected);
     }
 
     #[test]
-    #[should_panic]
-    fn bit_bits_redefined() {
+    fn direct_string_len_prefix() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field, bits=1..3)]
-            #[sorbit(bits=1..4)]
-            field: u8
+            #[sorbit(string=len_prefix(u8))]
+            field: String
         };
-        Field::try_from(input).unwrap();
+        let transform = Transform::String(StringEncoding::LengthPrefix(parse_quote!(u8)));
+        assert!(matches!(Field::try_from(input), Ok(Field::Direct { transform: actual, .. }) if actual == transform));
     }
 
     #[test]
-    #[should_panic]
-    fn bit_name_missing() {
+    fn direct_string_invalid_encoding() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bits=1..3)]
-            field: u8
+            #[sorbit(string=utf16)]
+            field: String
         };
-        Field::try_from(input).unwrap();
+        assert!(Field::try_from(input).is_err());
     }
 
     #[test]
-    #[should_panic]
-    fn bit_bits_missing() {
+    fn direct_string_pad_without_fixed() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field)]
-            field: u8
+            #[sorbit(string=null_terminated, pad=0x20)]
+            field: String
         };
-        Field::try_from(input).unwrap();
+        assert!(Field::try_from(input).is_err());
     }
 
     #[test]
-    #[should_panic]
-    fn bit_with_layout_redefined() {
+    fn direct_string_with_value() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field, bits=1..3, offset=1)]
-            #[sorbit(offset=2)]
-            field: u8
+            #[sorbit(string=null_terminated, value=same)]
+            field: String
         };
-        Field::try_from(input).unwrap();
+        assert!(Field::try_from(input).is_err());
     }
 
     #[test]
-    #[should_panic]
-    fn bit_invalid_meta_key() {
+    fn direct_option() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field, bits=1..3, invalid_key=1)]
-            field: u8
+            #[sorbit(option=sentinel(0xFF))]
+            field: Option<u8>
         };
-        Field::try_from(input).unwrap();
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(Option<u8>),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::Option(OptionEncoding::Sentinel(parse_quote!(0xFF))),
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
     }
 
     #[test]
-    #[should_panic]
-    fn bit_invalid_meta_value() {
+    fn direct_option_not_option() {
         let input: syn::Field = parse_quote! {
-            #[sorbit(bit_field=_bit_field, bits=1..3, offset=invalid_value)]
+            #[sorbit(option=remaining)]
             field: u8
         };
-        Field::try_from(input).unwrap();
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_option_invalid_encoding() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(option=bit(0))]
+            field: Option<u8>
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_option_with_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(option=remaining, value=same)]
+            field: Option<u8>
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_checksum() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(checksum=crc32(header, 2))]
+            field: u32
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u32),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::Checksum {
+                algorithm: ChecksumAlgorithm::Crc32,
+                over: vec![parse_quote!(header), parse_quote!(2)],
+            },
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_checksum_without_fields() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(checksum=crc16())]
+            field: u16
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_checksum_invalid_algorithm() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(checksum=md5(header))]
+            field: u16
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_checksum_with_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(checksum=crc16(header), value=same)]
+            field: u16
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_varint() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(varint)]
+            field: u32
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u32),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::VarInt,
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_varint_with_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(varint, value=len(items))]
+            field: u32
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_varint_with_len_prefix() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(len_prefix=u8, varint=true)]
+            field: Vec<u32>
+        };
+        let error = Field::try_from(input).unwrap_err();
+        assert_eq!(error.to_string(), "`len_prefix` cannot be combined with `varint`");
+    }
+
+    #[test]
+    fn direct_varint_false_with_len_prefix() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(len_prefix=u8, varint=false)]
+            field: Vec<u32>
+        };
+        let actual = Field::try_from(input).unwrap();
+        assert_eq!(actual.transform(), &Transform::LengthPrefix(parse_quote!(u8)));
+    }
+
+    #[test]
+    fn direct_range() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(min=1, max=100)]
+            field: u32
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u32),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::Range { min: Some(parse_quote!(1)), max: Some(parse_quote!(100)) },
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_range_min_only() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(min=1)]
+            field: u32
+        };
+        let actual = Field::try_from(input).unwrap();
+        assert_eq!(actual.transform(), &Transform::Range { min: Some(parse_quote!(1)), max: None });
+    }
+
+    #[test]
+    fn direct_range_with_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(max=100, value=len(items))]
+            field: u32
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_scale() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(scale=0.5, bias=-40.0, raw=u16)]
+            field: f32
+        };
+        let actual = Field::try_from(input).unwrap();
+        let expected =
+            Transform::Scale { scale: parse_quote!(0.5), bias: Some(parse_quote!(-40.0)), raw: parse_quote!(u16) };
+        assert_eq!(actual.transform(), &expected);
+    }
+
+    #[test]
+    fn direct_scale_missing_scale() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bias=-40.0)]
+            field: f32
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_magic() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(magic=b"RIFF")]
+            field: [u8; 4]
+        };
+        let actual = Field::try_from(input).unwrap();
+        assert_eq!(actual.transform(), &Transform::Magic(parse_quote!(b"RIFF")));
+    }
+
+    #[test]
+    fn direct_magic_not_byte_string() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(magic="RIFF")]
+            field: [u8; 4]
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn bit_field_byte_array_storage() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_b, repr=[u8; 12], bits=0..4)]
+            field: u8
+        };
+        let Field::Bit { storage_properties, .. } = Field::try_from(input).unwrap() else {
+            panic!("expected a bit field");
+        };
+        assert_eq!(storage_properties.storage_ty, Some(parse_quote!([u8; 12])));
+    }
+
+    #[test]
+    fn bit_field_scale() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_b, bits=0..12, scale=0.01)]
+            field: f32
+        };
+        let actual = Field::try_from(input).unwrap();
+        let expected = Transform::Scale { scale: parse_quote!(0.01), bias: None, raw: parse_quote!(u64) };
+        assert_eq!(actual.transform(), &expected);
+    }
+
+    #[test]
+    fn bit_field_scale_with_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_b, bits=0..12, scale=0.01, value=1)]
+            field: f32
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_with_module() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(with=encoding::bcd)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::With {
+                serialize: parse_quote!(encoding::bcd::serialize),
+                deserialize: parse_quote!(encoding::bcd::deserialize),
+            },
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_with_functions() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(serialize_with="to_bcd", deserialize_with=from_bcd)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::With { serialize: parse_quote!(to_bcd), deserialize: parse_quote!(from_bcd) },
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_with_missing_function() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(serialize_with=to_bcd)]
+            field: u8
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_with_module_and_function() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(with=bcd, deserialize_with=from_bcd)]
+            field: u8
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_with_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(with=bcd, value=same)]
+            field: u8
+        };
+        assert!(Field::try_from(input).is_err());
+    }
+
+    #[test]
+    fn direct_with_layout_split() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(offset=1)]
+            #[sorbit(align=2)]
+            #[sorbit(round=3)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: FieldLayoutProperties {
+                byte_order: None,
+                offset: Some(1),
+                allow_backward: None,
+                align: Some(2),
+                align_absolute: None,
+                round: Some(3),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
+            },
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn direct_with_layout_redefined() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(align=2)]
+            #[sorbit(align=3)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    fn direct_with_layout_overridden() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(offset=1, align=2)]
+            #[sorbit(override(align=3), round=4)]
+            #[sorbit(override(align=5))]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: FieldLayoutProperties {
+                byte_order: None,
+                offset: Some(1),
+                allow_backward: None,
+                align: Some(5),
+                align_absolute: None,
+                round: Some(4),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
+            },
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_with_allow_backward() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(offset=1, allow_backward=seek)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: FieldLayoutProperties {
+                offset: Some(1),
+                allow_backward: Some(Backward::Seek),
+                ..Default::default()
+            },
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_with_allow_backward_overlay() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(offset=0, allow_backward=overlay)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: FieldLayoutProperties {
+                offset: Some(0),
+                allow_backward: Some(Backward::Overlay),
+                ..Default::default()
+            },
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn direct_with_allow_backward_reorder() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(offset=4, allow_backward=reorder)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: FieldLayoutProperties {
+                offset: Some(4),
+                allow_backward: Some(Backward::Reorder),
+                ..Default::default()
+            },
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn direct_with_allow_backward_without_offset() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(allow_backward=seek)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn direct_with_layout_redefined_after_override() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(align=2)]
+            #[sorbit(override(align=3))]
+            #[sorbit(align=2)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    fn direct_deferred() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(deferred)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            deferred: true,
+            boxed: false,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn direct_deferred_with_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(deferred, value=len(items))]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    fn direct_boxed() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(boxed)]
+            field: Box<[u8; 4096]>
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(Box<[u8; 4096]>),
+            multi_pass: None,
+            deferred: false,
+            boxed: true,
+            borrow: false,
+            condition: None,
+            transform: Transform::None,
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn direct_boxed_not_box() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(boxed)]
+            field: [u8; 4096]
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    fn direct_borrow() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(borrow, len_prefix=u8)]
+            field: &'a [u8]
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Direct {
+            ident: parse_quote!(field),
+            ty: parse_quote!(&'a [u8]),
+            multi_pass: None,
+            deferred: false,
+            boxed: false,
+            borrow: true,
+            condition: None,
+            transform: Transform::LengthPrefix(parse_quote!(u8)),
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn direct_borrow_boxed() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(borrow, boxed)]
+            field: Box<Inner<'a>>
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn direct_invalid_meta_key() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(align=2, invalid_key=4)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn direct_invalid_meta_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(align=invalid_value)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    fn bit_default_merged() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field, bits=1..3)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Bit {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            transform: Transform::None,
+            bits: 1..3,
+            storage_ident: parse_quote!(_bit_field),
+            storage_properties: Default::default(),
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn bit_default_split() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field)]
+            #[sorbit(bits=1..3)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Bit {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            transform: Transform::None,
+            bits: 1..3,
+            storage_ident: parse_quote!(_bit_field),
+            storage_properties: Default::default(),
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn bit_foreign_attribute() {
+        let input: syn::Field = parse_quote! {
+            #[derive(Debug)]
+            #[sorbit(bit_field=_bit_field, bits=1..3)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Bit {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            transform: Transform::None,
+            bits: 1..3,
+            storage_ident: parse_quote!(_bit_field),
+            storage_properties: Default::default(),
+            layout_properties: Default::default(),
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn bit_with_layout_merged() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field, bits=1..3, offset=1, align=2, round=3)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Bit {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            transform: Transform::None,
+            bits: 1..3,
+            storage_ident: parse_quote!(_bit_field),
+            storage_properties: Default::default(),
+            layout_properties: FieldLayoutProperties {
+                byte_order: None,
+                offset: Some(1),
+                allow_backward: None,
+                align: Some(2),
+                align_absolute: None,
+                round: Some(3),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
+            },
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn bit_with_layout_split() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field, bits=1..3)]
+            #[sorbit(offset=1)]
+            #[sorbit(align=2)]
+            #[sorbit(round=3)]
+            field: u8
+        };
+        let actual = Field::try_from(input);
+        let expected = Field::Bit {
+            ident: parse_quote!(field),
+            ty: parse_quote!(u8),
+            multi_pass: None,
+            transform: Transform::None,
+            bits: 1..3,
+            storage_ident: parse_quote!(_bit_field),
+            storage_properties: Default::default(),
+            layout_properties: FieldLayoutProperties {
+                byte_order: None,
+                offset: Some(1),
+                allow_backward: None,
+                align: Some(2),
+                align_absolute: None,
+                round: Some(3),
+                stride: None,
+                size_prefix: None,
+                context: None,
+                if_version: None,
+                spans: Default::default(),
+            },
+        };
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bit_bits_redefined() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field, bits=1..3)]
+            #[sorbit(bits=1..4)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn bit_name_missing() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bits=1..3)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn bit_bits_missing() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn bit_with_layout_redefined() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field, bits=1..3, offset=1)]
+            #[sorbit(offset=2)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn bit_invalid_meta_key() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field, bits=1..3, invalid_key=1)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn bit_invalid_meta_value() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(bit_field=_bit_field, bits=1..3, offset=invalid_value)]
+            field: u8
+        };
+        Field::try_from(input).unwrap();
+    }
+
+    #[test]
+    fn skipped() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(skip)]
+            field: u8
+        };
+        let expected = SkippedField { member: parse_quote!(field), default: None };
+        assert_eq!(SkippedField::parse(&input, 0).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn skipped_default() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(skip, default=5)]
+            u8
+        };
+        let expected = SkippedField { member: parse_quote!(1), default: Some(parse_quote!(5)) };
+        assert_eq!(SkippedField::parse(&input, 1).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn not_skipped() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(offset=2)]
+            field: u8
+        };
+        assert_eq!(SkippedField::parse(&input, 0).unwrap(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn default_without_skip() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(default=5)]
+            field: u8
+        };
+        SkippedField::parse(&input, 0).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn skipped_invalid_meta_key() {
+        let input: syn::Field = parse_quote! {
+            #[sorbit(skip, offset=2)]
+            field: u8
+        };
+        SkippedField::parse(&input, 0).unwrap();
     }
 }
@@ -1,5 +1,5 @@
 mod field;
 mod r#struct;
 
-pub use field::{BitFieldStorageProperties, Field, FieldLayoutProperties};
+pub use field::{BitFieldStorageProperties, Field, FieldLayoutProperties, SkippedField};
 pub use r#struct::Struct;
@@ -1,18 +1,45 @@
-use syn::{DeriveInput, Generics, Ident, spanned::Spanned as _};
+use std::collections::HashMap;
 
-use super::field::Field;
+use quote::ToTokens as _;
+use syn::{DeriveInput, Expr, Generics, Ident, LitByteStr, Path, WherePredicate, spanned::Spanned as _};
 
-use crate::attribute::{ByteOrder, as_byte_order, as_literal_int, parse_nvp_attribute_group, path};
+use super::field::{Field, FieldLayoutProperties, SkippedField};
+
+use crate::attribute::{
+    BitNumbering, ByteOrder, SizePrefix, Transform, as_bit_numbering, as_byte_order, as_byte_string, as_literal_bool,
+    as_literal_int, as_path, as_where_predicates, parse_nvp_attribute_group, parse_size_prefix, path,
+};
 use crate::utility::check_invalid_parameters;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Struct {
     pub ident: Ident,
     pub generics: Generics,
+    /// The bounds that replace the inferred bounds of the generic parameters.
+    pub bound: Option<Vec<WherePredicate>>,
     pub byte_order: Option<ByteOrder>,
     pub len: Option<u64>,
     pub round: Option<u64>,
+    pub dyn_byte_order: bool,
+    /// The struct is (de)serialized exactly as its only field.
+    pub transparent: bool,
+    /// The byte counts are measured before the fields are serialized.
+    pub multi_pass: bool,
+    /// The maximum number of composites the struct may be nested within.
+    pub max_depth: Option<u32>,
+    /// The fixed size in bytes that the layout of the struct must have.
+    pub assert_size: Option<u64>,
+    /// The byte that padding is filled with.
+    pub fill: Option<u8>,
+    /// The default bit numbering of the struct's bit fields.
+    pub bit_numbering: Option<BitNumbering>,
+    /// The function that checks the struct after it's deserialized.
+    pub validate: Option<Path>,
+    /// The bytes that precede the fields of the struct.
+    pub magic: Option<LitByteStr>,
+    pub size_prefix: Option<SizePrefix>,
     pub fields: Vec<Field>,
+    pub skipped: Vec<SkippedField>,
 }
 
 impl TryFrom<DeriveInput> for Struct {
@@ -27,20 +54,85 @@ impl TryFrom<DeriveInput> for Struct {
                     path::byte_order(),
                     path::len(),
                     path::round(),
-                    path::catch_all(), // This is a bit hacky. Listed here only for fielded enum variants, struct ignores it.
+                    path::dyn_byte_order(),
+                    path::size_prefix(),
+                    path::includes_prefix(),
+                    path::transparent(),
+                    path::multi_pass(),
+                    path::bound(),
+                    path::max_depth(),
+                    path::assert_size(),
+                    path::fill(),
+                    path::bit_numbering(),
+                    path::validate(),
+                    path::magic(),
+                    // This is a bit hacky. Listed here only for fielded enum variants, struct ignores them.
+                    path::catch_all(),
+                    path::tag(),
                 ];
                 check_invalid_parameters(&parameters, accepted_parameters.iter())?;
 
-                let byte_order = parameters.get(&path::byte_order()).map(|expr| as_byte_order(expr)).transpose()?;
-                let len = parameters.get(&path::len()).map(|expr| as_literal_int(expr)).transpose()?;
-                let round = parameters.get(&path::round()).map(|expr| as_literal_int(expr)).transpose()?;
-                let fields = data_struct
-                    .fields
-                    .into_iter()
-                    .map(|field| Field::try_from(field))
-                    .collect::<Result<Vec<_>, _>>()?;
+                let bound = parameters.get(&path::bound()).map(as_where_predicates).transpose()?;
+                let byte_order = parameters.get(&path::byte_order()).map(as_byte_order).transpose()?;
+                let len = parameters.get(&path::len()).map(as_literal_int).transpose()?;
+                let round = parameters.get(&path::round()).map(as_literal_int).transpose()?;
+                let dyn_byte_order = parameters.get(&path::dyn_byte_order()).map(as_literal_bool).transpose()?;
+                if let (Some(_), Some(true)) = (byte_order, dyn_byte_order) {
+                    let message = "`dyn_byte_order` cannot be combined with a fixed `byte_order`";
+                    return Err(syn::Error::new(value.ident.span(), message));
+                }
+                let dyn_byte_order = dyn_byte_order.unwrap_or(false);
+                let size_prefix = parse_size_prefix(&parameters)?;
+                let max_depth = parameters.get(&path::max_depth()).map(as_literal_int).transpose()?;
+                let assert_size = parameters.get(&path::assert_size()).map(as_literal_int).transpose()?;
+                if let (Some(_), Some(param)) = (assert_size, value.generics.params.first()) {
+                    let message = "`assert_size` cannot be used on generic structures";
+                    return Err(syn::Error::new(param.span(), message));
+                }
+                let fill = parameters.get(&path::fill()).map(as_literal_int).transpose()?;
+                let bit_numbering = parameters.get(&path::bit_numbering()).map(as_bit_numbering).transpose()?;
+                let validate = parameters.get(&path::validate()).map(as_path).transpose()?;
+                let magic = parameters.get(&path::magic()).map(as_byte_string).transpose()?;
+                let mut fields = Vec::new();
+                let mut skipped = Vec::new();
+                for (index, field) in data_struct.fields.into_iter().enumerate() {
+                    match SkippedField::parse(&field, index)? {
+                        Some(skipped_field) => skipped.push(skipped_field),
+                        None if field.ident.is_none() && !skipped.is_empty() => {
+                            let message = "the fields of a tuple struct cannot follow a skipped field";
+                            return Err(syn::Error::new(field.ty.span(), message));
+                        }
+                        None => fields.push(Field::try_from(field)?),
+                    }
+                }
+
+                let transparent =
+                    parameters.get(&path::transparent()).map(as_literal_bool).transpose()?.unwrap_or(false);
+                if transparent {
+                    Self::check_transparent(&value.ident, &parameters, &fields)?;
+                }
+                let multi_pass = parameters.get(&path::multi_pass()).map(as_literal_bool).transpose()?.unwrap_or(false);
 
-                Ok(Self { ident: value.ident, generics: value.generics, byte_order, len, round, fields })
+                Ok(Self {
+                    ident: value.ident,
+                    generics: value.generics,
+                    bound,
+                    byte_order,
+                    len,
+                    round,
+                    dyn_byte_order,
+                    transparent,
+                    multi_pass,
+                    max_depth,
+                    assert_size,
+                    fill,
+                    bit_numbering,
+                    validate,
+                    magic,
+                    size_prefix,
+                    fields,
+                    skipped,
+                })
             }
             syn::Data::Enum(_) => Err(syn::Error::new(value.span(), "expected a struct, got an enum")),
             syn::Data::Union(_) => Err(syn::Error::new(value.span(), "expected a struct, got a union")),
@@ -48,6 +140,30 @@ impl TryFrom<DeriveInput> for Struct {
     }
 }
 
+impl Struct {
+    fn check_transparent(ident: &Ident, parameters: &HashMap<Path, Expr>, fields: &[Field]) -> Result<(), syn::Error> {
+        if let Some((name, value)) = parameters.iter().find(|(name, _)| *name != &path::transparent()) {
+            let message = format!("`transparent` cannot be combined with `{}`", name.to_token_stream());
+            return Err(syn::Error::new(value.span(), message));
+        }
+        match fields {
+            [
+                Field::Direct {
+                    deferred: false,
+                    boxed: false,
+                    borrow: false,
+                    condition: None,
+                    transform: Transform::None,
+                    layout_properties,
+                    ..
+                },
+            ] if layout_properties == &FieldLayoutProperties::default() => Ok(()),
+            [field] => Err(syn::Error::new(field.span(), "the field of a transparent struct cannot have attributes")),
+            _ => Err(syn::Error::new(ident.span(), "a transparent struct must have exactly one non-skipped field")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use syn::parse_quote;
@@ -65,10 +181,22 @@ mod tests {
         let expected = Struct {
             ident: parse_quote!(Struct),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
             len: None,
             round: None,
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            bit_numbering: None,
+            validate: None,
+            magic: None,
+            size_prefix: None,
             fields: vec![],
+            skipped: vec![],
         };
         assert_eq!(actual, expected);
     }
@@ -83,10 +211,22 @@ mod tests {
         let expected = Struct {
             ident: parse_quote!(Struct),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
             len: Some(1),
             round: Some(2),
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            bit_numbering: None,
+            validate: None,
+            magic: None,
+            size_prefix: None,
             fields: vec![],
+            skipped: vec![],
         };
         assert_eq!(actual, expected);
     }
@@ -102,14 +242,76 @@ mod tests {
         let expected = Struct {
             ident: parse_quote!(Struct),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
             len: Some(1),
             round: Some(2),
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            bit_numbering: None,
+            validate: None,
+            magic: None,
+            size_prefix: None,
             fields: vec![],
+            skipped: vec![],
         };
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn size_prefix() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(size_prefix = u32)]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        let expected = Struct {
+            ident: parse_quote!(Struct),
+            generics: Generics::default(),
+            bound: None,
+            byte_order: None,
+            len: None,
+            round: None,
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            bit_numbering: None,
+            validate: None,
+            magic: None,
+            size_prefix: Some(SizePrefix { ty: parse_quote!(u32), includes_prefix: false }),
+            fields: vec![],
+            skipped: vec![],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn dyn_byte_order() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(dyn_byte_order)]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert!(actual.dyn_byte_order);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dyn_byte_order_with_byte_order() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(dyn_byte_order, byte_order = big_endian)]
+            struct Struct {}
+        );
+        Struct::try_from(input).unwrap();
+    }
+
     #[test]
     fn foreign_attribute() {
         let input: DeriveInput = parse_quote!(
@@ -120,10 +322,22 @@ mod tests {
         let expected = Struct {
             ident: parse_quote!(Struct),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
             len: None,
             round: None,
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            bit_numbering: None,
+            validate: None,
+            magic: None,
+            size_prefix: None,
             fields: vec![],
+            skipped: vec![],
         };
         assert_eq!(actual, expected);
     }
@@ -139,20 +353,169 @@ mod tests {
         let expected = Struct {
             ident: parse_quote!(Struct),
             generics: Generics::default(),
+            bound: None,
             byte_order: None,
             len: None,
             round: None,
+            dyn_byte_order: false,
+            transparent: false,
+            multi_pass: false,
+            max_depth: None,
+            assert_size: None,
+            fill: None,
+            bit_numbering: None,
+            validate: None,
+            magic: None,
+            size_prefix: None,
             fields: vec![Field::Direct {
                 ident: parse_quote!(field),
                 ty: parse_quote!(u8),
                 multi_pass: None,
+                deferred: false,
+                boxed: false,
+                borrow: false,
+                condition: None,
                 transform: Transform::None,
                 layout_properties: Default::default(),
             }],
+            skipped: vec![],
         };
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn max_depth() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(max_depth = 64)]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert_eq!(actual.max_depth, Some(64));
+    }
+
+    #[test]
+    fn fill() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(fill = 0xFF)]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert_eq!(actual.fill, Some(0xFF));
+    }
+
+    #[test]
+    fn bit_numbering() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(bit_numbering = MSB0)]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert_eq!(actual.bit_numbering, Some(BitNumbering::MSB0));
+    }
+
+    #[test]
+    fn validate() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(validate = "Self::check")]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert_eq!(actual.validate, Some(parse_quote!(Self::check)));
+    }
+
+    #[test]
+    fn magic() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(magic = b"RIFF")]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert_eq!(actual.magic, Some(parse_quote!(b"RIFF")));
+    }
+
+    #[test]
+    fn multi_pass() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(multi_pass)]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert!(actual.multi_pass);
+    }
+
+    #[test]
+    fn assert_size() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(assert_size = 64)]
+            struct Struct {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert_eq!(actual.assert_size, Some(64));
+    }
+
+    #[test]
+    fn assert_size_generic() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(assert_size = 64)]
+            struct Struct<T> {}
+        );
+        assert!(Struct::try_from(input).is_err());
+    }
+
+    #[test]
+    fn bound() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(bound = "T: Copy, U: Default")]
+            struct Struct<T, U> {}
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert_eq!(actual.bound, Some(vec![parse_quote!(T: Copy), parse_quote!(U: Default)]));
+    }
+
+    #[test]
+    fn transparent() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(transparent)]
+            struct Struct(u32);
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert!(actual.transparent);
+        assert_eq!(actual.fields.len(), 1);
+    }
+
+    #[test]
+    fn transparent_with_skipped_field() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(transparent)]
+            struct Struct {
+                value: u32,
+                #[sorbit(skip)]
+                cache: u32,
+            }
+        );
+        let actual = Struct::try_from(input).unwrap();
+        assert!(actual.transparent);
+        assert_eq!(actual.skipped.len(), 1);
+    }
+
+    #[test]
+    fn transparent_with_byte_order() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(transparent, byte_order=big_endian)]
+            struct Struct(u32);
+        );
+        assert!(Struct::try_from(input).is_err());
+    }
+
+    #[test]
+    fn transparent_without_fields() {
+        let input: DeriveInput = parse_quote!(
+            #[sorbit(transparent)]
+            struct Struct {}
+        );
+        assert!(Struct::try_from(input).is_err());
+    }
+
     #[test]
     #[should_panic]
     fn invalid_key() {
@@ -1,8 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
-use proc_macro2::Span;
-use quote::{format_ident, quote};
-use syn::{Expr, GenericArgument, Path, PathArguments, PathSegment, TypePath, parse_quote, spanned::Spanned as _};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{ToTokens as _, format_ident, quote};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    Expr, ExprField, GenericArgument, Path, PathArguments, PathSegment, TypePath, parse_quote, spanned::Spanned as _,
+};
 
 /// Convert a type which is single ident into an actual type.
 pub fn ident_to_type(ident: syn::Ident) -> syn::Type {
@@ -21,7 +24,7 @@ pub fn member_to_ident(member: syn::Member) -> syn::Ident {
 
 pub fn to_member(ident: Option<syn::Ident>, index: usize, span: Span) -> syn::Member {
     ident
-        .map(|ident| syn::Member::from(ident))
+        .map(syn::Member::from)
         .unwrap_or_else(|| syn::Member::Unnamed(syn::Index { index: index as u32, span }))
 }
 
@@ -34,13 +37,16 @@ pub fn deconstruct_pattern(struct_ty: &syn::Type, members: impl Iterator<Item =
             quote! { #index: #ident }
         }
     });
-    parse_quote!(#struct_ty{ #(#members),* })
+    parse_quote!(#struct_ty{ #(#members,)* .. })
 }
 
 /// Return a pattern that deconstructs a structure with the identifier explicitly given.
+///
+/// When `rest` is set, the pattern ignores the members that are not listed.
 pub fn deconstruct_pattern_explicit(
     struct_ty: &syn::Type,
     members: impl Iterator<Item = (syn::Member, syn::Ident)>,
+    rest: bool,
 ) -> syn::Pat {
     let members = members.map(|(member, ident)| match member {
         syn::Member::Named(field) => {
@@ -54,7 +60,10 @@ pub fn deconstruct_pattern_explicit(
             quote! { #index: #ident }
         }
     });
-    parse_quote!(#struct_ty{ #(#members),* })
+    match rest {
+        true => parse_quote!(#struct_ty{ #(#members,)* .. }),
+        false => parse_quote!(#struct_ty{ #(#members),* }),
+    }
 }
 
 pub fn check_invalid_parameters<'a>(
@@ -62,7 +71,7 @@ pub fn check_invalid_parameters<'a>(
     accepted_parameters: impl Iterator<Item = &'a Path>,
 ) -> Result<(), syn::Error> {
     let accepted_parameters: HashSet<_> = accepted_parameters.cloned().collect();
-    for (parameter, _) in parameters {
+    for parameter in parameters.keys() {
         if !accepted_parameters.contains(parameter) {
             return Err(syn::Error::new(parameter.span(), "parameter is not accepted here"));
         }
@@ -70,6 +79,139 @@ pub fn check_invalid_parameters<'a>(
     Ok(())
 }
 
+/// Check if the type refers to any of the type parameters in the generics.
+pub fn uses_type_params(ty: &syn::Type, generics: &syn::Generics) -> bool {
+    fn contains_any(tokens: TokenStream, idents: &HashSet<syn::Ident>) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => idents.contains(&ident),
+            TokenTree::Group(group) => contains_any(group.stream(), idents),
+            _ => false,
+        })
+    }
+    let type_params: HashSet<_> = generics.type_params().map(|param| param.ident.clone()).collect();
+    contains_any(ty.to_token_stream(), &type_params)
+}
+
+/// Return the type `T` if the type is `Box<T>`.
+pub fn boxed_type(ty: &syn::Type) -> Option<&syn::Type> {
+    single_type_argument(ty, "Box")
+}
+
+/// Return the length `N` if the type is `[u8; N]`.
+pub fn byte_array_len(ty: &syn::Type) -> Option<&syn::Expr> {
+    match ty {
+        syn::Type::Array(array) if array.elem.to_token_stream().to_string() == "u8" => Some(&array.len),
+        _ => None,
+    }
+}
+
+/// Return the number of bits in the type if it's a fixed-width primitive
+/// integer or a byte array of literal length.
+pub fn bit_width(ty: &syn::Type) -> Option<u64> {
+    if let Some(len) = byte_array_len(ty) {
+        let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(len), .. }) = len else {
+            return None;
+        };
+        return len.base10_parse::<u64>().ok().map(|len| 8 * len);
+    }
+    match ty.to_token_stream().to_string().as_str() {
+        "u8" | "i8" => Some(8),
+        "u16" | "i16" => Some(16),
+        "u32" | "i32" => Some(32),
+        "u64" | "i64" => Some(64),
+        "u128" | "i128" => Some(128),
+        _ => None,
+    }
+}
+
+/// Return the lifetime `'a` if the type is `&'a T` or `Name<'a, ...>`.
+pub fn borrowed_lifetime(ty: &syn::Type) -> Option<&syn::Lifetime> {
+    match ty {
+        syn::Type::Reference(reference) => reference.lifetime.as_ref(),
+        syn::Type::Path(TypePath { qself: None, path }) => match &path.segments.last()?.arguments {
+            PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Lifetime(lifetime) => Some(lifetime),
+                _ => None,
+            }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Return the type `T` if the type is `Option<T>`.
+pub fn optional_type(ty: &syn::Type) -> Option<&syn::Type> {
+    single_type_argument(ty, "Option")
+}
+
+fn single_type_argument<'ty>(ty: &'ty syn::Type, name: &str) -> Option<&'ty syn::Type> {
+    let syn::Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    match path.segments.last() {
+        Some(PathSegment { ident, arguments: PathArguments::AngleBracketed(args) })
+            if ident == name && args.args.len() == 1 =>
+        {
+            match args.args.first() {
+                Some(GenericArgument::Type(ty)) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Replace the members of `self` in an expression of a field, such as its
+/// condition, by the variables that hold the members.
+///
+/// Only the `available` members may be referred to, which are the members
+/// that precede the field. The expression is called `what` in errors.
+pub fn detach_expr(expr: &Expr, available: &HashSet<syn::Member>, what: &str) -> Result<Expr, syn::Error> {
+    struct Detach<'a> {
+        available: &'a HashSet<syn::Member>,
+        what: &'a str,
+        error: Option<syn::Error>,
+    }
+
+    impl Detach<'_> {
+        fn report(&mut self, error: syn::Error) {
+            match &mut self.error {
+                Some(existing) => existing.combine(error),
+                None => self.error = Some(error),
+            }
+        }
+    }
+
+    impl VisitMut for Detach<'_> {
+        fn visit_expr_mut(&mut self, expr: &mut Expr) {
+            match expr {
+                Expr::Field(ExprField { base, member, .. }) if matches!(base.as_ref(), Expr::Path(path) if path.path.is_ident("self")) =>
+                {
+                    if !self.available.contains(member) {
+                        let message = format!("the {} may only refer to preceding fields", self.what);
+                        self.report(syn::Error::new(member.span(), message));
+                    }
+                    let ident = member_to_ident(member.clone());
+                    *expr = parse_quote!((*#ident));
+                }
+                Expr::Path(path) if path.path.is_ident("self") => {
+                    let message = format!("the {} may only refer to the fields of `self`", self.what);
+                    self.report(syn::Error::new(path.span(), message));
+                }
+                _ => visit_mut::visit_expr_mut(self, expr),
+            }
+        }
+    }
+
+    let mut detached = expr.clone();
+    let mut detach = Detach { available, what, error: None };
+    detach.visit_expr_mut(&mut detached);
+    match detach.error {
+        Some(error) => Err(error),
+        None => Ok(detached),
+    }
+}
+
 pub trait PhantomType {
     fn is_phantom(&self) -> bool;
     fn phantom_underlying_type(&self) -> &syn::Type;
@@ -118,10 +260,10 @@ impl PhantomType for syn::Type {
                     None if path.leading_colon.is_none() => (),
                     _ => return self,
                 };
-                if let Some(PathSegment { arguments: PathArguments::AngleBracketed(args), .. }) = path.segments.last() {
-                    if let (Some(GenericArgument::Type(ty)), 1) = (args.args.first(), args.args.len()) {
-                        return ty;
-                    }
+                if let Some(PathSegment { arguments: PathArguments::AngleBracketed(args), .. }) = path.segments.last()
+                    && let (Some(GenericArgument::Type(ty)), 1) = (args.args.first(), args.args.len())
+                {
+                    return ty;
                 };
                 self
             }
@@ -158,4 +300,71 @@ mod tests {
         );
         assert_eq!(ty.is_phantom(), is_phantom);
     }
+
+    #[rstest]
+    #[case(parse_quote!(u8), None)]
+    #[case(parse_quote!(Box<[u8; 4]>), Some(parse_quote!([u8; 4])))]
+    #[case(parse_quote!(alloc::boxed::Box<u8>), Some(parse_quote!(u8)))]
+    #[case(parse_quote!(Box<u8, A>), None)]
+    #[case(parse_quote!(Boxed<u8>), None)]
+    fn boxed_type(#[case] ty: Type, #[case] expected: Option<Type>) {
+        assert_eq!(super::boxed_type(&ty), expected.as_ref());
+    }
+
+    #[rstest]
+    #[case(parse_quote!(u8), None)]
+    #[case(parse_quote!(&[u8]), None)]
+    #[case(parse_quote!(&'a [u8]), Some(parse_quote!('a)))]
+    #[case(parse_quote!(&'de str), Some(parse_quote!('de)))]
+    #[case(parse_quote!(inner::Packet<'a, T>), Some(parse_quote!('a)))]
+    #[case(parse_quote!(Packet<T>), None)]
+    fn borrowed_lifetime(#[case] ty: Type, #[case] expected: Option<syn::Lifetime>) {
+        assert_eq!(super::borrowed_lifetime(&ty), expected.as_ref());
+    }
+
+    #[rstest]
+    #[case(parse_quote!(u8), None)]
+    #[case(parse_quote!(Option<u16>), Some(parse_quote!(u16)))]
+    #[case(parse_quote!(core::option::Option<u8>), Some(parse_quote!(u8)))]
+    #[case(parse_quote!(Optional<u8>), None)]
+    fn optional_type(#[case] ty: Type, #[case] expected: Option<Type>) {
+        assert_eq!(super::optional_type(&ty), expected.as_ref());
+    }
+
+    #[rstest]
+    #[case(parse_quote!(u8), Some(8))]
+    #[case(parse_quote!(i128), Some(128))]
+    #[case(parse_quote!([u8; 3]), Some(24))]
+    #[case(parse_quote!([u8; N]), None)]
+    #[case(parse_quote!(usize), None)]
+    fn bit_width(#[case] ty: Type, #[case] expected: Option<u64>) {
+        assert_eq!(super::bit_width(&ty), expected);
+    }
+
+    #[test]
+    fn detach_expr() {
+        let available = HashSet::from([parse_quote!(flags), syn::Member::from(1)]);
+        let condition: Expr = parse_quote!(self.flags & 0x80 != 0 && self.1 == 3);
+        let expected: Expr = parse_quote!((*flags) & 0x80 != 0 && (*m1) == 3);
+        assert_eq!(super::detach_expr(&condition, &available, "condition").unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(parse_quote!(self.len > 0))]
+    #[case(parse_quote!(self.is_present()))]
+    fn detach_expr_invalid(#[case] condition: Expr) {
+        let available = HashSet::from([parse_quote!(flags)]);
+        assert!(super::detach_expr(&condition, &available, "condition").is_err());
+    }
+
+    #[rstest]
+    #[case(parse_quote!(u8), false)]
+    #[case(parse_quote!(T), true)]
+    #[case(parse_quote!(Vec<T>), true)]
+    #[case(parse_quote!([(u8, T); 4]), true)]
+    #[case(parse_quote!(Vec<U>), false)]
+    fn uses_type_params(#[case] ty: Type, #[case] expected: bool) {
+        let generics: syn::Generics = parse_quote!(<'a, T, const N: usize>);
+        assert_eq!(super::uses_type_params(&ty, &generics), expected);
+    }
 }
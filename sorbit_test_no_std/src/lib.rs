@@ -0,0 +1,160 @@
+//! Derives sorbit's traits for representative types in a `no_std` crate.
+//!
+//! The crate builds only if the generated code refers to `::core` and
+//! `::sorbit` items exclusively. The prelude is shadowed below to catch
+//! generated code that relies on it, and no sorbit traits are imported to catch
+//! generated code that relies on the traits being in scope.
+
+#![no_std]
+#![allow(unused)]
+
+use core::marker::PhantomData;
+
+use sorbit::{Deserialize, PackInto, Serialize, SorbitTag, StaticLayout, UnpackFrom};
+
+mod shadow {
+    pub struct Ok;
+    pub struct Err;
+    pub struct Some;
+    pub struct None;
+    pub struct Result;
+    pub struct Option;
+    pub trait Clone {}
+    pub trait Copy {}
+    pub trait Default {}
+    pub trait Into {}
+    pub trait From {}
+    pub trait TryFrom {}
+    pub trait TryInto {}
+    pub trait Iterator {}
+    pub trait IntoIterator {}
+    pub trait FromIterator {}
+    pub trait Sized {}
+    pub trait PartialEq {}
+}
+
+#[allow(clippy::wildcard_imports)]
+use shadow::*;
+
+#[derive(Serialize, Deserialize, StaticLayout)]
+#[sorbit(byte_order=big_endian, len=16, round=4)]
+struct Fields {
+    a: u8,
+    #[sorbit(byte_order=little_endian, offset=2, align=2, round=4)]
+    b: u16,
+    #[sorbit(value=constant(0xFEu8))]
+    c: PhantomData<u8>,
+    d: [u32; 2],
+    e: (i8, bool),
+}
+
+#[derive(Serialize, Deserialize, StaticLayout)]
+struct BitFields {
+    #[sorbit(bit_field=_bits, repr=u16, bits=0..4)]
+    a: u8,
+    #[sorbit(bit_field=_bits, bits=4..5)]
+    b: bool,
+    #[sorbit(bit_field=_bits, bits=8..12)]
+    c: Discriminant,
+}
+
+#[derive(Serialize, Deserialize, StaticLayout)]
+struct Tuple(u8, Fields);
+
+#[derive(Serialize, Deserialize, StaticLayout)]
+struct Empty;
+
+#[derive(Serialize, Deserialize, StaticLayout, PackInto, UnpackFrom)]
+#[repr(u8)]
+enum Discriminant {
+    A = 1,
+    B = 2,
+}
+
+#[derive(Serialize, Deserialize, PackInto, UnpackFrom)]
+#[repr(u16)]
+enum CatchAll {
+    A = 1,
+    #[sorbit(catch_all)]
+    Other(u16),
+}
+
+#[derive(Serialize, Deserialize, StaticLayout)]
+#[repr(u8)]
+#[sorbit(byte_order=little_endian)]
+enum Fielded {
+    A = 1,
+    B { a: u32, b: Tuple } = 2,
+    C(u16, BitFields) = 3,
+}
+
+#[derive(Serialize, Deserialize, PackInto, UnpackFrom)]
+#[repr(u8)]
+enum NamedCatchAll {
+    A = 1,
+    #[sorbit(catch_all)]
+    Other {
+        value: u8,
+    },
+}
+
+#[derive(SorbitTag)]
+#[repr(u32)]
+#[sorbit(byte_order=big_endian)]
+enum Tag {
+    A = 1,
+    #[sorbit(catch_all)]
+    Other(u32),
+}
+
+#[derive(Serialize, Deserialize)]
+#[sorbit(dyn_byte_order)]
+struct DynByteOrder {
+    a: u16,
+    b: Tuple,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Deferred {
+    #[sorbit(deferred)]
+    a: Checksum,
+    b: Tuple,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Checksum(u8);
+
+impl sorbit::ser_de::DeferredSerialize for Checksum {
+    fn serialize_deferred<S: sorbit::ser_de::RevisableSerializer>(
+        &self,
+        serializer: &mut S,
+        sections: &[&S::Success],
+    ) -> core::result::Result<S::Success, S::Error> {
+        sorbit::ser_de::Serializer::serialize_u8(serializer, sections.len() as u8)
+    }
+}
+
+#[derive(Deserialize)]
+struct Overlay {
+    a: u16,
+    #[sorbit(offset=0, allow_backward=seek)]
+    b: [u8; 2],
+}
+
+#[derive(Serialize, Deserialize, StaticLayout)]
+#[sorbit(magic=b"RIFF", validate=Self::check)]
+struct Validated {
+    #[sorbit(magic = b"WAVE")]
+    format: [u8; 4],
+    #[sorbit(offset = 12)]
+    size: u32,
+}
+
+impl Validated {
+    fn check(&self) -> core::result::Result<(), &'static str> {
+        match self.size {
+            0 => core::result::Result::Err("the size is zero"),
+            _ => core::result::Result::Ok(()),
+        }
+    }
+}